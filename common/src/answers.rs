@@ -56,6 +56,8 @@ impl<W: AnswersWriter> AnswersWriter for SwitchableAnswersWriter<W> {
 mod binary {
     use core::ops::Range;
     use std::{
+        borrow::Cow,
+        collections::HashMap,
         format,
         io::{self, Write},
         path::PathBuf,
@@ -94,12 +96,24 @@ mod binary {
         }
     }
 
+    /// Maps the ids of answers to their byte offsets in the output buffer.
+    /// Ids not present in the layout are considered to be at the offset equal to the id.
+    pub type InputLayout = HashMap<usize, usize>;
+
+    /// A hook that transforms the output right before being written.
+    /// Useful for recomputing the fields derived from the answers (e.g., checksums
+    /// and length headers) which otherwise invalidate the output early.
+    pub type OutputPostProcessor = Box<dyn FnMut(&mut Vec<u8>)>;
+
     /// Outputs answers found for byte-typed symbolic values over time.
     /// It takes a directory and outputs each answer in a separate file named
     /// with the format `{prefix}{counter}.{extension}`.
     /// # Remarks
-    /// - Ids are the index of the byte in the output buffer.
+    /// - Ids are the index of the byte in the output buffer, unless a layout is set.
     /// - A `None` answer means it is not byte-typed.
+    /// - In patching mode, the default answers are the parent input and only the
+    ///   bytes corresponding to the given answers get overwritten. Thus, the answers
+    ///   are not required to cover all the bytes.
     pub struct BinaryFileMultiAnswersWriter {
         dir_path: PathBuf,
         counter: usize,
@@ -109,6 +123,8 @@ mod binary {
         /// The output buffer will be initially filled with this buffer.
         /// Useful when the output will be used as input again.
         default_answers: Box<[u8]>,
+        layout: Option<InputLayout>,
+        post_processors: Vec<OutputPostProcessor>,
         _phantom: core::marker::PhantomData<()>,
     }
 
@@ -136,10 +152,32 @@ mod binary {
                 extension,
                 buffer: default_answers.map(Vec::from).unwrap_or_default(),
                 default_answers: default_answers.map(Into::into).unwrap_or_default(),
+                layout: None,
+                post_processors: Vec::new(),
                 _phantom: Default::default(),
             }
         }
 
+        /// Switches the writer to patching mode, where the answers are placed in
+        /// the default answers (parent input) according to the layout.
+        pub fn set_patching(&mut self, layout: InputLayout) {
+            self.layout = Some(layout);
+        }
+
+        /// Adds a hook to be applied on the output before being written.
+        /// The hooks are applied in the order they are added.
+        pub fn add_post_processor(&mut self, processor: OutputPostProcessor) {
+            self.post_processors.push(processor);
+        }
+
+        #[inline]
+        fn offset_of(&self, id: usize) -> usize {
+            self.layout
+                .as_ref()
+                .and_then(|l| l.get(&id).copied())
+                .unwrap_or(id)
+        }
+
         fn write(&mut self, range: Range<usize>) -> Result<PathBuf, io::Error> {
             let path = self
                 .dir_path
@@ -147,8 +185,18 @@ mod binary {
                 .with_added_extension(&self.extension);
             log_debug!("Writing values to file: {}.", path.display());
 
+            let content = if self.post_processors.is_empty() {
+                Cow::Borrowed(&self.buffer[range])
+            } else {
+                let mut content = self.buffer[range].to_vec();
+                self.post_processors
+                    .iter_mut()
+                    .for_each(|p| p(&mut content));
+                Cow::Owned(content)
+            };
+
             std::fs::File::create(&path)
-                .and_then(|mut f| f.write(&self.buffer[range]))
+                .and_then(|mut f| f.write(&content))
                 .inspect(|_| {
                     self.counter += 1;
                 })
@@ -195,8 +243,8 @@ mod binary {
             }
 
             for (id, ans) in answers {
-                let index = id;
-                let byte_ans = ans.ok_or_else(|| BinaryFileAnswerError::NonByte(index))?;
+                let index = self.offset_of(id);
+                let byte_ans = ans.ok_or_else(|| BinaryFileAnswerError::NonByte(id))?;
 
                 if index >= max_upper {
                     max_upper = index + 1;
@@ -210,7 +258,8 @@ mod binary {
                 }
             }
             // If there are bytes not filled with the default answers nor answers.
-            if filled < max_upper {
+            // In patching mode, the gaps are expected and left as zeros.
+            if self.layout.is_none() && filled < max_upper {
                 return Err(BinaryFileAnswerError::Incomplete);
             }
 
@@ -218,4 +267,6 @@ mod binary {
         }
     }
 }
-pub use binary::{BinaryFileAnswerError, BinaryFileMultiAnswersWriter};
+pub use binary::{
+    BinaryFileAnswerError, BinaryFileMultiAnswersWriter, InputLayout, OutputPostProcessor,
+};
@@ -1,5 +1,11 @@
 use core::hash::Hash;
-use std::{collections::HashMap, path::Path, string::String, vec::Vec};
+use std::{
+    collections::{HashMap, HashSet},
+    format,
+    path::Path,
+    string::{String, ToString},
+    vec::Vec,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -23,9 +29,19 @@ pub struct CallDebugInfo {
 pub type CallGraphEdgeDestination<I = InstanceKindId> = (BasicBlockIndex, I, CallDebugInfo);
 pub type CallGraph<I = InstanceKindId> = AdjListGraph<I, CallGraphEdgeDestination<I>>;
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SourceLine {
+    pub file: String,
+    /// One-based.
+    pub line: u32,
+}
+
 #[derive(Default, Clone, Serialize, Deserialize)]
 pub struct DebugInfo<I: Eq + Hash> {
     pub func_names: HashMap<I, String>,
+    /// The source lines of the terminators of the basic blocks.
+    #[serde(default)]
+    pub block_lines: HashMap<I, HashMap<BasicBlockIndex, SourceLine>>,
 }
 
 #[derive(Default, Clone, Serialize, Deserialize)]
@@ -38,6 +54,8 @@ pub struct ProgramMap<I: Eq + Hash = InstanceKindId> {
 }
 
 impl ProgramMap {
+    pub const FILENAME: &'static str = "program_map.json";
+
     pub fn read(path: &Path) -> Result<Self, MessagedError> {
         let file =
             std::fs::OpenOptions::new()
@@ -71,3 +89,122 @@ impl ProgramMap {
             ))
     }
 }
+
+/// Rendering of the program map in Graphviz DOT, mainly for debugging the
+/// reachability of the locations.
+impl ProgramMap {
+    /// Renders the call graph, where the edges are labeled by the call sites.
+    /// If `towards` is given, only the bodies on the call paths from the entry
+    /// points to any of them are included.
+    pub fn call_graph_to_dot(&self, towards: Option<&HashSet<InstanceKindId>>) -> String {
+        let included = towards.map(|targets| self.bodies_on_paths_to(targets));
+        let is_included =
+            |body: &InstanceKindId| included.as_ref().is_none_or(|i| i.contains(body));
+
+        let mut dot = String::from("digraph call_graph {\n    node [shape=box];\n");
+        let mut bodies = self
+            .cfgs
+            .keys()
+            .filter(|b| is_included(b))
+            .collect::<Vec<_>>();
+        bodies.sort();
+        for body in bodies {
+            let mut attrs = format!("label=\"{}\"", escape(&self.name_of(body)));
+            if self.entry_points.contains(body) {
+                attrs.push_str(", style=bold");
+            }
+            dot.push_str(&format!("    \"{body}\" [{attrs}];\n"));
+        }
+        for (caller, calls) in self.call_graph.iter().filter(|(b, _)| is_included(b)) {
+            for (block, callee, info) in calls.iter().filter(|(_, c, _)| is_included(c)) {
+                dot.push_str(&format!(
+                    "    \"{caller}\" -> \"{callee}\" [label=\"bb{block}\", tooltip=\"{}\"];\n",
+                    escape(&info.location),
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the control flow graph of the body, where the edges are labeled by
+    /// the values of the discriminants and the return points are marked.
+    pub fn cfg_to_dot(&self, body: &InstanceKindId) -> Option<String> {
+        let cfg = self.cfgs.get(body)?;
+        let ret_points = self.ret_points.get(body);
+
+        let mut dot = format!(
+            "digraph cfg {{\n    label=\"{}\";\n    node [shape=box];\n",
+            escape(&self.name_of(body))
+        );
+        let mut blocks = cfg.keys().collect::<Vec<_>>();
+        blocks.sort();
+        for block in blocks {
+            let shape = if ret_points.is_some_and(|r| r.contains(block)) {
+                ", shape=doublecircle"
+            } else {
+                ""
+            };
+            dot.push_str(&format!("    bb{block} [label=\"bb{block}\"{shape}];\n"));
+            for (target, constraint) in &cfg[block] {
+                let label = match constraint {
+                    Some(CfgConstraint::Case(value)) => format!(" [label=\"{value}\"]"),
+                    Some(CfgConstraint::Otherwise) => " [label=\"otherwise\"]".to_owned(),
+                    None => String::new(),
+                };
+                dot.push_str(&format!("    bb{block} -> bb{target}{label};\n"));
+            }
+        }
+        dot.push_str("}\n");
+        Some(dot)
+    }
+
+    /// Returns the bodies that are reachable from the entry points and can reach
+    /// any of the targets in the call graph.
+    pub fn bodies_on_paths_to(&self, targets: &HashSet<InstanceKindId>) -> HashSet<InstanceKindId> {
+        let mut callers = HashMap::<InstanceKindId, Vec<InstanceKindId>>::new();
+        for (caller, calls) in &self.call_graph {
+            for (_, callee, _) in calls {
+                callers.entry(*callee).or_default().push(*caller);
+            }
+        }
+
+        let reaching = traverse(targets.iter().copied(), |b| {
+            callers.get(b).into_iter().flatten().copied()
+        });
+        let reachable = traverse(self.entry_points.iter().copied(), |b| {
+            self.call_graph
+                .get(b)
+                .into_iter()
+                .flatten()
+                .map(|(_, callee, _)| *callee)
+        });
+        reaching.intersection(&reachable).copied().collect()
+    }
+
+    fn name_of(&self, body: &InstanceKindId) -> String {
+        self.debug_info
+            .func_names
+            .get(body)
+            .cloned()
+            .unwrap_or_else(|| body.to_string())
+    }
+}
+
+fn traverse<I: IntoIterator<Item = InstanceKindId>>(
+    roots: impl Iterator<Item = InstanceKindId>,
+    mut neighbors: impl FnMut(&InstanceKindId) -> I,
+) -> HashSet<InstanceKindId> {
+    let mut visited = HashSet::new();
+    let mut stack = roots.collect::<Vec<_>>();
+    while let Some(body) = stack.pop() {
+        if visited.insert(body) {
+            stack.extend(neighbors(&body));
+        }
+    }
+    visited
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -8,7 +8,7 @@ unsafe impl<T> Transmute<T> for T {}
 
 #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
 #[repr(transparent)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct U128Pack<T = u128>(
     [u8; core::mem::size_of::<u128>()],
     core::marker::PhantomData<T>,
@@ -38,7 +38,7 @@ pub struct SlicePack<T> {
 
 #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub struct ConstStrPack {
     #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
     pub ptr: *const u8,
@@ -95,6 +95,13 @@ pub mod macros {
             // ----- Interaction -----
           { fn init_runtime_lib() }
           { fn shutdown_runtime_lib() }
+          #[allow(unused_parens)]
+          { fn report_termination(
+              node_loc: BasicBlockIndex,
+              result: PlaceRef,
+              discr_bit_rep: u32,
+              discr_ty: ($primitive_type_ty),
+          ) }
 
           #[allow(unused_parens)]
           { fn debug_info(info: ($dbg_info_ty)) }
@@ -102,6 +109,10 @@ pub mod macros {
           #[allow(unused_parens)]
           { fn push_tag(tag: ($tag_ty)) }
           { fn pop_tag() }
+          { fn violate_assumption() }
+          #[allow(unused_parens)]
+          { fn push_sym_var_info(name: ($tag_ty), type_name: ($tag_ty), file: ($tag_ty), line: u32, col: u32) }
+          { fn pop_sym_var_info() }
 
           // ----- Place -----
           { fn ref_place_return_value() -> PlaceRef }
@@ -125,6 +136,7 @@ pub mod macros {
           #[allow(unused_parens)]
           { fn place_with_primitive_type(place: PlaceRef, ty: ($primitive_type_ty)) -> PlaceRef }
           { fn place_with_size(place: PlaceRef, byte_size: TypeSize) -> PlaceRef }
+          { fn place_with_metadata(place: PlaceRef, metadata: PlaceMetadata) -> PlaceRef }
 
           // ----- Operand -----
           { fn ref_operand_copy(place: PlaceRef) -> OperandRef }
@@ -144,7 +156,13 @@ pub mod macros {
           #[allow(unused_parens)]
           { fn ref_operand_const_byte_str(value: ($byte_str_ty)) -> OperandRef }
           { fn ref_operand_const_addr(value: RawAddress) -> OperandRef }
+          #[allow(unused_parens)]
+          { fn ref_operand_const_ptr(value: RawAddress, type_id: ($type_id_ty)) -> OperandRef }
+          #[allow(unused_parens)]
+          { fn ref_operand_const_byref(bytes: ($byte_str_ty), type_id: ($type_id_ty)) -> OperandRef }
           { fn ref_operand_const_zst() -> OperandRef }
+          #[allow(unused_parens)]
+          { fn ref_operand_const_fn_item(type_id: ($type_id_ty)) -> OperandRef }
           { fn ref_operand_const_some() -> OperandRef }
 
           { fn ref_operand_some() -> OperandRef }
@@ -160,7 +178,7 @@ pub mod macros {
           { fn assign_use(id: AssignmentId, dest: PlaceRef, operand: OperandRef) }
           { fn assign_repeat(id: AssignmentId, dest: PlaceRef, operand: OperandRef, count: usize) }
           { fn assign_ref(id: AssignmentId, dest: PlaceRef, place: PlaceRef, is_mutable: bool) }
-          { fn assign_thread_local_ref(id: AssignmentId, dest: PlaceRef) }
+          { fn assign_thread_local_ref(id: AssignmentId, dest: PlaceRef, crate_id: u32, def_index: u32, addr: RawAddress) }
           { fn assign_raw_ptr_of(id: AssignmentId, dest: PlaceRef, place: PlaceRef, is_mutable: bool) }
 
           // ------- Cast ---
@@ -221,6 +239,8 @@ pub mod macros {
           // ----- Memory -----
           { fn mark_storage_live(place: PlaceRef) }
           { fn mark_storage_dead(place: PlaceRef) }
+          { fn mark_allocated(addr: RawAddress, size: usize) }
+          { fn mark_deallocated(addr: RawAddress) }
 
           // ----- Switch -----
           { fn take_branch(node_loc: BasicBlockIndex, case_index: SwitchCaseIndex) }
@@ -295,6 +315,9 @@ pub mod macros {
           ) }
           { fn assert_null_ptr_deref(info: AssertionInfo) }
           { fn assert_invalid_enum_ctn(info: AssertionInfo, discr: OperandRef) }
+          { fn check_assert_passed(location: BasicBlockIndex) }
+          #[allow(unused_parens)]
+          { fn check_panic(location: BasicBlockIndex, source: ($str_ty)) }
 
           // ----- Calling -----
           { fn before_call_control(call_site: BasicBlockIndex, callee_id: InstanceKindId) }
@@ -312,6 +335,7 @@ pub mod macros {
           #[allow(unused_parens)]
           { fn before_call_data(func: OperandRef, args: ($slice_ty!(OperandRef)), are_args_tupled: bool) }
           { fn before_call_some() }
+          { fn notify_thread_start() }
           { fn enter_func(body_id: InstanceKindId) }
           { fn enter_func_precise(body_id: InstanceKindId, static_addr: RawAddress) }
           { fn enter_func_precise_dyn_comp(
@@ -319,6 +343,7 @@ pub mod macros {
               static_addr: RawAddress,
               dyn_id: (DynRawMetadata, u64),
           ) }
+          { fn mark_frame_indirect() }
           #[allow(unused_parens)]
           { fn enter_func_data(arg_places: ($slice_ty!(PlaceRef)), ret_val_place: PlaceRef) }
           #[allow(unused_parens)]
@@ -332,6 +357,7 @@ pub mod macros {
           { fn enter_func_data_tupled_args(arg_places: ($slice_ty!(PlaceRef)), ret_val_place: PlaceRef) }
           { fn return_from_func(ret_point: BasicBlockIndex) }
           { fn override_return_value(operand: OperandRef) }
+          { fn concretize_return_value(operand: OperandRef) }
           { fn after_call_func(id: AssignmentId, dest: PlaceRef) }
 
           // ----- Drop ------
@@ -351,6 +377,7 @@ pub mod macros {
           { fn before_drop_in_place_data(func: OperandRef, ptr: OperandRef, conc_ptr: RawAddress, ptr_type_id: TypeId) }
           { fn before_drop_some() }
           { fn after_drop() }
+          { fn after_drop_unwind() }
 
 
           // ----- Intrinsics -----
@@ -403,6 +430,38 @@ pub mod macros {
               addend: OperandRef,
               carry: OperandRef,
           ) }
+          // ----- Float -----
+          { fn intrinsic_assign_sqrt(id: AssignmentId, dest: PlaceRef, x: OperandRef) }
+          { fn intrinsic_assign_fabs(id: AssignmentId, dest: PlaceRef, x: OperandRef) }
+          { fn intrinsic_assign_powi(id: AssignmentId, dest: PlaceRef, x: OperandRef, n: OperandRef) }
+          { fn intrinsic_assign_minimum(id: AssignmentId, dest: PlaceRef, first: OperandRef, second: OperandRef) }
+          { fn intrinsic_assign_maximum(id: AssignmentId, dest: PlaceRef, first: OperandRef, second: OperandRef) }
+          { fn intrinsic_assign_minimum_number(id: AssignmentId, dest: PlaceRef, first: OperandRef, second: OperandRef) }
+          { fn intrinsic_assign_maximum_number(id: AssignmentId, dest: PlaceRef, first: OperandRef, second: OperandRef) }
+          // ----- SIMD -----
+          // Vectors are passed lane by lane.
+          #[allow(unused_parens)]
+          { fn intrinsic_assign_simd_binary_op(
+              id: AssignmentId,
+              dest: PlaceRef,
+              operator: ($binary_op_ty),
+              first: ($slice_ty!(OperandRef)),
+              second: ($slice_ty!(OperandRef)),
+          ) }
+          #[allow(unused_parens)]
+          { fn intrinsic_assign_simd_unary_op(
+              id: AssignmentId,
+              dest: PlaceRef,
+              operator: ($unary_op_ty),
+              operand: ($slice_ty!(OperandRef)),
+          ) }
+          #[allow(unused_parens)]
+          { fn intrinsic_assign_simd_reduce(
+              id: AssignmentId,
+              dest: PlaceRef,
+              operator: ($binary_op_ty),
+              operand: ($slice_ty!(OperandRef)),
+          ) }
           // ----- Atomic -----
           // All atomic operations have an ordering, majority get applied on a pointer.
            #[allow(unused_parens)]
@@ -671,12 +730,20 @@ pub mod macros {
                 fn init_runtime_lib();
             }$modifier!{
                 fn shutdown_runtime_lib();
+            }$modifier!{
+                #[allow(unused_parens)]fn report_termination(node_loc: BasicBlockIndex,result: PlaceRef,discr_bit_rep: u32,discr_ty: ($primitive_type_ty),);
             }$modifier!{
                 #[allow(unused_parens)]fn debug_info(info: ($dbg_info_ty));
             }$modifier!{
                 #[allow(unused_parens)]fn push_tag(tag: ($tag_ty));
             }$modifier!{
                 fn pop_tag();
+            }$modifier!{
+                fn violate_assumption();
+            }$modifier!{
+                #[allow(unused_parens)]fn push_sym_var_info(name: ($tag_ty),type_name: ($tag_ty),file: ($tag_ty),line: u32,col: u32);
+            }$modifier!{
+                fn pop_sym_var_info();
             }$modifier!{
                 fn ref_place_return_value()->PlaceRef;
             }$modifier!{
@@ -711,6 +778,8 @@ pub mod macros {
                 #[allow(unused_parens)]fn place_with_primitive_type(place: PlaceRef,ty: ($primitive_type_ty))->PlaceRef;
             }$modifier!{
                 fn place_with_size(place: PlaceRef,byte_size: TypeSize)->PlaceRef;
+            }$modifier!{
+                fn place_with_metadata(place: PlaceRef,metadata: PlaceMetadata)->PlaceRef;
             }$modifier!{
                 fn ref_operand_copy(place: PlaceRef)->OperandRef;
             }$modifier!{
@@ -731,8 +800,14 @@ pub mod macros {
                 #[allow(unused_parens)]fn ref_operand_const_byte_str(value: ($byte_str_ty))->OperandRef;
             }$modifier!{
                 fn ref_operand_const_addr(value: RawAddress)->OperandRef;
+            }$modifier!{
+                #[allow(unused_parens)]fn ref_operand_const_ptr(value: RawAddress,type_id: ($type_id_ty))->OperandRef;
+            }$modifier!{
+                #[allow(unused_parens)]fn ref_operand_const_byref(bytes: ($byte_str_ty),type_id: ($type_id_ty))->OperandRef;
             }$modifier!{
                 fn ref_operand_const_zst()->OperandRef;
+            }$modifier!{
+                #[allow(unused_parens)]fn ref_operand_const_fn_item(type_id: ($type_id_ty))->OperandRef;
             }$modifier!{
                 fn ref_operand_const_some()->OperandRef;
             }$modifier!{
@@ -752,7 +827,7 @@ pub mod macros {
             }$modifier!{
                 fn assign_ref(id: AssignmentId,dest: PlaceRef,place: PlaceRef,is_mutable: bool);
             }$modifier!{
-                fn assign_thread_local_ref(id: AssignmentId,dest: PlaceRef);
+                fn assign_thread_local_ref(id: AssignmentId,dest: PlaceRef,crate_id: u32,def_index: u32,addr: RawAddress);
             }$modifier!{
                 fn assign_raw_ptr_of(id: AssignmentId,dest: PlaceRef,place: PlaceRef,is_mutable: bool);
             }$modifier!{
@@ -807,6 +882,10 @@ pub mod macros {
                 fn mark_storage_live(place: PlaceRef);
             }$modifier!{
                 fn mark_storage_dead(place: PlaceRef);
+            }$modifier!{
+                fn mark_allocated(addr: RawAddress,size: usize);
+            }$modifier!{
+                fn mark_deallocated(addr: RawAddress);
             }$modifier!{
                 fn take_branch(node_loc: BasicBlockIndex,case_index: SwitchCaseIndex);
             }$modifier!{
@@ -843,6 +922,10 @@ pub mod macros {
                 fn assert_null_ptr_deref(info: AssertionInfo);
             }$modifier!{
                 fn assert_invalid_enum_ctn(info: AssertionInfo,discr: OperandRef);
+            }$modifier!{
+                fn check_assert_passed(location: BasicBlockIndex);
+            }$modifier!{
+                #[allow(unused_parens)]fn check_panic(location: BasicBlockIndex,source: ($str_ty));
             }$modifier!{
                 fn before_call_control(call_site: BasicBlockIndex,callee_id: InstanceKindId);
             }$modifier!{
@@ -853,12 +936,16 @@ pub mod macros {
                 #[allow(unused_parens)]fn before_call_data(func: OperandRef,args: ($slice_ty!(OperandRef)),are_args_tupled: bool);
             }$modifier!{
                 fn before_call_some();
+            }$modifier!{
+                fn notify_thread_start();
             }$modifier!{
                 fn enter_func(body_id: InstanceKindId);
             }$modifier!{
                 fn enter_func_precise(body_id: InstanceKindId,static_addr: RawAddress);
             }$modifier!{
                 fn enter_func_precise_dyn_comp(body_id: InstanceKindId,static_addr: RawAddress,dyn_id: (DynRawMetadata,u64),);
+            }$modifier!{
+                fn mark_frame_indirect();
             }$modifier!{
                 #[allow(unused_parens)]fn enter_func_data(arg_places: ($slice_ty!(PlaceRef)),ret_val_place: PlaceRef);
             }$modifier!{
@@ -869,6 +956,8 @@ pub mod macros {
                 fn return_from_func(ret_point: BasicBlockIndex);
             }$modifier!{
                 fn override_return_value(operand: OperandRef);
+            }$modifier!{
+                fn concretize_return_value(operand: OperandRef);
             }$modifier!{
                 fn after_call_func(id: AssignmentId,dest: PlaceRef);
             }$modifier!{
@@ -885,6 +974,8 @@ pub mod macros {
                 fn before_drop_some();
             }$modifier!{
                 fn after_drop();
+            }$modifier!{
+                fn after_drop_unwind();
             }$modifier!{
                 fn intrinsic_assign_identity(id: AssignmentId,dest: PlaceRef,x: OperandRef);
             }$modifier!{
@@ -923,6 +1014,26 @@ pub mod macros {
                 fn intrinsic_assign_select_unpredictable(id: AssignmentId,dest: PlaceRef,condition: OperandRef,true_val: OperandRef,false_val: OperandRef,);
             }$modifier!{
                 fn intrinsic_assign_carrying_mul_add(id: AssignmentId,dest: PlaceRef,multiplier: OperandRef,multiplicand: OperandRef,addend: OperandRef,carry: OperandRef,);
+            }$modifier!{
+                fn intrinsic_assign_sqrt(id: AssignmentId,dest: PlaceRef,x: OperandRef);
+            }$modifier!{
+                fn intrinsic_assign_fabs(id: AssignmentId,dest: PlaceRef,x: OperandRef);
+            }$modifier!{
+                fn intrinsic_assign_powi(id: AssignmentId,dest: PlaceRef,x: OperandRef,n: OperandRef);
+            }$modifier!{
+                fn intrinsic_assign_minimum(id: AssignmentId,dest: PlaceRef,first: OperandRef,second: OperandRef);
+            }$modifier!{
+                fn intrinsic_assign_maximum(id: AssignmentId,dest: PlaceRef,first: OperandRef,second: OperandRef);
+            }$modifier!{
+                fn intrinsic_assign_minimum_number(id: AssignmentId,dest: PlaceRef,first: OperandRef,second: OperandRef);
+            }$modifier!{
+                fn intrinsic_assign_maximum_number(id: AssignmentId,dest: PlaceRef,first: OperandRef,second: OperandRef);
+            }$modifier!{
+                #[allow(unused_parens)]fn intrinsic_assign_simd_binary_op(id: AssignmentId,dest: PlaceRef,operator: ($binary_op_ty),first: ($slice_ty!(OperandRef)),second: ($slice_ty!(OperandRef)),);
+            }$modifier!{
+                #[allow(unused_parens)]fn intrinsic_assign_simd_unary_op(id: AssignmentId,dest: PlaceRef,operator: ($unary_op_ty),operand: ($slice_ty!(OperandRef)),);
+            }$modifier!{
+                #[allow(unused_parens)]fn intrinsic_assign_simd_reduce(id: AssignmentId,dest: PlaceRef,operator: ($binary_op_ty),operand: ($slice_ty!(OperandRef)),);
             }$modifier!{
                 #[allow(unused_parens)]fn intrinsic_atomic_binary_op(ordering: ($atomic_ord_ty),id: AssignmentId,ptr: OperandRef,conc_ptr: RawAddress,ptr_type_id: ($type_id_ty),operator: ($atomic_bin_op_ty),src: OperandRef,prev_dest: PlaceRef,);
             }$modifier!{
@@ -2,3 +2,40 @@ use super::Tag;
 
 #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
 pub const NO_DIVERGE: Tag = "no_diverge";
+
+/// Marks a call to a well-known function (e.g., `Option::map`) whose effect can be
+/// summarized by the backends when the function is not instrumented.
+/// The full tag is formed as `summary::<Type>::<function>`, e.g., `summary::Option::map`.
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+pub const SUMMARY_PREFIX: &str = "summary::";
+
+/// Marks a call that catches the panics unwinding through it, e.g.,
+/// `std::panic::catch_unwind`.
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+pub const CATCH_UNWIND: Tag = "catch_unwind";
+
+/// Marks the symbolic variables created from the bytes of the command-line arguments.
+/// The bytes are expected to be marked in order, starting from the first argument
+/// after the program name.
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+pub const SYMBOLIC_ARGS: Tag = "symbolic_args";
+
+/// Marks the symbolic variables created from the value of an environment variable.
+/// The tag is expected to be followed by a tag of the name of the variable.
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+pub const SYMBOLIC_ENV_VAR: Tag = "symbolic_env_var";
+
+/// Marks the symbolic variables created from the contents of a file.
+/// The tag is expected to be followed by a tag of the path of the file.
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+pub const SYMBOLIC_FILE: Tag = "symbolic_file";
+
+/// Marks reaching an objective of the program (see the `reach` annotation).
+/// The tag is expected to be followed by a tag of the label of the objective.
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+pub const REACH: Tag = "reach";
+
+/// Marks reaching a state that the program rejects as uninteresting
+/// (see the `reject` annotation).
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+pub const REJECT: Tag = "reject";
@@ -1,3 +1,4 @@
+use super::super::ffi::{ConstStrPack, U128Pack};
 pub use super::super::types::{
     Alignment, AssignmentId, BasicBlockIndex, BasicBlockLocation, CalleeDef, DefId, DynRawMetadata,
     FieldIndex, FuncDef, InstanceKindId, LocalIndex, RawAddress, SwitchCaseIndex, TypeId, TypeSize,
@@ -95,6 +96,12 @@ enum_like_type! {
         ROT_L = 11;
         ROT_R = 12;
         CARRYLESS_MUL = 13;
+        NAND = 14;
+        MIN = 15;
+        MAX = 16;
+        POWI = 17;
+        MINIMUM = 18;
+        MAXIMUM = 19;
         EQ = 21;
         LT = 22;
         LE = 23;
@@ -125,6 +132,8 @@ enum_like_type! {
         CTLZ_NONZERO = 38;
         CTLZ = 39;
         BSWAP = 40;
+        SQRT = 41;
+        ABS = 42;
     }
 }
 
@@ -135,6 +144,39 @@ pub struct AssertionInfo {
     pub location: BasicBlockIndex,
     pub condition: OperandRef,
     pub expected: bool,
+    /// The static part of the panic message, with placeholders for the operands.
+    pub message: ConstStrPack,
+}
+
+/// The metadata of a place, packed to be passed in a single call.
+/// Only the parts indicated by `flags` are valid.
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct PlaceMetadata {
+    pub flags: u8,
+    pub address: RawAddress,
+    pub type_id: U128Pack<TypeId>,
+    pub primitive_type: PrimitiveType,
+    pub size: TypeSize,
+}
+
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+impl PlaceMetadata {
+    #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+    pub const HAS_ADDRESS: u8 = 1 << 0;
+    #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+    pub const HAS_TYPE_ID: u8 = 1 << 1;
+    #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+    pub const HAS_PRIMITIVE_TYPE: u8 = 1 << 2;
+    #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+    pub const HAS_SIZE: u8 = 1 << 3;
+
+    #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+    #[inline(always)]
+    pub const fn has(&self, flag: u8) -> bool {
+        self.flags & flag != 0
+    }
 }
 
 enum_like_type! {
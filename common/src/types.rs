@@ -149,7 +149,7 @@ impl From<FuncDef> for InstanceKindId {
 #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
 #[cfg(feature = "trace_types")]
 pub mod trace {
-    use std::{vec, vec::Vec};
+    use std::{string::String, vec, vec::Vec};
 
     use super::{BasicBlockLocation, InstanceKindId};
 
@@ -336,6 +336,33 @@ pub mod trace {
             broken: bool,
         },
         Branch(BranchRecord<C>),
+        /// A chain of string comparisons that a `match` on a string is lowered to,
+        /// recorded as a multi-way branch at the first comparison.
+        /// The cases are the basic blocks deciding on the comparisons.
+        MatchChain(BranchRecord<C>),
+        /// Execution left the instrumented code by calling an uninstrumented function.
+        ExternalCall {
+            from: BasicBlockLocation,
+            callee: Option<InstanceKindId>,
+        },
+        /// Execution got back from uninstrumented code to the caller.
+        ExternalReturn {
+            to: BasicBlockLocation,
+        },
+        /// The program terminated by returning from the entry function with the exit code.
+        Termination {
+            from: BasicBlockLocation,
+            exit_code: u8,
+        },
+        /// The program reached the objective with the label (see the `reach` annotation).
+        Reach {
+            from: BasicBlockLocation,
+            label: String,
+        },
+        /// The program reached a state it rejects (see the `reject` annotation).
+        Reject {
+            from: BasicBlockLocation,
+        },
     }
 
     mod fmt {
@@ -400,6 +427,21 @@ pub mod trace {
                     ExeTraceRecord::Branch(BranchRecord { location, decision }) => {
                         write!(f, "{location}: {decision}")
                     }
+                    ExeTraceRecord::MatchChain(BranchRecord { location, decision }) => {
+                        write!(f, "{location}: match {decision}")
+                    }
+                    ExeTraceRecord::ExternalCall { from, callee } => match callee {
+                        Some(callee) => write!(f, "{from} ⤞ ⟨external {callee}⟩"),
+                        None => write!(f, "{from} ⤞ ⟨external⟩"),
+                    },
+                    ExeTraceRecord::ExternalReturn { to } => write!(f, "{to} ⤝ ⟨external⟩"),
+                    ExeTraceRecord::Termination { from, exit_code } => {
+                        write!(f, "{from} ⤓ ⟨exit {exit_code}⟩")
+                    }
+                    ExeTraceRecord::Reach { from, label } => {
+                        write!(f, "{from} ⚑ ⟨reach {label}⟩")
+                    }
+                    ExeTraceRecord::Reject { from } => write!(f, "{from} ⚑ ⟨reject⟩"),
                 }
             }
         }
@@ -50,6 +50,30 @@ pub fn search_current_ancestor_dirs_for(name: &str) -> Option<std::path::PathBuf
     .map(|p| p.join(name))
 }
 
+#[cfg(feature = "std")]
+/// Finds the root directory of the Cargo workspace containing the current
+/// working directory, i.e., the furthest ancestor with a `Cargo.toml` that
+/// defines a workspace, or the nearest one with a `Cargo.toml` if none does.
+pub fn find_current_workspace_root() -> Option<std::path::PathBuf> {
+    const FILE_MANIFEST: &str = "Cargo.toml";
+    const TABLE_WORKSPACE: &str = "[workspace]";
+
+    let current_dir = std::env::current_dir().ok()?;
+    let manifest_dirs = current_dir
+        .ancestors()
+        .filter(|p| p.join(FILE_MANIFEST).is_file())
+        .collect::<Vec<_>>();
+    manifest_dirs
+        .iter()
+        .rev()
+        .find(|p| {
+            std::fs::read_to_string(p.join(FILE_MANIFEST))
+                .is_ok_and(|content| content.lines().any(|l| l.trim() == TABLE_WORKSPACE))
+        })
+        .or(manifest_dirs.first())
+        .map(|p| p.to_path_buf())
+}
+
 #[cfg(feature = "std")]
 /// Searches current exe's folder and `deps` folder next to it to find the entry with the name in them.
 /// If found, returns the path to the file or directory.
@@ -64,6 +88,17 @@ pub fn search_next_to_exe_for(name: &str) -> Option<std::path::PathBuf> {
         .find_map(|d| try_join_path(d, name))
 }
 
+#[cfg(feature = "std")]
+/// Returns the path to the executable with the name if it is next to the current exe,
+/// otherwise its file name to be looked up in the path.
+pub fn find_exe_next_to_current(name: &str) -> std::path::PathBuf {
+    let file_name = format!("{name}{}", std::env::consts::EXE_SUFFIX);
+    std::env::current_exe()
+        .ok()
+        .and_then(|p| try_join_path(p.parent()?, &file_name))
+        .unwrap_or_else(|| file_name.into())
+}
+
 #[cfg(feature = "std")]
 pub fn try_join_path(
     path: impl AsRef<std::path::Path>,
@@ -5,7 +5,7 @@ pub mod serdes;
 mod solve;
 
 pub use node::*;
-pub use solve::{WrappedSolver, set_global_params};
+pub use solve::{QueryObserver, ValuePreferences, WrappedSolver, set_global_params};
 use z3::ast::{self, Ast};
 
 pub trait BVExt {
@@ -31,3 +31,239 @@ impl<'ctx> BVExt for ast::BV {
         }
     }
 }
+
+/// IEEE-754 operations with the semantics of Rust's floating-point types.
+/// The arithmetic operations round to nearest, ties to even.
+pub trait FloatExt: Sized {
+    fn from_ieee_bv(bv: &ast::BV, sort: FloatSort) -> Self;
+
+    fn to_ieee_bv(&self) -> ast::BV;
+
+    /// Converts the integer to the floating-point sort, rounding to nearest.
+    fn from_int_bv_rne(bv: &ast::BV, is_signed: bool, sort: FloatSort) -> Self;
+
+    /// Converts to another floating-point sort, rounding to nearest.
+    fn to_float_rne(&self, sort: FloatSort) -> Self;
+
+    /// Converts to an integer like Rust's `as`, i.e., the fraction is truncated,
+    /// the out-of-range values saturate, and NaN becomes zero.
+    fn to_int_bv_saturating(&self, size: u32, is_signed: bool) -> ast::BV;
+
+    fn add_rne(&self, other: &Self) -> Self;
+    fn sub_rne(&self, other: &Self) -> Self;
+    fn mul_rne(&self, other: &Self) -> Self;
+    fn div_rne(&self, other: &Self) -> Self;
+    /// The IEEE-754 remainder, i.e., with the quotient rounded to nearest.
+    fn ieee_rem(&self, other: &Self) -> Self;
+    fn sqrt_rne(&self) -> Self;
+    /// The IEEE-754 minNum, i.e., NaN operands are ignored.
+    /// The result for zeros of different signs is unspecified.
+    fn fp_min(&self, other: &Self) -> Self;
+    /// The IEEE-754 maxNum, i.e., NaN operands are ignored.
+    /// The result for zeros of different signs is unspecified.
+    fn fp_max(&self, other: &Self) -> Self;
+
+    fn neg(&self) -> Self;
+    fn abs(&self) -> Self;
+
+    fn fp_eq(&self, other: &Self) -> ast::Bool;
+    fn fp_lt(&self, other: &Self) -> ast::Bool;
+    fn fp_le(&self, other: &Self) -> ast::Bool;
+    fn fp_gt(&self, other: &Self) -> ast::Bool;
+    fn fp_ge(&self, other: &Self) -> ast::Bool;
+
+    fn is_negative(&self) -> ast::Bool;
+    fn is_zero(&self) -> ast::Bool;
+    fn is_nan(&self) -> ast::Bool;
+}
+
+macro_rules! fpa_ops {
+    (rne: $($name:ident => $f:ident),* $(,)?) => {
+        $(
+            fn $name(&self, other: &Self) -> Self {
+                unsafe {
+                    let ctx = self.get_ctx();
+                    let z3_ctx = ctx.get_z3_context();
+                    let rm = z3_sys::Z3_mk_fpa_round_nearest_ties_to_even(z3_ctx).unwrap();
+                    Self::wrap(
+                        ctx,
+                        z3_sys::$f(z3_ctx, rm, self.get_z3_ast(), other.get_z3_ast()).unwrap(),
+                    )
+                }
+            }
+        )*
+    };
+    (rne unary: $($name:ident => $f:ident),* $(,)?) => {
+        $(
+            fn $name(&self) -> Self {
+                unsafe {
+                    let ctx = self.get_ctx();
+                    let z3_ctx = ctx.get_z3_context();
+                    let rm = z3_sys::Z3_mk_fpa_round_nearest_ties_to_even(z3_ctx).unwrap();
+                    Self::wrap(ctx, z3_sys::$f(z3_ctx, rm, self.get_z3_ast()).unwrap())
+                }
+            }
+        )*
+    };
+    (binary -> $ret:ty: $($name:ident => $f:ident),* $(,)?) => {
+        $(
+            fn $name(&self, other: &Self) -> $ret {
+                unsafe {
+                    let ctx = self.get_ctx();
+                    <$ret>::wrap(
+                        ctx,
+                        z3_sys::$f(ctx.get_z3_context(), self.get_z3_ast(), other.get_z3_ast())
+                            .unwrap(),
+                    )
+                }
+            }
+        )*
+    };
+    (unary -> $ret:ty: $($name:ident => $f:ident),* $(,)?) => {
+        $(
+            fn $name(&self) -> $ret {
+                unsafe {
+                    let ctx = self.get_ctx();
+                    <$ret>::wrap(
+                        ctx,
+                        z3_sys::$f(ctx.get_z3_context(), self.get_z3_ast()).unwrap(),
+                    )
+                }
+            }
+        )*
+    };
+}
+
+impl FloatExt for ast::Float {
+    fn from_ieee_bv(bv: &ast::BV, sort: FloatSort) -> Self {
+        debug_assert_eq!(bv.get_size(), sort.e_bits + sort.s_bits);
+        unsafe {
+            let ctx = bv.get_ctx();
+            let z3_ctx = ctx.get_z3_context();
+            let z3_sort = z3_sys::Z3_mk_fpa_sort(z3_ctx, sort.e_bits, sort.s_bits).unwrap();
+            Self::wrap(
+                ctx,
+                z3_sys::Z3_mk_fpa_to_fp_bv(z3_ctx, bv.get_z3_ast(), z3_sort).unwrap(),
+            )
+        }
+    }
+
+    fpa_ops!(unary -> ast::BV: to_ieee_bv => Z3_mk_fpa_to_ieee_bv);
+
+    fn from_int_bv_rne(bv: &ast::BV, is_signed: bool, sort: FloatSort) -> Self {
+        unsafe {
+            let ctx = bv.get_ctx();
+            let z3_ctx = ctx.get_z3_context();
+            let z3_sort = z3_sys::Z3_mk_fpa_sort(z3_ctx, sort.e_bits, sort.s_bits).unwrap();
+            float_from_int_bv(bv, is_signed, z3_sort)
+        }
+    }
+
+    fn to_float_rne(&self, sort: FloatSort) -> Self {
+        unsafe {
+            let ctx = self.get_ctx();
+            let z3_ctx = ctx.get_z3_context();
+            let rm = z3_sys::Z3_mk_fpa_round_nearest_ties_to_even(z3_ctx).unwrap();
+            let z3_sort = z3_sys::Z3_mk_fpa_sort(z3_ctx, sort.e_bits, sort.s_bits).unwrap();
+            Self::wrap(
+                ctx,
+                z3_sys::Z3_mk_fpa_to_fp_float(z3_ctx, rm, self.get_z3_ast(), z3_sort).unwrap(),
+            )
+        }
+    }
+
+    fn to_int_bv_saturating(&self, size: u32, is_signed: bool) -> ast::BV {
+        let (truncated, z3_sort) = unsafe {
+            let ctx = self.get_ctx();
+            let z3_ctx = ctx.get_z3_context();
+            let rm = z3_sys::Z3_mk_fpa_round_toward_zero(z3_ctx).unwrap();
+            let to_bv = if is_signed {
+                z3_sys::Z3_mk_fpa_to_sbv
+            } else {
+                z3_sys::Z3_mk_fpa_to_ubv
+            };
+            (
+                ast::BV::wrap(ctx, to_bv(z3_ctx, rm, self.get_z3_ast(), size).unwrap()),
+                z3_sys::Z3_get_sort(z3_ctx, self.get_z3_ast()).unwrap(),
+            )
+        };
+        // 2^k, which is exact in the sort unless it is out of its range (i.e., infinity).
+        let pow2 = |k: u32| unsafe {
+            let bv = ast::BV::from_u64(1, 1).concat(&ast::BV::from_u64(0, k));
+            float_from_int_bv(&bv, false, z3_sort)
+        };
+
+        let all_ones = ast::BV::from_i64(-1, size);
+        let (min, max, is_below, is_above) = if is_signed {
+            let bound = pow2(size - 1);
+            let max = all_ones.bvlshr(&ast::BV::from_u64(1, size));
+            (
+                max.bvnot(),
+                max,
+                self.fp_lt(&bound.neg()),
+                self.fp_ge(&bound),
+            )
+        } else {
+            let zero = unsafe { float_from_int_bv(&ast::BV::from_u64(0, 1), false, z3_sort) };
+            (
+                ast::BV::from_u64(0, size),
+                all_ones,
+                self.fp_lt(&zero),
+                self.fp_ge(&pow2(size)),
+            )
+        };
+
+        self.is_nan().ite(
+            &ast::BV::from_u64(0, size),
+            &is_above.ite(&max, &is_below.ite(&min, &truncated)),
+        )
+    }
+
+    fpa_ops!(rne:
+        add_rne => Z3_mk_fpa_add,
+        sub_rne => Z3_mk_fpa_sub,
+        mul_rne => Z3_mk_fpa_mul,
+        div_rne => Z3_mk_fpa_div,
+    );
+    fpa_ops!(rne unary: sqrt_rne => Z3_mk_fpa_sqrt);
+    fpa_ops!(binary -> Self:
+        ieee_rem => Z3_mk_fpa_rem,
+        fp_min => Z3_mk_fpa_min,
+        fp_max => Z3_mk_fpa_max,
+    );
+
+    fpa_ops!(unary -> Self:
+        neg => Z3_mk_fpa_neg,
+        abs => Z3_mk_fpa_abs,
+    );
+
+    fpa_ops!(binary -> ast::Bool:
+        fp_eq => Z3_mk_fpa_eq,
+        fp_lt => Z3_mk_fpa_lt,
+        fp_le => Z3_mk_fpa_leq,
+        fp_gt => Z3_mk_fpa_gt,
+        fp_ge => Z3_mk_fpa_geq,
+    );
+
+    fpa_ops!(unary -> ast::Bool:
+        is_negative => Z3_mk_fpa_is_negative,
+        is_zero => Z3_mk_fpa_is_zero,
+        is_nan => Z3_mk_fpa_is_nan,
+    );
+}
+
+/// # Safety
+/// `sort` must be a floating-point sort of the context of `bv`.
+unsafe fn float_from_int_bv(bv: &ast::BV, is_signed: bool, sort: z3_sys::Z3_sort) -> ast::Float {
+    unsafe {
+        let ctx = bv.get_ctx();
+        let z3_ctx = ctx.get_z3_context();
+        let rm = z3_sys::Z3_mk_fpa_round_nearest_ties_to_even(z3_ctx).unwrap();
+        let to_fp = if is_signed {
+            z3_sys::Z3_mk_fpa_to_fp_signed
+        } else {
+            z3_sys::Z3_mk_fpa_to_fp_unsigned
+        };
+        ast::Float::wrap(ctx, to_fp(z3_ctx, rm, bv.get_z3_ast(), sort).unwrap())
+    }
+}
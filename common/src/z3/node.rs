@@ -13,6 +13,7 @@ use z3::ast::{self, Ast};
 pub enum AstNode {
     Bool(ast::Bool),
     BitVector(BVNode),
+    Float(FloatNode),
     Array(ArrayNode),
 }
 
@@ -22,6 +23,12 @@ impl From<BVNode> for AstNode {
     }
 }
 
+impl From<FloatNode> for AstNode {
+    fn from(node: FloatNode) -> Self {
+        Self::Float(node)
+    }
+}
+
 impl From<ArrayNode> for AstNode {
     fn from(node: ArrayNode) -> Self {
         Self::Array(node)
@@ -56,6 +63,20 @@ impl BVNode {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, dm::Display)]
+#[display("{_0}")]
+pub struct FloatNode(pub ast::Float, pub FloatSort);
+
+impl FloatNode {
+    #[inline]
+    pub fn map<F>(&self, f: F) -> Self
+    where
+        F: FnOnce(&ast::Float) -> ast::Float,
+    {
+        Self(f(&self.0), self.1)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, dm::Display)]
 #[display("{_0}")]
 pub struct ArrayNode(pub ast::Array, pub ArraySort);
@@ -64,6 +85,7 @@ pub struct ArrayNode(pub ast::Array, pub ArraySort);
 pub enum AstNodeSort {
     Bool,
     BitVector(BVSort),
+    Float(FloatSort),
     Array(ArraySort),
 }
 
@@ -72,9 +94,18 @@ pub struct BVSort {
     pub is_signed: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, dm::From, Serialize, Deserialize)]
+/// An IEEE-754 floating-point sort, with the significand size including the hidden bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FloatSort {
+    pub e_bits: u32,
+    pub s_bits: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ArraySort {
     pub range: Box<AstNodeSort>,
+    /// The number of elements, as the domain of the array covers all the indices.
+    pub len: u64,
 }
 
 impl From<ast::Bool> for AstNode {
@@ -94,6 +125,9 @@ impl AstNode {
             AstNodeSort::BitVector(sort) => {
                 ast.as_bv().map(|ast| Self::BitVector(BVNode(ast, *sort)))
             }
+            AstNodeSort::Float(sort) => {
+                ast.as_float().map(|ast| Self::Float(FloatNode(ast, *sort)))
+            }
             AstNodeSort::Array(sort) => ast
                 .as_array()
                 .map(|ast| Self::Array(ArrayNode(ast, sort.clone()))),
@@ -135,6 +169,7 @@ impl AstNode {
         match self {
             Self::Bool(ast) => ast,
             Self::BitVector(BVNode(ast, _)) => ast,
+            Self::Float(FloatNode(ast, _)) => ast,
             Self::Array(ArrayNode(ast, _)) => ast,
         }
     }
@@ -147,6 +182,7 @@ impl AstNode {
         match self {
             Self::Bool(_) => AstNodeSort::Bool,
             Self::BitVector(BVNode(_, sort)) => AstNodeSort::BitVector(*sort),
+            Self::Float(FloatNode(_, sort)) => AstNodeSort::Float(*sort),
             Self::Array(ArrayNode(_, sort)) => AstNodeSort::Array(sort.clone()),
         }
     }
@@ -155,6 +191,7 @@ impl AstNode {
         match self {
             Self::Bool(ast) => ast.get_sort(),
             Self::BitVector(BVNode(ast, _)) => ast.get_sort(),
+            Self::Float(FloatNode(ast, _)) => ast.get_sort(),
             Self::Array(ArrayNode(ast, _)) => ast.get_sort(),
         }
     }
@@ -168,6 +205,7 @@ impl AstNode {
         match self {
             Self::Bool(ast) => to_smt_string!(ast),
             Self::BitVector(BVNode(ast, _)) => to_smt_string!(ast),
+            Self::Float(FloatNode(ast, _)) => to_smt_string!(ast),
             Self::Array(ArrayNode(ast, _)) => to_smt_string!(ast),
         }
     }
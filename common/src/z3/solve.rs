@@ -5,7 +5,7 @@ use z3::{
 };
 
 use std::prelude::rust_2024::*;
-use std::{collections::HashMap, hash::Hash};
+use std::{collections::HashMap, hash::Hash, rc::Rc};
 
 use super::super::{
     log_debug,
@@ -26,7 +26,9 @@ trait Z3Solver {
 
     fn assert(&self, ast: &ast::Bool);
     fn check(&self) -> SatResult;
+    fn check_assumptions(&self, assumptions: &[ast::Bool]) -> SatResult;
     fn get_model(&self) -> Option<Model>;
+    fn get_unsat_core(&self) -> Vec<ast::Bool>;
 }
 
 impl Z3Solver for Solver {
@@ -36,7 +38,9 @@ impl Z3Solver for Solver {
 
             fn assert(&self, ast: &ast::Bool);
             fn check(&self) -> SatResult;
+            fn check_assumptions(&self, assumptions: &[ast::Bool]) -> SatResult;
             fn get_model(&self) -> Option<Model>;
+            fn get_unsat_core(&self) -> Vec<ast::Bool>;
         }
     }
 
@@ -53,12 +57,17 @@ impl Z3Solver for Optimize {
 
             fn assert(&self, ast: &ast::Bool);
             fn get_model(&self) -> Option<Model>;
+            fn get_unsat_core(&self) -> Vec<ast::Bool>;
         }
     }
 
     fn check(&self) -> SatResult {
         self.check(&[])
     }
+
+    fn check_assumptions(&self, assumptions: &[ast::Bool]) -> SatResult {
+        self.check(assumptions)
+    }
 }
 
 impl Z3Solver for SolverImpl {
@@ -77,13 +86,71 @@ impl Z3Solver for SolverImpl {
             #[through(Z3Solver)]
             fn check(&self) -> SatResult;
             #[through(Z3Solver)]
+            fn check_assumptions(&self, assumptions: &[ast::Bool]) -> SatResult;
+            #[through(Z3Solver)]
             fn get_model(&self) -> Option<Model>;
+            #[through(Z3Solver)]
+            fn get_unsat_core(&self) -> Vec<ast::Bool>;
+        }
+    }
+}
+
+const CORE_TRACKER_PREFIX: &str = "core!";
+const ASSUMPTION_TRACKER_PREFIX: &str = "assumption!";
+
+/// Observes the (hard) constraints of each query and its result.
+pub type QueryObserver = Rc<dyn Fn(&[ast::Bool], SatResult)>;
+
+/// The preferred values for the variables, which bias the models toward
+/// human-readable inputs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValuePreferences {
+    /// Prefer integers of small magnitudes.
+    pub small_magnitude: bool,
+    /// Prefer printable ASCII characters for bytes.
+    pub printable_bytes: bool,
+}
+
+impl ValuePreferences {
+    const SMALL_MAGNITUDE: i64 = 255;
+
+    fn is_any(&self) -> bool {
+        self.small_magnitude || self.printable_bytes
+    }
+
+    fn soft_constraints(&self, var: &AstNode) -> Vec<(ast::Bool, u32)> {
+        let AstNode::BitVector(BVNode(var, BVSort { is_signed })) = var else {
+            return Vec::new();
+        };
+        let size = var.get_size();
+        let mut constraints = Vec::new();
+        if self.printable_bytes && size == 8 {
+            let printable = ast::Bool::and(&[
+                &var.bvuge(&ast::BV::from_u64(b' ' as u64, size)),
+                &var.bvule(&ast::BV::from_u64(b'~' as u64, size)),
+            ]);
+            constraints.push((printable, 2));
+        }
+        if self.small_magnitude && size > 8 {
+            let bound = ast::BV::from_i64(Self::SMALL_MAGNITUDE, size);
+            let small = if *is_signed {
+                ast::Bool::and(&[&var.bvsle(&bound), &var.bvsge(&bound.bvneg())])
+            } else {
+                var.bvule(&bound)
+            };
+            constraints.push((small, 1));
         }
+        constraints
     }
 }
 
 pub struct WrappedSolver<I> {
     solver: SolverImpl,
+    query_observer: Option<QueryObserver>,
+    preferences: ValuePreferences,
+    /// The constraints asserted to hold in all the checks and their variables.
+    asserted: Vec<ast::Bool>,
+    asserted_vars: HashMap<I, AstNode>,
     _phantom: core::marker::PhantomData<(I,)>,
 }
 
@@ -95,10 +162,24 @@ impl<I> WrappedSolver<I> {
     pub fn new() -> Self {
         Self {
             solver: SolverImpl::Solver(Solver::new()),
+            query_observer: None,
+            preferences: Default::default(),
+            asserted: Vec::new(),
+            asserted_vars: HashMap::new(),
             _phantom: Default::default(),
         }
     }
 
+    pub fn with_preferences(mut self, preferences: ValuePreferences) -> Self {
+        self.preferences = preferences;
+        self
+    }
+
+    pub fn with_query_observer(mut self, observer: QueryObserver) -> Self {
+        self.query_observer = Some(observer);
+        self
+    }
+
     // pub fn context(&self) -> &'ctx Context {
     //     self.context
     // }
@@ -113,7 +194,11 @@ impl<I> Default for WrappedSolver<I> {
 impl<I> Clone for WrappedSolver<I> {
     fn clone(&self) -> Self {
         // Prevent cloning the assumptions in the solver
-        Self::new()
+        Self {
+            query_observer: self.query_observer.clone(),
+            preferences: self.preferences,
+            ..Self::new()
+        }
     }
 }
 
@@ -124,46 +209,174 @@ where
     pub fn check(
         &self,
         constraints: impl Iterator<Item = Constraint<AstAndVars<I>, AstNode>>,
+    ) -> (SatResult, HashMap<I, AstNode>)
+    where
+        I: Clone,
+    {
+        let (result, model, _) = self.check_with_assumption(constraints, None);
+        (result, model)
+    }
+
+    /// Checks the constraints along with the assumption in a single query.
+    /// # Returns
+    /// The result, the model (if satisfiable), and whether the constraints are
+    /// found to be unsatisfiable regardless of the assumption.
+    /// # Remarks
+    /// The latter is based on the unsat core, which is not necessarily minimal.
+    /// Thus, it is possible to miss the unsatisfiability of the constraints alone.
+    pub fn check_assuming(
+        &self,
+        constraints: impl Iterator<Item = Constraint<AstAndVars<I>, AstNode>>,
+        assumption: Constraint<AstAndVars<I>, AstNode>,
+    ) -> (SatResult, HashMap<I, AstNode>, bool)
+    where
+        I: Clone,
+    {
+        self.check_with_assumption(constraints, Some(assumption))
+    }
+
+    fn check_with_assumption(
+        &self,
+        constraints: impl Iterator<Item = Constraint<AstAndVars<I>, AstNode>>,
+        assumption: Option<Constraint<AstAndVars<I>, AstNode>>,
+    ) -> (SatResult, HashMap<I, AstNode>, bool)
+    where
+        I: Clone,
+    {
+        let mut all_vars = self.asserted_vars.clone();
+        let asts = constraints
+            .map(|constraint| Self::to_ast(constraint, &mut all_vars))
+            .collect::<Vec<_>>();
+        let assumption = assumption.map(|assumption| Self::to_ast(assumption, &mut all_vars));
+
+        // Possible answers (if any) already bias the models.
+        if let (true, SolverImpl::Solver(..)) = (self.preferences.is_any(), &self.solver) {
+            let optimize = Optimize::new();
+            for (constraint, weight) in all_vars
+                .values()
+                .flat_map(|var| self.preferences.soft_constraints(var))
+            {
+                optimize.assert_soft(&constraint, weight, None);
+            }
+            for constraint in &self.asserted {
+                optimize.assert(constraint);
+            }
+            self.check_using(&optimize, &asts, assumption, all_vars)
+        } else {
+            self.check_using(&self.solver, &asts, assumption, all_vars)
+        }
+    }
+
+    /// Asserts the constraint to hold in all the later checks, which are
+    /// performed in their own scopes on top of the asserted constraints.
+    /// This enables checking the queries sharing a growing prefix incrementally.
+    pub fn assert(&mut self, constraint: Constraint<AstAndVars<I>, AstNode>) {
+        let ast = Self::to_ast(constraint, &mut self.asserted_vars);
+        self.solver.assert(&ast);
+        self.asserted.push(ast);
+    }
+
+    /// Checks the hard constraints while maximizing the total weight of
+    /// the soft constraints that are satisfied.
+    /// # Remarks
+    /// A fresh optimizer is used for each call, thus the soft constraints
+    /// do not carry over to the later checks.
+    pub fn check_maximizing(
+        &self,
+        hard: impl Iterator<Item = Constraint<AstAndVars<I>, AstNode>>,
+        soft: impl Iterator<Item = (Constraint<AstAndVars<I>, AstNode>, u32)>,
     ) -> (SatResult, HashMap<I, AstNode>) {
         let mut all_vars = HashMap::<I, AstNode>::new();
-        let asts = constraints
-            .map(|constraint| {
-                let Constraint { discr, kind } = constraint;
-                use ConstraintKind::*;
-                let (kind, negated) = match kind {
-                    True => (True, false),
-                    False => (True, true),
-                    OneOf(options) => (OneOf(options), false),
-                    NoneOf(options) => (OneOf(options), true),
-                };
-
-                let ast = match kind {
-                    True => discr.value.as_bool().clone(),
-                    OneOf(cases) => {
-                        let value_ast = ast::Dynamic::from_ast(discr.value.ast());
-                        cases
-                            .iter()
-                            .map(|c| ast::Dynamic::from_ast(c.ast()))
-                            .map(|c| ast::Dynamic::eq(&value_ast, &c))
-                            .reduce(|all, m| all.xor(&m))
-                            .unwrap()
-                    }
-                    _ => unreachable!(),
-                };
-                all_vars.extend(discr.variables.into_iter());
-                if negated { ast.not() } else { ast }
+        let hard = hard
+            .map(|constraint| Self::to_ast(constraint, &mut all_vars))
+            .collect::<Vec<_>>();
+
+        let optimize = Optimize::new();
+        for (constraint, weight) in soft {
+            optimize.assert_soft(&Self::to_ast(constraint, &mut all_vars), weight, None);
+        }
+
+        let (result, model, _) = self.check_using(&optimize, &hard, None, all_vars);
+        (result, model)
+    }
+
+    /// Checks the constraints and, if they are unsatisfiable, finds an unsatisfiable core.
+    /// # Returns
+    /// The result and the (sorted) indices of the constraints in the core,
+    /// which is empty if the constraints are not unsatisfiable.
+    pub fn check_with_unsat_core(
+        &self,
+        constraints: impl Iterator<Item = Constraint<AstAndVars<I>, AstNode>>,
+    ) -> (SatResult, Vec<usize>) {
+        let mut all_vars = HashMap::<I, AstNode>::new();
+        let solver = Solver::new();
+        let trackers = constraints
+            .enumerate()
+            .map(|(i, constraint)| {
+                let tracker = ast::Bool::new_const(format!("{CORE_TRACKER_PREFIX}{i}"));
+                solver.assert_and_track(&Self::to_ast(constraint, &mut all_vars), &tracker);
+                tracker
             })
             .collect::<Vec<_>>();
 
-        self.check_using(&self.solver, &asts, all_vars)
+        let result = solver.check();
+        let core = match result {
+            SatResult::Unsat => {
+                let mut core = solver
+                    .get_unsat_core()
+                    .iter()
+                    .filter_map(|c| trackers.iter().position(|t| t == c))
+                    .collect::<Vec<_>>();
+                core.sort_unstable();
+                core
+            }
+            SatResult::Sat | SatResult::Unknown => Vec::new(),
+        };
+        (result, core)
+    }
+
+    /// Builds the assertion corresponding to the constraint and collects its variables.
+    pub fn to_ast(
+        constraint: Constraint<AstAndVars<I>, AstNode>,
+        all_vars: &mut HashMap<I, AstNode>,
+    ) -> ast::Bool {
+        let Constraint { discr, kind } = constraint;
+        use ConstraintKind::*;
+        let (kind, negated) = match kind {
+            True => (True, false),
+            False => (True, true),
+            OneOf(options) => (OneOf(options), false),
+            NoneOf(options) => (OneOf(options), true),
+        };
+
+        let ast = match kind {
+            True => discr.value.as_bool().clone(),
+            OneOf(cases) => {
+                let value_ast = ast::Dynamic::from_ast(discr.value.ast());
+                cases
+                    .iter()
+                    .map(|c| ast::Dynamic::from_ast(c.ast()))
+                    .map(|c| ast::Dynamic::eq(&value_ast, &c))
+                    .reduce(|all, m| all.xor(&m))
+                    .unwrap()
+            }
+            _ => unreachable!(),
+        };
+        all_vars.extend(discr.variables.into_iter());
+        if negated { ast.not() } else { ast }
     }
 
+    /// Checks the constraints (and the assumption if any) in a new scope.
+    /// # Returns
+    /// The result, the model (if satisfiable), and whether the constraints are
+    /// unsatisfiable regardless of the assumption.
     fn check_using(
         &self,
         solver: &(impl Z3Solver + ?Sized),
         constraints: &[ast::Bool],
+        assumption: Option<ast::Bool>,
         vars: HashMap<I, AstNode>,
-    ) -> (SatResult, HashMap<I, AstNode>) {
+    ) -> (SatResult, HashMap<I, AstNode>, bool) {
         log_debug!("Sending constraints to Z3: {:#?}", constraints);
 
         solver.push();
@@ -172,7 +385,34 @@ where
             solver.assert(constraint);
         }
 
-        let result = match solver.check() {
+        /* NOTE: The assumption is tracked by a literal, so that the unsat core
+         * tells if it has a part in the unsatisfiability. */
+        let tracker = assumption.as_ref().map(|assumption| {
+            let tracker = ast::Bool::fresh_const(ASSUMPTION_TRACKER_PREFIX);
+            solver.assert(&tracker.implies(assumption));
+            tracker
+        });
+        let sat_result = match tracker.as_ref() {
+            Some(tracker) => solver.check_assumptions(core::slice::from_ref(tracker)),
+            None => solver.check(),
+        };
+        if let Some(observer) = self.query_observer.as_ref() {
+            if self.asserted.is_empty() && assumption.is_none() {
+                observer(constraints, sat_result);
+            } else {
+                observer(
+                    &[self.asserted.as_slice(), constraints, assumption.as_slice()].concat(),
+                    sat_result,
+                );
+            }
+        }
+        let is_unconditionally_unsat = match (sat_result, tracker) {
+            (SatResult::Unsat, Some(tracker)) => !solver.get_unsat_core().contains(&tracker),
+            (SatResult::Unsat, None) => true,
+            (SatResult::Sat | SatResult::Unknown, _) => false,
+        };
+
+        let result = match sat_result {
             SatResult::Sat => {
                 let model = solver.get_model().unwrap();
                 let mut values = HashMap::new();
@@ -182,15 +422,20 @@ where
                         AstNode::BitVector(BVNode(ast, is_signed)) => {
                             AstNode::BitVector(BVNode(model.eval(&ast, true).unwrap(), is_signed))
                         }
+                        AstNode::Float(FloatNode(ast, sort)) => {
+                            AstNode::Float(FloatNode(model.eval(&ast, true).unwrap(), sort))
+                        }
                         AstNode::Array(ArrayNode(ast, sort)) => {
-                            AstNode::Array(ArrayNode(model.eval(&ast, true).unwrap(), sort))
+                            AstNode::Array(ArrayNode(eval_array(&model, &ast, &sort), sort))
                         }
                     };
                     values.insert(id, value.into());
                 }
-                (SatResult::Sat, values)
+                (SatResult::Sat, values, false)
+            }
+            result @ (SatResult::Unsat | SatResult::Unknown) => {
+                (result, HashMap::new(), is_unconditionally_unsat)
             }
-            result @ (SatResult::Unsat | SatResult::Unknown) => (result, HashMap::new()),
         };
 
         solver.pop();
@@ -204,7 +449,11 @@ where
 {
     pub fn consider_possible_answer(&mut self, var: AstNode, answer: AstNode) {
         if let SolverImpl::Solver(..) = self.solver {
-            self.solver = SolverImpl::Optimize(Optimize::new());
+            let optimize = Optimize::new();
+            for constraint in &self.asserted {
+                optimize.assert(constraint);
+            }
+            self.solver = SolverImpl::Optimize(optimize);
         }
         let SolverImpl::Optimize(optimize) = &mut self.solver else {
             unreachable!();
@@ -224,3 +473,72 @@ pub fn set_global_params<K: AsRef<str>, V: AsRef<str>>(params: impl Iterator<Ite
         z3::set_global_param(k.as_ref(), v.as_ref());
     }
 }
+
+/// Evaluates the array element by element and puts the elements in a chain of
+/// stores, as models may represent arrays in forms that do not directly give
+/// their elements (e.g., `as-array` or lambdas).
+fn eval_array(model: &Model, array: &ast::Array, sort: &ArraySort) -> ast::Array {
+    use z3::ast::Ast;
+
+    let domain = array.get_sort().array_domain().unwrap();
+    let index_size = domain.bv_size().unwrap();
+    let element_at = |i: u64| {
+        model
+            .eval(&array.select(&ast::BV::from_u64(i, index_size)), true)
+            .unwrap()
+    };
+    (1..sort.len).fold(
+        ast::Array::const_array(&domain, &element_at(0)),
+        |result, i| result.store(&ast::BV::from_u64(i, index_size), &element_at(i)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use z3::ast::Ast;
+
+    use super::*;
+
+    const INDEX_SIZE: u32 = 64;
+
+    fn index(i: u64) -> ast::BV {
+        ast::BV::from_u64(i, INDEX_SIZE)
+    }
+
+    #[test]
+    fn model_arrays_are_evaluated_element_wise() {
+        let array = ast::Array::new_const(
+            "arr",
+            &z3::Sort::bitvector(INDEX_SIZE),
+            &z3::Sort::bitvector(8),
+        );
+        let solver = Solver::new();
+        for (i, value) in [(0, 4), (1, 7), (2, 9)] {
+            solver.assert(
+                &array
+                    .select(&index(i))
+                    .as_bv()
+                    .unwrap()
+                    .eq(&ast::BV::from_u64(value, 8)),
+            );
+        }
+        assert_eq!(solver.check(), SatResult::Sat);
+        let model = solver.get_model().unwrap();
+
+        let sort = ArraySort {
+            range: Box::new(AstNodeSort::BitVector(BVSort { is_signed: false })),
+            len: 3,
+        };
+        let evaluated = eval_array(&model, &array, &sort);
+        for (i, value) in [(0, 4), (1, 7), (2, 9)] {
+            assert_eq!(
+                evaluated
+                    .select(&index(i))
+                    .simplify()
+                    .as_bv()
+                    .and_then(|bv| bv.as_u64()),
+                Some(value)
+            );
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! A Cargo subcommand to build and run whole Cargo projects with Leaf.
+//!
+//! `cargo leaf build` and `cargo leaf run` run the corresponding Cargo commands
+//! with `leafc` as the compiler wrapper, so all the crates in the dependency
+//! graph are compiled by `leafc`. The rest of the arguments are passed to Cargo
+//! as they are.
+//!
+//! The configurations of `leafc` are propagated through the environment. In
+//! addition to `LEAFC_*` variables (inherited as usual), the configuration file
+//! found for the current directory is passed as a string, and the workspace
+//! configuration file (`leafc.toml`) is passed by its path, because Cargo
+//! invokes the compiler in the directories of the dependencies as well.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode},
+};
+
+use clap::{Args, Parser, Subcommand};
+
+const ENV_CARGO: &str = "CARGO";
+const ENV_RUSTC_WRAPPER: &str = "RUSTC_WRAPPER";
+const ENV_LD_LIBRARY_PATH: &str = "LD_LIBRARY_PATH";
+const ENV_LEAFC_CONFIG_STR: &str = "LEAFC_CONFIG_STR";
+const ENV_LEAFC_CONFIG_STR_FORMAT: &str = "LEAFC_CONFIG_STR_FMT";
+const ENV_LEAFC_CONFIG_PATH: &str = "LEAFC_CONFIG_PATH";
+
+const FILE_LEAFC: &str = "leafc";
+const FILE_CONFIG_DEFAULT: &str = "leafc_config.toml";
+const FILE_CONFIG_WORKSPACE: &str = "leafc.toml";
+const FILE_RUNTIME_DYLIB: &str = "libleafrt.so";
+const DIR_RUNTIME_DYLIB_PREFIX: &str = "runtime_";
+
+// The deps folder when building the project. Set by the build script.
+const DIR_DEPS: &str = env!("DEPS_DIR");
+
+#[derive(Parser)]
+#[command(name = "cargo", bin_name = "cargo")]
+enum Cargo {
+    /// Builds or runs a Cargo project instrumented by Leaf.
+    Leaf(LeafArgs),
+}
+
+#[derive(Args)]
+struct LeafArgs {
+    #[command(subcommand)]
+    command: LeafCommand,
+}
+
+#[derive(Subcommand)]
+enum LeafCommand {
+    /// Builds the project using `leafc`.
+    Build(CommonArgs),
+    /// Builds the project using `leafc` and runs it with the given runtime.
+    Run {
+        /// The runtime flavor to run the program with, e.g., `symex` or `cf_tracer`.
+        /// If not set, the runtime linked by `leafc` is used.
+        #[arg(long)]
+        runtime: Option<String>,
+        #[command(flatten)]
+        common: CommonArgs,
+    },
+}
+
+#[derive(Args)]
+struct CommonArgs {
+    /// The configuration file for `leafc`.
+    /// By default, `leafc_config.toml` is searched for in the current directory and its ancestors.
+    #[arg(long)]
+    leafc_config: Option<PathBuf>,
+    /// The arguments passed to Cargo.
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    cargo_args: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    let Cargo::Leaf(args) = Cargo::parse();
+
+    match run(args.command) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: LeafCommand) -> Result<ExitCode, String> {
+    let (cargo_command, common, runtime) = match command {
+        LeafCommand::Build(common) => ("build", common, None),
+        LeafCommand::Run { runtime, common } => ("run", common, runtime),
+    };
+
+    let mut cmd = Command::new(env::var_os(ENV_CARGO).unwrap_or_else(|| "cargo".into()));
+    cmd.arg(cargo_command).args(&common.cargo_args);
+
+    // The `leafc` installed next to this executable is preferred over the one in the path.
+    cmd.env(
+        ENV_RUSTC_WRAPPER,
+        common::utils::find_exe_next_to_current(FILE_LEAFC),
+    );
+    propagate_leafc_config(&mut cmd, common.leafc_config.as_deref())?;
+    propagate_workspace_config(&mut cmd);
+
+    if let Some(flavor) = runtime {
+        let runtime_dir = provide_runtime_dylib_dir(&flavor)?;
+        let paths = env::var_os(ENV_LD_LIBRARY_PATH).unwrap_or_default();
+        let paths = env::join_paths(std::iter::once(runtime_dir).chain(env::split_paths(&paths)))
+            .map_err(|e| e.to_string())?;
+        cmd.env(ENV_LD_LIBRARY_PATH, paths);
+    }
+
+    let status = cmd
+        .status()
+        .map_err(|e| format!("Failed to run cargo: {e}"))?;
+    Ok(status
+        .code()
+        .map_or(ExitCode::FAILURE, |code| ExitCode::from(code as u8)))
+}
+
+fn propagate_leafc_config(cmd: &mut Command, config_path: Option<&Path>) -> Result<(), String> {
+    if env::var_os(ENV_LEAFC_CONFIG_STR).is_some() {
+        return Ok(());
+    }
+
+    let config_path = match config_path {
+        Some(path) => path.to_path_buf(),
+        None => {
+            let Some(path) = env::current_dir().ok().and_then(|d| {
+                d.ancestors()
+                    .find_map(|d| common::utils::try_join_path(d, FILE_CONFIG_DEFAULT))
+            }) else {
+                return Ok(());
+            };
+            path
+        }
+    };
+
+    let content = fs::read_to_string(&config_path).map_err(|e| {
+        format!(
+            "Failed to read leafc configuration file `{}`: {e}",
+            config_path.display()
+        )
+    })?;
+    let format = config_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("toml");
+    cmd.env(ENV_LEAFC_CONFIG_STR, content)
+        .env(ENV_LEAFC_CONFIG_STR_FORMAT, format);
+    Ok(())
+}
+
+fn propagate_workspace_config(cmd: &mut Command) {
+    if env::var_os(ENV_LEAFC_CONFIG_PATH).is_some() {
+        return;
+    }
+
+    if let Some(path) = common::utils::find_current_workspace_root()
+        .and_then(|root| common::utils::try_join_path(root, FILE_CONFIG_WORKSPACE))
+    {
+        cmd.env(ENV_LEAFC_CONFIG_PATH, path);
+    }
+}
+
+/// Makes the runtime dylib of the flavor available under the name the programs
+/// are linked against, i.e., `runtime_<flavor>/libleafrt.so` next to the flavor's library.
+/// # Returns
+/// The directory containing the runtime dylib.
+fn provide_runtime_dylib_dir(flavor: &str) -> Result<PathBuf, String> {
+    let file_name = format!("libleafrt_{flavor}.so");
+    let physical_path = common::utils::search_next_to_exe_for(&file_name)
+        .or_else(|| common::utils::try_join_path(DIR_DEPS, &file_name))
+        .ok_or_else(|| {
+            format!(
+                "Could not find the runtime library `{file_name}`. Is the runtime flavor built?"
+            )
+        })?;
+
+    let runtime_dir = physical_path
+        .parent()
+        .unwrap()
+        .join(format!("{DIR_RUNTIME_DYLIB_PREFIX}{flavor}"));
+    fs::create_dir_all(&runtime_dir).map_err(|e| e.to_string())?;
+
+    let link_path = runtime_dir.join(FILE_RUNTIME_DYLIB);
+    if fs::symlink_metadata(&link_path).is_ok() {
+        fs::remove_file(&link_path).map_err(|e| e.to_string())?;
+    }
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&physical_path, &link_path).map_err(|e| e.to_string())?;
+    #[cfg(windows)]
+    std::os::windows::fs::symlink_file(&physical_path, &link_path).map_err(|e| e.to_string())?;
+
+    Ok(runtime_dir)
+}
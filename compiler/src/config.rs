@@ -1,9 +1,12 @@
+use std::{collections::HashMap, env, path::PathBuf};
+
 use derive_more::{Deref, derive::From};
 use serde::Deserialize;
 
-use crate::CONFIG_ENV_PREFIX;
 use crate::passes::{InstrumentationRules, InternalizationRules};
+use crate::{CONFIG_ENV_PREFIX, constants::ENV_CONFIG_PATH};
 use common::{log_error, log_info};
+use rules::{GlobPattern, InclusionRules};
 
 #[derive(Debug, Default, Clone, Deserialize)]
 pub(crate) struct LeafCompilerConfig {
@@ -15,12 +18,28 @@ pub(crate) struct LeafCompilerConfig {
     pub override_sysroot: bool,
     #[serde(default = "default_codegen_all_mir")]
     pub codegen_all_mir: bool,
+    /// The runtime flavor (e.g., `symex`) that the programs are linked against.
+    /// If not set, the programs are linked against the no-op runtime, and the
+    /// flavor is expected to be provided at execution.
+    #[serde(default)]
+    pub runtime_flavor: Option<String>,
+    /// The directory that the program information (e.g., types and program map)
+    /// is exported to. If not set, the output directory of the crate is used.
+    #[serde(default)]
+    pub exports_dir: Option<PathBuf>,
     #[serde(default = "default_marker_cfg_name")]
     pub marker_cfg_name: String,
+    /// If set, the programs are not instrumented, and instead a report of what
+    /// would be instrumented and the constructs that are not supported is
+    /// exported (to `exports_dir` or the output directory of the crate).
+    #[serde(default)]
+    pub dry_run: bool,
     #[serde(default)]
     #[serde(alias = "rules")]
     instr_rules: InstrumentationRules,
     #[serde(default)]
+    pub filter: InstrumentationFilter,
+    #[serde(default)]
     pub passes: PassesConfig,
 }
 
@@ -101,6 +120,7 @@ fn default_runtime_shim_crate_name() -> String {
 }
 
 const CONFIG_FILENAME: &str = "leafc_config";
+const WORKSPACE_CONFIG_FILENAME: &str = "leafc.toml";
 
 #[derive(Debug, Default, Clone, Deserialize)]
 pub(crate) struct PassesConfig {
@@ -113,13 +133,15 @@ pub(crate) struct PassesConfig {
     #[serde(default)]
     pub internalization: GatedPassConfig<InternalizationPassConfig>,
     #[serde(default)]
-    pub program_map: GatedPassConfig<()>,
+    pub program_map: GatedPassConfig<ProgramMapPassConfig>,
     #[serde(default)]
     pub program_dep: GatedPassConfig<()>,
     #[serde(default)]
     pub type_export: GatedPassConfig<()>,
     #[serde(default)]
     pub md_info: GatedPassConfig<()>,
+    #[serde(default)]
+    pub plugins: PluginsConfig,
 }
 
 #[derive(Debug, Clone, Deserialize, Deref)]
@@ -131,6 +153,17 @@ pub(crate) struct GatedPassConfig<T> {
     pub config: T,
 }
 
+/// Per-plugin configurations, keyed by the names plugins are registered with.
+#[derive(Debug, Default, Clone, Deserialize, Deref)]
+pub(crate) struct PluginsConfig(HashMap<String, GatedPassConfig<PluginConfig>>);
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct PluginConfig {
+    /// The path to the dynamic library of the plugin.
+    #[cfg_attr(not(feature = "plugins"), allow(dead_code))]
+    pub path: PathBuf,
+}
+
 fn default_pass_enabled() -> bool {
     true
 }
@@ -144,6 +177,40 @@ impl<T: Default> Default for GatedPassConfig<T> {
     }
 }
 
+/// Coarse filters on the crates and the definitions to instrument, given as glob
+/// patterns, e.g., `serde*` for crate names or `my_crate::parser::*` for def paths.
+/// An item passes a filter if it matches any of the `include` patterns (or none is given)
+/// and none of the `exclude` patterns.
+/// # Remarks
+/// The filters are consulted after the attributes and the instrumentation rules,
+/// i.e., they only exclude the items that are not explicitly decided otherwise.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub(crate) struct InstrumentationFilter {
+    #[serde(default)]
+    pub crates: InclusionRules<GlobPattern>,
+    #[serde(default)]
+    pub def_paths: InclusionRules<GlobPattern>,
+}
+
+impl InstrumentationFilter {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.crates.is_empty() && self.def_paths.is_empty()
+    }
+
+    pub(crate) fn accepts_crate(&self, crate_name: &str) -> bool {
+        accepts_by_globs(&self.crates, crate_name)
+    }
+
+    pub(crate) fn accepts_def_path(&self, def_path: &str) -> bool {
+        accepts_by_globs(&self.def_paths, def_path)
+    }
+}
+
+fn accepts_by_globs(rules: &InclusionRules<GlobPattern>, subject: &str) -> bool {
+    let matches = |patterns: &[GlobPattern]| patterns.iter().any(|p| p.matches(subject));
+    (rules.include.is_empty() || matches(&rules.include)) && !matches(&rules.exclude)
+}
+
 #[derive(Debug, Default, Clone, Deserialize)]
 pub(crate) struct InstrumentationPassConfig {
     #[serde(default)]
@@ -156,10 +223,33 @@ pub(crate) struct InternalizationPassConfig {
     pub(crate) rules: InternalizationRules,
 }
 
+/// Graphviz DOT renderings of the program map, written next to it.
+/// The bodies are selected by glob patterns on their def paths.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub(crate) struct ProgramMapPassConfig {
+    /// Whether to render the call graph (`call_graph.dot`).
+    #[serde(default)]
+    pub(crate) dot: bool,
+    /// Limits the rendered call graph to the bodies on the call paths from the
+    /// entry point to the bodies matching the pattern, e.g., the target of a
+    /// directed search.
+    #[serde(default)]
+    pub(crate) dot_towards: Option<GlobPattern>,
+    /// The bodies to render the control flow graph of (`cfg_<crate>_<index>_<discr>.dot`).
+    #[serde(default)]
+    pub(crate) dot_cfgs: Vec<GlobPattern>,
+}
+
 pub(super) fn load_config() -> LeafCompilerConfig {
     let mut config: LeafCompilerConfig =
         common::config::load_config(CONFIG_FILENAME, CONFIG_ENV_PREFIX, |b| {
             Ok(b)
+                .map(|b| match find_workspace_config() {
+                    Some((path, required)) => {
+                        b.add_source(::config::File::from(path).required(required))
+                    }
+                    None => b,
+                })
                 .and_then(|b| {
                     b.set_default(
                         format!(
@@ -203,6 +293,23 @@ pub(super) fn load_config() -> LeafCompilerConfig {
     config
 }
 
+/// # Returns
+/// The path to the workspace-level configuration file, and whether it is required.
+/// The path given explicitly by the environment takes precedence over `leafc.toml`
+/// in the root of the current Cargo workspace.
+/// # Remarks
+/// The workspace configuration has the lowest priority among the sources, i.e.,
+/// the configuration file next to the crate and the environment variables override it.
+fn find_workspace_config() -> Option<(PathBuf, bool)> {
+    if let Some(path) = env::var_os(ENV_CONFIG_PATH) {
+        return Some((PathBuf::from(path), true));
+    }
+
+    common::utils::find_current_workspace_root()
+        .and_then(|root| common::utils::try_join_path(root, WORKSPACE_CONFIG_FILENAME))
+        .map(|path| (path, false))
+}
+
 pub(crate) mod rules {
     use super::*;
 
@@ -249,6 +356,18 @@ pub(crate) mod rules {
     #[derive(Debug, Clone, Deserialize, Deref, From)]
     pub(crate) struct PatternMatch(String);
 
+    #[derive(Debug, Clone, Deserialize, Deref)]
+    #[serde(try_from = "String")]
+    pub(crate) struct GlobPattern(glob::Pattern);
+
+    impl TryFrom<String> for GlobPattern {
+        type Error = glob::PatternError;
+
+        fn try_from(pattern: String) -> Result<Self, Self::Error> {
+            glob::Pattern::new(&pattern).map(GlobPattern)
+        }
+    }
+
     #[derive(Debug, Clone, Deserialize)]
     #[serde(untagged)]
     pub(crate) enum LogicFormula<T> {
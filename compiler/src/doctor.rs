@@ -0,0 +1,381 @@
+//! A diagnostic tool that checks the installation of Leaf end to end.
+//!
+//! Each check reports whether a piece that the compilation or the execution of
+//! the instrumented programs relies on is in place, along with a hint to fix it
+//! otherwise. The checks are independent of each other and all are run, so the
+//! report reflects every problem found at once.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode, Output},
+};
+
+const FILE_LEAFC: &str = "leafc";
+const FILE_RUNTIME_DYLIB_PREFIX: &str = "libleafrt_";
+const FILE_RUNTIME_DYLIB_SUFFIX: &str = ".so";
+const FILE_SHIM_LIB: &str = "libleafrtsh.rlib";
+const FILE_TOOLCHAIN_MARKER: &str = ".leafc_toolchain";
+
+const DIR_RUNTIME_SHIM_LIB: &str = "runtime_shim";
+const DIR_TOOLCHAINS: &str = "leafc_toolchains";
+const DIR_TOOLCHAIN_BUILDER: [&str; 2] = ["toolchain_builder", "build"];
+
+const FLAVOR_NOOP: &str = "noop";
+const FLAVOR_SYMEX: &str = "symex";
+// The flavors that are built along with the compiler (its dependencies).
+const FLAVORS_BUILT: [&str; 3] = [FLAVOR_NOOP, FLAVOR_SYMEX, "symex_no_implicit"];
+
+const ENV_RUSTUP_TOOLCHAIN: &str = "RUSTUP_TOOLCHAIN";
+const ENV_LEAFC_CODEGEN_ALL_MIR: &str = "LEAFC_CODEGEN_ALL_MIR";
+
+// The deps folder when building the project. Set by the build script.
+const DIR_DEPS: &str = env!("DEPS_DIR");
+
+const TOOLCHAIN_FILE_CONTENT: &str =
+    include_str!(concat!(env!("WORKSPACE_DIR"), "/rust-toolchain.toml"));
+
+const SMOKE_PROGRAM: &str = r#"
+fn main() {
+    let x = std::env::args().count() as u8;
+    if x > 10 {
+        println!("many");
+    }
+}
+"#;
+
+macro_rules! names {
+    ($($name: ident),* $(,)?) => {
+        &[$(stringify!($name)),*]
+    };
+}
+
+const PRI_FUNC_NAMES: &[&str] = common::pri::pass_func_names_to!(names, all_comma_separated);
+
+enum Status {
+    Ok,
+    Warning,
+    Error,
+}
+
+struct Report {
+    status: Status,
+    summary: String,
+    hint: Option<String>,
+}
+
+impl Report {
+    fn ok(summary: impl Into<String>) -> Self {
+        Self {
+            status: Status::Ok,
+            summary: summary.into(),
+            hint: None,
+        }
+    }
+
+    fn warning(summary: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            status: Status::Warning,
+            summary: summary.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn error(summary: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            status: Status::Error,
+            summary: summary.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+const CHECKS: [(&str, fn() -> Report); 6] = [
+    ("toolchain", check_toolchain),
+    ("sysroot", check_sysroot),
+    ("runtime", check_runtime_dylibs),
+    ("z3", check_z3),
+    ("shim/runtime interface", check_interface),
+    ("smoke program", check_smoke_program),
+];
+
+fn main() -> ExitCode {
+    let mut has_error = false;
+    for (name, check) in CHECKS {
+        let report = check();
+        let label = match report.status {
+            Status::Ok => "ok",
+            Status::Warning => "warning",
+            Status::Error => {
+                has_error = true;
+                "error"
+            }
+        };
+        println!("[{label}] {name}: {}", report.summary);
+        if let Some(hint) = report.hint {
+            println!("    hint: {hint}");
+        }
+    }
+
+    if has_error {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn check_toolchain() -> Report {
+    let Some(channel) = TOOLCHAIN_FILE_CONTENT.lines().find_map(|l| {
+        let (key, value) = l.split_once('=')?;
+        (key.trim() == "channel").then(|| value.trim().trim_matches('"').to_owned())
+    }) else {
+        return Report::warning(
+            "Could not determine the pinned toolchain.",
+            "Check `rust-toolchain.toml` in the Leaf repository.",
+        );
+    };
+
+    if let Ok(active) = env::var(ENV_RUSTUP_TOOLCHAIN) {
+        if !active.starts_with(&channel) {
+            return Report::warning(
+                format!("The active toolchain `{active}` differs from the pinned `{channel}`."),
+                format!("Unset `{ENV_RUSTUP_TOOLCHAIN}` or set it to `{channel}`."),
+            );
+        }
+    }
+
+    match run(Command::new("rustup").args(["run", &channel, "rustc", "--version"])) {
+        Ok(output) => Report::ok(format!("`{channel}` is installed ({}).", output.trim())),
+        Err(RunError::NotFound) => Report::warning(
+            format!("`rustup` is not found to verify the pinned toolchain `{channel}`."),
+            format!("Make sure `{channel}` is the toolchain `leafc` is used with."),
+        ),
+        Err(RunError::Failed(e)) => Report::error(
+            format!("The pinned toolchain `{channel}` is not available: {e}"),
+            format!("Run `rustup toolchain install {channel} --component rustc-dev llvm-tools`."),
+        ),
+    }
+}
+
+fn check_sysroot() -> Report {
+    let has_built_toolchain = exe_dir()
+        .map(|d| d.join(DIR_TOOLCHAINS))
+        .and_then(|d| fs::read_dir(d).ok())
+        .is_some_and(|mut entries| {
+            entries.any(|e| e.is_ok_and(|e| e.path().join(FILE_TOOLCHAIN_MARKER).exists()))
+        });
+    if has_built_toolchain {
+        return Report::ok("A sysroot built for codegen-all mode is available.");
+    }
+
+    if find_dependency(PathBuf::from_iter(DIR_TOOLCHAIN_BUILDER)).is_some() {
+        Report::warning(
+            "No sysroot is built for codegen-all mode yet.",
+            "It will be built on the first compilation in codegen-all mode, which takes a while.",
+        )
+    } else {
+        Report::error(
+            "Neither a sysroot for codegen-all mode nor the toolchain builder is found.",
+            "Reinstall the compiler or disable `codegen_all_mir` in the configuration.",
+        )
+    }
+}
+
+fn check_runtime_dylibs() -> Report {
+    if find_runtime_dylib(FLAVOR_NOOP).is_none() {
+        return Report::error(
+            format!(
+                "The default runtime library `{}` is not found.",
+                dylib_name(FLAVOR_NOOP)
+            ),
+            "Build the runtime flavors along with the compiler (e.g., `cargo build` in the workspace).",
+        );
+    }
+
+    let missing = FLAVORS_BUILT
+        .into_iter()
+        .filter(|f| find_runtime_dylib(f).is_none())
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        Report::ok("The runtime libraries are found.")
+    } else {
+        Report::warning(
+            format!("Some runtime flavors are not built: {}", missing.join(", ")),
+            "Build the missing flavors if you are going to use them.",
+        )
+    }
+}
+
+fn check_z3() -> Report {
+    let Some(symex_lib) = find_runtime_dylib(FLAVOR_SYMEX) else {
+        return Report::warning(
+            "Skipped as the symex runtime library is not found.",
+            "Build the symex flavor to use the symbolic execution backend.",
+        );
+    };
+
+    match run(Command::new("ldd").arg(&symex_lib)) {
+        Ok(output) => {
+            let missing = output
+                .lines()
+                .filter(|l| l.contains("not found"))
+                .map(|l| l.trim().to_owned())
+                .collect::<Vec<_>>();
+            if missing.is_empty() {
+                Report::ok("The dependencies of the symex runtime (including Z3) are resolved.")
+            } else {
+                Report::error(
+                    format!(
+                        "Unresolved dependencies of the symex runtime: {}",
+                        missing.join("; ")
+                    ),
+                    "Install Z3 or add the directory of `libz3.so` to `LD_LIBRARY_PATH`.",
+                )
+            }
+        }
+        Err(e) => Report::warning(
+            format!("Could not inspect the dependencies of the symex runtime: {e}"),
+            "Make sure `libz3.so` is discoverable by the dynamic linker.",
+        ),
+    }
+}
+
+fn check_interface() -> Report {
+    if find_dependency(Path::new(DIR_RUNTIME_SHIM_LIB).join(FILE_SHIM_LIB)).is_none() {
+        return Report::error(
+            "The runtime shim library is not found.",
+            "Rebuild the compiler; the shim is built by its build script.",
+        );
+    }
+
+    let Some(runtime_lib) = find_runtime_dylib(FLAVOR_NOOP) else {
+        return Report::warning(
+            "Skipped as the default runtime library is not found.",
+            "See the runtime check.",
+        );
+    };
+
+    match run(Command::new("nm")
+        .args(["-D", "--defined-only"])
+        .arg(&runtime_lib))
+    {
+        Ok(output) => {
+            let exported = output
+                .lines()
+                .filter_map(|l| l.split_whitespace().last())
+                .collect::<std::collections::HashSet<_>>();
+            let missing = PRI_FUNC_NAMES
+                .iter()
+                .filter(|n| !exported.contains(*n))
+                .collect::<Vec<_>>();
+            if missing.is_empty() {
+                Report::ok(format!(
+                    "The runtime exports all {} functions the shim calls.",
+                    PRI_FUNC_NAMES.len()
+                ))
+            } else {
+                Report::error(
+                    format!(
+                        "The runtime does not export {} functions the shim calls, e.g., `{}`.",
+                        missing.len(),
+                        missing[0]
+                    ),
+                    "The runtime and the compiler are from different versions; rebuild both.",
+                )
+            }
+        }
+        Err(e) => Report::warning(
+            format!("Could not inspect the symbols of the runtime: {e}"),
+            "Make sure `nm` (binutils) is installed.",
+        ),
+    }
+}
+
+fn check_smoke_program() -> Report {
+    let work_dir = env::temp_dir().join(format!("leaf_doctor_{}", std::process::id()));
+    let result = run_smoke_program(&work_dir);
+    let _ = fs::remove_dir_all(&work_dir);
+    match result {
+        Ok(()) => Report::ok("A program is compiled by `leafc` and executed successfully."),
+        Err(e) => Report::error(
+            e,
+            "Run `leafc` on a small program with `LEAFC_LOG=debug` for more details.",
+        ),
+    }
+}
+
+fn run_smoke_program(work_dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(work_dir).map_err(|e| format!("Could not create a work directory: {e}"))?;
+    let src_path = work_dir.join("smoke.rs");
+    fs::write(&src_path, SMOKE_PROGRAM)
+        .map_err(|e| format!("Could not write the smoke program: {e}"))?;
+    let bin_path = work_dir.join("smoke");
+
+    // The `leafc` installed next to this executable is preferred over the one in the path.
+    let leafc = common::utils::find_exe_next_to_current(FILE_LEAFC);
+    run(Command::new(leafc)
+        .arg(&src_path)
+        .arg("-o")
+        .arg(&bin_path)
+        .current_dir(work_dir)
+        // The sysroot is checked separately and building it is too slow for a smoke test.
+        .env(ENV_LEAFC_CODEGEN_ALL_MIR, "false"))
+    .map_err(|e| format!("Compilation of the smoke program failed: {e}"))?;
+
+    run(Command::new(&bin_path).current_dir(work_dir))
+        .map_err(|e| format!("Execution of the smoke program failed: {e}"))?;
+    Ok(())
+}
+
+enum RunError {
+    NotFound,
+    Failed(String),
+}
+
+impl std::fmt::Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunError::NotFound => write!(f, "the command is not found"),
+            RunError::Failed(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// Runs the command and returns its standard output if it succeeds.
+fn run(cmd: &mut Command) -> Result<String, RunError> {
+    let Output {
+        status,
+        stdout,
+        stderr,
+    } = cmd.output().map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => RunError::NotFound,
+        _ => RunError::Failed(e.to_string()),
+    })?;
+    if status.success() {
+        Ok(String::from_utf8_lossy(&stdout).into_owned())
+    } else {
+        let stderr = String::from_utf8_lossy(&stderr);
+        Err(RunError::Failed(format!(
+            "{status}: {}",
+            stderr.lines().last().unwrap_or_default()
+        )))
+    }
+}
+
+fn exe_dir() -> Option<PathBuf> {
+    env::current_exe().ok()?.parent().map(Path::to_path_buf)
+}
+
+/// Searches next to the executable and in the deps folder of the build.
+fn find_dependency(path: impl AsRef<Path>) -> Option<PathBuf> {
+    common::utils::search_next_to_exe_for(&path.as_ref().to_string_lossy())
+        .or_else(|| common::utils::try_join_path(DIR_DEPS, path))
+}
+
+fn dylib_name(flavor: &str) -> String {
+    format!("{FILE_RUNTIME_DYLIB_PREFIX}{flavor}{FILE_RUNTIME_DYLIB_SUFFIX}")
+}
+
+fn find_runtime_dylib(flavor: &str) -> Option<PathBuf> {
+    find_dependency(dylib_name(flavor))
+}
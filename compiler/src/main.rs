@@ -84,7 +84,7 @@ pub fn set_up_compiler() {
 pub fn run_compiler(args: impl IntoIterator<Item = String>) -> std::process::ExitCode {
     let config = config::load_config();
 
-    let args = driver_args::set_up_args(args);
+    let args = driver_args::set_up_args(args, config.runtime_flavor.as_deref());
     log_info!("Running compiler with args: {:?}", args);
 
     let mut callbacks = driver_callbacks::set_up_callbacks(
@@ -151,6 +151,11 @@ mod driver_callbacks {
                 "Leafc will work as the normal Rust compiler as the crate is identified as ineffective."
             );
             Box::new(NoOpPass.to_callbacks())
+        } else if !config.codegen_all_mir && is_filtered_out_crate(&config, crate_options) {
+            log_info!(
+                "Leafc will work as the normal Rust compiler as the crate is excluded by the filter."
+            );
+            Box::new(NoOpPass.to_callbacks())
         } else {
             let mut passes = if config.codegen_all_mir && is_ineffective_crate {
                 log_info!(
@@ -192,22 +197,38 @@ mod driver_callbacks {
         let instrumentation_pass = Instrumentor::new(
             None, /* FIXME */
             config.passes.instrumentation.rules.clone(),
+            config.filter.clone(),
+        );
+        let audit_pass = InstrumentationAuditor::new(
+            config.exports_dir.clone(),
+            config.passes.instrumentation.rules.clone(),
+            config.filter.clone(),
         );
+        // In the dry-run mode, the instrumentation is only audited.
+        let is_instrumenting = !config.dry_run;
 
         let passes = chain!(
             prerequisites_pass,
             MdInfoExporter::default().into_gated(config.passes.md_info.enabled),
-            TypeInfoExporter::default().into_gated(config.passes.type_export.enabled),
-            ProgramMapExporter::default().into_gated(config.passes.program_map.enabled),
-            ProgramDependenceMapExporter::default().into_gated(config.passes.program_dep.enabled),
-            instrumentation_pass.into_gated(config.passes.instrumentation.enabled),
+            TypeInfoExporter::new(config.exports_dir.clone())
+                .into_gated(config.passes.type_export.enabled),
+            ProgramMapExporter::new(
+                config.exports_dir.clone(),
+                config.passes.program_map.config.clone(),
+            )
+            .into_gated(config.passes.program_map.enabled),
+            ProgramDependenceMapExporter::new(config.exports_dir.clone())
+                .into_gated(config.passes.program_dep.enabled),
+            instrumentation_pass
+                .into_gated(is_instrumenting && config.passes.instrumentation.enabled),
+            audit_pass.into_gated(!is_instrumenting && config.passes.instrumentation.enabled),
             InstrumentationCounter::default()
-                .into_gated(config.passes.instrumentation_counter.enabled),
+                .into_gated(is_instrumenting && config.passes.instrumentation_counter.enabled),
             InstrumentationRecursionChecker::default()
-                .into_gated(config.passes.instrumentation_rec_check.enabled),
+                .into_gated(is_instrumenting && config.passes.instrumentation_rec_check.enabled),
         );
 
-        if config.codegen_all_mir {
+        let callbacks: Box<Callbacks> = if config.codegen_all_mir {
             Box::new(
                 chain!(force_codegen_all_pass(), passes,)
                     .into_logged()
@@ -215,7 +236,9 @@ mod driver_callbacks {
             )
         } else {
             Box::new(passes.into_logged().to_callbacks())
-        }
+        };
+
+        with_plugins(callbacks, &config.passes.plugins)
     }
 
     fn build_dep_passes_in_codegen_all_mode(config: &LeafCompilerConfig) -> Box<Callbacks> {
@@ -259,6 +282,24 @@ mod driver_callbacks {
         from_cargo && !is_primary
     }
 
+    /// Returns `true` if the dependency is excluded by the crate filter,
+    /// so it can be compiled as a normal crate.
+    /// # Remarks
+    /// In the codegen all mode, the items of dependencies are instrumented in
+    /// the final crate, thus the passes for dependencies are still required.
+    fn is_filtered_out_crate(
+        config: &LeafCompilerConfig,
+        crate_options: &driver_args::CrateOptions,
+    ) -> bool {
+        /* NOTE: The final (primary) crate is always compiled by Leaf,
+         * as the instrumented generic items of other crates may be instantiated in it. */
+        is_dependency_crate(crate_options.crate_name.as_ref())
+            && crate_options
+                .crate_name
+                .as_ref()
+                .is_some_and(|name| !config.filter.accepts_crate(name))
+    }
+
     fn config_marker_cfg(
         rustc_config: &mut rustc_interface::Config,
         leafc_config: &mut LeafCompilerConfig,
@@ -532,6 +573,8 @@ pub mod constants {
 
     pub const ENV_FORCE_NOOP: &str = concatcp!(CONFIG_ENV_PREFIX, "_FORCE_NOOP");
 
+    pub(crate) const ENV_CONFIG_PATH: &str = concatcp!(CONFIG_ENV_PREFIX, "_CONFIG_PATH");
+
     pub const LOG_PASS_OBJECTS_TAG: &str = super::passes::logger::TAG_OBJECTS;
     pub const LOG_PRI_DISCOVERY_TAG: &str = super::passes::pri_utils::TAG_DISCOVERY;
     pub const LOG_BB_JUMP_TAG: &str = super::mir_transform::TAG_BB_JUMP;
@@ -549,16 +592,21 @@ mod driver_args {
     use std::path::{Path, PathBuf};
     use std::{env, fs, iter};
 
+    const CMD_RUSTC: &str = "rustc";
+
     const CODEGEN_LINK_ARG: &str = "link-arg";
 
     const FILE_RUNTIME_DYLIB_DEFAULT: &str = FILE_RUNTIME_DYLIB_NOOP;
     const FILE_RUNTIME_DYLIB_NOOP: &str = "libleafrt_noop.so";
     #[allow(dead_code)]
     const FILE_RUNTIME_DYLIB: &str = "libleafrt.so";
+    const FILE_RUNTIME_DYLIB_PREFIX: &str = "libleafrt_";
+    const FILE_RUNTIME_DYLIB_SUFFIX: &str = ".so";
 
     const DIR_RUNTIME_DYLIB_DEFAULT: &str = DIR_RUNTIME_DYLIB_NOOP;
     #[allow(dead_code)]
     const DIR_RUNTIME_DYLIB_NOOP: &str = "runtime_noop";
+    const DIR_RUNTIME_DYLIB_PREFIX: &str = "runtime_";
 
     const LIB_RUNTIME: &str = "leafrt";
 
@@ -615,9 +663,18 @@ mod driver_args {
         }
     }
 
-    pub(super) fn set_up_args(given_args: impl IntoIterator<Item = String>) -> Vec<String> {
+    pub(super) fn set_up_args(
+        given_args: impl IntoIterator<Item = String>,
+        runtime_flavor: Option<&str>,
+    ) -> Vec<String> {
         let mut args = given_args.into_iter().collect::<Vec<_>>();
 
+        /* When used as `RUSTC_WRAPPER` (e.g., by `cargo leaf`), the original
+         * compiler is passed as the first argument. */
+        if args.get(1).is_some_and(|arg| is_rustc_path(arg)) {
+            args.remove(1);
+        }
+
         let crate_options = args.parse_crate_options();
 
         /* Add linking to the runtime dynamic library for all commands.
@@ -625,8 +682,7 @@ mod driver_args {
          * with embedded runtime shim, we add it anyway. It won't be effective
          * if there is no linking required.
          * Related to #462. */
-        // FIXME: Use the parsed config instead.
-        set_up_runtime_dylib(&mut args, &crate_options);
+        set_up_runtime_dylib(&mut args, &crate_options, runtime_flavor);
 
         if is_ineffective_crate(&crate_options) {
             return args;
@@ -635,11 +691,61 @@ mod driver_args {
         args
     }
 
-    fn set_up_runtime_dylib(args: &mut Vec<String>, opts: &CrateOptions) {
-        let use_noop_runtime = is_ineffective_crate(opts);
+    fn is_rustc_path(arg: &str) -> bool {
+        /* Besides `rustc` itself, the compiler can be a wrapper or a
+         * toolchain-specific binary (e.g., set through `RUSTC`). So any
+         * existing file other than a source file is taken as the compiler. */
+        let path = Path::new(arg);
+        !arg.starts_with('-')
+            && path.extension().is_none_or(|ext| ext != "rs")
+            && (path
+                .file_stem()
+                .is_some_and(|stem| stem.to_string_lossy().starts_with(CMD_RUSTC))
+                || path.is_file())
+    }
+
+    /// The runtime dylib that a crate is linked against.
+    enum RuntimeDylib<'a> {
+        Default,
+        Noop,
+        Flavor(&'a str),
+    }
 
-        ensure_runtime_dylib_exists(use_noop_runtime);
-        let runtime_dylib_dir = find_runtime_dylib_dir(use_noop_runtime)
+    impl RuntimeDylib<'_> {
+        fn folder_name(&self) -> String {
+            match self {
+                RuntimeDylib::Default => DIR_RUNTIME_DYLIB_DEFAULT.to_owned(),
+                RuntimeDylib::Noop => DIR_RUNTIME_DYLIB_NOOP.to_owned(),
+                RuntimeDylib::Flavor(flavor) => format!("{DIR_RUNTIME_DYLIB_PREFIX}{flavor}"),
+            }
+        }
+
+        fn physical_file_name(&self) -> String {
+            match self {
+                RuntimeDylib::Default => FILE_RUNTIME_DYLIB_DEFAULT.to_owned(),
+                RuntimeDylib::Noop => FILE_RUNTIME_DYLIB_NOOP.to_owned(),
+                RuntimeDylib::Flavor(flavor) => {
+                    format!("{FILE_RUNTIME_DYLIB_PREFIX}{flavor}{FILE_RUNTIME_DYLIB_SUFFIX}")
+                }
+            }
+        }
+    }
+
+    fn set_up_runtime_dylib(
+        args: &mut Vec<String>,
+        opts: &CrateOptions,
+        runtime_flavor: Option<&str>,
+    ) {
+        let runtime_dylib = if is_ineffective_crate(opts) {
+            RuntimeDylib::Noop
+        } else if let Some(flavor) = runtime_flavor {
+            RuntimeDylib::Flavor(flavor)
+        } else {
+            RuntimeDylib::Default
+        };
+
+        ensure_runtime_dylib_exists(&runtime_dylib);
+        let runtime_dylib_dir = find_runtime_dylib_dir(&runtime_dylib)
             .to_string_lossy()
             .to_string();
         // Add the runtime dynamic library as a dynamic dependency.
@@ -661,9 +767,9 @@ mod driver_args {
         );
     }
 
-    fn ensure_runtime_dylib_exists(use_noop_runtime: bool) {
-        ensure_runtime_dylib_dir_exist(use_noop_runtime);
-        let runtime_dylib_dir = PathBuf::from(find_runtime_dylib_dir(use_noop_runtime));
+    fn ensure_runtime_dylib_exists(runtime_dylib: &RuntimeDylib) {
+        ensure_runtime_dylib_dir_exist(runtime_dylib);
+        let runtime_dylib_dir = find_runtime_dylib_dir(runtime_dylib);
 
         fn sym_link_exists(sym_path: &Path) -> bool {
             fs::symlink_metadata(sym_path).is_ok()
@@ -674,11 +780,8 @@ mod driver_args {
             return;
         }
 
-        let physical_dylib_path = if use_noop_runtime {
-            find_dependency_path(FILE_RUNTIME_DYLIB_NOOP, iter::empty())
-        } else {
-            find_dependency_path(FILE_RUNTIME_DYLIB_DEFAULT, iter::empty())
-        };
+        let physical_dylib_path =
+            find_dependency_path(&runtime_dylib.physical_file_name(), iter::empty());
 
         // NOTE: Parallel execution of the compiler may cause race conditions.
         // FIXME: Come up with a better solution.
@@ -701,16 +804,16 @@ mod driver_args {
         .expect("Could not create a symlink to the fallback runtime dylib.");
     }
 
-    fn ensure_runtime_dylib_dir_exist(use_noop_runtime: bool) {
-        let runtime_dylib_folder = get_runtime_dylib_folder(use_noop_runtime);
+    fn ensure_runtime_dylib_dir_exist(runtime_dylib: &RuntimeDylib) {
+        let runtime_dylib_folder = runtime_dylib.folder_name();
         // FIXME: Come up with a better solution.
         retry(MAX_RETRY, std::time::Duration::from_secs(1), || {
-            if try_find_dependency_path(runtime_dylib_folder, iter::empty()).is_none() {
+            if try_find_dependency_path(&runtime_dylib_folder, iter::empty()).is_none() {
                 let runtime_dylib_dir = env::current_exe()
                     .unwrap()
                     .parent()
                     .unwrap()
-                    .join(runtime_dylib_folder);
+                    .join(&runtime_dylib_folder);
                 std::fs::create_dir(&runtime_dylib_dir)
             } else {
                 Ok(())
@@ -719,16 +822,8 @@ mod driver_args {
         .expect("Could not create a symlink to the fallback runtime dylib.");
     }
 
-    fn find_runtime_dylib_dir(use_noop_runtime: bool) -> PathBuf {
-        find_dependency_path(get_runtime_dylib_folder(use_noop_runtime), iter::empty())
-    }
-
-    fn get_runtime_dylib_folder(use_noop_runtime: bool) -> &'static str {
-        if use_noop_runtime {
-            DIR_RUNTIME_DYLIB_NOOP
-        } else {
-            DIR_RUNTIME_DYLIB_DEFAULT
-        }
+    fn find_runtime_dylib_dir(runtime_dylib: &RuntimeDylib) -> PathBuf {
+        find_dependency_path(&runtime_dylib.folder_name(), iter::empty())
     }
 
     fn retry<T, E>(
@@ -1,14 +1,18 @@
-mod instr;
-/// This module hosts general utilities for modifying MIR bodies.
+//! This module hosts general utilities for modifying MIR bodies.
+//! They are independent of the instrumentation (PRI) specifics, thus usable
+//! by any pass that needs to inject code, e.g., calls to other runtime functions.
+//! See [`BodyModificationUnit`] for the entry point.
+
 mod jump;
 mod misc;
+mod modification;
 
 use rustc_middle::mir::{BasicBlock, BasicBlockData, HasLocalDecls, Local, LocalDecl};
 
-pub(crate) use self::instr::BodyInstrumentationUnit;
 use self::jump::JumpTargetAttribute;
 pub(crate) use self::jump::TAG_BB_JUMP;
 pub(crate) use self::misc::{noop_blocks_with, split_blocks_with};
+pub(crate) use self::modification::{BodyModificationUnit, call_terminator};
 
 pub(crate) const NEXT_BLOCK: BasicBlock = BasicBlock::MAX;
 
@@ -41,6 +45,10 @@ pub(crate) enum JumpModificationConstraint {
     None,
     SwitchValue(u128),
     SwitchOtherwise,
+    /// The unwind action of a terminator that continues unwinding in the
+    /// caller, which is turned into a jump to the (cleanup) target.
+    /// The source of the jump is expected to be [`NEXT_BLOCK`].
+    UnwindContinue,
 }
 
 pub(crate) trait JumpTargetModifier {
@@ -1,14 +1,41 @@
+//! Deferred modification of MIR bodies.
+//!
+//! Inserting blocks in a body shifts the indices of the existing blocks, which
+//! makes it hard to refer to them while the body is being visited. Thus,
+//! all the changes are collected in a [`BodyModificationUnit`] and applied at
+//! once when [committed](BodyModificationUnit::commit).
+//!
+//! A typical usage for injecting a call before a block is like the following:
+//! ```rust,ignore
+//! let mut modification = BodyModificationUnit::new(body.local_decls());
+//! let result = modification.add_local(ret_ty);
+//! let call_block = BasicBlockData::new(
+//!     Some(call_terminator(tcx, func_def_id, [], args, result.into(), None, source_info)),
+//!     false,
+//! );
+//! modification.insert_blocks_before(block, [call_block], true);
+//! modification.commit(body, None::<fn(&BasicBlockData)>);
+//! ```
+//! - New blocks jumping to [`NEXT_BLOCK`] continue to the next block in their
+//!   chunk, or the block they were inserted before (after) at the end.
+//! - The indices returned at insertion are placeholders that can be used as
+//!   jump targets (e.g., in [`JumpTargetModifier`]) until the commit.
+//! - Sticky blocks inserted before a block receive the jumps targeting that block,
+//!   while non-sticky ones are only reached through the previous block.
+
 use std::collections::HashMap;
 
 use rustc_ast::Mutability;
 use rustc_index::IndexVec;
 use rustc_middle::{
     mir::{
-        BasicBlock, BasicBlockData, Body, ClearCrossCrate, HasLocalDecls, Local, LocalDecl,
-        LocalDecls, SourceInfo, UnwindAction,
+        BasicBlock, BasicBlockData, Body, CallSource, ClearCrossCrate, HasLocalDecls, Local,
+        LocalDecl, LocalDecls, Operand, Place, SourceInfo, Terminator, TerminatorKind,
+        UnwindAction,
     },
-    ty::Ty,
+    ty::{GenericArg, Ty, TyCtxt},
 };
+use rustc_span::{DUMMY_SP, Spanned, def_id::DefId};
 
 use super::*;
 use common::{log_debug, log_warn};
@@ -20,7 +47,9 @@ struct NewBasicBlock<'tcx> {
     is_sticky: bool,
 }
 
-pub(crate) struct BodyInstrumentationUnit<'tcx> {
+/// Collects the modifications to a MIR body (new locals, new blocks, and
+/// jump retargeting) to be applied together.
+pub(crate) struct BodyModificationUnit<'tcx> {
     all_locals: IndexVec<Local, LocalDecl<'tcx>>,
     first_new_local: Local,
     // new_blocks_before maps BasicBlocks from MIR already in the AST to a list of new basic blocks
@@ -32,7 +61,7 @@ pub(crate) struct BodyInstrumentationUnit<'tcx> {
         HashMap<BasicBlock, Vec<(BasicBlock, JumpModificationConstraint, BasicBlock)>>,
 }
 
-impl<'tcx> BodyInstrumentationUnit<'tcx> {
+impl<'tcx> BodyModificationUnit<'tcx> {
     pub fn new(local_decls: &LocalDecls<'tcx>) -> Self {
         Self {
             /* We clone locals, so we implement HasLocalDecl with both original
@@ -130,13 +159,13 @@ impl JumpModificationConstraint {
     }
 }
 
-impl<'tcx> HasLocalDecls<'tcx> for BodyInstrumentationUnit<'tcx> {
+impl<'tcx> HasLocalDecls<'tcx> for BodyModificationUnit<'tcx> {
     fn local_decls(&self) -> &LocalDecls<'tcx> {
         &self.all_locals
     }
 }
 
-impl<'tcx> BodyLocalManager<'tcx> for BodyInstrumentationUnit<'tcx> {
+impl<'tcx> BodyLocalManager<'tcx> for BodyModificationUnit<'tcx> {
     fn add_local<T>(&mut self, decl_info: T) -> Local
     where
         T: Into<NewLocalDecl<'tcx>>,
@@ -145,7 +174,7 @@ impl<'tcx> BodyLocalManager<'tcx> for BodyInstrumentationUnit<'tcx> {
     }
 }
 
-impl<'tcx> BodyBlockManager<'tcx> for BodyInstrumentationUnit<'tcx> {
+impl<'tcx> BodyBlockManager<'tcx> for BodyModificationUnit<'tcx> {
     fn insert_blocks_before<I>(
         &mut self,
         index: BasicBlock,
@@ -168,7 +197,7 @@ impl<'tcx> BodyBlockManager<'tcx> for BodyInstrumentationUnit<'tcx> {
     }
 }
 
-impl JumpTargetModifier for BodyInstrumentationUnit<'_> {
+impl JumpTargetModifier for BodyModificationUnit<'_> {
     fn modify_jump_target_where(
         &mut self,
         terminator_location: BasicBlock,
@@ -192,7 +221,7 @@ impl JumpTargetModifier for BodyInstrumentationUnit<'_> {
 }
 
 type InsertionPair<'tcx> = (BasicBlock, Vec<NewBasicBlock<'tcx>>);
-impl<'tcx> BodyInstrumentationUnit<'tcx> {
+impl<'tcx> BodyModificationUnit<'tcx> {
     // No blocks actually get added to the MIR of the current body until this function gets called.
     pub fn commit(
         mut self,
@@ -200,7 +229,14 @@ impl<'tcx> BodyInstrumentationUnit<'tcx> {
         check_bb_pre_insert: Option<impl Fn(&BasicBlockData<'tcx>)>,
     ) {
         self.add_new_locals(&mut body.local_decls);
+        self.commit_blocks(body.basic_blocks_mut(), check_bb_pre_insert);
+    }
 
+    fn commit_blocks(
+        mut self,
+        blocks: &mut IndexVec<BasicBlock, BasicBlockData<'tcx>>,
+        check_bb_pre_insert: Option<impl Fn(&BasicBlockData<'tcx>)>,
+    ) {
         if let Some(check_bb_pre_insert) = check_bb_pre_insert {
             for bb in self
                 .new_blocks_before
@@ -215,7 +251,7 @@ impl<'tcx> BodyInstrumentationUnit<'tcx> {
         // this function applies any jump modifications to terminators of blocks as specified
         Self::update_jumps_pre_insert(
             Iterator::chain(
-                body.basic_blocks_mut().iter_enumerated_mut(),
+                blocks.iter_enumerated_mut(),
                 self.new_blocks_before
                     .values_mut()
                     .chain(self.new_blocks_after.values_mut())
@@ -226,12 +262,9 @@ impl<'tcx> BodyInstrumentationUnit<'tcx> {
         );
 
         if !(self.new_blocks_before.is_empty() && self.new_blocks_after.is_empty()) {
-            let index_mapping = Self::insert_new_blocks(
-                body.basic_blocks_mut(),
-                self.new_blocks_before,
-                self.new_blocks_after,
-            );
-            Self::update_jumps_post_insert(body.basic_blocks_mut(), index_mapping);
+            let index_mapping =
+                Self::insert_new_blocks(blocks, self.new_blocks_before, self.new_blocks_after);
+            Self::update_jumps_post_insert(blocks, index_mapping);
         }
     }
 
@@ -439,7 +472,7 @@ impl<'tcx> BodyInstrumentationUnit<'tcx> {
                 .unwrap()
                 .terminator_mut();
             let unwind = terminator.unwind().cloned();
-            
+
             let mut original_block_target = None;
             terminator.successors_mut(|t| {
                 if original_block_target.is_none() {
@@ -470,14 +503,10 @@ impl<'tcx> BodyInstrumentationUnit<'tcx> {
         for (i, mut bb) in chunk.drain(..).enumerate() {
             if i == chunk_len - 1 {
                 let mut updated = false;
-                 bb.data.terminator.as_mut().unwrap().successors_mut(|t| {
-                    
-                assert!(
-                    !updated,
-                    "Expected block with single successor"
-                );
-                *t = original_block_target;
-                updated = true;
+                bb.data.terminator.as_mut().unwrap().successors_mut(|t| {
+                    assert!(!updated, "Expected block with single successor");
+                    *t = original_block_target;
+                    updated = true;
                 });
             }
 
@@ -508,7 +537,12 @@ impl<'tcx> BodyInstrumentationUnit<'tcx> {
     ) where
         'tcx: 'b,
     {
-        let blocks = blocks.filter(|(i, _)| jump_modifications.contains_key(i));
+        let blocks = blocks
+            .filter(|(i, _)| jump_modifications.contains_key(i))
+            .map(|(i, block)| {
+                Self::add_unwind_cleanup(block, jump_modifications.get(&i).unwrap());
+                (i, block)
+            });
 
         update_jumps(
             blocks,
@@ -524,11 +558,45 @@ impl<'tcx> BodyInstrumentationUnit<'tcx> {
                     .cloned()
             },
             false,
-            Some(&|i, c| jump_modifications.get(&i).unwrap().len() == c),
+            Some(&|i, c| {
+                jump_modifications
+                    .get(&i)
+                    .unwrap()
+                    .iter()
+                    .filter(|(_, constraint, _)| {
+                        *constraint != JumpModificationConstraint::UnwindContinue
+                    })
+                    .count()
+                    == c
+            }),
             true,
         );
     }
 
+    /// Turns the unwind action of the terminator from continuing to unwind in
+    /// the caller into a jump to the cleanup block, if requested.
+    fn add_unwind_cleanup(
+        block: &mut BasicBlockData<'tcx>,
+        modifications: &[(BasicBlock, JumpModificationConstraint, BasicBlock)],
+    ) {
+        let Some((_, _, cleanup)) = modifications
+            .iter()
+            .find(|(_, constraint, _)| *constraint == JumpModificationConstraint::UnwindContinue)
+        else {
+            return;
+        };
+
+        match block.terminator_mut().unwind_mut() {
+            Some(unwind) if matches!(unwind, UnwindAction::Continue) => {
+                *unwind = UnwindAction::Cleanup(*cleanup);
+            }
+            unwind => panic!(
+                "Expected a terminator that continues unwinding, found: {:?}",
+                unwind
+            ),
+        }
+    }
+
     fn update_jumps_post_insert(
         blocks: &mut IndexVec<BasicBlock, BasicBlockData<'tcx>>,
         index_mapping: HashMap<BasicBlock, BasicBlock>,
@@ -542,3 +610,184 @@ impl<'tcx> BodyInstrumentationUnit<'tcx> {
         );
     }
 }
+
+/// Creates a terminator calling the function with the given arguments.
+/// The call continues to `target` or to [`NEXT_BLOCK`] if not given.
+pub(crate) fn call_terminator<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    func_def_id: DefId,
+    generic_args: impl IntoIterator<Item = GenericArg<'tcx>>,
+    args: Vec<Operand<'tcx>>,
+    destination: Place<'tcx>,
+    target: Option<BasicBlock>,
+    source_info: SourceInfo,
+) -> Terminator<'tcx> {
+    Terminator {
+        source_info,
+        kind: TerminatorKind::Call {
+            /* NOTE: Check if it is supposed to be the same operand for each function definition,
+             * i.e. caching/lazy singleton. */
+            func: Operand::function_handle(tcx, func_def_id, generic_args, DUMMY_SP),
+            args: args
+                .into_iter()
+                .map(|a| Spanned {
+                    node: a,
+                    span: DUMMY_SP,
+                })
+                .collect(),
+            destination,
+            target: Some(target.unwrap_or(NEXT_BLOCK)),
+            unwind: UnwindAction::Continue,
+            call_source: CallSource::Normal,
+            fn_span: DUMMY_SP,
+        },
+        attributes: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc_index::IndexVec;
+    use rustc_middle::mir::{
+        BasicBlock, BasicBlockData, Local, LocalDecls, Place, SourceInfo, Terminator,
+        TerminatorKind, UnwindAction,
+    };
+    use rustc_span::DUMMY_SP;
+
+    use super::super::{
+        BodyBlockManager, JumpModificationConstraint, JumpTargetModifier, NEXT_BLOCK,
+    };
+    use super::BodyModificationUnit;
+
+    type Blocks<'tcx> = IndexVec<BasicBlock, BasicBlockData<'tcx>>;
+
+    fn block<'tcx>(kind: TerminatorKind<'tcx>) -> BasicBlockData<'tcx> {
+        BasicBlockData::new(
+            Some(Terminator {
+                source_info: SourceInfo::outermost(DUMMY_SP),
+                kind,
+                attributes: Default::default(),
+            }),
+            false,
+        )
+    }
+
+    fn goto<'tcx>(target: u32) -> BasicBlockData<'tcx> {
+        block(TerminatorKind::Goto {
+            target: BasicBlock::from_u32(target),
+        })
+    }
+
+    fn goto_next<'tcx>() -> BasicBlockData<'tcx> {
+        block(TerminatorKind::Goto { target: NEXT_BLOCK })
+    }
+
+    fn ret<'tcx>() -> BasicBlockData<'tcx> {
+        block(TerminatorKind::Return)
+    }
+
+    fn drop<'tcx>(target: u32, unwind: UnwindAction) -> BasicBlockData<'tcx> {
+        block(TerminatorKind::Drop {
+            place: Place::from(Local::from_u32(0)),
+            target: BasicBlock::from_u32(target),
+            unwind,
+            replace: false,
+            drop: None,
+        })
+    }
+
+    /// The target of each block, with `None` for the returning ones.
+    fn targets(blocks: &Blocks) -> Vec<Option<u32>> {
+        blocks
+            .iter()
+            .map(|b| match b.terminator().kind {
+                TerminatorKind::Goto { target } => Some(target.as_u32()),
+                TerminatorKind::Return => None,
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    fn commit<'tcx>(
+        blocks: impl IntoIterator<Item = BasicBlockData<'tcx>>,
+        modify: impl FnOnce(&mut BodyModificationUnit<'tcx>),
+    ) -> Blocks<'tcx> {
+        let mut blocks = blocks.into_iter().collect::<Blocks>();
+        let mut unit = BodyModificationUnit::new(&LocalDecls::new());
+        modify(&mut unit);
+        unit.commit_blocks(&mut blocks, None::<fn(&BasicBlockData)>);
+        blocks
+    }
+
+    #[test]
+    fn sticky_blocks_before_receive_jumps() {
+        let blocks = commit([goto(1), ret()], |unit| {
+            unit.insert_blocks_before(BasicBlock::from_u32(1), [goto_next(), goto_next()], true);
+        });
+        assert_eq!(targets(&blocks), [Some(1), Some(2), Some(3), None]);
+    }
+
+    #[test]
+    fn non_sticky_blocks_before_do_not_receive_jumps() {
+        let blocks = commit([goto(1), ret()], |unit| {
+            unit.insert_blocks_before(BasicBlock::from_u32(1), [goto_next()], false);
+        });
+        assert_eq!(targets(&blocks), [Some(2), Some(2), None]);
+    }
+
+    #[test]
+    fn blocks_after_continue_to_the_original_target() {
+        let blocks = commit([goto(1), ret()], |unit| {
+            unit.insert_blocks_after(BasicBlock::from_u32(0), [goto_next(), goto_next()]);
+        });
+        assert_eq!(targets(&blocks), [Some(1), Some(2), Some(3), None]);
+    }
+
+    #[test]
+    fn jumps_are_retargeted_to_new_blocks() {
+        let blocks = commit([goto(2), ret(), ret()], |unit| {
+            let new_blocks = unit.insert_blocks_before(BasicBlock::from_u32(1), [goto(2)], false);
+            unit.modify_jump_target_where(
+                BasicBlock::from_u32(0),
+                BasicBlock::from_u32(2),
+                new_blocks[0],
+                JumpModificationConstraint::None,
+            );
+        });
+        assert_eq!(targets(&blocks), [Some(1), Some(3), None, None]);
+    }
+
+    #[test]
+    fn blocks_inserted_relative_to_new_blocks_are_flattened() {
+        let blocks = commit([goto(1), ret()], |unit| {
+            let new_blocks =
+                unit.insert_blocks_before(BasicBlock::from_u32(1), [goto_next()], true);
+            unit.insert_blocks_before(new_blocks[0], [goto_next()], true);
+        });
+        assert_eq!(targets(&blocks), [Some(1), Some(2), Some(3), None]);
+    }
+
+    #[test]
+    fn continued_unwinds_are_turned_into_cleanups() {
+        let blocks = commit([drop(1, UnwindAction::Continue), ret()], |unit| {
+            let cleanup = unit.insert_blocks_before(
+                BasicBlock::from_u32(1),
+                [block(TerminatorKind::UnwindResume)],
+                false,
+            );
+            unit.modify_jump_target_where(
+                BasicBlock::from_u32(0),
+                NEXT_BLOCK,
+                cleanup[0],
+                JumpModificationConstraint::UnwindContinue,
+            );
+        });
+        let TerminatorKind::Drop { target, unwind, .. } =
+            blocks[BasicBlock::from_u32(0)].terminator().kind
+        else {
+            unreachable!()
+        };
+        assert_eq!(target.as_u32(), 2);
+        assert_eq!(unwind, UnwindAction::Cleanup(BasicBlock::from_u32(1)));
+    }
+}
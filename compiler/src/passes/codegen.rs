@@ -7,16 +7,24 @@ use rustc_middle::{
 
 use common::{log_debug, log_info, log_warn};
 
-use crate::config::rules::{InclusionRules, LogicFormula};
+use crate::{
+    config::rules::{InclusionRules, LogicFormula},
+    utils::rules::{InclusionPredicate, ToPredicate},
+};
 
-use super::{CompilationPass, StorageExt};
+use super::{CompilationPass, StorageExt, StorageKey};
 
-const KEY_RULES: &str = "internalization_rules";
-const KEY_BAKED_RULES: &str = "internalization_rules_baked";
+const KEY_RULES: StorageKey<InternalizationRules> = StorageKey::new("internalization_rules");
+const KEY_BAKED_RULES: StorageKey<BakedInternalizationRules> =
+    StorageKey::new("internalization_rules_baked");
 
 pub(crate) type InternalizationRules =
     InclusionRules<LogicFormula<crate::config::rules::PatternMatch>>;
 
+type BakedInternalizationRules = InclusionPredicate<
+    <LogicFormula<crate::config::rules::PatternMatch> as ToPredicate<str>>::Predicate,
+>;
+
 #[derive(Debug)]
 pub(crate) struct MonoItemInternalizer {
     rules: Option<InternalizationRules>,
@@ -38,7 +46,7 @@ impl CompilationPass for MonoItemInternalizer {
         _tcx: TyCtxt<'tcx>,
         storage: &mut dyn super::Storage,
     ) {
-        storage.get_or_insert_with(KEY_RULES.to_owned(), || self.rules.take().unwrap());
+        storage.get_or_insert_with(&KEY_RULES, || self.rules.take().unwrap());
     }
 
     fn visit_codegen_units<'tcx>(
@@ -58,8 +66,8 @@ impl CompilationPass for MonoItemInternalizer {
             )
         }
 
-        let rules = storage.get_or_insert_with_acc(KEY_BAKED_RULES.to_owned(), |storage| {
-            let rules = storage.get_or_default::<InternalizationRules>(KEY_RULES.to_owned());
+        let rules = storage.get_or_insert_with_acc(&KEY_BAKED_RULES, |storage| {
+            let rules = storage.get_or_default(&KEY_RULES);
             rules.to_baked()
         });
 
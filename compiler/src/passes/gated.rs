@@ -2,7 +2,7 @@ use rustc_middle::{mir, ty::TyCtxt};
 
 use crate::passes::StorageExt;
 
-use super::{Compilation, CompilationPass, Storage};
+use super::{Compilation, CompilationPass, Storage, StorageKey, StorageScope};
 
 /// A wrapper pass that enables or disables the inner pass.
 pub(crate) struct GatedPass<T> {
@@ -16,8 +16,12 @@ impl<T> GatedPass<T> {
     }
 }
 
-macro_rules! storage_key {
-    () => {{ core::any::type_name::<T>().to_owned() + "_enabled" }};
+#[inline]
+fn enabled_key<T>() -> StorageKey<bool> {
+    StorageKey::dynamic(
+        core::any::type_name::<T>().to_owned() + "_enabled",
+        StorageScope::Session,
+    )
 }
 
 impl<T> CompilationPass for GatedPass<T>
@@ -33,7 +37,7 @@ where
         krate: &super::ast::Crate,
         storage: &mut dyn Storage,
     ) -> Compilation {
-        storage.get_or_insert_with::<bool>(storage_key!(), || self.enabled);
+        storage.get_or_insert_with(&enabled_key::<T>(), || self.enabled);
 
         if !is_enabled::<T>(storage) {
             return Compilation::Continue;
@@ -142,8 +146,7 @@ where
 
 #[inline]
 fn is_enabled<T>(storage: &mut dyn Storage) -> bool {
-    let key = storage_key!();
-    *storage.get_mut(&key).unwrap()
+    *storage.get_mut(&enabled_key::<T>()).unwrap()
 }
 
 pub(crate) trait CompilationPassLogExt {
@@ -0,0 +1,311 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
+
+use rustc_middle::{
+    mir::{
+        self, Body, Const, ConstOperand, Location, Rvalue, Statement, StatementKind, Terminator,
+        TerminatorKind, visit::Visitor,
+    },
+    ty::{self as mir_ty, TyCtxt},
+};
+use rustc_span::Span;
+
+use serde::Serialize;
+
+use common::{log_info, log_warn};
+
+use crate::{
+    config::InstrumentationFilter,
+    passes::{CompilationPass, OverrideFlags, Storage, StorageExt, StorageKey},
+    utils::{file::TyCtxtFileExt, mir::TyCtxtExt},
+};
+
+use super::{InstrumentationRules, MirSourceExt, decision};
+
+const KEY_REPORT: StorageKey<AuditReport> = StorageKey::new("instr_audit_report");
+
+const FILE_OUTPUT: &str = "instr_audit.json";
+
+/// The dry-run counterpart of the instrumentation pass.
+/// It goes through the same decisions as the instrumentation, but leaves the bodies
+/// intact and reports what would be instrumented and which constructs would hit
+/// the unsupported paths of the instrumentation.
+/// # Remarks
+/// The fine-grained rules (e.g., on place info or operand kinds) are not reflected
+/// in the report, i.e., the constructs are counted as long as their body is instrumented.
+#[derive(Default)]
+pub(crate) struct InstrumentationAuditor {
+    out_dir: Option<PathBuf>,
+    rules: Option<InstrumentationRules>,
+    filter: Option<InstrumentationFilter>,
+}
+
+impl InstrumentationAuditor {
+    pub(crate) fn new(
+        out_dir: Option<PathBuf>,
+        rules: InstrumentationRules,
+        filter: InstrumentationFilter,
+    ) -> Self {
+        Self {
+            out_dir,
+            rules: Some(rules),
+            filter: Some(filter),
+        }
+    }
+}
+
+impl CompilationPass for InstrumentationAuditor {
+    fn override_flags() -> OverrideFlags {
+        OverrideFlags::OPTIMIZED_MIR
+            | OverrideFlags::EXTERN_OPTIMIZED_MIR
+            | OverrideFlags::MIR_SHIMS
+            | OverrideFlags::MAKE_CODEGEN_BACKEND
+    }
+
+    fn visit_ast_before(
+        &mut self,
+        _krate: &rustc_ast::Crate,
+        storage: &mut dyn Storage,
+    ) -> rustc_driver::Compilation {
+        storage.get_or_insert_with(&decision::rules::KEY_RULES, || self.rules.take().unwrap());
+        storage.get_or_insert_with(&decision::KEY_FILTER, || self.filter.take().unwrap());
+        rustc_driver::Compilation::Continue
+    }
+
+    fn transform_mir_body<'tcx>(
+        tcx: TyCtxt<'tcx>,
+        body: &mut Body<'tcx>,
+        storage: &mut dyn Storage,
+    ) {
+        let key = body.source.to_log_str();
+        if !decision::should_instrument(tcx, body, storage) {
+            storage.get_or_default(&KEY_REPORT).skipped.insert(key);
+            return;
+        }
+
+        let body = &*body;
+        let mut auditor = BodyAuditor {
+            tcx,
+            body,
+            audit: BodyAudit {
+                span: format!("{:?}", body.span),
+                ..Default::default()
+            },
+        };
+        auditor.visit_body(body);
+        let audit = auditor.audit;
+
+        audit.unsupported.iter().for_each(|c| {
+            log_warn!(
+                "Unsupported construct in {}: {:?} {} at {}",
+                key,
+                c.kind,
+                c.detail,
+                c.span,
+            )
+        });
+        storage
+            .get_or_default(&KEY_REPORT)
+            .instrumented
+            .insert(key, audit);
+    }
+
+    fn visit_tcx_at_codegen_after(&mut self, tcx: TyCtxt, storage: &mut dyn Storage) {
+        // Make sure all the bodies that would be instrumented are visited.
+        tcx.collect_and_partition_mono_items(())
+            .codegen_units
+            .iter()
+            .flat_map(|unit| unit.items())
+            .for_each(|(item, _)| {
+                if let rustc_middle::mono::MonoItem::Fn(instance) = item {
+                    tcx.instance_mir(instance.def);
+                }
+            });
+
+        let report = storage.get_or_default(&KEY_REPORT);
+        log_info!(
+            "Instrumentation audit: {} bodies to instrument ({} with unsupported constructs), {} skipped",
+            report.instrumented.len(),
+            report
+                .instrumented
+                .values()
+                .filter(|a| !a.unsupported.is_empty())
+                .count(),
+            report.skipped.len(),
+        );
+
+        let path = self
+            .out_dir
+            .clone()
+            .unwrap_or_else(|| tcx.output_dir())
+            .join(FILE_OUTPUT);
+        let file = std::fs::File::create(&path)
+            .expect("Failed to open file for writing the instrumentation audit");
+        serde_json::to_writer_pretty(file, &*report)
+            .expect("Failed to write the instrumentation audit");
+        log_info!("Instrumentation audit is written to {}", path.display());
+    }
+}
+
+#[derive(Default, Serialize)]
+struct AuditReport {
+    /// The bodies that would be instrumented, keyed by their instance.
+    instrumented: BTreeMap<String, BodyAudit>,
+    /// The bodies that would be skipped by the instrumentation decisions.
+    skipped: BTreeSet<String>,
+}
+
+#[derive(Default, Serialize)]
+struct BodyAudit {
+    span: String,
+    /// The number of the constructs that would be instrumented, by their kind.
+    counts: BTreeMap<&'static str, usize>,
+    unsupported: Vec<UnsupportedConstruct>,
+}
+
+#[derive(Serialize)]
+struct UnsupportedConstruct {
+    kind: UnsupportedKind,
+    detail: String,
+    span: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum UnsupportedKind {
+    UnevaluatedConst,
+    InlineAsm,
+    TailCall,
+    Intrinsic,
+    LlvmIntrinsic,
+}
+
+struct BodyAuditor<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'a Body<'tcx>,
+    audit: BodyAudit,
+}
+
+impl<'tcx> BodyAuditor<'_, 'tcx> {
+    fn count(&mut self, kind: &'static str) {
+        *self.audit.counts.entry(kind).or_default() += 1;
+    }
+
+    fn report(&mut self, kind: UnsupportedKind, detail: String, span: Span) {
+        self.audit.unsupported.push(UnsupportedConstruct {
+            kind,
+            detail,
+            span: format!("{:?}", span),
+        });
+    }
+
+    fn audit_call(&mut self, func: &mir::Operand<'tcx>, span: Span) {
+        let mir_ty::TyKind::FnDef(def_id, ..) = func.ty(self.body, self.tcx).kind() else {
+            return;
+        };
+        let def_id = *def_id;
+
+        if self.tcx.is_llvm_intrinsic(def_id) {
+            self.report(
+                UnsupportedKind::LlvmIntrinsic,
+                self.tcx.def_path_str(def_id),
+                span,
+            );
+        } else if let Some(intrinsic) = self.tcx.intrinsic(def_id) {
+            use decision::IntrinsicDecision::*;
+            let reason = match decision::try_decide_intrinsic_call(intrinsic) {
+                Some(ToDo) => "to be supported",
+                Some(ConstEvaluated) => "const evaluated",
+                Some(NotPlanned) => "not planned",
+                Some(Unsupported) => "not supported",
+                Some(Unexpected) => "unexpected",
+                None => "uncovered",
+                Some(
+                    OneToOneAssign(..) | Atomic(..) | Memory { .. } | Simd(..) | NoOp | Contract,
+                ) => {
+                    return;
+                }
+            };
+            self.report(
+                UnsupportedKind::Intrinsic,
+                format!("{} ({})", intrinsic.name, reason),
+                span,
+            );
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for BodyAuditor<'_, 'tcx> {
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
+        let kind = match &statement.kind {
+            StatementKind::Assign(box (_, rvalue)) => match rvalue {
+                Rvalue::Use(..) => "assign::use",
+                Rvalue::Repeat(..) => "assign::repeat",
+                Rvalue::Ref(..) => "assign::ref",
+                Rvalue::ThreadLocalRef(..) => "assign::thread_local_ref",
+                Rvalue::RawPtr(..) => "assign::raw_ptr",
+                Rvalue::Cast(..) => "assign::cast",
+                Rvalue::BinaryOp(..) => "assign::binary_op",
+                Rvalue::UnaryOp(..) => "assign::unary_op",
+                Rvalue::Discriminant(..) => "assign::discriminant",
+                Rvalue::Aggregate(..) => "assign::aggregate",
+                Rvalue::CopyForDeref(..) => "assign::copy_for_deref",
+                Rvalue::WrapUnsafeBinder(..) => "assign::wrap_unsafe_binder",
+                Rvalue::Reborrow(..) => "assign::reborrow",
+            },
+            StatementKind::SetDiscriminant { .. } => "set_discriminant",
+            StatementKind::StorageLive(..) => "storage_live",
+            StatementKind::StorageDead(..) => "storage_dead",
+            StatementKind::Intrinsic(..) => "intrinsic",
+            _ => return self.super_statement(statement, location),
+        };
+        self.count(kind);
+        self.super_statement(statement, location);
+    }
+
+    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
+        let span = terminator.source_info.span;
+        match &terminator.kind {
+            TerminatorKind::SwitchInt { .. } => self.count("switch"),
+            TerminatorKind::Return => self.count("return"),
+            TerminatorKind::Drop { .. } => self.count("drop"),
+            TerminatorKind::Assert { .. } => self.count("assert"),
+            TerminatorKind::Call { func, .. } => {
+                self.count("call");
+                self.audit_call(func, span);
+            }
+            TerminatorKind::TailCall { .. } => {
+                self.report(UnsupportedKind::TailCall, String::new(), span)
+            }
+            TerminatorKind::InlineAsm { template, .. } => self.report(
+                UnsupportedKind::InlineAsm,
+                rustc_ast::InlineAsmTemplatePiece::to_string(template),
+                span,
+            ),
+            _ => {}
+        }
+        self.super_terminator(terminator, location);
+    }
+
+    fn visit_const_operand(&mut self, constant: &ConstOperand<'tcx>, location: Location) {
+        // Corresponds to the constants that the operand referencer cannot handle.
+        let is_unevaluated = match constant.const_ {
+            // Promoted constants are referenced by their addresses.
+            Const::Unevaluated(c, ty) => !(c.promoted.is_some() && ty.is_ref()),
+            Const::Ty(_, c) => {
+                matches!(c.kind(), mir_ty::ConstKind::Alias(mir_ty::IsRigid::No, ..))
+            }
+            _ => false,
+        };
+        if is_unevaluated {
+            self.report(
+                UnsupportedKind::UnevaluatedConst,
+                format!("{:?}", constant.const_),
+                constant.span,
+            );
+        }
+        self.super_const_operand(constant, location);
+    }
+}
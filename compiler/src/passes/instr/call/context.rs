@@ -12,7 +12,7 @@ use rustc_span::def_id::DefId;
 
 use crate::{
     mir_transform::{
-        BodyBlockManager, BodyInstrumentationUnit, BodyLocalManager, JumpModificationConstraint,
+        BodyBlockManager, BodyLocalManager, BodyModificationUnit, JumpModificationConstraint,
         JumpTargetModifier, NewLocalDecl,
     },
     passes::Storage,
@@ -122,7 +122,7 @@ impl<'tcx, C> BaseContext<'tcx> for C where
 
 pub(crate) struct DefaultContext<'tcx, 'm, 'p, 's> {
     tcx: TyCtxt<'tcx>,
-    modification_unit: &'m mut BodyInstrumentationUnit<'tcx>,
+    modification_unit: &'m mut BodyModificationUnit<'tcx>,
     pri: &'p PriItems,
     storage: &'s mut dyn Storage,
     config: Config,
@@ -140,7 +140,7 @@ pub(crate) struct PriItems {
 impl<'tcx, 'm, 'p, 's> DefaultContext<'tcx, 'm, 'p, 's> {
     pub(crate) fn new(
         tcx: TyCtxt<'tcx>,
-        modification_unit: &'m mut BodyInstrumentationUnit<'tcx>,
+        modification_unit: &'m mut BodyModificationUnit<'tcx>,
         pri: &'p PriItems,
         storage: &'s mut dyn Storage,
         config: Config,
@@ -1,3 +1,5 @@
+use rustc_span::Span;
+
 use super::{
     AssertionHandler, OperandReferencer,
     ctxt_reqs::ForAssertion,
@@ -27,6 +29,7 @@ where
                     self.original_bb_index_as_arg(),
                     operand::move_for_local(cond.into()),
                     operand::const_from_bool(self.context.tcx(), expected),
+                    operand::const_from_str(self.context.tcx(), &static_message_of(msg)),
                 ],
                 Default::default(),
             )
@@ -43,6 +46,26 @@ where
 
         self.insert_blocks([info_block, block]);
     }
+
+    fn check_assert_passed(&mut self) {
+        let block = self.make_bb_for_call(
+            sym::check_assert_passed,
+            vec![self.original_bb_index_as_arg()],
+        );
+        self.insert_blocks([block]);
+    }
+
+    fn check_panic(&mut self) {
+        let source = source_of_panic(self.context.tcx(), self.source_info().span);
+        let block = self.make_bb_for_call(
+            sym::check_panic,
+            vec![
+                self.original_bb_index_as_arg(),
+                operand::const_from_str(self.context.tcx(), &source),
+            ],
+        );
+        self.insert_blocks([block]);
+    }
 }
 impl<'tcx, C> RuntimeCallAdder<C>
 where
@@ -146,6 +169,29 @@ where
     }
 }
 
+/// # Returns
+/// The static part of the panic message of the assertion, i.e., the format
+/// string with placeholders for the operands.
+fn static_message_of(msg: &rustc_middle::mir::AssertMessage<'_>) -> String {
+    let mut args = String::new();
+    msg.fmt_assert_args(&mut args).unwrap();
+    // The format string comes first, quoted.
+    args.strip_prefix('"')
+        .and_then(|rest| rest.split_once('"'))
+        .map(|(message, _)| message.to_owned())
+        .unwrap_or(args)
+}
+
+/// # Returns
+/// The source text of the expression panicking at `span`, i.e., the invocation
+/// of the macro (e.g., `assert!`) that the panic call is expanded from.
+fn source_of_panic(tcx: TyCtxt<'_>, span: Span) -> String {
+    tcx.sess
+        .source_map()
+        .span_to_snippet(span.source_callsite())
+        .unwrap_or_default()
+}
+
 mod utils {
     pub(super) use super::super::utils::{convert_mir_binop_to_pri, operand};
 }
@@ -51,11 +51,34 @@ where
         )
     }
 
-    fn by_thread_local_ref(&mut self, _def_id: &DefId) {
+    fn by_thread_local_ref(&mut self, def_id: &DefId) {
         if cfg!(feature = "abs_concrete") {
             self.to_some_concrete()
         } else {
-            self.add_bb_for_assign_call(sym::assign_thread_local_ref, vec![])
+            let tcx = self.tcx();
+            // The address of the thread-local in the current thread.
+            let ptr_local = self.add_local(tcx.thread_local_ptr_ty(*def_id));
+            let raw_addr_ty = Ty::new_imm_ptr(tcx, tcx.types.unit);
+            let addr_local = self.add_local(raw_addr_ty);
+            self.add_bb_for_assign_call_with_statements(
+                sym::assign_thread_local_ref,
+                vec![
+                    operand::const_from_uint(tcx, def_id.krate.as_u32()),
+                    operand::const_from_uint(tcx, def_id.index.as_u32()),
+                    operand::move_for_local(addr_local),
+                ],
+                vec![
+                    assignment::create(Place::from(ptr_local), Rvalue::ThreadLocalRef(*def_id)),
+                    assignment::create(
+                        Place::from(addr_local),
+                        Rvalue::Cast(
+                            CastKind::Transmute,
+                            operand::move_for_local(ptr_local),
+                            raw_addr_ty,
+                        ),
+                    ),
+                ],
+            )
         }
     }
 
@@ -271,7 +294,7 @@ where
         self.add_bb_for_assign_call_with_statements(func_name, args, additional_stmts)
     }
 
-    fn make_slice_for_adt_elements(
+    pub(super) fn make_slice_for_adt_elements(
         &mut self,
         elements: &[OperandRef],
     ) -> (Local, [Statement<'tcx>; 3]) {
@@ -292,7 +315,7 @@ where
         self.add_bb_for_assign_call_with_statements(func_name, args, vec![])
     }
 
-    fn add_bb_for_assign_call_with_statements(
+    pub(super) fn add_bb_for_assign_call_with_statements(
         &mut self,
         func_name: LeafSymbol,
         args: Vec<Operand<'tcx>>,
@@ -456,7 +479,8 @@ where
 
 mod utils {
     pub(super) use super::super::utils::{
-        convert_mir_binop_to_pri, convert_mir_unop_to_pri, prepare_operand_for_slice, ty,
+        assignment, convert_mir_binop_to_pri, convert_mir_unop_to_pri, prepare_operand_for_slice,
+        ty,
     };
 
     pub(super) mod operand {
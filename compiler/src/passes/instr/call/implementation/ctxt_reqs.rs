@@ -33,12 +33,18 @@ ctxt_req_trait!(
     ForFunctionCalling<'tcx>: ForInsertion<'tcx> + JumpTargetModifier + BlockOriginalIndexProvider
 );
 
-ctxt_req_trait!(ForDropping<'tcx>: ForInsertion<'tcx> + BlockOriginalIndexProvider);
+ctxt_req_trait!(
+    ForDropping<'tcx>: ForInsertion<'tcx> + JumpTargetModifier + BlockOriginalIndexProvider
+);
 
 ctxt_req_trait!(ForReturning<'tcx>: ForInsertion<'tcx>);
 
 ctxt_req_trait!(ForEntryFunction<'tcx>: ForInsertion<'tcx> + InEntryFunction);
 
+ctxt_req_trait!(
+    ForTermination<'tcx>: ForEntryFunction<'tcx> + ForPlaceRef<'tcx> + BlockOriginalIndexProvider
+);
+
 ctxt_req_trait!(
     ForAtomicIntrinsic<'tcx>:
         ForInsertion<'tcx> + AtomicIntrinsicParamsProvider<'tcx> + PointerInfoProvider<'tcx>
@@ -1,4 +1,4 @@
-use rustc_middle::mir::{BasicBlockData, Operand};
+use rustc_middle::mir::{BasicBlockData, Operand, UnwindAction};
 use rustc_span::Spanned;
 use rustc_type_ir::ClosureArgs;
 
@@ -51,6 +51,11 @@ where
         }
     }
 
+    fn notify_thread_start(&mut self) {
+        let block = self.make_bb_for_call(sym::notify_thread_start, vec![]);
+        self.insert_blocks([block]);
+    }
+
     fn enter_func(&mut self) {
         self.debug_info(&format!(
             "{}",
@@ -64,6 +69,11 @@ where
         }
     }
 
+    fn mark_frame_indirect(&mut self) {
+        let block = self.make_bb_for_call(sym::mark_frame_indirect, vec![]);
+        self.insert_blocks([block]);
+    }
+
     fn return_from_func(&mut self) {
         let block =
             self.make_bb_for_call(sym::return_from_func, vec![self.original_bb_index_as_arg()]);
@@ -167,6 +177,10 @@ where
         );
         self.insert_blocks([block]);
     }
+
+    fn after_call_drop_on_unwind(&mut self, unwind: &UnwindAction) {
+        self.call_on_unwind(sym::after_drop_unwind, unwind);
+    }
 }
 
 impl<'tcx, C> RuntimeCallAdder<C>
@@ -925,8 +939,8 @@ mod utils {
         call_site_arg: Operand<'tcx>,
         is_precise: bool,
     ) -> BasicBlockData<'tcx> {
+        let fn_ty = fn_value.ty(call_adder, tcx);
         let instance_kind_id_args = {
-            let fn_ty = fn_value.ty(call_adder, tcx);
             let instance_kind = match fn_ty.kind() {
                 TyKind::FnDef(def_id, generic_args) => {
                     tcx.try_resolve_instance_raw(typing_env, *def_id, generic_args)
@@ -944,7 +958,10 @@ mod utils {
         };
         let base_args = [[call_site_arg].to_vec(), instance_kind_id_args.to_vec()].concat();
 
-        if is_precise {
+        /* NOTE: For function pointers, the pointer value is the only identity of the callee
+         * available. So it is passed regardless of the configuration, and if it does not match
+         * the entered function, the call is treated as an external one. */
+        if is_precise || fn_ty.is_fn_ptr() {
             before_call_precisely::<FOR_DROP>(tcx, call_adder, base_args, fn_value, first_arg)
         } else {
             let (block, _) = call_adder.make_bb_for_helper_call_with_all(
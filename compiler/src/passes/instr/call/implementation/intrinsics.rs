@@ -1,7 +1,7 @@
-use common::pri::{AtomicBinaryOp, AtomicOrdering};
+use common::pri::{AtomicBinaryOp, AtomicOrdering, BinaryOp, UnaryOp};
 
 use super::{
-    Assigner, AtomicIntrinsicHandler, IntrinsicHandler,
+    Assigner, AtomicIntrinsicHandler, IntrinsicHandler, SimdIntrinsicHandler,
     context::{AssignmentInfoProvider, PointerInfoProvider},
     ctxt_reqs::{Basic, ForAssignment, ForAtomicIntrinsic, ForMemoryIntrinsic},
     prelude::{mir::*, *},
@@ -31,6 +31,86 @@ where
     }
 }
 
+impl<'tcx, C> SimdIntrinsicHandler<'tcx> for RuntimeCallAdder<C>
+where
+    Self: MirCallAdder<'tcx> + BlockInserter<'tcx>,
+    C: ForAssignment<'tcx>,
+{
+    fn simd_binary_op(&mut self, operator: BinaryOp, first: &[OperandRef], second: &[OperandRef]) {
+        let operator_local = self.make_operator_local(
+            self.context.pri_helper_funcs().const_binary_op_of,
+            operator.to_raw(),
+        );
+        let (first_local, first_stmts) = self.make_slice_for_adt_elements(first);
+        let (second_local, second_stmts) = self.make_slice_for_adt_elements(second);
+
+        self.add_bb_for_assign_call_with_statements(
+            *sym::intrinsics::intrinsic_assign_simd_binary_op,
+            vec![
+                operand::move_for_local(operator_local),
+                operand::move_for_local(first_local),
+                operand::move_for_local(second_local),
+            ],
+            [first_stmts, second_stmts].concat(),
+        )
+    }
+
+    fn simd_unary_op(&mut self, operator: UnaryOp, operand: &[OperandRef]) {
+        let operator_local = self.make_operator_local(
+            self.context.pri_helper_funcs().const_unary_op_of,
+            operator.to_raw(),
+        );
+        let (operand_local, operand_stmts) = self.make_slice_for_adt_elements(operand);
+
+        self.add_bb_for_assign_call_with_statements(
+            *sym::intrinsics::intrinsic_assign_simd_unary_op,
+            vec![
+                operand::move_for_local(operator_local),
+                operand::move_for_local(operand_local),
+            ],
+            operand_stmts.to_vec(),
+        )
+    }
+
+    fn simd_reduce(&mut self, operator: BinaryOp, operand: &[OperandRef]) {
+        let operator_local = self.make_operator_local(
+            self.context.pri_helper_funcs().const_binary_op_of,
+            operator.to_raw(),
+        );
+        let (operand_local, operand_stmts) = self.make_slice_for_adt_elements(operand);
+
+        self.add_bb_for_assign_call_with_statements(
+            *sym::intrinsics::intrinsic_assign_simd_reduce,
+            vec![
+                operand::move_for_local(operator_local),
+                operand::move_for_local(operand_local),
+            ],
+            operand_stmts.to_vec(),
+        )
+    }
+}
+
+impl<'tcx, C> RuntimeCallAdder<C>
+where
+    Self: MirCallAdder<'tcx> + BlockInserter<'tcx>,
+    C: ForAssignment<'tcx>,
+{
+    fn make_operator_local(
+        &mut self,
+        const_of_func: super::FunctionInfo,
+        raw_operator: u8,
+    ) -> Local {
+        let (block, local) = self.make_bb_for_helper_call_with_all(
+            const_of_func,
+            vec![],
+            vec![operand::const_from_uint(self.tcx(), raw_operator)],
+            Default::default(),
+        );
+        self.insert_blocks([block]);
+        local
+    }
+}
+
 impl<'tcx, C> RuntimeCallAdder<C>
 where
     C: Basic<'tcx>,
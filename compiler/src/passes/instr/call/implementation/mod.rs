@@ -2,7 +2,7 @@ use core::debug_assert_matches;
 use std::collections::HashMap;
 
 use rustc_middle::{
-    mir::{self, BasicBlock, BasicBlockData, HasLocalDecls},
+    mir::{self, BasicBlock, BasicBlockData, HasLocalDecls, UnwindAction},
     ty::{self as mir_ty},
 };
 
@@ -33,7 +33,7 @@ mod operand;
 mod place;
 mod storage;
 
-use ctxt_reqs::{ForEntryFunction, ForInsertion};
+use ctxt_reqs::{ForEntryFunction, ForInsertion, ForTermination};
 
 pub(crate) struct RuntimeCallAdder<C> {
     context: C,
@@ -118,7 +118,7 @@ pub(crate) trait BlockInserter<'tcx> {
 impl<'tcx, 'm, 'p, 's> RuntimeCallAdder<DefaultContext<'tcx, 'm, 'p, 's>> {
     pub fn new(
         tcx: TyCtxt<'tcx>,
-        modification_unit: &'m mut BodyInstrumentationUnit<'tcx>,
+        modification_unit: &'m mut BodyModificationUnit<'tcx>,
         pri: &'p PriItems,
         storage: &'s mut dyn Storage,
         config: Config,
@@ -501,6 +501,46 @@ where
     }
 }
 
+impl<'tcx, C> TerminationHandler for RuntimeCallAdder<C>
+where
+    Self: MirCallAdder<'tcx> + BlockInserter<'tcx>,
+    C: ForTermination<'tcx>,
+{
+    fn report_termination(&mut self, result: PlaceRef) {
+        let tcx = self.tcx();
+        let discr_ty = self.body().return_ty().discriminant_ty(tcx);
+
+        let (primitive_ty_block, primitive_ty_local) = self.make_primitive_type_of_bb(discr_ty);
+
+        let discr_local = self.add_local(discr_ty);
+        let discr_bit_rep_local = self.add_local(tcx.types.u32);
+        let mut block = self.make_bb_for_call(
+            sym::report_termination,
+            vec![
+                self.original_bb_index_as_arg(),
+                operand::copy_for_local(result.into()),
+                operand::move_for_local(discr_bit_rep_local),
+                operand::move_for_local(primitive_ty_local),
+            ],
+        );
+        block.statements.extend([
+            assignment::create(
+                mir::Place::from(discr_local),
+                mir::Rvalue::Discriminant(mir::Place::return_place()),
+            ),
+            assignment::create(
+                mir::Place::from(discr_bit_rep_local),
+                mir::Rvalue::Cast(
+                    mir::CastKind::IntToInt,
+                    operand::move_for_local(discr_local),
+                    tcx.types.u32,
+                ),
+            ),
+        ]);
+        self.insert_blocks([primitive_ty_block, block]);
+    }
+}
+
 impl<'tcx, C> RuntimeCallAdder<C>
 where
     Self: MirCallAdder<'tcx>,
@@ -561,6 +601,83 @@ where
     }
 }
 
+impl<'tcx, C> TagHandler for RuntimeCallAdder<C>
+where
+    Self: MirCallAdder<'tcx>,
+    C: ForInsertion<'tcx> + JumpTargetModifier,
+{
+    fn push_tag(&mut self, tag: &str) {
+        let block = self.make_bb_for_call(
+            sym::push_tag,
+            vec![utils::operand::const_from_str(self.context.tcx(), tag)],
+        );
+        self.insert_blocks([block]);
+    }
+
+    fn pop_tag(&mut self) {
+        let block = self.make_bb_for_call(sym::pop_tag, Vec::default());
+        self.insert_blocks([block]);
+    }
+
+    fn pop_tag_on_unwind(&mut self, unwind: &UnwindAction) {
+        self.call_on_unwind(sym::pop_tag, unwind);
+    }
+}
+
+impl<'tcx, C> RuntimeCallAdder<C>
+where
+    Self: MirCallAdder<'tcx>,
+    C: ForInsertion<'tcx> + JumpTargetModifier,
+{
+    /// Calls the function on the unwinding path of the call terminating the current block.
+    fn call_on_unwind(&mut self, func_name: LeafSymbol, unwind: &UnwindAction) {
+        /* NOTE: The blocks are not sticky, so they are only reached through
+         * the unwind edge of this call, and not by the other jumps to the
+         * blocks they are inserted before. */
+        let call_block = self.context.block_index();
+        let cleanup = match unwind {
+            UnwindAction::Cleanup(cleanup) => Some(*cleanup),
+            UnwindAction::Continue => None,
+            UnwindAction::Unreachable | UnwindAction::Terminate(..) => return,
+        };
+
+        let mut block = self.make_bb_for_call_with_target(func_name, Vec::default(), cleanup);
+        block.is_cleanup = true;
+        if let mir::TerminatorKind::Call { unwind, .. } = &mut block.terminator_mut().kind {
+            *unwind = UnwindAction::Terminate(mir::UnwindTerminateReason::InCleanup);
+        }
+
+        if let Some(cleanup) = cleanup {
+            let index = self.context.insert_blocks_before(cleanup, [block], false)[0];
+            self.context.modify_jump_target_where(
+                call_block,
+                cleanup,
+                index,
+                JumpModificationConstraint::None,
+            );
+        } else {
+            // Continuing the unwind after popping the tag.
+            let resume = BasicBlockData::new(
+                Some(mir::Terminator {
+                    source_info: self.context.source_info(),
+                    kind: mir::TerminatorKind::UnwindResume,
+                    attributes: Default::default(),
+                }),
+                true,
+            );
+            let index = self
+                .context
+                .insert_blocks_before(call_block, [block, resume], false)[0];
+            self.context.modify_jump_target_where(
+                call_block,
+                NEXT_BLOCK,
+                index,
+                JumpModificationConstraint::UnwindContinue,
+            );
+        }
+    }
+}
+
 struct BlocksAndResult<'tcx>(Vec<BasicBlockData<'tcx>>, Local);
 
 impl<'tcx> BlocksAndResult<'tcx> {
@@ -577,14 +694,12 @@ impl<'tcx> From<(BasicBlockData<'tcx>, Local)> for BlocksAndResult<'tcx> {
 
 pub(super) mod utils {
     use rustc_middle::{
-        mir::{
-            self, BasicBlock, HasLocalDecls, Local, Operand, Place, Rvalue, SourceInfo, Statement,
-        },
+        mir::{self, HasLocalDecls, Local, Operand, Place, Rvalue, SourceInfo, Statement},
         ty::{Ty, TyCtxt, TyKind, TypingEnv, adjustment::PointerCoercion},
     };
     use rustc_span::DUMMY_SP;
 
-    use crate::mir_transform::{BodyLocalManager, NEXT_BLOCK};
+    use crate::mir_transform::BodyLocalManager;
 
     pub(super) use self::assignment::rvalue;
 
@@ -664,6 +779,21 @@ pub(super) mod utils {
             }))
         }
 
+        pub fn const_from_str<'tcx>(tcx: TyCtxt<'tcx>, value: &str) -> Operand<'tcx> {
+            let ty = Ty::new_static_str(tcx);
+            Operand::Constant(Box::new(ConstOperand {
+                span: DUMMY_SP,
+                user_ty: None,
+                const_: Const::from_value(
+                    tcx.valtree_to_const_val(mir_ty::Value {
+                        ty,
+                        valtree: mir_ty::ValTree::from_raw_bytes(tcx, value.as_bytes()),
+                    }),
+                    ty,
+                ),
+            }))
+        }
+
         pub fn copy_for_local<'tcx>(value: Local) -> Operand<'tcx> {
             for_local(value, true)
         }
@@ -815,42 +945,7 @@ pub(super) mod utils {
     }
 
     pub(super) mod terminator {
-        use rustc_middle::mir::{Terminator, TerminatorKind, UnwindAction};
-        use rustc_span::Spanned;
-
-        use super::*;
-
-        pub fn call<'tcx>(
-            tcx: TyCtxt<'tcx>,
-            func_def_id: DefId,
-            generic_args: impl IntoIterator<Item = GenericArg<'tcx>>,
-            args: Vec<Operand<'tcx>>,
-            destination: Place<'tcx>,
-            target: Option<BasicBlock>,
-            source_info: SourceInfo,
-        ) -> Terminator<'tcx> {
-            Terminator {
-                source_info,
-                kind: TerminatorKind::Call {
-                    /* NOTE: Check if it is supposed to be the same operand for each function definition,
-                     * i.e. caching/lazy singleton. */
-                    func: operand::func(tcx, func_def_id, generic_args),
-                    args: args
-                        .into_iter()
-                        .map(|a| Spanned {
-                            node: a,
-                            span: DUMMY_SP,
-                        })
-                        .collect(),
-                    destination,
-                    target: Some(target.unwrap_or(NEXT_BLOCK)),
-                    unwind: UnwindAction::Continue,
-                    call_source: mir::CallSource::Normal,
-                    fn_span: DUMMY_SP,
-                },
-                attributes: Default::default(),
-            }
-        }
+        pub use crate::mir_transform::call_terminator as call;
     }
 
     pub(super) fn prepare_operand_for_slice<'tcx>(
@@ -72,7 +72,8 @@ where
 
         let tcx = self.tcx();
         let ty = place.ty(&self.context, tcx).ty;
-        if ty.is_adt() || ty.is_trivially_tuple() || ty.is_array() || !ty.is_known_rigid() {
+        // With the address included, the size is already packed in the place's metadata.
+        if !self.context.config().place_info_filter.address && super::place::is_size_needed(ty) {
             if let Some(BlocksAndResult(new_blocks, new_ref)) = self.add_place_size(place_ref, ty) {
                 additional_blocks.extend(new_blocks);
                 place_ref = new_ref;
@@ -114,19 +115,18 @@ where
         } else if cfg!(feature = "abs_concrete") {
             None
         }
-        // &str
+        // &str, &&str, ...
         else if ty.peel_refs().is_str() {
             config.str.then(|| {
-                self.internal_reference_const_operand_directly(sym::ref_operand_const_str, constant)
+                self.internal_reference_str_const_operand(sym::ref_operand_const_str, constant)
             })
         }
-        // &[u8]
-        else if Self::is_u8_slice_ref(tcx, ty) {
+        // &[u8], &&[u8], ...
+        else if ty.peel_refs().is_slice()
+            && ty.peel_refs().sequence_element_type(tcx) == tcx.types.u8
+        {
             config.byte_str.then(|| {
-                self.internal_reference_const_operand_directly(
-                    sym::ref_operand_const_byte_str,
-                    constant,
-                )
+                self.internal_reference_str_const_operand(sym::ref_operand_const_byte_str, constant)
             })
         }
         // &[u8; N]
@@ -137,17 +137,39 @@ where
                 .byte_str
                 .then(|| self.internal_reference_byte_str_const_operand(constant))
         }
+        // Function items are ZSTs distinguished by their type.
+        else if let TyKind::FnDef(..) = ty.kind() {
+            config
+                .zst
+                .then(|| self.internal_reference_func_def_const_operand(constant))
+        }
         // NOTE: Check this after all other ZSTs that you want to distinguish.
         else if ty.size(tcx, self.current_typing_env()) == rustc_abi::Size::ZERO {
             config
                 .zst
                 .then(|| self.internal_reference_zst_const_operand())
-        } else if let TyKind::FnDef(..) = ty.kind() {
-            self.internal_reference_func_def_const_operand(constant)
         } else if let Some(c) = operand::const_try_as_unevaluated(constant) {
-            self.internal_reference_unevaluated_const_operand(&c)
-        } else if let Some(def_id) = Self::try_as_immut_static(tcx, constant) {
-            self.internal_reference_static_ref_const_operand(def_id, ty)
+            // Promoted constants are references to allocations made at codegen,
+            // thus they are referenced by their addresses as other pointers.
+            if c.promoted.is_some() && ty.is_ref() {
+                config
+                    .ptr
+                    .then(|| self.internal_reference_scalar_ptr_const_operand(constant))
+            } else {
+                self.internal_reference_unevaluated_const_operand(&c)
+            }
+        }
+        // Function pointers, references to statics, ...
+        else if operand::const_is_scalar_ptr(constant) {
+            config
+                .ptr
+                .then(|| self.internal_reference_scalar_ptr_const_operand(constant))
+        }
+        // Large aggregates, arrays, ...
+        else if operand::const_is_indirect(constant) {
+            config
+                .by_ref
+                .then(|| self.internal_reference_byref_const_operand(constant))
         } else {
             unimplemented!(
                 "Encountered unknown constant {:?} with type {:?}",
@@ -251,6 +273,35 @@ where
             .into()
     }
 
+    /// References a string-like constant, i.e., a (possibly nested) reference to `str` or `[u8]`.
+    /// Nested references are dereferenced down to a single reference through locals, as the
+    /// PRI functions accept the slice references only.
+    fn internal_reference_str_const_operand(
+        &mut self,
+        func_name: LeafSymbol,
+        constant: &Box<ConstOperand<'tcx>>,
+    ) -> BlocksAndResult<'tcx> {
+        let tcx = self.tcx();
+        let mut statements = Vec::new();
+        let mut ty = constant.ty();
+        let mut value = operand::const_from_existing(constant);
+        while let TyKind::Ref(_, inner_ty, _) = ty.kind()
+            && inner_ty.is_ref()
+        {
+            let local = self.add_local(ty);
+            statements.push(assignment::create(
+                Place::from(local),
+                Rvalue::Use(value, rustc_middle::mir::WithRetag::No),
+            ));
+            value = Operand::Copy(Place::from(local).project_deeper(&[ProjectionElem::Deref], tcx));
+            ty = *inner_ty;
+        }
+
+        let (mut block, result) = self.make_bb_for_operand_ref_call(func_name, vec![value]);
+        block.statements.splice(0..0, statements);
+        (block, result).into()
+    }
+
     fn internal_reference_int_const_operand(
         &mut self,
         constant: &Box<ConstOperand<'tcx>>,
@@ -349,11 +400,106 @@ where
         BlocksAndResult::from(block_pair)
     }
 
+    /// References a constant that is materialized in memory by exporting its bytes, so that
+    /// the runtime can reconstruct the value (lazily) using its type.
+    fn internal_reference_byref_const_operand(
+        &mut self,
+        constant: &Box<ConstOperand<'tcx>>,
+    ) -> BlocksAndResult<'tcx> {
+        let tcx = self.tcx();
+        let ty = constant.ty();
+        let Some(bytes) =
+            operand::const_try_as_indirect_bytes(tcx, self.current_typing_env(), constant)
+        else {
+            log_warn!(
+                "Constant is not exportable by its bytes, probably containing pointers: {:?}",
+                constant
+            );
+            return self.internal_reference_const_some();
+        };
+
+        let (type_id_block, type_id_local) = self.make_type_id_of_bb(ty);
+        let block_pair = self.make_bb_for_operand_ref_call(
+            sym::ref_operand_const_byref,
+            vec![
+                operand::const_from_byte_str(tcx, &bytes),
+                operand::move_for_local(type_id_local),
+            ],
+        );
+        BlocksAndResult::from(block_pair).prepend([type_id_block])
+    }
+
+    /// References a constant pointer that points to an allocation of the program
+    /// (e.g., a function, a static, or a promoted constant) by exporting its address.
+    fn internal_reference_scalar_ptr_const_operand(
+        &mut self,
+        constant: &Box<ConstOperand<'tcx>>,
+    ) -> BlocksAndResult<'tcx> {
+        let tcx = self.tcx();
+        let ty = constant.ty();
+
+        let raw_ptr_ty = Ty::new_imm_ptr(tcx, tcx.types.unit);
+        let mut statements = Vec::new();
+        let ptr_operand = if ty.is_fn_ptr() {
+            operand::const_from_existing(constant)
+        } else if let TyKind::Ref(_, pointee_ty, _) = ty.kind() {
+            let ref_local: Local = self.add_local(ty);
+            let ptr_local: Local = self.add_local(Ty::new_imm_ptr(tcx, *pointee_ty));
+            statements.push(assignment::create(
+                Place::from(ref_local),
+                Rvalue::Use(
+                    operand::const_from_existing(constant),
+                    rustc_middle::mir::WithRetag::No,
+                ),
+            ));
+            statements.push(assignment::create(
+                Place::from(ptr_local),
+                Rvalue::RawPtr(
+                    rustc_middle::mir::RawPtrKind::Const,
+                    Place::from(ref_local).project_deeper(&[ProjectionElem::Deref], tcx),
+                ),
+            ));
+            operand::move_for_local(ptr_local)
+        } else {
+            // e.g., non-null pointers wrapped in ADTs.
+            log_warn!("Unexpected constant pointer: {:?}", constant);
+            return self.internal_reference_const_some();
+        };
+        let cast_kind = if ty.is_fn_ptr() {
+            rustc_middle::mir::CastKind::FnPtrToPtr
+        } else {
+            rustc_middle::mir::CastKind::PtrToPtr
+        };
+        let local: Local = self.add_local(raw_ptr_ty);
+        statements.push(assignment::create(
+            Place::from(local),
+            Rvalue::Cast(cast_kind, ptr_operand, raw_ptr_ty),
+        ));
+
+        let (type_id_block, type_id_local) = self.make_type_id_of_bb(ty);
+        let (mut block, result) = self.make_bb_for_operand_ref_call(
+            sym::ref_operand_const_ptr,
+            vec![
+                operand::move_for_local(local),
+                operand::move_for_local(type_id_local),
+            ],
+        );
+        block.statements.splice(0..0, statements);
+        BlocksAndResult::from((block, result)).prepend([type_id_block])
+    }
+
+    /// References a function item by its type, so that the function can be resolved
+    /// from the type when it is called.
     fn internal_reference_func_def_const_operand(
         &mut self,
-        _constant: &Box<ConstOperand<'tcx>>,
-    ) -> ! {
-        panic!("Function definition constant is not supported by this configuration.")
+        constant: &Box<ConstOperand<'tcx>>,
+    ) -> BlocksAndResult<'tcx> {
+        let (type_id_block, type_id_local) = self.make_type_id_of_bb(constant.ty());
+        let block_pair = self.make_bb_for_operand_ref_call(
+            sym::ref_operand_const_fn_item,
+            vec![operand::move_for_local(type_id_local)],
+        );
+        BlocksAndResult::from(block_pair).prepend([type_id_block])
     }
 
     fn internal_reference_unevaluated_const_operand(&mut self, _constant: &UnevaluatedConst) -> !
@@ -363,13 +509,6 @@ where
         panic!("Unevaluated constant is not supported by this configuration.")
     }
 
-    fn internal_reference_static_ref_const_operand(&mut self, _def_id: DefId, _ty: Ty<'tcx>) -> !
-    where
-        C: ForOperandRef<'tcx>,
-    {
-        panic!("Static reference constant is not supported by this configuration.")
-    }
-
     fn make_bb_for_operand_ref_call(
         &mut self,
         func_name: LeafSymbol,
@@ -378,31 +517,6 @@ where
         self.make_bb_for_call_with_ret(func_name, args)
     }
 
-    #[inline]
-    fn is_u8_slice_ref(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
-        // This is just for mitigating the bug in rustfmt. Track: rustfmt#5863
-        if let TyKind::Ref(_, ty, _) = ty.kind() {
-            if let TyKind::Slice(ty) = ty.kind() {
-                return ty == &tcx.types.u8;
-            }
-        }
-
-        false
-    }
-
-    #[inline]
-    fn try_as_immut_static(tcx: TyCtxt<'tcx>, constant: &Box<ConstOperand<'tcx>>) -> Option<DefId> {
-        /* Immutable statics are accessed by a constant reference which points to a statically
-         * allocated program memory block. If the static item's type is T then the constant is
-         * of type &T. */
-        constant
-            .ty()
-            .ref_mutability()
-            .is_some_and(|m| m.is_not())
-            .then(|| constant.check_static_ptr(tcx))
-            .flatten()
-    }
-
     fn const_config(&self) -> &ConstantTypeRules<bool> {
         self.context
             .config()
@@ -423,7 +537,10 @@ mod utils {
     };
 
     pub(super) mod operand {
-        use rustc_middle::{mir::Const, ty};
+        use rustc_middle::{
+            mir::{Const, ConstValue, interpret::alloc_range},
+            ty::{self, TypingEnv},
+        };
 
         pub use super::super::super::utils::operand::*;
 
@@ -447,6 +564,44 @@ mod utils {
                 _ => None,
             }
         }
+
+        pub fn const_is_scalar_ptr(constant: &ConstOperand) -> bool {
+            matches!(
+                constant.const_,
+                Const::Val(ConstValue::Scalar(mir::interpret::Scalar::Ptr(..)), _)
+            )
+        }
+
+        pub fn const_is_indirect(constant: &ConstOperand) -> bool {
+            matches!(constant.const_, Const::Val(ConstValue::Indirect { .. }, _))
+        }
+
+        /// Returns the bytes of a constant materialized in memory, if they are self-contained,
+        /// i.e., they do not hold any pointer.
+        pub fn const_try_as_indirect_bytes<'tcx>(
+            tcx: TyCtxt<'tcx>,
+            typing_env: TypingEnv<'tcx>,
+            constant: &ConstOperand<'tcx>,
+        ) -> Option<Vec<u8>> {
+            let Const::Val(ConstValue::Indirect { alloc_id, offset }, ty) = constant.const_ else {
+                return None;
+            };
+            let size = tcx.layout_of(typing_env.as_query_input(ty)).ok()?.size;
+            let alloc = tcx.global_alloc(alloc_id).unwrap_memory();
+            let alloc = alloc.inner();
+            let range = alloc_range(offset, size);
+            if !alloc.provenance().range_empty(range, &tcx) {
+                return None;
+            }
+            // Uninitialized bytes (e.g., paddings) are not expected to be read.
+            Some(
+                alloc
+                    .inspect_with_uninit_and_ptr_outside_interpreter(
+                        range.start.bytes_usize()..range.end().bytes_usize(),
+                    )
+                    .to_vec(),
+            )
+        }
     }
 
     #[allow(clippy::borrowed_box)]
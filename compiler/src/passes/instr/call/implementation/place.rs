@@ -1,6 +1,6 @@
 use rustc_middle::mir::{PlaceRef as MirPlaceRef, ProjectionElem};
 
-use common::log_warn;
+use common::{log_warn, pri::PlaceMetadata};
 
 use super::{
     BodyProvider, PlaceReferencer, PlaceStructureRules,
@@ -35,17 +35,13 @@ where
         let referrals =
             filter_and_fold_place(&self.context.config().place_info_filter.structure, place);
 
-        let set_type_addr = |this: &mut Self,
-                             blocks: &mut Vec<BasicBlockData<'tcx>>,
-                             mut place_ref: Local,
-                             rel_place: MirPlaceRef<'tcx>| {
+        let set_metadata = |this: &mut Self,
+                            blocks: &mut Vec<BasicBlockData<'tcx>>,
+                            mut place_ref: Local,
+                            rel_place: MirPlaceRef<'tcx>| {
             let ty = rel_place.ty(this.local_decls(), tcx).ty;
-            if let Some(BlocksAndResult(new_blocks, new_ref)) = this.add_place_type(place_ref, ty) {
-                blocks.extend(new_blocks);
-                place_ref = new_ref;
-            }
             if let Some(BlocksAndResult(new_blocks, new_ref)) =
-                this.add_place_addr(place_ref, rel_place.to_place(tcx), ty)
+                this.add_place_metadata(place_ref, rel_place.to_place(tcx), ty)
             {
                 blocks.extend(new_blocks);
                 place_ref = new_ref;
@@ -66,7 +62,7 @@ where
                 }
             };
             blocks.push(block);
-            place_ref = set_type_addr(self, &mut blocks, place_ref, rel_place);
+            place_ref = set_metadata(self, &mut blocks, place_ref, rel_place);
 
             place_ref
         };
@@ -91,7 +87,7 @@ where
             };
             blocks.extend(added_blocks);
             place_ref = new_ref;
-            place_ref = set_type_addr(self, &mut blocks, place_ref, cur_place);
+            place_ref = set_metadata(self, &mut blocks, place_ref, cur_place);
         }
 
         BlocksAndResult(blocks, place_ref)
@@ -109,12 +105,8 @@ where
         let mut blocks = vec![block];
 
         let ty = self.local_decls()[local].ty;
-        if let Some(BlocksAndResult(new_blocks, new_ref)) = self.add_place_type(place_ref, ty) {
-            blocks.extend(new_blocks);
-            place_ref = new_ref;
-        }
         if let Some(BlocksAndResult(new_blocks, new_ref)) =
-            self.add_place_addr(place_ref, local.into(), ty)
+            self.add_place_metadata(place_ref, local.into(), ty)
         {
             blocks.extend(new_blocks);
             place_ref = new_ref;
@@ -211,28 +203,47 @@ where
         self.make_bb_for_call_with_ret(func_name, args)
     }
 
-    fn add_place_addr(
+    /// Attaches the metadata of the place to its reference.
+    /// If the address is included, all parts of the metadata are packed in a
+    /// single call, the rest of which is constant at this point.
+    fn add_place_metadata(
         &mut self,
         place_ref: Local,
         place: Place<'tcx>,
         place_ty: Ty<'tcx>,
     ) -> Option<BlocksAndResult<'tcx>> {
-        if !self.context.config().place_info_filter.address {
-            return None;
+        let filter = &self.context.config().place_info_filter;
+        if !filter.address {
+            return self.add_place_type(place_ref, place_ty);
         }
 
-        let (stmt, ptr_local) = utils::ptr_to_place(self.tcx(), &mut self.context, place, place_ty);
-        let (mut block, place_ref) = {
-            self.make_bb_for_helper_call_with_all(
-                self.context.pri_helper_funcs().place_with_address_typed,
-                vec![place_ty.into()],
-                vec![
-                    operand::copy_for_local(place_ref),
-                    operand::move_for_local(ptr_local),
-                ],
-                None,
-            )
-        };
+        let tcx = self.tcx();
+        let mut flags = PlaceMetadata::HAS_ADDRESS;
+        let mut primitive_type = 0;
+        if filter.ty {
+            if place_ty.is_primitive() {
+                flags |= PlaceMetadata::HAS_PRIMITIVE_TYPE;
+                primitive_type = convert_primitive_ty_to_pri(tcx, place_ty).to_raw();
+            } else {
+                flags |= PlaceMetadata::HAS_TYPE_ID;
+            }
+        }
+        if is_size_needed(place_ty) {
+            flags |= PlaceMetadata::HAS_SIZE;
+        }
+
+        let (stmt, ptr_local) = utils::ptr_to_place(tcx, &mut self.context, place, place_ty);
+        let (mut block, place_ref) = self.make_bb_for_helper_call_with_all(
+            self.context.pri_helper_funcs().place_with_metadata_typed,
+            vec![place_ty.into()],
+            vec![
+                operand::copy_for_local(place_ref),
+                operand::move_for_local(ptr_local),
+                operand::const_from_scalar_int(tcx, primitive_type.into(), tcx.types.i8),
+                operand::const_from_uint(tcx, flags),
+            ],
+            None,
+        );
         block.statements.push(stmt);
         Some((block, place_ref).into())
     }
@@ -380,7 +391,13 @@ fn filter_and_fold_place<'tcx>(
     PlaceReferralChain { base, projs }
 }
 
+/// Whether the size of the place should be sent, i.e., it may not be
+/// easily known from the type at runtime.
+pub(super) fn is_size_needed(ty: Ty<'_>) -> bool {
+    ty.is_adt() || ty.is_trivially_tuple() || ty.is_array() || !ty.is_known_rigid()
+}
+
 mod utils {
-    pub(super) use super::super::utils::{operand, ptr_to_place};
+    pub(super) use super::super::utils::{convert_primitive_ty_to_pri, operand, ptr_to_place};
 }
 use utils::*;
@@ -1,6 +1,12 @@
 use core::debug_assert_matches;
 
-use super::{InsertionLocation, StorageMarker, ctxt_reqs::ForStorageMarking, prelude::*};
+use rustc_middle::mir::{CastKind, Terminator, TerminatorKind, WithRetag};
+
+use super::{
+    AllocationMarker, InsertionLocation, NEXT_BLOCK, StorageMarker,
+    ctxt_reqs::ForStorageMarking,
+    prelude::{mir::*, *},
+};
 
 impl<'tcx, C> StorageMarker for RuntimeCallAdder<C>
 where
@@ -53,7 +59,74 @@ where
     }
 }
 
+impl<'tcx, C> AllocationMarker<'tcx> for RuntimeCallAdder<C>
+where
+    Self: MirCallAdder<'tcx> + BlockInserter<'tcx>,
+    C: ForStorageMarking<'tcx>,
+{
+    fn keep_before_call(&mut self, operand: &Operand<'tcx>) -> Local {
+        let ty = operand.ty(self, self.tcx());
+        let local = self.add_local(ty);
+        let mut block = BasicBlockData::new(
+            Some(Terminator {
+                source_info: self.source_info(),
+                kind: TerminatorKind::Goto { target: NEXT_BLOCK },
+                attributes: Default::default(),
+            }),
+            false,
+        );
+        block.statements.push(assignment::create(
+            Place::from(local),
+            Rvalue::Use(operand.to_copy(), WithRetag::No),
+        ));
+        self.insert_blocks([block]);
+        local
+    }
+
+    fn mark_allocated(&mut self, ptr: &Place<'tcx>, size: Local) {
+        let (stmt, addr_local) = self.raw_addr_of(Operand::Copy(*ptr));
+        let mut block = self.make_bb_for_call(
+            sym::mark_allocated,
+            vec![
+                operand::move_for_local(addr_local),
+                operand::move_for_local(size),
+            ],
+        );
+        block.statements.push(stmt);
+        self.insert_blocks([block]);
+    }
+
+    fn mark_deallocated(&mut self, ptr: &Operand<'tcx>) {
+        let (stmt, addr_local) = self.raw_addr_of(ptr.to_copy());
+        let mut block = self.make_bb_for_call(
+            sym::mark_deallocated,
+            vec![operand::move_for_local(addr_local)],
+        );
+        block.statements.push(stmt);
+        self.insert_blocks([block]);
+    }
+}
+
+impl<'tcx, C> RuntimeCallAdder<C>
+where
+    Self: MirCallAdder<'tcx>,
+    C: ForStorageMarking<'tcx>,
+{
+    /// Reinterprets the pointer (`*mut u8` or `NonNull<u8>` for the allocator)
+    /// as a raw address.
+    fn raw_addr_of(&mut self, ptr: Operand<'tcx>) -> (Statement<'tcx>, Local) {
+        let tcx = self.tcx();
+        let raw_addr_ty = Ty::new_imm_ptr(tcx, tcx.types.unit);
+        let raw_addr_local = self.add_local(raw_addr_ty);
+        let stmt = assignment::create(
+            Place::from(raw_addr_local),
+            Rvalue::Cast(CastKind::Transmute, ptr, raw_addr_ty),
+        );
+        (stmt, raw_addr_local)
+    }
+}
+
 mod utils {
-    pub(super) use super::super::utils::operand;
+    pub(super) use super::super::utils::{assignment, operand};
 }
 use utils::*;
@@ -4,7 +4,9 @@ pub(super) mod context;
 
 use rustc_abi::{FieldIdx, VariantIdx};
 use rustc_middle::{
-    mir::{BasicBlock, BinOp, Body, CastKind, ConstOperand, Local, Operand, Place, UnOp},
+    mir::{
+        BasicBlock, BinOp, Body, CastKind, ConstOperand, Local, Operand, Place, UnOp, UnwindAction,
+    },
     ty::{Const, GenericArg, Ty, TyCtxt},
 };
 use rustc_span::{Spanned, def_id::DefId};
@@ -14,7 +16,7 @@ use std::vec;
 
 use serde::Serialize;
 
-use common::pri::{AssignmentId, AtomicBinaryOp, AtomicOrdering};
+use common::pri::{AssignmentId, AtomicBinaryOp, AtomicOrdering, BinaryOp, UnaryOp};
 
 use super::{
     decision::rules::{
@@ -160,6 +162,18 @@ pub(crate) trait StorageMarker: Sized {
     fn mark_dead(&mut self, place: impl FnOnce(&mut Self) -> PlaceRef);
 }
 
+pub(crate) trait AllocationMarker<'tcx> {
+    /// Copies the operand to a new local before the call terminating the
+    /// current block, as the operand may be moved into the call.
+    fn keep_before_call(&mut self, operand: &Operand<'tcx>) -> Local;
+
+    /// Reports the memory that `ptr` points to as an allocation of `size` bytes.
+    fn mark_allocated(&mut self, ptr: &Place<'tcx>, size: Local);
+
+    /// Reports the allocation that `ptr` points to as freed.
+    fn mark_deallocated(&mut self, ptr: &Operand<'tcx>);
+}
+
 #[derive(Clone)]
 pub struct SwitchInfo<'tcx> {
     pub(super) node_index: BasicBlock,
@@ -188,8 +202,14 @@ pub(crate) trait FunctionHandler<'tcx> {
         no_def: bool,
     );
 
+    /// Notifies the start of a thread, before entering its first function.
+    fn notify_thread_start(&mut self);
+
     fn enter_func(&mut self);
 
+    /// Notifies that the current function accesses memory through dereferences or statics.
+    fn mark_frame_indirect(&mut self);
+
     fn return_from_func(&mut self);
 
     fn after_call_func(&mut self)
@@ -203,6 +223,9 @@ pub(crate) trait DropHandler<'tcx> {
     fn before_call_drop_in_place(&mut self, func: &Operand<'tcx>, to_drop: &Spanned<Operand<'tcx>>);
 
     fn after_call_drop(&mut self);
+
+    /// Finishes the drop on the unwinding path of the drop terminating the current block.
+    fn after_call_drop_on_unwind(&mut self, unwind: &UnwindAction);
 }
 
 pub(crate) trait IntrinsicHandler<'tcx> {
@@ -214,6 +237,15 @@ pub(crate) trait IntrinsicHandler<'tcx> {
     );
 }
 
+/// Handles the SIMD intrinsics by passing the vectors lane by lane.
+pub(crate) trait SimdIntrinsicHandler<'tcx> {
+    fn simd_binary_op(&mut self, operator: BinaryOp, first: &[OperandRef], second: &[OperandRef]);
+
+    fn simd_unary_op(&mut self, operator: UnaryOp, operand: &[OperandRef]);
+
+    fn simd_reduce(&mut self, operator: BinaryOp, operand: &[OperandRef]);
+}
+
 pub(crate) trait MemoryIntrinsicHandler<'tcx> {
     fn load(&mut self, is_ptr_aligned: bool);
 
@@ -280,6 +312,12 @@ pub(crate) trait EntryFunctionHandler {
     fn shutdown_runtime_lib(&mut self);
 }
 
+pub(crate) trait TerminationHandler {
+    /// Reports the status that the returned value of the entry function
+    /// terminates the program with, i.e., what `Termination::report` decides.
+    fn report_termination(&mut self, result: PlaceRef);
+}
+
 pub(crate) trait AssertionHandler<'tcx> {
     fn check_assert(
         &mut self,
@@ -287,12 +325,29 @@ pub(crate) trait AssertionHandler<'tcx> {
         expected: bool,
         msg: &rustc_middle::mir::AssertMessage<'tcx>,
     );
+
+    /// Reports that the assertion terminating the current block has held.
+    /// Expected to be inserted after the block.
+    fn check_assert_passed(&mut self);
+
+    /// Reports the call to a panicking function terminating the current block,
+    /// e.g., by `panic!` or a failed `assert!`.
+    fn check_panic(&mut self);
 }
 
 pub(crate) trait DebugInfoHandler {
     fn debug_info<T: Serialize>(&mut self, info: &T);
 }
 
+pub(crate) trait TagHandler {
+    fn push_tag(&mut self, tag: &str);
+
+    fn pop_tag(&mut self);
+
+    /// Pops the tag on the unwinding path of the call terminating the current block.
+    fn pop_tag_on_unwind(&mut self, unwind: &UnwindAction);
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum InsertionLocation {
     Before(BasicBlock),
@@ -128,6 +128,7 @@ pub(crate) enum ConstantType {
     ByteStr,
     Ptr,
     Zst,
+    ByRef,
 }
 
 filter_struct! { AssignmentFilter {
@@ -25,8 +25,7 @@ impl CompilationPass for InstrumentationCounter {
         tcx: rustc_middle::ty::TyCtxt,
         storage: &mut dyn Storage,
     ) {
-        let pri_items =
-            storage.get_or_insert_with(KEY_PRI_ITEMS.to_owned(), || super::make_pri_items(tcx));
+        let pri_items = storage.get_or_insert_with(&KEY_PRI_ITEMS, || super::make_pri_items(tcx));
 
         let mut counts = HashMap::new();
 
@@ -1,6 +1,6 @@
 use rustc_middle::ty::IntrinsicDef;
 
-use common::pri::AtomicBinaryOp;
+use common::pri::{AtomicBinaryOp, BinaryOp, UnaryOp};
 
 use super::super::pri_utils;
 
@@ -11,6 +11,7 @@ pub(crate) enum IntrinsicDecision {
         kind: MemoryIntrinsicKind,
         is_volatile: bool,
     },
+    Simd(SimdIntrinsicKind),
     NoOp,
     ConstEvaluated,
     Contract,
@@ -39,6 +40,13 @@ pub(crate) enum MemoryIntrinsicKind {
     CompareBytes,
 }
 
+/// The element-wise and reduction operations on SIMD vectors.
+pub(crate) enum SimdIntrinsicKind {
+    BinOp(BinaryOp),
+    UnOp(UnaryOp),
+    Reduce(BinaryOp),
+}
+
 macro_rules! of_mir_translated_funcs {
     ($macro:ident) => {
         // These functions are expected to be translated to MIR elements and not appear at the
@@ -141,10 +149,6 @@ macro_rules! of_float_arith_funcs {
             truncf32,
             truncf64,
             truncf128,
-            sqrtf128,
-            sqrtf64,
-            sqrtf16,
-            sqrtf32,
             sinf128,
             sinf32,
             sinf16,
@@ -169,26 +173,6 @@ macro_rules! of_float_arith_funcs {
             logf32,
             logf64,
             logf128,
-            minimum_number_nsz_f16,
-            minimum_number_nsz_f32,
-            minimum_number_nsz_f64,
-            minimum_number_nsz_f128,
-            minimumf16,
-            minimumf32,
-            minimumf64,
-            minimumf128,
-            maximum_number_nsz_f16,
-            maximum_number_nsz_f32,
-            maximum_number_nsz_f64,
-            maximum_number_nsz_f128,
-            maximumf16,
-            maximumf32,
-            maximumf64,
-            maximumf128,
-            powif16,
-            powif32,
-            powif64,
-            powif128,
             powf16,
             powf32,
             powf64,
@@ -214,7 +198,6 @@ macro_rules! of_float_arith_funcs {
             fdiv_fast,
             fadd_fast,
             fdiv_algebraic,
-            fabs,
             fadd_algebraic,
             expf64,
             exp2f128,
@@ -434,6 +417,31 @@ macro_rules! of_one_to_one_funcs {
             unchecked_funnel_shl,
             unchecked_funnel_shr,
             carrying_mul_add,
+            sqrtf16,
+            sqrtf32,
+            sqrtf64,
+            sqrtf128,
+            fabs,
+            powif16,
+            powif32,
+            powif64,
+            powif128,
+            minimumf16,
+            minimumf32,
+            minimumf64,
+            minimumf128,
+            maximumf16,
+            maximumf32,
+            maximumf64,
+            maximumf128,
+            minimum_number_nsz_f16,
+            minimum_number_nsz_f32,
+            minimum_number_nsz_f64,
+            minimum_number_nsz_f128,
+            maximum_number_nsz_f16,
+            maximum_number_nsz_f32,
+            maximum_number_nsz_f64,
+            maximum_number_nsz_f128,
         )
     };
 }
@@ -487,13 +495,20 @@ use pri_utils::sym::intrinsics as psym;
 use rustc_span::sym as rsym;
 
 pub(crate) fn decide_intrinsic_call<'tcx>(intrinsic: IntrinsicDef) -> IntrinsicDecision {
+    try_decide_intrinsic_call(intrinsic)
+        .unwrap_or_else(|| panic!("Uncovered intrinsic: {:?}", intrinsic))
+}
+
+/// Same as [`decide_intrinsic_call`], but returns `None` for the intrinsics
+/// that are not covered yet.
+pub(crate) fn try_decide_intrinsic_call(intrinsic: IntrinsicDef) -> Option<IntrinsicDecision> {
     macro_rules! any_of {
         ($($intrinsic:ident),*$(,)?) => {
             $(rsym::$intrinsic)|*
         };
     }
 
-    match intrinsic.name {
+    let decision = match intrinsic.name {
         of_one_to_one_funcs!(any_of) => decide_one_to_one_intrinsic_call(intrinsic),
         of_noop_funcs!(any_of) => IntrinsicDecision::NoOp,
         of_contract_funcs!(any_of) => IntrinsicDecision::Contract,
@@ -501,11 +516,12 @@ pub(crate) fn decide_intrinsic_call<'tcx>(intrinsic: IntrinsicDef) -> IntrinsicD
         of_to_be_supported_funcs!(any_of) => IntrinsicDecision::ToDo,
         of_float_arith_funcs!(any_of) => IntrinsicDecision::NotPlanned,
         of_mir_translated_funcs!(any_of) => IntrinsicDecision::Unexpected,
-        of_simd_op_funcs!(any_of) => IntrinsicDecision::Unsupported,
+        of_simd_op_funcs!(any_of) => decide_simd_intrinsic_call(intrinsic),
         other if other.as_str().starts_with("atomic") => decide_atomic_intrinsic_call(intrinsic),
         of_memory_funcs!(any_of) => decide_memory_intrinsic_call(intrinsic),
-        _ => panic!("Uncovered intrinsic: {:?}", intrinsic),
-    }
+        _ => return None,
+    };
+    Some(decision)
 }
 
 fn decide_one_to_one_intrinsic_call(intrinsic: IntrinsicDef) -> IntrinsicDecision {
@@ -529,6 +545,27 @@ fn decide_one_to_one_intrinsic_call(intrinsic: IntrinsicDef) -> IntrinsicDecisio
         rsym::select_unpredictable => psym::intrinsic_assign_select_unpredictable,
         rsym::carrying_mul_add => psym::intrinsic_assign_carrying_mul_add,
         rsym::black_box => psym::intrinsic_assign_identity,
+        rsym::sqrtf16 | rsym::sqrtf32 | rsym::sqrtf64 | rsym::sqrtf128 => {
+            psym::intrinsic_assign_sqrt
+        }
+        rsym::fabs => psym::intrinsic_assign_fabs,
+        rsym::powif16 | rsym::powif32 | rsym::powif64 | rsym::powif128 => {
+            psym::intrinsic_assign_powi
+        }
+        rsym::minimumf16 | rsym::minimumf32 | rsym::minimumf64 | rsym::minimumf128 => {
+            psym::intrinsic_assign_minimum
+        }
+        rsym::maximumf16 | rsym::maximumf32 | rsym::maximumf64 | rsym::maximumf128 => {
+            psym::intrinsic_assign_maximum
+        }
+        rsym::minimum_number_nsz_f16
+        | rsym::minimum_number_nsz_f32
+        | rsym::minimum_number_nsz_f64
+        | rsym::minimum_number_nsz_f128 => psym::intrinsic_assign_minimum_number,
+        rsym::maximum_number_nsz_f16
+        | rsym::maximum_number_nsz_f32
+        | rsym::maximum_number_nsz_f64
+        | rsym::maximum_number_nsz_f128 => psym::intrinsic_assign_maximum_number,
         _ => unreachable!(),
     };
     IntrinsicDecision::OneToOneAssign(pri_sym)
@@ -600,6 +637,45 @@ fn decide_memory_intrinsic_call(intrinsic: IntrinsicDef) -> IntrinsicDecision {
     IntrinsicDecision::Memory { kind, is_volatile }
 }
 
+fn decide_simd_intrinsic_call(intrinsic: IntrinsicDef) -> IntrinsicDecision {
+    use SimdIntrinsicKind::*;
+    let kind = match intrinsic.name {
+        rsym::simd_add => BinOp(BinaryOp::ADD),
+        rsym::simd_sub => BinOp(BinaryOp::SUB),
+        rsym::simd_mul => BinOp(BinaryOp::MUL),
+        rsym::simd_div => BinOp(BinaryOp::DIV),
+        rsym::simd_rem => BinOp(BinaryOp::REM),
+        rsym::simd_and => BinOp(BinaryOp::BIT_AND),
+        rsym::simd_or => BinOp(BinaryOp::BIT_OR),
+        rsym::simd_xor => BinOp(BinaryOp::BIT_XOR),
+        rsym::simd_shl => BinOp(BinaryOp::SHL_UNCHECKED),
+        rsym::simd_shr => BinOp(BinaryOp::SHR_UNCHECKED),
+        rsym::simd_saturating_add => BinOp(BinaryOp::ADD_SATURATING),
+        rsym::simd_saturating_sub => BinOp(BinaryOp::SUB_SATURATING),
+        rsym::simd_eq => BinOp(BinaryOp::EQ),
+        rsym::simd_ne => BinOp(BinaryOp::NE),
+        rsym::simd_lt => BinOp(BinaryOp::LT),
+        rsym::simd_le => BinOp(BinaryOp::LE),
+        rsym::simd_gt => BinOp(BinaryOp::GT),
+        rsym::simd_ge => BinOp(BinaryOp::GE),
+        rsym::simd_neg => UnOp(UnaryOp::NEG),
+        rsym::simd_bitreverse => UnOp(UnaryOp::BIT_REVERSE),
+        rsym::simd_bswap => UnOp(UnaryOp::BSWAP),
+        rsym::simd_ctpop => UnOp(UnaryOp::CTPOP),
+        rsym::simd_ctlz => UnOp(UnaryOp::CTLZ),
+        rsym::simd_cttz => UnOp(UnaryOp::CTTZ),
+        rsym::simd_reduce_add_unordered => Reduce(BinaryOp::ADD),
+        rsym::simd_reduce_mul_unordered => Reduce(BinaryOp::MUL),
+        rsym::simd_reduce_and => Reduce(BinaryOp::BIT_AND),
+        rsym::simd_reduce_or => Reduce(BinaryOp::BIT_OR),
+        rsym::simd_reduce_xor => Reduce(BinaryOp::BIT_XOR),
+        rsym::simd_reduce_min => Reduce(BinaryOp::MIN),
+        rsym::simd_reduce_max => Reduce(BinaryOp::MAX),
+        _ => return IntrinsicDecision::Unsupported,
+    };
+    IntrinsicDecision::Simd(kind)
+}
+
 fn decide_atomic_intrinsic_call<'tcx>(intrinsic: IntrinsicDef) -> IntrinsicDecision {
     macro_rules! str_any_of {
         ($($intrinsic:ident),*$(,)?) => {
@@ -1,5 +1,6 @@
 mod intrinsics;
 pub(super) mod rules;
+mod summary;
 
 use const_format::concatcp;
 
@@ -12,7 +13,11 @@ use rustc_span::Symbol;
 
 use common::{log_debug, log_info, log_warn};
 
-use crate::{passes::Storage, utils::mir::TyCtxtExt};
+use crate::{
+    config::InstrumentationFilter,
+    passes::{Storage, StorageExt, StorageKey},
+    utils::mir::TyCtxtExt,
+};
 
 use super::config::WholeBodyFilter;
 
@@ -20,10 +25,17 @@ pub(super) const TAG_INSTR_DECISION: &str = concatcp!(super::TAG_INSTRUMENTATION
 
 const TOOL_NAME: &str = crate::constants::TOOL_LEAF;
 const ATTR_NAME: &str = "instrument";
+const ATTR_NAME_SKIP: &str = "skip";
+const ATTR_NAME_TAG: &str = "tag";
+const ATTR_ARG_FORCE: &str = "force";
+
+pub(super) const KEY_FILTER: StorageKey<InstrumentationFilter> = StorageKey::new("instr_filter");
 
 pub(super) use intrinsics::{
-    AtomicIntrinsicKind, IntrinsicDecision, MemoryIntrinsicKind, decide_intrinsic_call,
+    AtomicIntrinsicKind, IntrinsicDecision, MemoryIntrinsicKind, SimdIntrinsicKind,
+    decide_intrinsic_call, try_decide_intrinsic_call,
 };
+pub(super) use summary::{catch_tag_of, summary_tag_of};
 
 pub(super) fn should_instrument<'tcx>(
     tcx: TyCtxt<'tcx>,
@@ -50,12 +62,36 @@ pub(super) fn should_instrument<'tcx>(
         return false;
     }
 
+    // Const fns are not skipped, as they are also called at runtime.
+    // Only their runtime copy (`optimized_mir`) reaches here, while the
+    // compile-time evaluation uses `mir_for_ctfe`, which is computed beforehand
+    // and kept intact.
+
+    decide_by_rules(tcx, def_id, storage)
+}
+
+/// Returns whether the calls to the item enter instrumented code, i.e., the
+/// item has a body that is instrumented.
+pub(super) fn is_instrumented_item<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    storage: &mut dyn Storage,
+) -> bool {
+    if is_lang_start_item(tcx, def_id)
+        || tcx.intrinsic(def_id).is_some()
+        || !tcx.is_mir_available(def_id)
+    {
+        return false;
+    }
+
+    decide_by_rules(tcx, def_id, storage)
+}
+
+fn decide_by_rules<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId, storage: &mut dyn Storage) -> bool {
     rules::bake_rules(storage, get_exceptional_exclusions);
     let rules = rules::get_baked_body_rules(storage);
     if let Some((decision, item)) =
-        find_inheritable_first_filtered(tcx, def_id, move |tcx, def_id| {
-            rules.accept(&(tcx, def_id))
-        })
+        find_inheritable_first_filtered(tcx, def_id, |tcx, def_id| rules.accept(&(tcx, def_id)))
     {
         log_debug!(
             target: TAG_INSTR_DECISION,
@@ -66,8 +102,26 @@ pub(super) fn should_instrument<'tcx>(
         );
         return decision;
     }
+    drop(rules);
+
+    // The filter is matched against the item itself, as its ancestors (e.g., the crate root)
+    // are not expected to match the included paths.
+    decide_by_filter(&storage.get_or_default(&KEY_FILTER), tcx, def_id).unwrap_or(true)
+}
+
+/// Returns `Some(false)` if the item is filtered out, otherwise leaves the decision to the default.
+fn decide_by_filter<'tcx>(
+    filter: &InstrumentationFilter,
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+) -> Option<bool> {
+    if filter.is_empty() {
+        return None;
+    }
 
-    true
+    let accepted = filter.accepts_crate(tcx.crate_name(def_id.krate).as_str())
+        && filter.accepts_def_path(&tcx.def_path_str(def_id));
+    (!accepted).then_some(false)
 }
 
 fn decide_instance_kind(kind: &InstanceKind) -> bool {
@@ -133,41 +187,77 @@ fn get_exceptional_exclusions() -> Vec<WholeBodyFilter> {
     .collect()
 }
 
+/// The instrumentation decision explicitly placed on an item by attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InstrumentAttr {
+    /// `#[leaf_attr::instrument]`, `#[leaf_attr::instrument(true|false)]`, or `#[leaf_attr::skip]`.
+    Explicit(bool),
+    /// `#[leaf_attr::instrument(force)]`, which also overrides the rules matching the descendants.
+    Forced,
+}
+
+/// Finds the first item in the ancestors (including itself) that has an instrumentation decision.
+/// # Remarks
+/// As the closures are children of the functions they are defined in,
+/// they inherit the decision made for those functions.
 fn find_inheritable_first_filtered<'tcx>(
     tcx: TyCtxt<'tcx>,
     def_id: DefId,
     rules: impl Fn(TyCtxt<'tcx>, DefId) -> Option<bool>,
 ) -> Option<(bool, DefId)> {
-    let mut current = def_id;
-    loop {
-        // Attributes take precedence over filters.
-        if let Some(explicit) = opt_instrument_attr(tcx, current) {
-            log_info!(
-                target: TAG_INSTR_DECISION,
-                "Found explicit instrumentation attribute for {:?} on {:?} with value: {}",
-                def_id,
-                current,
-                explicit
-            );
-            return Some((explicit, current));
-        }
+    decide_over_ancestors(
+        core::iter::successors(Some(def_id), |id| tcx.opt_parent(*id)),
+        |current| opt_instrument_attr(tcx, current),
+        |current| rules(tcx, current),
+    )
+}
 
-        if let Some(include) = rules(tcx, current) {
-            return Some((include, current));
+/// Decides over the chain of the item and its ancestors (innermost first).
+/// Attributes take precedence over the rules, and after a rule is found,
+/// only forced attributes of the ancestors can override it.
+fn decide_over_ancestors<I: Copy + core::fmt::Debug>(
+    ancestors: impl Iterator<Item = I>,
+    attr_of: impl Fn(I) -> Option<InstrumentAttr>,
+    rules: impl Fn(I) -> Option<bool>,
+) -> Option<(bool, I)> {
+    let mut ancestors = ancestors.peekable();
+    let item = *ancestors.peek()?;
+    let mut ruled = None;
+    for current in ancestors {
+        match attr_of(current) {
+            Some(InstrumentAttr::Forced) => {
+                log_info!(
+                    target: TAG_INSTR_DECISION,
+                    "Found forced instrumentation attribute for {:?} on {:?}",
+                    item,
+                    current,
+                );
+                return Some((true, current));
+            }
+            Some(InstrumentAttr::Explicit(explicit)) if ruled.is_none() => {
+                log_info!(
+                    target: TAG_INSTR_DECISION,
+                    "Found explicit instrumentation attribute for {:?} on {:?} with value: {}",
+                    item,
+                    current,
+                    explicit
+                );
+                return Some((explicit, current));
+            }
+            _ => {}
         }
 
-        let parent = tcx.opt_parent(current);
-        current = match parent {
-            Some(parent) => parent,
-            None => return None,
-        };
+        if ruled.is_none() {
+            ruled = rules(current).map(|include| (include, current));
+        }
     }
+    ruled
 }
 
-/// Returns the value of the `instrument` attribute if it is placed on the item.
-/// If the attribute is not found, or the argument passed to the attribute is invalid
+/// Returns the instrumentation decision of the attributes placed on the item.
+/// If no attribute is found, or the argument passed to the attribute is invalid
 /// returns `None`.
-fn opt_instrument_attr<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId) -> Option<bool> {
+fn opt_instrument_attr<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId) -> Option<InstrumentAttr> {
     use rustc_hir::{AttrArgs, Attribute};
     // Avoid possibly problematic const items.
     // See https://github.com/rust-lang/rust/issues/128145
@@ -178,41 +268,125 @@ fn opt_instrument_attr<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId) -> Option<bool> {
         return None;
     }
 
-    tcx.get_attrs_by_path(
-        def_id,
-        &[Symbol::intern(TOOL_NAME), Symbol::intern(ATTR_NAME)],
-    )
-    .next()
-    .and_then(|attr| match attr {
-        Attribute::Unparsed(attr) => Some(attr),
-        _ => None,
-    })
-    .and_then(|attr| match &attr.args {
-        AttrArgs::Delimited(delim_args) => Some(delim_args.tokens.iter().next().cloned()),
-        AttrArgs::Empty | AttrArgs::Eq { .. } => None,
-    })
-    .and_then(|token| {
-        match token {
-            // No argument means it's enabled.
-            None => Some(true),
-            Some(token) => {
-                let as_bool = match &token {
-                    rustc_ast::tokenstream::TokenTree::Token(token, ..) => token
-                        .is_bool_lit()
-                        .then(|| token.ident().unwrap().0.name == rustc_span::symbol::kw::True),
-                    _ => None,
-                };
-                if as_bool.is_none() {
-                    log_warn!(
-                        "Invalid argument for attribute `{}`: {:?}",
-                        ATTR_NAME,
-                        token
-                    );
+    let tool = Symbol::intern(TOOL_NAME);
+    if tcx
+        .get_attrs_by_path(def_id, &[tool, Symbol::intern(ATTR_NAME_SKIP)])
+        .next()
+        .is_some()
+    {
+        return Some(InstrumentAttr::Explicit(false));
+    }
+
+    tcx.get_attrs_by_path(def_id, &[tool, Symbol::intern(ATTR_NAME)])
+        .next()
+        .and_then(|attr| match attr {
+            Attribute::Unparsed(attr) => Some(attr),
+            _ => None,
+        })
+        .and_then(|attr| match &attr.args {
+            AttrArgs::Delimited(delim_args) => Some(delim_args.tokens.iter().next().cloned()),
+            AttrArgs::Empty | AttrArgs::Eq { .. } => None,
+        })
+        .and_then(|token| {
+            match token {
+                // No argument means it's enabled.
+                None => Some(InstrumentAttr::Explicit(true)),
+                Some(token) => {
+                    let decision = match &token {
+                        rustc_ast::tokenstream::TokenTree::Token(token, ..) => {
+                            token.ident().and_then(|(ident, _)| {
+                                if token.is_bool_lit() {
+                                    Some(InstrumentAttr::Explicit(
+                                        ident.name == rustc_span::symbol::kw::True,
+                                    ))
+                                } else if ident.name.as_str() == ATTR_ARG_FORCE {
+                                    Some(InstrumentAttr::Forced)
+                                } else {
+                                    None
+                                }
+                            })
+                        }
+                        _ => None,
+                    };
+                    if decision.is_none() {
+                        log_warn!(
+                            "Invalid argument for attribute `{}`: {:?}",
+                            ATTR_NAME,
+                            token
+                        );
+                    }
+                    decision
                 }
-                as_bool
             }
-        }
-    })
+        })
+}
+
+/// Returns the tag placed on the function by `#[leaf_attr::tag("...")]`.
+/// If no attribute is found, or the argument is not a string literal, returns `None`.
+pub(super) fn tag_attr_of<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId) -> Option<String> {
+    use rustc_ast::token::{LitKind, TokenKind};
+    use rustc_hir::{AttrArgs, Attribute};
+    // Avoid possibly problematic const items.
+    // See https://github.com/rust-lang/rust/issues/128145
+    if matches!(
+        tcx.def_key(def_id).disambiguated_data.data,
+        DefPathData::AnonConst
+    ) {
+        return None;
+    }
+
+    let attr = tcx
+        .get_attrs_by_path(
+            def_id,
+            &[Symbol::intern(TOOL_NAME), Symbol::intern(ATTR_NAME_TAG)],
+        )
+        .next()?;
+    let token = match attr {
+        Attribute::Unparsed(attr) => match &attr.args {
+            AttrArgs::Delimited(delim_args) => delim_args.tokens.iter().next().cloned(),
+            AttrArgs::Empty | AttrArgs::Eq { .. } => None,
+        },
+        _ => None,
+    };
+    let tag = token.as_ref().and_then(|token| match token {
+        rustc_ast::tokenstream::TokenTree::Token(token, ..) => match token.kind {
+            TokenKind::Literal(lit) if lit.kind == LitKind::Str => {
+                Some(lit.symbol.as_str().to_owned())
+            }
+            _ => None,
+        },
+        _ => None,
+    });
+    if tag.is_none() {
+        log_warn!(
+            "Invalid argument for attribute `{}`: {:?}",
+            ATTR_NAME_TAG,
+            token
+        );
+    }
+    tag
+}
+
+/// The functions of the global allocator that the allocations on the heap
+/// are eventually delegated to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum AllocatorFunc {
+    /// `__rust_alloc` and `__rust_alloc_zeroed`
+    Alloc,
+    Realloc,
+    Dealloc,
+}
+
+pub(super) fn allocator_func_of(tcx: TyCtxt<'_>, def_id: DefId) -> Option<AllocatorFunc> {
+    if !tcx.is_foreign_item(def_id) {
+        return None;
+    }
+    match tcx.item_name(def_id).as_str() {
+        "__rust_alloc" | "__rust_alloc_zeroed" => Some(AllocatorFunc::Alloc),
+        "__rust_realloc" => Some(AllocatorFunc::Realloc),
+        "__rust_dealloc" => Some(AllocatorFunc::Dealloc),
+        _ => None,
+    }
 }
 
 fn is_lang_start_item(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
@@ -244,3 +418,82 @@ fn is_drop_fn(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
             )
             .is_some_and(|(t1, t2)| t1 == t2)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{InstrumentAttr, decide_over_ancestors};
+
+    /// An item and its ancestors, e.g., a closure, its function, module, and crate root.
+    const CHAIN: [u32; 4] = [0, 1, 2, 3];
+
+    fn decide(attrs: &[(u32, InstrumentAttr)], rules: &[(u32, bool)]) -> Option<(bool, u32)> {
+        let find = |id: u32| attrs.iter().find(|(i, _)| *i == id).map(|(_, a)| *a);
+        let rule = |id: u32| rules.iter().find(|(i, _)| *i == id).map(|(_, r)| *r);
+        decide_over_ancestors(CHAIN.into_iter(), find, rule)
+    }
+
+    #[test]
+    fn no_decision_without_attrs_and_rules() {
+        assert_eq!(decide(&[], &[]), None);
+    }
+
+    #[test]
+    fn closures_inherit_skip() {
+        assert_eq!(
+            decide(&[(1, InstrumentAttr::Explicit(false))], &[]),
+            Some((false, 1))
+        );
+    }
+
+    #[test]
+    fn innermost_attr_wins() {
+        assert_eq!(
+            decide(
+                &[
+                    (0, InstrumentAttr::Explicit(true)),
+                    (2, InstrumentAttr::Explicit(false))
+                ],
+                &[]
+            ),
+            Some((true, 0))
+        );
+    }
+
+    #[test]
+    fn attr_takes_precedence_over_rule_on_same_item() {
+        assert_eq!(
+            decide(&[(1, InstrumentAttr::Explicit(false))], &[(1, true)]),
+            Some((false, 1))
+        );
+    }
+
+    #[test]
+    fn explicit_attr_of_ancestor_does_not_override_rule() {
+        assert_eq!(
+            decide(&[(2, InstrumentAttr::Explicit(true))], &[(1, false)]),
+            Some((false, 1))
+        );
+    }
+
+    #[test]
+    fn forced_attr_of_ancestor_overrides_rule() {
+        assert_eq!(
+            decide(&[(2, InstrumentAttr::Forced)], &[(0, false)]),
+            Some((true, 2))
+        );
+    }
+
+    #[test]
+    fn skip_inside_forced_module_is_kept() {
+        assert_eq!(
+            decide(
+                &[
+                    (1, InstrumentAttr::Explicit(false)),
+                    (3, InstrumentAttr::Forced)
+                ],
+                &[]
+            ),
+            Some((false, 1))
+        );
+    }
+}
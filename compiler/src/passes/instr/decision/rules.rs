@@ -8,24 +8,35 @@ use rustc_middle::ty::TyCtxt;
 
 use crate::{
     config::rules::{InclusionRules, LogicFormula},
-    passes::{Storage, StorageExt},
+    passes::{Storage, StorageExt, StorageKey},
     utils::rules::{InclusionPredicate, Predicate, ToPredicate},
 };
 
 use super::super::config::*;
 
-pub(crate) const KEY_RULES: &str = "instr_rules";
-pub(crate) const KEY_BAKED_BODY_RULES: &str = "i_r_b_body";
-pub(crate) const KEY_BAKED_DYN_DEF_RULES: &str = "i_r_b_dyn_def";
-pub(crate) const KEY_BAKED_PLACE_INFO_RULES: &str = "i_r_b_place_info";
-pub(crate) const KEY_BAKED_OPERAND_INFO_RULES: &str = "i_r_b_operand_info";
-pub(crate) const KEY_BAKED_CONST_TYPE_RULES: &str = "i_r_b_const_ty";
-pub(crate) const KEY_BAKED_ASSIGNMENT_RULES: &str = "i_r_b_assignment";
-pub(crate) const KEY_BAKED_ASSIGNMENT_INFO_RULES: &str = "i_r_b_assignment_info";
-pub(crate) const KEY_BAKED_STORAGE_LIFETIME_RULES: &str = "i_r_b_storage_lifetime";
-pub(crate) const KEY_BAKED_CALL_FLOW_RULES: &str = "i_r_b_call_flow";
-pub(crate) const KEY_BAKED_DROP_RULES: &str = "i_r_b_drop";
-pub(crate) const KEY_BAKED_SWITCH_RULES: &str = "i_r_b_switch";
+pub(crate) const KEY_RULES: StorageKey<InstrumentationRules> = StorageKey::new("instr_rules");
+const KEY_BAKED_BODY_RULES: StorageKey<BakedWholeBodyFilterRules<'static>> =
+    StorageKey::new("i_r_b_body");
+const KEY_BAKED_DYN_DEF_RULES: StorageKey<BakedMethodDynDefinitionFilterRules<'static>> =
+    StorageKey::new("i_r_b_dyn_def");
+const KEY_BAKED_PLACE_INFO_RULES: StorageKey<BakedPlaceInfoFilterRules<'static>> =
+    StorageKey::new("i_r_b_place_info");
+const KEY_BAKED_OPERAND_INFO_RULES: StorageKey<BakedOperandKindFilterRules<'static>> =
+    StorageKey::new("i_r_b_operand_info");
+const KEY_BAKED_CONST_TYPE_RULES: StorageKey<BakedConstantTypeFilterRules<'static>> =
+    StorageKey::new("i_r_b_const_ty");
+const KEY_BAKED_ASSIGNMENT_RULES: StorageKey<BakedAssignmentFilterRules<'static>> =
+    StorageKey::new("i_r_b_assignment");
+const KEY_BAKED_ASSIGNMENT_INFO_RULES: StorageKey<BakedAssignmentInfoFilterRules<'static>> =
+    StorageKey::new("i_r_b_assignment_info");
+const KEY_BAKED_STORAGE_LIFETIME_RULES: StorageKey<BakedStorageLifetimeMarkerFilterRules<'static>> =
+    StorageKey::new("i_r_b_storage_lifetime");
+const KEY_BAKED_CALL_FLOW_RULES: StorageKey<BakedCallFlowFilterRules<'static>> =
+    StorageKey::new("i_r_b_call_flow");
+const KEY_BAKED_DROP_RULES: StorageKey<BakedDropFilterRules<'static>> =
+    StorageKey::new("i_r_b_drop");
+const KEY_BAKED_SWITCH_RULES: StorageKey<BakedSwitchFilterRules<'static>> =
+    StorageKey::new("i_r_b_switch");
 
 type LocationQuery<'tcx> = (TyCtxt<'tcx>, DefId);
 
@@ -177,6 +188,7 @@ define_filter_rule_group!(
         ByteStr => byte_str,
         Ptr => ptr,
         Zst => zst,
+        ByRef => by_ref,
     }
 );
 
@@ -239,16 +251,15 @@ define_filter_rule_group!(
 pub(crate) fn get_baked_body_rules<'tcx>(
     storage: &mut dyn Storage,
 ) -> impl Deref<Target = BakedEntityLocationFilterRules<'tcx>> + '_ {
-    get_baked_rules(storage, KEY_BAKED_BODY_RULES)
+    get_baked_rules(storage, &KEY_BAKED_BODY_RULES)
 }
 
 fn get_baked_rules<'a, T: Any>(
     storage: &'a mut dyn Storage,
-    key: &str,
+    key: &StorageKey<T>,
 ) -> impl Deref<Target = T> + 'a {
-    let key = key.to_owned();
     storage
-        .get_mut::<T>(&key)
+        .get_mut(key)
         .expect("Filter rules are expected to be baked at this point.")
 }
 
@@ -259,8 +270,7 @@ pub(crate) fn accept_dyn_def_filter_rules<'tcx, I>(
 where
     EntityLocationFilterPredicate<'tcx>: Predicate<I>,
 {
-    get_baked_rules::<BakedEntityLocationFilterRules<'tcx>>(storage, KEY_BAKED_DYN_DEF_RULES)
-        .accept(item)
+    get_baked_rules(storage, &KEY_BAKED_DYN_DEF_RULES).accept(item)
 }
 
 pub(crate) type PlaceInfoFilterResult =
@@ -270,8 +280,7 @@ pub(crate) fn accept_place_info_rules<'tcx>(
     storage: &mut dyn Storage,
     item: &LocationQuery<'tcx>,
 ) -> PlaceInfoFilterResult {
-    let rules =
-        get_baked_rules::<BakedPlaceInfoFilterRules<'tcx>>(storage, KEY_BAKED_PLACE_INFO_RULES);
+    let rules = get_baked_rules(storage, &KEY_BAKED_PLACE_INFO_RULES);
 
     PlaceInfoRules {
         structure: PlaceStructureRules::accept(|piece| rules.structure.accept(&(piece, *item))),
@@ -286,8 +295,7 @@ pub(crate) fn accept_operand_info_rules<'tcx>(
     storage: &mut dyn Storage,
     item: &LocationQuery<'tcx>,
 ) -> OperandKindFilterResult {
-    let rules =
-        get_baked_rules::<BakedOperandKindFilterRules<'tcx>>(storage, KEY_BAKED_OPERAND_INFO_RULES);
+    let rules = get_baked_rules(storage, &KEY_BAKED_OPERAND_INFO_RULES);
 
     use OperandKind::*;
     OperandKindRules {
@@ -303,8 +311,7 @@ pub(crate) fn accept_constant_type_rules<'tcx>(
     storage: &mut dyn Storage,
     item: &LocationQuery<'tcx>,
 ) -> ConstantTypeFilterResult {
-    let rules =
-        get_baked_rules::<BakedConstantTypeFilterRules<'tcx>>(storage, KEY_BAKED_CONST_TYPE_RULES);
+    let rules = get_baked_rules(storage, &KEY_BAKED_CONST_TYPE_RULES);
 
     ConstantTypeRules::accept(|kind| rules.accept(&(kind, *item)))
 }
@@ -317,11 +324,11 @@ pub(crate) fn accept_assignment_rules<'tcx>(
     info: bool,
 ) -> AssignmentFilterResult {
     let key = if !info {
-        KEY_BAKED_ASSIGNMENT_RULES
+        &KEY_BAKED_ASSIGNMENT_RULES
     } else {
-        KEY_BAKED_ASSIGNMENT_INFO_RULES
+        &KEY_BAKED_ASSIGNMENT_INFO_RULES
     };
-    let rules = get_baked_rules::<BakedAssignmentFilterRules<'tcx>>(storage, key);
+    let rules = get_baked_rules(storage, key);
 
     AssignmentRules::accept(|kind| rules.accept(&(kind, *item)))
 }
@@ -332,10 +339,7 @@ pub(crate) fn accept_storage_lifetime_rules<'tcx>(
     storage: &mut dyn Storage,
     item: &LocationQuery<'tcx>,
 ) -> StorageLifetimeFilterResult {
-    let rules = get_baked_rules::<BakedStorageLifetimeMarkerFilterRules<'tcx>>(
-        storage,
-        KEY_BAKED_STORAGE_LIFETIME_RULES,
-    );
+    let rules = get_baked_rules(storage, &KEY_BAKED_STORAGE_LIFETIME_RULES);
 
     StorageLifetimeMarkerRules::accept(|kind| rules.accept(&(kind, *item)))
 }
@@ -346,8 +350,7 @@ pub(crate) fn accept_call_flow_rules<'tcx>(
     storage: &mut dyn Storage,
     item: &LocationQuery<'tcx>,
 ) -> CallFlowFilterResult {
-    let rules =
-        get_baked_rules::<BakedCallFlowFilterRules<'tcx>>(storage, KEY_BAKED_CALL_FLOW_RULES);
+    let rules = get_baked_rules(storage, &KEY_BAKED_CALL_FLOW_RULES);
 
     CallFlowRules::accept(|kind| rules.accept(&(kind, *item)))
 }
@@ -358,7 +361,7 @@ pub(crate) fn accept_drop_rules<'tcx>(
     storage: &mut dyn Storage,
     item: &LocationQuery<'tcx>,
 ) -> DropFilterResult {
-    let rules = get_baked_rules::<BakedDropFilterRules<'tcx>>(storage, KEY_BAKED_DROP_RULES);
+    let rules = get_baked_rules(storage, &KEY_BAKED_DROP_RULES);
 
     DropRules::accept(|kind| rules.accept(&(kind, *item)))
 }
@@ -369,7 +372,7 @@ pub(crate) fn accept_switch_rules<'tcx>(
     storage: &mut dyn Storage,
     item: &LocationQuery<'tcx>,
 ) -> SwitchFilterResult {
-    let rules = get_baked_rules::<BakedSwitchFilterRules<'tcx>>(storage, KEY_BAKED_SWITCH_RULES);
+    let rules = get_baked_rules(storage, &KEY_BAKED_SWITCH_RULES);
 
     SwitchRules::accept(|kind| rules.accept(&(kind, *item)))
 }
@@ -386,8 +389,8 @@ pub(super) fn bake_rules(
             |$rules:ident| $body:block
         ) => {
             // We use explicit types to ensure not using the wrong type by mistake.
-            let _ = storage.get_or_insert_with_acc($key.to_owned(), |storage| -> $baked {
-                let all_rules = storage.get_or_default::<InstrumentationRules>(KEY_RULES.to_owned());
+            let _ = storage.get_or_insert_with_acc(&$key, |storage| -> $baked {
+                let all_rules = storage.get_or_default(&KEY_RULES);
                 #[allow(unused_mut)]
                 let mut $rules: InclusionRules<_> = all_rules.clone().filter_map(|r| match r {
                     EntityFilter::$variant(filter) => Some(filter),
@@ -420,9 +423,9 @@ pub(super) fn bake_rules(
 
     // Place is a bit structurally different
     let _ = storage.get_or_insert_with_acc(
-        KEY_BAKED_PLACE_INFO_RULES.to_owned(),
+        &KEY_BAKED_PLACE_INFO_RULES,
         |storage| -> BakedPlaceInfoFilterRules<'_> {
-            let rules = storage.get_or_default::<InstrumentationRules>(KEY_RULES.to_owned());
+            let rules = storage.get_or_default(&KEY_RULES);
             let rules = rules.clone().filter_map(|r| match r {
                 EntityFilter::PlaceInfo(filter) => Some(filter),
                 _ => None,
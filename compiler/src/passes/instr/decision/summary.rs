@@ -0,0 +1,199 @@
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::{GenericArgsRef, Ty, TyCtxt, TyKind};
+use rustc_span::{Symbol, sym};
+
+use common::pri::tags::{CATCH_UNWIND, SUMMARY_PREFIX};
+
+/// The combinators of `Option` and `Result` that the runtime can summarize when they are
+/// not instrumented, i.e., when the standard library is not built with instrumentation.
+const SUMMARIZED_COMBINATORS: [&str; 5] =
+    ["map", "map_err", "and_then", "unwrap_or", "unwrap_or_else"];
+
+/// The methods of `Iterator` that consume the iterator by calling a closure on the items.
+const SUMMARIZED_ITERATOR_CONSUMERS: [&str; 4] = ["fold", "any", "all", "for_each"];
+
+/// The iterator adapters whose `next` calls the closure they hold.
+const SUMMARIZED_ITERATOR_ADAPTERS: [&str; 2] = ["Map", "Filter"];
+
+/// The methods of slices that call a comparator closure on the elements.
+const SUMMARIZED_SLICE_METHODS: [&str; 2] = ["sort_by", "sort_unstable_by"];
+
+/// The methods of slices that take no closure and compare the elements.
+const SUMMARIZED_SLICE_SEARCHES: [&str; 3] = ["contains", "starts_with", "ends_with"];
+
+/// Returns the tag marking the calls to the function, if its effect is summarized by the runtime.
+/// The function is identified by its def path, i.e., the type of its impl (or its trait)
+/// and its name.
+/// Only the calls to uninstrumented functions are expected to be tagged, as the
+/// instrumented ones are observed directly.
+pub(crate) fn summary_tag_of<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    generic_args: GenericArgsRef<'tcx>,
+) -> Option<String> {
+    let func_name = tcx.item_name(def_id);
+    let path = combinator_owner(tcx, def_id, func_name)
+        .or_else(|| higher_order_owner(tcx, def_id, generic_args, func_name))
+        .map(|name| name.to_string())
+        .or_else(|| no_closure_owner(tcx, def_id, generic_args, func_name))
+        .map(|ty_name| format!("{ty_name}::{func_name}"))
+        .or_else(|| parsing_path(tcx, def_id, generic_args, func_name))?;
+    Some(format!("{SUMMARY_PREFIX}{path}"))
+}
+
+/// Returns the tag marking the calls to the function, if it catches the panics
+/// unwinding through it, i.e., `std::panic::catch_unwind` or the functions and
+/// the intrinsic it is implemented with.
+pub(crate) fn catch_tag_of(tcx: TyCtxt, def_id: DefId) -> Option<String> {
+    (tcx.item_name(def_id) == sym::catch_unwind
+        && [sym::std, sym::core].contains(&tcx.crate_name(def_id.krate)))
+    .then(|| CATCH_UNWIND.to_owned())
+}
+
+fn combinator_owner(tcx: TyCtxt, def_id: DefId, func_name: Symbol) -> Option<Symbol> {
+    let impl_id = tcx.impl_of_assoc(def_id)?;
+    if tcx.impl_opt_trait_id(impl_id).is_some() {
+        return None;
+    }
+
+    let adt_def = tcx.type_of(impl_id).instantiate_identity().ty_adt_def()?;
+    let ty_name = [sym::Option, sym::Result]
+        .into_iter()
+        .find(|name| tcx.is_diagnostic_item(*name, adt_def.did()))?;
+
+    SUMMARIZED_COMBINATORS
+        .contains(&func_name.as_str())
+        .then_some(ty_name)
+}
+
+/// # Remarks
+/// Higher-order functions are only summarized if the closure passed to them is
+/// instrumented, as otherwise the runtime does not observe its invocations.
+fn higher_order_owner<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    generic_args: GenericArgsRef<'tcx>,
+    func_name: Symbol,
+) -> Option<Symbol> {
+    let owner = if let Some(trait_id) = tcx.trait_of_assoc(def_id) {
+        if !tcx.is_diagnostic_item(sym::Iterator, trait_id) {
+            return None;
+        }
+        if SUMMARIZED_ITERATOR_CONSUMERS.contains(&func_name.as_str()) {
+            sym::Iterator
+        } else if func_name == sym::next {
+            let adt_def = generic_args.type_at(0).ty_adt_def()?;
+            let adapter_name = tcx.item_name(adt_def.did());
+            (is_std_crate(tcx, adt_def.did())
+                && SUMMARIZED_ITERATOR_ADAPTERS.contains(&adapter_name.as_str()))
+            .then_some(adapter_name)?
+        } else {
+            return None;
+        }
+    } else {
+        let impl_id = tcx.impl_of_assoc(def_id)?;
+        let is_slice_impl = tcx.impl_opt_trait_id(impl_id).is_none()
+            && tcx.type_of(impl_id).instantiate_identity().is_slice();
+        (is_slice_impl && SUMMARIZED_SLICE_METHODS.contains(&func_name.as_str()))
+            .then_some(sym::slice)?
+    };
+
+    let has_instrumented_closure = generic_args
+        .iter()
+        .flat_map(|arg| arg.walk())
+        .filter_map(|arg| arg.as_type())
+        .any(|ty| match ty.kind() {
+            TyKind::Closure(id, _) | TyKind::FnDef(id, _) => !is_std_crate(tcx, *id),
+            _ => false,
+        });
+    has_instrumented_closure.then_some(owner)
+}
+
+/// # Remarks
+/// For comparisons, the owner is prefixed by an `&` for each reference wrapping
+/// the compared type (e.g., `&str` for `<&str as PartialEq>::eq`), so that the
+/// runtime can reach the compared values.
+fn no_closure_owner<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    generic_args: GenericArgsRef<'tcx>,
+    func_name: Symbol,
+) -> Option<String> {
+    if let Some(trait_id) = tcx.trait_of_assoc(def_id) {
+        if !tcx.is_diagnostic_item(sym::PartialEq, trait_id)
+            || ![sym::eq, sym::ne].contains(&func_name)
+        {
+            return None;
+        }
+        let (self_ty, rhs_ty) = (generic_args.type_at(0), generic_args.type_at(1));
+        if self_ty != rhs_ty {
+            return None;
+        }
+
+        let mut ty = self_ty;
+        let mut refs = String::new();
+        while let TyKind::Ref(_, pointee, _) = ty.kind() {
+            refs.push('&');
+            ty = *pointee;
+        }
+        let owner = if ty.is_str() {
+            sym::str
+        } else if ty.is_slice() {
+            sym::slice
+        } else {
+            return None;
+        };
+        Some(format!("{refs}{owner}"))
+    } else {
+        let impl_id = tcx.impl_of_assoc(def_id)?;
+        let is_slice_impl = tcx.impl_opt_trait_id(impl_id).is_none()
+            && tcx.type_of(impl_id).instantiate_identity().is_slice();
+        (is_slice_impl && SUMMARIZED_SLICE_SEARCHES.contains(&func_name.as_str()))
+            .then(|| sym::slice.to_string())
+    }
+}
+
+/// Returns the path of the parsing function that the runtime models, i.e.,
+/// `from_utf8` and parsing integers.
+/// # Remarks
+/// `str::parse` is identified by the `FromStr` implementation it calls, e.g.,
+/// `u32::from_str`, as the parsed type is not otherwise available to the runtime.
+fn parsing_path<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    generic_args: GenericArgsRef<'tcx>,
+    func_name: Symbol,
+) -> Option<String> {
+    if !is_std_crate(tcx, def_id) {
+        return None;
+    }
+
+    let int_from_str = |ty: Ty<'tcx>| ty.is_integral().then(|| format!("{ty}::from_str"));
+    if let Some(trait_id) = tcx.trait_of_assoc(def_id) {
+        if tcx.item_name(trait_id).as_str() != "FromStr" || func_name.as_str() != "from_str" {
+            return None;
+        }
+        int_from_str(generic_args.type_at(0))
+    } else if let Some(impl_id) = tcx.impl_of_assoc(def_id) {
+        if tcx.impl_opt_trait_id(impl_id).is_some() {
+            return None;
+        }
+        let self_ty = tcx.type_of(impl_id).instantiate_identity();
+        match func_name.as_str() {
+            "from_str_radix" if self_ty.is_integral() => Some(format!("{self_ty}::from_str_radix")),
+            "parse" if self_ty.is_str() => int_from_str(generic_args.type_at(0)),
+            "from_utf8" if self_ty.is_str() => Some("str::from_utf8".to_owned()),
+            _ => None,
+        }
+    } else {
+        // The free function in `core::str`, as the others are associated with `String`.
+        (func_name.as_str() == "from_utf8").then(|| "str::from_utf8".to_owned())
+    }
+}
+
+fn is_std_crate(tcx: TyCtxt, def_id: DefId) -> bool {
+    matches!(
+        tcx.crate_name(def_id.krate),
+        sym::core | sym::alloc | sym::std
+    )
+}
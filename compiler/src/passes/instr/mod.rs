@@ -1,3 +1,4 @@
+mod audit;
 mod call;
 mod config;
 mod counter;
@@ -12,8 +13,8 @@ use rustc_index::IndexVec;
 use rustc_middle::{
     mir::{
         self, BasicBlock, BasicBlockData, Body, BorrowKind, CastKind, HasLocalDecls, Location,
-        MirSource, Operand, Place, Rvalue, SourceInfo, Statement, TerminatorKind, UnwindAction,
-        visit::Visitor,
+        MirSource, Operand, Place, ProjectionElem, Rvalue, SourceInfo, Statement, TerminatorKind,
+        UnwindAction, visit::Visitor,
     },
     ty::{self as mir_ty, IntrinsicDef, Ty, TyCtxt},
 };
@@ -29,29 +30,33 @@ use std::{
 
 use common::{
     log_debug, log_info, log_warn,
-    pri::{AssignmentId, AtomicBinaryOp},
+    pri::{AssignmentId, AtomicBinaryOp, BinaryOp},
 };
 
 use crate::{
-    mir_transform::{self, BodyInstrumentationUnit, JumpTargetModifier},
+    config::InstrumentationFilter,
+    mir_transform::{self, BodyModificationUnit, JumpTargetModifier},
     passes::StorageExt,
     utils::mir::{BodyExt, TyCtxtExt},
     visit::*,
 };
 
-use super::{CompilationPass, OverrideFlags, Storage};
+use super::{CompilationPass, OverrideFlags, Storage, StorageKey, StorageScope};
 
 use self::{
     call::{
-        AssertionHandler, Assigner, AtomicIntrinsicHandler, BranchingHandler, BranchingReferencer,
-        CastAssigner, Config, DropHandler, EntryFunctionHandler, FunctionHandler,
+        AllocationMarker, AssertionHandler, Assigner, AtomicIntrinsicHandler, BranchingHandler,
+        BranchingReferencer, CastAssigner, Config, DropHandler, EntryFunctionHandler,
+        FunctionHandler,
         InsertionLocation::*,
         IntrinsicHandler, MemoryIntrinsicHandler, OperandRef, OperandReferencer, PlaceRef,
-        PlaceReferencer, RuntimeCallAdder, StorageMarker,
+        PlaceReferencer, RuntimeCallAdder, SimdIntrinsicHandler, StorageMarker, TagHandler,
+        TerminationHandler,
         context::ConfigProvider,
         context::{
             AtLocationContext, BlockIndexProvider, BlockOriginalIndexProvider, BodyProvider,
-            PointerPackage, PriItems, PriItemsProvider, SourceInfoProvider, TyContextProvider,
+            PointerPackage, PriItems, PriItemsProvider, SourceInfoProvider, StorageProvider,
+            TyContextProvider,
         },
         ctxt_reqs as cr,
     },
@@ -59,6 +64,7 @@ use self::{
     pri_utils::sym::intrinsics::LeafIntrinsicSymbol,
 };
 
+pub(crate) use audit::InstrumentationAuditor;
 pub(crate) use config::InstrumentationRules;
 pub(crate) use counter::InstrumentationCounter;
 pub(crate) use rec_check::InstrumentationRecursionChecker;
@@ -67,24 +73,28 @@ const TAG_INSTRUMENTATION: &str = "instrumentation";
 use TAG_INSTRUMENTATION as TAG_INSTR;
 const TAG_INSTR_COUNTER: &str = concatcp!(TAG_INSTRUMENTATION, "::counter");
 
-const KEY_PRI_ITEMS: &str = "pri_items";
-const KEY_TOTAL_COUNT: &str = "total_body_count";
-const KEY_SWITCH_ORIG_INDICES: &str = "instr_switch_indices";
+const KEY_PRI_ITEMS: StorageKey<PriItems> = StorageKey::new("pri_items");
+const KEY_TOTAL_COUNT: StorageKey<Option<NonZeroUsize>> = StorageKey::new("total_body_count");
+const KEY_SWITCH_ORIG_INDICES: StorageKey<Vec<BasicBlock>> =
+    StorageKey::scoped("instr_switch_indices", StorageScope::Body);
 
 #[derive(Default)]
 pub(crate) struct Instrumentor {
     total_body_count: Option<NonZeroUsize>,
     rules: Option<InstrumentationRules>,
+    filter: Option<InstrumentationFilter>,
 }
 
 impl Instrumentor {
     pub(crate) fn new(
         total_body_count: Option<NonZeroUsize>,
-        filters: InstrumentationRules,
+        rules: InstrumentationRules,
+        filter: InstrumentationFilter,
     ) -> Self {
         Self {
             total_body_count,
-            rules: Some(filters),
+            rules: Some(rules),
+            filter: Some(filter),
         }
     }
 }
@@ -101,10 +111,9 @@ impl CompilationPass for Instrumentor {
         _krate: &rustc_ast::Crate,
         storage: &mut dyn Storage,
     ) -> rustc_driver::Compilation {
-        storage.get_or_insert_with(KEY_TOTAL_COUNT.to_owned(), || self.total_body_count);
-        storage.get_or_insert_with(decision::rules::KEY_RULES.to_owned(), || {
-            self.rules.take().unwrap()
-        });
+        storage.get_or_insert_with(&KEY_TOTAL_COUNT, || self.total_body_count);
+        storage.get_or_insert_with(&decision::rules::KEY_RULES, || self.rules.take().unwrap());
+        storage.get_or_insert_with(&decision::KEY_FILTER, || self.filter.take().unwrap());
         rustc_driver::Compilation::Continue
     }
 
@@ -147,7 +156,7 @@ fn transform<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>, storage: &mut dyn S
     );
 
     let pri_items = storage
-        .get_or_insert_with(KEY_PRI_ITEMS.to_owned(), || make_pri_items(tcx))
+        .get_or_insert_with(&KEY_PRI_ITEMS, || make_pri_items(tcx))
         .leak();
 
     let config = make_config(storage, tcx, def_id);
@@ -159,11 +168,11 @@ fn transform<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>, storage: &mut dyn S
 
     let orig_index_map = make_orig_index_map(body, storage);
 
-    let mut modification = BodyInstrumentationUnit::new(body.local_decls());
+    let mut modification = BodyModificationUnit::new(body.local_decls());
     let mut call_adder = RuntimeCallAdder::new(tcx, &mut modification, &pri_items, storage, config);
     let mut call_adder = call_adder.in_body(body, orig_index_map);
 
-    let is_entry = tcx.entry_fn(()).is_some_and(|(id, _)| id == def_id);
+    let is_entry = is_entry_function(tcx, body);
 
     if is_entry {
         handle_entry_function_pre(&mut call_adder, body);
@@ -193,9 +202,7 @@ fn on_start(_tcx: TyCtxt, storage: &mut dyn Storage) {
     {
         static COUNTER: atomic::AtomicUsize = atomic::AtomicUsize::new(0);
         let counter = COUNTER.fetch_add(1, atomic::Ordering::SeqCst);
-        let total = *storage
-            .get_mut::<Option<NonZeroUsize>>(&KEY_TOTAL_COUNT.to_owned())
-            .unwrap();
+        let total = *storage.get_mut(&KEY_TOTAL_COUNT).unwrap();
         let total_num: usize = total.unwrap_or(NonZeroUsize::MAX).into();
         let update_interval = total.map_or(100, |t| usize::from(t) / 100);
         if total_num - update_interval < counter || counter % update_interval == 0 {
@@ -314,14 +321,13 @@ fn make_config<'tcx>(storage: &mut dyn Storage, tcx: TyCtxt<'tcx>, def_id: DefId
 }
 
 fn record_original_indices(body: &Body, storage: &mut dyn Storage) {
-    let mut entry = storage.get_or_default::<Vec<BasicBlock>>(KEY_SWITCH_ORIG_INDICES.to_owned());
+    let mut entry = storage.get_or_default(&KEY_SWITCH_ORIG_INDICES);
     *entry = TerminatorLocationRecorder::default().visit_body(body);
 }
 
 fn make_orig_index_map(body: &Body, storage: &mut dyn Storage) -> HashMap<BasicBlock, BasicBlock> {
     let split_indices = TerminatorLocationRecorder::default().visit_body(body);
-    let mut orig_indices =
-        storage.get_or_default::<Vec<BasicBlock>>(KEY_SWITCH_ORIG_INDICES.to_owned());
+    let mut orig_indices = storage.get_or_default(&KEY_SWITCH_ORIG_INDICES);
     let orig_indices = core::mem::replace(orig_indices.as_mut(), Default::default());
     assert_eq!(
         split_indices.len(),
@@ -339,7 +345,7 @@ pub(crate) fn clear_existing_instrumentation<'tcx>(
     body: &mut Body<'tcx>,
     storage: &mut dyn Storage,
 ) -> bool {
-    let pri_items = storage.get_or_insert_with(KEY_PRI_ITEMS.to_owned(), || make_pri_items(tcx));
+    let pri_items = storage.get_or_insert_with(&KEY_PRI_ITEMS, || make_pri_items(tcx));
     clear_existing_instrumentation_inner(body, &pri_items.all_items)
 }
 
@@ -398,8 +404,25 @@ fn handle_body_pre_blocks<'tcx, C>(call_adder: &mut RuntimeCallAdder<C>)
 where
     C: cr::ForFunctionCalling<'tcx> + cr::ForStorageMarking<'tcx>,
 {
+    if is_thread_entry_closure(call_adder.tcx(), call_adder.body()) {
+        call_adder.notify_thread_start();
+    }
+
     call_adder.enter_func();
 
+    /* The concrete fast path of the shim is only sound for the frames that cannot
+     * read or overwrite symbolic data without passing through their operands. */
+    if crate::toolchain_build::is_concrete_fast_path_enabled()
+        && has_indirect_access(call_adder.tcx(), call_adder.body())
+    {
+        call_adder.mark_frame_indirect();
+    }
+
+    // The tag placed on the function is active during its whole body.
+    if let Some(tag) = tag_attr_of_body(call_adder.tcx(), call_adder.body()) {
+        call_adder.push_tag(&tag);
+    }
+
     rustc_mir_dataflow::impls::always_storage_live_locals(call_adder.body())
         .iter()
         .for_each(|l| match call_adder.body().local_kind(l) {
@@ -411,6 +434,106 @@ where
         });
 }
 
+/// Returns the tag to be active during the body, placed by `#[leaf_attr::tag("...")]`.
+/// # Remarks
+/// The tag is not popped if the function unwinds.
+fn tag_attr_of_body<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> Option<String> {
+    decision::tag_attr_of(tcx, body.source.def_id())
+}
+
+/// Returns whether the body accesses memory through dereferences, statics, or thread locals.
+fn has_indirect_access<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> bool {
+    struct IndirectAccessFinder<'tcx> {
+        tcx: TyCtxt<'tcx>,
+        found: bool,
+    }
+
+    impl<'tcx> Visitor<'tcx> for IndirectAccessFinder<'tcx> {
+        fn visit_place(
+            &mut self,
+            place: &Place<'tcx>,
+            _context: mir::visit::PlaceContext,
+            _location: Location,
+        ) {
+            self.found |= place.is_indirect();
+        }
+
+        fn visit_const_operand(&mut self, constant: &mir::ConstOperand<'tcx>, _location: Location) {
+            self.found |= constant.check_static_ptr(self.tcx).is_some();
+        }
+
+        fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+            if let Rvalue::ThreadLocalRef(..) = rvalue {
+                self.found = true;
+            } else {
+                self.super_rvalue(rvalue, location);
+            }
+        }
+    }
+
+    let mut finder = IndirectAccessFinder { tcx, found: false };
+    finder.visit_body(body);
+    finder.found
+}
+
+fn is_entry_function(tcx: TyCtxt, body: &Body) -> bool {
+    tcx.entry_fn(())
+        .is_some_and(|(id, _)| id == body.source.def_id())
+}
+
+const THREAD_SPAWN_FUNC_NAMES: [&str; 3] = ["spawn", "spawn_scoped", "spawn_unchecked"];
+
+/// Returns whether the body is a closure passed directly to a function spawning
+/// a thread, e.g., `std::thread::spawn(|| ...)` or `scope.spawn(|| ...)`.
+/// # Remarks
+/// The closures passed through variables are not detected, and their threads
+/// are only distinguished by the runtime at their first call.
+fn is_thread_entry_closure<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> bool {
+    use rustc_hir::{ExprKind, Node};
+
+    let def_id = body.source.def_id();
+    if !matches!(body.source.instance, mir_ty::InstanceKind::Item(..))
+        || !tcx.is_closure_like(def_id)
+    {
+        return false;
+    }
+    let Some(local_id) = def_id.as_local() else {
+        return false;
+    };
+
+    let Node::Expr(call) = tcx.parent_hir_node(tcx.local_def_id_to_hir_id(local_id)) else {
+        return false;
+    };
+    let typeck = tcx.typeck(local_id);
+    let callee = match call.kind {
+        ExprKind::Call(func, _) => match &func.kind {
+            ExprKind::Path(qpath) => typeck.qpath_res(qpath, func.hir_id).opt_def_id(),
+            _ => None,
+        },
+        ExprKind::MethodCall(..) => typeck.type_dependent_def_id(call.hir_id),
+        _ => None,
+    };
+    callee.is_some_and(|callee| {
+        tcx.crate_name(callee.krate).as_str() == "std"
+            && THREAD_SPAWN_FUNC_NAMES.contains(&tcx.item_name(callee).as_str())
+            && tcx.def_path_str(callee).starts_with("std::thread::")
+    })
+}
+
+/// Returns whether the body is the entry function and returns `Result<(), E>`.
+/// For such functions, the exit code is decided by `Termination::report` on the
+/// returned value after the runtime is shut down, so it is reported beforehand.
+fn is_termination_reported<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> bool {
+    if !is_entry_function(tcx, body) {
+        return false;
+    }
+
+    let mir_ty::TyKind::Adt(def, args) = body.return_ty().kind() else {
+        return false;
+    };
+    tcx.is_diagnostic_item(rustc_span::sym::Result, def.did()) && args.type_at(0).is_unit()
+}
+
 fn handle_entry_function_pre<'tcx, C>(call_adder: &mut RuntimeCallAdder<C>, body: &Body<'tcx>)
 where
     C: cr::Basic<'tcx>,
@@ -694,6 +817,11 @@ where
     }
 
     fn visit_return(&mut self) {
+        if is_termination_reported(self.call_adder.tcx(), self.call_adder.body()) {
+            let result = self.call_adder.reference_place(&Place::return_place());
+            self.call_adder.in_entry_fn().report_termination(result);
+        }
+
         rustc_mir_dataflow::impls::always_storage_live_locals(self.call_adder.body())
             .iter()
             .for_each(|l| {
@@ -705,6 +833,10 @@ where
                     .mark_dead(|call_adder| call_adder.reference_place(&l.into()));
             });
 
+        if tag_attr_of_body(self.call_adder.tcx(), self.call_adder.body()).is_some() {
+            self.call_adder.before().pop_tag();
+        }
+
         self.call_adder.return_from_func();
     }
 
@@ -716,11 +848,12 @@ where
         &mut self,
         place: &Place<'tcx>,
         _target: &BasicBlock,
-        _unwind: &UnwindAction,
+        unwind: &UnwindAction,
         _replace: &bool,
     ) {
         let mut call_adder = self.call_adder.before();
         call_adder.before_call_drop(place);
+        call_adder.after_call_drop_on_unwind(unwind);
 
         let mut call_adder = call_adder.after();
         call_adder.after_call_drop();
@@ -732,7 +865,7 @@ where
         args: &[Spanned<Operand<'tcx>>],
         destination: &Place<'tcx>,
         target: &Option<BasicBlock>,
-        _unwind: &UnwindAction,
+        unwind: &UnwindAction,
         _call_source: &mir::CallSource,
         fn_span: Span,
     ) {
@@ -753,6 +886,7 @@ where
             args,
             destination,
             target,
+            unwind,
             fn_span,
         };
 
@@ -771,6 +905,12 @@ where
             {
                 self.instrument_drop_in_place_call(params)
             }
+            Some(def_id) if target.is_none() && tcx.is_in_panicking_module(def_id) => {
+                self.instrument_panic_call(params)
+            }
+            Some(def_id) if let Some(func) = decision::allocator_func_of(tcx, def_id) => {
+                self.instrument_allocator_call(func, params)
+            }
             _ => self.instrument_regular_call(params),
         }
     }
@@ -796,8 +936,12 @@ where
         _target: &BasicBlock,
         _unwind: &UnwindAction,
     ) {
-        let cond_ref = self.call_adder.reference_operand(cond);
-        self.call_adder.check_assert(cond_ref, *expected, msg);
+        let mut call_adder = self.call_adder.before();
+        let cond_ref = call_adder.reference_operand(cond);
+        call_adder.check_assert(cond_ref, *expected, msg);
+
+        let mut call_adder = call_adder.after();
+        call_adder.check_assert_passed();
     }
 
     fn visit_yield(
@@ -833,6 +977,7 @@ struct CallParams<'a, 'tcx> {
     args: &'a [Spanned<Operand<'tcx>>],
     destination: &'a Place<'tcx>,
     target: &'a Option<BasicBlock>,
+    unwind: &'a UnwindAction,
     fn_span: Span,
 }
 
@@ -889,6 +1034,9 @@ where
                     is_volatile,
                 );
             }
+            Simd(kind) => {
+                self.instrument_simd_intrinsic_call(def, params, kind);
+            }
             NoOp => {
                 self.instrument_noop_intrinsic_call(params);
             }
@@ -1073,6 +1221,121 @@ where
         }
     }
 
+    fn instrument_simd_intrinsic_call(
+        &mut self,
+        def: IntrinsicDef,
+        params: CallParams<'_, 'tcx>,
+        kind: decision::SimdIntrinsicKind,
+    ) {
+        use decision::SimdIntrinsicKind::*;
+
+        let tcx = self.call_adder.tcx();
+        let lanes = params
+            .args
+            .iter()
+            .map(|arg| self.simd_lanes_of(&arg.node))
+            .collect::<Option<Vec<_>>>();
+        let is_supported = match kind {
+            // Min and max are only supported for integers.
+            Reduce(BinaryOp::MIN | BinaryOp::MAX) => params
+                .destination
+                .ty(self.call_adder.body(), tcx)
+                .ty
+                .is_integral(),
+            _ => true,
+        };
+        let Some(lanes) = lanes.filter(|_| is_supported) else {
+            log_info!(
+                target: TAG_INSTR,
+                "SIMD intrinsic call to {:?} observed with unsupported operands.",
+                def.name
+            );
+            return self.instrument_unsupported_call(params);
+        };
+
+        let rules = &self.call_adder.config().assignment_filter;
+        let filter = match kind {
+            BinOp(..) => rules.intrinsic_binary_op,
+            UnOp(..) => rules.intrinsic_unary_op,
+            Reduce(..) => rules.intrinsic_misc_op,
+        };
+
+        match filter {
+            Some(include_info) => {
+                let mut call_adder = self.call_adder.before();
+                let dest_ref = call_adder.reference_place(params.destination);
+
+                if include_info {
+                    let mut lane_refs = Vec::with_capacity(lanes.len());
+                    for vector in lanes.iter() {
+                        lane_refs.push(
+                            vector
+                                .iter()
+                                .map(|lane| call_adder.reference_operand(lane))
+                                .collect::<Vec<_>>(),
+                        );
+                    }
+
+                    let mut call_adder = call_adder.assign(self.assignment_id.unwrap(), dest_ref);
+                    match kind {
+                        BinOp(operator) => {
+                            call_adder.simd_binary_op(operator, &lane_refs[0], &lane_refs[1])
+                        }
+                        UnOp(operator) => call_adder.simd_unary_op(operator, &lane_refs[0]),
+                        Reduce(operator) => call_adder.simd_reduce(operator, &lane_refs[0]),
+                    }
+                } else {
+                    call_adder
+                        .assign(self.assignment_id.unwrap(), dest_ref)
+                        .by_some();
+                }
+            }
+            None => {
+                // Filter out completely
+            }
+        }
+    }
+
+    /// Returns the operands corresponding to the lanes of the SIMD vector.
+    /// The lanes are accessed by constant indices into the array field of the
+    /// vector, thus the other layouts are not supported.
+    fn simd_lanes_of(&self, vector: &Operand<'tcx>) -> Option<Vec<Operand<'tcx>>> {
+        let tcx = self.call_adder.tcx();
+        let place = vector.place()?;
+        let ty = place.ty(self.call_adder.body(), tcx).ty;
+        let mir_ty::TyKind::Adt(def, args) = ty.kind() else {
+            return None;
+        };
+        if !def.repr().simd() {
+            return None;
+        }
+        let [field] = def.non_enum_variant().fields.raw.as_slice() else {
+            return None;
+        };
+        let array_ty = field.ty(tcx, args);
+        let mir_ty::TyKind::Array(_, len) = array_ty.kind() else {
+            return None;
+        };
+        let len = len.try_to_target_usize(tcx)?;
+        Some(
+            (0..len)
+                .map(|i| {
+                    Operand::Copy(place.project_deeper(
+                        &[
+                            ProjectionElem::Field(FieldIdx::ZERO, array_ty),
+                            ProjectionElem::ConstantIndex {
+                                offset: i,
+                                min_length: len,
+                                from_end: false,
+                            },
+                        ],
+                        tcx,
+                    ))
+                })
+                .collect(),
+        )
+    }
+
     fn instrument_llvm_intrinsic_call(&mut self, params: CallParams<'_, 'tcx>) {
         // Currently, we do not support for LLVM intrinsics.
         self.instrument_unsupported_call(params);
@@ -1085,12 +1348,14 @@ where
             args,
             destination: _,
             target,
+            unwind,
             fn_span: _,
         }: CallParams<'_, 'tcx>,
     ) {
         let mut call_adder = self.call_adder.before();
         assert_eq!(args.len(), 1);
         call_adder.before_call_drop_in_place(func, &args[0]);
+        call_adder.after_call_drop_on_unwind(unwind);
 
         if target.is_some() {
             let mut call_adder = call_adder.after();
@@ -1098,6 +1363,40 @@ where
         }
     }
 
+    fn instrument_panic_call(&mut self, _params: CallParams<'_, 'tcx>) {
+        /* NOTE: The call itself is not instrumented. It never returns, and the
+         * control may continue in the caller's cleanup blocks or a `catch_unwind`
+         * after unwinding, which the call control must not be waiting for. */
+        self.call_adder.before().check_panic();
+    }
+
+    fn instrument_allocator_call(
+        &mut self,
+        func: decision::AllocatorFunc,
+        params: CallParams<'_, 'tcx>,
+    ) {
+        use decision::AllocatorFunc::*;
+        let (destination, args) = (params.destination, params.args);
+        let new_size = match func {
+            Alloc => Some(self.call_adder.before().keep_before_call(&args[0].node)),
+            Realloc => {
+                let mut call_adder = self.call_adder.before();
+                call_adder.mark_deallocated(&args[0].node);
+                Some(call_adder.keep_before_call(&args[3].node))
+            }
+            Dealloc => {
+                self.call_adder.before().mark_deallocated(&args[0].node);
+                None
+            }
+        };
+
+        self.instrument_regular_call(params);
+
+        if let Some(size) = new_size {
+            self.call_adder.after().mark_allocated(destination, size);
+        }
+    }
+
     fn instrument_regular_call(&mut self, params: CallParams<'_, 'tcx>) {
         self.instrument_call_general(params, false);
     }
@@ -1118,12 +1417,31 @@ where
             args,
             destination,
             target,
+            unwind,
             fn_span: _,
         }: CallParams<'_, 'tcx>,
         no_definition: bool,
     ) {
+        /* Calls to some well-known functions are marked, so that the runtime
+         * can summarize them or tell the panics they catch.
+         * The instrumented ones are observed directly. */
+        let tcx = self.call_adder.tcx();
+        let call_tag = func
+            .const_fn_def()
+            .filter(|(def_id, _)| {
+                !decision::is_instrumented_item(tcx, *def_id, self.call_adder.storage())
+            })
+            .and_then(|(def_id, args)| {
+                decision::summary_tag_of(tcx, def_id, args)
+                    .or_else(|| decision::catch_tag_of(tcx, def_id))
+            });
+
         let mut call_adder = self.call_adder.before();
 
+        if let Some(tag) = &call_tag {
+            call_adder.push_tag(tag);
+            call_adder.pop_tag_on_unwind(unwind);
+        }
         call_adder.before_call_func(func, args, no_definition);
 
         if target.is_some() {
@@ -1131,6 +1449,9 @@ where
             let dest_ref = call_adder.reference_place(destination);
             let mut call_adder = call_adder.assign(self.assignment_id.unwrap(), dest_ref);
             call_adder.after_call_func();
+            if call_tag.is_some() {
+                call_adder.pop_tag();
+            }
         } else {
             // This branch is only triggered by hitting a divergent function:
             // https://doc.rust-lang.org/rust-by-example/fn/diverging.html
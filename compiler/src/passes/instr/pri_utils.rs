@@ -74,7 +74,7 @@ pub(crate) mod sym {
 
         common::pri::pass_func_names_to!(symbols_in_pri, all_comma_separated);
 
-        pub(crate) const ALL_MAINS: [LeafSymbol; 141] =
+        pub(crate) const ALL_MAINS: [LeafSymbol; 167] =
             common::pri::pass_func_names_to!(bracket, all_comma_separated);
 
         pub(crate) mod intrinsics {
@@ -116,6 +116,17 @@ pub(crate) mod sym {
                 intrinsic_assign_funnel_shr,
                 intrinsic_assign_select_unpredictable,
                 intrinsic_assign_carrying_mul_add,
+                intrinsic_assign_sqrt,
+                intrinsic_assign_fabs,
+                intrinsic_assign_powi,
+                intrinsic_assign_minimum,
+                intrinsic_assign_maximum,
+                intrinsic_assign_minimum_number,
+                intrinsic_assign_maximum_number,
+
+                intrinsic_assign_simd_binary_op,
+                intrinsic_assign_simd_unary_op,
+                intrinsic_assign_simd_reduce,
 
                 intrinsic_atomic_load,
                 intrinsic_atomic_store,
@@ -221,7 +232,7 @@ pub(crate) mod sym {
                 f32_to_bits,
                 f64_to_bits,
 
-                place_with_address_typed,
+                place_with_metadata_typed,
                 type_id_of,
                 size_of,
 
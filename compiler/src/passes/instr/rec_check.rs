@@ -26,8 +26,7 @@ impl CompilationPass for InstrumentationRecursionChecker {
         tcx: rustc_middle::ty::TyCtxt,
         storage: &mut dyn Storage,
     ) {
-        let pri_items =
-            storage.get_or_insert_with(KEY_PRI_ITEMS.to_owned(), || super::make_pri_items(tcx));
+        let pri_items = storage.get_or_insert_with(&KEY_PRI_ITEMS, || super::make_pri_items(tcx));
 
         let all_available_instances = tcx
             .collect_and_partition_mono_items(())
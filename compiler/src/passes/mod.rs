@@ -5,13 +5,17 @@ pub(crate) mod logger;
 mod md_types;
 mod noop;
 mod p_map_exp;
+#[cfg(feature = "plugins")]
+mod plugins;
 mod program_dep;
 mod runtime_adder;
 pub(crate) mod type_info;
 
 use common::log_debug;
 use std::any::Any;
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::marker::PhantomData;
 use std::sync::{Arc, Mutex};
 
 use rustc_ast as ast;
@@ -29,8 +33,8 @@ use crate::utils::Chain;
 pub(crate) use codegen::{InternalizationRules, MonoItemInternalizer};
 pub(crate) use gated::CompilationPassLogExt as GatedCompilationPassLogExt;
 pub(crate) use instr::{
-    InstrumentationCounter, InstrumentationRecursionChecker, InstrumentationRules, Instrumentor,
-    pri_utils,
+    InstrumentationAuditor, InstrumentationCounter, InstrumentationRecursionChecker,
+    InstrumentationRules, Instrumentor, pri_utils,
 };
 pub(crate) use logger::CompilationPassLogExt as LoggedCompilationPassLogExt;
 pub(crate) use md_types::MdInfoExporter;
@@ -38,12 +42,23 @@ pub(crate) use md_types::MdInfoExporter;
 pub(crate) use noop::NoOpPass;
 pub(crate) use noop::OverrideFlagsForcePass;
 pub(crate) use p_map_exp::ProgramMapExporter;
+#[cfg(feature = "plugins")]
+pub(crate) use plugins::with_plugins;
 pub(crate) use program_dep::ProgramDependenceMapExporter;
 pub(crate) use runtime_adder::RuntimeExternCrateAdder;
 pub(crate) use type_info::TypeInfoExporter;
 
 pub(super) type Callbacks = dyn CallbacksExt + Send;
 
+/// Plugins are only loaded if the `plugins` feature is enabled.
+#[cfg(not(feature = "plugins"))]
+pub(crate) fn with_plugins(
+    callbacks: Box<Callbacks>,
+    _config: &crate::config::PluginsConfig,
+) -> Box<Callbacks> {
+    callbacks
+}
+
 macro_rules! visit_before_after {
     (fn $name:ident $($sig:tt)*) => {
         paste!{
@@ -163,11 +178,74 @@ use implementation::ValueBorrow;
 pub(crate) trait Storage: std::fmt::Debug {
     fn get_raw_or_insert_with<'a>(
         &'a mut self,
-        key: String,
+        key: RawStorageKey,
         default: Box<dyn FnOnce(&mut dyn Storage) -> Box<dyn Any> + '_>,
     ) -> ValueBorrow<'a>;
 
-    fn get_raw_mut<'a>(&'a mut self, key: &String) -> Option<ValueBorrow<'a>>;
+    fn get_raw_mut<'a>(&'a mut self, key: &RawStorageKey) -> Option<ValueBorrow<'a>>;
+}
+
+/// Determines how long a value lives in [Storage].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum StorageScope {
+    /// The value lives throughout the compilation session.
+    Session,
+    /// The value is dropped once all passes are done with the current MIR body.
+    /// Bodies transformed in a nested fashion (e.g., through queries) observe
+    /// their own values.
+    Body,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct RawStorageKey {
+    pub name: Cow<'static, str>,
+    pub scope: StorageScope,
+}
+
+impl core::fmt::Display for RawStorageKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}@{:?}", self.name, self.scope)
+    }
+}
+
+/// A key to a value of type `V` in [Storage].
+/// Passes are expected to define their keys as constants next to their
+/// implementation, so the type of the shared value is checked at compile time.
+pub(crate) struct StorageKey<V> {
+    raw: RawStorageKey,
+    _phantom: PhantomData<fn() -> V>,
+}
+
+impl<V> StorageKey<V> {
+    pub(crate) const fn new(name: &'static str) -> Self {
+        Self::scoped(name, StorageScope::Session)
+    }
+
+    pub(crate) const fn scoped(name: &'static str, scope: StorageScope) -> Self {
+        Self {
+            raw: RawStorageKey {
+                name: Cow::Borrowed(name),
+                scope,
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a key with a name that is not known statically, e.g., based on a type name.
+    pub(crate) fn dynamic(name: String, scope: StorageScope) -> Self {
+        Self {
+            raw: RawStorageKey {
+                name: Cow::Owned(name),
+                scope,
+            },
+            _phantom: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn raw(&self) -> &RawStorageKey {
+        &self.raw
+    }
 }
 
 pub(crate) trait StorageExt {
@@ -179,7 +257,7 @@ pub(crate) trait StorageExt {
 
     fn get_or_insert_with<'a, V: Any>(
         &'a mut self,
-        key: String,
+        key: &StorageKey<V>,
         default: impl FnOnce() -> V + 'a,
     ) -> Self::MutAccessor<'a, V> {
         self.get_or_insert_with_acc(key, |_| default())
@@ -187,15 +265,18 @@ pub(crate) trait StorageExt {
 
     fn get_or_insert_with_acc<'a, V: Any>(
         &'a mut self,
-        key: String,
+        key: &StorageKey<V>,
         default: impl FnOnce(&mut dyn Storage) -> V + 'a,
     ) -> Self::MutAccessor<'a, V>;
 
-    fn get_or_default<'a, V: Default + Any>(&'a mut self, key: String) -> Self::MutAccessor<'a, V> {
+    fn get_or_default<'a, V: Default + Any>(
+        &'a mut self,
+        key: &StorageKey<V>,
+    ) -> Self::MutAccessor<'a, V> {
         self.get_or_insert_with(key, V::default)
     }
 
-    fn get_mut<'a, V: Any>(&'a mut self, key: &String) -> Option<Self::MutAccessor<'a, V>>;
+    fn get_mut<'a, V: Any>(&'a mut self, key: &StorageKey<V>) -> Option<Self::MutAccessor<'a, V>>;
 }
 
 mod implementation {
@@ -458,6 +539,7 @@ mod implementation {
              * something from arena. So, we have to clone the body. */
             let mut body = ORIGINAL_OPTIMIZED_MIR.get()(tcx, id).clone();
             let mut storage = global::get_storage();
+            storage.enter_body_scope();
             /* NOTE: Ideally this should not happen.
              * However, it is observed that in some cases (presumably because of inlining),
              * there are existing calls to our instrumentation functions, and they are
@@ -468,6 +550,7 @@ mod implementation {
             T::visit_mir_body_before(tcx, &body, &mut storage);
             T::transform_mir_body(tcx, &mut body, &mut storage);
             T::visit_mir_body_after(tcx, &body, &mut storage);
+            storage.exit_body_scope();
             tcx.arena.alloc(body)
         }
 
@@ -479,10 +562,12 @@ mod implementation {
              * something from arena. So, we have to clone the body. */
             let mut body = ORIGINAL_EXTERN_OPTIMIZED_MIR.get()(tcx, id).clone();
             let mut storage = global::get_storage();
+            storage.enter_body_scope();
             instr::clear_existing_instrumentation(tcx, &mut body, &mut storage);
             T::visit_mir_body_before(tcx, &body, &mut storage);
             T::transform_mir_body(tcx, &mut body, &mut storage);
             T::visit_mir_body_after(tcx, &body, &mut storage);
+            storage.exit_body_scope();
             tcx.arena.alloc(body)
         }
 
@@ -494,9 +579,11 @@ mod implementation {
              * something from arena. So, we have to clone the body. */
             let mut body = ORIGINAL_MIR_SHIMS.get()(tcx, kind);
             let mut storage = global::get_storage();
+            storage.enter_body_scope();
             T::visit_mir_body_before(tcx, &body, &mut storage);
             T::transform_mir_body(tcx, &mut body, &mut storage);
             T::visit_mir_body_after(tcx, &body, &mut storage);
+            storage.exit_body_scope();
             body
         }
 
@@ -795,29 +882,77 @@ mod implementation {
          *   downcast type is not easy to implement with `RefCell`.
          */
 
+        /* NOTE: How are scopes implemented?
+         * Each scope is a stack of frames. Session-scoped values always reside
+         * in the single bottom frame. Every time the passes start working on
+         * a MIR body, a new body frame is pushed which gets dropped (along with
+         * its values) when they are done. As body transformations may get nested
+         * (e.g., a pass queries another body), keys are resolved in the top frame
+         * at the time of access.
+         */
+
         pub(super) type StorageValueImpl = Box<dyn Any>;
-        pub(super) type StorageImpl = HashMap<String, StorageValueImpl>;
+        type FramedKey = (usize, RawStorageKey);
+
+        #[derive(Debug, Default)]
+        pub(super) struct StorageImpl {
+            values: HashMap<FramedKey, StorageValueImpl>,
+            body_depth: usize,
+        }
+
+        impl StorageImpl {
+            pub(super) fn clear(&mut self) {
+                self.values.clear();
+                self.body_depth = 0;
+            }
+
+            fn frame_key(&self, key: &RawStorageKey) -> FramedKey {
+                let frame = match key.scope {
+                    StorageScope::Session => 0,
+                    StorageScope::Body => self.body_depth,
+                };
+                (frame, key.clone())
+            }
+        }
 
         pub(super) struct GlobalStorage(pub RRef<StorageImpl>);
 
+        impl GlobalStorage {
+            pub(super) fn enter_body_scope(&mut self) {
+                self.0.borrow_mut().body_depth += 1;
+            }
+
+            pub(super) fn exit_body_scope(&mut self) {
+                let mut storage = self.0.borrow_mut();
+                let depth = storage.body_depth;
+                debug_assert!(depth > 0, "Exiting a body scope that is not entered.");
+                storage
+                    .values
+                    .retain(|(frame, key), _| key.scope != StorageScope::Body || *frame != depth);
+                storage.body_depth = depth - 1;
+            }
+        }
+
         impl Storage for GlobalStorage {
             fn get_raw_or_insert_with<'a>(
                 &'a mut self,
-                key: String,
+                key: RawStorageKey,
                 default: Box<dyn FnOnce(&mut dyn Storage) -> Box<dyn Any> + '_>,
             ) -> ValueBorrow<'a> {
                 /* Although the signature requires mutably borrowing the storage,
                  * for the global storage, we use interior mutability. */
-                let value = self.0.borrow_mut().remove(&key);
+                let key = self.0.borrow().frame_key(&key);
+                let value = self.0.borrow_mut().values.remove(&key);
                 let value = value.unwrap_or_else(|| default(self));
 
                 ValueBorrow::new(self, key, value)
             }
 
-            fn get_raw_mut<'a>(&'a mut self, key: &String) -> Option<ValueBorrow<'a>> {
+            fn get_raw_mut<'a>(&'a mut self, key: &RawStorageKey) -> Option<ValueBorrow<'a>> {
                 log_debug!("Getting mutable reference with key: {}", key);
-                let value = self.0.borrow_mut().remove(key)?;
-                Some(ValueBorrow::new(self, key.clone(), value))
+                let key = self.0.borrow().frame_key(key);
+                let value = self.0.borrow_mut().values.remove(&key)?;
+                Some(ValueBorrow::new(self, key, value))
             }
         }
 
@@ -829,13 +964,13 @@ mod implementation {
 
         pub(crate) struct ValueBorrow<'a> {
             storage: &'a GlobalStorage,
-            key: Option<String>,
+            key: Option<FramedKey>,
             value: Option<StorageValueImpl>,
             leaked: bool,
         }
 
         impl<'a> ValueBorrow<'a> {
-            fn new(storage: &'a GlobalStorage, key: String, value: StorageValueImpl) -> Self {
+            fn new(storage: &'a GlobalStorage, key: FramedKey, value: StorageValueImpl) -> Self {
                 ValueBorrow {
                     storage,
                     key: Some(key),
@@ -858,6 +993,7 @@ mod implementation {
                 self.storage
                     .0
                     .borrow_mut()
+                    .values
                     .insert(self.key.take().unwrap(), self.value.take().unwrap());
             }
         }
@@ -886,15 +1022,18 @@ mod implementation {
 
             fn get_or_insert_with_acc<'a, V: Any>(
                 &'a mut self,
-                key: String,
+                key: &StorageKey<V>,
                 default: impl FnOnce(&mut dyn Storage) -> V + 'a,
             ) -> Self::MutAccessor<'a, V> {
-                self.get_raw_or_insert_with(key.clone(), Box::new(|s| Box::new(default(s))));
-                self.get_mut(&key).unwrap()
+                self.get_raw_or_insert_with(key.raw().clone(), Box::new(|s| Box::new(default(s))));
+                self.get_mut(key).unwrap()
             }
 
-            fn get_mut<'a, V: Any>(&'a mut self, key: &String) -> Option<Self::MutAccessor<'a, V>> {
-                self.get_raw_mut(key)
+            fn get_mut<'a, V: Any>(
+                &'a mut self,
+                key: &StorageKey<V>,
+            ) -> Option<Self::MutAccessor<'a, V>> {
+                self.get_raw_mut(key.raw())
                     .map(|v| DowncastValueBorrow::<'a, V>(v, Default::default()))
             }
         }
@@ -907,7 +1046,7 @@ mod implementation {
         impl<V: 'static> DowncastValueBorrow<'_, V> {
             pub(crate) fn leak(mut self) -> ManualBorrow<V> {
                 ManualBorrow {
-                    key: self.0.key.take().unwrap(),
+                    key: self.0.key.take().unwrap().1,
                     value: self.0.leak().downcast::<V>().unwrap(),
                 }
             }
@@ -934,7 +1073,7 @@ mod implementation {
         }
 
         pub(crate) struct ManualBorrow<V> {
-            key: String,
+            key: RawStorageKey,
             value: Box<V>,
         }
 
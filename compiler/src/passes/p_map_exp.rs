@@ -3,29 +3,47 @@ use rustc_middle::{
     ty::{InstanceKind, TyCtxt},
 };
 
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
 use common::{
     directed::{
         BasicBlockIndex, CallDebugInfo, CallGraphEdgeDestination, CfgConstraint,
-        CfgEdgeDestination, ControlFlowGraph, ProgramMap,
+        CfgEdgeDestination, ControlFlowGraph, InstanceKindId, ProgramMap, SourceLine,
     },
-    log_info,
+    log_info, log_warn,
+    types::DefId,
 };
 
-use super::{CompilationPass, OverrideFlags, Storage, StorageExt};
-use crate::utils::{
-    file::TyCtxtFileExt,
-    mir::{InstanceKindExt, TyCtxtExt},
+use super::{CompilationPass, OverrideFlags, Storage, StorageExt, StorageKey};
+use crate::{
+    config::{ProgramMapPassConfig, rules::GlobPattern},
+    utils::{
+        file::TyCtxtFileExt,
+        mir::{InstanceKindExt, TyCtxtExt},
+    },
 };
 
 type Calls = Vec<CallGraphEdgeDestination>;
 type ReturnPoints = Vec<BasicBlockIndex>;
 
 #[derive(Default)]
-pub(crate) struct ProgramMapExporter;
+pub(crate) struct ProgramMapExporter {
+    out_dir: Option<PathBuf>,
+    config: ProgramMapPassConfig,
+}
 
-const KEY_MAP: &str = "program_map";
+impl ProgramMapExporter {
+    pub(crate) fn new(out_dir: Option<PathBuf>, config: ProgramMapPassConfig) -> Self {
+        Self { out_dir, config }
+    }
+}
 
-const FILE_OUTPUT: &str = "program_map.json";
+const KEY_MAP: StorageKey<ProgramMap> = StorageKey::new("program_map");
+
+const FILE_CALL_GRAPH_DOT: &str = "call_graph.dot";
 
 impl CompilationPass for ProgramMapExporter {
     fn override_flags() -> super::OverrideFlags {
@@ -40,14 +58,14 @@ impl CompilationPass for ProgramMapExporter {
         body: &Body<'tcx>,
         storage: &mut dyn Storage,
     ) {
-        let mut p_map = storage.get_or_default::<ProgramMap>(KEY_MAP.to_owned());
+        let mut p_map = storage.get_or_default(&KEY_MAP);
         visit_and_add(&mut p_map, tcx, body);
     }
 
     fn visit_tcx_at_codegen_after(&mut self, tcx: TyCtxt, storage: &mut dyn Storage) {
         log_info!("Exporting program map");
 
-        let mut p_map = storage.get_or_default::<ProgramMap>(KEY_MAP.to_owned());
+        let mut p_map = storage.get_or_default(&KEY_MAP);
 
         tcx.collect_and_partition_mono_items(())
             .codegen_units
@@ -67,9 +85,51 @@ impl CompilationPass for ProgramMapExporter {
                 .map(|(def_id, _)| InstanceKind::Item(*def_id).to_plain_id()),
         );
 
+        let out_dir = self.out_dir.clone().unwrap_or_else(|| tcx.output_dir());
         p_map
-            .write(tcx.output_dir().join(FILE_OUTPUT))
+            .write(out_dir.join(ProgramMap::FILENAME))
             .expect("Failed to write program map");
+
+        self.write_dots(&p_map, &out_dir);
+    }
+}
+
+impl ProgramMapExporter {
+    fn write_dots(&self, p_map: &ProgramMap, out_dir: &std::path::Path) {
+        let write = |file_name: String, content: String| {
+            let path = out_dir.join(file_name);
+            match std::fs::write(&path, content) {
+                Ok(()) => log_info!("Graph written to: {}", path.display()),
+                Err(e) => log_warn!("Failed to write graph to {}: {e}", path.display()),
+            }
+        };
+        let bodies_matching = |pattern: &GlobPattern| {
+            p_map
+                .debug_info
+                .func_names
+                .iter()
+                .filter(|(_, name)| pattern.matches(name))
+                .map(|(body, _)| *body)
+                .collect::<HashSet<_>>()
+        };
+
+        if self.config.dot {
+            let targets = self.config.dot_towards.as_ref().map(bodies_matching);
+            if targets.as_ref().is_some_and(HashSet::is_empty) {
+                log_warn!("No body matches the target of the call graph");
+            }
+            write(
+                FILE_CALL_GRAPH_DOT.to_owned(),
+                p_map.call_graph_to_dot(targets.as_ref()),
+            );
+        }
+
+        for body in self.config.dot_cfgs.iter().flat_map(bodies_matching) {
+            if let Some(dot) = p_map.cfg_to_dot(&body) {
+                let InstanceKindId(discr, DefId(krate, index)) = body;
+                write(format!("cfg_{krate}_{index}_{discr}.dot"), dot);
+            }
+        }
     }
 }
 
@@ -87,6 +147,39 @@ fn visit_and_add<'tcx>(p_map: &mut ProgramMap, tcx: TyCtxt<'tcx>, body: &Body<'t
         .debug_info
         .func_names
         .insert(key, tcx.def_path_str(body.source.def_id()));
+    p_map
+        .debug_info
+        .block_lines
+        .insert(key, block_lines_of(tcx, body));
+}
+
+fn block_lines_of<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+) -> HashMap<BasicBlockIndex, SourceLine> {
+    let source_map = tcx.sess.source_map();
+    body.basic_blocks
+        .iter_enumerated()
+        .filter_map(|(index, block)| {
+            // Macro expansions are attributed to their invocations.
+            let span = block.terminator().source_info.span.source_callsite();
+            if span.is_dummy() {
+                return None;
+            }
+            let loc = source_map.lookup_char_pos(span.lo());
+            Some((
+                index.as_u32(),
+                SourceLine {
+                    file: loc
+                        .file
+                        .name
+                        .display(rustc_span::RemapPathScopeComponents::DEBUGINFO)
+                        .to_string(),
+                    line: loc.line as u32,
+                },
+            ))
+        })
+        .collect()
 }
 
 fn visit_body<'tcx>(
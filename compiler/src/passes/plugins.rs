@@ -0,0 +1,166 @@
+//! The loading of plugin passes from dynamic libraries.
+//!
+//! Plugins are additional compiler callbacks (e.g., extra metadata exporters)
+//! that run after the built-in passes on the primary package. A plugin is a
+//! `dylib` crate built with the same toolchain as leafc (using `rustc_private`),
+//! that exports its callbacks through the following function, similar to how
+//! rustc loads codegen backends:
+//!
+//! ```ignore
+//! #[unsafe(no_mangle)]
+//! pub fn __leaf_plugin_callbacks() -> Box<dyn rustc_driver::Callbacks + Send> {
+//!     Box::new(MyExporter::default())
+//! }
+//! ```
+//!
+//! Each plugin is registered under a unique name with the path to its library
+//! through the configuration, e.g.,
+//! `passes.plugins.my_exporter = { path = "/path/to/libmy_exporter.so" }`,
+//! and can be disabled by its name, e.g.,
+//! `passes.plugins.my_exporter.enabled = false`.
+//!
+//! The plugins are configured before the built-in passes. Thus, the query
+//! overrides set by the plugins are chained by the built-in passes.
+
+use std::path::Path;
+
+use rustc_ast as ast;
+use rustc_driver::{self as driver, Compilation};
+use rustc_interface::interface;
+use rustc_middle::ty::TyCtxt;
+
+use common::log_info;
+
+use crate::config::{LeafCompilerConfig, PluginsConfig};
+
+use super::{Callbacks, CallbacksExt};
+
+const ENTRY_SYMBOL: &[u8] = b"__leaf_plugin_callbacks";
+
+type PluginCallbacks = dyn driver::Callbacks + Send;
+type PluginEntry = fn() -> Box<PluginCallbacks>;
+
+/// Appends the callbacks of the enabled plugins to the given callbacks.
+pub(crate) fn with_plugins(callbacks: Box<Callbacks>, config: &PluginsConfig) -> Box<Callbacks> {
+    let plugins = config
+        .iter()
+        .filter(|(name, plugin)| {
+            if !plugin.enabled {
+                log_info!("Plugin pass `{}` is disabled.", name);
+            }
+            plugin.enabled
+        })
+        .map(|(name, plugin)| load_plugin(name, &plugin.path))
+        .collect::<Vec<_>>();
+
+    if plugins.is_empty() {
+        callbacks
+    } else {
+        Box::new(WithPlugins {
+            passes: callbacks,
+            plugins,
+        })
+    }
+}
+
+fn load_plugin(name: &str, path: &Path) -> Box<PluginCallbacks> {
+    // SAFETY: Plugins are trusted code built with the same toolchain.
+    let library = unsafe { libloading::Library::new(path) }.unwrap_or_else(|e| {
+        panic!(
+            "Failed to load plugin `{}` from `{}`: {}",
+            name,
+            path.display(),
+            e
+        )
+    });
+    // SAFETY: The entry symbol is expected to have the documented signature.
+    let entry = unsafe { library.get::<PluginEntry>(ENTRY_SYMBOL) }
+        .map(|entry| *entry)
+        .unwrap_or_else(|e| {
+            panic!(
+                "Plugin `{}` does not export its entry function `{}`: {}",
+                name,
+                String::from_utf8_lossy(ENTRY_SYMBOL),
+                e
+            )
+        });
+    // The callbacks are used throughout the compilation session.
+    core::mem::forget(library);
+
+    log_info!(
+        "Plugin pass `{}` is loaded from `{}`.",
+        name,
+        path.display()
+    );
+    entry()
+}
+
+/// The callbacks of the built-in passes followed by the plugins.
+/// The compilation stops as soon as any of them requests it.
+struct WithPlugins {
+    passes: Box<Callbacks>,
+    plugins: Vec<Box<PluginCallbacks>>,
+}
+
+impl WithPlugins {
+    fn run_in_order(
+        &mut self,
+        mut callback: impl FnMut(&mut dyn driver::Callbacks) -> Compilation,
+    ) -> Compilation {
+        if callback(self.passes.as_mut()) == Compilation::Stop {
+            return Compilation::Stop;
+        }
+        for plugin in self.plugins.iter_mut() {
+            if callback(plugin.as_mut()) == Compilation::Stop {
+                return Compilation::Stop;
+            }
+        }
+        Compilation::Continue
+    }
+}
+
+impl driver::Callbacks for WithPlugins {
+    fn config(&mut self, config: &mut interface::Config) {
+        for plugin in self.plugins.iter_mut() {
+            plugin.config(config);
+        }
+        self.passes.config(config);
+    }
+
+    fn after_crate_root_parsing<'tcx>(
+        &mut self,
+        compiler: &interface::Compiler,
+        krate: &mut ast::Crate,
+    ) -> Compilation {
+        self.run_in_order(|callbacks| callbacks.after_crate_root_parsing(compiler, krate))
+    }
+
+    fn after_expansion<'tcx>(
+        &mut self,
+        compiler: &interface::Compiler,
+        tcx: TyCtxt<'tcx>,
+    ) -> Compilation {
+        self.run_in_order(|callbacks| callbacks.after_expansion(compiler, tcx))
+    }
+
+    fn after_analysis<'tcx>(
+        &mut self,
+        compiler: &interface::Compiler,
+        tcx: TyCtxt<'tcx>,
+    ) -> Compilation {
+        self.run_in_order(|callbacks| callbacks.after_analysis(compiler, tcx))
+    }
+}
+
+impl CallbacksExt for WithPlugins {
+    fn set_leaf_config(&mut self, config: LeafCompilerConfig) {
+        self.passes.set_leaf_config(config);
+    }
+
+    fn add_config_callback(
+        &mut self,
+        callback: Box<dyn FnOnce(&mut interface::Config, &mut LeafCompilerConfig) + Send + 'static>,
+    ) {
+        self.passes.add_config_callback(callback);
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf};
 
 use rustc_abi::VariantIdx;
 use rustc_data_structures::graph::dominators::{Dominators, dominators};
@@ -20,12 +20,20 @@ use crate::{
     utils::{control_dependence::PostDominators, file::TyCtxtFileExt, mir::InstanceKindExt},
 };
 
-use super::{CompilationPass, OverrideFlags, Storage, StorageExt};
+use super::{CompilationPass, OverrideFlags, Storage, StorageExt, StorageKey};
 
 #[derive(Default)]
-pub(crate) struct ProgramDependenceMapExporter;
+pub(crate) struct ProgramDependenceMapExporter {
+    out_dir: Option<PathBuf>,
+}
+
+impl ProgramDependenceMapExporter {
+    pub(crate) fn new(out_dir: Option<PathBuf>) -> Self {
+        Self { out_dir }
+    }
+}
 
-const KEY_MAP: &str = "program_dep";
+const KEY_MAP: StorageKey<PlainProgramDependenceMap> = StorageKey::new("program_dep");
 
 impl CompilationPass for ProgramDependenceMapExporter {
     fn override_flags() -> super::OverrideFlags {
@@ -40,14 +48,14 @@ impl CompilationPass for ProgramDependenceMapExporter {
         body: &Body<'tcx>,
         storage: &mut dyn Storage,
     ) {
-        let mut pdm = storage.get_or_default::<PlainProgramDependenceMap>(KEY_MAP.to_owned());
+        let mut pdm = storage.get_or_default(&KEY_MAP);
         visit_and_add(&mut pdm, tcx, body);
     }
 
     fn visit_tcx_at_codegen_after(&mut self, tcx: TyCtxt, storage: &mut dyn Storage) {
         log_info!("Exporting program dependence");
 
-        let mut pdm = storage.get_or_default::<PlainProgramDependenceMap>(KEY_MAP.to_owned());
+        let mut pdm = storage.get_or_default(&KEY_MAP);
 
         tcx.collect_and_partition_mono_items(())
             .codegen_units
@@ -62,8 +70,11 @@ impl CompilationPass for ProgramDependenceMapExporter {
                 visit_and_add(&mut pdm, tcx, tcx.instance_mir(instance_kind));
             });
 
-        common::program_dep::rw::write_program_dep_map(&pdm, tcx.output_dir())
-            .expect("Failed to write program dependence map");
+        common::program_dep::rw::write_program_dep_map(
+            &pdm,
+            self.out_dir.clone().unwrap_or_else(|| tcx.output_dir()),
+        )
+        .expect("Failed to write program dependence map");
     }
 }
 
@@ -12,6 +12,7 @@ use rustc_type_ir::inherent::AdtDef;
 
 use std::collections::HashMap;
 use std::env::{self};
+use std::path::PathBuf;
 
 use common::{
     log_debug, log_info, log_warn,
@@ -21,12 +22,20 @@ use common::{
 use crate::passes::StorageExt;
 use crate::utils::file::TyCtxtFileExt;
 
-use super::{CompilationPass, Storage};
+use super::{CompilationPass, Storage, StorageKey};
 
 const TAG_TYPE_EXPORT: &str = "type_export";
 
 #[derive(Default)]
-pub(crate) struct TypeInfoExporter;
+pub(crate) struct TypeInfoExporter {
+    out_dir: Option<PathBuf>,
+}
+
+impl TypeInfoExporter {
+    pub(crate) fn new(out_dir: Option<PathBuf>) -> Self {
+        Self { out_dir }
+    }
+}
 
 impl CompilationPass for TypeInfoExporter {
     fn override_flags() -> super::OverrideFlags {
@@ -45,9 +54,16 @@ impl CompilationPass for TypeInfoExporter {
         let out_dir = tcx.output_dir();
         let is_single_file_program =
             out_dir.as_os_str().is_empty() || !rustc_session::utils::was_invoked_from_cargo();
-        let out_dirs = if is_single_file_program {
+        let is_primary_package = env::var("CARGO_PRIMARY_PACKAGE").is_ok();
+        let out_dirs = if let Some(configured_dir) = self.out_dir.as_deref() {
+            if is_single_file_program || is_primary_package {
+                vec![configured_dir]
+            } else {
+                vec![]
+            }
+        } else if is_single_file_program {
             vec![out_dir.as_path()]
-        } else if env::var("CARGO_PRIMARY_PACKAGE").is_ok() {
+        } else if is_primary_package {
             /* For compiling a single file program, the final type export file is placed in the same directory as the program file.
              * For compiling a project, the final type export file is also placed in the output directory (i.e. "./target/debug/") */
             vec![out_dir.as_path(), out_dir.parent().unwrap()]
@@ -78,7 +94,8 @@ impl CompilationPass for TypeInfoExporter {
     }
 }
 
-const KEY_TYPE_DB_METADATA: &str = "type_db_metadata";
+const KEY_TYPE_DB_METADATA: StorageKey<HashMap<String, MetadataValue>> =
+    StorageKey::new("type_db_metadata");
 
 pub(crate) fn add_metadata_to_types_db(
     storage: &mut dyn Storage,
@@ -86,13 +103,13 @@ pub(crate) fn add_metadata_to_types_db(
     metadata: MetadataValue,
 ) {
     storage
-        .get_or_default::<HashMap<String, MetadataValue>>(KEY_TYPE_DB_METADATA.to_owned())
+        .get_or_default(&KEY_TYPE_DB_METADATA)
         .insert(key, metadata);
 }
 
 fn take_metadata_for_types_db(storage: &mut dyn Storage) -> HashMap<String, MetadataValue> {
     storage
-        .get_or_default::<HashMap<String, MetadataValue>>(KEY_TYPE_DB_METADATA.to_owned())
+        .get_or_default(&KEY_TYPE_DB_METADATA)
         .drain()
         .collect()
 }
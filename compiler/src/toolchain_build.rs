@@ -24,8 +24,13 @@ const ENV_OUT_DIR: &str = "OUT_DIR";
 const ENV_LEAFC: &str = "LEAFC";
 const ENV_TOOLCHAIN_MARKER: &str = concatcp!(PREFIX_LEAF_SCRIPT_CONFIG, "_TOOLCHAIN_MARKER_FILE");
 const ENV_LEAF_WORKSPACE: &str = "LEAF_WORKSPACE";
+const ENV_CONCRETE_FAST_PATH: &str = concatcp!(PREFIX_LEAF_SCRIPT_CONFIG, "_CONCRETE_FAST_PATH");
+const ENV_CONCRETE_FAST_PATH_MARKER: &str =
+    concatcp!(PREFIX_LEAF_SCRIPT_CONFIG, "_CONCRETE_FAST_PATH_MARKER_FILE");
 
 pub const FILE_TOOLCHAIN_MARKER: &str = ".leafc_toolchain";
+/// Present in the toolchains whose shim is built with the concrete fast path.
+const FILE_CONCRETE_FAST_PATH_MARKER: &str = ".leafc_concrete_fast_path";
 
 const PATH_WORKSPACE: &str = env!("WORKSPACE_DIR"); // Set by the build script.
 
@@ -121,6 +126,10 @@ fn set_env_vars(
         .env(ENV_OUT_DIR, out_dir)
         .env(ENV_LEAFC, exe_path)
         .env(ENV_TOOLCHAIN_MARKER, FILE_TOOLCHAIN_MARKER)
+        .env(
+            ENV_CONCRETE_FAST_PATH_MARKER,
+            FILE_CONCRETE_FAST_PATH_MARKER,
+        )
         .env(ENV_RUSTUP_TOOLCHAIN, sysroot)
         .env(ENV_LEAF_WORKSPACE, PATH_WORKSPACE);
 
@@ -161,7 +170,14 @@ fn run_builder(mut cmd: process::Command) -> Result<PathBuf, String> {
 
 fn persist_toolchain(built_toolchain_path: &Path, target_triple: &str) -> Result<PathBuf, String> {
     let toolchains = persistent_toolchains_path();
-    let uid = get_unique_id(built_toolchain_path, target_triple);
+    let mut uid = get_unique_id(built_toolchain_path, target_triple);
+    // The configuration flags do not necessarily change the hash of the libraries.
+    if built_toolchain_path
+        .join(FILE_CONCRETE_FAST_PATH_MARKER)
+        .exists()
+    {
+        uid.push_str("-fast_path");
+    }
     let dest = toolchains.join(&uid);
 
     log_debug!(
@@ -194,9 +210,19 @@ pub(super) fn try_find_compatible_toolchain(provided_sysroot: &Path) -> Option<P
         .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
         .filter(|e| fs::read_dir(e.path()).is_ok_and(|mut d| d.next().is_some()))
         .map(|e| e.path())
+        // The shim is built with the fast path or without it, based on the builder's environment.
+        .filter(|p| {
+            p.join(FILE_CONCRETE_FAST_PATH_MARKER).exists() == is_concrete_fast_path_enabled()
+        })
         .find(|p| is_sysroot_compatible(provided_sysroot, Some(&p)))
 }
 
+/// Whether the concrete fast path is requested for the shim (see the toolchain builder).
+pub(crate) fn is_concrete_fast_path_enabled() -> bool {
+    env::var(ENV_CONCRETE_FAST_PATH)
+        .is_ok_and(|v| matches!(v.to_lowercase().as_str(), "true" | "1" | "yes" | "y" | "on"))
+}
+
 #[tracing::instrument(level = "debug", ret)]
 pub(super) fn is_sysroot_compatible(sysroot: &Path, built_sysroot: Option<&Path>) -> bool {
     // If the original set sysroot has the marker, it is our own sysroot.
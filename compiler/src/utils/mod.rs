@@ -40,6 +40,7 @@ pub(crate) mod mir {
     pub(crate) trait TyCtxtExt<'tcx> {
         fn is_llvm_intrinsic(self, def_id: DefId) -> bool;
         fn module_of(self, def_id: DefId) -> impl Iterator<Item = DisambiguatedDefPathData>;
+        fn is_in_panicking_module(self, def_id: DefId) -> bool;
         fn typing_env_in_body(self, def_id: DefId) -> TypingEnv<'tcx>;
         fn typing_mode_for_body(self, def_id: LocalDefId) -> TypingMode<'tcx>;
         fn pretty_mir(self, body: &Body<'tcx>) -> String;
@@ -69,6 +70,15 @@ pub(crate) mod mir {
                 .take_while(|p| matches!(p.data, DefPathData::TypeNs(_)))
         }
 
+        /// Whether the item belongs to a `panicking` module, e.g., `core::panicking::panic_fmt`.
+        fn is_in_panicking_module(self, def_id: DefId) -> bool {
+            self.module_of(def_id).any(|p| {
+                p.data
+                    .get_opt_name()
+                    .is_some_and(|name| name.as_str() == "panicking")
+            })
+        }
+
         fn typing_env_in_body(self, def_id: DefId) -> TypingEnv<'tcx> {
             TypingEnv::new(self.param_env(def_id), {
                 if let Some(def_id) = def_id.as_local() {
@@ -293,7 +303,7 @@ pub(super) mod file {
     const DIR_DEPS: &str = env!("DEPS_DIR");
 
     pub(crate) fn find_dependency_path<'a>(
-        name: &'static str,
+        name: &str,
         priority_dirs: impl IntoIterator<Item = &'a Path>,
     ) -> PathBuf {
         try_find_dependency_path(name, priority_dirs)
@@ -35,14 +35,16 @@ impl CallHandler for CftCallHandler<'_> {
 
     fn before_call(self, def: CalleeDef, call_site: BasicBlockIndex) {
         self.flow_manager.prepare_for_calling(def);
-        self.recorder
-            .start_call(self.flow_manager.current_func().at_basic_block(call_site));
+        self.recorder.start_call(
+            self.flow_manager.current_func().at_basic_block(call_site),
+            Some(def),
+        );
     }
 
     fn before_call_some(self) {
         self.flow_manager.prepare_for_call();
         self.recorder
-            .start_call(self.flow_manager.current_func().at_basic_block(0));
+            .start_call(self.flow_manager.current_func().at_basic_block(0), None);
     }
 
     fn take_data_before_call(
@@ -53,6 +55,10 @@ impl CallHandler for CftCallHandler<'_> {
     ) {
     }
 
+    fn start_thread(self) {
+        self.flow_manager.start_thread();
+    }
+
     fn enter(self, def: FuncDef) {
         let sanity = self.flow_manager.enter(def);
         self.recorder.finish_call(def, sanity.is_broken());
@@ -68,6 +74,8 @@ impl CallHandler for CftCallHandler<'_> {
 
     fn override_return_value(self, _value: Self::Operand) {}
 
+    fn concretize_return_value(self, _value: Self::Operand) {}
+
     fn ret(self, ret_point: BasicBlockIndex) {
         self.recorder
             .start_return(self.flow_manager.current_func().at_basic_block(ret_point));
@@ -102,4 +110,6 @@ impl DropHandler for CftCallHandler<'_> {
         self.recorder
             .finish_return(token.sanity().is_broken().unwrap());
     }
+
+    fn after_drop_unwind(self) {}
 }
@@ -7,7 +7,7 @@ use leaf_runtime::{
     pri::fluent::backend::{ConstraintHandler, SwitchHandler},
 };
 
-use super::{CftBackend, NullOperand, Recorder};
+use super::{CftBackend, NullOperand, NullPlace, Recorder};
 
 pub(crate) struct CftConstraintHandler<'a> {
     recorder: &'a mut Recorder<SwitchCaseIndex>,
@@ -30,6 +30,7 @@ pub(crate) struct CftSwitchHandler<'a> {
 
 impl<'a> ConstraintHandler for CftConstraintHandler<'a> {
     type Operand = NullOperand;
+    type DiscriminablePlace = NullPlace;
 
     type SwitchHandler = CftSwitchHandler<'a>;
 
@@ -50,6 +51,18 @@ impl<'a> ConstraintHandler for CftConstraintHandler<'a> {
             },
         );
     }
+
+    fn assert_passed(self) {
+        // Already recorded as a decision at the assertion.
+    }
+
+    fn panic(self, _source: &'static str) {
+        // The call is not a branching node.
+    }
+
+    fn terminate(self, _result: Self::DiscriminablePlace, _discr: Constant, _exit_code: u8) {
+        // No branching node exists in the program for the termination.
+    }
 }
 
 impl<'a> SwitchHandler for CftSwitchHandler<'a> {
@@ -4,7 +4,7 @@ use valuable::Valuable;
 
 use leaf_runtime::{
     abs::{
-        BasicBlockLocation, Constant, ConstraintKind, FuncDef, SwitchCaseIndex,
+        BasicBlockLocation, CalleeDef, Constant, ConstraintKind, FuncDef, SwitchCaseIndex,
         backend::{DecisionTraceRecorder, PhasedCallTraceRecorder},
     },
     utils::IntTypeExt,
@@ -26,6 +26,8 @@ struct StackedData {
 
 struct EphemeralData {
     last_ret_point: Option<BasicBlockLocation<FuncDef>>,
+    /// The expected callee of the latest call that has not entered yet.
+    pending_callee: Option<Option<CalleeDef>>,
 }
 
 macro_rules! body_id_to_str {
@@ -40,6 +42,7 @@ impl<C> Default for Recorder<C> {
             stack: Vec::new(),
             ephemeral: EphemeralData {
                 last_ret_point: None,
+                pending_callee: None,
             },
             _phantom: Default::default(),
         }
@@ -80,6 +83,26 @@ impl<C> Recorder<C> {
         }
     }
 
+    #[inline]
+    fn notify_external_call(
+        &self,
+        call_site: BasicBlockLocation<FuncDef>,
+        callee: Option<CalleeDef>,
+    ) {
+        core::hint::cold_path();
+        let callee = callee.map(|c| body_id_to_str!(c.callee_id).to_owned());
+        event!(
+            name: EVENT_EXTERNAL,
+            target: TARGET,
+            parent: self.current_span(),
+            LEVEL,
+            { FIELD_FROM_BODY } = body_id_to_str!(call_site.body.body_id),
+            { FIELD_FROM_BLOCK } = call_site.index,
+            { FIELD_CALLEE } = callee.as_deref(),
+            { FIELD_TRANSFER_KIND } = TRANSFER_KIND_CALL,
+        );
+    }
+
     fn ensure_in_current_body(&self, mut location: BasicBlockLocation) -> BasicBlockLocation {
         let current = if let Some(current) = self.stack.last().map(|s| s.current) {
             current.body_id
@@ -108,6 +131,7 @@ const SPAN_FUNCTION: &str = "func";
 const FIELD_BODY: &str = "body";
 const FIELD_ADDRESS: &str = "address";
 const FIELD_BROKEN: &str = "broken";
+const FIELD_CALLEE: &str = "callee";
 
 const FIELD_FROM_BODY: &str = "from_body";
 const FIELD_FROM_BLOCK: &str = "from_block";
@@ -123,11 +147,14 @@ const TRANSFER_KIND_RETURN: &str = "return";
 const EVENT_TRANSFER_START: &str = "transfer_start";
 const EVENT_TRANSFER: &str = "transfer";
 const EVENT_DECISION: &str = "decision";
+/// Transfers between the instrumented and the uninstrumented code.
+const EVENT_EXTERNAL: &str = "external";
 
 impl<C> PhasedCallTraceRecorder for Recorder<C> {
-    fn start_call(&mut self, call_site: BasicBlockLocation<FuncDef>) {
+    fn start_call(&mut self, call_site: BasicBlockLocation<FuncDef>, callee: Option<CalleeDef>) {
         let last_ret_point = self.ephemeral.last_ret_point.take();
         self.handle_maybe_unfinished_return(last_ret_point, call_site);
+        self.ephemeral.pending_callee = Some(callee);
 
         let top = self.stack.last_mut().expect("Inconsistent stack info");
         debug_assert_eq!(top.current, call_site.body);
@@ -151,6 +178,13 @@ impl<C> PhasedCallTraceRecorder for Recorder<C> {
                 index,
             })
         });
+        // The expected callee was external and has called back the instrumented code.
+        let pending_callee = self.ephemeral.pending_callee.take();
+        if broken == Some(true) {
+            if let Some((call_site, callee)) = Option::zip(call_site, pending_callee) {
+                self.notify_external_call(call_site, callee);
+            }
+        }
         self.stack.push(StackedData {
             current: entered_func,
             last_call_site: None,
@@ -221,6 +255,19 @@ impl<C> PhasedCallTraceRecorder for Recorder<C> {
                     caller
                 )
             } else {
+                // The callee has been external. It may or may not have called back the instrumented code.
+                if let Some(callee) = self.ephemeral.pending_callee.take() {
+                    self.notify_external_call(caller, callee);
+                }
+                event!(
+                    name: EVENT_EXTERNAL,
+                    target: TARGET,
+                    parent: self.current_span(),
+                    LEVEL,
+                    { FIELD_TO_BODY } = body_id_to_str!(caller.body.body_id),
+                    { FIELD_TO_BLOCK } = caller.index,
+                    { FIELD_TRANSFER_KIND } = TRANSFER_KIND_RETURN,
+                );
                 return caller.body;
             }
         };
@@ -73,6 +73,10 @@ impl<'a> CallHandler for MdSanCallHandler<'a> {
         );
     }
 
+    fn start_thread(self) {
+        self.flow_manager.start_thread();
+    }
+
     fn enter(self, def: FuncDef) {
         let _sanity = self.flow_manager.enter(def);
     }
@@ -105,6 +109,11 @@ impl<'a> CallHandler for MdSanCallHandler<'a> {
         self.flow_manager.override_return_value(value)
     }
 
+    #[inline]
+    fn concretize_return_value(self, value: Self::Operand) {
+        self.override_return_value(value)
+    }
+
     #[inline]
     fn ret(self, _ret_point: BasicBlockIndex) {
         if self.flow_manager.current_func().body_id.1 == common::pri::DefId(2, 2225) {}
@@ -152,6 +161,8 @@ impl DropHandler for MdSanCallHandler<'_> {
             .expect("Inconsistent instrumentation.");
         self.variables_state.mark_place_dropped(&dropped_place);
     }
+
+    fn after_drop_unwind(self) {}
 }
 
 mod tupling {
@@ -111,7 +111,7 @@ impl RuntimeBackend for MdSanBackend {
         Self: 'a;
 
     type ConstraintHandler<'a>
-        = NoOpConstraintHandler<Self::Operand>
+        = NoOpConstraintHandler<Self::Operand, Self::DiscriminablePlace>
     where
         Self: 'a;
 
@@ -1,4 +1,7 @@
-use leaf_runtime::pri::fluent::backend::LifetimeHandler;
+use leaf_runtime::{
+    abs::{RawAddress, TypeId, TypeSize},
+    pri::fluent::backend::LifetimeHandler,
+};
 
 use super::super::alias::backend;
 use backend::{MdMemoryState, MdSanBackend, MdSanVariablesState};
@@ -36,4 +39,16 @@ impl<'s> LifetimeHandler for MdSanLifetimeHandler<'s> {
             | PlaceValue::ToDropMaybeMdWrapped { .. } => unreachable!(),
         }
     }
+
+    fn mark_allocated(self, _addr: RawAddress, _size: TypeSize) {
+        // Nothing to do for now.
+    }
+
+    fn mark_deallocated(self, _addr: RawAddress) {
+        // Nothing to do for now.
+    }
+
+    fn mark_global(self, _addr: RawAddress, _ptr_type_id: TypeId) {
+        // Nothing to do for now.
+    }
 }
@@ -0,0 +1,34 @@
+//! Benchmarks for the hot paths of the backend, driven by a captured PRI event
+//! stream (recorded using the recorder flavor).
+//!
+//! ```sh
+//! LEAF_BENCH_EVENTS=path/to/pri_events.bin cargo bench -p runtime_backend_symex --features bench
+//! ```
+//!
+//! The benchmarks should be run in the directory of the recorded program, so
+//! that its type information and the backend configuration are found.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+
+use leafrtb_symex::bench::Workload;
+
+const ENV_EVENTS: &str = "LEAF_BENCH_EVENTS";
+
+fn hot_paths(c: &mut Criterion) {
+    let path = std::env::var(ENV_EVENTS)
+        .unwrap_or_else(|_| panic!("The path to the events file is expected in `{ENV_EVENTS}`"));
+    let workload = Workload::load(&path).expect("Failed to load the events file");
+    println!(
+        "Loaded {} events with {} constraints from `{}`",
+        workload.events_count(),
+        workload.values_count(),
+        path,
+    );
+
+    c.bench_function("replay", |b| b.iter(|| workload.replay()));
+    c.bench_function("expr_building", |b| b.iter(|| workload.build_exprs()));
+    c.bench_function("z3_translation", |b| b.iter(|| workload.translate()));
+}
+
+criterion_group!(benches, hot_paths);
+criterion_main!(benches);
@@ -0,0 +1,243 @@
+//! A model of the allocations with known bounds, used to bound the pointer
+//! arithmetic with symbolic offsets.
+//!
+//! The allocations are learned from the allocation sites, i.e., the locals
+//! whose references are taken (e.g., an array whose pointer is obtained to be
+//! iterated over), the statics and the constants referred to, and the memory
+//! returned by the allocator. They are forgotten when the locals die or the
+//! memory is freed. The parts of allocations (e.g., elements and fields) are
+//! not allocations of their own, as pointers into them may legally go beyond
+//! their bounds within the allocation.
+//! When a symbolic offset is applied to a pointer into a known allocation,
+//! the resulting pointer is constrained to stay within (or one past the end of)
+//! the allocation. This implicit constraint makes the model more precise and
+//! its negation leads to inputs that make the pointer go out of bounds.
+//! Similarly, the indices into slices with symbolic lengths are constrained to
+//! be within the lengths.
+//! The constraints are tagged by the kind of the check, so that their violations
+//! can be searched for as candidates of undefined behavior.
+
+use std::collections::BTreeMap;
+
+use common::log_debug;
+use leaf_runtime::{
+    abs::{Alignment, ConstraintKind, IntType, RawAddress, Tag, TypeSize},
+    utils::alias::RRef,
+};
+
+use super::alias::backend;
+use backend::{
+    ConstValue, Implied, LazyTypeInfo, SymExConstraint, SymExTraceManager, SymExValue, ValueRef,
+    alias::SymExValueExprBuilder,
+};
+
+const TAG: &str = "offset_bounds";
+
+/// The tags of the steps checking against undefined behavior.
+pub(crate) mod tags {
+    use super::Tag;
+
+    pub(crate) const BOUNDS: Tag = "ub::bounds";
+    pub(crate) const ALIGNMENT: Tag = "ub::alignment";
+
+    pub(crate) const ALL: [Tag; 2] = [BOUNDS, ALIGNMENT];
+}
+
+#[derive(Default)]
+pub(crate) struct AllocationModel {
+    /// The sizes of the allocations, keyed by their start address.
+    allocations: BTreeMap<usize, TypeSize>,
+}
+
+impl AllocationModel {
+    /// Records the allocation unless it is a part of an already known one.
+    /// The known allocations overlapping with it are replaced.
+    pub(crate) fn add(&mut self, addr: RawAddress, size: TypeSize) {
+        if size == 0 {
+            return;
+        }
+
+        let (start, end) = range_of(addr, size);
+        if self.find_containing(start, end).is_some() {
+            return;
+        }
+
+        self.remove(addr, size);
+        let overlapping_prev = self
+            .allocations
+            .range(..start)
+            .next_back()
+            .filter(|(prev_start, prev_size)| *prev_start + **prev_size as usize > start)
+            .map(|(prev_start, _)| *prev_start);
+        if let Some(prev_start) = overlapping_prev {
+            self.allocations.remove(&prev_start);
+        }
+        self.allocations.insert(start, size);
+    }
+
+    /// Forgets the allocation starting at the address, if any.
+    pub(crate) fn remove_at(&mut self, addr: RawAddress) {
+        self.allocations.remove(&addr.addr());
+    }
+
+    /// Forgets the allocations starting within the given range.
+    pub(crate) fn remove(&mut self, addr: RawAddress, size: TypeSize) {
+        let (start, end) = range_of(addr, size);
+        let starts = self
+            .allocations
+            .range(start..end.max(start + 1))
+            .map(|(start, _)| *start)
+            .collect::<Vec<_>>();
+        starts.into_iter().for_each(|start| {
+            self.allocations.remove(&start);
+        });
+    }
+
+    /// Finds the allocation that the address points into or one past its end.
+    pub(crate) fn find(&self, addr: RawAddress) -> Option<(RawAddress, TypeSize)> {
+        let addr = addr.addr();
+        self.find_containing(addr, addr)
+            .map(|(start, size)| (core::ptr::without_provenance(start), size))
+    }
+
+    fn find_containing(&self, start: usize, end: usize) -> Option<(usize, TypeSize)> {
+        self.allocations
+            .range(..=start)
+            .next_back()
+            .filter(|(alloc_start, size)| end <= *alloc_start + **size as usize)
+            .map(|(start, size)| (*start, *size))
+    }
+}
+
+#[inline]
+fn range_of(addr: RawAddress, size: TypeSize) -> (usize, usize) {
+    let start = addr.addr();
+    (start, start + size as usize)
+}
+
+/// Adds the in-bounds constraints for the pointers offset symbolically.
+/// If enabled, it also adds the alignment constraints for the pointers cast
+/// from symbolic addresses.
+pub(crate) struct OffsetBounder {
+    pub(crate) allocations: AllocationModel,
+    trace_manager: RRef<SymExTraceManager>,
+    tags: RRef<Vec<Tag>>,
+    check_alignment: bool,
+}
+
+impl OffsetBounder {
+    pub(crate) fn new(
+        trace_manager: RRef<SymExTraceManager>,
+        tags: RRef<Vec<Tag>>,
+        check_alignment: bool,
+    ) -> Self {
+        Self {
+            allocations: Default::default(),
+            trace_manager,
+            tags,
+            check_alignment,
+        }
+    }
+
+    /// Constrains the result of offsetting `ptr` to be in the bounds of the
+    /// allocation `ptr` points into, if any is known.
+    pub(crate) fn bound(
+        &mut self,
+        expr_builder: &mut impl SymExValueExprBuilder,
+        ptr: RawAddress,
+        offset_result: &SymExValue,
+    ) {
+        let Some((start, size)) = self.allocations.find(ptr) else {
+            return;
+        };
+        log_debug!(
+            target: TAG,
+            "Bounding offset from {:p} to allocation {:p}..+{}",
+            ptr,
+            start,
+            size,
+        );
+
+        let lower = Implied::always(ConstValue::Addr(start).to_value_ref());
+        let upper = Implied::always(
+            ConstValue::Addr(start.wrapping_byte_add(size as usize)).to_value_ref(),
+        );
+        let lower_check = expr_builder.ge((offset_result.clone(), lower));
+        let upper_check = expr_builder.le((offset_result.clone(), upper));
+        let in_bounds = expr_builder.and((lower_check, upper_check));
+
+        notify_check(&self.trace_manager, &self.tags, tags::BOUNDS, in_bounds);
+    }
+
+    /// Constrains the pointer to be aligned for its pointee, if alignment checks
+    /// are enabled.
+    pub(crate) fn align(
+        &mut self,
+        expr_builder: &mut impl SymExValueExprBuilder,
+        ptr: &SymExValue,
+        align: Alignment,
+        usize_ty: LazyTypeInfo,
+    ) {
+        if !self.check_alignment || align <= 1 {
+            return;
+        }
+        log_debug!(target: TAG, "Checking alignment of {} to {}", ptr.value, align);
+
+        let addr = expr_builder.to_int(ptr.clone(), IntType::USIZE, usize_ty);
+        let align = Implied::always(ConstValue::new_int(align, IntType::USIZE).to_value_ref());
+        let misalignment = expr_builder.rem((addr, align));
+        let zero = Implied::always(ConstValue::new_int(0u64, IntType::USIZE).to_value_ref());
+        let is_aligned = expr_builder.eq((misalignment, zero));
+
+        notify_check(&self.trace_manager, &self.tags, tags::ALIGNMENT, is_aligned);
+    }
+}
+
+/// Adds the in-bounds constraints for the indices into slices whose lengths are
+/// kept symbolic.
+/// Similar to the offsets, the negation of these constraints leads to
+/// out-of-bounds accesses, and otherwise they keep the index consistent with
+/// the length chosen for the slice.
+pub(crate) struct LenBounder {
+    trace_manager: RRef<SymExTraceManager>,
+    tags: RRef<Vec<Tag>>,
+}
+
+impl LenBounder {
+    pub(crate) fn new(trace_manager: RRef<SymExTraceManager>, tags: RRef<Vec<Tag>>) -> Self {
+        Self {
+            trace_manager,
+            tags,
+        }
+    }
+
+    /// Records the check of an index against the length of the indexed slice.
+    pub(crate) fn bound(&self, in_bounds: ValueRef) {
+        log_debug!("Bounding index by symbolic length: {}", in_bounds);
+        notify_check(
+            &self.trace_manager,
+            &self.tags,
+            tags::BOUNDS,
+            Implied::by_unknown(in_bounds),
+        );
+    }
+}
+
+/// Records the check as a decision taken in the current path, tagged by its kind.
+fn notify_check(
+    trace_manager: &RRef<SymExTraceManager>,
+    tags: &RRef<Vec<Tag>>,
+    tag: Tag,
+    check: SymExValue,
+) {
+    let constraint = SymExConstraint {
+        discr: check,
+        kind: ConstraintKind::True,
+    };
+    tags.borrow_mut().push(tag);
+    trace_manager.borrow_mut().notify_step(
+        Default::default(), /* TODO: A unique index like basic block index. */
+        constraint,
+    );
+    tags.borrow_mut().pop();
+}
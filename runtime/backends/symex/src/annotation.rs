@@ -1,22 +1,52 @@
 use std::cell::RefMut;
 
-use common::log_debug;
+use common::{
+    log_debug, log_info, log_warn,
+    pri::{Tag, tags},
+    types::trace::ExeTraceRecord,
+};
 
-use leaf_runtime::pri::fluent::backend::AnnotationHandler;
+use leaf_runtime::{abs::SymVariableInfo, pri::fluent::backend::AnnotationHandler};
 
 use super::alias::backend;
-use backend::SymExBackend;
+use backend::{SymExBackend, SymExExeTraceRecorder, SymExSymVariablesManager};
 
 const LOG_TAG_TAGS: &str = "tags";
 
 pub(crate) struct SymExAnnotationHandler<'a> {
     tags: RefMut<'a, Vec<common::pri::Tag>>,
+    trace_recorder: RefMut<'a, SymExExeTraceRecorder>,
+    sym_values: RefMut<'a, SymExSymVariablesManager>,
 }
 
 impl<'a> SymExAnnotationHandler<'a> {
     pub(super) fn new(backend: &'a mut SymExBackend) -> Self {
         Self {
             tags: backend.tags.borrow_mut(),
+            trace_recorder: backend.trace_recorder.borrow_mut(),
+            sym_values: backend.sym_values.borrow_mut(),
+        }
+    }
+
+    /// Records reaching the objectives marked by the tags (see the `reach` and
+    /// `reject` annotations) at the call site of the annotation.
+    fn record_objective(&mut self, tag: Tag) {
+        let previous = self.tags.iter().rev().nth(1).copied();
+        let record = |from| match (previous, tag) {
+            (Some(tags::REACH), label) => Some(ExeTraceRecord::Reach {
+                from,
+                label: label.to_owned(),
+            }),
+            (_, tags::REJECT) => Some(ExeTraceRecord::Reject { from }),
+            _ => None,
+        };
+
+        let Some(from) = self.trace_recorder.current_location() else {
+            return;
+        };
+        if let Some(record) = record(from) {
+            log_info!("Objective reached: {record}");
+            self.trace_recorder.notify_objective(record);
         }
     }
 
@@ -27,12 +57,36 @@ impl<'a> SymExAnnotationHandler<'a> {
 
 impl<'a> AnnotationHandler for SymExAnnotationHandler<'a> {
     fn push_tag(mut self, tag: common::pri::Tag) {
+        if tag == tags::CATCH_UNWIND {
+            crate::crash::notify_catch_entered();
+        }
         self.tags.push(tag);
         self.log_current_tags();
+        self.record_objective(tag);
     }
 
     fn pop_tag(mut self) {
-        self.tags.pop();
+        if self.tags.pop() == Some(tags::CATCH_UNWIND) {
+            crate::crash::notify_catch_exited();
+        }
         self.log_current_tags();
     }
+
+    fn violate_assumption(self) {
+        log_warn!("An assumption does not hold, the execution is outside the assumed input space");
+    }
+
+    fn push_sym_var_info(mut self, info: SymVariableInfo) {
+        log_debug!(
+            "Marking `{}` ({}) symbolic at {}",
+            info.name,
+            info.type_name,
+            info.location
+        );
+        self.sym_values.push_info(info);
+    }
+
+    fn pop_sym_var_info(mut self) {
+        self.sym_values.pop_info();
+    }
 }
@@ -0,0 +1,285 @@
+//! Outcome of the assertions.
+//!
+//! Assertions are reported to the backend before they are checked, so they
+//! are recorded in the trace as if they always hold. If one does not, the
+//! program panics right after and the regular shutdown is never reached.
+//! The panic hook installed here identifies the assertion that has actually
+//! failed, reports it along with the polarity of its condition, and lets the
+//! backend flush the trace and the outputs before the process terminates.
+//! Assertions that hold are cleared as soon as the execution reaches their
+//! target, so later panics are not mistaken for their failure.
+//! Explicit panics (e.g., by `panic!` or a failed `assert!`) are reported right
+//! before they happen and are reported the same way with their messages.
+
+use std::{
+    cell::Cell,
+    panic::PanicHookInfo,
+    sync::{Mutex, PoisonError},
+};
+
+use derive_more as dm;
+use serde::Serialize;
+
+use common::{log_error, log_info, log_warn};
+
+use leaf_runtime::abs::{AssertKind, BasicBlockLocation, Tag};
+
+use super::alias::backend;
+use backend::{config::OutputConfig, crash};
+
+const FILENAME_DEFAULT: &str = "failed_assertion";
+
+/// The tags of the steps recording the conditions of the assertions that the
+/// compiler inserts for the operations panicking on invalid operands.
+pub(crate) mod tags {
+    use super::Tag;
+
+    pub(crate) const BOUNDS_CHECK: Tag = "assert::bounds_check";
+    pub(crate) const OVERFLOW: Tag = "assert::overflow";
+    pub(crate) const OVERFLOW_NEG: Tag = "assert::overflow_neg";
+    pub(crate) const DIVISION_BY_ZERO: Tag = "assert::division_by_zero";
+    pub(crate) const REMAINDER_BY_ZERO: Tag = "assert::remainder_by_zero";
+
+    pub(crate) const ALL: [Tag; 5] = [
+        BOUNDS_CHECK,
+        OVERFLOW,
+        OVERFLOW_NEG,
+        DIVISION_BY_ZERO,
+        REMAINDER_BY_ZERO,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, dm::Display)]
+#[serde(rename_all = "snake_case")]
+enum AssertionKind {
+    #[display("bounds check")]
+    BoundsCheck,
+    #[display("overflow")]
+    Overflow,
+    #[display("negation overflow")]
+    OverflowNeg,
+    #[display("division by zero")]
+    DivisionByZero,
+    #[display("remainder by zero")]
+    RemainderByZero,
+    #[display("resumed after return")]
+    ResumedAfterReturn,
+    #[display("resumed after panic")]
+    ResumedAfterPanic,
+    #[display("misaligned pointer dereference")]
+    #[serde(rename = "misaligned_ptr_deref")]
+    MisalignedPointerDereference,
+    #[display("null pointer dereference")]
+    #[serde(rename = "null_ptr_deref")]
+    NullPointerDereference,
+    #[display("invalid enum construction")]
+    InvalidEnumConstruction,
+    /// An explicit panic, e.g., by `panic!` or a failed `assert!`.
+    #[display("panic")]
+    Panic,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+struct PendingAssertion {
+    location: BasicBlockLocation,
+    kind: AssertionKind,
+    /// The value the condition is expected to have for the assertion to hold.
+    expected: bool,
+    /// Whether the constraint of the condition is recorded in the trace.
+    is_symbolic: bool,
+}
+
+#[derive(Serialize)]
+struct FailedAssertion {
+    #[serde(flatten)]
+    assertion: PendingAssertion,
+    /// The value the condition has actually had, i.e., the negation of the
+    /// constraint recorded in the trace.
+    actual: bool,
+    message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PendingPanic {
+    location: BasicBlockLocation,
+    /// The source text of the panicking expression, e.g., the `assert!` invocation.
+    source: &'static str,
+}
+
+#[derive(Serialize)]
+struct ExplicitPanic {
+    location: BasicBlockLocation,
+    kind: AssertionKind,
+    source: Option<&'static str>,
+    message: Option<String>,
+}
+
+thread_local! {
+    static PENDING: Cell<Option<PendingAssertion>> = const { Cell::new(None) };
+    static PENDING_PANIC: Cell<Option<PendingPanic>> = const { Cell::new(None) };
+}
+
+/// Shared by all threads, as the panic hook runs on the panicking one.
+static REPORT: Mutex<Option<OutputConfig>> = Mutex::new(None);
+
+pub(crate) fn init_assertion_reporting(report: Option<&OutputConfig>) {
+    *REPORT.lock().unwrap_or_else(PoisonError::into_inner) = report.cloned();
+}
+
+/// Marks the assertion as the one being checked right after.
+pub(crate) fn notify_assertion<O>(
+    location: BasicBlockLocation,
+    kind: &AssertKind<O>,
+    expected: bool,
+    is_symbolic: bool,
+) {
+    PENDING.set(Some(PendingAssertion {
+        location,
+        kind: AssertionKind::from(kind),
+        expected,
+        is_symbolic,
+    }));
+}
+
+/// Marks the explicit panic as the one happening right after.
+pub(crate) fn notify_panic(location: BasicBlockLocation, source: &'static str) {
+    PENDING.set(None);
+    PENDING_PANIC.set(Some(PendingPanic { location, source }));
+}
+
+/// Marks the pending assertion (if any) as passed, as the execution has moved on.
+#[inline]
+pub(crate) fn clear_pending_assertion() {
+    PENDING.set(None);
+}
+
+/// Installs a panic hook that reports the failed assertion (if the panic is
+/// caused by one), triages the crash (see `crash`) at the location given by
+/// `current_location`, and flushes the backend using `flush` before proceeding
+/// with the previously installed hook.
+/// `flush` is expected to return `false` if the backend is not accessible,
+/// e.g., when the panic originates from the backend itself.
+pub(crate) fn install_panic_hook(
+    current_location: impl Fn() -> Option<BasicBlockLocation> + Send + Sync + 'static,
+    flush: impl Fn() -> bool + Send + Sync + 'static,
+) {
+    let prev_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        report_panic(info);
+        crash::triage_panic(info, current_location());
+        if flush() {
+            log_info!("Backend flushed at panic");
+        } else {
+            log_warn!("Could not flush the backend at panic");
+        }
+        prev_hook(info)
+    }));
+}
+
+fn report_panic(info: &PanicHookInfo) {
+    let message = info.payload_as_str();
+    if let Some(panic) = PENDING_PANIC.take() {
+        report_explicit_panic(panic, message);
+        return;
+    }
+
+    let Some(assertion) = PENDING
+        .take()
+        .filter(|a| message.is_some_and(|m| is_caused_by(a, m)))
+    else {
+        return;
+    };
+
+    log_error!(
+        "Assertion ({}) failed at {}, the condition was expected to be {} (symbolic: {})",
+        assertion.kind,
+        assertion.location,
+        assertion.expected,
+        assertion.is_symbolic,
+    );
+
+    write_report(&FailedAssertion {
+        assertion,
+        actual: !assertion.expected,
+        message: message.map(ToOwned::to_owned),
+    });
+}
+
+fn report_explicit_panic(panic: PendingPanic, message: Option<&str>) {
+    log_error!(
+        "Explicit panic at {}: {}",
+        panic.location,
+        message.unwrap_or(panic.source),
+    );
+
+    write_report(&ExplicitPanic {
+        location: panic.location,
+        kind: AssertionKind::Panic,
+        source: Some(panic.source).filter(|s| !s.is_empty()),
+        message: message.map(ToOwned::to_owned),
+    });
+}
+
+fn write_report(record: &impl Serialize) {
+    let report = REPORT.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(OutputConfig::File(file_config)) = report.as_ref() else {
+        return;
+    };
+    let result = file_config
+        .open_or_create_single_with_path(FILENAME_DEFAULT, None, true)
+        .and_then(|(path, file)| {
+            serde_json::to_writer_pretty(file, record)?;
+            log_info!("Failed assertion written to: {}", path.display());
+            Ok(())
+        });
+    let _ = result.inspect_err(|e| log_warn!("Could not write failed assertion: {e}"));
+}
+
+pub(crate) fn tag_of<O>(kind: &AssertKind<O>) -> Option<Tag> {
+    match kind {
+        AssertKind::BoundsCheck { .. } => Some(tags::BOUNDS_CHECK),
+        AssertKind::Overflow(..) => Some(tags::OVERFLOW),
+        AssertKind::OverflowNeg(..) => Some(tags::OVERFLOW_NEG),
+        AssertKind::DivisionByZero(..) => Some(tags::DIVISION_BY_ZERO),
+        AssertKind::RemainderByZero(..) => Some(tags::REMAINDER_BY_ZERO),
+        _ => None,
+    }
+}
+
+impl<O> From<&AssertKind<O>> for AssertionKind {
+    fn from(kind: &AssertKind<O>) -> Self {
+        match kind {
+            AssertKind::BoundsCheck { .. } => Self::BoundsCheck,
+            AssertKind::Overflow(..) => Self::Overflow,
+            AssertKind::OverflowNeg(..) => Self::OverflowNeg,
+            AssertKind::DivisionByZero(..) => Self::DivisionByZero,
+            AssertKind::RemainderByZero(..) => Self::RemainderByZero,
+            AssertKind::ResumedAfterReturn(..) => Self::ResumedAfterReturn,
+            AssertKind::ResumedAfterPanic(..) => Self::ResumedAfterPanic,
+            AssertKind::MisalignedPointerDereference { .. } => Self::MisalignedPointerDereference,
+            AssertKind::NullPointerDereference => Self::NullPointerDereference,
+            AssertKind::InvalidEnumConstruction(..) => Self::InvalidEnumConstruction,
+        }
+    }
+}
+
+/// Checks whether the panic message matches the one the compiler generates
+/// for the kind of the assertion, so that other panics happening after a
+/// passed assertion are not mistaken for its failure.
+fn is_caused_by(assertion: &PendingAssertion, message: &str) -> bool {
+    use AssertionKind::*;
+    match assertion.kind {
+        BoundsCheck => message.starts_with("index out of bounds"),
+        Overflow | OverflowNeg => {
+            message.starts_with("attempt to") && message.ends_with("with overflow")
+        }
+        DivisionByZero => message.starts_with("attempt to divide by zero"),
+        RemainderByZero => message.starts_with("attempt to calculate the remainder"),
+        ResumedAfterReturn | ResumedAfterPanic => message.contains("resumed after"),
+        MisalignedPointerDereference => message.starts_with("misaligned pointer dereference"),
+        NullPointerDereference => message.starts_with("null pointer dereference"),
+        InvalidEnumConstruction => message.contains("invalid value"),
+        // Explicit panics are pending separately.
+        Panic => false,
+    }
+}
@@ -1,22 +1,27 @@
 use core::{iter, ops::DerefMut};
 
-use common::type_info::TagEncodingInfo;
+use common::{log_debug, log_warn, type_info::TagEncodingInfo};
 
 use leaf_runtime::{
+    abs::backend::CoreTypeProvider,
     abs::{
-        self, AssignmentId, BinaryOp, CastKind, FieldIndex, InstanceKindId, IntType, UnaryOp,
-        VariantIndex,
+        self, AssignmentId, BinaryOp, CastKind, DefId, FieldIndex, InstanceKindId, IntType, TypeId,
+        UnaryOp, VariantIndex,
     },
     pri::fluent::backend::AssignmentHandler,
-    type_info::{TypeLayoutResolver, TypeLayoutResolverExt},
+    type_info::{FieldsShapeInfoExt, TypeInfoExt, TypeLayoutResolver, TypeLayoutResolverExt},
     utils::{MutAccess, RRef},
 };
 
 use super::alias::backend;
 use backend::{
-    Implied, PlaceValueRef, Precondition, SymExBackend, SymExExprBuilder, SymExValue, TypeDatabase,
-    ValueRef, VariablesState, alias::SymExValueExprBuilder, expr::prelude::*,
-    implication::PreconditionConstruct, place::DiscriminantPossiblePlace,
+    Implied, OffsetBounder, PlaceValueRef, Precondition, SymExBackend, SymExExprBuilder,
+    SymExValue, TypeDatabase, ValueRef, VariablesState,
+    alias::SymExValueExprBuilder,
+    diagnostics::{SymbolismLossReason, report_symbolism_loss},
+    expr::prelude::*,
+    implication::PreconditionConstruct,
+    place::DiscriminantPossiblePlace,
 };
 
 #[cfg(feature = "implicit_flow")]
@@ -25,9 +30,13 @@ use backend::ImplicationInvestigator;
 pub(super) struct AssignmentServices<'a, EB> {
     #[cfg(feature = "implicit_flow")]
     pub(super) current_func: InstanceKindId,
+    /// Whether the current frame is beyond the call depth limit, where the
+    /// data flow is not followed.
+    pub(super) is_summarized: bool,
     pub(super) vars_state: &'a mut dyn VariablesState,
     pub(super) expr_builder: RRef<EB>,
     pub(super) type_manager: &'a dyn TypeDatabase,
+    pub(super) offset_bounder: Option<RRef<OffsetBounder>>,
     #[cfg(feature = "implicit_flow")]
     pub(super) implication_investigator: &'a dyn ImplicationInvestigator,
 }
@@ -39,9 +48,11 @@ macro_rules! services_from_backend {
         AssignmentServices {
             #[cfg(feature = "implicit_flow")]
             current_func: $backend.call_flow_manager.current_func().body_id,
-            vars_state: &mut $backend.vars_state,
+            is_summarized: $backend.threads.current().call_depth.is_summarized(),
+            vars_state: &mut $backend.threads.current().vars_state,
             expr_builder: $backend.expr_builder.clone(),
             type_manager: $backend.type_manager.as_ref(),
+            offset_bounder: $backend.offset_bounder.clone(),
             #[cfg(feature = "implicit_flow")]
             implication_investigator: $backend.implication_investigator.as_ref(),
         }
@@ -122,15 +133,21 @@ impl<EB: SymExValueExprBuilder> AssignmentHandler for SymExAssignmentHandler<'_,
                     ));
                 }
             },
-            PlaceValue::Deterministic(..) => {
+            PlaceValue::Deterministic(_) => {
+                // The referenced locals are registered as allocations by the place handler.
                 self.set_value(Implied::always(UnevalValue::Some.into()))
             }
         }
     }
 
-    fn thread_local_ref_to(self) {
-        // Thread local references cannot refer to symbolic places, so the reference is concrete.
-        self.some()
+    fn thread_local_ref_to(mut self, def_id: DefId, addr: RawAddress) {
+        /* NOTE: The thread-local is an addressable place in the memory like any other static,
+         * but at a different address in each thread. Thus, the reference is to the place
+         * registered for the current thread, and the accesses through it are resolved
+         * by the pointer-based state. */
+        log_debug!("Referencing thread-local static {} at {:p}", def_id, addr);
+        let value = self.services.vars_state.ref_thread_local(def_id, addr);
+        self.set(value)
     }
 
     fn address_of(self, place: Self::Place, is_mutable: bool) {
@@ -143,6 +160,9 @@ impl<EB: SymExValueExprBuilder> AssignmentHandler for SymExAssignmentHandler<'_,
             .expr_builder()
             .cast(operand, target, self.dest.type_info().clone());
 
+        if matches!(target, CastKind::ToPointer(..)) && cast_value.is_symbolic() {
+            self.check_alignment(&cast_value);
+        }
         self.set(cast_value)
     }
 
@@ -152,6 +172,19 @@ impl<EB: SymExValueExprBuilder> AssignmentHandler for SymExAssignmentHandler<'_,
         first: Self::Operand,
         second: Self::Operand,
     ) {
+        if operator == BinaryOp::Powi && (first.is_symbolic() || second.is_symbolic()) {
+            // The floating-point theories of the solvers do not support exponentiation.
+            log_warn!(
+                "Symbolic operands of powi are not supported and will be concretized: {} ** {}",
+                first.value,
+                second.value,
+            );
+            report_symbolism_loss(SymbolismLossReason::Unsupported, || {
+                format!("Floating-point powi: {} ** {}", first.value, second.value)
+            });
+            return self.some();
+        }
+
         let operator =
             self.to_expr_builder_binary_op(operator, first.is_symbolic() || second.is_symbolic());
 
@@ -167,7 +200,16 @@ impl<EB: SymExValueExprBuilder> AssignmentHandler for SymExAssignmentHandler<'_,
             second
         };
 
+        let conc_ptr = match operator {
+            ExprBuilderBinaryOp::Offset(..) if second.is_symbolic() => self.conc_addr_of(&first),
+            _ => None,
+        };
         let result_value = self.expr_builder().binary_op((first, second), operator);
+        if let Some((bounder, ptr)) = self.services.offset_bounder.clone().zip(conc_ptr) {
+            bounder
+                .borrow_mut()
+                .bound(&mut *self.expr_builder(), ptr, &result_value);
+        }
         self.set(result_value)
     }
 
@@ -209,6 +251,52 @@ impl<EB: SymExValueExprBuilder> AssignmentHandler for SymExAssignmentHandler<'_,
         )
     }
 
+    fn simd_binary_op_between(
+        self,
+        operator: BinaryOp,
+        first: impl Iterator<Item = Self::Operand>,
+        second: impl Iterator<Item = Self::Operand>,
+    ) {
+        use BinaryOp::*;
+        // Comparisons result in masks (all ones for true, zero for false) in the lanes.
+        let mask_ty = matches!(operator, Eq | Ne | Lt | Le | Gt | Ge)
+            .then(|| self.get_int_type(&LazyTypeInfo::Id(self.simd_lane_ty())));
+
+        let lanes = first
+            .zip(second)
+            .map(|(first, second)| {
+                let operator = self.to_expr_builder_binary_op(
+                    operator,
+                    first.is_symbolic() || second.is_symbolic(),
+                );
+                let result = self.expr_builder().binary_op((first, second), operator);
+                match mask_ty {
+                    Some(ty) => self.to_simd_mask(result, ty),
+                    None => result,
+                }
+            })
+            .collect::<Vec<_>>();
+        self.set_simd_lanes(lanes)
+    }
+
+    fn simd_unary_op_on(self, operator: UnaryOp, operand: impl Iterator<Item = Self::Operand>) {
+        let lanes = operand
+            .map(|lane| self.expr_builder().unary_op(lane, operator.into()))
+            .collect::<Vec<_>>();
+        self.set_simd_lanes(lanes)
+    }
+
+    fn simd_reduce(mut self, operator: BinaryOp, operand: impl Iterator<Item = Self::Operand>) {
+        let result_value = operand
+            .reduce(|acc, lane| {
+                let operator = self
+                    .to_expr_builder_binary_op(operator, acc.is_symbolic() || lane.is_symbolic());
+                self.expr_builder().binary_op((acc, lane), operator)
+            })
+            .expect("SIMD vectors are expected to have at least one lane");
+        self.set(result_value)
+    }
+
     fn discriminant_from(mut self, place: Self::DiscriminablePlace) {
         let discr_value = match place {
             DiscriminantPossiblePlace::None => {
@@ -288,7 +376,8 @@ impl<EB: SymExValueExprBuilder> AssignmentHandler for SymExAssignmentHandler<'_,
         self.adt_from([data_ptr, metadata].into_iter(), None)
     }
 
-    // TODO: Need to add support for the Deinit MIR instruction to have this working properly.
+    // NOTE: The Deinit MIR statement that used to precede the field assignments is no longer
+    // emitted by the compiler, so there is no point at which the place is marked uninitialized.
     // This solution works for now to avoid crashes when samples are run.
     fn variant_index(mut self, variant_index: VariantIndex) {
         // FIXME: This implementation relies on internals of the VariablesState.
@@ -344,6 +433,48 @@ impl<EB: SymExValueExprBuilder> AssignmentHandler for SymExAssignmentHandler<'_,
 }
 
 impl<'a, EB> SymExAssignmentHandler<'_, 'a, EB> {
+    /// # Returns
+    /// The address held by the operand if it is a concrete pointer.
+    fn conc_addr_of(&self, operand: &SymExValue) -> Option<RawAddress> {
+        if self.services.offset_bounder.is_none() {
+            return None;
+        }
+
+        match operand.value.as_ref() {
+            Value::Concrete(ConcreteValue::Const(ConstValue::Addr(addr))) => Some(*addr),
+            Value::Concrete(ConcreteValue::Unevaluated(UnevalValue::Lazy(raw))) => {
+                match unsafe { raw.try_retrieve_as_scalar(self.type_manager()) } {
+                    Ok(ConstValue::Addr(addr)) => Some(addr),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks the alignment of the pointer resulted from a cast to its pointee type.
+    fn check_alignment(&self, ptr: &SymExValue)
+    where
+        EB: SymExValueExprBuilder,
+    {
+        let Some(bounder) = self.services.offset_bounder.as_ref() else {
+            return;
+        };
+        let Some(pointee_ty) = self
+            .dest
+            .type_info()
+            .id()
+            .and_then(|ptr_ty| self.type_manager().get_pointee_ty(&ptr_ty))
+        else {
+            return;
+        };
+        let align = self.type_manager().get_type(&pointee_ty).align;
+        let usize_ty = CoreTypeProvider::<LazyTypeInfo>::usize(self.type_manager());
+        bounder
+            .borrow_mut()
+            .align(&mut *self.expr_builder(), ptr, align, usize_ty);
+    }
+
     fn expr_builder(&self) -> impl DerefMut<Target = EB> + '_ {
         self.services.expr_builder.as_ref().borrow_mut()
     }
@@ -365,17 +496,70 @@ impl<'a, EB> SymExAssignmentHandler<'_, 'a, EB> {
 
     #[inline]
     fn set_no_ant(&mut self, value: SymExValue) {
+        // In summarized frames, the destination is only invalidated, so no stale symbolic value remains.
+        let value = if self.services.is_summarized {
+            Implied::by_unknown(ConcreteValue::from(abs::Constant::Some).to_value_ref())
+        } else {
+            value
+        };
         self.services.vars_state.set_place(&self.dest, value);
     }
 
     fn get_int_type(&self, ty_info: &LazyTypeInfo) -> IntType {
-        let ty = self
-            .type_manager()
-            .try_to_value_type(ty_info.clone())
-            .expect("Expected the type of the discriminant raw value to be a primitive");
-        *ty.as_int()
-            // https://doc.rust-lang.org/reference/type-layout.html#primitive-representations
-            .unwrap_or_else(|| panic!("Expected the type of the tag to be a int type: {:?}", ty))
+        get_int_type(self.type_manager(), ty_info)
+    }
+
+    /// Returns the type of the lanes of the SIMD vector destination, which wraps
+    /// a single array field.
+    fn simd_lane_ty(&self) -> TypeId {
+        let vector_ty = self.type_manager().get_type(&self.dest.type_id());
+        let array_ty = vector_ty
+            .expect_single_variant()
+            .fields
+            .expect_struct()
+            .fields()[0]
+            .ty;
+        self.type_manager()
+            .get_type(&array_ty)
+            .expect_array()
+            .item_ty
+    }
+
+    /// Sets the lanes as the array field of the SIMD vector destination.
+    fn set_simd_lanes(mut self, lanes: Vec<SymExValue>)
+    where
+        EB: SymExValueExprBuilder,
+    {
+        let (preconditions, values) = lanes
+            .into_iter()
+            .map(Implied::into_tuple)
+            .unzip::<_, _, Vec<_>, Vec<_>>();
+
+        #[cfg(not(feature = "implicit_flow"))]
+        let precondition = Precondition::unknown();
+        #[cfg(feature = "implicit_flow")]
+        let precondition = self.precondition_of_array(preconditions);
+
+        let array = ConcreteValue::Array(ArrayValue { elements: values });
+        self.set_adt_value(
+            AdtKind::Struct,
+            iter::once(Some(Implied {
+                by: precondition,
+                value: array.to_value_ref(),
+            })),
+        )
+    }
+
+    /// Converts a boolean lane to a SIMD mask lane of the given type.
+    fn to_simd_mask(&self, condition: SymExValue, ty: IntType) -> SymExValue
+    where
+        EB: SymExValueExprBuilder,
+    {
+        let all_ones = u128::MAX >> (u128::BITS as u64 - ty.bit_size);
+        let true_value = Implied::always(ConstValue::new_int(all_ones, ty).to_value_ref());
+        let false_value = Implied::always(ConstValue::new_int(0_u128, ty).to_value_ref());
+        self.expr_builder()
+            .if_then_else((condition, true_value, false_value))
     }
 }
 
@@ -410,7 +594,6 @@ impl<EB> SymExAssignmentHandler<'_, '_, EB> {
         })
     }
 
-    #[tracing::instrument(level = "debug", skip(self))]
     fn build_discriminant_expr(
         &self,
         tag_value: SymValueRef,
@@ -421,72 +604,14 @@ impl<EB> SymExAssignmentHandler<'_, '_, EB> {
     where
         EB: SymExValueExprBuilder,
     {
-        use TagEncodingInfo::*;
-        match tag_encoding {
-            Direct => tag_value,
-            Niche {
-                non_niche_value,
-                niche_value_range,
-                tag_value_start,
-            } => {
-                let discr_ty = self.get_int_type(discr_ty_info);
-                let tag_ty = self.get_int_type(tag_ty_info);
-
-                let into_tag_value = |v: u128| ConstValue::new_int(v, tag_ty).to_value_ref();
-                let into_discr_value = |v: u128| ConstValue::new_int(v, discr_ty).to_value_ref();
-
-                // Based on: `rustc_codegen_ssa::mir::place::PlaceRef::codegen_get_discr`
-                let niche_start = *niche_value_range.start();
-                let tag_value: ValueRef = tag_value.into();
-                let relative_max = niche_value_range.end() - niche_start;
-                let (is_niche, tagged_discr) = if relative_max == 0 {
-                    let is_niche = SymValueRef::new(
-                        self.expr_builder()
-                            .inner()
-                            .eq((tag_value.clone(), into_tag_value(*tag_value_start)).into())
-                            .into(),
-                    );
-                    let tagged_discr = into_discr_value(niche_start);
-                    (is_niche, tagged_discr)
-                } else {
-                    let relative_tag_value: ValueRef = self
-                        .expr_builder()
-                        .inner()
-                        .sub((tag_value.clone(), into_tag_value(*tag_value_start)).into())
-                        .into();
-                    let is_niche = SymValueRef::new(
-                        self.expr_builder()
-                            .inner()
-                            .le((relative_tag_value.clone(), into_tag_value(relative_max)).into())
-                            .into(),
-                    );
-                    let relative_discr_value = self
-                        .expr_builder()
-                        .inner()
-                        .to_int(relative_tag_value.into(), discr_ty, discr_ty_info.clone())
-                        .into();
-                    let tagged_discr = self
-                        .expr_builder()
-                        .inner()
-                        .add((relative_discr_value, into_discr_value(niche_start)).into())
-                        .into();
-                    let tagged_discr = self.expr_builder().inner().to_int(
-                        tagged_discr,
-                        discr_ty,
-                        discr_ty_info.clone(),
-                    );
-                    debug_assert!(tagged_discr.is_symbolic());
-                    (is_niche, tagged_discr)
-                };
-
-                let discr_value = self.expr_builder().inner().if_then_else((
-                    is_niche.into(),
-                    tagged_discr.into(),
-                    into_discr_value(*non_niche_value),
-                ));
-                SymValueRef::new(discr_value)
-            }
-        }
+        build_discriminant_expr(
+            &self.services.expr_builder,
+            self.type_manager(),
+            tag_value,
+            tag_encoding,
+            discr_ty_info,
+            tag_ty_info,
+        )
     }
 
     fn ensure_type_of_ptr_for_raw_ptr(&self, data_ptr: SymExValue) -> SymExValue
@@ -715,3 +840,97 @@ pub(super) mod precondition {
         value.add_antecedents(Cow::Owned(antecedent), whole_size);
     }
 }
+
+#[tracing::instrument(level = "debug", skip(expr_builder, type_manager))]
+pub(super) fn build_discriminant_expr<EB: SymExValueExprBuilder>(
+    expr_builder: &RRef<EB>,
+    type_manager: &dyn TypeDatabase,
+    tag_value: SymValueRef,
+    tag_encoding: &TagEncodingInfo,
+    discr_ty_info: &LazyTypeInfo,
+    tag_ty_info: &LazyTypeInfo,
+) -> SymValueRef {
+    use TagEncodingInfo::*;
+    match tag_encoding {
+        Direct => tag_value,
+        Niche {
+            non_niche_value,
+            niche_value_range,
+            tag_value_start,
+        } => {
+            let discr_ty = get_int_type(type_manager, discr_ty_info);
+            let tag_ty = get_int_type(type_manager, tag_ty_info);
+
+            let into_tag_value = |v: u128| ConstValue::new_int(v, tag_ty).to_value_ref();
+            let into_discr_value = |v: u128| ConstValue::new_int(v, discr_ty).to_value_ref();
+
+            // Based on: `rustc_codegen_ssa::mir::place::PlaceRef::codegen_get_discr`
+            let niche_start = *niche_value_range.start();
+            let tag_value: ValueRef = tag_value.into();
+            let relative_max = niche_value_range.end() - niche_start;
+            let (is_niche, tagged_discr) = if relative_max == 0 {
+                let is_niche = SymValueRef::new(
+                    expr_builder
+                        .borrow_mut()
+                        .inner()
+                        .eq((tag_value.clone(), into_tag_value(*tag_value_start)).into())
+                        .into(),
+                );
+                let tagged_discr = into_discr_value(niche_start);
+                (is_niche, tagged_discr)
+            } else {
+                let relative_tag_value: ValueRef = expr_builder
+                    .borrow_mut()
+                    .inner()
+                    .sub((tag_value.clone(), into_tag_value(*tag_value_start)).into())
+                    .into();
+                let is_niche = SymValueRef::new(
+                    expr_builder
+                        .borrow_mut()
+                        .inner()
+                        .le((relative_tag_value.clone(), into_tag_value(relative_max)).into())
+                        .into(),
+                );
+                let relative_discr_value = expr_builder
+                    .borrow_mut()
+                    .inner()
+                    .to_int(relative_tag_value.into(), discr_ty, discr_ty_info.clone())
+                    .into();
+                let tagged_discr = expr_builder
+                    .borrow_mut()
+                    .inner()
+                    .add((relative_discr_value, into_discr_value(niche_start)).into())
+                    .into();
+                let tagged_discr = expr_builder.borrow_mut().inner().to_int(
+                    tagged_discr,
+                    discr_ty,
+                    discr_ty_info.clone(),
+                );
+                debug_assert!(tagged_discr.is_symbolic());
+                (is_niche, tagged_discr)
+            };
+
+            let discr_value = expr_builder.borrow_mut().inner().if_then_else((
+                is_niche.into(),
+                tagged_discr.into(),
+                into_discr_value(*non_niche_value),
+            ));
+            SymValueRef::new(discr_value)
+        }
+    }
+}
+
+pub(super) fn get_int_type(type_manager: &dyn TypeDatabase, ty_info: &LazyTypeInfo) -> IntType {
+    let ty = type_manager
+        .try_to_value_type(ty_info.clone())
+        .expect("Expected the type of the discriminant raw value to be a primitive");
+    *ty.as_int()
+        // https://doc.rust-lang.org/reference/type-layout.html#primitive-representations
+        .unwrap_or_else(|| {
+            crate::failure::fail!(
+                InconsistentState,
+                "Expected the type of the tag to be a int type: {:?}",
+                ty
+            )
+        })
+}
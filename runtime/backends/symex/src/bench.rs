@@ -0,0 +1,139 @@
+//! Hooks for benchmarking the hot paths of the backend (refer to `benches/`).
+//!
+//! The workloads are driven by PRI event streams captured by the recorder
+//! flavor, so that the measurements reflect real programs. As for the
+//! embedding API, the backend is a single instance per process and needs the
+//! type information of the recorded program.
+//!
+//! The recorded references are replayed as is, so the stream is checked to
+//! match the references that the backend assigns on load. Streams recorded
+//! before the recorder flavor assigned real references should be recorded
+//! again.
+
+use std::{fs::File, io, io::BufReader, path::Path};
+
+use leaf_runtime::pri::{
+    events::{EventReader, PriEvent, Replayer},
+    fluent::InstanceManager,
+};
+
+use crate::{
+    config::Z3TranslationConfig,
+    embed::{EmbeddedBackend, Pri},
+    expr::{
+        prelude::{BinaryExprBuilder, UnaryExprBuilder, ValueRef},
+        translators::z3::Z3ValueTranslator,
+    },
+    instance::SymExInstanceManager,
+};
+
+/// A captured stream loaded on the backend.
+pub struct Workload {
+    _backend: EmbeddedBackend,
+    replayer: Replayer<Pri>,
+    events: Vec<PriEvent>,
+    /// The discriminants of the constraints observed in the stream.
+    values: Vec<ValueRef>,
+}
+
+impl Workload {
+    /// Loads the stream, initializes the backend, and replays the stream once
+    /// to collect the constraints as representative expressions.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        // The backend gets initialized once, so the lifecycle events are not replayed.
+        let events = EventReader::new(BufReader::new(File::open(path)?))?
+            .filter(|event| {
+                event.as_ref().map_or(true, |e| {
+                    !matches!(e.func, "init_runtime_lib" | "shutdown_runtime_lib")
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut workload = Self {
+            _backend: EmbeddedBackend::init(),
+            replayer: Replayer::new(),
+            events,
+            values: Vec::new(),
+        };
+        workload.replay_checked()?;
+        workload.values = SymExInstanceManager::perform_on_backend(|backend| {
+            backend
+                .constraints
+                .borrow()
+                .iter()
+                .map(|c| c.discr.value.clone())
+                .collect()
+        });
+        Ok(workload)
+    }
+
+    pub fn events_count(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn values_count(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Replays the events of the stream while checking the returned references
+    /// against the recorded ones.
+    fn replay_checked(&self) -> io::Result<()> {
+        Self::reset_refs();
+        for (i, event) in self.events.iter().enumerate() {
+            let result = self.replayer.replay(event);
+            if result != event.ret {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "Mismatched reference at event {i} (`{}`): recorded {:?}, got {:?}. \
+                        The stream is probably recorded with an older recorder.",
+                        event.func, event.ret, result,
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes the references assigned in a replay start from the same point as
+    /// the recorded ones.
+    fn reset_refs() {
+        SymExInstanceManager::perform_on_place_ref_manager(|m| *m = Default::default());
+        SymExInstanceManager::perform_on_operand_ref_manager(|m| *m = Default::default());
+    }
+
+    /// Replays the events of the stream.
+    /// Besides handling of the events, this mostly stresses the resolution of
+    /// places in the variables state.
+    /// # Remarks
+    /// The constraints accumulate in the trace with each replay, so the
+    /// inspectors of the trace (e.g., divergence) should be disabled in the
+    /// configuration to exclude solving.
+    pub fn replay(&self) {
+        Self::reset_refs();
+        for event in &self.events {
+            core::hint::black_box(self.replayer.replay(event));
+        }
+    }
+
+    /// Builds the conditions of the collected constraints again, in the same
+    /// way as the decisions are recorded.
+    pub fn build_exprs(&self) {
+        SymExInstanceManager::perform_on_backend(|backend| {
+            let mut builder = backend.expr_builder.borrow_mut();
+            for value in &self.values {
+                let eq = builder.eq((value.clone(), value.clone()));
+                core::hint::black_box(builder.not(eq));
+            }
+        });
+    }
+
+    /// Translates the collected constraints to Z3 with a fresh translator.
+    pub fn translate(&self) {
+        let config = Z3TranslationConfig::default();
+        let mut translator = Z3ValueTranslator::new(config.select_ite_max_size, config.node_budget);
+        for value in &self.values {
+            core::hint::black_box(translator(value));
+        }
+    }
+}
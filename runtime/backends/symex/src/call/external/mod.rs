@@ -0,0 +1,393 @@
+//! Summaries of well-known external functions that take no closure.
+//!
+//! Unlike the higher-order functions, these functions never call back into the
+//! instrumented code, so their whole effect is observed as a single breakage when
+//! they return. Instead of returning an unknown value, the result is built from
+//! the arguments according to the semantics of the function.
+//! As the arguments are mostly references (e.g., to slices), the summaries read the
+//! referenced values from the memory, which is still intact when the call returns.
+//!
+//! The summaries are registered by the key that the compiler tags the calls with,
+//! i.e., the def path of the function consisting of its owner type and its name
+//! (e.g., `slice::contains`). The key is prefixed by an `&` for each reference
+//! wrapping the receiver type of trait methods (e.g., `&slice::eq` for
+//! `<&[u8] as PartialEq>::eq`).
+//!
+//! Some of the summaries also record the constraints that the function implicitly
+//! checks on its symbolic inputs (e.g., the validity of the digits in parsing),
+//! so that the other outcomes of the function are also explored.
+
+mod parse;
+
+use std::{
+    cell::{RefCell, RefMut},
+    collections::HashMap,
+};
+
+use common::log_debug;
+use leaf_runtime::{
+    abs::{ConstraintKind, RawAddress, TypeId},
+    type_info::{TypeInfo, TypeInfoExt},
+};
+
+use super::backend;
+use backend::{
+    GenericVariablesState, Implied, SymExConstraint, SymExExprBuilder, SymExTraceManager,
+    SymExValue, SymExVariablesState, TypeDatabase,
+    expr::{
+        lazy::RawPointerRetriever,
+        prelude::{
+            BinaryExprBuilder, ConcreteValue, ConstValue, DeterministicPlaceValue, FatPtrValue,
+            LazyTypeInfo, UnaryExprBuilder, UnevalValue, Value, ValueRef,
+        },
+    },
+};
+
+/// The maximum number of references followed from an argument to reach the summarized value.
+const MAX_DEREFS: usize = 2;
+
+type SummaryFn = fn(&mut SummaryContext<'_>, &[SymExValue]) -> Option<SymExValue>;
+
+pub(super) struct ExternalSummaries {
+    summaries: HashMap<&'static str, SummaryFn>,
+}
+
+impl ExternalSummaries {
+    pub(super) fn new() -> Self {
+        let mut this = Self {
+            summaries: HashMap::new(),
+        };
+        this.register("slice::eq", slice_eq);
+        this.register("slice::ne", slice_ne);
+        this.register("slice::contains", slice_contains);
+        this.register("slice::starts_with", slice_starts_with);
+        this.register("slice::ends_with", slice_ends_with);
+        this.register("str::eq", slice_eq);
+        this.register("str::ne", slice_ne);
+        this.register("str::from_utf8", parse::from_utf8);
+        for (key, summary) in parse::int_summaries() {
+            this.register(key, summary);
+        }
+        this
+    }
+
+    pub(super) fn register(&mut self, key: &'static str, summary: SummaryFn) {
+        self.summaries.insert(key, summary);
+    }
+
+    /// Returns the call to the summarized function, given the key of the summary tag.
+    /// # Returns
+    /// The pending call along with the key of the summarized function in the configuration.
+    pub(super) fn pending_call<'k>(
+        &self,
+        key: &'k str,
+        args: &[SymExValue],
+    ) -> Option<(PendingSummary, &'k str)> {
+        let func_key = key.trim_start_matches('&');
+        let summary = *self.summaries.get(func_key)?;
+        Some((
+            PendingSummary {
+                summary,
+                key: func_key.to_owned(),
+                receiver_derefs: key.len() - func_key.len(),
+                args: args.to_vec(),
+            },
+            func_key,
+        ))
+    }
+}
+
+/// A call to a summarized external function that has returned, waiting for its
+/// result to be built in the caller.
+pub(crate) struct PendingSummary {
+    summary: SummaryFn,
+    key: String,
+    /// The number of references wrapping the receiver type.
+    receiver_derefs: usize,
+    args: Vec<SymExValue>,
+}
+
+impl PendingSummary {
+    /// Builds the result of the call.
+    /// # Returns
+    /// The result of the function if it is expressible by the summary and depends
+    /// on some symbolic value, otherwise `None`, in which case the concrete result
+    /// holds.
+    pub(super) fn apply(
+        self,
+        vars_state: &SymExVariablesState,
+        type_manager: &dyn TypeDatabase,
+        expr_builder: RefMut<'_, SymExExprBuilder>,
+        trace_manager: &RefCell<SymExTraceManager>,
+    ) -> Option<SymExValue> {
+        let mut context = SummaryContext {
+            key: &self.key,
+            vars_state,
+            type_manager,
+            expr_builder,
+            trace_manager,
+            receiver_derefs: self.receiver_derefs,
+        };
+        let result = (self.summary)(&mut context, &self.args);
+        log_debug!(
+            "Summarized the call to {}: {}",
+            self.key,
+            result
+                .as_ref()
+                .map_or("concrete".to_owned(), |r| format!("{r:?}")),
+        );
+        result
+    }
+}
+
+struct SummaryContext<'a> {
+    /// The key of the summarized function, e.g., `u32::from_str`.
+    key: &'a str,
+    vars_state: &'a SymExVariablesState,
+    type_manager: &'a dyn TypeDatabase,
+    expr_builder: RefMut<'a, SymExExprBuilder>,
+    trace_manager: &'a RefCell<SymExTraceManager>,
+    receiver_derefs: usize,
+}
+
+impl SummaryContext<'_> {
+    /// Reads the items of the slice (or `str`) referenced by the receiver argument.
+    fn read_receiver_slice(&self, arg: &SymExValue) -> Option<Vec<SymExValue>> {
+        self.read_slice(arg, self.receiver_derefs)
+    }
+
+    /// Reads the items of the slice (or `str`) referenced by the argument.
+    /// # Returns
+    /// The items if the reference is concrete and the items are of primitive types.
+    fn read_slice(&self, arg: &SymExValue, derefs: usize) -> Option<Vec<SymExValue>> {
+        let (addr, len, item_ty) = self.slice_parts(arg, derefs)?;
+        Some(
+            (0..len)
+                .map(|i| {
+                    self.read(
+                        addr.wrapping_byte_add(i * item_ty.size as usize),
+                        item_ty.id,
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the address, the length, and the item type of the slice (or `str`)
+    /// referenced by the argument, if its items are of a primitive type.
+    fn slice_parts(
+        &self,
+        arg: &SymExValue,
+        derefs: usize,
+    ) -> Option<(RawAddress, usize, &'static TypeInfo)> {
+        let fat_ptr = self.as_fat_ptr(&self.deref_n(arg, derefs)?)?;
+        let pointee = self
+            .type_manager
+            .get_type(&self.type_manager.get_pointee_ty(&fat_ptr.ty)?);
+        if !pointee.is_slice() {
+            return None;
+        }
+        let item_ty = self.type_manager.get_type(&pointee.expect_array().item_ty);
+        self.type_manager
+            .try_to_value_type(LazyTypeInfo::from(item_ty.id))?;
+
+        let addr = fat_ptr
+            .address
+            .expect_addr(self.type_manager, self.retriever());
+        let len = fat_ptr
+            .metadata
+            .expect_int(self.type_manager, self.retriever()) as usize;
+        Some((addr, len, item_ty))
+    }
+
+    /// Returns the value of the argument if it is a concrete integer.
+    fn concrete_int(&self, arg: &SymExValue) -> Option<u128> {
+        let conc_value = self.as_concrete(&arg.value)?;
+        matches!(
+            conc_value,
+            ConcreteValue::Const(ConstValue::Int { .. })
+                | ConcreteValue::Unevaluated(UnevalValue::Lazy(..))
+        )
+        .then(|| conc_value.expect_int(self.type_manager, self.retriever()))
+    }
+
+    /// Reads the primitive value referenced by the argument.
+    fn read_pointee(&self, arg: &SymExValue) -> Option<SymExValue> {
+        let pointee_ty = self
+            .type_manager
+            .get_pointee_ty(&self.type_of(&arg.value)?.id)?;
+        self.type_manager
+            .try_to_value_type(LazyTypeInfo::from(pointee_ty))?;
+        self.deref_n(arg, 1)
+    }
+
+    /// Follows `n` (thin) references from the value.
+    fn deref_n(&self, value: &SymExValue, n: usize) -> Option<SymExValue> {
+        if n > MAX_DEREFS {
+            return None;
+        }
+        let mut value = value.clone();
+        for _ in 0..n {
+            let ptr_ty = self.type_of(&value.value)?;
+            let pointee_ty = self.type_manager.get_pointee_ty(&ptr_ty.id)?;
+            if !self.type_manager.get_type(&pointee_ty).is_sized() {
+                return None;
+            }
+            let addr = self
+                .as_concrete(&value.value)?
+                .expect_addr(self.type_manager, self.retriever());
+            value = self.read(addr, pointee_ty);
+        }
+        Some(value)
+    }
+
+    fn as_fat_ptr(&self, value: &SymExValue) -> Option<FatPtrValue> {
+        let conc_value = self.as_concrete(&value.value)?;
+        match conc_value {
+            ConcreteValue::FatPointer(fat_ptr) => Some(fat_ptr.clone()),
+            ConcreteValue::Unevaluated(UnevalValue::Lazy(..)) => {
+                let pointee_ty = self
+                    .type_manager
+                    .get_pointee_ty(&self.type_of(&value.value)?.id)?;
+                (!self.type_manager.get_type(&pointee_ty).is_sized())
+                    .then(|| conc_value.expect_fat_ptr(self.type_manager, self.retriever()))
+            }
+            _ => None,
+        }
+    }
+
+    fn as_concrete<'v>(&self, value: &'v ValueRef) -> Option<&'v ConcreteValue> {
+        match value.as_ref() {
+            Value::Concrete(conc_value) => Some(conc_value),
+            Value::Symbolic(..) => None,
+        }
+    }
+
+    /// Returns the type of the value if it is available, i.e., for values read lazily from the memory.
+    fn type_of<'v>(&'v self, value: &'v ValueRef) -> Option<&'v TypeInfo> {
+        match self.as_concrete(value)? {
+            ConcreteValue::Unevaluated(UnevalValue::Lazy(raw)) => raw.1.get_type(self.type_manager),
+            _ => None,
+        }
+    }
+
+    fn read(&self, addr: RawAddress, ty: TypeId) -> SymExValue {
+        self.vars_state
+            .copy_place(&DeterministicPlaceValue::from_addr_type(addr, ty).to_value_ref())
+    }
+
+    fn retriever(&self) -> &dyn RawPointerRetriever {
+        self.vars_state
+    }
+
+    /// Records the constraint that the function has implicitly checked on its inputs.
+    /// # Arguments
+    /// * `discr` - The boolean condition checked.
+    /// * `holds` - Whether the condition has held in the concrete execution.
+    fn constrain(&self, discr: SymExValue, holds: bool) {
+        if !discr.is_symbolic() {
+            return;
+        }
+        let constraint = SymExConstraint {
+            discr,
+            kind: if holds {
+                ConstraintKind::True
+            } else {
+                ConstraintKind::False
+            },
+        };
+        self.trace_manager
+            .borrow_mut()
+            .notify_step(Default::default(), constraint);
+    }
+
+    /// Returns whether all the corresponding items are equal.
+    fn all_eq(&mut self, first: Vec<SymExValue>, second: Vec<SymExValue>) -> Option<SymExValue> {
+        debug_assert_eq!(first.len(), second.len());
+        if !first.iter().chain(&second).any(|v| v.is_symbolic()) {
+            return None;
+        }
+        let eqs: Vec<_> = first
+            .into_iter()
+            .zip(second)
+            .map(|pair| self.expr_builder.eq(pair))
+            .collect();
+        eqs.into_iter()
+            .reduce(|acc, eq| self.expr_builder.and((acc, eq)))
+    }
+}
+
+fn bool_const(value: bool) -> SymExValue {
+    Implied::always(ConstValue::Bool(value).to_value_ref())
+}
+
+/// `<[T] as PartialEq>::eq` and `<str as PartialEq>::eq`
+fn slice_eq(cx: &mut SummaryContext<'_>, args: &[SymExValue]) -> Option<SymExValue> {
+    let [first, second] = args else {
+        return None;
+    };
+    let first = cx.read_receiver_slice(first)?;
+    let second = cx.read_receiver_slice(second)?;
+    if first.len() != second.len() {
+        // The lengths are concrete, so is the result.
+        return None;
+    }
+    cx.all_eq(first, second)
+}
+
+/// `<[T] as PartialEq>::ne` and `<str as PartialEq>::ne`
+fn slice_ne(cx: &mut SummaryContext<'_>, args: &[SymExValue]) -> Option<SymExValue> {
+    let eq = slice_eq(cx, args)?;
+    Some(cx.expr_builder.not(eq))
+}
+
+/// `<[T]>::contains`
+fn slice_contains(cx: &mut SummaryContext<'_>, args: &[SymExValue]) -> Option<SymExValue> {
+    let [slice, item] = args else {
+        return None;
+    };
+    let items = cx.read_slice(slice, 0)?;
+    let item = cx.read_pointee(item)?;
+    if !items.iter().chain([&item]).any(|v| v.is_symbolic()) {
+        return None;
+    }
+    let eqs: Vec<_> = items
+        .into_iter()
+        .map(|x| cx.expr_builder.eq((x, item.clone())))
+        .collect();
+    Some(
+        eqs.into_iter()
+            .reduce(|acc, eq| cx.expr_builder.or((acc, eq)))
+            .unwrap_or_else(|| bool_const(false)),
+    )
+}
+
+/// `<[T]>::starts_with`
+fn slice_starts_with(cx: &mut SummaryContext<'_>, args: &[SymExValue]) -> Option<SymExValue> {
+    slice_affix(cx, args, |items, n| items.truncate(n))
+}
+
+/// `<[T]>::ends_with`
+fn slice_ends_with(cx: &mut SummaryContext<'_>, args: &[SymExValue]) -> Option<SymExValue> {
+    slice_affix(cx, args, |items, n| {
+        items.drain(..items.len() - n);
+    })
+}
+
+fn slice_affix(
+    cx: &mut SummaryContext<'_>,
+    args: &[SymExValue],
+    keep: impl FnOnce(&mut Vec<SymExValue>, usize),
+) -> Option<SymExValue> {
+    let [slice, needle] = args else {
+        return None;
+    };
+    let mut items = cx.read_slice(slice, 0)?;
+    let needle = cx.read_slice(needle, 0)?;
+    // Otherwise, the result is concrete.
+    if needle.is_empty() || needle.len() > items.len() {
+        return None;
+    }
+    keep(&mut items, needle.len());
+    cx.all_eq(items, needle)
+}
@@ -0,0 +1,300 @@
+//! Models of parsing strings, the most common entry point of symbolic bytes.
+//!
+//! The models follow the concrete execution of the function and record the checks
+//! it performs on the bytes as constraints, as the instrumented version would do.
+//! For example, parsing `"12"` as an integer records that both bytes are digits,
+//! and negating any of them leads to an input on which the parsing fails.
+
+use leaf_runtime::{
+    abs::{IntType, backend::CoreTypeProvider},
+    type_info::TypeInfo,
+};
+
+use super::{super::summary::wrap_in_variant, SummaryContext, SummaryFn, backend, bool_const};
+use backend::{
+    Implied, SymExValue,
+    expr::prelude::{
+        BinaryExprBuilder, CastExprBuilder, ConstValue, LazyTypeInfo, TernaryExprBuilder,
+    },
+};
+
+macro_rules! int_keys {
+    ($($ty:ident),* $(,)?) => {
+        [$(
+            (concat!(stringify!($ty), "::from_str_radix"), from_str_radix as SummaryFn),
+            (concat!(stringify!($ty), "::from_str"), from_str as SummaryFn),
+        )*]
+    };
+}
+
+/// The summaries of parsing integers, i.e., `from_str_radix` and `from_str` (used by
+/// `str::parse`) for all integer types.
+pub(super) fn int_summaries() -> impl Iterator<Item = (&'static str, SummaryFn)> {
+    int_keys!(
+        u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+    )
+    .into_iter()
+}
+
+/// `<int>::from_str_radix`
+fn from_str_radix(cx: &mut SummaryContext<'_>, args: &[SymExValue]) -> Option<SymExValue> {
+    let [src, radix] = args else {
+        return None;
+    };
+    let radix = cx.concrete_int(radix)? as u32;
+    parse_int(cx, src, radix)
+}
+
+/// `<int as FromStr>::from_str`
+fn from_str(cx: &mut SummaryContext<'_>, args: &[SymExValue]) -> Option<SymExValue> {
+    let [src] = args else {
+        return None;
+    };
+    parse_int(cx, src, 10)
+}
+
+/// Models the parsing of the integer type given by the owner in the key of the function.
+/// # Remarks
+/// The result is only modeled if the number of digits guarantees no overflow, as
+/// otherwise the value is not expressible without the checks on each digit.
+fn parse_int(cx: &mut SummaryContext<'_>, src: &SymExValue, radix: u32) -> Option<SymExValue> {
+    if !(2..=36).contains(&radix) {
+        return None;
+    }
+    let ty = int_type_of(cx.key.split_once("::")?.0)?;
+    let (bytes, conc_bytes) = read_bytes(cx, src)?;
+    let len = bytes.len();
+
+    // Mirroring `from_str_radix`
+    let (is_negative, start) = match conc_bytes.as_slice() {
+        [] => return None,
+        [b'+' | b'-'] => (false, 0),
+        [b'+', ..] => (false, 1),
+        [b'-', ..] if ty.is_signed => (true, 1),
+        _ => (false, 0),
+    };
+    if len > 1 {
+        let sign_check = if start == 0 {
+            let is_plus = cx.expr_builder.eq((bytes[0].clone(), u8_const(b'+')));
+            if ty.is_signed {
+                let is_minus = cx.expr_builder.eq((bytes[0].clone(), u8_const(b'-')));
+                cx.expr_builder.or((is_plus, is_minus))
+            } else {
+                is_plus
+            }
+        } else {
+            let sign = if is_negative { b'-' } else { b'+' };
+            cx.expr_builder.eq((bytes[0].clone(), u8_const(sign)))
+        };
+        cx.constrain(sign_check, start == 1);
+    }
+
+    let mut digits = Vec::with_capacity(len - start);
+    for (byte, conc_byte) in bytes.into_iter().zip(conc_bytes).skip(start) {
+        let (is_valid, digit) = digit_of(cx, byte, radix);
+        let is_conc_valid = char::from(conc_byte).to_digit(radix).is_some();
+        cx.constrain(is_valid, is_conc_valid);
+        if !is_conc_valid {
+            // The error is concrete.
+            return None;
+        }
+        digits.push(digit);
+    }
+
+    let max = if ty.is_signed {
+        (1u128 << (ty.bit_size - 1)) - 1
+    } else {
+        u128::MAX >> (u128::BITS as u64 - ty.bit_size)
+    };
+    let may_overflow = (radix as u128)
+        .checked_pow(digits.len() as u32)
+        .is_none_or(|bound| bound - 1 > max);
+    if may_overflow || !digits.iter().any(|d| d.is_symbolic()) {
+        return None;
+    }
+
+    let ty_info = CoreTypeProvider::<LazyTypeInfo>::int_type(cx.type_manager, ty);
+    let int_const = |value: u128| Implied::always(ConstValue::new_int(value, ty).to_value_ref());
+    let value = digits
+        .into_iter()
+        .map(|digit| cx.expr_builder.to_int(digit, ty, ty_info.clone()))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .reduce(|acc, digit| {
+            let shifted = cx.expr_builder.mul((acc, int_const(radix as u128)));
+            cx.expr_builder.add((shifted, digit))
+        })?;
+    let value = if is_negative {
+        cx.expr_builder.sub((int_const(0), value))
+    } else {
+        value
+    };
+    // `Ok`
+    Some(wrap_in_variant(value, 0))
+}
+
+/// `core::str::from_utf8`
+/// # Remarks
+/// The result consists of concrete pointers to the bytes, thus only the checks
+/// are recorded.
+pub(super) fn from_utf8(cx: &mut SummaryContext<'_>, args: &[SymExValue]) -> Option<SymExValue> {
+    let [src] = args else {
+        return None;
+    };
+    let (bytes, conc_bytes) = read_bytes(cx, src)?;
+    if !bytes.iter().any(|b| b.is_symbolic()) {
+        return None;
+    }
+
+    let (valid_up_to, is_valid) = match core::str::from_utf8(&conc_bytes) {
+        Ok(_) => (conc_bytes.len(), true),
+        Err(err) => (err.valid_up_to(), false),
+    };
+    // The valid characters are checked as the concrete execution has decoded them.
+    let valid = core::str::from_utf8(&conc_bytes[..valid_up_to]).unwrap();
+    let mut start = 0;
+    for ch in valid.chars() {
+        let width = ch.len_utf8();
+        let is_valid = utf8_sequence(cx, &bytes[start..start + width]);
+        cx.constrain(is_valid, true);
+        start += width;
+    }
+    // No valid sequence of any width starts at the position of the error.
+    if !is_valid {
+        let at = valid_up_to;
+        let is_valid = (1..=4)
+            .filter(|width| at + width <= bytes.len())
+            .map(|width| utf8_sequence(cx, &bytes[at..at + width]))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .reduce(|acc, is_valid| cx.expr_builder.or((acc, is_valid)))
+            .unwrap_or_else(|| bool_const(false));
+        cx.constrain(is_valid, false);
+    }
+    None
+}
+
+/// Returns whether the bytes form a valid UTF-8 encoded character, following the
+/// well-formed byte sequences of the Unicode standard (Table 3-7).
+fn utf8_sequence(cx: &mut SummaryContext<'_>, bytes: &[SymExValue]) -> SymExValue {
+    const CONT: (u8, u8) = (0x80, 0xBF);
+    let conds = match bytes {
+        [b0] => vec![in_range(cx, b0, (0x00, 0x7F))],
+        [b0, b1] => vec![in_range(cx, b0, (0xC2, 0xDF)), in_range(cx, b1, CONT)],
+        [b0, b1, b2] => vec![
+            // The second byte depends on the first one to reject overlongs and surrogates.
+            lead_cases_of(
+                cx,
+                (b0, b1),
+                &[
+                    ((0xE0, 0xE0), (0xA0, 0xBF)),
+                    ((0xE1, 0xEC), CONT),
+                    ((0xED, 0xED), (0x80, 0x9F)),
+                    ((0xEE, 0xEF), CONT),
+                ],
+            ),
+            in_range(cx, b2, CONT),
+        ],
+        [b0, b1, b2, b3] => vec![
+            lead_cases_of(
+                cx,
+                (b0, b1),
+                &[
+                    ((0xF0, 0xF0), (0x90, 0xBF)),
+                    ((0xF1, 0xF3), CONT),
+                    ((0xF4, 0xF4), (0x80, 0x8F)),
+                ],
+            ),
+            in_range(cx, b2, CONT),
+            in_range(cx, b3, CONT),
+        ],
+        _ => unreachable!("Invalid width of a UTF-8 sequence: {}", bytes.len()),
+    };
+    conds
+        .into_iter()
+        .reduce(|acc, cond| cx.expr_builder.and((acc, cond)))
+        .unwrap()
+}
+
+/// Returns whether the first two bytes fall in the ranges of any of the cases.
+fn lead_cases_of(
+    cx: &mut SummaryContext<'_>,
+    (b0, b1): (&SymExValue, &SymExValue),
+    cases: &[((u8, u8), (u8, u8))],
+) -> SymExValue {
+    let cases: Vec<_> = cases
+        .iter()
+        .map(|&(range0, range1)| {
+            let first = in_range(cx, b0, range0);
+            let second = in_range(cx, b1, range1);
+            cx.expr_builder.and((first, second))
+        })
+        .collect();
+    cases
+        .into_iter()
+        .reduce(|acc, case| cx.expr_builder.or((acc, case)))
+        .unwrap()
+}
+
+fn in_range(cx: &mut SummaryContext<'_>, byte: &SymExValue, (low, high): (u8, u8)) -> SymExValue {
+    let lower = cx.expr_builder.ge((byte.clone(), u8_const(low)));
+    let upper = cx.expr_builder.le((byte.clone(), u8_const(high)));
+    cx.expr_builder.and((lower, upper))
+}
+
+/// Returns whether the byte is a digit in the radix and its value as a digit,
+/// following `char::to_digit`.
+fn digit_of(cx: &mut SummaryContext<'_>, byte: SymExValue, radix: u32) -> (SymExValue, SymExValue) {
+    let decimal = cx.expr_builder.sub((byte.clone(), u8_const(b'0')));
+    if radix <= 10 {
+        let is_valid = cx.expr_builder.lt((decimal.clone(), u8_const(radix as u8)));
+        return (is_valid, decimal);
+    }
+
+    let is_decimal = cx.expr_builder.lt((decimal.clone(), u8_const(10)));
+    // Lowercasing for letters
+    let lower = cx.expr_builder.or((byte, u8_const(0x20)));
+    let alphabetic = cx.expr_builder.sub((lower, u8_const(b'a')));
+    let is_alphabetic = cx
+        .expr_builder
+        .lt((alphabetic.clone(), u8_const(radix as u8 - 10)));
+    let is_valid = cx.expr_builder.or((is_decimal.clone(), is_alphabetic));
+    let alphabetic = cx.expr_builder.add((alphabetic, u8_const(10)));
+    let digit = cx
+        .expr_builder
+        .if_then_else((is_decimal, decimal, alphabetic));
+    (is_valid, digit)
+}
+
+/// Reads the bytes of the `str` (or `[u8]`) referenced by the argument, both as
+/// values and concretely.
+fn read_bytes(cx: &SummaryContext<'_>, arg: &SymExValue) -> Option<(Vec<SymExValue>, Vec<u8>)> {
+    let (addr, len, item_ty) = cx.slice_parts(arg, 0)?;
+    if item_ty.id != CoreTypeProvider::<&TypeInfo>::u8(cx.type_manager).id {
+        return None;
+    }
+    let bytes = cx.read_slice(arg, 0)?;
+    // The bytes are still alive as the referencing argument is.
+    let conc_bytes = unsafe { core::slice::from_raw_parts(addr as *const u8, len) }.to_vec();
+    Some((bytes, conc_bytes))
+}
+
+fn int_type_of(name: &str) -> Option<IntType> {
+    let is_signed = match name.as_bytes().first()? {
+        b'i' => true,
+        b'u' => false,
+        _ => return None,
+    };
+    let bit_size = match &name[1..] {
+        "size" => usize::BITS as u64,
+        bits => bits.parse().ok()?,
+    };
+    Some(IntType {
+        bit_size,
+        is_signed,
+    })
+}
+
+fn u8_const(value: u8) -> SymExValue {
+    Implied::always(ConstValue::from(value).to_value_ref())
+}
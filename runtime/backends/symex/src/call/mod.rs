@@ -1,4 +1,6 @@
-use std::cell::RefMut;
+use core::iter;
+
+use std::cell::RefCell;
 
 use leaf_runtime::{
     abs::{
@@ -9,70 +11,260 @@ use leaf_runtime::{
         CallControlFlowManager, CallDataFlowManager, CallFlowManager, CallShadowMemory,
         DefaultCallFlowManager, SignaturePlaces, tupling::ArgsTuplingInfo,
     },
-    pri::fluent::backend::{ArgsTupling, CallHandler, DropHandler},
-    utils::InPlaceSelfHierarchical,
+    pri::{
+        fast_path,
+        fluent::backend::{ArgsTupling, CallHandler, DropHandler},
+    },
+    utils::{InPlaceSelfHierarchical, RRef},
 };
 
+use common::{log_debug, log_warn};
+
+mod external;
+mod summary;
+
+pub(crate) use external::PendingSummary;
+
 use super::alias::backend;
 use backend::{
-    GenericVariablesState, Implied, PlaceValueRef, SymExBackend, SymExValue, SymExVariablesState,
-    TypeDatabase, Value, config::CallConfig, expr::prelude::DeterPlaceValueRef,
+    ConcreteValue, ConcreteValueRef, ConstValue, GenericVariablesState, Implied, PlaceValueRef,
+    SymExBackend, SymExExprBuilder, SymExTraceManager, SymExValue, SymExVariablesState,
+    SymValueRef, Tag, TypeDatabase, Value, concrete::Concretizer, config::CallConfig,
+    expr::prelude::DeterPlaceValueRef,
 };
 
 pub(super) type SymExCallFlowManager =
     DefaultCallFlowManager<DeterPlaceValueRef, SymExValue, breakage::SymExBreakageCallback>;
 
-pub(crate) fn default_flow_manager(config: CallConfig) -> SymExCallFlowManager
+pub(crate) fn default_flow_manager(
+    config: CallConfig,
+    tags: RRef<Vec<Tag>>,
+    concretization: RRef<Option<Concretization>>,
+    pending_summary: RRef<Option<PendingSummary>>,
+) -> SymExCallFlowManager
 where
     SymExCallFlowManager: CallControlFlowManager
         + CallDataFlowManager<Place = DeterPlaceValueRef, Value = SymExValue>,
 {
     DefaultCallFlowManager::new(breakage::SymExBreakageCallback {
         strategy: config.external_call,
+        tags,
+        summarizer: summary::CallSummarizer::new(config.summaries),
+        concretization,
+        pending_summary,
     })
 }
 
+/// The places being dropped by the drop calls in progress.
+/// A place is invalidated once the drop glue for it returns.
+#[derive(Default)]
+pub(crate) struct DroppedPlaces {
+    stack: Vec<Option<PlaceValueRef>>,
+    /// Whether the top entry is pushed by the control part of the drop being
+    /// prepared, and is waiting for its data part.
+    is_top_preparing: bool,
+}
+
+impl DroppedPlaces {
+    fn start(&mut self) {
+        self.stack.push(None);
+        self.is_top_preparing = true;
+    }
+
+    fn set_place(&mut self, place: PlaceValueRef) {
+        if !core::mem::take(&mut self.is_top_preparing) {
+            self.stack.push(None);
+        }
+        *self.stack.last_mut().unwrap() = Some(place);
+    }
+
+    fn finish(&mut self) -> Option<PlaceValueRef> {
+        self.is_top_preparing = false;
+        self.stack
+            .pop()
+            .expect("Drop is finished without being started.")
+    }
+}
+
+/// The depth of the call stack, used to track the frames beyond the limit in a
+/// summarized mode, where neither the call flow nor the data flow is followed.
+/// The calls into such frames are seen as external calls with unknown returned
+/// values by the caller.
+pub(crate) struct CallDepth {
+    current: usize,
+    limit: Option<usize>,
+}
+
+impl CallDepth {
+    pub(crate) fn new(limit: Option<usize>) -> Self {
+        Self { current: 0, limit }
+    }
+
+    pub(crate) fn is_summarized(&self) -> bool {
+        self.limit.is_some_and(|limit| self.current > limit)
+    }
+
+    /// Whether the callee of a call made in the current frame is summarized.
+    fn is_calling_summarized(&self) -> bool {
+        self.limit.is_some_and(|limit| self.current >= limit)
+    }
+
+    fn enter(&mut self) {
+        self.current += 1;
+        if self.limit.is_some_and(|limit| self.current == limit + 1) {
+            log_warn!(
+                "Call depth limit ({}) is reached, the deeper frames are summarized.",
+                self.current - 1,
+            );
+        }
+    }
+
+    fn ret(&mut self) {
+        self.current -= 1;
+    }
+}
+
+/// Whether the value may carry symbolic data to the callee, i.e., it is symbolic
+/// or it is not a scalar (thus may point to symbolic data).
+fn may_carry_symbolic(value: &SymExValue) -> bool {
+    !matches!(
+        value.value.as_ref(),
+        Value::Concrete(ConcreteValue::Const(
+            ConstValue::Bool(_)
+                | ConstValue::Char(_)
+                | ConstValue::Int { .. }
+                | ConstValue::Float { .. }
+        ))
+    )
+}
+
+/// The concretization of the value returned from an external call, requested
+/// by the `concretize` annotation.
+/// The symbolic argument of the call is passed through as the returned value,
+/// then replaced by the concrete value when the call is finalized.
+pub(crate) struct Concretization {
+    /// The concrete value, if it is resolvable to a constant.
+    value: Option<ConstValue>,
+}
+
 pub(crate) struct SymExCallHandler<'a> {
     flow_manager: &'a mut SymExCallFlowManager,
+    dropped_places: &'a mut DroppedPlaces,
+    call_depth: &'a mut CallDepth,
     variables_state: &'a mut SymExVariablesState,
     variables_state_factory: &'a dyn Fn() -> SymExVariablesState,
     type_manager: &'a dyn TypeDatabase,
     #[cfg(feature = "implicit_flow")]
     implication_investigator: &'a dyn super::ImplicationInvestigator,
-    trace_recorder: RefMut<'a, dyn PhasedCallTraceRecorder>,
+    trace_recorder: &'a RefCell<dyn PhasedCallTraceRecorder>,
+    concretization: &'a RefCell<Option<Concretization>>,
+    concretizer: &'a mut dyn Concretizer,
+    pending_summary: &'a RefCell<Option<PendingSummary>>,
+    expr_builder: &'a RefCell<SymExExprBuilder>,
+    trace_manager: &'a RefCell<SymExTraceManager>,
 }
 
 impl<'a> SymExCallHandler<'a> {
     pub(super) fn new(backend: &'a mut SymExBackend) -> Self {
+        let thread = backend.threads.current();
         Self {
             flow_manager: &mut backend.call_flow_manager,
-            variables_state: &mut backend.vars_state,
-            variables_state_factory: &backend.vars_state_factory,
+            dropped_places: &mut thread.dropped_places,
+            call_depth: &mut thread.call_depth,
+            variables_state: &mut thread.vars_state,
+            variables_state_factory: &*backend.vars_state_factory,
             type_manager: backend.type_manager.as_ref(),
             #[cfg(feature = "implicit_flow")]
             implication_investigator: backend.implication_investigator.as_ref(),
-            trace_recorder: backend.trace_recorder.borrow_mut(),
+            trace_recorder: &*backend.trace_recorder,
+            concretization: &*backend.concretization,
+            concretizer: &mut *backend.concretizer,
+            pending_summary: &*backend.pending_summary,
+            expr_builder: &*backend.expr_builder,
+            trace_manager: &*backend.trace_manager,
         }
     }
 
     fn current_func(&self) -> FuncDef {
         self.flow_manager.current_func()
     }
+
+    /// Finalizes the call in progress and returns the value returned from it,
+    /// unless the data flow is not tracked for the current frame.
+    fn finalize_call(&mut self) -> Option<SymExValue> {
+        if self.call_depth.is_summarized() {
+            return None;
+        } else if self.call_depth.is_calling_summarized() {
+            return Some(breakage::unknown_value());
+        }
+
+        let token = self.flow_manager.finalize_call();
+        let caller = self
+            .trace_recorder
+            .borrow_mut()
+            .finish_return(token.sanity().is_broken().unwrap());
+        debug_assert_eq!(caller, self.current_func());
+        Some(self.flow_manager.give_return_value(token))
+    }
+
+    /// Invalidates the place of the drop in progress.
+    fn finish_drop(&mut self) {
+        // The pointee of a symbolic pointer cannot be determined, so it is left as is.
+        if let Some(place) = self
+            .dropped_places
+            .finish()
+            .filter(|place| !place.is_symbolic())
+        {
+            self.variables_state.drop_place(&place);
+        }
+    }
+
+    /// Replaces the symbolic value returned from a call with its concrete value,
+    /// while the symbolic value is constrained to be equal to it.
+    fn concretize(&mut self, concretization: Concretization, value: SymExValue) -> SymExValue {
+        if !value.is_symbolic() {
+            return value;
+        }
+
+        let Some(conc_value) = concretization.value else {
+            log_warn!(
+                "Concretizing a value not resolvable to a constant without constraining it: {:?}",
+                value,
+            );
+            return breakage::unknown_value();
+        };
+        log_debug!("Concretizing the returned value to {}", conc_value);
+        let conc_value = self.concretizer.stamp(
+            SymValueRef::new(value.value),
+            Box::new(move || ConcreteValueRef::new(conc_value.to_value_ref())),
+        );
+        Implied::by_unknown(conc_value.into())
+    }
 }
 
 impl<'a> CallHandler for SymExCallHandler<'a> {
     type Place = PlaceValueRef;
     type Operand = SymExValue;
 
-    fn before_call(mut self, def: CalleeDef, call_site: BasicBlockIndex) {
+    fn before_call(self, def: CalleeDef, call_site: BasicBlockIndex) {
+        if self.call_depth.is_calling_summarized() {
+            return;
+        }
+
         let call_site = self.current_func().at_basic_block(call_site);
-        self.trace_recorder.start_call(call_site);
+        self.trace_recorder
+            .borrow_mut()
+            .start_call(call_site, Some(def));
         self.flow_manager.prepare_for_calling(def);
     }
 
-    fn before_call_some(mut self) {
+    fn before_call_some(self) {
+        if self.call_depth.is_calling_summarized() {
+            return;
+        }
+
         let call_site = self.current_func().at_basic_block(Default::default());
-        self.trace_recorder.start_call(call_site);
+        self.trace_recorder.borrow_mut().start_call(call_site, None);
         self.flow_manager.prepare_for_call();
     }
 
@@ -82,16 +274,31 @@ impl<'a> CallHandler for SymExCallHandler<'a> {
         args: impl IntoIterator<Item = Self::Operand>,
         are_args_tupled: bool,
     ) {
-        self.flow_manager.prepare_for_call_with_values(
-            func,
-            args.into_iter().collect(),
-            are_args_tupled,
-        );
+        if self.call_depth.is_calling_summarized() {
+            return;
+        }
+
+        let args: Vec<_> = args.into_iter().collect();
+        fast_path::notify_call(args.iter().any(may_carry_symbolic));
+        self.flow_manager
+            .prepare_for_call_with_values(func, args, are_args_tupled);
+    }
+
+    fn start_thread(self) {
+        self.flow_manager.start_thread();
     }
 
-    fn enter(mut self, def: FuncDef) {
+    fn enter(self, def: FuncDef) {
+        self.dropped_places.is_top_preparing = false;
+        self.call_depth.enter();
+        if self.call_depth.is_summarized() {
+            return;
+        }
+
         let sanity = self.flow_manager.enter(def);
-        self.trace_recorder.finish_call(def, sanity.is_broken());
+        self.trace_recorder
+            .borrow_mut()
+            .finish_call(def, sanity.is_broken());
     }
 
     fn emplace_arguments(
@@ -100,6 +307,18 @@ impl<'a> CallHandler for SymExCallHandler<'a> {
         ret_val_place: Self::Place,
         tupling: ArgsTupling,
     ) {
+        if self.call_depth.is_summarized() {
+            // The places may hold stale symbolic values from the earlier frames.
+            for place in arg_places.iter().chain(iter::once(&ret_val_place)) {
+                CallShadowMemory::set_place(
+                    self.variables_state,
+                    &DeterPlaceValueRef::new(place.clone()),
+                    breakage::unknown_value(),
+                );
+            }
+            return;
+        }
+
         fn ensure_deter_place(place: PlaceValueRef) -> DeterPlaceValueRef {
             debug_assert!(!place.is_symbolic());
             DeterPlaceValueRef::new(place)
@@ -126,12 +345,33 @@ impl<'a> CallHandler for SymExCallHandler<'a> {
 
     #[inline]
     fn override_return_value(self, value: Self::Operand) {
+        if self.call_depth.is_summarized() {
+            return;
+        }
+
         self.flow_manager.override_return_value(value)
     }
 
+    fn concretize_return_value(self, value: Self::Operand) {
+        if self.call_depth.is_summarized() {
+            return;
+        }
+
+        // Resolved right away, as the value may reside in the frame of the callee.
+        let value = ConcreteValueRef::new(value.value).try_resolve_as_const(self.type_manager);
+        *self.concretization.borrow_mut() = Some(Concretization { value });
+    }
+
     #[inline]
-    fn ret(mut self, ret_point: BasicBlockIndex) {
+    fn ret(self, ret_point: BasicBlockIndex) {
+        let is_summarized = self.call_depth.is_summarized();
+        self.call_depth.ret();
+        if is_summarized {
+            return;
+        }
+
         self.trace_recorder
+            .borrow_mut()
             .start_return(self.flow_manager.current_func().at_basic_block(ret_point));
         let token = self.flow_manager.start_return();
         self.flow_manager
@@ -143,19 +383,32 @@ impl<'a> CallHandler for SymExCallHandler<'a> {
     fn after_call(mut self, assignment_id: AssignmentId, result_dest: Self::Place) {
         debug_assert!(!result_dest.is_symbolic());
 
-        let token = self.flow_manager.finalize_call();
-        let caller = self
-            .trace_recorder
-            .finish_return(token.sanity().is_broken().unwrap());
-        debug_assert_eq!(caller, self.current_func());
-
-        let mut return_val = self.flow_manager.give_return_value(token);
+        let concretization = self.concretization.take();
+        let return_val = self.finalize_call();
+        // Set by the breakage of the call, if any.
+        let pending_summary = self.pending_summary.take();
+        let Some(mut return_val) = return_val else {
+            return;
+        };
+        if let Some(concretization) = concretization {
+            return_val = self.concretize(concretization, return_val);
+        }
+        if let Some(summarized) = pending_summary.and_then(|pending| {
+            pending.apply(
+                self.variables_state,
+                self.type_manager,
+                self.expr_builder.borrow_mut(),
+                self.trace_manager,
+            )
+        }) {
+            return_val = summarized;
+        }
 
         #[cfg(feature = "implicit_flow")]
         super::assignment::precondition::add_antecedent(
             self.implication_investigator,
             || result_dest.type_info().get_size(self.type_manager).unwrap(),
-            (caller.body_id, assignment_id),
+            (self.current_func().body_id, assignment_id),
             &mut return_val,
         );
 
@@ -167,31 +420,36 @@ impl<'a> CallHandler for SymExCallHandler<'a> {
     }
 }
 
-// Currently, we have no special mechanism for dropping beyond calling the (possible) glue
+/// Drops are handled as calls to the (possible) glue. In addition, the dropped
+/// place is invalidated after the glue returns, so that no stale symbolic value
+/// is read from it afterwards.
 impl DropHandler for SymExCallHandler<'_> {
     type Place = PlaceValueRef;
     type Operand = SymExValue;
 
     fn before_drop(self, def: CalleeDef, call_site: BasicBlockIndex) {
+        self.dropped_places.start();
         <Self as CallHandler>::before_call(self, def, call_site);
     }
 
     fn before_drop_some(self) {
+        self.dropped_places.start();
         <Self as CallHandler>::before_call_some(self);
     }
 
-    fn take_data_before_drop(self, func: Self::Operand, arg: Self::Operand, _place: Self::Place) {
+    fn take_data_before_drop(self, func: Self::Operand, arg: Self::Operand, place: Self::Place) {
+        self.dropped_places.set_place(place);
         <Self as CallHandler>::take_data_before_call(self, func, vec![arg], false);
     }
 
     fn after_drop(mut self) {
-        let token = self.flow_manager.finalize_call();
-        let caller = self
-            .trace_recorder
-            .finish_return(token.sanity().is_broken().unwrap());
-        debug_assert_eq!(caller, self.current_func());
+        let _ = self.finalize_call();
+        self.finish_drop();
+    }
 
-        let _ = self.flow_manager.give_return_value(token);
+    fn after_drop_unwind(mut self) {
+        // The place is considered dropped even if its glue has panicked.
+        self.finish_drop();
     }
 }
 
@@ -312,19 +570,28 @@ mod breakage {
     use const_format::concatcp;
 
     use leaf_runtime::{
-        abs::{CalleeDef, Constant, FuncDef},
+        abs::{CalleeDef, Constant, FuncDef, Tag},
         call::CallFlowBreakageCallback,
-        utils::alias::check_value_loss,
+        utils::{RRef, alias::check_value_loss},
     };
 
-    use super::backend;
-    use backend::{ConcreteValue, Implied, SymExValue, config::ExternalCallStrategy};
+    use super::{backend, summary::CallSummarizer};
+    use backend::{
+        ConcreteValue, Implied, SymExValue,
+        config::ExternalCallStrategy,
+        diagnostics::{SymbolismLossReason, report_symbolism_loss},
+        failure::fail_or_concretize,
+    };
     use common::{log_debug, log_warn};
 
     const TAG: &str = concatcp!(leaf_runtime::call::TAG, "::breakage");
 
     pub(crate) struct SymExBreakageCallback {
         pub(super) strategy: ExternalCallStrategy,
+        pub(super) tags: RRef<Vec<Tag>>,
+        pub(super) summarizer: CallSummarizer,
+        pub(super) concretization: RRef<Option<super::Concretization>>,
+        pub(super) pending_summary: RRef<Option<super::PendingSummary>>,
     }
 
     impl SymExBreakageCallback {
@@ -358,6 +625,9 @@ mod breakage {
                     "Symbolic arguments passed to the function: {:?}",
                     symbolic_args,
                 );
+                report_symbolism_loss(SymbolismLossReason::ExternalCallArgs, || {
+                    format!("In {current_func}, arguments: {symbolic_args:?}")
+                });
             }
             symbolic_args
         }
@@ -387,11 +657,14 @@ mod breakage {
                     "Symbolic returned value from a function: {:?}",
                     returned_value,
                 );
+                report_symbolism_loss(SymbolismLossReason::ExternalReturnValue, || {
+                    format!("From {callee} in {current_func}: {returned_value:?}")
+                });
             }
         }
     }
 
-    fn unknown_value() -> SymExValue {
+    pub(super) fn unknown_value() -> SymExValue {
         Implied::by_unknown(ConcreteValue::from(Constant::Some).to_value_ref())
     }
 
@@ -402,6 +675,32 @@ mod breakage {
             current: FuncDef,
             unconsumed_args: Vec<SymExValue>,
         ) -> SymExValue {
+            // The symbolic argument is concretized by the caller.
+            if self.concretization.borrow().is_some() {
+                return unconsumed_args
+                    .into_iter()
+                    .next()
+                    .unwrap_or_else(unknown_value);
+            }
+
+            // The result is built by the caller.
+            if let Some(pending) = self.summarizer.external_after_return(
+                &self.tags.borrow(),
+                current,
+                &unconsumed_args,
+            ) {
+                *self.pending_summary.borrow_mut() = Some(pending);
+                return unknown_value();
+            }
+
+            if let Some(result) = self.summarizer.after_return_with_args(
+                &self.tags.borrow(),
+                current,
+                &unconsumed_args,
+            ) {
+                return result;
+            }
+
             let symbolic_args = self.inspect_external_call_info(current, &unconsumed_args);
 
             enum Action {
@@ -430,19 +729,34 @@ mod breakage {
             match action {
                 Concretize => unknown_value(),
                 OverApproximate => {
-                    todo!("#306: Over-approximated symbolic values are not supported.")
+                    fail_or_concretize!(
+                        UnsupportedFeature,
+                        "#306: Over-approximated symbolic values are not supported."
+                    );
+                    unknown_value()
                 }
             }
         }
 
         fn at_enter(
             &mut self,
-            _caller: FuncDef,
+            caller: FuncDef,
             _expected_callee: CalleeDef,
             current: FuncDef,
             unconsumed_args: Vec<SymExValue>,
             current_arg_places: &[P],
         ) -> Vec<SymExValue> {
+            if let Some(args) = self.summarizer.at_enter(
+                &self.tags.borrow(),
+                caller,
+                current,
+                &unconsumed_args,
+                current_arg_places.len(),
+                unknown_value,
+            ) {
+                return args;
+            }
+
             self.inspect_external_call_info(current, &unconsumed_args);
             self.at_enter_with_no_caller(current, current_arg_places)
         }
@@ -453,14 +767,30 @@ mod breakage {
             current: FuncDef,
             unconsumed_return_value: SymExValue,
         ) {
-            self.inspect_returned_value(callee, current, &unconsumed_return_value);
+            if let Err(unconsumed_return_value) = self.summarizer.at_enter_with_return_val(
+                &self.tags.borrow(),
+                callee,
+                current,
+                unconsumed_return_value,
+            ) {
+                self.inspect_returned_value(callee, current, &unconsumed_return_value);
+            }
         }
 
         fn at_enter_with_no_caller(
             &mut self,
-            _current: FuncDef,
+            current: FuncDef,
             current_arg_places: &[P],
         ) -> Vec<SymExValue> {
+            if let Some(args) = self.summarizer.at_enter_with_no_caller(
+                &self.tags.borrow(),
+                current,
+                current_arg_places.len(),
+                unknown_value,
+            ) {
+                return args;
+            }
+
             core::iter::repeat_n(unknown_value(), current_arg_places.len()).collect()
         }
 
@@ -470,8 +800,17 @@ mod breakage {
             current: FuncDef,
             unconsumed_return_value: SymExValue,
         ) -> SymExValue {
-            self.inspect_returned_value(callee, current, &unconsumed_return_value);
-            unknown_value()
+            match self.summarizer.after_return_with_return_val(
+                &self.tags.borrow(),
+                current,
+                unconsumed_return_value,
+            ) {
+                Ok(result) => result,
+                Err(unconsumed_return_value) => {
+                    self.inspect_returned_value(callee, current, &unconsumed_return_value);
+                    unknown_value()
+                }
+            }
         }
 
         fn at_return_with_return_val(
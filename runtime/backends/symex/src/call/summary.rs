@@ -0,0 +1,494 @@
+//! Summaries of well-known functions that are not instrumented.
+//!
+//! When the standard library is not instrumented, calls to functions such as
+//! `Option::map` or `Iterator::fold` break the flow of symbolic values, both to
+//! the closure and back from it.
+//! The compiler marks calls to these functions with a summary tag (refer to
+//! [`common::pri::tags::SUMMARY_PREFIX`]), and the breakages happening under
+//! such a tag are resolved according to the semantics of the function.
+//!
+//! The combinators of `Option` and `Result` call their closure at most once.
+//! Higher-order functions over iterators and slices are modeled as repeatedly
+//! invoking their closure, up to a configurable bound, over the collection.
+
+use std::collections::HashMap;
+
+use common::log_debug;
+use leaf_runtime::abs::{FuncDef, Tag, VariantIndex};
+
+use super::{
+    backend,
+    external::{ExternalSummaries, PendingSummary},
+};
+use backend::{AdtField, AdtKind, AdtValue, SymExValue, Value, ValueRef, config::SummaryConfig};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Wrapper {
+    Option,
+    Result,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    Map,
+    MapErr,
+    AndThen,
+    UnwrapOr,
+    UnwrapOrElse,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HigherOrderFunc {
+    Fold,
+    Any,
+    All,
+    ForEach,
+    MapNext,
+    FilterNext,
+    SortBy,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Summary {
+    Combinator(CombinatorSummary),
+    HigherOrder(HigherOrderFunc),
+}
+
+impl Summary {
+    /// Parses the summary tag and returns the summary along with the key of
+    /// the summarized function in the configuration.
+    fn parse(tag: &str) -> Option<(Self, &str)> {
+        let key = tag.strip_prefix(common::pri::tags::SUMMARY_PREFIX)?;
+        let (ty, func) = key.split_once("::")?;
+        let summary = CombinatorSummary::parse(ty, func)
+            .map(Self::Combinator)
+            .or_else(|| HigherOrderFunc::parse(ty, func).map(Self::HigherOrder))?;
+        Some((summary, key))
+    }
+
+    /// The result of the function, given the value returned by the latest
+    /// invocation of the closure.
+    /// Gives the value back if the result is not determined by it.
+    fn result_by_closure(&self, returned: SymExValue) -> Result<SymExValue, SymExValue> {
+        match self {
+            Self::Combinator(summary) => Ok(summary.result_by_closure(returned)),
+            Self::HigherOrder(func) => func.result_by_closure(returned),
+        }
+    }
+
+    /// The result of the function when the closure has not been called.
+    fn result_by_args(&self, args: &[SymExValue]) -> Option<SymExValue> {
+        match self {
+            Self::Combinator(summary) => summary.result_by_args(args),
+            Self::HigherOrder(func) => func.result_by_args(args),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CombinatorSummary(Wrapper, Combinator);
+
+impl CombinatorSummary {
+    fn parse(ty: &str, func: &str) -> Option<Self> {
+        let wrapper = match ty {
+            "Option" => Wrapper::Option,
+            "Result" => Wrapper::Result,
+            _ => return None,
+        };
+        let combinator = match func {
+            "map" => Combinator::Map,
+            "map_err" if wrapper == Wrapper::Result => Combinator::MapErr,
+            "and_then" => Combinator::AndThen,
+            "unwrap_or" => Combinator::UnwrapOr,
+            "unwrap_or_else" => Combinator::UnwrapOrElse,
+            _ => return None,
+        };
+        Some(Self(wrapper, combinator))
+    }
+
+    /// The variant holding the value, i.e., `Some` or `Ok`.
+    fn value_variant(&self) -> VariantIndex {
+        match self.0 {
+            Wrapper::Option => 1,
+            Wrapper::Result => 0,
+        }
+    }
+
+    /// The variant on which the closure is applied.
+    fn applied_variant(&self) -> VariantIndex {
+        match self.1 {
+            Combinator::Map | Combinator::AndThen | Combinator::UnwrapOr => self.value_variant(),
+            // `None` or `Err`
+            Combinator::MapErr | Combinator::UnwrapOrElse => 1 - self.value_variant(),
+        }
+    }
+
+    /// The arguments of the closure (excluding its environment), given the receiver.
+    fn closure_args(&self, receiver: &SymExValue) -> Option<Vec<SymExValue>> {
+        let (variant, payload) = as_variant(receiver)?;
+        if variant != self.applied_variant() {
+            return None;
+        }
+        match (self.0, self.1) {
+            (_, Combinator::UnwrapOr) => None,
+            (Wrapper::Option, Combinator::UnwrapOrElse) => Some(vec![]),
+            _ => payload.map(|p| vec![p]),
+        }
+    }
+
+    /// The result of the combinator, given the value returned by the closure.
+    fn result_by_closure(&self, returned: SymExValue) -> SymExValue {
+        match self.1 {
+            Combinator::Map | Combinator::MapErr => {
+                wrap_in_variant(returned, self.applied_variant())
+            }
+            Combinator::AndThen | Combinator::UnwrapOrElse | Combinator::UnwrapOr => returned,
+        }
+    }
+
+    /// The result of the combinator when the closure has not been called.
+    fn result_by_args(&self, args: &[SymExValue]) -> Option<SymExValue> {
+        let receiver = args.first()?;
+        let (variant, payload) = as_variant(receiver)?;
+        match self.1 {
+            // The receiver is passed through (`None` or the other variant of `Result`).
+            Combinator::Map | Combinator::MapErr | Combinator::AndThen => {
+                (variant != self.applied_variant()).then(|| receiver.clone())
+            }
+            Combinator::UnwrapOr if variant == self.value_variant() => payload,
+            // The default value
+            Combinator::UnwrapOr => args.get(1).cloned(),
+            Combinator::UnwrapOrElse => (variant == self.value_variant())
+                .then_some(payload)
+                .flatten(),
+        }
+    }
+}
+
+impl HigherOrderFunc {
+    fn parse(ty: &str, func: &str) -> Option<Self> {
+        Some(match (ty, func) {
+            ("Iterator", "fold") => Self::Fold,
+            ("Iterator", "any") => Self::Any,
+            ("Iterator", "all") => Self::All,
+            ("Iterator", "for_each") => Self::ForEach,
+            ("Map", "next") => Self::MapNext,
+            ("Filter", "next") => Self::FilterNext,
+            ("slice", "sort_by" | "sort_unstable_by") => Self::SortBy,
+            _ => return None,
+        })
+    }
+
+    /// The number of the arguments of the closure (excluding its environment).
+    fn closure_arg_count(&self) -> usize {
+        match self {
+            Self::Fold | Self::SortBy => 2,
+            Self::Any | Self::All | Self::ForEach | Self::MapNext | Self::FilterNext => 1,
+        }
+    }
+
+    /// The arguments of the closure (excluding its environment) for an invocation.
+    /// # Arguments
+    /// * `args` - The arguments passed to the function, available at the first invocation.
+    /// * `previous` - The value returned by the previous invocation, if any.
+    /// # Remarks
+    /// The items of the collection are produced by the external code, thus they
+    /// are left unknown. For items passed by reference, the referenced values are
+    /// still accessible through memory.
+    fn closure_args(
+        &self,
+        args: Option<&[SymExValue]>,
+        previous: Option<SymExValue>,
+        unknown: impl Fn() -> SymExValue,
+    ) -> Vec<SymExValue> {
+        match self {
+            // The accumulator is the initial value or the result of the previous invocation.
+            Self::Fold => {
+                let acc = previous
+                    .or_else(|| args.and_then(|args| args.get(1).cloned()))
+                    .unwrap_or_else(&unknown);
+                vec![acc, unknown()]
+            }
+            _ => core::iter::repeat_with(unknown)
+                .take(self.closure_arg_count())
+                .collect(),
+        }
+    }
+
+    fn result_by_closure(&self, returned: SymExValue) -> Result<SymExValue, SymExValue> {
+        match self {
+            // Both short-circuit on the value that determines the result.
+            Self::Fold | Self::Any | Self::All => Ok(returned),
+            // `Some` of the mapped item
+            Self::MapNext => Ok(wrap_in_variant(returned, 1)),
+            Self::ForEach | Self::FilterNext | Self::SortBy => Err(returned),
+        }
+    }
+
+    fn result_by_args(&self, args: &[SymExValue]) -> Option<SymExValue> {
+        match self {
+            // The initial value
+            Self::Fold => args.get(1).cloned(),
+            _ => None,
+        }
+    }
+}
+
+pub(super) fn wrap_in_variant(value: SymExValue, variant: VariantIndex) -> SymExValue {
+    value.map_value(|value| {
+        Value::from(AdtValue {
+            kind: AdtKind::Enum { variant },
+            fields: vec![AdtField::from(Some(value))],
+        })
+        .to_value_ref()
+    })
+}
+
+/// Returns the variant index and the value of the first field (if any) of an enum value.
+fn as_variant(value: &SymExValue) -> Option<(VariantIndex, Option<SymExValue>)> {
+    let Value::Concrete(backend::ConcreteValue::Adt(AdtValue {
+        kind: AdtKind::Enum { variant },
+        fields,
+    })) = value.value.as_ref()
+    else {
+        return None;
+    };
+    let payload = fields
+        .first()
+        .and_then(|f| f.value.clone())
+        .map(|v: ValueRef| SymExValue {
+            by: value.by.clone(),
+            value: v,
+        });
+    Some((*variant, payload))
+}
+
+/// A summarized call that has entered its closure.
+struct Frame {
+    /// The depth of the summary tag.
+    depth: usize,
+    caller: FuncDef,
+    closure: FuncDef,
+    summary: Summary,
+    invocations: usize,
+    /// The value returned by the latest invocation of the closure, not consumed yet.
+    returned: Option<SymExValue>,
+    /// Whether the invocations have exceeded the configured bound.
+    exceeded: bool,
+}
+
+pub(super) struct CallSummarizer {
+    configs: HashMap<String, SummaryConfig>,
+    /// The frames making the summarized calls in progress, as the depth of
+    /// their tag and the function of the frame.
+    callers: Vec<(usize, FuncDef)>,
+    in_closure: Vec<Frame>,
+    external: ExternalSummaries,
+}
+
+impl CallSummarizer {
+    pub(super) fn new(configs: HashMap<String, SummaryConfig>) -> Self {
+        Self {
+            configs,
+            callers: Vec::new(),
+            in_closure: Vec::new(),
+            external: ExternalSummaries::new(),
+        }
+    }
+
+    /// Provides the arguments of the closure entered from a summarized function
+    /// for the first time.
+    /// # Remarks
+    /// The environment of the closure is left unknown, as it may be passed
+    /// either by value or by reference depending on the closure.
+    pub(super) fn at_enter(
+        &mut self,
+        tags: &[Tag],
+        caller: FuncDef,
+        current: FuncDef,
+        unconsumed_args: &[SymExValue],
+        arg_count: usize,
+        unknown: impl Fn() -> SymExValue,
+    ) -> Option<Vec<SymExValue>> {
+        let (summary, config) = self.current(tags, caller)?;
+        let args = match summary {
+            Summary::Combinator(summary) => summary.closure_args(unconsumed_args.first()?)?,
+            Summary::HigherOrder(_) if config.max_invocations == 0 => return None,
+            Summary::HigherOrder(func) => func.closure_args(Some(unconsumed_args), None, &unknown),
+        };
+        if args.len() + 1 != arg_count {
+            return None;
+        }
+        self.in_closure.push(Frame {
+            depth: tags.len(),
+            caller,
+            closure: current,
+            summary,
+            invocations: 1,
+            returned: None,
+            exceeded: false,
+        });
+        Some(core::iter::once(unknown()).chain(args).collect())
+    }
+
+    /// Keeps the value returned by the closure of a summarized higher-order function,
+    /// if it is entered again.
+    /// Gives the value back if it is not consumed.
+    pub(super) fn at_enter_with_return_val(
+        &mut self,
+        tags: &[Tag],
+        callee: FuncDef,
+        current: FuncDef,
+        returned: SymExValue,
+    ) -> Result<(), SymExValue> {
+        match self.top_frame(tags) {
+            Some(frame) if frame.closure == callee && frame.closure == current => {
+                frame.returned = Some(returned);
+                Ok(())
+            }
+            _ => Err(returned),
+        }
+    }
+
+    /// Provides the arguments of the closure entered again from a summarized
+    /// higher-order function.
+    pub(super) fn at_enter_with_no_caller(
+        &mut self,
+        tags: &[Tag],
+        current: FuncDef,
+        arg_count: usize,
+        unknown: impl Fn() -> SymExValue,
+    ) -> Option<Vec<SymExValue>> {
+        let max_invocations = self.config_of(tags.last()?).max_invocations;
+        let frame = self.top_frame(tags)?;
+        let Summary::HigherOrder(func) = frame.summary else {
+            return None;
+        };
+        if frame.closure != current || frame.exceeded {
+            return None;
+        }
+
+        frame.invocations += 1;
+        if frame.invocations > max_invocations {
+            log_debug!(
+                "Invocations of the closure in {:?} exceeded the bound, the rest are not summarized.",
+                func,
+            );
+            frame.exceeded = true;
+            return None;
+        }
+
+        let args = func.closure_args(None, frame.returned.take(), &unknown);
+        (args.len() + 1 == arg_count).then(|| core::iter::once(unknown()).chain(args).collect())
+    }
+
+    /// Provides the result of a summarized function that has called its closure.
+    /// Gives the value back if the result is not determined by it.
+    pub(super) fn after_return_with_return_val(
+        &mut self,
+        tags: &[Tag],
+        current: FuncDef,
+        returned: SymExValue,
+    ) -> Result<SymExValue, SymExValue> {
+        // Returning to the calling frame finishes the summarized call anyway.
+        self.finish_call(tags, current);
+        if !self
+            .in_closure
+            .last()
+            .is_some_and(|frame| frame.depth == tags.len() && frame.caller == current)
+        {
+            return Err(returned);
+        }
+        let frame = self.in_closure.pop().unwrap();
+        if frame.exceeded {
+            return Err(returned);
+        }
+        frame.summary.result_by_closure(returned)
+    }
+
+    /// Provides the result of a summarized function that has not called its closure.
+    pub(super) fn after_return_with_args(
+        &mut self,
+        tags: &[Tag],
+        current: FuncDef,
+        unconsumed_args: &[SymExValue],
+    ) -> Option<SymExValue> {
+        let (summary, _) = self.current(tags, current)?;
+        self.finish_call(tags, current);
+        summary.result_by_args(unconsumed_args)
+    }
+
+    /// Returns the call to a summarized function that takes no closure, if the
+    /// returned call is one.
+    /// The result is built later by the caller, as it requires access to the memory.
+    pub(super) fn external_after_return(
+        &mut self,
+        tags: &[Tag],
+        current: FuncDef,
+        unconsumed_args: &[SymExValue],
+    ) -> Option<PendingSummary> {
+        let key = tags
+            .last()?
+            .strip_prefix(common::pri::tags::SUMMARY_PREFIX)?;
+        let (pending, func_key) = self.external.pending_call(key, unconsumed_args)?;
+        if !self.config_of(func_key).enabled || !self.is_calling_frame(tags.len(), current) {
+            return None;
+        }
+        self.finish_call(tags, current);
+        Some(pending)
+    }
+
+    /// Returns the summary for the latest tag, if it is enabled and the breakage
+    /// belongs to the summarized call itself, i.e., it is observed in the frame
+    /// making the call and not in a call nested in its closure or in the other
+    /// functions it calls.
+    fn current(&mut self, tags: &[Tag], frame: FuncDef) -> Option<(Summary, SummaryConfig)> {
+        let (summary, key) = Summary::parse(tags.last()?)?;
+        let config = self.config_of(key);
+        (config.enabled
+            && self.in_closure.last().map(|frame| frame.depth) != Some(tags.len())
+            && self.is_calling_frame(tags.len(), frame))
+        .then_some((summary, config))
+    }
+
+    /// Checks if the frame is the one making the summarized call with the tag
+    /// at the given depth.
+    /// # Remarks
+    /// The tags do not carry their frames, so the frame observed at the first
+    /// breakage under a tag is taken, as it is the caller of the summarized
+    /// function.
+    fn is_calling_frame(&mut self, depth: usize, frame: FuncDef) -> bool {
+        // The tags of the entries above are already popped.
+        while self.callers.last().is_some_and(|(d, _)| *d > depth) {
+            self.callers.pop();
+        }
+        match self.callers.last() {
+            Some((d, caller)) if *d == depth => *caller == frame,
+            _ => {
+                self.callers.push((depth, frame));
+                true
+            }
+        }
+    }
+
+    fn finish_call(&mut self, tags: &[Tag], frame: FuncDef) {
+        if self.callers.last() == Some(&(tags.len(), frame)) {
+            self.callers.pop();
+        }
+    }
+
+    /// Returns the frame of the summarized call with the latest tag, if its
+    /// closure has been entered.
+    fn top_frame(&mut self, tags: &[Tag]) -> Option<&mut Frame> {
+        self.in_closure
+            .last_mut()
+            .filter(|frame| frame.depth == tags.len())
+    }
+
+    fn config_of(&self, key: &str) -> SummaryConfig {
+        let key = key
+            .strip_prefix(common::pri::tags::SUMMARY_PREFIX)
+            .unwrap_or(key);
+        self.configs.get(key).cloned().unwrap_or_default()
+    }
+}
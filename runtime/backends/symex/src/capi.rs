@@ -0,0 +1,249 @@
+//! A stable C API for embedding the runtime library (`libleafrt.so`) in
+//! non-Rust harnesses and tools, declared in `runtime/include/leafrt.h`.
+//!
+//! The configuration overrides and the callbacks are only effective if they
+//! are set before the runtime is initialized, either by [`leafrt_init`] or by
+//! the first call from an instrumented program.
+//!
+//! # Remarks
+//! Bump `LEAFRT_API_VERSION` (here and in the header) on any change to the
+//! signatures or the layout of the types below.
+
+use core::{
+    borrow::Borrow,
+    ffi::{CStr, c_char, c_int, c_void},
+};
+use std::{
+    ffi::CString,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use common::{
+    log_warn,
+    pri::ProgramRuntimeInterface,
+    types::trace::{Constraint, ConstraintKind},
+    utils::UnsafeSend,
+};
+
+use ::config::{ConfigBuilder, ConfigError, builder::DefaultState};
+
+use leaf_runtime::trace::StepInspector;
+
+use super::{ConstValue, ValueRef, interface::DefaultPri, trace::Step};
+
+const LEAFRT_API_VERSION: u32 = 1;
+
+const LEAFRT_OK: c_int = 0;
+const LEAFRT_ERR_INVALID_ARG: c_int = 1;
+const LEAFRT_ERR_INITIALIZED: c_int = 2;
+
+#[repr(C)]
+pub struct LeafrtLocation {
+    pub instance_kind: u8,
+    pub crate_num: u32,
+    pub def_index: u32,
+    pub basic_block: u32,
+}
+
+#[repr(C)]
+pub enum LeafrtDecisionKind {
+    True = 0,
+    False = 1,
+    OneOf = 2,
+    NoneOf = 3,
+}
+
+/// A decision taken in the execution, passed to the callbacks.
+/// The strings are only valid during the call.
+#[repr(C)]
+pub struct LeafrtDecision {
+    pub location: LeafrtLocation,
+    pub kind: LeafrtDecisionKind,
+    pub is_symbolic: bool,
+    pub discr: *const c_char,
+    pub cases: *const *const c_char,
+    pub cases_len: usize,
+}
+
+pub type LeafrtDecisionCallback =
+    Option<unsafe extern "C" fn(decision: *const LeafrtDecision, user_data: *mut c_void)>;
+
+type Callback = (
+    unsafe extern "C" fn(*const LeafrtDecision, *mut c_void),
+    UnsafeSend<*mut c_void>,
+);
+
+struct ApiState {
+    config_overrides: Vec<(String, String)>,
+    /// Called for the decisions with a symbolic discriminant.
+    constraint_callbacks: Vec<Callback>,
+    /// Called for all decisions.
+    branch_callbacks: Vec<Callback>,
+}
+
+static STATE: Mutex<ApiState> = Mutex::new(ApiState {
+    config_overrides: Vec::new(),
+    constraint_callbacks: Vec::new(),
+    branch_callbacks: Vec::new(),
+});
+
+fn state() -> MutexGuard<'static, ApiState> {
+    STATE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[no_mangle]
+pub extern "C" fn leafrt_api_version() -> u32 {
+    LEAFRT_API_VERSION
+}
+
+#[no_mangle]
+pub extern "C" fn leafrt_init() {
+    DefaultPri::init_runtime_lib();
+}
+
+#[no_mangle]
+pub extern "C" fn leafrt_shutdown() {
+    DefaultPri::shutdown_runtime_lib();
+}
+
+/// # Safety
+/// `key` and `value` must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn leafrt_config_set(key: *const c_char, value: *const c_char) -> c_int {
+    if super::instance::is_initialized() {
+        return LEAFRT_ERR_INITIALIZED;
+    }
+    let (Some(key), Some(value)) = (unsafe { to_str(key) }, unsafe { to_str(value) }) else {
+        return LEAFRT_ERR_INVALID_ARG;
+    };
+    state()
+        .config_overrides
+        .push((key.to_owned(), value.to_owned()));
+    LEAFRT_OK
+}
+
+/// # Safety
+/// `callback` must be safe to call with `user_data` from any thread
+/// running the program until the runtime is shut down.
+#[no_mangle]
+pub unsafe extern "C" fn leafrt_on_constraint(
+    callback: LeafrtDecisionCallback,
+    user_data: *mut c_void,
+) -> c_int {
+    register(callback, user_data, |s| &mut s.constraint_callbacks)
+}
+
+/// # Safety
+/// `callback` must be safe to call with `user_data` from any thread
+/// running the program until the runtime is shut down.
+#[no_mangle]
+pub unsafe extern "C" fn leafrt_on_branch(
+    callback: LeafrtDecisionCallback,
+    user_data: *mut c_void,
+) -> c_int {
+    register(callback, user_data, |s| &mut s.branch_callbacks)
+}
+
+fn register(
+    callback: LeafrtDecisionCallback,
+    user_data: *mut c_void,
+    list: impl FnOnce(&mut ApiState) -> &mut Vec<Callback>,
+) -> c_int {
+    if super::instance::is_initialized() {
+        return LEAFRT_ERR_INITIALIZED;
+    }
+    let Some(callback) = callback else {
+        return LEAFRT_ERR_INVALID_ARG;
+    };
+    list(&mut state()).push((callback, UnsafeSend::new(user_data)));
+    LEAFRT_OK
+}
+
+unsafe fn to_str<'a>(ptr: *const c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        return None;
+    }
+    unsafe { CStr::from_ptr(ptr) }.to_str().ok()
+}
+
+/// Applies the overrides set through the API, which take precedence over all
+/// the other configuration sources.
+pub(crate) fn apply_config_overrides(
+    mut builder: ConfigBuilder<DefaultState>,
+) -> Result<ConfigBuilder<DefaultState>, ConfigError> {
+    for (key, value) in state().config_overrides.iter() {
+        builder = builder.set_override(key.as_str(), value.as_str())?;
+    }
+    Ok(builder)
+}
+
+/// Creates an inspector passing the decisions to the registered callbacks,
+/// if there is any.
+pub(crate) fn create_callback_inspector<S, V, C>() -> Option<impl StepInspector<S, V, C>>
+where
+    S: Borrow<Step>,
+    V: Borrow<ValueRef>,
+    C: Borrow<ConstValue>,
+{
+    let (constraint_callbacks, branch_callbacks) = {
+        let mut state = state();
+        (
+            core::mem::take(&mut state.constraint_callbacks),
+            core::mem::take(&mut state.branch_callbacks),
+        )
+    };
+    if constraint_callbacks.is_empty() && branch_callbacks.is_empty() {
+        return None;
+    }
+
+    let inspector = move |step: &S, constraint: Constraint<&V, &C>| {
+        let discr: &ValueRef = constraint.discr.borrow();
+        let is_symbolic = discr.is_symbolic();
+        if !is_symbolic && branch_callbacks.is_empty() {
+            return;
+        }
+
+        let (kind, cases) = match &constraint.kind {
+            ConstraintKind::True => (LeafrtDecisionKind::True, &[][..]),
+            ConstraintKind::False => (LeafrtDecisionKind::False, &[][..]),
+            ConstraintKind::OneOf(cases) => (LeafrtDecisionKind::OneOf, cases.as_slice()),
+            ConstraintKind::NoneOf(cases) => (LeafrtDecisionKind::NoneOf, cases.as_slice()),
+        };
+        let discr = to_c_string(discr.to_string());
+        let cases = cases
+            .iter()
+            .map(|c| to_c_string(Borrow::<ConstValue>::borrow(*c).to_string()))
+            .collect::<Vec<_>>();
+        let case_ptrs = cases.iter().map(|c| c.as_ptr()).collect::<Vec<_>>();
+
+        let location = **step.borrow();
+        let decision = LeafrtDecision {
+            location: LeafrtLocation {
+                instance_kind: location.body.0,
+                crate_num: location.body.1.0,
+                def_index: location.body.1.1,
+                basic_block: location.index,
+            },
+            kind,
+            is_symbolic,
+            discr: discr.as_ptr(),
+            cases: case_ptrs.as_ptr(),
+            cases_len: case_ptrs.len(),
+        };
+
+        let callbacks = branch_callbacks
+            .iter()
+            .chain(constraint_callbacks.iter().filter(|_| is_symbolic));
+        for (callback, user_data) in callbacks {
+            unsafe { callback(&decision, **user_data) };
+        }
+    };
+    Some(inspector)
+}
+
+fn to_c_string(value: String) -> CString {
+    CString::new(value).unwrap_or_else(|e| {
+        log_warn!("Interior NUL in the textual form of a value: {e}");
+        CString::default()
+    })
+}
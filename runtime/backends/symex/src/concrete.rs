@@ -6,6 +6,7 @@ use super::alias::backend;
 use backend::{
     ConcreteValueRef, Implied, SymExConstraint, SymExTraceManager, SymValueRef,
     alias::ValueRefBinaryExprBuilder,
+    diagnostics::{SymbolismLossReason, report_symbolism_loss},
     expr::{Expr, PorterValue, RawConcreteValue, SymValue},
 };
 
@@ -40,6 +41,7 @@ impl<EB: ValueRefBinaryExprBuilder> Concretizer for DefaultConcretizer<EB> {
         get_conc: Box<ConcolicValueObtainer<ConcreteValueRef>>,
     ) -> ConcreteValueRef {
         log_info!("Stamping {} to its concrete value", sym_value);
+        report_symbolism_loss(SymbolismLossReason::Stamping, || sym_value.to_string());
 
         let (eq_expr, conc_value) = match sym_value.as_ref() {
             SymValue::Expression(Expr::Partial(PorterValue {
@@ -1,12 +1,14 @@
 use derive_more as dm;
 use serde::Deserialize;
 
-use std::{collections::HashMap, num::NonZero};
+use std::{collections::HashMap, num::NonZero, path::PathBuf};
 
 use common::{log_debug, log_warn};
 
 use leaf_runtime::utils::{alias::check_value_loss, file::FileGenConfig};
 
+use crate::failure::FailureKind;
+
 impl TryFrom<::config::Config> for SymExBackendConfig {
     type Error = ::config::ConfigError;
 
@@ -42,20 +44,88 @@ pub(crate) struct SymExBackendConfig {
     #[serde(default)]
     pub sym_place: SymbolicPlaceConfig,
 
+    #[serde(default)]
+    pub sym_vars: SymVariablesConfig,
+
     #[serde(default)]
     pub outputs: Vec<OutputConfig>,
 
+    /// The outputs for the generated command-line arguments, if they are marked
+    /// symbolic. The arguments are written as a JSON array of strings or as
+    /// NUL-terminated byte strings in the binary format.
+    #[serde(default)]
+    pub args_outputs: Vec<OutputConfig>,
+
+    #[serde(default)]
+    pub env_vars: SymEnvVarsConfig,
+
+    #[serde(default)]
+    pub files: SymFilesConfig,
+
+    #[serde(default)]
+    pub parent_input: Option<ParentInputConfig>,
+
+    #[serde(default)]
+    pub output_hooks: Vec<OutputHookConfig>,
+
+    #[serde(default)]
+    pub input_schema: Option<InputSchemaConfig>,
+
     #[serde(default)]
     pub exe_trace: ExecutionTraceConfig,
 
     #[serde(default)]
     pub solver: SolverImpl,
+
+    #[serde(default)]
+    pub diagnostics: DiagnosticsConfig,
+
+    #[serde(default)]
+    pub failure: FailureConfig,
 }
 
 #[derive(Debug, Default, Clone, Deserialize)]
 pub(crate) struct CallConfig {
     #[serde(default)]
     pub external_call: ExternalCallStrategy,
+    /// Per-function configuration of the summaries of uninstrumented functions,
+    /// keyed by the summarized function, e.g., `Iterator::fold`, `Option::map`,
+    /// or `slice::contains`.
+    /// The summaries not listed here are enabled with the default configuration.
+    #[serde(default)]
+    pub summaries: HashMap<String, SummaryConfig>,
+    /// The maximum depth of the call stack up to which the frames are fully tracked.
+    /// The frames beyond it are summarized, i.e., they are treated as external calls
+    /// with unknown returned values, and no data flow is followed inside them.
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct SummaryConfig {
+    #[serde(default = "default_summary_enabled")]
+    pub enabled: bool,
+    /// The maximum number of closure invocations modeled for a call to a higher-order
+    /// function. The invocations beyond this bound are treated as regular external calls.
+    #[serde(default = "default_summary_max_invocations")]
+    pub max_invocations: usize,
+}
+
+impl Default for SummaryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_summary_enabled(),
+            max_invocations: default_summary_max_invocations(),
+        }
+    }
+}
+
+fn default_summary_enabled() -> bool {
+    true
+}
+
+fn default_summary_max_invocations() -> usize {
+    64
 }
 
 /* NOTE: Aliases don't work at the moment. */
@@ -81,6 +151,21 @@ pub(crate) struct SymbolicPlaceConfig {
     pub read: SymbolicPlaceStrategy,
     #[serde(default)]
     pub write: SymbolicPlaceStrategy,
+    /// The strategy for the places that are referenced (e.g., `&a[i]`).
+    /// If not set, the dereferences are kept symbolic and the read strategy is used
+    /// for the rest.
+    #[serde(default, alias = "ref")]
+    pub reference: Option<SymbolicPlaceStrategy>,
+    /// Whether to constrain the pointers offset by symbolic values to stay
+    /// within their allocations, if the allocations are known.
+    /// The negation of these constraints leads to out-of-bounds accesses.
+    #[serde(default)]
+    pub offset_bounds: bool,
+    /// Whether to keep the lengths of slices symbolic when their pointers are
+    /// dereferenced, instead of handling the pointers as symbolic places.
+    /// The indices into such slices are constrained to be within their lengths.
+    #[serde(default, alias = "symbolic_lengths")]
+    pub sym_lengths: bool,
 }
 
 #[derive(Debug, Default, Clone, Copy, Deserialize)]
@@ -95,6 +180,13 @@ pub(crate) enum SymbolicPlaceStrategy {
     /// Builds an expression with multiple possible values.
     #[serde(alias = "proj", alias = "expr")]
     ProjExpression,
+    /// Builds an expression with multiple possible values only if the number of
+    /// possible targets is known and does not exceed `max_candidates`, otherwise
+    /// falls back to stamping.
+    /// For example, indexing an array of length 8 by a symbolic index stays symbolic
+    /// with `max_candidates = 16`, while indexing a slice of length 100 is stamped.
+    #[serde(alias = "bounded_expr", alias = "expand")]
+    BoundedProjExpression { max_candidates: u64 },
     /// Uses the concrete value of place for memory reading/writing.
     #[serde(alias = "conc", alias = "concretize", alias = "underapprox")]
     Concretization,
@@ -115,6 +207,171 @@ pub(crate) enum OutputConfig {
     File(FileGenConfig),
 }
 
+#[derive(Debug, Default, Clone, Deserialize)]
+pub(crate) struct SymVariablesConfig {
+    /// If set, the ids of symbolic variables are derived from the portion of
+    /// the input they are created from, so that the same input byte maps to
+    /// the same variable across executions.
+    /// Otherwise, the ids are assigned in the order of creation.
+    #[serde(default)]
+    pub stable_ids: Option<StableSymVarIdsConfig>,
+    /// The output for the table of the symbolic variables, written as JSON at
+    /// the end of the execution. It maps the ids to the names, the declaring
+    /// types, and the source locations given by `mark_symbolic_named`.
+    #[serde(default)]
+    pub table: Option<OutputConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct StableSymVarIdsConfig {
+    /// A JSON file listing the input portions that the symbolic variables are
+    /// created from in the order of creation, e.g.,
+    /// `[{ "source": "stdin", "offset": 0, "length": 1 }]`.
+    pub descriptor: PathBuf,
+    /// A JSON file keeping the ids assigned to the input portions.
+    /// It is meant to be shared by the executions, and gets extended with the
+    /// portions that are seen for the first time. The updates are synchronized
+    /// through a sibling `.lock` file.
+    pub registry: PathBuf,
+}
+
+/// The environment variables whose values are made symbolic when marked so
+/// (see `mark_env_var_symbolic`). The values of the other variables are kept concrete.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub(crate) struct SymEnvVarsConfig {
+    #[serde(default)]
+    pub names: Vec<String>,
+    /// The outputs for the generated values of the variables, written as a JSON
+    /// object, as `NAME=value` lines in the text format, or as NUL-terminated
+    /// `NAME=value` entries in the binary format.
+    #[serde(default)]
+    pub outputs: Vec<OutputConfig>,
+}
+
+/// The files whose contents are made symbolic when marked so (see `mark_file_symbolic`).
+/// A path matches a selected one if it is equal to or ends with it (component-wise).
+/// The contents of the other files are kept concrete.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub(crate) struct SymFilesConfig {
+    #[serde(default)]
+    pub paths: Vec<PathBuf>,
+    /// The outputs for the generated contents of the files, written into a
+    /// directory per input, where the files are placed at their paths.
+    #[serde(default)]
+    pub outputs: Vec<OutputConfig>,
+}
+
+/// The input that the current execution is running on.
+/// When set, generated inputs are obtained by patching the bytes of this input
+/// that correspond to the variables in the answers, rather than regenerating
+/// the whole input from the current values of the symbolic variables.
+/// If the input or its layout cannot be loaded, a warning is logged and whole
+/// inputs are generated.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ParentInputConfig {
+    pub path: PathBuf,
+    /// A JSON file mapping the ids of symbolic variables to their byte offsets in the input.
+    /// If not set, the variable with id `i` is considered to be at offset `i - 1`.
+    #[serde(default)]
+    pub layout: Option<PathBuf>,
+}
+
+/// Describes the structure of the input in terms of the symbolic variables.
+/// Binary outputs are encoded field by field according to this description,
+/// rather than treating the input as a flat array of byte-typed variables.
+/// # Remarks
+/// Symbolic variables are consumed by the fields in the order of their ids.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct InputSchemaConfig {
+    pub fields: Vec<InputFieldConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum InputFieldConfig {
+    /// A single symbolic variable.
+    Value {
+        #[serde(flatten)]
+        encoding: ValueEncodingConfig,
+    },
+    /// A sequence of `count` symbolic variables.
+    #[serde(alias = "array", alias = "bytes")]
+    Sequence {
+        count: usize,
+        #[serde(flatten)]
+        encoding: ValueEncodingConfig,
+    },
+    /// A sequence of up to `count` symbolic variables preceded by the number of its elements.
+    /// Only the leading variables that have answers are encoded, e.g., when the
+    /// program has read fewer elements. The field still takes `count` ids.
+    LengthPrefixed {
+        count: usize,
+        prefix_size: usize,
+        #[serde(default)]
+        prefix_big_endian: bool,
+        #[serde(flatten)]
+        encoding: ValueEncodingConfig,
+    },
+    /// Constant bytes that do not correspond to any symbolic variable (e.g., magic numbers).
+    Literal { bytes: Vec<u8> },
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+pub(crate) struct ValueEncodingConfig {
+    /// The number of bytes to encode the value in.
+    /// Defaults to the size of the variable's type.
+    #[serde(default)]
+    pub size: Option<usize>,
+    #[serde(default)]
+    pub big_endian: bool,
+}
+
+/// Post-processing applied on the generated (binary) outputs before being written.
+/// Mainly useful to repair the fields derived from the input bytes changed by the solver.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum OutputHookConfig {
+    /// Writes the length of the range `[from, to)` as an unsigned integer of
+    /// `size` bytes at `offset`.
+    LengthField {
+        offset: usize,
+        size: usize,
+        #[serde(flatten)]
+        range: OutputRangeConfig,
+        /// A constant added to the length (e.g., to account for the header itself).
+        #[serde(default)]
+        adjustment: i64,
+        #[serde(default)]
+        big_endian: bool,
+    },
+    /// Writes the CRC-32 (IEEE) checksum of the range `[from, to)` at `offset`.
+    Crc32 {
+        offset: usize,
+        #[serde(flatten)]
+        range: OutputRangeConfig,
+        #[serde(default)]
+        big_endian: bool,
+    },
+    /// Pipes the output to the standard input of the command and replaces it with
+    /// whatever the command writes to its standard output.
+    Command {
+        program: PathBuf,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+pub(crate) struct OutputRangeConfig {
+    #[serde(default)]
+    pub from: usize,
+    /// Exclusive. Defaults to the end of the output.
+    #[serde(default)]
+    pub to: Option<usize>,
+}
+
 #[derive(Debug, Default, Clone, Deserialize)]
 pub(crate) struct ExecutionTraceConfig {
     #[serde(default = "default_trace_inspectors")]
@@ -126,12 +383,38 @@ pub(crate) struct ExecutionTraceConfig {
     #[serde(default)]
     pub control_flow_dump: Option<OutputConfig>,
 
+    /// The endpoint to stream the control flow trace to during the execution,
+    /// as an alternative to dumping it in files.
+    #[serde(default)]
+    pub control_flow_stream: Option<TraceStreamConfig>,
+
     #[serde(default)]
     pub constraints_dump: Option<OutputConfig>,
 
     #[serde(default)]
     pub preconditions_dump: Option<OutputConfig>,
 
+    /// Where the concretizations of symbolic places (dereferences, indices, and sizes)
+    /// are recorded (as JSON lines), which indicate the incompleteness of the exploration
+    /// for the current path.
+    #[serde(default)]
+    pub concretizations_dump: Option<OutputConfig>,
+
+    /// Where the coverage of the whole execution (hit counts of basic blocks and
+    /// decisions per body) is reported at the end of the execution.
+    /// JSON outputs get the full report, and text outputs get it in the lcov format.
+    /// The uncovered parts and the source lines are taken from the program map
+    /// (`program_map.json`) found next to the executable, which the lcov format requires.
+    #[serde(default)]
+    pub coverage_reports: Vec<OutputConfig>,
+
+    /// Where the counters of the execution (e.g., the decisions and the solver queries
+    /// per result) are written, along with the other dumps.
+    /// The function overheads are also written if `diagnostics.func_overhead` is set.
+    /// JSON outputs get an object, and text outputs get the Prometheus text format.
+    #[serde(default)]
+    pub metrics: Option<OutputConfig>,
+
     /// The time interval between dumping inspectors' data (e.g., snapshots) during the execution
     /// and not merely in the end.
     /// # Remarks
@@ -142,6 +425,16 @@ pub(crate) struct ExecutionTraceConfig {
     pub dump_interval: Option<NonZero<u64>>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type")]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum TraceStreamConfig {
+    /// A Unix domain socket listening at the path.
+    Unix { path: PathBuf },
+    /// A TCP socket listening at the address, e.g., `127.0.0.1:9000`.
+    Tcp { address: String },
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
@@ -157,11 +450,76 @@ pub(crate) enum TraceInspectorType {
         check_optimistic: bool,
         #[serde(default)]
         filters: Vec<DivergenceFilterType>,
+        /// Where the predicted paths of the generated inputs are written.
+        /// `prediction_<n>` corresponds to the `n`th generated input.
+        #[serde(default)]
+        predictions: Option<OutputConfig>,
+        /// Where the unsatisfiable cores are reported (as JSON lines) for the decisions
+        /// whose negations are unsatisfiable, i.e., the earlier decisions blocking them.
+        #[serde(default)]
+        unsat_cores: Option<OutputConfig>,
     },
     BranchCoverage {
         #[serde(default)]
         output: Option<OutputConfig>,
     },
+    /// Tracks the cost of the execution path (the length of the trace and the loop iterations)
+    /// and searches for an input maximizing it at the end of the execution.
+    CostMaximizer {
+        /// Where the cost of the current path is reported.
+        #[serde(default)]
+        report: Option<OutputConfig>,
+        /// Where the cost-maximizing input is written. It should not overlap
+        /// with the main outputs.
+        #[serde(default)]
+        outputs: Vec<OutputConfig>,
+    },
+    /// Verifies that the current execution takes the path predicted when its
+    /// input was generated, and reports the first mispredicted decision otherwise.
+    PredictionCheck {
+        /// The prediction written for the input by the diverging input inspector.
+        prediction: PathBuf,
+        #[serde(default)]
+        report: Option<OutputConfig>,
+    },
+    /// Publishes a fixed-size map of the branch hits in shared memory (AFL-style),
+    /// which can be read after the execution without parsing the trace.
+    CoverageMap {
+        /// The environment variable holding the id of the shared memory segment.
+        #[serde(default = "default_coverage_map_shm_id_env")]
+        shm_id_env: String,
+        /// The size of the map in bytes.
+        #[serde(default = "default_coverage_map_size")]
+        size: usize,
+    },
+    /// Searches for the inputs violating the implicit checks against undefined
+    /// behavior along the path, i.e., the bounds of the pointers offset symbolically
+    /// and the alignment of the pointers cast from symbolic addresses.
+    /// # Remarks
+    /// The bounds are checked against the allocations tracked for `sym_place.offset_bounds`,
+    /// which is enabled by this inspector.
+    UbChecker {
+        /// Where the candidate inputs are written. It should not overlap
+        /// with the main outputs.
+        #[serde(default)]
+        outputs: Vec<OutputConfig>,
+        /// Where the violated checks are reported (as JSON lines).
+        #[serde(default)]
+        report: Option<OutputConfig>,
+    },
+    /// Searches for the inputs failing the assertions of the operations that panic
+    /// on invalid operands along the path, i.e., arithmetic overflow, division
+    /// (and remainder) by zero, and out-of-bounds indexing.
+    /// The candidate inputs are expected to crash the program.
+    PanicFinder {
+        /// Where the candidate inputs are written. It should not overlap
+        /// with the main outputs.
+        #[serde(default)]
+        outputs: Vec<OutputConfig>,
+        /// Where the failed assertions are reported (as JSON lines).
+        #[serde(default)]
+        report: Option<OutputConfig>,
+    },
 }
 
 #[derive(Debug, Default, Clone, Copy, Deserialize, PartialEq, PartialOrd)]
@@ -176,13 +534,19 @@ fn default_diverging_input_check_optimistic() -> bool {
     true
 }
 
+fn default_coverage_map_shm_id_env() -> String {
+    "__LEAF_COV_SHM_ID".to_owned()
+}
+
+fn default_coverage_map_size() -> usize {
+    1 << 16
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
 pub(crate) enum DivergenceFilterType {
-    Tags {
-        exclude_any_of: Vec<String>,
-    },
+    Tags(TagSelection),
     BranchDepthDistance {
         #[serde(default = "default_branch_depth_distance_factor")]
         distance_threshold_factor: f32,
@@ -205,6 +569,8 @@ fn default_trace_inspectors() -> Vec<TraceInspectorType> {
         TraceInspectorType::DivergingInput {
             check_optimistic: default_diverging_input_check_optimistic(),
             filters: vec![],
+            predictions: None,
+            unsat_cores: None,
         },
     ]
 }
@@ -213,7 +579,22 @@ fn default_trace_inspectors() -> Vec<TraceInspectorType> {
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
 pub(crate) enum ConstraintFilterType {
-    SanityChecker { output: Option<OutputConfig> },
+    SanityChecker {
+        output: Option<OutputConfig>,
+    },
+    /// Keeps only the constraints of the steps selected by their tags.
+    Tags(TagSelection),
+}
+
+/// Selects the steps based on the tags active at them.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub(crate) struct TagSelection {
+    /// If not empty, only the steps having any of these tags are selected.
+    #[serde(default)]
+    pub include_any_of: Vec<String>,
+    /// The steps having any of these tags are not selected.
+    #[serde(default)]
+    pub exclude_any_of: Vec<String>,
 }
 
 fn default_constraint_filters() -> Vec<ConstraintFilterType> {
@@ -223,6 +604,36 @@ fn default_constraint_filters() -> Vec<ConstraintFilterType> {
     ]
 }
 
+#[derive(Debug, Default, Clone, Deserialize)]
+pub(crate) struct DiagnosticsConfig {
+    /// If set, every event discarding symbolic information (e.g., concretization)
+    /// is recorded and an aggregated report is written at the end of the execution.
+    #[serde(default)]
+    pub symbolism_loss: Option<OutputConfig>,
+    /// If set, the time spent in the backend is attributed to the functions being executed
+    /// and the functions are reported sorted by their overhead in the execution metrics
+    /// (`exe_trace.metrics`).
+    #[serde(default)]
+    pub func_overhead: bool,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+pub(crate) struct FailureConfig {
+    /// The kinds of failures to be downgraded to concretization instead of
+    /// terminating the execution, where a concrete fallback is available.
+    #[serde(default)]
+    pub concretize_on: Vec<FailureKind>,
+    /// If set, the failures are counted per kind and a report is written
+    /// at the end of the execution or at the terminating failure.
+    /// The assertion of the program that fails (if any) is also reported next to it.
+    #[serde(default)]
+    pub report: Option<OutputConfig>,
+    /// If set, the crashes (panics) of the program are bucketed by their location
+    /// in this directory, with one reproducer (a copy of the parent input) per bucket.
+    #[serde(default)]
+    pub crashes: Option<PathBuf>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
@@ -231,6 +642,35 @@ pub(crate) enum SolverImpl {
         #[serde(flatten)]
         config: Z3Config,
     },
+    /// Finds the diverging inputs using an external solver that reads SMT-LIB2 scripts,
+    /// e.g., Bitwuzla, which is faster on pure bit-vector queries.
+    /// # Remarks
+    /// Z3 is still used for building the queries and for the features not provided
+    /// through the external solver (e.g., sanity checks and optimization).
+    Process {
+        /// The solver executable, which receives the queries on its standard input.
+        program: PathBuf,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        z3: Z3Config,
+    },
+    /// Finds the diverging inputs by running multiple instances of Z3 in parallel
+    /// and taking the first definitive result.
+    Portfolio {
+        #[serde(default = "default_portfolio_workers")]
+        workers: usize,
+        /// The tactics assigned to the workers in a round-robin manner, e.g.,
+        /// `(then simplify bit-blast sat)`. The default strategy is used if empty.
+        #[serde(default)]
+        tactics: Vec<String>,
+        #[serde(flatten)]
+        config: Z3Config,
+    },
+}
+
+fn default_portfolio_workers() -> usize {
+    4
 }
 
 impl Default for SolverImpl {
@@ -245,6 +685,59 @@ impl Default for SolverImpl {
 pub(crate) struct Z3Config {
     #[serde(default)]
     pub global_params: HashMap<String, ParamValue>,
+    /// The time limit of each query in milliseconds, after which the result is unknown.
+    /// Overrides `timeout` in the global parameters.
+    #[serde(default)]
+    pub timeout_ms: Option<u32>,
+    /// The memory limit of the solver in megabytes, after which the results are unknown.
+    /// Overrides `memory_max_size` in the global parameters.
+    #[serde(default)]
+    pub memory_limit_mb: Option<u32>,
+    #[serde(default)]
+    pub translation: Z3TranslationConfig,
+    /// If set, every query sent to the solver is written as an SMT-LIB2 script
+    /// (`query_<n>.smt2` by default), which can be replayed with other solvers.
+    #[serde(default)]
+    pub queries_dump: Option<OutputConfig>,
+    /// Biases the models toward human-readable values, at the cost of
+    /// optimizing instead of merely solving the queries.
+    #[serde(default)]
+    pub preferences: ValuePreferencesConfig,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+pub(crate) struct ValuePreferencesConfig {
+    /// Prefer integers of small magnitudes (up to 255).
+    #[serde(default)]
+    pub small_magnitude: bool,
+    /// Prefer printable ASCII characters for bytes.
+    #[serde(default)]
+    pub printable_bytes: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Z3TranslationConfig {
+    /// Symbolic reads with at most this many possible values are encoded as
+    /// a chain of ITEs, and larger ones as an array with all the values stored.
+    #[serde(default = "default_select_ite_max_size")]
+    pub select_ite_max_size: usize,
+    /// If set, values with more expression nodes than this are translated with
+    /// their largest subexpressions replaced by their concrete values.
+    #[serde(default)]
+    pub node_budget: Option<usize>,
+}
+
+impl Default for Z3TranslationConfig {
+    fn default() -> Self {
+        Self {
+            select_ite_max_size: default_select_ite_max_size(),
+            node_budget: None,
+        }
+    }
+}
+
+fn default_select_ite_max_size() -> usize {
+    16
 }
 
 #[derive(Debug, Clone, Deserialize, dm::Display)]
@@ -2,19 +2,25 @@ use std::cell::RefMut;
 
 use leaf_runtime::{
     abs::{
-        self, AssertKind, BasicBlockIndex, BasicBlockLocation, ConstraintKind, SwitchCaseIndex,
-        utils::BasicBlockLocationExt,
+        self, AssertKind, BasicBlockIndex, BasicBlockLocation, ConstraintKind, RawAddress,
+        SwitchCaseIndex, Tag, ValueType, backend::CoreTypeProvider, utils::BasicBlockLocationExt,
     },
     pri::fluent::backend::{ConstraintHandler, SwitchHandler},
-    utils::RRef,
+    type_info::TypeInfo,
+    utils::{PerThread, RRef},
 };
 
 use leaf_runtime::call::CallFlowManager;
 
 use super::alias::backend;
 use backend::{
-    SymExBackend, SymExExprBuilder, SymExTraceManager, SymExValue,
-    alias::SymExValueUnaryExprBuilder, expr::prelude::ConstValue,
+    Implied, SymExBackend, SymExExeTraceRecorder, SymExExprBuilder, SymExTraceManager, SymExValue,
+    TypeDatabase, VariablesState,
+    alias::SymExValueExprBuilder,
+    assertion,
+    assignment::build_discriminant_expr,
+    expr::prelude::{ConstValue, LazyTypeInfo, SymValueRef},
+    place::DiscriminantPossiblePlace,
 };
 
 pub(super) type Constraint = leaf_runtime::abs::Constraint<SymExValue, ConstValue>;
@@ -23,14 +29,28 @@ pub(super) type DecisionCase = ConstValue;
 pub(crate) struct SymExConstraintHandler<'a, EB> {
     location: BasicBlockLocation,
     trace_manager: RefMut<'a, SymExTraceManager>,
+    trace_recorder: RRef<SymExExeTraceRecorder>,
     expr_builder: RRef<EB>,
+    /// Whether the current frame is beyond the call depth limit, where no
+    /// constraint is recorded.
+    is_summarized: bool,
+    vars_state: &'a dyn VariablesState,
+    type_manager: &'a dyn TypeDatabase,
+    tags: RRef<Vec<Tag>>,
+    string_matches: RRef<PerThread<StringMatchChains>>,
 }
 
 impl<'a> SymExConstraintHandler<'a, SymExExprBuilder> {
     pub(super) fn new(backend: &'a mut SymExBackend, location: BasicBlockIndex) -> Self {
         Self {
             trace_manager: backend.trace_manager.borrow_mut(),
+            trace_recorder: backend.trace_recorder.clone(),
             expr_builder: backend.expr_builder.clone(),
+            is_summarized: backend.threads.current().call_depth.is_summarized(),
+            vars_state: &backend.threads.current().vars_state,
+            type_manager: backend.type_manager.as_ref(),
+            tags: backend.tags.clone(),
+            string_matches: backend.string_matches.clone(),
             location: backend
                 .call_flow_manager
                 .current_func()
@@ -40,8 +60,9 @@ impl<'a> SymExConstraintHandler<'a, SymExExprBuilder> {
     }
 }
 
-impl<'a, EB: SymExValueUnaryExprBuilder> ConstraintHandler for SymExConstraintHandler<'a, EB> {
+impl<'a, EB: SymExValueExprBuilder> ConstraintHandler for SymExConstraintHandler<'a, EB> {
     type Operand = SymExValue;
+    type DiscriminablePlace = DiscriminantPossiblePlace;
     type SwitchHandler = SymExSwitchHandler<'a, EB>;
 
     #[inline]
@@ -58,11 +79,16 @@ impl<'a, EB: SymExValueUnaryExprBuilder> ConstraintHandler for SymExConstraintHa
         mut self,
         cond: Self::Operand,
         expected: bool,
-        _assert_kind: AssertKind<Self::Operand>,
+        assert_kind: AssertKind<Self::Operand>,
     ) {
-        // For now, we will call this function before the assert occurs and assume that assertions always succeed.
-        // TODO: add a result: bool parameter to this function, and add support for it using a panic hook.
-        if cond.is_symbolic() {
+        if self.is_summarized {
+            return;
+        }
+
+        // This function is called before the assert occurs, so we assume that assertions always succeed.
+        // If it fails, the panic hook reports it (see `assertion`).
+        let is_symbolic = cond.is_symbolic();
+        if is_symbolic {
             // NOTE: This is a trick to pass the value through the expression builder
             // to ensure value resolving and simplifications.
             let cond = self.expr_builder.borrow_mut().no_op(cond);
@@ -74,13 +100,98 @@ impl<'a, EB: SymExValueUnaryExprBuilder> ConstraintHandler for SymExConstraintHa
                 constraint = constraint.not();
             }
 
+            // Tagged so that the inspectors can target the panicking operations.
+            let tag = assertion::tag_of(&assert_kind);
+            if let Some(tag) = tag {
+                self.tags.borrow_mut().push(tag);
+            }
             self.notify_constraint(constraint);
+            if tag.is_some() {
+                self.tags.borrow_mut().pop();
+            }
         }
+        assertion::notify_assertion(self.location, &assert_kind, expected, is_symbolic);
+    }
+
+    fn assert_passed(self) {
+        assertion::clear_pending_assertion();
+    }
+
+    fn panic(self, source: &'static str) {
+        assertion::notify_panic(self.location, source);
+    }
+
+    fn terminate(mut self, result: Self::DiscriminablePlace, discr: abs::Constant, exit_code: u8) {
+        let abs::Constant::Int { ty: discr_ty, .. } = discr else {
+            unreachable!("The discriminant is expected to be an integer: {discr:?}")
+        };
+        let discr = ConstValue::try_from(discr).unwrap();
+
+        // Similar to reading the discriminant in the assignment, but without a destination.
+        let discr_value = match result {
+            DiscriminantPossiblePlace::TagPlaceWithInfo(tag_place, tag_encoding) => {
+                let tag_value = self.vars_state.copy_place(&tag_place);
+                if tag_value.is_symbolic() {
+                    let discr_ty_info = LazyTypeInfo::from((
+                        Some(
+                            CoreTypeProvider::<&TypeInfo>::int_type(self.type_manager, discr_ty).id,
+                        ),
+                        Some(ValueType::Int(discr_ty)),
+                    ));
+                    Some(tag_value.map_value(|value| {
+                        build_discriminant_expr(
+                            &self.expr_builder,
+                            self.type_manager,
+                            SymValueRef::new(value),
+                            tag_encoding,
+                            &discr_ty_info,
+                            tag_place.type_info(),
+                        )
+                        .into()
+                    }))
+                } else {
+                    None
+                }
+            }
+            DiscriminantPossiblePlace::None | DiscriminantPossiblePlace::SingleVariant { .. } => {
+                None
+            }
+        };
+        let discr_value =
+            discr_value.unwrap_or_else(|| Implied::always(discr.clone().to_value_ref()));
+
+        self.notify_constraint(Constraint {
+            discr: discr_value,
+            kind: ConstraintKind::OneOf(vec![discr]),
+        });
+        self.trace_recorder
+            .borrow_mut()
+            .notify_termination(self.location, exit_code);
     }
 }
 
 impl<'a, EB> SymExConstraintHandler<'a, EB> {
     fn notify_constraint(&mut self, constraint: Constraint) {
+        assertion::clear_pending_assertion();
+        if self.is_summarized {
+            return;
+        }
+
+        let matched = match constraint.kind {
+            ConstraintKind::True => Some(true),
+            ConstraintKind::False => Some(false),
+            _ => None,
+        };
+        let chains = self
+            .string_matches
+            .borrow_mut()
+            .current_mut()
+            .notify_decision(self.location, matched);
+        for (head, decision) in chains {
+            self.trace_recorder
+                .borrow_mut()
+                .notify_match_chain(head, decision);
+        }
         self.trace_manager
             .notify_step(Into::into(self.location), constraint);
     }
@@ -123,3 +234,87 @@ impl<'a, EB> SymExSwitchHandler<'a, EB> {
         }
     }
 }
+
+/// Follows the chains of string comparisons that a `match` on a string is lowered to,
+/// so that the trace records each chain as a single multi-way branch, at its first comparison.
+///
+/// # Remarks
+/// The cases of the branch are the basic blocks deciding on the comparisons, i.e.,
+/// the arms of the match. The comparisons with a different length are decided in `str::eq`
+/// without comparing the bytes, thus they appear as a `false` decision with no comparison.
+#[derive(Default)]
+pub(crate) struct StringMatchChains {
+    /// The scrutinee of the last symbolic comparison, not decided on yet.
+    pending: Option<RawAddress>,
+    /// The ongoing chains, at most one per body.
+    chains: Vec<MatchChain>,
+}
+
+struct MatchChain {
+    head: BasicBlockLocation,
+    scrutinee: RawAddress,
+    missed: Vec<BasicBlockIndex>,
+}
+
+impl StringMatchChains {
+    pub(crate) fn notify_comparison(&mut self, scrutinee: RawAddress) {
+        self.pending = Some(scrutinee);
+    }
+
+    /// # Returns
+    /// The chains ended by the decision, as their heads and the multi-way decision taken.
+    fn notify_decision(
+        &mut self,
+        location: BasicBlockLocation,
+        matched: Option<bool>,
+    ) -> Vec<(BasicBlockLocation, ConstraintKind<ConstValue>)> {
+        let comparison = self.pending.take();
+        let chain_index = self
+            .chains
+            .iter()
+            .position(|c| c.head.body == location.body);
+        let mut ended = Vec::new();
+        match (comparison, matched, chain_index) {
+            (Some(scrutinee), Some(matched), chain_index) => {
+                let chain_index = match chain_index {
+                    Some(i) if self.chains[i].scrutinee == scrutinee => i,
+                    _ => {
+                        ended.extend(chain_index.map(|i| self.end(i)));
+                        self.chains.push(MatchChain {
+                            head: location,
+                            scrutinee,
+                            missed: Vec::new(),
+                        });
+                        self.chains.len() - 1
+                    }
+                };
+                if matched {
+                    let chain = self.chains.swap_remove(chain_index);
+                    ended.push((
+                        chain.head,
+                        ConstraintKind::OneOf(vec![Self::case(location.index)]),
+                    ));
+                } else {
+                    self.chains[chain_index].missed.push(location.index);
+                }
+            }
+            (None, Some(false), Some(i)) => self.chains[i].missed.push(location.index),
+            (_, _, Some(i)) => ended.push(self.end(i)),
+            (_, _, None) => {}
+        }
+        ended
+    }
+
+    fn end(&mut self, index: usize) -> (BasicBlockLocation, ConstraintKind<ConstValue>) {
+        let chain = self.chains.swap_remove(index);
+        (
+            chain.head,
+            ConstraintKind::NoneOf(chain.missed.into_iter().map(Self::case).collect()),
+        )
+    }
+
+    #[inline]
+    fn case(index: BasicBlockIndex) -> ConstValue {
+        ConstValue::from(index)
+    }
+}
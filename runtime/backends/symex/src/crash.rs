@@ -0,0 +1,181 @@
+//! Triage of the crashes (panics) of the program under execution.
+//!
+//! Crashes are bucketed by their location, i.e., the source location of the
+//! panic and the basic block it happens at. The first execution hitting a
+//! bucket creates its directory with a description of the crash and a copy of
+//! the input as the reproducer. The later ones are deduplicated.
+//!
+//! Panics caught by `catch_unwind` are not crashes. The compiler marks the calls
+//! catching the panics with a tag (refer to [`common::pri::tags::CATCH_UNWIND`]),
+//! and the panics happening under such a tag are not triaged.
+
+use std::{
+    backtrace::Backtrace,
+    cell::Cell,
+    fs, io,
+    panic::PanicHookInfo,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+};
+
+use serde::Serialize;
+
+use common::{log_info, log_warn};
+
+use leaf_runtime::abs::BasicBlockLocation;
+
+const DESCRIPTION_FILENAME: &str = "crash.json";
+const REPRODUCER_FILENAME: &str = "input";
+
+#[derive(Serialize)]
+struct CrashLocation {
+    /// The source location of the panic.
+    source: Option<String>,
+    location: Option<BasicBlockLocation>,
+}
+
+#[derive(Serialize)]
+struct CrashDescription {
+    bucket: String,
+    #[serde(flatten)]
+    location: CrashLocation,
+    message: Option<String>,
+    backtrace: String,
+}
+
+struct CrashTriage {
+    directory: PathBuf,
+    input: Option<PathBuf>,
+}
+
+/// Shared by all threads, as the panic hook runs on the panicking one.
+static TRIAGE: Mutex<Option<CrashTriage>> = Mutex::new(None);
+
+thread_local! {
+    /// The number of the calls catching the panics that the thread is in.
+    static CATCHING_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+pub(crate) fn init_crash_triage(directory: &Path, input: Option<&Path>) {
+    if input.is_none() {
+        log_warn!("The parent input is not set, crashes will not have reproducers");
+    }
+    *TRIAGE.lock().unwrap_or_else(PoisonError::into_inner) = Some(CrashTriage {
+        directory: directory.to_path_buf(),
+        input: input.map(Path::to_path_buf),
+    });
+}
+
+/// Notifies entering a call that catches the panics unwinding through it.
+pub(crate) fn notify_catch_entered() {
+    CATCHING_DEPTH.set(CATCHING_DEPTH.get() + 1);
+}
+
+/// Notifies returning from a call that catches the panics unwinding through it.
+pub(crate) fn notify_catch_exited() {
+    CATCHING_DEPTH.set(CATCHING_DEPTH.get().saturating_sub(1));
+}
+
+/// Records the crash in its bucket if the triage is enabled and the bucket is new.
+/// `location` is the basic block the execution is at, if known.
+pub(crate) fn triage_panic(info: &PanicHookInfo, location: Option<BasicBlockLocation>) {
+    let triage = TRIAGE.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(triage) = triage.as_ref() else {
+        return;
+    };
+    if is_caught() {
+        log_info!("Panic is caught by the program, not triaged as a crash");
+        return;
+    }
+    let _ = triage
+        .record(info, location)
+        .inspect_err(|e| log_warn!("Could not record the crash: {e}"));
+}
+
+impl CrashTriage {
+    fn record(&self, info: &PanicHookInfo, location: Option<BasicBlockLocation>) -> io::Result<()> {
+        let location = CrashLocation {
+            source: info.location().map(ToString::to_string),
+            location,
+        };
+        let bucket = bucket_of(&location);
+
+        fs::create_dir_all(&self.directory)?;
+        let dir = self.directory.join(&bucket);
+        match fs::create_dir(&dir) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                log_info!("Crash belongs to a known bucket: {bucket}");
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        }
+
+        if let Some(input) = &self.input {
+            fs::copy(input, dir.join(REPRODUCER_FILENAME))?;
+        }
+        let description = CrashDescription {
+            bucket,
+            location,
+            message: info.payload_as_str().map(ToOwned::to_owned),
+            backtrace: Backtrace::force_capture().to_string(),
+        };
+        let file = fs::File::create(dir.join(DESCRIPTION_FILENAME))?;
+        serde_json::to_writer_pretty(file, &description)?;
+        log_info!("New crash bucket written to: {}", dir.display());
+        Ok(())
+    }
+}
+
+/// Whether the panic is going to be caught by the program.
+fn is_caught() -> bool {
+    cfg!(panic = "unwind") && CATCHING_DEPTH.get() > 0
+}
+
+/// Returns the id of the bucket of the crash location.
+/// The id is the FNV-1a hash of the serialized location, thus it is stable
+/// across the builds and the toolchains.
+fn bucket_of(location: &CrashLocation) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let bytes = serde_json::to_vec(location).expect("Crash location is serializable");
+    let hash = bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    });
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_are_stable() {
+        let unknown = CrashLocation {
+            source: None,
+            location: None,
+        };
+        assert_eq!(bucket_of(&unknown), "9a44d5e9a063b2c3");
+
+        let source_only = CrashLocation {
+            source: Some("src/main.rs:3:5".to_owned()),
+            location: None,
+        };
+        assert_eq!(bucket_of(&source_only), "7da782efeb11bbc7");
+    }
+
+    #[test]
+    fn panics_under_catch_are_caught() {
+        assert!(!is_caught());
+        notify_catch_entered();
+        notify_catch_entered();
+        assert_eq!(is_caught(), cfg!(panic = "unwind"));
+        notify_catch_exited();
+        assert_eq!(is_caught(), cfg!(panic = "unwind"));
+        notify_catch_exited();
+        assert!(!is_caught());
+        notify_catch_exited();
+        assert!(!is_caught());
+    }
+}
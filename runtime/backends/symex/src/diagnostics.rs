@@ -0,0 +1,259 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use derive_more as dm;
+use serde::Serialize;
+
+use common::{log_debug, log_info, log_warn};
+
+use common::{types::InstanceKindId, utils::UnsafeSend};
+use leaf_runtime::{abs::BasicBlockLocation, utils::RRef};
+
+use super::alias::backend;
+use backend::{
+    SymExBackend, SymExExeTraceRecorder, config::OutputConfig, expr::builders::InterningStats,
+};
+
+const FILENAME_DEFAULT: &str = "symbolism_loss";
+
+/// The reasons for which symbolic information gets discarded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, dm::Display)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SymbolismLossReason {
+    /// A symbolic place (through dereference, index, or size) is replaced by its concrete value.
+    #[display("place concretization")]
+    PlaceConcretization,
+    /// A symbolic value is replaced by its concrete value while constrained to be equal to it.
+    #[display("stamping")]
+    Stamping,
+    /// Symbolic arguments are passed to an uninstrumented function.
+    #[display("external call with symbolic arguments")]
+    ExternalCallArgs,
+    /// A symbolic value is returned to or from an uninstrumented function.
+    #[display("symbolic return value through external call")]
+    ExternalReturnValue,
+    /// The operation on the symbolic value is not supported by the backend.
+    #[display("unsupported operation")]
+    Unsupported,
+}
+
+#[derive(Serialize)]
+struct LossEntry {
+    location: Option<BasicBlockLocation>,
+    reason: SymbolismLossReason,
+    count: usize,
+    /// The details of the first occurrence.
+    example: String,
+}
+
+struct SymbolismLossRecorder {
+    output: OutputConfig,
+    trace_recorder: RRef<SymExExeTraceRecorder>,
+    entries: HashMap<(Option<BasicBlockLocation>, SymbolismLossReason), (usize, String)>,
+}
+
+impl SymbolismLossRecorder {
+    fn record(&mut self, reason: SymbolismLossReason, details: impl FnOnce() -> String) {
+        // The recorder may be already borrowed if the loss happens during call control.
+        let location = self
+            .trace_recorder
+            .try_borrow()
+            .ok()
+            .and_then(|r| r.current_location());
+        self.entries
+            .entry((location, reason))
+            .and_modify(|(count, _)| *count += 1)
+            .or_insert_with(|| (1, details()));
+    }
+
+    fn dump(&self) -> std::io::Result<()> {
+        let OutputConfig::File(file_config) = &self.output;
+        let (path, file) =
+            file_config.open_or_create_single_with_path(FILENAME_DEFAULT, None, true)?;
+
+        let mut entries = self
+            .entries
+            .iter()
+            .map(|((location, reason), (count, example))| LossEntry {
+                location: *location,
+                reason: *reason,
+                count: *count,
+                example: example.clone(),
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| b.count.cmp(&a.count));
+
+        serde_json::to_writer_pretty(file, &entries)?;
+        log_info!(
+            "Symbolism loss report with {} entries written to: {}",
+            entries.len(),
+            path.display()
+        );
+        Ok(())
+    }
+}
+
+/// Shared by all threads, so that the losses on the spawned threads are recorded as well.
+/// The trace recorder in it is only accessed while reporting from the backend,
+/// thus while the backend is held by the reporting thread.
+static LOSS_RECORDER: Mutex<Option<UnsafeSend<SymbolismLossRecorder>>> = Mutex::new(None);
+
+/// Enables recording of the events that discard symbolic information.
+pub(crate) fn init_symbolism_loss_recording(
+    output: &OutputConfig,
+    trace_recorder: RRef<SymExExeTraceRecorder>,
+) {
+    log_debug!("Enabling symbolism loss recording");
+    *LOSS_RECORDER.lock().unwrap_or_else(PoisonError::into_inner) =
+        Some(UnsafeSend::new(SymbolismLossRecorder {
+            output: output.clone(),
+            trace_recorder,
+            entries: Default::default(),
+        }));
+}
+
+/// Records an event that discards symbolic information if the recording is enabled.
+/// The details are only evaluated for the first occurrence at each location.
+#[inline]
+pub(crate) fn report_symbolism_loss(reason: SymbolismLossReason, details: impl FnOnce() -> String) {
+    if let Some(recorder) = LOSS_RECORDER
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_deref_mut()
+    {
+        recorder.record(reason, details)
+    }
+}
+
+pub(crate) fn dump_symbolism_loss() {
+    if let Some(recorder) = LOSS_RECORDER
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_deref()
+    {
+        let _ = recorder
+            .dump()
+            .inspect_err(|e| log_warn!("Could not write symbolism loss report: {e}"));
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct FuncOverheadEntry {
+    pub body_id: InstanceKindId,
+    /// The number of PRI calls attributed to the function.
+    pub calls: u64,
+    /// The total time spent in the PRI calls attributed to the function.
+    pub total_micros: u128,
+}
+
+type FuncOverheads = HashMap<Option<InstanceKindId>, (u64, Duration)>;
+
+/// Shared by all threads, so that the calls on the spawned threads are attributed as well.
+static FUNC_OVERHEADS: Mutex<Option<FuncOverheads>> = Mutex::new(None);
+static OVERHEAD_RECORDING: AtomicBool = AtomicBool::new(false);
+
+/// Enables the attribution of the time spent in the backend to the function being executed.
+/// The overheads are reported in the execution metrics.
+pub(crate) fn init_func_overhead_recording() {
+    log_debug!("Enabling function overhead recording");
+    *FUNC_OVERHEADS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = Some(Default::default());
+    OVERHEAD_RECORDING.store(true, Ordering::Relaxed);
+}
+
+/// Performs the action and attributes its time to the current function if the recording is enabled.
+/// The current function is the one of the last recorded basic block after the action,
+/// thus the calls at function boundaries are attributed to the entered function.
+#[inline]
+pub(crate) fn measure_func_overhead<T>(
+    backend: &mut SymExBackend,
+    action: impl FnOnce(&mut SymExBackend) -> T,
+) -> T {
+    if !OVERHEAD_RECORDING.load(Ordering::Relaxed) {
+        return action(backend);
+    }
+
+    let start = Instant::now();
+    let result = action(backend);
+    let elapsed = start.elapsed();
+    let body_id = backend.current_location().map(|l| l.body);
+    if let Some(overheads) = FUNC_OVERHEADS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_mut()
+    {
+        let (calls, total) = overheads.entry(body_id).or_default();
+        *calls += 1;
+        *total += elapsed;
+    }
+    result
+}
+
+/// # Returns
+/// The functions sorted by their overhead, if the recording is enabled.
+pub(crate) fn func_overheads() -> Option<Vec<FuncOverheadEntry>> {
+    let guard = FUNC_OVERHEADS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let mut entries = guard
+        .as_ref()?
+        .iter()
+        .map(|(body_id, (calls, total))| FuncOverheadEntry {
+            // Calls before entering any function (e.g., initialization) are reported as invalid.
+            body_id: body_id.unwrap_or(InstanceKindId::INVALID),
+            calls: *calls,
+            total_micros: total.as_micros(),
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| b.total_micros.cmp(&a.total_micros));
+    Some(entries)
+}
+
+/// Stops the recording, as the backend is shutting down.
+pub(crate) fn finish_func_overhead_recording() {
+    if !OVERHEAD_RECORDING.swap(false, Ordering::Relaxed) {
+        return;
+    }
+    if let Some(top) = func_overheads()
+        .as_ref()
+        .and_then(|entries| entries.first())
+    {
+        log_info!(
+            "Function with the highest instrumentation overhead: {} ({} calls, {} µs)",
+            top.body_id,
+            top.calls,
+            top.total_micros,
+        );
+    }
+}
+
+static EXPR_INTERNING: Mutex<Option<InterningStats>> = Mutex::new(None);
+
+/// Records the final statistics of the expression interning, as the backend is
+/// shutting down. They are reported in the execution metrics.
+pub(crate) fn report_expr_interning(stats: InterningStats) {
+    log_info!(
+        "Expression interning: {} hits, {} misses, ~{} bytes saved",
+        stats.hits,
+        stats.misses,
+        stats.saved_bytes,
+    );
+    *EXPR_INTERNING
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = Some(stats);
+}
+
+/// # Returns
+/// The statistics of the expression interning, if already reported.
+pub(crate) fn expr_interning() -> Option<InterningStats> {
+    *EXPR_INTERNING
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
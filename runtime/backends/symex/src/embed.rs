@@ -0,0 +1,67 @@
+//! A public API for embedding the symbolic execution backend directly.
+//!
+//! This allows tools (e.g., differential testers) to drive the backend
+//! programmatically, without instrumenting a program. The events are fed
+//! through [`Pri`], the same program runtime interface that instrumented
+//! programs call through the shim. As in instrumented programs, the backend
+//! is a single instance per process and is configured through the usual
+//! configuration sources.
+//!
+//! ```ignore
+//! use leafrtb_symex::embed::*;
+//!
+//! let backend = EmbeddedBackend::init();
+//! Pri::enter_func(body_id);
+//! let value = Pri::new_sym_value_bool(true);
+//! /* ... */
+//! for constraint in backend.constraints() {
+//!     println!("{constraint:?}");
+//! }
+//! backend.shutdown();
+//! ```
+
+use leaf_runtime::pri::fluent::InstanceManager;
+
+pub use common::pri::ProgramRuntimeInterface;
+pub use common::types::trace::{Constraint, ConstraintKind};
+
+use super::instance::SymExInstanceManager;
+
+/// The program runtime interface of the backend.
+/// Every event is fed by calling the corresponding function.
+pub type Pri = super::interface::DefaultPri;
+
+/// A handle to the embedded backend instance.
+pub struct EmbeddedBackend {
+    _private: (),
+}
+
+impl EmbeddedBackend {
+    /// Initializes the backend instance.
+    /// The type information of the target program is loaded as for instrumented programs.
+    pub fn init() -> Self {
+        Pri::init_runtime_lib();
+        Self { _private: () }
+    }
+
+    /// Returns the constraints of the execution trace so far,
+    /// with the values and cases rendered in their textual form.
+    pub fn constraints(&self) -> Vec<Constraint<String, String>> {
+        SymExInstanceManager::perform_on_backend(|backend| {
+            backend
+                .constraints
+                .borrow()
+                .iter()
+                .map(|c| {
+                    c.as_ref()
+                        .map(|discr| discr.value.to_string(), ToString::to_string)
+                })
+                .collect()
+        })
+    }
+
+    /// Shuts down the backend, which flushes all the outputs (e.g., generated inputs).
+    pub fn shutdown(self) {
+        Pri::shutdown_runtime_lib();
+    }
+}
@@ -144,6 +144,8 @@ where
     impl_binary_expr_method!(shl shl_unchecked shr shr_unchecked);
     impl_binary_expr_method!(rotate_left rotate_right);
     impl_binary_expr_method!(carryless_mul);
+    impl_binary_expr_method!(nand min max);
+    impl_binary_expr_method!(powi minimum maximum);
     impl_binary_expr_method!(eq ne lt le gt ge cmp);
     impl_binary_expr_method!(offset + pointee_size: TypeSize);
 }
@@ -162,6 +164,7 @@ where
 
     impl_unary_expr_method!(no_op not neg ptr_metadata);
     impl_unary_expr_method!(bit_reverse count_ones byte_swap);
+    impl_unary_expr_method!(sqrt abs);
     impl_unary_expr_method!(trailing_zeros + non_zero: bool);
     impl_unary_expr_method!(leading_zeros + non_zero: bool);
 }
@@ -39,6 +39,12 @@ macro_rules! impl_general_binary_op_through_singulars {
                 RotateL => self.rotate_left(operands),
                 RotateR => self.rotate_right(operands),
                 CarrylessMul => self.carryless_mul(operands),
+                Nand => self.nand(operands),
+                Min => self.min(operands),
+                Max => self.max(operands),
+                Powi => self.powi(operands),
+                Minimum => self.minimum(operands),
+                Maximum => self.maximum(operands),
                 Eq => self.eq(operands),
                 Lt => self.lt(operands),
                 Le => self.le(operands),
@@ -92,6 +98,12 @@ macro_rules! impl_singular_binary_ops_through_general {
             (rotate_left = $crate::expr::builders::abs::BinaryOp::RotateL)
             (rotate_right = $crate::expr::builders::abs::BinaryOp::RotateR)
             (carryless_mul = $crate::expr::builders::abs::BinaryOp::CarrylessMul)
+            (nand = $crate::expr::builders::abs::BinaryOp::Nand)
+            (min = $crate::expr::builders::abs::BinaryOp::Min)
+            (max = $crate::expr::builders::abs::BinaryOp::Max)
+            (powi = $crate::expr::builders::abs::BinaryOp::Powi)
+            (minimum = $crate::expr::builders::abs::BinaryOp::Minimum)
+            (maximum = $crate::expr::builders::abs::BinaryOp::Maximum)
             (eq = $crate::expr::builders::abs::BinaryOp::Eq)
             (lt = $crate::expr::builders::abs::BinaryOp::Lt)
             (le = $crate::expr::builders::abs::BinaryOp::Le)
@@ -125,6 +137,8 @@ macro_rules! impl_general_unary_op_through_singulars {
                 NonZeroLeadingZeros => self.leading_zeros(operand, true),
                 LeadingZeros => self.leading_zeros(operand, false),
                 ByteSwap => self.byte_swap(operand),
+                Sqrt => self.sqrt(operand),
+                Abs => self.abs(operand),
             }
         }
     };
@@ -165,6 +179,8 @@ macro_rules! impl_singular_unary_ops_through_general {
                     $crate::expr::builders::abs::UnaryOp::LeadingZeros
                 })
             (byte_swap = $crate::expr::builders::abs::UnaryOp::ByteSwap)
+            (sqrt = $crate::expr::builders::abs::UnaryOp::Sqrt)
+            (abs = $crate::expr::builders::abs::UnaryOp::Abs)
         );
     };
 }
@@ -27,6 +27,8 @@ aug_enum! {
         Shl, ShlUnchecked, Shr, ShrUnchecked,
         RotateL, RotateR,
         CarrylessMul,
+        Nand, Min, Max,
+        Powi, Minimum, Maximum,
         Eq, Lt, Le, Ne, Ge, Gt, Cmp,
         +
         Offset(TypeSize),
@@ -94,6 +96,8 @@ super_enum! {
         NonZeroLeadingZeros,
         LeadingZeros,
         ByteSwap,
+        Sqrt,
+        Abs,
         +
         NoOp,
     }
@@ -177,6 +181,8 @@ pub(crate) trait BinaryExprBuilder {
     bin_fn_signature!(shl shl_unchecked shr shr_unchecked);
     bin_fn_signature!(rotate_left rotate_right);
     bin_fn_signature!(carryless_mul);
+    bin_fn_signature!(nand min max);
+    bin_fn_signature!(powi minimum maximum);
     bin_fn_signature!(eq ne lt le gt ge cmp);
     bin_fn_signature!(offset + pointee_size: TypeSize);
 }
@@ -189,6 +195,7 @@ pub(crate) trait UnaryExprBuilder {
 
     unary_fn_signature!(no_op not neg ptr_metadata);
     unary_fn_signature!(bit_reverse count_ones byte_swap);
+    unary_fn_signature!(sqrt abs);
     unary_fn_signature!(trailing_zeros + non_zero: bool);
     unary_fn_signature!(leading_zeros + non_zero: bool);
 }
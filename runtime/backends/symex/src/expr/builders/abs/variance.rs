@@ -63,6 +63,8 @@ where
     delegate_binary_op!(shl shl_unchecked shr shr_unchecked);
     delegate_binary_op!(rotate_left rotate_right);
     delegate_binary_op!(carryless_mul);
+    delegate_binary_op!(nand min max);
+    delegate_binary_op!(powi minimum maximum);
     delegate_binary_op!(eq ne lt le gt ge cmp);
     delegate_binary_op!(offset + pointee_size: TypeSize);
 }
@@ -107,6 +109,7 @@ where
     delegate_singular_unary_op!(unary_op + op: UnaryOp);
     delegate_singular_unary_op!(no_op not neg ptr_metadata);
     delegate_singular_unary_op!(bit_reverse count_ones byte_swap);
+    delegate_singular_unary_op!(sqrt abs);
     delegate_singular_unary_op!(trailing_zeros + non_zero: bool);
     delegate_singular_unary_op!(leading_zeros + non_zero: bool);
 }
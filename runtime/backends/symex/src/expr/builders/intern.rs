@@ -0,0 +1,269 @@
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    rc::{Rc, Weak},
+};
+
+use serde::Serialize;
+
+use super::*;
+
+/// The identity of a node given its operands, which are identified by their allocations.
+/// # Remarks
+/// As interned operands are shared, structurally equal nodes built on top of them
+/// get the same key. Operands that are not interned (e.g., constants) only lead to
+/// missed sharing, not incorrect sharing.
+#[derive(PartialEq, Eq, Hash)]
+enum NodeKey {
+    Unary(u8, *const Value),
+    Binary {
+        operator: u8,
+        bound_check: Option<bool>,
+        operands: (*const Value, *const Value, bool),
+    },
+    Offset((*const Value, *const Value, bool), TypeSize),
+    Extension(*const Value, bool, u32, ValueType),
+    Truncation(*const Value, IntType),
+    Ite(*const Value, *const Value, *const Value),
+}
+
+impl NodeKey {
+    fn of(value: &ValueRef) -> Option<Self> {
+        let Value::Symbolic(SymValue::Expression(expr)) = value.as_ref() else {
+            return None;
+        };
+        let key = match expr {
+            Expr::Unary { operator, operand } => Self::Unary(*operator as u8, ptr(operand)),
+            Expr::Binary(BinaryExpr { operator, operands }) => Self::Binary {
+                operator: *operator as u8,
+                bound_check: None,
+                operands: operands_key(operands),
+            },
+            Expr::BinaryBoundCheck {
+                bin_expr: BinaryExpr { operator, operands },
+                is_overflow,
+            } => Self::Binary {
+                operator: *operator as u8,
+                bound_check: Some(*is_overflow),
+                operands: operands_key(operands),
+            },
+            Expr::Offset {
+                operands,
+                pointee_size,
+            } => Self::Offset(operands_key(operands), *pointee_size),
+            Expr::Extension(ExtensionExpr {
+                source,
+                is_zero_ext,
+                bits_to_add,
+                ty,
+            }) => Self::Extension(ptr(source), *is_zero_ext, bits_to_add.get(), *ty),
+            Expr::Truncation(TruncationExpr { source, ty }) => Self::Truncation(ptr(source), *ty),
+            Expr::Ite {
+                condition,
+                if_target,
+                else_target,
+            } => Self::Ite(ptr(condition), ptr(if_target), ptr(else_target)),
+            Expr::FloatCast(..)
+            | Expr::Transmutation { .. }
+            | Expr::Multi(..)
+            | Expr::Ref(..)
+            | Expr::Partial(..)
+            | Expr::Concat(..)
+            | Expr::PtrMetadata(..) => return None,
+        };
+        Some(key)
+    }
+}
+
+#[inline]
+fn ptr(value: &ValueRef) -> *const Value {
+    Rc::as_ptr(&value.0)
+}
+
+#[inline]
+fn operands_key(operands: &SymBinaryOperands) -> (*const Value, *const Value, bool) {
+    (
+        ptr(operands.first()),
+        ptr(operands.second()),
+        matches!(operands, SymBinaryOperands::Rev { .. }),
+    )
+}
+
+/// Interns the expressions so that structurally identical ones share a single allocation.
+/// # Remarks
+/// The table only holds weak references, thus it does not keep the expressions alive.
+/// Also, mutating a value through `make_mut` disassociates it from the table.
+pub(crate) struct ExprInterner {
+    table: HashMap<NodeKey, Weak<Value>>,
+    purge_threshold: usize,
+    hits: usize,
+    misses: usize,
+}
+
+impl Default for ExprInterner {
+    fn default() -> Self {
+        Self {
+            table: HashMap::new(),
+            purge_threshold: Self::MIN_PURGE_THRESHOLD,
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl ExprInterner {
+    const MIN_PURGE_THRESHOLD: usize = 1 << 12;
+
+    pub(crate) fn intern(&mut self, value: ValueRef) -> ValueRef {
+        let Some(key) = NodeKey::of(&value) else {
+            return value;
+        };
+
+        match self.table.entry(key) {
+            Entry::Occupied(mut entry) => {
+                if let Some(existing) = entry.get().upgrade() {
+                    self.hits += 1;
+                    return ValueRef(existing);
+                }
+                entry.insert(Rc::downgrade(&value.0));
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(Rc::downgrade(&value.0));
+            }
+        }
+        self.misses += 1;
+
+        if self.table.len() > self.purge_threshold {
+            self.purge();
+        }
+        value
+    }
+
+    /// Removes the entries of the expressions that are no longer alive.
+    fn purge(&mut self) {
+        self.table.retain(|_, value| value.strong_count() > 0);
+        self.purge_threshold = (self.table.len() * 2).max(Self::MIN_PURGE_THRESHOLD);
+    }
+
+    /// The (approximate) number of bytes saved by sharing the allocations.
+    pub(crate) fn saved_bytes(&self) -> usize {
+        self.hits * core::mem::size_of::<Value>()
+    }
+
+    pub(crate) fn stats(&self) -> InterningStats {
+        InterningStats {
+            hits: self.hits as u64,
+            misses: self.misses as u64,
+            saved_bytes: self.saved_bytes() as u64,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub(crate) struct InterningStats {
+    /// The number of built expressions replaced by an existing one.
+    pub hits: u64,
+    pub misses: u64,
+    /// The (approximate) number of bytes saved by sharing the allocations.
+    pub saved_bytes: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const U32: IntType = IntType {
+        bit_size: 32,
+        is_signed: false,
+    };
+
+    fn var(id: u32) -> SymValueRef {
+        SymValue::Variable(SymbolicVar::new(id, U32.into())).to_value_ref()
+    }
+
+    fn int(value: u128) -> ValueRef {
+        ConstValue::new_int(value, U32).to_value_ref()
+    }
+
+    fn add(first: &SymValueRef, second: &ValueRef) -> ValueRef {
+        Expr::Binary(BinaryExpr {
+            operator: SymExBinaryOp::Add,
+            operands: SymBinaryOperands::Orig {
+                first: first.clone(),
+                second: second.clone(),
+            },
+        })
+        .to_value_ref()
+        .into()
+    }
+
+    #[test]
+    fn equal_expressions_share_the_allocation() {
+        let mut interner = ExprInterner::default();
+        let (x, one) = (var(1), int(1));
+
+        let first = interner.intern(add(&x, &one));
+        let second = interner.intern(add(&x, &one));
+
+        assert!(Rc::ptr_eq(&first.0, &second.0));
+        assert_eq!(
+            interner.stats(),
+            InterningStats {
+                hits: 1,
+                misses: 1,
+                saved_bytes: core::mem::size_of::<Value>() as u64,
+            }
+        );
+    }
+
+    #[test]
+    fn operands_are_compared_by_allocation() {
+        let mut interner = ExprInterner::default();
+        let x = var(1);
+
+        let first = interner.intern(add(&x, &int(1)));
+        let second = interner.intern(add(&x, &int(1)));
+
+        assert!(!Rc::ptr_eq(&first.0, &second.0));
+        assert_eq!(interner.stats().hits, 0);
+    }
+
+    #[test]
+    fn dropped_expressions_are_not_revived() {
+        let mut interner = ExprInterner::default();
+        let (x, one) = (var(1), int(1));
+
+        drop(interner.intern(add(&x, &one)));
+        let value = interner.intern(add(&x, &one));
+
+        assert_eq!(Rc::strong_count(&value.0), 1);
+        assert_eq!(interner.stats().hits, 0);
+        assert_eq!(interner.stats().misses, 2);
+    }
+
+    #[test]
+    fn non_expressions_are_passed_through() {
+        let mut interner = ExprInterner::default();
+        let x: ValueRef = var(1).into();
+
+        let value = interner.intern(x.clone());
+
+        assert!(Rc::ptr_eq(&value.0, &x.0));
+        assert_eq!(interner.stats(), InterningStats::default());
+        assert!(interner.table.is_empty());
+    }
+
+    #[test]
+    fn purging_keeps_the_alive_entries() {
+        let mut interner = ExprInterner::default();
+        let (x, one) = (var(1), int(1));
+        let alive = interner.intern(add(&x, &one));
+
+        for i in 0..=ExprInterner::MIN_PURGE_THRESHOLD {
+            drop(interner.intern(add(&x, &int(i as u128))));
+        }
+
+        assert!(interner.table.len() <= ExprInterner::MIN_PURGE_THRESHOLD);
+        let value = interner.intern(add(&x, &one));
+        assert!(Rc::ptr_eq(&value.0, &alive.0));
+    }
+}
@@ -1,4 +1,5 @@
 pub(super) mod abs;
+mod intern;
 
 use leaf_runtime::{
     abs::CastKind,
@@ -50,6 +51,15 @@ pub(crate) fn to_implied_expr_builder(
     adapters::ImpliedValueRefExprBuilderAdapter(expr_builder)
 }
 
+pub(crate) use intern::InterningStats;
+
+impl DefaultImpliedExprBuilder {
+    /// The statistics of sharing the built symbolic expressions.
+    pub(crate) fn interning_stats(&self) -> InterningStats {
+        self.0.borrow().interning_stats()
+    }
+}
+
 impl ValueRefExprBuilder for DefaultExprBuilder {}
 
 impl ValueRefBinaryExprBuilder for DefaultExprBuilder {}
@@ -57,7 +67,7 @@ impl ValueRefBinaryExprBuilder for DefaultExprBuilder {}
 impl ImpliedValueRefUnaryExprBuilder for DefaultImpliedExprBuilder {}
 
 mod toplevel {
-    use super::{concrete::ConcreteBuilder, symbolic::SymbolicBuilder, *};
+    use super::{concrete::ConcreteBuilder, intern::ExprInterner, symbolic::SymbolicBuilder, *};
 
     /// An expression builder that separates the path for expressions that involve symbolic values,
     /// or the ones that are fully based on concrete values.
@@ -66,6 +76,7 @@ mod toplevel {
     pub(crate) struct TopLevelBuilder {
         sym_builder: SymbolicBuilder,
         conc_builder: ConcreteBuilder,
+        interner: ExprInterner,
     }
 
     impl TopLevelBuilder {
@@ -76,8 +87,13 @@ mod toplevel {
             Self {
                 sym_builder: SymbolicBuilder::new(type_manager.clone()),
                 conc_builder: ConcreteBuilder::default(),
+                interner: ExprInterner::default(),
             }
         }
+
+        pub(crate) fn interning_stats(&self) -> super::InterningStats {
+            self.interner.stats()
+        }
     }
 
     impl BinaryExprBuilder for TopLevelBuilder {
@@ -90,7 +106,10 @@ mod toplevel {
             op: AbsBinaryOp,
         ) -> Self::Expr<'a> {
             match SymBinaryOperands::try_from(operands) {
-                Ok(operands) => self.sym_builder.binary_op(operands, op),
+                Ok(operands) => {
+                    let result = self.sym_builder.binary_op(operands, op);
+                    self.interner.intern(result)
+                }
                 Err((first, second)) => self.conc_builder.binary_op(
                     (ConcreteValueRef::new(first), ConcreteValueRef::new(second)),
                     op,
@@ -107,9 +126,11 @@ mod toplevel {
 
         fn unary_op<'a>(&mut self, operand: Self::ExprRef<'a>, op: AbsUnaryOp) -> Self::Expr<'a> {
             if operand.is_symbolic() {
-                self.sym_builder
+                let result = self
+                    .sym_builder
                     .unary_op(SymValueRef::new(operand).into(), op)
-                    .into()
+                    .into();
+                self.interner.intern(result)
             } else {
                 self.conc_builder
                     .unary_op(ConcreteValueRef::new(operand).into(), op)
@@ -129,12 +150,14 @@ mod toplevel {
             op: AbsTernaryOp,
         ) -> Self::Expr<'a> {
             if operands.0.is_symbolic() || operands.1.is_symbolic() || operands.2.is_symbolic() {
-                self.sym_builder
+                let result = self
+                    .sym_builder
                     .ternary_op(
                         SymTernaryOperands::new(operands.0, operands.1, operands.2),
                         op,
                     )
-                    .into()
+                    .into();
+                self.interner.intern(result)
             } else {
                 self.conc_builder.ternary_op(
                     (
@@ -162,9 +185,11 @@ mod toplevel {
             metadata: Self::Metadata<'b>,
         ) -> Self::Expr<'a> {
             if operand.is_symbolic() {
-                self.sym_builder
+                let result = self
+                    .sym_builder
                     .cast(SymValueRef::new(operand).into(), target, metadata)
-                    .into()
+                    .into();
+                self.interner.intern(result)
             } else {
                 self.conc_builder
                     .cast(ConcreteValueRef::new(operand).into(), target, metadata)
@@ -299,9 +324,11 @@ mod symbolic {
                             *value = retrieved.to_value_ref();
                         }
                         Err(type_info) if expect_scalar => {
-                            panic!(
+                            crate::failure::fail!(
+                                InconsistentState,
                                 "Expected the value to be retrievable as a scalar: {:?}: {:?}",
-                                value, type_info,
+                                value,
+                                type_info,
                             );
                         }
                         _ => (),
@@ -322,9 +349,11 @@ mod symbolic {
                             *value = resolved.into();
                         }
                         Err(type_info) if expect_scalar => {
-                            panic!(
+                            crate::failure::fail!(
+                                InconsistentState,
                                 "Porter value participating in an expression is expected to be convertible to masked value {:?}: {:?}",
-                                value, type_info,
+                                value,
+                                type_info,
                             );
                         }
                         _ => (),
@@ -383,7 +412,7 @@ mod symbolic {
                     NoOp => false,
                     PtrMetadata => false,
                     Not | Neg | BitReverse | NonZeroTrailingZeros | TrailingZeros | CountOnes
-                    | NonZeroLeadingZeros | LeadingZeros | ByteSwap => true,
+                    | NonZeroLeadingZeros | LeadingZeros | ByteSwap | Sqrt | Abs => true,
                 }
             };
             self.resolve_sym(operand.as_mut(), expect_scalar);
@@ -1030,6 +1059,10 @@ mod core {
                     _ => unreachable!(),
                 };
                 self.saturating_op(operands, wrapping_op).into()
+            } else if let AbsBinaryOp::Powi = op {
+                unreachable!(
+                    "Symbolic powi is expected to be concretized as no solver theory supports it."
+                )
             } else if let AbsBinaryOp::Offset(pointee_size) = op {
                 Expr::Offset {
                     operands,
@@ -1087,8 +1120,11 @@ mod core {
             let (make_check_expr_if_possible, wrapping_value, ty) =
                 self.break_down_for_overflow(operands, wrapping_op);
             // (Sign-extended bit representation)
+            // NOTE: Shifting by the full width (u128) must result in zero, not wrap around.
             let min_of_ty = Wrapping(
-                u128::MAX.wrapping_shl(ty.bit_size as u32 - (if ty.is_signed { 1 } else { 0 })),
+                u128::MAX
+                    .checked_shl(ty.bit_size as u32 - (if ty.is_signed { 1 } else { 0 }))
+                    .unwrap_or(0),
             );
 
             let value = wrapping_value;
@@ -1167,7 +1203,7 @@ mod core {
             match op {
                 NoOp => operand,
                 Neg | Not | BitReverse | TrailingZeros | NonZeroTrailingZeros | LeadingZeros
-                | NonZeroLeadingZeros | CountOnes | ByteSwap => Expr::Unary {
+                | NonZeroLeadingZeros | CountOnes | ByteSwap | Sqrt | Abs => Expr::Unary {
                     operator: op.try_into().unwrap(),
                     operand: operand,
                 }
@@ -1290,14 +1326,19 @@ mod core {
                             self.truncate(operand, ty)
                         }
                     }
-                    ValueType::Float { .. } => todo!(),
+                    ValueType::Float { .. } => Expr::FloatCast(FloatCastExpr {
+                        source: operand,
+                        ty: ty.into(),
+                    })
+                    .to_value_ref(),
                 }
             }
             // Special case for u8 as we don't really need the source type for it.
             else if ty == IntType::U8 {
                 self.truncate(operand, ty)
             } else {
-                panic!(
+                crate::failure::fail!(
+                    InconsistentState,
                     "Could not determine the type of the operand for int cast: {:?}",
                     operand,
                 )
@@ -1306,11 +1347,23 @@ mod core {
 
         fn to_float<'a, 'b>(
             &mut self,
-            _operand: Self::ExprRef<'a>,
-            _ty: Self::FloatType,
+            operand: Self::ExprRef<'a>,
+            ty: Self::FloatType,
             _metadata: Self::Metadata<'b>,
         ) -> Self::Expr<'a> {
-            todo!()
+            match ValueType::try_from(operand.value()) {
+                Ok(ValueType::Float(from_ty)) if from_ty == ty => operand,
+                Ok(ValueType::Int(..) | ValueType::Float(..)) => Expr::FloatCast(FloatCastExpr {
+                    source: operand,
+                    ty: ty.into(),
+                })
+                .to_value_ref(),
+                _ => crate::failure::fail!(
+                    InconsistentState,
+                    "Could not determine the type of the operand for float cast: {:?}",
+                    operand,
+                ),
+            }
         }
 
         fn to_ptr<'a, 'b>(
@@ -1329,7 +1382,10 @@ mod core {
             _metadata: Self::Metadata<'b>,
         ) -> Self::Expr<'a> {
             // NOTE: Implementation only requires a concrete value to be used as metadata.
-            todo!("#331: Add support for casting symbolic pointers")
+            crate::failure::fail!(
+                UnsupportedFeature,
+                "#331: Casting symbolic pointers is not supported."
+            )
         }
 
         fn expose_prov<'a, 'b>(
@@ -1337,7 +1393,10 @@ mod core {
             _operand: Self::ExprRef<'a>,
             _metadata: Self::Metadata<'b>,
         ) -> Self::Expr<'a> {
-            todo!("#331: Add support for casting symbolic pointers")
+            crate::failure::fail!(
+                UnsupportedFeature,
+                "#331: Casting symbolic pointers is not supported."
+            )
         }
 
         fn transmute<'a, 'b>(
@@ -1583,6 +1642,34 @@ mod simp {
         }
     }
 
+    /// If the other operand is an ITE with two distinct constant targets one of which
+    /// is the constant operand, returns its condition and whether the constant is the
+    /// if target.
+    fn ite_condition_for_const(
+        operands: &WithConstOperand<'_, SymValueRef>,
+    ) -> Option<(SymValueRef, bool)> {
+        let (other, konst, _) = operands.as_flat();
+        let SymValue::Expression(Expr::Ite {
+            condition,
+            if_target,
+            else_target,
+        }) = other.as_ref()
+        else {
+            return None;
+        };
+        let as_const = |target: &ValueRef| target.as_conc().and_then(ConcreteValue::as_const);
+        let (if_target, else_target) = (as_const(if_target)?, as_const(else_target)?);
+        if if_target == else_target {
+            None
+        } else if if_target == *konst {
+            Some((condition.clone(), true))
+        } else if else_target == *konst {
+            Some((condition.clone(), false))
+        } else {
+            None
+        }
+    }
+
     impl BinaryExprBuilder for ConstSimplifier {
         type ExprRefPair<'a> = WithConstOperand<'a, SymValueRef>;
         type Expr<'a> = Result<ValueRef, Self::ExprRefPair<'a>>;
@@ -1683,8 +1770,8 @@ mod simp {
             else if operands.is_second_one() {
                 Ok((&match operands.konst() {
                     ConstValue::Int { ty, .. } => ConstValue::new_int(0_u128, *ty),
-                    ConstValue::Float { .. } => todo!(),
-                    _ => unreachable!("The second operand should be numeric."),
+                    // Floats are never checked to be one.
+                    _ => unreachable!("The second operand should be an integer."),
                 })
                     .into())
             } else {
@@ -1791,6 +1878,30 @@ mod simp {
             Err(operands)
         }
 
+        fn nand<'a>(&mut self, operands: Self::ExprRefPair<'a>) -> Self::Expr<'a> {
+            Err(operands)
+        }
+
+        fn min<'a>(&mut self, operands: Self::ExprRefPair<'a>) -> Self::Expr<'a> {
+            Err(operands)
+        }
+
+        fn max<'a>(&mut self, operands: Self::ExprRefPair<'a>) -> Self::Expr<'a> {
+            Err(operands)
+        }
+
+        fn powi<'a>(&mut self, operands: Self::ExprRefPair<'a>) -> Self::Expr<'a> {
+            Err(operands)
+        }
+
+        fn minimum<'a>(&mut self, operands: Self::ExprRefPair<'a>) -> Self::Expr<'a> {
+            Err(operands)
+        }
+
+        fn maximum<'a>(&mut self, operands: Self::ExprRefPair<'a>) -> Self::Expr<'a> {
+            Err(operands)
+        }
+
         fn eq<'a>(&mut self, operands: Self::ExprRefPair<'a>) -> Self::Expr<'a> {
             if operands.konst() == &ConstValue::Bool(true) {
                 Ok(operands.other_into())
@@ -1801,6 +1912,18 @@ mod simp {
                 }
                 .to_value_ref()
                 .into())
+            } else if let Some((condition, is_if_target)) = ite_condition_for_const(&operands) {
+                // ite(c, k, k') == k = c
+                Ok(if is_if_target {
+                    condition.into()
+                } else {
+                    Expr::Unary {
+                        operator: UnaryOp::Not,
+                        operand: condition,
+                    }
+                    .to_value_ref()
+                    .into()
+                })
             } else {
                 Err(operands)
             }
@@ -1834,6 +1957,18 @@ mod simp {
                 .into())
             } else if operands.konst() == &ConstValue::Bool(false) {
                 Ok(operands.other_into())
+            } else if let Some((condition, is_if_target)) = ite_condition_for_const(&operands) {
+                // ite(c, k, k') != k = !c
+                Ok(if is_if_target {
+                    Expr::Unary {
+                        operator: UnaryOp::Not,
+                        operand: condition,
+                    }
+                    .to_value_ref()
+                    .into()
+                } else {
+                    condition.into()
+                })
             } else {
                 Err(operands)
             }
@@ -2282,6 +2417,30 @@ mod simp {
             Err(operands)
         }
 
+        fn nand<'a>(&mut self, operands: Self::ExprRefPair<'a>) -> Self::Expr<'a> {
+            Err(operands)
+        }
+
+        fn min<'a>(&mut self, operands: Self::ExprRefPair<'a>) -> Self::Expr<'a> {
+            Err(operands)
+        }
+
+        fn max<'a>(&mut self, operands: Self::ExprRefPair<'a>) -> Self::Expr<'a> {
+            Err(operands)
+        }
+
+        fn powi<'a>(&mut self, operands: Self::ExprRefPair<'a>) -> Self::Expr<'a> {
+            Err(operands)
+        }
+
+        fn minimum<'a>(&mut self, operands: Self::ExprRefPair<'a>) -> Self::Expr<'a> {
+            Err(operands)
+        }
+
+        fn maximum<'a>(&mut self, operands: Self::ExprRefPair<'a>) -> Self::Expr<'a> {
+            Err(operands)
+        }
+
         fn eq<'a>(&mut self, operands: Self::ExprRefPair<'a>) -> Self::Expr<'a> {
             Err(operands)
         }
@@ -2376,6 +2535,21 @@ mod simp {
             Err(operand)
         }
 
+        fn sqrt<'a>(&mut self, operand: Self::ExprRef<'a>) -> Self::Expr<'a> {
+            Err(operand)
+        }
+
+        fn abs<'a>(&mut self, operand: Self::ExprRef<'a>) -> Self::Expr<'a> {
+            match operand.as_ref() {
+                // abs(abs(x)) = abs(x)
+                SymValue::Expression(Expr::Unary {
+                    operator: SymExUnaryOp::Abs,
+                    ..
+                }) => Ok(operand),
+                _ => Err(operand),
+            }
+        }
+
         fn byte_swap<'a>(&mut self, operand: Self::ExprRef<'a>) -> Self::Expr<'a> {
             match operand.as_ref() {
                 SymValue::Expression(Expr::Unary {
@@ -2521,7 +2695,11 @@ mod shift {
                         .map(ValueType::expect_int)
                         .zip(second_ty.map(ValueType::expect_int))
                         .unwrap_or_else(|| {
-                            panic!("Could not find types for operands: {:?}", operands)
+                            crate::failure::fail!(
+                                InconsistentState,
+                                "Could not find types for operands: {:?}",
+                                operands
+                            )
                         });
 
                     if first_ty == second_ty {
@@ -2531,7 +2709,8 @@ mod shift {
                     let (x, y, is_reversed) = operands.flatten();
                     if is_reversed || y.is_symbolic() {
                         let second = x;
-                        panic!(
+                        crate::failure::fail!(
+                            InconsistentState,
                             "Symbolic right hand side of a shift operation is expected to be cast before: {:?}",
                             second,
                         );
@@ -2545,7 +2724,11 @@ mod shift {
                                 second: ConstValue::integer_cast(second, first_ty).to_value_ref(),
                             },
                             _ => {
-                                panic!("Only const integer values are expected: {:?}", second);
+                                crate::failure::fail!(
+                                    InconsistentState,
+                                    "Only const integer values are expected: {:?}",
+                                    second
+                                );
                             }
                         }
                     }
@@ -2619,7 +2802,11 @@ mod shift {
                             ConstValue::binary_op(second, &mask, BinaryOp::BitAnd).to_value_ref()
                         }
                         Value::Concrete(..) => {
-                            panic!("Only const integer values are expected: {:?}", second);
+                            crate::failure::fail!(
+                                InconsistentState,
+                                "Only const integer values are expected: {:?}",
+                                second
+                            );
                         }
                         Value::Symbolic(..) => {
                             // Const shift amounts (e.g., division) are much more common.
@@ -2646,6 +2833,26 @@ mod shift {
 mod translators {
     use super::*;
 
+    /// The integer type with twice the width of `ty`, in which the results that do not fit
+    /// `ty` are computed.
+    fn double_width_int_type(
+        ty: IntType,
+        type_manager: &dyn TypeDatabase,
+    ) -> (IntType, LazyTypeInfo) {
+        let ty = IntType {
+            bit_size: ty.bit_size * 2,
+            ..ty
+        };
+        // There is no core type wider than 128 bits (for i128/u128 operands),
+        // but the symbolic expressions do not depend on it.
+        let info: LazyTypeInfo = if ty.bit_size <= u128::BITS as u64 {
+            type_manager.int_type(ty)
+        } else {
+            LazyTypeInfo::None
+        };
+        (ty, info)
+    }
+
     mod funnel_shift {
         use super::*;
 
@@ -2711,11 +2918,7 @@ mod translators {
                         .expect_int();
                     (ty, self.type_manager.int_type(ty))
                 };
-                let double_ty: (IntType, LazyTypeInfo) = {
-                    let mut ty = ty.0.clone();
-                    ty.bit_size *= 2;
-                    (ty, self.type_manager.int_type(ty))
-                };
+                let double_ty = double_width_int_type(ty.0, self.type_manager.as_ref());
 
                 let shift = if !shift.is_symbolic() {
                     let shift_val = *shift
@@ -2724,7 +2927,8 @@ mod translators {
                         .inspect(|(_, shift_ty)| debug_assert_eq!(*shift_ty, &IntType::U32))
                         .map(|(val, _)| val)
                         .unwrap_or_else(|| {
-                            panic!(
+                            crate::failure::fail!(
+                                InconsistentState,
                                 "Shift value is expected to be a retrieved scalar: {:?}",
                                 shift
                             )
@@ -2768,7 +2972,8 @@ mod translators {
                 .as_conc()
                 .and_then(|v| v.as_const())
                 .unwrap_or_else(|| {
-                    panic!(
+                    crate::failure::fail!(
+                        InconsistentState,
                         "Unexpected concrete value in a funnel shift operation: {:?}",
                         value
                     )
@@ -2781,7 +2986,11 @@ mod translators {
             ty: IntType,
             double_ty: &(IntType, LazyTypeInfo),
         ) -> ValueRef {
-            if !first.is_symbolic() && !second.is_symbolic() {
+            // NOTE: Constants wider than 128 bits (for i128/u128 halves) are not representable.
+            if !first.is_symbolic()
+                && !second.is_symbolic()
+                && double_ty.0.bit_size <= u128::BITS as u64
+            {
                 let first = expect_const_value(&first);
                 let second = expect_const_value(&second);
 
@@ -2883,6 +3092,49 @@ mod translators {
                 }
             }
         }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            fn concat_consts(high: u128, low: u128, bit_size: u64) -> ValueRef {
+                let ty = IntType {
+                    bit_size,
+                    is_signed: false,
+                };
+                let double_ty = IntType {
+                    bit_size: bit_size * 2,
+                    is_signed: false,
+                };
+                concat_left_sides(
+                    ConstValue::new_int(high, ty).to_value_ref(),
+                    ConstValue::new_int(low, ty).to_value_ref(),
+                    ty,
+                    &(double_ty, LazyTypeInfo::None),
+                )
+            }
+
+            #[test]
+            fn constant_halves_of_u64_are_folded() {
+                let concatenated = concat_consts(1, 2, 64);
+                assert_eq!(
+                    concatenated.as_conc().and_then(|c| c.as_int()).unwrap().0,
+                    &((1 << 64) | 2)
+                );
+            }
+
+            #[test]
+            fn constant_halves_of_u128_are_concatenated_symbolically() {
+                let concatenated = concat_consts(u128::MAX, 2, 128);
+                let Value::Symbolic(SymValue::Expression(Expr::Concat(ConcatExpr {
+                    values, ..
+                }))) = concatenated.as_ref()
+                else {
+                    panic!("Unexpected concatenation: {:?}", concatenated);
+                };
+                assert_eq!(values.len(), 2);
+            }
+        }
     }
     pub(super) use funnel_shift::FunnelShiftSymbolicTranslator;
 
@@ -2958,11 +3210,7 @@ mod translators {
                         .expect_int();
                     (ty, type_manager.int_type(ty))
                 };
-                let double_ty: (IntType, LazyTypeInfo) = {
-                    let mut ty = ty.0.clone();
-                    ty.bit_size *= 2;
-                    (ty, type_manager.int_type(ty))
-                };
+                let double_ty = double_width_int_type(ty.0, type_manager);
                 let unsigned_ty: (IntType, LazyTypeInfo) = {
                     let mut ty = ty.0.clone();
                     ty.is_signed = false;
@@ -2999,3 +3247,151 @@ mod translators {
     pub(crate) use carrying_mul::CarryingMulAddBuilderExt;
 }
 pub(crate) use translators::CarryingMulAddBuilderExt;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const I8: IntType = IntType {
+        bit_size: 8,
+        is_signed: true,
+    };
+    const I128: IntType = IntType {
+        bit_size: 128,
+        is_signed: true,
+    };
+    const U128: IntType = IntType {
+        bit_size: 128,
+        is_signed: false,
+    };
+
+    fn build_with_one(op: AbsBinaryOp, ty: IntType) -> Value {
+        let operands = SymBinaryOperands::Orig {
+            first: SymValue::Variable(SymbolicVar::new(1, ty.into())).to_value_ref(),
+            second: ConstValue::new_int(1_u128, ty).to_value_ref(),
+        };
+        super::core::CoreBuilder.binary_op(operands, op)
+    }
+
+    fn as_bound_check(value: &Value) -> Option<(OverflowingBinaryOp, bool)> {
+        match value {
+            Value::Symbolic(SymValue::Expression(Expr::BinaryBoundCheck {
+                bin_expr,
+                is_overflow,
+            })) => Some((bin_expr.operator, *is_overflow)),
+            _ => None,
+        }
+    }
+
+    /// The bounds that a saturating result is clamped to, as (underflow, overflow).
+    fn saturation_bounds(value: &Value) -> (Option<u128>, Option<u128>) {
+        let mut bounds = (None, None);
+        let mut value = value;
+        while let Value::Symbolic(SymValue::Expression(Expr::Ite {
+            condition,
+            if_target,
+            else_target,
+        })) = value
+        {
+            let (_, is_overflow) = as_bound_check(&condition.clone_to()).unwrap();
+            let bound = if_target.as_conc().and_then(|c| c.as_int()).unwrap().0;
+            if is_overflow {
+                bounds.1 = Some(*bound);
+            } else {
+                bounds.0 = Some(*bound);
+            }
+            value = &***else_target;
+        }
+        bounds
+    }
+
+    /// The bound checks in the second field of a with-overflow result.
+    fn overflow_checks(value: &Value) -> Vec<(OverflowingBinaryOp, bool)> {
+        fn collect(value: &Value, checks: &mut Vec<(OverflowingBinaryOp, bool)>) {
+            if let Some(check) = as_bound_check(value) {
+                checks.push(check);
+            } else if let Value::Symbolic(SymValue::Expression(Expr::Binary(BinaryExpr {
+                operator: SymExBinaryOp::BitOr,
+                operands,
+            }))) = value
+            {
+                collect(operands.first(), checks);
+                collect(operands.second(), checks);
+            } else {
+                panic!("Unexpected overflow check: {:?}", value);
+            }
+        }
+
+        let Value::Concrete(ConcreteValue::Adt(AdtValue { fields, .. })) = value else {
+            panic!("Unexpected with-overflow result: {:?}", value);
+        };
+        let mut checks = Vec::new();
+        collect(fields[1].value.as_ref().unwrap(), &mut checks);
+        checks
+    }
+
+    #[test]
+    fn saturating_ops_clamp_to_the_bounds_of_i128() {
+        let bounds = (Some(i128::MIN as u128), Some(i128::MAX as u128));
+        assert_eq!(
+            saturation_bounds(&build_with_one(AbsBinaryOp::AddSaturating, I128)),
+            bounds
+        );
+        assert_eq!(
+            saturation_bounds(&build_with_one(AbsBinaryOp::SubSaturating, I128)),
+            bounds
+        );
+    }
+
+    #[test]
+    fn saturating_ops_clamp_to_the_bounds_of_u128() {
+        assert_eq!(
+            saturation_bounds(&build_with_one(AbsBinaryOp::AddSaturating, U128)),
+            (None, Some(u128::MAX))
+        );
+        assert_eq!(
+            saturation_bounds(&build_with_one(AbsBinaryOp::SubSaturating, U128)),
+            (Some(0), None)
+        );
+    }
+
+    #[test]
+    fn saturating_ops_clamp_to_the_sign_extended_bounds_of_narrow_types() {
+        assert_eq!(
+            saturation_bounds(&build_with_one(AbsBinaryOp::AddSaturating, I8)),
+            (Some(i8::MIN as u128), Some(i8::MAX as u128))
+        );
+    }
+
+    #[test]
+    fn with_overflow_ops_check_both_bounds_of_i128() {
+        use OverflowingBinaryOp::*;
+        for (op, wrapping_op) in [
+            (AbsBinaryOp::AddWithOverflow, Add),
+            (AbsBinaryOp::SubWithOverflow, Sub),
+            (AbsBinaryOp::MulWithOverflow, Mul),
+        ] {
+            assert_eq!(
+                overflow_checks(&build_with_one(op, I128)),
+                vec![(wrapping_op, true), (wrapping_op, false)]
+            );
+        }
+    }
+
+    #[test]
+    fn with_overflow_ops_check_only_the_possible_bound_of_u128() {
+        use OverflowingBinaryOp::*;
+        assert_eq!(
+            overflow_checks(&build_with_one(AbsBinaryOp::AddWithOverflow, U128)),
+            vec![(Add, true)]
+        );
+        assert_eq!(
+            overflow_checks(&build_with_one(AbsBinaryOp::SubWithOverflow, U128)),
+            vec![(Sub, false)]
+        );
+        assert_eq!(
+            overflow_checks(&build_with_one(AbsBinaryOp::MulWithOverflow, U128)),
+            vec![(Mul, true)]
+        );
+    }
+}
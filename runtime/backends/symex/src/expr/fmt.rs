@@ -162,6 +162,7 @@ impl Expr {
             Expr::Offset { .. } => write!(f, "{}", leaf_runtime::abs::BinaryOp::Offset),
             Expr::Extension(..) => write!(f, "Ext"),
             Expr::Truncation(..) => write!(f, "Trunc"),
+            Expr::FloatCast(..) => write!(f, "FCast"),
             Expr::Ite { .. } => write!(f, "Ite"),
             Expr::Transmutation { .. } => write!(f, "Trans"),
             Expr::Multi(_) => write!(f, "Multi"),
@@ -194,6 +195,7 @@ impl Expr {
                 if *is_zero_ext { "0" } else { "S" }
             ),
             Expr::Truncation(TruncationExpr { source, ty }) => write!(f, "{source}, |- as {ty}"),
+            Expr::FloatCast(FloatCastExpr { source, ty }) => write!(f, "{source} as {ty}"),
             Expr::Ite {
                 condition,
                 if_target,
@@ -0,0 +1,76 @@
+use super::*;
+
+/// Evaluates the symbolic value to a constant if it does not depend on the values
+/// of the symbolic variables, e.g., `x == x` or `ite(c, y, y)`.
+/// # Remarks
+/// The builders already fold the operations on constants at construction, so this
+/// mainly finds the cases that are only recognizable in the whole expression
+/// (e.g., the identical operands through shared subexpressions).
+pub(crate) fn try_fold(value: &SymValue) -> Option<ConstValue> {
+    let SymValue::Expression(expr) = value else {
+        return None;
+    };
+
+    match expr {
+        Expr::Unary {
+            operator: UnaryOp::Not,
+            operand,
+        } => match try_fold_ref(operand)? {
+            ConstValue::Bool(value) => Some(ConstValue::Bool(!value)),
+            _ => None,
+        },
+        Expr::Binary(BinaryExpr { operator, operands }) => {
+            fold_binary(*operator, operands.first(), operands.second())
+        }
+        Expr::Ite {
+            condition,
+            if_target,
+            else_target,
+        } => match try_fold_ref(condition) {
+            Some(ConstValue::Bool(true)) => try_fold_ref(if_target),
+            Some(ConstValue::Bool(false)) => try_fold_ref(else_target),
+            _ if if_target == else_target => try_fold_ref(if_target),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn try_fold_ref(value: &ValueRef) -> Option<ConstValue> {
+    match value.as_ref() {
+        Value::Concrete(conc) => conc.as_const().cloned(),
+        Value::Symbolic(sym) => try_fold(sym),
+    }
+}
+
+fn fold_binary(operator: BinaryOp, first: &ValueRef, second: &ValueRef) -> Option<ConstValue> {
+    use BinaryOp::*;
+
+    if first == second {
+        // NOTE: The identities do not hold for floats because of NaNs.
+        return match (operator, ValueType::try_from(first).ok()?) {
+            (_, ValueType::Float(..)) => None,
+            (Eq | Le | Ge, _) => Some(ConstValue::Bool(true)),
+            (Ne | Lt | Gt, _) => Some(ConstValue::Bool(false)),
+            (Sub | BitXor, ValueType::Bool) => Some(ConstValue::Bool(false)),
+            (Sub | BitXor, ValueType::Int(ty)) => Some(ConstValue::new_int(0u128, ty)),
+            _ => None,
+        };
+    }
+
+    let (first, second) = (try_fold_ref(first)?, try_fold_ref(second)?);
+    let foldable = match operator {
+        Eq | Lt | Le | Ne | Ge | Gt => matches!(
+            (&first, &second),
+            (ConstValue::Bool(..), ConstValue::Bool(..))
+                | (ConstValue::Char(..), ConstValue::Char(..))
+                | (ConstValue::Int { .. }, ConstValue::Int { .. })
+        ),
+        Add | Sub | BitXor | BitAnd | BitOr => matches!(
+            (&first, &second),
+            (ConstValue::Int { .. }, ConstValue::Int { .. })
+        ),
+        _ => false,
+    };
+    foldable.then(|| ConstValue::binary_op(&first, &second, operator))
+}
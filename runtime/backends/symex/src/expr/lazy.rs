@@ -128,7 +128,9 @@ mod retrieval {
             match self {
                 Self::Fetched(ty) => *ty,
                 Self::Forced(ref ty) => ty.as_ref(),
-                Self::None => panic!("Type info is not available."),
+                Self::None => {
+                    crate::failure::fail!(InconsistentState, "Type info is not available.")
+                }
                 Self::Id(..) | Self::IdPrimitive(..) | Self::IdSize(..) => unreachable!(),
             }
         }
@@ -213,7 +215,10 @@ mod retrieval {
                             Err(())
                         }
                     }),
-                "f32" | "f64" => unimplemented!(),
+                "f32" | "f64" => crate::failure::fail!(
+                    UnsupportedFeature,
+                    "Retrieval of float values is not supported yet."
+                ),
                 _ if ty.pointee_ty.is_some() => match ty.as_single_variant() {
                     Some(VariantInfo {
                         fields: FieldsShapeInfo::NoFields,
@@ -279,7 +284,10 @@ mod retrieval {
                 bit_rep: Wrapping(retrieve_int(addr, *bit_size as usize)),
                 ty: *ty,
             },
-            ScalarType::Float(_) => unimplemented!(),
+            ScalarType::Float(_) => crate::failure::fail!(
+                UnsupportedFeature,
+                "Retrieval of float values is not supported yet."
+            ),
             ScalarType::Address => ConstValue::Addr(*(to_ptr::<*const ()>(addr))),
         }
     }
@@ -308,7 +316,8 @@ mod retrieval {
     ) -> ConcreteValue {
         log_debug!("Retrieving value at {:p} of type: {:?}", addr, ty.id);
         let unsupported = || {
-            unimplemented!(
+            crate::failure::fail!(
+                UnsupportedFeature,
                 "Evaluation of raw concrete value with this type is not supported yet. {:?}",
                 ty
             )
@@ -1,5 +1,6 @@
 pub(super) mod builders;
 mod fmt;
+pub(crate) mod fold;
 pub(crate) mod lazy;
 pub(super) mod place;
 pub(crate) mod prelude;
@@ -89,8 +90,6 @@ pub(crate) enum ConcreteValue {
     Unevaluated(UnevalValue),
 }
 
-// FIXME: Remove this error suppression after adding support for floats.
-#[allow(unused)]
 #[derive(Clone, Debug, PartialEq, Eq, dm::From)]
 pub(crate) enum ConstValue {
     Bool(bool),
@@ -121,7 +120,9 @@ impl ConstValue {
         match self {
             Self::Bool(value) => !value,
             Self::Int { bit_rep, .. } => *bit_rep == Wrapping(0),
-            Self::Float { .. } => todo!(),
+            /* NOTE: The identities based on zero and one (e.g., `x + 0 = x`) do not hold
+             * for IEEE-754 numbers because of signed zeros and NaNs. */
+            Self::Float { .. } => false,
             _ => unreachable!("Only numerical values can be checked for zero: {:?}", self),
         }
     }
@@ -131,7 +132,7 @@ impl ConstValue {
         match self {
             Self::Bool(value) => *value,
             Self::Int { bit_rep, .. } => *bit_rep == Wrapping(1),
-            Self::Float { .. } => todo!(),
+            Self::Float { .. } => false,
             _ => unreachable!("Only numerical values can be checked for one."),
         }
     }
@@ -312,6 +313,8 @@ mod operators {
             LeadingZeros +| NonZeroLeadingZeros,
             CountOnes,
             ByteSwap,
+            Sqrt,
+            Abs,
         }
     }
 
@@ -333,6 +336,11 @@ mod operators {
             RotateL,
             RotateR,
             CarrylessMul,
+            Nand,
+            Min,
+            Max,
+            Minimum,
+            Maximum,
 
             Eq,
             Lt,
@@ -396,6 +404,8 @@ pub(crate) enum Expr {
 
     Truncation(TruncationExpr),
 
+    FloatCast(FloatCastExpr),
+
     Ite {
         condition: SymValueRef,
         if_target: ValueRef,
@@ -454,6 +464,14 @@ pub(crate) struct TruncationExpr {
     pub(crate) ty: IntType,
 }
 
+/// A numeric conversion with a floating-point source or destination.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct FloatCastExpr {
+    pub(crate) source: SymValueRef,
+    // The destination type can be an integer or a floating-point type.
+    pub(crate) ty: ValueType,
+}
+
 pub(crate) type SymIndex = SliceIndex<SymValueRef>;
 pub(crate) type MultiValueLeaf = ValueRef;
 pub(crate) type MultiValueTree<V = MultiValueLeaf> = SymbolicReadTree<SymIndex, V>;
@@ -725,7 +743,7 @@ mod convert {
                 }),
                 Float { bit_rep, ty } => Ok(Self::Float { bit_rep, ty }),
                 Addr(addr) => Ok(Self::Addr(addr)),
-                Zst | Str(..) | ByteStr(..) | Some => Err(value),
+                Zst | Str(..) | ByteStr(..) | ByRef { .. } | FnItem(..) | Some => Err(value),
             }
         }
     }
@@ -736,6 +754,18 @@ mod convert {
             use abs::Constant::*;
             match val {
                 Zst | Str(..) | ByteStr(..) | Some => UnevalValue::Some.into(),
+                // The bytes are in the static memory, thus can be read lazily as any other value.
+                ByRef { bytes, ty } => UnevalValue::Lazy(RawConcreteValue(
+                    bytes.as_ptr() as RawAddress,
+                    LazyTypeInfo::Id(ty),
+                ))
+                .into(),
+                // No bytes are read for a function item, but its type resolves the function.
+                FnItem(ty) => UnevalValue::Lazy(RawConcreteValue(
+                    core::ptr::NonNull::<()>::dangling().as_ptr() as RawAddress,
+                    LazyTypeInfo::Id(ty),
+                ))
+                .into(),
                 _ => Self::Const(val.try_into().unwrap()),
             }
         }
@@ -823,6 +853,7 @@ mod convert {
         BinaryExpr,
         ExtensionExpr,
         TruncationExpr,
+        FloatCastExpr,
         PorterValue,
         ConcatExpr,
     );
@@ -880,7 +911,8 @@ mod convert {
                         use BinaryOp::*;
                         match operator {
                             Eq | Lt | Le | Ne | Ge | Gt => Ok(ValueType::Bool),
-                            Add | Sub | Mul | Div | Rem | BitXor | BitAnd | BitOr => {
+                            Add | Sub | Mul | Div | Rem | BitXor | BitAnd | BitOr | Nand | Min
+                            | Max | Minimum | Maximum => {
                                 ValueType::try_from(operands).map_err(|_| value)
                             }
                             Shl | Shr | RotateL | RotateR | CarrylessMul => {
@@ -898,6 +930,7 @@ mod convert {
                     }
                     Expr::Extension(ExtensionExpr { ty, .. }) => Ok(ty.clone()),
                     Expr::Truncation(TruncationExpr { ty, .. }) => Ok((*ty).into()),
+                    Expr::FloatCast(FloatCastExpr { ty, .. }) => Ok(ty.clone()),
                     Expr::Ite {
                         if_target,
                         else_target,
@@ -1035,13 +1068,32 @@ mod ops {
                     Self::binary_op_arithmetic(first, second, operator.try_into().unwrap())
                 }
                 Shl | Shr => Self::binary_op_shift(first, second, operator),
+                Nand => match Self::binary_op_arithmetic(first, second, BitAnd) {
+                    Self::Int { bit_rep, ty } => Self::Int {
+                        bit_rep: Wrapping(Self::to_size((!bit_rep).0, &ty)),
+                        ty,
+                    },
+                    Self::Bool(value) => Self::Bool(!value),
+                    _ => unreachable!(),
+                },
+                Min | Max => {
+                    let is_first =
+                        Self::binary_op_cmp(first, second, if operator == Min { Le } else { Ge });
+                    if is_first { first } else { second }.clone()
+                }
 
                 Eq | Lt | Le | Ne | Ge | Gt => ConstValue::Bool(Self::binary_op_cmp(
                     first,
                     second,
                     operator.try_into().unwrap(),
                 )),
-                _ => unimplemented!("{:?} {:?} {:?}", first, second, operator),
+                _ => crate::failure::fail!(
+                    UnsupportedFeature,
+                    "Binary operation is not supported for constants: {:?} {:?} {:?}",
+                    first,
+                    second,
+                    operator
+                ),
             }
         }
 
@@ -1071,7 +1123,27 @@ mod ops {
                     bit_rep: Wrapping(*value as u128),
                     ty: to,
                 },
-                Self::Float { .. } => todo!("Casting float to integer is not implemented yet."),
+                // Like `as`, the fraction is truncated, and the out-of-range values saturate.
+                Self::Float { bit_rep, ty } => {
+                    let value = match ty.e_bits + ty.s_bits {
+                        32 => f32::from_bits(*bit_rep as u32) as f64,
+                        64 => f64::from_bits(*bit_rep as u64),
+                        _ => crate::failure::fail!(
+                            UnsupportedFeature,
+                            "Casting {this:?} to integer is not supported."
+                        ),
+                    };
+                    let shift = 128 - to.bit_size as u32;
+                    let bit_rep = if to.is_signed {
+                        (value as i128).clamp(i128::MIN >> shift, i128::MAX >> shift) as u128
+                    } else {
+                        (value as u128).min(u128::MAX >> shift)
+                    };
+                    Self::Int {
+                        bit_rep: Wrapping(Self::to_size(bit_rep, &to)),
+                        ty: to,
+                    }
+                }
                 _ => unreachable!("Casting {this:?} to integer is not possible."),
             }
         }
@@ -1108,7 +1180,10 @@ mod ops {
                     }
                 }
 
-                (Self::Float { .. }, Self::Float { .. }) => unimplemented!(),
+                (Self::Float { .. }, Self::Float { .. }) => crate::failure::fail!(
+                    UnsupportedFeature,
+                    "Arithmetic operations on float constants are not supported yet."
+                ),
 
                 (Self::Bool(first_value), Self::Bool(second_value)) => {
                     let result = match operator {
@@ -1204,9 +1279,17 @@ mod ops {
                         }
                     }
 
-                    (Self::Float { .. }, Self::Float { .. }) => unimplemented!(),
-
-                    _ => unimplemented!(),
+                    (Self::Float { .. }, Self::Float { .. }) => crate::failure::fail!(
+                        UnsupportedFeature,
+                        "Comparison of float constants is not supported yet."
+                    ),
+
+                    _ => crate::failure::fail!(
+                        InconsistentState,
+                        "Comparison of constants with different types: {:?} {:?}",
+                        first,
+                        second
+                    ),
                 },
             }
         }
@@ -1,8 +1,9 @@
 pub(crate) use super::{
     AdtField, AdtKind, AdtValue, ArrayValue, BinaryExpr, ConcatExpr, ConcreteValue,
-    ConcreteValueRef, ConstValue, Expr, ExtensionExpr, FatPtrValue, LazyTypeInfo, MultiValue,
-    MultiValueLeaf, MultiValueTree, PorterValue, RawAddress, RawConcreteValue, SymValue,
-    SymValueRef, SymbolicVar, TruncationExpr, TypeId, UnevalValue, Value, ValueRef, ValueType,
+    ConcreteValueRef, ConstValue, Expr, ExtensionExpr, FatPtrValue, FloatCastExpr, LazyTypeInfo,
+    MultiValue, MultiValueLeaf, MultiValueTree, PorterValue, RawAddress, RawConcreteValue,
+    SymValue, SymValueRef, SymbolicVar, TruncationExpr, TypeId, UnevalValue, Value, ValueRef,
+    ValueType,
     builders::{
         BinaryExprBuilder, CarryingMulAddBuilderExt, CastExprBuilder, TernaryExprBuilder,
         UnaryExprBuilder, abs::BinaryOp as ExprBuilderBinaryOp,
@@ -6,14 +6,15 @@ pub(crate) mod z3 {
         default::Default,
         mem::{discriminant, size_of},
         ops::Not,
+        rc::Rc,
     };
 
     use z3::ast;
 
-    use leaf_runtime::abs::{IntType, ValueType};
+    use leaf_runtime::abs::{FloatType, IntType, ValueType};
 
-    use common::log_debug;
     use common::z3::*;
+    use common::{log_debug, log_warn};
 
     use super::{
         super::{BinaryOp, OverflowingBinaryOp, SymBinaryOperands, SymVarId, UnaryOp, prelude::*},
@@ -25,17 +26,42 @@ pub(crate) mod z3 {
     const ADDR_BIT_SIZE: u32 = size_of::<*const ()>() as u32 * 8;
     const POSSIBLE_VALUES_PREFIX: &str = "pvs";
 
+    /// Provides the concrete value of a symbolic variable in the current execution.
+    pub(crate) type SeedProvider = Rc<dyn Fn(SymVarId) -> Option<ConstValue>>;
+
     #[derive(Clone)]
     pub(crate) struct Z3ValueTranslator {
         variables: HashMap<SymVarId, AstNode>,
+        /// The maximum number of possible values for which a select is encoded as a chain of ITEs.
+        select_ite_max_size: usize,
+        /// The maximum number of expression nodes to translate for a single value.
+        node_budget: Option<usize>,
+        seeds: Option<SeedProvider>,
+        /// When set, subexpressions larger than this are replaced by their concrete values.
+        concretization_threshold: Option<usize>,
+        sizes: HashMap<*const SymValue, usize>,
+        /// The translations of the nodes in the current value, so that shared
+        /// (e.g., interned) subexpressions are translated once.
+        translated: HashMap<*const SymValue, AstNode>,
     }
 
     impl Z3ValueTranslator {
-        pub(crate) fn new() -> Self {
+        pub(crate) fn new(select_ite_max_size: usize, node_budget: Option<usize>) -> Self {
             Self {
                 variables: Default::default(),
+                select_ite_max_size,
+                node_budget,
+                seeds: None,
+                concretization_threshold: None,
+                sizes: Default::default(),
+                translated: Default::default(),
             }
         }
+
+        pub(crate) fn with_seeds(mut self, seeds: SeedProvider) -> Self {
+            self.seeds = Some(seeds);
+            self
+        }
     }
 
     type TranslatedValue = AstAndVars<SymVarId>;
@@ -121,7 +147,25 @@ pub(crate) mod z3 {
     impl Z3ValueTranslator {
         pub(crate) fn translate(&mut self, value: &ValueRef) -> AstAndVars<SymVarId> {
             log_debug!(target: TAG, "Translating value: {}", value);
-            let ast = self.translate_value(value);
+            let ast = match (self.node_budget, self.seeds.is_some(), value.as_ref()) {
+                (Some(budget), true, Value::Symbolic(symbolic @ SymValue::Expression(expr)))
+                    if self.measure_symbolic(symbolic, budget) > budget =>
+                {
+                    log_warn!(
+                        target: TAG,
+                        "Value exceeds the translation budget of {} nodes, concretizing its largest subexpressions.",
+                        budget,
+                    );
+                    // The root itself is translated as usual, otherwise the whole value becomes constant.
+                    self.concretization_threshold = Some(budget / 2);
+                    let ast = self.translate_symbolic_expr(expr);
+                    self.concretization_threshold = None;
+                    ast
+                }
+                _ => self.translate_value(value),
+            };
+            self.sizes.clear();
+            self.translated.clear();
             AstAndVars {
                 value: ast,
                 variables: self.variables.drain().collect(),
@@ -129,6 +173,124 @@ pub(crate) mod z3 {
         }
     }
 
+    impl Z3ValueTranslator {
+        /// Translates the value with its variables substituted by their concrete values
+        /// and simplifies the result, which effectively results in a constant.
+        fn translate_seeded(&mut self, symbolic: &SymValue) -> AstNode {
+            let threshold = self.concretization_threshold.take();
+            let variables = std::mem::take(&mut self.variables);
+            // The seeded translations are not valid outside.
+            let translated = std::mem::take(&mut self.translated);
+            let ast = self.translate_symbolic(symbolic);
+            let seeded_vars = std::mem::replace(&mut self.variables, variables);
+            self.translated = translated;
+            self.concretization_threshold = threshold;
+
+            let seeds = self.seeds.clone().unwrap();
+            let mut substitutions = Vec::with_capacity(seeded_vars.len());
+            for (id, node) in seeded_vars {
+                match seeds(id) {
+                    Some(seed) => {
+                        substitutions.push((node.dyn_ast(), self.translate_const(&seed).dyn_ast()))
+                    }
+                    // Keep the variable if no concrete value is available.
+                    None => {
+                        self.variables.insert(id, node);
+                    }
+                }
+            }
+            let substitutions = substitutions
+                .iter()
+                .map(|(var, seed)| (var, seed))
+                .collect::<Vec<_>>();
+            let simplified = ast.dyn_ast().substitute(&substitutions).simplify();
+            AstNode::from_ast(simplified, &ast.sort())
+        }
+
+        /// Measures the size of the expression tree up to the given cap.
+        /// Sizes are cached per node, so shared subexpressions are measured once.
+        fn measure_symbolic(&mut self, symbolic: &SymValue, cap: usize) -> usize {
+            let key = symbolic as *const SymValue;
+            if let Some(size) = self.sizes.get(&key) {
+                return *size;
+            }
+
+            let size = match symbolic {
+                SymValue::Variable(_) => 1,
+                SymValue::Expression(expr) => {
+                    let mut size = 1usize;
+                    for operand in Self::operands_of(expr) {
+                        if size > cap {
+                            break;
+                        }
+                        size = size.saturating_add(self.measure_value(operand, cap));
+                    }
+                    size
+                }
+            };
+            self.sizes.insert(key, size);
+            size
+        }
+
+        fn measure_value(&mut self, value: &ValueRef, cap: usize) -> usize {
+            match value.as_ref() {
+                Value::Concrete(_) => 1,
+                Value::Symbolic(symbolic) => self.measure_symbolic(symbolic, cap),
+            }
+        }
+
+        fn operands_of(expr: &Expr) -> Vec<&ValueRef> {
+            use Expr::*;
+            match expr {
+                Unary { operand, .. } => vec![operand.as_ref()],
+                Binary(BinaryExpr { operands, .. })
+                | BinaryBoundCheck {
+                    bin_expr: BinaryExpr { operands, .. },
+                    ..
+                }
+                | Offset { operands, .. } => vec![operands.first(), operands.second()],
+                Extension(ExtensionExpr { source, .. })
+                | Truncation(TruncationExpr { source, .. })
+                | FloatCast(FloatCastExpr { source, .. })
+                | Transmutation { source, .. }
+                | PtrMetadata(source) => vec![source.as_ref()],
+                Ite {
+                    condition,
+                    if_target,
+                    else_target,
+                } => vec![condition.as_ref(), if_target, else_target],
+                Multi(select) => {
+                    let mut operands = Vec::new();
+                    Self::collect_select_operands(select, &mut operands);
+                    operands
+                }
+                Concat(ConcatExpr { values, .. }) => values.iter().collect(),
+                Ref(..) | Partial(..) => vec![],
+            }
+        }
+
+        fn collect_select_operands<'a>(select: &'a MultiValue, operands: &mut Vec<&'a ValueRef>) {
+            use super::super::sym_place::SelectTarget;
+            operands.push(select.index.index.as_ref());
+            match &select.target {
+                SelectTarget::Array(values) => values
+                    .iter()
+                    .for_each(|v| Self::collect_tree_operands(v, operands)),
+                SelectTarget::Nested(box inner) => Self::collect_select_operands(inner, operands),
+            }
+        }
+
+        fn collect_tree_operands<'a>(tree: &'a MultiValueTree, operands: &mut Vec<&'a ValueRef>) {
+            match tree {
+                MultiValueTree::Single(single) => operands.push(single),
+                MultiValueTree::Array(values) => values
+                    .iter()
+                    .for_each(|v| Self::collect_tree_operands(v, operands)),
+                MultiValueTree::SymRead(select) => Self::collect_select_operands(select, operands),
+            }
+        }
+    }
+
     impl Z3ValueTranslator {
         fn translate_value(&mut self, value: &ValueRef) -> AstNode {
             match value.as_ref() {
@@ -140,19 +302,20 @@ pub(crate) mod z3 {
         fn translate_concrete(&mut self, concrete: &ConcreteValue) -> AstNode {
             match concrete {
                 ConcreteValue::Const(c) => self.translate_const(c),
-                ConcreteValue::Adt(_) => {
-                    unimplemented!("Expressions involving ADTs directly are not supported.")
-                }
+                ConcreteValue::Adt(_) => crate::failure::fail!(
+                    SolverError,
+                    "Expressions involving ADTs directly are not supported."
+                ),
                 ConcreteValue::Array(array) => AstNode::Array(self.translate_array(array)),
-                ConcreteValue::FatPointer(_) => {
-                    panic!("Pointer value should not exist at this phase.")
-                }
-                ConcreteValue::Unevaluated(unevaluated) => {
-                    panic!(
-                        "Unevaluated value should not exist at this phase. {:?}",
-                        unevaluated
-                    )
-                }
+                ConcreteValue::FatPointer(_) => crate::failure::fail!(
+                    InconsistentState,
+                    "Pointer value should not exist at this phase."
+                ),
+                ConcreteValue::Unevaluated(unevaluated) => crate::failure::fail!(
+                    InconsistentState,
+                    "Unevaluated value should not exist at this phase. {:?}",
+                    unevaluated
+                ),
             }
         }
 
@@ -182,7 +345,12 @@ pub(crate) mod z3 {
                     };
                     BVNode::new(ast, *is_signed).into()
                 }
-                ConstValue::Float { .. } => todo!(),
+                ConstValue::Float { bit_rep, ty } => {
+                    let sort = float_sort(ty);
+                    let bits =
+                        ast::BV::from_str(sort.e_bits + sort.s_bits, &bit_rep.to_string()).unwrap();
+                    FloatNode(ast::Float::from_ieee_bv(&bits, sort), sort).into()
+                }
                 ConstValue::Addr(addr) => {
                     BVNode::new(ast::BV::from_u64(*addr as u64, ADDR_BIT_SIZE), false).into()
                 }
@@ -194,9 +362,23 @@ pub(crate) mod z3 {
         }
 
         fn translate_symbolic(&mut self, symbolic: &SymValue) -> AstNode {
+            if let Some(threshold) = self.concretization_threshold {
+                if self.measure_symbolic(symbolic, threshold) > threshold {
+                    return self.translate_seeded(symbolic);
+                }
+            }
+
             match symbolic {
                 SymValue::Variable(var) => self.translate_symbolic_var(var),
-                SymValue::Expression(expr) => self.translate_symbolic_expr(expr),
+                SymValue::Expression(expr) => {
+                    let key = symbolic as *const SymValue;
+                    if let Some(ast) = self.translated.get(&key) {
+                        return ast.clone();
+                    }
+                    let ast = self.translate_symbolic_expr(expr);
+                    self.translated.insert(key, ast.clone());
+                    ast
+                }
             }
         }
 
@@ -215,7 +397,11 @@ pub(crate) mod z3 {
                     let ast = ast::BV::new_const(var.id, bit_size as u32);
                     BVNode::new(ast, is_signed).into()
                 }
-                ValueType::Float { .. } => todo!(),
+                ValueType::Float(ref ty) => {
+                    let sort = float_sort(ty);
+                    let ast = ast::Float::new_const(var.id, sort.e_bits, sort.s_bits);
+                    FloatNode(ast, sort).into()
+                }
             };
             self.variables.insert(var.id, node.clone());
             node
@@ -265,6 +451,10 @@ pub(crate) mod z3 {
                     let source = self.translate_symbolic(source);
                     self.translate_truncation_expr(source, ty.bit_size as u32, ty.is_signed)
                 }
+                FloatCast(FloatCastExpr { source, ty }) => {
+                    let source = self.translate_symbolic(source);
+                    self.translate_float_cast_expr(source, ty)
+                }
                 Ite {
                     condition,
                     if_target,
@@ -277,15 +467,12 @@ pub(crate) mod z3 {
                 }
                 Transmutation { source, dst_ty } => {
                     let ast = self.translate_symbolic(source);
-                    let ast = if let Some(bv_sort) = ValueType::try_from(dst_ty)
-                        .ok()
-                        .and_then(|value_ty| BVSort::try_from(value_ty).ok())
-                    {
-                        ast.transmute(bv_sort)
+                    if let Ok(dst_ty) = ValueType::try_from(dst_ty) {
+                        self.translate_transmutation_expr(ast, dst_ty)
                     } else {
+                        // Non-scalar destinations keep the bits as they are.
                         ast
-                    };
-                    ast
+                    }
                 }
                 Multi(select) => self.translate_select(select, None),
                 Concat(ConcatExpr { values, ty }) => {
@@ -325,7 +512,13 @@ pub(crate) mod z3 {
                     LeadingZeros => self.translate_count_zeros_expr::<false>(bv),
                     CountOnes => self.translate_count_ones_expr(bv),
                     ByteSwap => self.translate_byte_swap_expr(bv),
+                    Sqrt | Abs => {
+                        unreachable!("{operator:?} is only expected for floating-point numbers.")
+                    }
                 },
+                (Neg, AstNode::Float(float)) => float.map(FloatExt::neg).into(),
+                (Sqrt, AstNode::Float(float)) => float.map(FloatExt::sqrt_rne).into(),
+                (Abs, AstNode::Float(float)) => float.map(FloatExt::abs).into(),
                 (operator, operand) => unreachable!(
                     "Unary operator {operator:?} is not supported for the operand: {operand:?}"
                 ),
@@ -360,6 +553,7 @@ pub(crate) mod z3 {
                         BinaryOp::BitAnd => ast::Bool::and(&[left, right]),
                         BinaryOp::BitOr => ast::Bool::or(&[left, right]),
                         BinaryOp::BitXor => ast::Bool::xor(left, right),
+                        BinaryOp::Nand => ast::Bool::and(&[left, right]).not(),
                         _ => {
                             unreachable!(
                                 "Bool can only used with logical operators. {:?}",
@@ -413,6 +607,7 @@ pub(crate) mod z3 {
                             (BinaryOp::BitXor, _) => Some(ast::BV::bvxor),
                             (BinaryOp::BitAnd, _) => Some(ast::BV::bvand),
                             (BinaryOp::BitOr, _) => Some(ast::BV::bvor),
+                            (BinaryOp::Nand, _) => Some(ast::BV::bvnand),
                             (BinaryOp::Shl, _) => Some(ast::BV::bvshl),
                             (BinaryOp::Shr, true) => Some(ast::BV::bvashr),
                             (BinaryOp::Shr, false) => Some(ast::BV::bvlshr),
@@ -457,6 +652,19 @@ pub(crate) mod z3 {
                                 let gt = self.translate_ite_expr(gt_check, greater, equal);
                                 self.translate_ite_expr(lt_check, less, gt)
                             })),
+                            (BinaryOp::Min | BinaryOp::Max, _) => Some(Box::new(|left, right| {
+                                let check_op = if operator == BinaryOp::Min {
+                                    BinaryOp::Le
+                                } else {
+                                    BinaryOp::Ge
+                                };
+                                let check = self.translate_binary_expr(
+                                    check_op,
+                                    left.clone(),
+                                    right.clone(),
+                                );
+                                self.translate_ite_expr(check, left, right)
+                            })),
                             (BinaryOp::CarrylessMul, _) => {
                                 assert!(
                                     !is_signed,
@@ -476,10 +684,60 @@ pub(crate) mod z3 {
                         .or_else(handle_other_func)
                         .unwrap()
                 }
+                AstNode::Float(FloatNode(ref left, sort)) => {
+                    let AstNode::Float(FloatNode(ref right, _)) = right else {
+                        unreachable!()
+                    };
+                    use BinaryOp::*;
+                    match operator {
+                        Add => FloatNode(left.add_rne(right), sort).into(),
+                        Sub => FloatNode(left.sub_rne(right), sort).into(),
+                        Mul => FloatNode(left.mul_rne(right), sort).into(),
+                        Div => FloatNode(left.div_rne(right), sort).into(),
+                        Rem => FloatNode(Self::translate_float_rem(left, right), sort).into(),
+                        Min => FloatNode(left.fp_min(right), sort).into(),
+                        Max => FloatNode(left.fp_max(right), sort).into(),
+                        Minimum | Maximum => {
+                            // Unlike minNum and maxNum, NaN operands propagate.
+                            let result = if operator == Minimum {
+                                left.fp_min(right)
+                            } else {
+                                left.fp_max(right)
+                            };
+                            let result = right.is_nan().ite(right, &result);
+                            FloatNode(left.is_nan().ite(left, &result), sort).into()
+                        }
+                        Eq => left.fp_eq(right).into(),
+                        Ne => left.fp_eq(right).not().into(),
+                        Lt => left.fp_lt(right).into(),
+                        Le => left.fp_le(right).into(),
+                        Gt => left.fp_gt(right).into(),
+                        Ge => left.fp_ge(right).into(),
+                        _ => unreachable!(
+                            "Floating-point numbers cannot be used with this operator: {:?}",
+                            operator
+                        ),
+                    }
+                }
                 _ => unreachable!("Binary expressions are not supported for this type: {left:#?}"),
             }
         }
 
+        /// Translates the remainder with the semantics of Rust (and C's `fmod`),
+        /// where the quotient is truncated and the result has the sign of the dividend.
+        fn translate_float_rem(left: &ast::Float, right: &ast::Float) -> ast::Float {
+            let rem = left.ieee_rem(right);
+            // The IEEE remainder is off by the divisor when the quotient is rounded away from zero,
+            // in which case the signs differ. The correction is exact.
+            let is_off = ast::Bool::and(&[
+                &ast::Bool::eq(&rem.is_negative(), &left.is_negative()).not(),
+                &rem.is_zero().not(),
+            ]);
+            let divisor = right.abs();
+            let divisor = left.is_negative().ite(&divisor.neg(), &divisor);
+            is_off.ite(&rem.add_rne(&divisor), &rem)
+        }
+
         fn translate_offset_expr(
             &mut self,
             pointer: AstNode,
@@ -513,6 +771,35 @@ pub(crate) mod z3 {
             }
         }
 
+        /// Reinterprets the bits of the source as the destination type.
+        fn translate_transmutation_expr(&mut self, source: AstNode, dst_ty: ValueType) -> AstNode {
+            let bits = match source {
+                AstNode::BitVector(BVNode(ast, _)) => ast,
+                AstNode::Float(FloatNode(ast, _)) => ast.to_ieee_bv(),
+                // Booleans take a byte.
+                AstNode::Bool(ast) => ast.ite(&ast::BV::from_u64(1, 8), &ast::BV::from_u64(0, 8)),
+                AstNode::Array(..) => {
+                    return source.transmute(BVSort::try_from(dst_ty).unwrap_or_else(|_| {
+                        crate::failure::fail!(
+                            InconsistentState,
+                            "Arrays are expected to be transmuted to bit vectors. Got: {dst_ty:?}"
+                        )
+                    }));
+                }
+            };
+            match dst_ty {
+                ValueType::Bool => {
+                    AstNode::Bool(bits.eq(&ast::BV::from_u64(0, bits.get_size())).not())
+                }
+                ValueType::Float(ty) => {
+                    let sort = float_sort(&ty);
+                    FloatNode(ast::Float::from_ieee_bv(&bits, sort), sort).into()
+                }
+                ValueType::Char => BVNode::new(bits, false).into(),
+                ValueType::Int(ty) => BVNode::new(bits, ty.is_signed).into(),
+            }
+        }
+
         fn translate_truncation_expr(
             &mut self,
             source: AstNode,
@@ -527,6 +814,33 @@ pub(crate) mod z3 {
             }
         }
 
+        fn translate_float_cast_expr(&mut self, source: AstNode, ty: &ValueType) -> AstNode {
+            match (source, ty) {
+                (AstNode::Float(FloatNode(ast, _)), ValueType::Float(ty)) => {
+                    let sort = float_sort(ty);
+                    FloatNode(ast.to_float_rne(sort), sort).into()
+                }
+                (
+                    AstNode::Float(FloatNode(ast, _)),
+                    ValueType::Int(IntType {
+                        bit_size,
+                        is_signed,
+                    }),
+                ) => BVNode::new(
+                    ast.to_int_bv_saturating(*bit_size as u32, *is_signed),
+                    *is_signed,
+                )
+                .into(),
+                (AstNode::BitVector(BVNode(ast, BVSort { is_signed })), ValueType::Float(ty)) => {
+                    let sort = float_sort(ty);
+                    FloatNode(ast::Float::from_int_bv_rne(&ast, is_signed, sort), sort).into()
+                }
+                (source, ty) => {
+                    unreachable!("Invalid floating-point cast of {:?} to {}.", source, ty)
+                }
+            }
+        }
+
         fn translate_ite_expr(
             &mut self,
             condition: AstNode,
@@ -547,25 +861,44 @@ pub(crate) mod z3 {
 
         fn translate_select(&mut self, select: &MultiValue, const_prefix: Option<&str>) -> AstNode {
             let index = self.translate_symbolic(&select.index.index);
+            debug_assert_eq!(index.z3_sort(), z3::Sort::bitvector(USIZE_BIT_SIZE));
+
+            use super::super::sym_place::SelectTarget;
             let index = if select.index.from_end {
-                todo!("#485")
+                // The index is the offset from the end, i.e., `len - offset`.
+                let SelectTarget::Array(possible_values) = &select.target else {
+                    crate::failure::fail!(
+                        UnsupportedFeature,
+                        "#485: Index from end on a nested select is not supported."
+                    )
+                };
+                let len = ast::BV::from_u64(possible_values.len() as u64, USIZE_BIT_SIZE);
+                BVNode::new(len.bvsub(index.as_bit_vector()), false).into()
             } else {
                 index
             };
-            debug_assert_eq!(index.z3_sort(), z3::Sort::bitvector(USIZE_BIT_SIZE));
 
             /* NOTE: Do we need to add constraint that index is within bounds?
              * This code is meant for safe Rust. Thus,
              * Bound constraints are automatically implied by the bound checks compiler adds.
              * Also, we don't need to worry about the empty arrays for the same reason. */
 
-            use super::super::sym_place::SelectTarget;
             match &select.target {
+                /* NOTE: Storing every possible value in an array blows up for large sets,
+                 * while a chain of ITEs is cheaper for the small ones. */
+                SelectTarget::Array(possible_values)
+                    if possible_values.len() <= self.select_ite_max_size =>
+                {
+                    self.translate_ite_chain_of_values(&index, possible_values.iter(), |this, r| {
+                        this.translate_multi_value_tree(r, const_prefix)
+                    })
+                }
                 SelectTarget::Array(possible_values) => {
                     let ArrayNode(
                         ast,
                         ArraySort {
                             range: box elem_sort,
+                            ..
                         },
                     ) = self.translate_array_of_values(
                         const_prefix.unwrap_or(POSSIBLE_VALUES_PREFIX),
@@ -579,7 +912,12 @@ pub(crate) mod z3 {
                 }
                 SelectTarget::Nested(box inner) => {
                     let inner = self.translate_select(inner, const_prefix);
-                    if let AstNode::Array(ArrayNode(array, ArraySort { range: range_sort })) = inner
+                    if let AstNode::Array(ArrayNode(
+                        array,
+                        ArraySort {
+                            range: range_sort, ..
+                        },
+                    )) = inner
                     {
                         AstNode::from_ast(ast::Array::select(&array, &index.dyn_ast()), &range_sort)
                     } else {
@@ -589,6 +927,34 @@ pub(crate) mod z3 {
             }
         }
 
+        /// Encodes `values[index]` as `index == 0 ? v0 : (index == 1 ? v1 : ...)`.
+        fn translate_ite_chain_of_values<'a, V: 'a>(
+            &mut self,
+            index: &AstNode,
+            values: impl DoubleEndedIterator<Item = &'a V>,
+            translate: impl Fn(&mut Self, &V) -> AstNode,
+        ) -> AstNode {
+            let index = index.as_bit_vector();
+            let mut values = values
+                .map(|v| translate(self, v))
+                .enumerate()
+                .collect::<Vec<_>>()
+                .into_iter()
+                .rev();
+            // The index is in bounds, so the last value needs no condition.
+            let (_, last) = values
+                .next()
+                .expect("Indices on zero-sized arrays should be prevented by the bound checks.");
+            let element_sort = last.sort();
+
+            let result = values.fold(last.dyn_ast(), |acc, (i, value)| {
+                index
+                    .eq(&ast::BV::from_u64(i as u64, USIZE_BIT_SIZE))
+                    .ite(&value.dyn_ast(), &acc)
+            });
+            AstNode::from_ast(result, &element_sort)
+        }
+
         fn translate_array_of_values<'a, V: 'a>(
             &mut self,
             const_prefix: &str,
@@ -607,17 +973,20 @@ pub(crate) mod z3 {
                 &first.z3_sort(),
             );
 
+            let mut len = 0;
             for (i, value) in std::iter::once(first).chain(values).enumerate() {
                 array = array.store(
                     &ast::BV::from_u64(i as u64, USIZE_BIT_SIZE),
                     &value.dyn_ast(),
                 );
+                len += 1;
             }
 
             ArrayNode(
                 array,
                 ArraySort {
                     range: Box::new(element_sort),
+                    len,
                 },
             )
         }
@@ -772,6 +1141,13 @@ pub(crate) mod z3 {
         }
     }
 
+    fn float_sort(ty: &FloatType) -> FloatSort {
+        FloatSort {
+            e_bits: ty.e_bits as u32,
+            s_bits: ty.s_bits as u32,
+        }
+    }
+
     trait BVSortTransmute {
         type Result;
         fn transmute(self, to_sort: BVSort) -> Self::Result;
@@ -783,7 +1159,10 @@ pub(crate) mod z3 {
             match &mut self {
                 AstNode::BitVector(BVNode(_, sort)) => *sort = to_sort,
                 AstNode::Array(ArrayNode(_, sort)) => *sort.range = sort.range.transmute(to_sort),
-                AstNode::Bool(..) => panic!("Transmutation of boolean sort is not expected."),
+                AstNode::Bool(..) | AstNode::Float(..) => crate::failure::fail!(
+                    InconsistentState,
+                    "Transmutation of non-bit-vector sorts is not expected."
+                ),
             };
             self
         }
@@ -795,8 +1174,14 @@ pub(crate) mod z3 {
             use AstNodeSort::*;
             match self {
                 BitVector(_) => to_sort.into(),
-                Array(arr) => Array(Box::new(arr.range.transmute(to_sort)).into()),
-                Bool => panic!("Transmutation of boolean sort is not expected."),
+                Array(arr) => Array(ArraySort {
+                    range: Box::new(arr.range.transmute(to_sort)),
+                    len: arr.len,
+                }),
+                Bool | Float(_) => crate::failure::fail!(
+                    InconsistentState,
+                    "Transmutation of non-bit-vector sorts is not expected."
+                ),
             }
         }
     }
@@ -814,11 +1199,311 @@ pub(crate) mod z3 {
                         },
                     )
                 }
-                AstNode::Array(_) => {
-                    unimplemented!("Symbolic arrays are not supported by this converter.")
+                AstNode::Float(FloatNode(ast, FloatSort { e_bits, s_bits })) => {
+                    super::super::ConstValue::Float {
+                        bit_rep: ast.to_ieee_bv().simplify().as_u128().unwrap(),
+                        ty: FloatType {
+                            e_bits: e_bits as u64,
+                            s_bits: s_bits as u64,
+                        },
+                    }
+                }
+                AstNode::Array(ArrayNode(ast, sort)) => {
+                    return super::super::ConcreteValue::Array(super::super::ArrayValue {
+                        elements: model_array_elements(ast, &sort)
+                            .into_iter()
+                            .map(Into::into)
+                            .collect(),
+                    })
+                    .to_value_ref();
                 }
             }
             .to_value_ref()
         }
     }
+
+    /// Enumerates the elements of an array value obtained from a model.
+    /// # Remarks
+    /// The length is taken from the sort, as the domain of the array covers all
+    /// the indices. The elements are read by selecting each index, so that the
+    /// representation of the array in the model does not matter.
+    fn model_array_elements(array: ast::Array, sort: &ArraySort) -> Vec<AstNode> {
+        use z3::ast::Ast;
+
+        (0..sort.len)
+            .map(|i| {
+                array
+                    .select(&ast::BV::from_u64(i, USIZE_BIT_SIZE))
+                    .simplify()
+            })
+            .map(|value| AstNode::from_ast(value, &sort.range))
+            .collect()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use z3::ast::Ast;
+
+        use super::super::super::{SliceIndex, sym_place::SelectTarget};
+        use super::*;
+
+        const U8: IntType = IntType {
+            bit_size: 8,
+            is_signed: false,
+        };
+        const U32: IntType = IntType {
+            bit_size: 32,
+            is_signed: false,
+        };
+        const USIZE: IntType = IntType {
+            bit_size: USIZE_BIT_SIZE as u64,
+            is_signed: false,
+        };
+
+        fn var(id: SymVarId, ty: IntType) -> SymValueRef {
+            SymValue::Variable(SymbolicVar::new(id, ty.into())).to_value_ref()
+        }
+
+        fn int(value: u128, ty: IntType) -> ValueRef {
+            ConstValue::new_int(value, ty).to_value_ref()
+        }
+
+        /// `[0, 3, 6, ...][index]` with `index` being variable 1.
+        fn select_over(len: usize) -> ValueRef {
+            Expr::Multi(MultiValue {
+                index: SliceIndex {
+                    index: var(1, USIZE),
+                    from_end: false,
+                },
+                target: SelectTarget::Array(
+                    (0..len)
+                        .map(|i| MultiValueTree::Single(int(i as u128 * 3, U8)))
+                        .collect(),
+                ),
+            })
+            .to_value_ref()
+            .into()
+        }
+
+        /// `((((x + 1) + 1) + ...) + 1)` with `depth` additions.
+        fn add_chain(x: SymValueRef, depth: usize) -> SymValueRef {
+            (0..depth).fold(x, |acc, _| {
+                Expr::Binary(BinaryExpr {
+                    operator: BinaryOp::Add,
+                    operands: SymBinaryOperands::Orig {
+                        first: acc,
+                        second: int(1, U32),
+                    },
+                })
+                .to_value_ref()
+            })
+        }
+
+        /// Evaluates the translated value with the given values for its variables.
+        fn evaluate(translated: &TranslatedValue, values: &[(SymVarId, u64)]) -> u64 {
+            let substitutions = translated
+                .variables
+                .iter()
+                .map(|(id, node)| {
+                    let (_, value) = values.iter().find(|(v, _)| v == id).unwrap();
+                    let size = node.as_bit_vector().get_size();
+                    (
+                        node.dyn_ast(),
+                        ast::Dynamic::from_ast(&ast::BV::from_u64(*value, size)),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let substitutions = substitutions
+                .iter()
+                .map(|(v, s)| (v, s))
+                .collect::<Vec<_>>();
+            translated
+                .value
+                .dyn_ast()
+                .substitute(&substitutions)
+                .simplify()
+                .as_bv()
+                .and_then(|bv| bv.as_u64())
+                .unwrap()
+        }
+
+        #[test]
+        fn small_select_is_an_ite_chain() {
+            let translated = Z3ValueTranslator::new(4, None).translate(&select_over(4));
+
+            assert!(
+                !translated
+                    .value
+                    .dyn_ast()
+                    .to_string()
+                    .contains(POSSIBLE_VALUES_PREFIX)
+            );
+            assert_eq!(translated.variables.len(), 1);
+            for i in 0..4 {
+                assert_eq!(evaluate(&translated, &[(1, i)]), i * 3);
+            }
+        }
+
+        #[test]
+        fn large_select_is_an_array() {
+            let translated = Z3ValueTranslator::new(4, None).translate(&select_over(5));
+
+            assert!(
+                translated
+                    .value
+                    .dyn_ast()
+                    .to_string()
+                    .contains(POSSIBLE_VALUES_PREFIX)
+            );
+            for i in 0..5 {
+                assert_eq!(evaluate(&translated, &[(1, i)]), i * 3);
+            }
+        }
+
+        #[test]
+        fn single_value_select_needs_no_condition() {
+            let translated = Z3ValueTranslator::new(4, None).translate(&select_over(1));
+
+            assert_eq!(
+                translated
+                    .value
+                    .dyn_ast()
+                    .simplify()
+                    .as_bv()
+                    .unwrap()
+                    .as_u64(),
+                Some(0)
+            );
+        }
+
+        fn seeds(values: &'static [(SymVarId, u128)]) -> SeedProvider {
+            Rc::new(move |id: SymVarId| {
+                values
+                    .iter()
+                    .find(|(v, _)| *v == id)
+                    .map(|(_, value)| ConstValue::new_int(*value, U32))
+            })
+        }
+
+        /// `add_chain(x, 10) + y`
+        fn oversized_sum() -> ValueRef {
+            Expr::Binary(BinaryExpr {
+                operator: BinaryOp::Add,
+                operands: SymBinaryOperands::Orig {
+                    first: add_chain(var(1, U32), 10),
+                    second: var(2, U32).into(),
+                },
+            })
+            .to_value_ref()
+            .into()
+        }
+
+        #[test]
+        fn oversized_subexpressions_are_concretized() {
+            let translated = Z3ValueTranslator::new(16, Some(8))
+                .with_seeds(seeds(&[(1, 5), (2, 7)]))
+                .translate(&oversized_sum());
+
+            // Only the large operand is replaced, the root keeps the other variable.
+            let variables = translated
+                .variables
+                .iter()
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>();
+            assert_eq!(variables, vec![2]);
+            assert_eq!(evaluate(&translated, &[(2, 7)]), 5 + 10 + 7);
+        }
+
+        #[test]
+        fn values_within_budget_are_kept() {
+            let translated = Z3ValueTranslator::new(16, Some(64))
+                .with_seeds(seeds(&[(1, 5), (2, 7)]))
+                .translate(&oversized_sum());
+
+            assert_eq!(translated.variables.len(), 2);
+            assert_eq!(evaluate(&translated, &[(1, 1), (2, 2)]), 1 + 10 + 2);
+        }
+
+        #[test]
+        fn budget_is_ignored_without_seeds() {
+            let translated = Z3ValueTranslator::new(16, Some(8)).translate(&oversized_sum());
+
+            assert_eq!(translated.variables.len(), 2);
+        }
+
+        #[test]
+        fn variables_without_seeds_are_kept() {
+            let translated = Z3ValueTranslator::new(16, Some(8))
+                .with_seeds(seeds(&[(2, 7)]))
+                .translate(&oversized_sum());
+
+            assert_eq!(translated.variables.len(), 2);
+            assert_eq!(evaluate(&translated, &[(1, 5), (2, 7)]), 5 + 10 + 7);
+        }
+
+        fn u8_array(values: &[u64]) -> ast::Array {
+            values.iter().enumerate().fold(
+                ast::Array::const_array(
+                    &z3::Sort::bitvector(USIZE_BIT_SIZE),
+                    &ast::BV::from_u64(0, 8),
+                ),
+                |array, (i, value)| {
+                    array.store(
+                        &ast::BV::from_u64(i as u64, USIZE_BIT_SIZE),
+                        &ast::BV::from_u64(*value, 8),
+                    )
+                },
+            )
+        }
+
+        fn u8_array_sort(len: u64) -> ArraySort {
+            ArraySort {
+                range: Box::new(AstNodeSort::BitVector(BVSort { is_signed: false })),
+                len,
+            }
+        }
+
+        fn elements_of(value: &ValueRef) -> Vec<u128> {
+            let Some(ConcreteValue::Array(ArrayValue { elements })) = value.as_conc() else {
+                panic!("Unexpected model value: {:?}", value);
+            };
+            elements
+                .iter()
+                .map(|e| *e.as_conc().and_then(|c| c.as_int()).unwrap().0)
+                .collect()
+        }
+
+        #[test]
+        fn model_arrays_are_converted_to_their_elements() {
+            let value = ValueRef::from(AstNode::Array(ArrayNode(
+                u8_array(&[4, 7, 9]),
+                u8_array_sort(3),
+            )));
+            assert_eq!(elements_of(&value), vec![4, 7, 9]);
+        }
+
+        #[test]
+        fn model_array_length_is_taken_from_the_sort() {
+            let value = ValueRef::from(AstNode::Array(ArrayNode(
+                u8_array(&[4, 7, 9]),
+                u8_array_sort(2),
+            )));
+            assert_eq!(elements_of(&value), vec![4, 7]);
+
+            let value = ValueRef::from(AstNode::Array(ArrayNode(u8_array(&[4]), u8_array_sort(3))));
+            assert_eq!(elements_of(&value), vec![4, 0, 0]);
+        }
+
+        #[test]
+        fn arrays_of_values_record_their_length() {
+            let ArrayNode(array, sort) = Z3ValueTranslator::new(4, None).translate_array_of_values(
+                POSSIBLE_VALUES_PREFIX,
+                [4u64, 7, 9].iter(),
+                |_, value| AstNode::from_ubv(ast::BV::from_u64(*value, 8)),
+            );
+            assert_eq!(sort.len, 3);
+            let value = ValueRef::from(AstNode::Array(ArrayNode(array, sort)));
+            assert_eq!(elements_of(&value), vec![4, 7, 9]);
+        }
+    }
 }
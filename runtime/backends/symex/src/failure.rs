@@ -0,0 +1,174 @@
+//! Structured failures of the backend.
+//!
+//! The failures are categorized, logged with the location of the execution
+//! they happen at, and counted. A failure terminates the execution unless the
+//! failure site has a concrete fallback and its kind is configured to be
+//! downgraded, in which case the execution continues with the concrete value.
+
+use std::{cell::RefCell, collections::HashMap, fmt};
+
+use derive_more as dm;
+use serde::{Deserialize, Serialize};
+
+use common::{log_error, log_info, log_warn};
+
+use leaf_runtime::utils::RRef;
+
+use super::alias::backend;
+use backend::{
+    SymExExeTraceRecorder,
+    config::{FailureConfig, OutputConfig},
+    diagnostics::{SymbolismLossReason, report_symbolism_loss},
+};
+
+const FILENAME_DEFAULT: &str = "failures";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, dm::Display)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum FailureKind {
+    /// A feature used by the program is not supported by the backend (yet).
+    #[display("unsupported feature")]
+    #[serde(alias = "unsupported")]
+    UnsupportedFeature,
+    /// The backend has reached a state that contradicts its assumptions.
+    #[display("inconsistent state")]
+    InconsistentState,
+    /// The solver or the translation of the constraints for it has failed.
+    #[display("solver error")]
+    #[serde(alias = "solver")]
+    SolverError,
+}
+
+#[derive(Serialize)]
+struct FailureEntry {
+    kind: FailureKind,
+    count: usize,
+    /// The message of the first occurrence.
+    example: String,
+}
+
+#[derive(Default)]
+struct FailureRecorder {
+    downgraded: Vec<FailureKind>,
+    report: Option<OutputConfig>,
+    trace_recorder: Option<RRef<SymExExeTraceRecorder>>,
+    entries: HashMap<FailureKind, (usize, String)>,
+}
+
+impl FailureRecorder {
+    fn record(&mut self, kind: FailureKind, message: &str, is_downgraded: bool) {
+        // The recorder may be already borrowed if the failure happens during call control.
+        let location = self
+            .trace_recorder
+            .as_ref()
+            .and_then(|r| r.try_borrow().ok())
+            .and_then(|r| r.current_location());
+        if is_downgraded {
+            log_warn!("Failure ({kind}) at {location:?}, continuing concretely: {message}");
+        } else {
+            log_error!("Failure ({kind}) at {location:?}: {message}");
+        }
+
+        self.entries
+            .entry(kind)
+            .and_modify(|(count, _)| *count += 1)
+            .or_insert_with(|| (1, message.to_owned()));
+    }
+
+    fn dump(&self) -> std::io::Result<()> {
+        let Some(OutputConfig::File(file_config)) = &self.report else {
+            return Ok(());
+        };
+        let (path, file) =
+            file_config.open_or_create_single_with_path(FILENAME_DEFAULT, None, true)?;
+
+        let mut entries = self
+            .entries
+            .iter()
+            .map(|(kind, (count, example))| FailureEntry {
+                kind: *kind,
+                count: *count,
+                example: example.clone(),
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| b.count.cmp(&a.count));
+
+        serde_json::to_writer_pretty(file, &entries)?;
+        log_info!(
+            "Failure report with {} entries written to: {}",
+            entries.len(),
+            path.display()
+        );
+        Ok(())
+    }
+}
+
+thread_local! {
+    static RECORDER: RefCell<FailureRecorder> = RefCell::new(Default::default());
+}
+
+pub(crate) fn init_failure_handling(
+    config: &FailureConfig,
+    trace_recorder: RRef<SymExExeTraceRecorder>,
+) {
+    RECORDER.with_borrow_mut(|r| {
+        *r = FailureRecorder {
+            downgraded: config.concretize_on.clone(),
+            report: config.report.clone(),
+            trace_recorder: Some(trace_recorder),
+            entries: Default::default(),
+        }
+    });
+}
+
+/// Records the failure and terminates the execution.
+#[track_caller]
+pub(crate) fn raise(kind: FailureKind, message: fmt::Arguments) -> ! {
+    let message = message.to_string();
+    RECORDER.with_borrow_mut(|r| r.record(kind, &message, false));
+    dump_failures();
+    panic!("{kind}: {message}")
+}
+
+/// Records the failure and returns if its kind is downgraded to concretization,
+/// i.e., the caller is expected to continue with the concrete value.
+/// Otherwise, terminates the execution.
+#[track_caller]
+pub(crate) fn raise_or_concretize(kind: FailureKind, message: fmt::Arguments) {
+    let is_downgraded = RECORDER.with_borrow(|r| r.downgraded.contains(&kind));
+    if !is_downgraded {
+        raise(kind, message)
+    }
+
+    let message = message.to_string();
+    RECORDER.with_borrow_mut(|r| r.record(kind, &message, true));
+    report_symbolism_loss(SymbolismLossReason::Unsupported, || message);
+}
+
+pub(crate) fn dump_failures() {
+    RECORDER.with_borrow(|r| {
+        let _ = r
+            .dump()
+            .inspect_err(|e| log_warn!("Could not write failure report: {e}"));
+    });
+}
+
+macro_rules! fail {
+    ($kind:ident, $($arg:tt)+) => {
+        $crate::failure::raise(
+            $crate::failure::FailureKind::$kind,
+            format_args!($($arg)+),
+        )
+    };
+}
+pub(crate) use fail;
+
+macro_rules! fail_or_concretize {
+    ($kind:ident, $($arg:tt)+) => {
+        $crate::failure::raise_or_concretize(
+            $crate::failure::FailureKind::$kind,
+            format_args!($($arg)+),
+        )
+    };
+}
+pub(crate) use fail_or_concretize;
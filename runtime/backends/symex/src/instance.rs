@@ -1,7 +1,13 @@
 #![cfg_attr(feature = "runtime_access_raw_ptr", allow(static_mut_refs))]
 
 /// Singleton instance management for the basic backend.
-/// Multi-threaded programs are not supported, and we have few options to implement a singleton based on the safety and performance requirements.
+/// We have few options to implement a singleton based on the safety and performance requirements.
+/// The backend is accessed through a mutex by default, which supports multi-threaded
+/// programs, where the backend keeps the stack-dependent parts of its state per thread.
+/// The raw pointer access (`runtime_access_raw_ptr`) skips the synchronization,
+/// thus is only sound for single-threaded programs.
+/// The auxiliary recorders (e.g., coverage and failures) are still thread-local,
+/// thus only observe the thread initializing the backend.
 
 #[cfg(not(feature = "runtime_access_raw_ptr"))]
 use std::cell::RefCell;
@@ -12,9 +18,12 @@ use common::type_info::rw::LoadedTypeDatabase;
 
 use cfg_if::cfg_if;
 
-use leaf_runtime::pri::{
-    fluent::{InstanceManager, backend::RuntimeBackend},
-    refs::DefaultRefManager,
+use leaf_runtime::{
+    abs::backend::Shutdown,
+    pri::{
+        fluent::{InstanceManager, backend::RuntimeBackend},
+        refs::DefaultRefManager,
+    },
 };
 
 use super::{SymExBackend, SymExBackendConfig, SymExPlaceBuilder};
@@ -27,12 +36,17 @@ static INIT: Once = Once::new();
 cfg_if! {
     if #[cfg(feature = "runtime_access_raw_ptr")] {
         static mut BACKEND: Option<BackendImpl> = None;
-    } else if #[cfg(feature = "runtime_access_mutex")] {
-        use std::sync::Mutex;
-        static BACKEND: Mutex<Option<BackendImpl>> = Mutex::new(None);
     } else {
-        use common::utils::UnsafeSync;
-        static BACKEND: UnsafeSync<RefCell<Option<BackendImpl>>> = UnsafeSync::new(RefCell::new(None));
+        use std::sync::{Mutex, MutexGuard, PoisonError};
+        use common::utils::UnsafeSend;
+        // The backend is only accessed while holding the lock, so its non-thread-safe
+        // internals (e.g., reference counters) are never touched concurrently.
+        static BACKEND: Mutex<Option<UnsafeSend<BackendImpl>>> = Mutex::new(None);
+
+        fn lock_backend() -> MutexGuard<'static, Option<UnsafeSend<BackendImpl>>> {
+            // A panic in the target does not invalidate the backend's state.
+            BACKEND.lock().unwrap_or_else(PoisonError::into_inner)
+        }
     }
 }
 
@@ -70,8 +84,17 @@ cfg_if! {
 
 const CONFIG_FILENAME: &str = "leaf_config";
 fn load_config() -> ::config::Config {
-    common::config::load_config(CONFIG_FILENAME, "LEAF", |b| Ok(b))
-        .expect("Failed to read configurations")
+    common::config::load_config(CONFIG_FILENAME, "LEAF", |b| {
+        #[cfg(feature = "c_api")]
+        let b = crate::capi::apply_config_overrides(b)?;
+        Ok(b)
+    })
+    .expect("Failed to read configurations")
+}
+
+#[cfg(feature = "c_api")]
+pub(crate) fn is_initialized() -> bool {
+    INIT.is_completed()
 }
 
 pub(crate) struct SymExInstanceManager;
@@ -79,15 +102,34 @@ pub(crate) struct SymExInstanceManager;
 impl SymExInstanceManager {
     #[inline]
     fn check_and_perform_on_backend<T>(
-        backend: &mut Option<BackendImpl>,
+        backend: Option<&mut BackendImpl>,
         action: impl FnOnce(&mut BackendImpl) -> T,
     ) -> T {
         let backend = if cfg!(debug_assertions) {
-            backend.as_mut().expect("Runtime is not initialized.")
+            backend.expect("Runtime is not initialized.")
         } else {
-            unsafe { backend.as_mut().unwrap_unchecked() }
+            unsafe { backend.unwrap_unchecked() }
         };
-        action(backend)
+        crate::diagnostics::measure_func_overhead(backend, action)
+    }
+
+    /// Performs the action on the backend only if it is not in use,
+    /// e.g., when called from the panic hook while the backend is panicking.
+    fn try_perform_on_backend<T>(action: impl FnOnce(&mut BackendImpl) -> T) -> Option<T> {
+        cfg_if! {
+            if #[cfg(feature = "runtime_access_raw_ptr")] {
+                // No way to detect whether the backend is in use.
+                let _ = action;
+                None
+            } else {
+                let mut guard = match BACKEND.try_lock() {
+                    Ok(guard) => guard,
+                    Err(std::sync::TryLockError::Poisoned(err)) => err.into_inner(),
+                    Err(std::sync::TryLockError::WouldBlock) => return None,
+                };
+                guard.as_deref_mut().map(action)
+            }
+        }
     }
 }
 
@@ -122,14 +164,25 @@ impl InstanceManager for SymExInstanceManager {
             cfg_if! {
                 if #[cfg(feature = "runtime_access_raw_ptr")] {
                     unsafe { BACKEND = Some(backend); }
-                } else if #[cfg(feature = "runtime_access_mutex")] {
-                    let mut guard = BACKEND.lock().unwrap();
-                    *guard = Some(backend);
                 } else {
-                    let mut binding = BACKEND.borrow_mut();
-                    *binding = Some(backend);
+                    let mut guard = lock_backend();
+                    *guard = Some(UnsafeSend::new(backend));
                 }
             }
+            crate::assertion::install_panic_hook(
+                || Self::try_perform_on_backend(|b| b.current_location()).flatten(),
+                || {
+                    Self::try_perform_on_backend(|b| {
+                        // Unwinding panics may be caught, after which the execution continues.
+                        if cfg!(panic = "abort") {
+                            b.shutdown()
+                        } else {
+                            b.flush()
+                        }
+                    })
+                    .is_some()
+                },
+            );
             log_info!("SymEx backend initialized");
         });
     }
@@ -140,13 +193,10 @@ impl InstanceManager for SymExInstanceManager {
     fn perform_on_backend<T>(action: impl for<'a> FnOnce(&'a mut Self::Backend) -> T) -> T {
         cfg_if! {
             if #[cfg(feature = "runtime_access_raw_ptr")] {
-                Self::check_and_perform_on_backend(unsafe { &mut BACKEND }, action)
-            } else if #[cfg(feature = "runtime_access_mutex")] {
-                let mut guard = BACKEND.lock().unwrap();
-                Self::check_and_perform_on_backend(&mut guard, action)
+                Self::check_and_perform_on_backend(unsafe { BACKEND.as_mut() }, action)
             } else {
-                let mut binding = BACKEND.borrow_mut();
-                Self::check_and_perform_on_backend(&mut binding, action)
+                let mut guard = lock_backend();
+                Self::check_and_perform_on_backend(guard.as_deref_mut(), action)
             }
         }
     }
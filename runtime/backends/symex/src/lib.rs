@@ -10,13 +10,25 @@
 #![feature(btree_cursors)]
 
 mod alias;
+mod alloc;
 mod annotation;
+mod assertion;
 mod assignment;
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench;
 mod call;
+#[cfg(feature = "c_api")]
+pub mod capi;
 mod concrete;
 mod config;
 mod constraint;
+mod crash;
+mod diagnostics;
+#[cfg(feature = "embedding")]
+pub mod embed;
 mod expr;
+mod failure;
 mod implication;
 mod instance;
 pub mod interface;
@@ -25,6 +37,7 @@ mod operand;
 mod outgen;
 mod place;
 mod state;
+mod sym_sources;
 mod sym_vars;
 mod trace;
 mod type_info;
@@ -32,19 +45,23 @@ mod type_info;
 use std::{cell::RefCell, rc::Rc};
 
 use common::{
-    log_info,
+    log_info, log_warn,
     pri::{AssignmentId, BasicBlockIndex},
     types::InstanceKindId,
 };
 
 use leaf_runtime::{
-    abs::{Constraint, PlaceUsage, SymVariable, Tag, TypeId, TypeSize, backend::*},
+    abs::{
+        BasicBlockLocation, Constraint, DefId, PlaceUsage, SymVariable, Tag, TypeId, TypeSize,
+        backend::*,
+    },
     pri::fluent::backend::*,
-    utils::{HasIndex, RefView, alias::RRef},
+    utils::{HasIndex, PerThread, RefView, alias::RRef},
 };
 
 use self::{
     alias::{TraceManager, TypeDatabase, VariablesState},
+    alloc::{LenBounder, OffsetBounder},
     expr::{SymVarId, prelude::*},
     implication::{Antecedents, Implied, Precondition},
     place::DiscriminantPossiblePlace,
@@ -103,18 +120,48 @@ mod associated_types {
 use associated_types::*;
 
 pub(crate) struct SymExBackend {
-    vars_state: SymExVariablesState,
+    threads: ThreadStates,
     call_flow_manager: SymExCallFlowManager,
-    vars_state_factory: Box<dyn Fn() -> SymExVariablesState>,
+    concretization: RRef<Option<call::Concretization>>,
+    pending_summary: RRef<Option<call::PendingSummary>>,
+    concretizer: Box<dyn concrete::Concretizer>,
+    vars_state_factory: Rc<dyn Fn() -> SymExVariablesState>,
     trace_manager: RRef<SymExTraceManager>,
     trace_recorder: RRef<SymExExeTraceRecorder>,
     expr_builder: RRef<SymExExprBuilder>,
     sym_values: RRef<SymExSymVariablesManager>,
     type_manager: Rc<SymExTypeManager>,
     sym_place_handler: RRef<SymExSymPlaceHandler>,
+    offset_bounder: Option<RRef<OffsetBounder>>,
     #[cfg(feature = "implicit_flow")]
     implication_investigator: Rc<SymExImplicationInvestigator>,
     tags: RRef<Vec<Tag>>,
+    string_matches: RRef<PerThread<constraint::StringMatchChains>>,
+    #[cfg(feature = "embedding")]
+    constraints: RefView<Vec<SymExConstraint>>,
+    /// Whether the backend is already shut down, e.g., by the panic hook.
+    is_shut_down: bool,
+}
+
+/// The part of the state that follows the stack of a thread, thus kept separately for each thread.
+/// The rest of the state (e.g., the trace and the solver) is shared among the threads.
+struct ThreadState {
+    vars_state: SymExVariablesState,
+    dropped_places: call::DroppedPlaces,
+    call_depth: call::CallDepth,
+}
+
+struct ThreadStates {
+    states: PerThread<ThreadState>,
+    factory: Box<dyn Fn() -> ThreadState>,
+}
+
+impl ThreadStates {
+    /// Returns the state of the current thread, creating it on the first access by the thread.
+    #[inline]
+    fn current(&mut self) -> &mut ThreadState {
+        self.states.current_or_insert_with(&*self.factory)
+    }
 }
 
 impl SymExBackend {
@@ -127,24 +174,63 @@ impl SymExBackend {
             type_manager_ref.clone(),
         )));
         let expr_builder = expr_builder_ref.clone();
-        let sym_var_manager = Rc::new(RefCell::new(SymExSymVariablesManager::default()));
-
         let tags_ref = Rc::new(RefCell::new(Vec::new()));
 
+        let sym_var_manager = Rc::new(RefCell::new(SymExSymVariablesManager::new(
+            &config.sym_vars,
+            tags_ref.clone(),
+        )));
+        sym_sources::init_args_outputs(&config.args_outputs);
+        sym_sources::init_env_vars(&config.env_vars);
+        sym_sources::init_files(&config.files);
+
         let type_manager = type_manager_ref.clone();
 
         let trace_recorder_ref = Rc::new(RefCell::new(trace::create_trace_recorder(
             config.exe_trace.control_flow_dump.as_ref(),
+            config.exe_trace.control_flow_stream.as_ref(),
         )));
 
+        if let Some(output) = config.exe_trace.concretizations_dump.as_ref() {
+            trace::init_concretization_recording(output, trace_recorder_ref.clone());
+        }
+        trace::init_coverage_reporting(
+            &config.exe_trace.coverage_reports,
+            trace_recorder_ref.clone(),
+        );
+        if let Some(output) = config.diagnostics.symbolism_loss.as_ref() {
+            diagnostics::init_symbolism_loss_recording(output, trace_recorder_ref.clone());
+        }
+        if config.diagnostics.func_overhead {
+            if config.exe_trace.metrics.is_none() {
+                log_warn!(
+                    "Function overheads are reported in the execution metrics, which are not enabled."
+                );
+            }
+            diagnostics::init_func_overhead_recording();
+        }
+        failure::init_failure_handling(&config.failure, trace_recorder_ref.clone());
+        assertion::init_assertion_reporting(config.failure.report.as_ref());
+        if let Some(directory) = config.failure.crashes.as_ref() {
+            crash::init_crash_triage(
+                directory,
+                config.parent_input.as_ref().map(|i| i.path.as_path()),
+            );
+        }
+
         let trace_manager = trace::create_trace_manager(
             trace_recorder_ref.clone(),
             tags_ref.clone(),
             sym_var_manager.clone(),
             &config.exe_trace,
             &config.outputs,
+            config.parent_input.as_ref(),
+            &config.output_hooks,
+            config.input_schema.as_ref(),
             &config.solver,
         );
+        #[cfg(feature = "embedding")]
+        let constraints = TraceViewProvider::<SymExConstraint>::view(&trace_manager);
         #[cfg(feature = "implicit_flow")]
         let implication_investigator = {
             let constraint_steps = TraceViewProvider::view(&trace_manager);
@@ -171,23 +257,72 @@ impl SymExBackend {
         };
         let sym_read_handler_ref = sym_place_handler_factory(config.sym_place.read);
         let sym_write_handler_ref = sym_place_handler_factory(config.sym_place.write);
+        let sym_ref_handler_ref = config.sym_place.reference.map(sym_place_handler_factory);
         // Writes are more difficult, and the handler is usually more restrictive, so we use the write handler as the general one.
         let sym_place_handler = sym_write_handler_ref.clone();
 
-        let variables_state_factory = Box::new(move || {
+        let concretization = Rc::new(RefCell::new(None));
+        let pending_summary = Rc::new(RefCell::new(None));
+        let concretizer = Box::new(SymExConcretizer::new(
+            expr_builder_ref.clone(),
+            trace_manager_ref.clone(),
+        ));
+
+        let ub_checking = config
+            .exe_trace
+            .inspectors
+            .iter()
+            .any(|t| matches!(t, config::TraceInspectorType::UbChecker { .. }));
+        let offset_bounder = (config.sym_place.offset_bounds || ub_checking).then(|| {
+            Rc::new(RefCell::new(OffsetBounder::new(
+                trace_manager_ref.clone(),
+                tags_ref.clone(),
+                ub_checking,
+            )))
+        });
+
+        let len_bounder = config
+            .sym_place
+            .sym_lengths
+            .then(|| Rc::new(LenBounder::new(trace_manager_ref.clone(), tags_ref.clone())));
+
+        let variables_state_factory: Rc<dyn Fn() -> SymExVariablesState> = Rc::new(move || {
             SymExVariablesState::new(
                 type_manager_ref.clone(),
                 sym_read_handler_ref.clone(),
                 sym_write_handler_ref.clone(),
+                sym_ref_handler_ref.clone(),
+                len_bounder.clone(),
                 Rc::new(RefCell::new(expr::builders::to_sym_expr_builder(
                     expr_builder_ref.clone(),
                 ))),
             )
         });
 
+        let max_call_depth = config.call.max_depth;
+        let thread_state_factory = {
+            let variables_state_factory = variables_state_factory.clone();
+            Box::new(move || ThreadState {
+                vars_state: variables_state_factory(),
+                dropped_places: Default::default(),
+                call_depth: call::CallDepth::new(max_call_depth),
+            })
+        };
+
         Self {
-            call_flow_manager: call::default_flow_manager(config.call),
-            vars_state: variables_state_factory(),
+            threads: ThreadStates {
+                states: PerThread::new(),
+                factory: thread_state_factory,
+            },
+            call_flow_manager: call::default_flow_manager(
+                config.call,
+                tags_ref.clone(),
+                concretization.clone(),
+                pending_summary.clone(),
+            ),
+            concretization,
+            pending_summary,
+            concretizer,
             vars_state_factory: variables_state_factory,
             trace_manager: trace_manager_ref.clone(),
             trace_recorder: trace_recorder_ref.clone(),
@@ -197,9 +332,14 @@ impl SymExBackend {
             sym_values: sym_var_manager.clone(),
             type_manager,
             sym_place_handler,
+            offset_bounder,
             #[cfg(feature = "implicit_flow")]
             implication_investigator,
             tags: tags_ref.clone(),
+            string_matches: Default::default(),
+            #[cfg(feature = "embedding")]
+            constraints,
+            is_shut_down: false,
         }
     }
 }
@@ -296,10 +436,44 @@ impl RuntimeBackend for SymExBackend {
     }
 }
 
+impl SymExBackend {
+    /// # Returns
+    /// The basic block the execution is currently at, if known.
+    pub(crate) fn current_location(&self) -> Option<BasicBlockLocation> {
+        // The recorder may be already borrowed if the panic happens during call control.
+        self.trace_recorder
+            .try_borrow()
+            .ok()
+            .and_then(|r| r.current_location())
+    }
+
+    /// Writes out the records so far without shutting down the backend, as the
+    /// execution may continue afterwards (e.g., when a panic is caught).
+    pub(crate) fn flush(&mut self) {
+        if self.is_shut_down {
+            return;
+        }
+        self.trace_recorder.borrow_mut().flush();
+        trace::dump_coverage();
+        failure::dump_failures();
+    }
+}
+
 impl Shutdown for SymExBackend {
     fn shutdown(&mut self) {
+        if core::mem::replace(&mut self.is_shut_down, true) {
+            return;
+        }
         log_info!("Shutting down the backend");
+        // Reported before the trace manager dumps the metrics for the last time.
+        diagnostics::report_expr_interning(self.expr_builder.borrow().interning_stats());
         self.trace_manager.borrow_mut().shutdown();
+        self.trace_recorder.borrow_mut().flush();
+        trace::dump_coverage();
+        self.sym_values.borrow().dump_table();
+        diagnostics::dump_symbolism_loss();
+        diagnostics::finish_func_overhead_recording();
+        failure::dump_failures();
     }
 }
 
@@ -348,6 +522,10 @@ trait GenericVariablesState {
     fn set_place(&mut self, place: &Self::PlaceValue, value: Self::Value);
 
     fn drop_place(&mut self, place: &Self::PlaceValue);
+
+    /// Returns a value referring to the thread-local static in the current thread.
+    /// The place of the thread-local is registered on its first reference in each thread.
+    fn ref_thread_local(&mut self, def_id: DefId, addr: RawAddress) -> Self::Value;
 }
 
 trait ExeTraceStorage {
@@ -3,16 +3,19 @@ use std::borrow::Cow;
 use common::log_warn;
 
 use leaf_runtime::{
-    abs::{AssignmentId, IntType, PlaceUsage, RawAddress, TypeId, TypeSize},
+    abs::{AssignmentId, PlaceUsage, RawAddress, TypeId, TypeSize},
     pri::fluent::backend::{AssignmentHandler, RawMemoryHandler, RuntimeBackend},
+    utils::{PerThread, RRef},
 };
 
 use super::alias::backend;
 use backend::{
-    LazyTypeInfo, SymExBackend, SymExExprBuilder, SymExPlaceValue, SymExSymPlaceHandler,
-    SymExValue, TypeDatabase,
+    SymExBackend, SymExExprBuilder, SymExPlaceValue, SymExSymPlaceHandler, SymExValue,
+    TypeDatabase,
     alias::SymExValueExprBuilder,
     assignment::{self, AssignmentServices},
+    constraint::StringMatchChains,
+    diagnostics::{SymbolismLossReason, report_symbolism_loss},
     expr::prelude::{
         BinaryExprBuilder, ConcatExpr, ConcreteValue, ConcreteValueRef, ConstValue, SymValueRef,
         UnevalValue, Value, ValueRef,
@@ -26,6 +29,7 @@ type AssignmentHandlerImpl<'a> = <SymExBackend as RuntimeBackend>::AssignmentHan
 pub(crate) struct SymExRawMemoryHandler<'a, EB> {
     services: AssignmentServices<'a, EB>,
     sym_size_handler: &'a mut SymExSymPlaceHandler,
+    string_matches: RRef<PerThread<StringMatchChains>>,
 }
 
 impl SymExRawMemoryHandler<'_, SymExExprBuilder> {
@@ -33,11 +37,13 @@ impl SymExRawMemoryHandler<'_, SymExExprBuilder> {
         backend: &'a mut SymExBackend,
     ) -> SymExRawMemoryHandler<'a, SymExExprBuilder> {
         let sym_size_handler = &mut backend.sym_place_handler;
+        let string_matches = backend.string_matches.clone();
         let services = assignment::services_from_backend!(backend);
 
         SymExRawMemoryHandler {
             services,
             sym_size_handler,
+            string_matches,
         }
     }
 }
@@ -243,6 +249,9 @@ impl<'a, EB: SymExValueExprBuilder + 'static> RawMemoryHandler for SymExRawMemor
                     conc_second_ptr,
                     ptr_type_id,
                 );
+                report_symbolism_loss(SymbolismLossReason::Unsupported, || {
+                    format!("Multi-byte raw equality check of type {ptr_type_id}")
+                });
             }
             return Implied::always(UnevalValue::Some.to_value_ref());
         }
@@ -300,6 +309,9 @@ impl<'a, EB: SymExValueExprBuilder + 'static> RawMemoryHandler for SymExRawMemor
         conc_count: usize,
         ptr_type_id: TypeId,
     ) -> Self::Operand {
+        /* NOTE: A symbolic count (e.g., the length of a str with symbolic lengths enabled)
+         * is handled like any other symbolic size. The bytes are read one place at a time,
+         * and the memory cannot be read as a region of symbolic size. */
         self.check_count(&count, conc_count);
         let count = count.map_value(|_| conc_count);
 
@@ -331,34 +343,48 @@ impl<'a, EB: SymExValueExprBuilder + 'static> RawMemoryHandler for SymExRawMemor
             });
 
         let expr_builder = self.services.expr_builder.clone();
+        let bool_const = |value: bool| Implied::always(ConstValue::Bool(value).to_value_ref());
+        let i32_const = |value: i32| Implied::always(ConstValue::from(value).to_value_ref());
 
-        let result = first_values
+        let (eqs, lts): (Vec<_>, Vec<_>) = first_values
             .zip(second_values)
-            .map(|pair| expr_builder.borrow_mut().cmp(pair))
-            .rev()
-            .reduce(|acc, prev| {
-                let eq = expr_builder.borrow_mut().eq((
-                    prev.clone(),
-                    Implied::always(ConstValue::from(core::cmp::Ordering::Equal).to_value_ref()),
-                ));
-                expr_builder.borrow_mut().if_then_else((eq, acc, prev))
+            .map(|(first, second)| {
+                let mut expr_builder = expr_builder.borrow_mut();
+                (
+                    expr_builder.eq((first.clone(), second.clone())),
+                    expr_builder.lt((first, second)),
+                )
             })
+            .unzip();
+
+        /* NOTE: The result is expressed as `all_eq ? 0 : (is_less ? -1 : 1)`,
+         * so that the dominant use case, i.e., `compare_bytes(..) == 0` for slice (and str)
+         * equality, gets simplified to a conjunction of byte-wise equalities. */
+        let is_less = eqs
+            .iter()
+            .zip(lts)
+            .rev()
+            .fold(bool_const(false), |acc, (eq, lt)| {
+                expr_builder
+                    .borrow_mut()
+                    .if_then_else((eq.clone(), acc, lt))
+            });
+        let all_eq = eqs
+            .into_iter()
+            .reduce(|acc, next| expr_builder.borrow_mut().and((acc, next)))
             .unwrap();
 
-        let i8_type: LazyTypeInfo = self.services.type_manager.i8();
-        let x = self.services.expr_builder.borrow_mut().transmute(
-            result,
-            i8_type.id().unwrap(),
-            i8_type,
-        );
-        self.services.expr_builder.borrow_mut().to_int(
-            x,
-            IntType {
-                bit_size: i32::BITS as _,
-                is_signed: true,
-            },
-            self.services.type_manager.i32(),
-        )
+        let mut expr_builder = expr_builder.borrow_mut();
+        let ordering = expr_builder.if_then_else((is_less, i32_const(-1), i32_const(1)));
+        let result = expr_builder.if_then_else((all_eq, i32_const(0), ordering));
+        if result.is_symbolic() {
+            // The first operand is taken as the scrutinee, e.g., `s == "foo"` in string matches.
+            self.string_matches
+                .borrow_mut()
+                .current_mut()
+                .notify_comparison(conc_first_ptr);
+        }
+        result
     }
 }
 
@@ -382,7 +408,13 @@ impl<'a, EB> SymExRawMemoryHandler<'a, EB> {
     fn pointee_size(&self, ptr_type_id: TypeId) -> TypeSize {
         self.type_manager()
             .get_pointee_size(&ptr_type_id)
-            .unwrap_or_else(|| panic!("Pointer to unsized type is not expected: {}", ptr_type_id))
+            .unwrap_or_else(|| {
+                crate::failure::fail!(
+                    InconsistentState,
+                    "Pointer to unsized type is not expected: {}",
+                    ptr_type_id
+                )
+            })
     }
 
     fn check_count(&mut self, count: &SymExValue, conc_count: usize) {
@@ -396,6 +428,9 @@ impl<'a, EB> SymExRawMemoryHandler<'a, EB> {
                     "Symbolic count {} is not supported and will be ignored",
                     count
                 );
+                report_symbolism_loss(SymbolismLossReason::Unsupported, || {
+                    format!("Symbolic count in raw memory operation: {count}")
+                });
             }
         }
     }
@@ -433,7 +468,11 @@ impl<EB> SymExRawMemoryHandler<'_, EB> {
 
                         let ptr = match retrieved.as_ref() {
                             ConcreteValue::Const(ConstValue::Addr(addr)) => *addr,
-                            _ => panic!("Expected a concrete pointer, got: {}", retrieved),
+                            _ => crate::failure::fail!(
+                                InconsistentState,
+                                "Expected a concrete pointer, got: {}",
+                                retrieved
+                            ),
                         };
 
                         assert_eq!(ptr, conc_ptr, "Concrete value does not match");
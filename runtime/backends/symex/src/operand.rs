@@ -1,7 +1,5 @@
-use common::log_error;
-
 use leaf_runtime::{
-    abs::{Constant, SymVariable},
+    abs::{Constant, SymVariable, Tag},
     pri::fluent::backend::OperandHandler,
     utils::RRef,
 };
@@ -9,7 +7,7 @@ use leaf_runtime::{
 use super::alias::backend;
 use backend::{
     Implied, PlaceValueRef, SymExBackend, SymExSymVariablesManager, SymVariablesManager,
-    VariablesState, expr::prelude::ConcreteValue,
+    VariablesState, expr::prelude::ConcreteValue, failure::fail, sym_sources,
 };
 
 use super::SymExValue;
@@ -17,13 +15,15 @@ use super::SymExValue;
 pub(crate) struct SymExOperandHandler<'a> {
     vars_state: &'a mut dyn VariablesState,
     sym_values: RRef<SymExSymVariablesManager>,
+    tags: RRef<Vec<Tag>>,
 }
 
 impl<'a> SymExOperandHandler<'a> {
     pub fn new(backend: &'a mut SymExBackend) -> Self {
         Self {
-            vars_state: &mut backend.vars_state,
+            vars_state: &mut backend.threads.current().vars_state,
             sym_values: backend.sym_values.clone(),
+            tags: backend.tags.clone(),
         }
     }
 }
@@ -45,11 +45,18 @@ impl OperandHandler for SymExOperandHandler<'_> {
     }
 
     fn some(self) -> Self::Operand {
-        log_error!("Operand info is not available.");
-        panic!("Operand details are expected in this backend.")
+        fail!(
+            InconsistentState,
+            "Operand details are expected in this backend."
+        )
     }
 
     fn new_symbolic(self, var: SymVariable<Self::Operand>) -> Self::Operand {
+        if !sym_sources::is_selected(&self.tags.borrow()) {
+            return var
+                .conc_value
+                .expect("Concrete value of symbolic variables is required.");
+        }
         let value = self.sym_values.borrow_mut().add_variable(var).into();
         Implied::by_unknown(value)
     }
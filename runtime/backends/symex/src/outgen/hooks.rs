@@ -0,0 +1,102 @@
+use common::{answers::OutputPostProcessor, log_warn};
+
+use super::backend;
+use backend::config::{OutputHookConfig, OutputRangeConfig};
+
+pub(super) fn create_post_processor(config: &OutputHookConfig) -> OutputPostProcessor {
+    match config.clone() {
+        OutputHookConfig::LengthField {
+            offset,
+            size,
+            range,
+            adjustment,
+            big_endian,
+        } => Box::new(move |output: &mut Vec<u8>| {
+            let len = range_of(&range, output).len() as i64 + adjustment;
+            let Ok(len) = u128::try_from(len) else {
+                log_warn!(
+                    "The adjusted length is negative: {len}. Keeping the length field intact."
+                );
+                return;
+            };
+            write_uint(output, offset, size, len, big_endian);
+        }),
+        OutputHookConfig::Crc32 {
+            offset,
+            range,
+            big_endian,
+        } => Box::new(move |output: &mut Vec<u8>| {
+            let checksum = crc32(&output[range_of(&range, output)]);
+            write_uint(output, offset, 4, checksum as u128, big_endian);
+        }),
+        OutputHookConfig::Command { program, args } => Box::new(move |output: &mut Vec<u8>| {
+            use std::{
+                io::Write,
+                process::{Command, Stdio},
+            };
+
+            let result = Command::new(&program)
+                .args(&args)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .spawn()
+                .and_then(|mut child| {
+                    let mut stdin = child.stdin.take().unwrap();
+                    let input: &[u8] = output;
+                    // Written concurrently, as the child may block on a full stdout pipe
+                    // before reading the whole input. Stdin is closed once written.
+                    std::thread::scope(|scope| {
+                        let writer = scope.spawn(move || stdin.write_all(input));
+                        let result = child.wait_with_output();
+                        writer.join().unwrap()?;
+                        result
+                    })
+                });
+            match result {
+                Ok(result) if result.status.success() => *output = result.stdout,
+                Ok(result) => log_warn!(
+                    "Output hook `{}` failed with {}. Keeping the output intact.",
+                    program.display(),
+                    result.status,
+                ),
+                Err(err) => log_warn!(
+                    "Could not run output hook `{}`: {err}. Keeping the output intact.",
+                    program.display(),
+                ),
+            }
+        }),
+    }
+}
+
+fn range_of(range: &OutputRangeConfig, output: &[u8]) -> core::ops::Range<usize> {
+    let to = range.to.unwrap_or(output.len()).min(output.len());
+    range.from.min(to)..to
+}
+
+/// Writes the lowest `size` bytes of the value at the offset, growing the output if needed.
+pub(super) fn write_uint(
+    output: &mut Vec<u8>,
+    offset: usize,
+    size: usize,
+    value: u128,
+    big_endian: bool,
+) {
+    let size = size.min(size_of::<u128>());
+    if output.len() < offset + size {
+        output.resize(offset + size, 0);
+    }
+    let field = &mut output[offset..offset + size];
+    field.copy_from_slice(&value.to_le_bytes()[..size]);
+    if big_endian {
+        field.reverse();
+    }
+}
+
+/// CRC-32 (IEEE 802.3), as used by zlib, PNG, etc.
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, byte| {
+        (0..8).fold(crc ^ *byte as u32, |crc, _| {
+            (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
@@ -0,0 +1,390 @@
+use std::{borrow::Cow, collections::HashMap, debug_assert_matches, path::PathBuf};
+
+use common::{
+    answers::{
+        AnswersWriter, BinaryFileAnswerError, BinaryFileMultiAnswersWriter, InputLayout,
+        SwitchableAnswersWriter,
+    },
+    log_info, log_warn,
+};
+
+use leaf_runtime::{
+    abs::IntType,
+    utils::file::{FileFormat, FileGenConfig},
+};
+
+mod hooks;
+mod schema;
+
+use super::{
+    alias::backend,
+    config::{InputSchemaConfig, OutputConfig, OutputHookConfig, ParentInputConfig},
+    expr::{SymVarId, prelude::*},
+    sym_sources, sym_vars,
+};
+
+pub(super) struct DefaultOutputGenerator {
+    writers: Vec<Box<dyn SpecializedAnswersWriter>>,
+    requires_all_answers: bool,
+    /// The offsets of the variables in the input, if their ids are not their positions.
+    input_offsets: Option<HashMap<SymVarId, usize>>,
+}
+
+impl DefaultOutputGenerator {
+    pub(super) fn new(
+        configs: &[OutputConfig],
+        parent_input: Option<&ParentInputConfig>,
+        hooks: &[OutputHookConfig],
+        schema: Option<&InputSchemaConfig>,
+    ) -> Self {
+        let mut writers = vec![Box::new(LoggingAnswersWriter) as Box<dyn SpecializedAnswersWriter>];
+        let input_offsets = sym_vars::input_offsets();
+
+        let parent_input = match (parent_input, schema) {
+            (Some(_), Some(_)) => {
+                log_warn!(
+                    "Parent input patching is not applicable to schema-based encoding. Ignoring the parent input."
+                );
+                None
+            }
+            (parent_input, _) => parent_input.and_then(|config| {
+                let mut parent_input = ParentInput::load(config)
+                    .inspect_err(|err| {
+                        log_warn!(
+                            "Could not load the parent input: {err}. Generated inputs will be written in full."
+                        )
+                    })
+                    .ok()?;
+                if input_offsets.is_some() && !parent_input.layout.is_empty() {
+                    log_warn!(
+                        "The offsets of the symbolic variables are taken from their input portions. Ignoring the layout of the parent input."
+                    );
+                    parent_input.layout.clear();
+                }
+                Some(parent_input)
+            }),
+        };
+        let requires_all_answers = parent_input.is_none();
+
+        writers.extend(configs.iter().map(|c| match c {
+            OutputConfig::File(file_config) => match file_config.format() {
+                FileFormat::Binary => Box::new(BinaryFileAnswersWriter::new(
+                    file_config,
+                    parent_input.as_ref(),
+                    hooks,
+                    schema,
+                )),
+                format @ (FileFormat::Text | FileFormat::Json | FileFormat::JsonLines) => {
+                    unimplemented!("Format is not supported: {:?}", format);
+                }
+            },
+        } as Box<dyn SpecializedAnswersWriter>));
+
+        Self {
+            writers,
+            requires_all_answers,
+            input_offsets,
+        }
+    }
+
+    /// Whether the answers for all symbolic variables are required to generate the outputs,
+    /// or the ones not in the model can be left out.
+    #[inline]
+    pub(super) fn requires_all_answers(&self) -> bool {
+        self.requires_all_answers
+    }
+
+    pub(super) fn generate(&mut self, answers: &HashMap<u32, ValueRef>) {
+        let source_ids = sym_sources::variable_ids();
+        if !source_ids.is_empty() {
+            sym_sources::write_outputs(
+                &answers
+                    .iter()
+                    .map(|(id, v)| (*id, TryInto::<u8>::try_into(AsRef::<Value>::as_ref(v)).ok()))
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        let answers = match self.input_offsets.as_ref() {
+            Some(offsets) => Cow::Owned(place_at_offsets(answers, &source_ids, offsets)),
+            None if source_ids.is_empty() => Cow::Borrowed(answers),
+            None => Cow::Owned(exclude_ids(answers, &source_ids)),
+        };
+        self.write(&answers);
+    }
+
+    fn write(&mut self, answers: &HashMap<u32, ValueRef>) {
+        for writer in self.writers.iter_mut() {
+            writer.write(answers);
+        }
+    }
+
+    /// Flags the outputs of this execution as coming from an infeasible path,
+    /// i.e., the path condition has become unsatisfiable.
+    pub(super) fn mark_infeasible(&mut self, info: &InfeasibilityInfo) {
+        for writer in self.writers.iter_mut() {
+            writer.mark_infeasible(info);
+        }
+    }
+}
+
+/// Removes the answers of the variables created from the other input sources
+/// (e.g., the command-line arguments), and shifts the ids of the rest to keep
+/// them contiguous for the other outputs.
+fn exclude_ids(answers: &HashMap<u32, ValueRef>, ids: &[SymVarId]) -> HashMap<u32, ValueRef> {
+    answers
+        .iter()
+        .filter_map(|(id, v)| match ids.binary_search(id) {
+            Ok(_) => None,
+            Err(preceding) => Some((id - preceding as SymVarId, v.clone())),
+        })
+        .collect()
+}
+
+/// Keys the answers by the (one-based) positions of the input portions that
+/// the variables are created from, leaving out the variables of the other
+/// input sources.
+fn place_at_offsets(
+    answers: &HashMap<u32, ValueRef>,
+    excluded_ids: &[SymVarId],
+    offsets: &HashMap<SymVarId, usize>,
+) -> HashMap<u32, ValueRef> {
+    answers
+        .iter()
+        .filter(|(id, _)| excluded_ids.binary_search(id).is_err())
+        .filter_map(|(id, v)| match offsets.get(id) {
+            Some(offset) => Some((*offset as u32 + 1, v.clone())),
+            None => {
+                log_warn!("No input offset is known for symbolic variable {id}. Leaving it out.");
+                None
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, serde::Serialize)]
+pub(super) struct InfeasibilityInfo {
+    /// The number of steps observed when the infeasibility was detected.
+    pub steps: usize,
+    /// The number of constraints in the path condition when the infeasibility was detected.
+    pub constraints: usize,
+}
+
+/// The marker file to which the infeasible executions are appended,
+/// as the output directory is shared between the executions.
+const FILENAME_INFEASIBLE: &str = "infeasible";
+
+#[derive(serde::Serialize)]
+struct InfeasibilityRecord<'a> {
+    /// The process of the execution, which tells the executions apart.
+    pid: u32,
+    #[serde(flatten)]
+    info: &'a InfeasibilityInfo,
+}
+
+trait SpecializedAnswersWriter {
+    fn write(&mut self, answers: &HashMap<u32, ValueRef>);
+
+    fn mark_infeasible(&mut self, _info: &InfeasibilityInfo) {}
+}
+
+struct LoggingAnswersWriter;
+
+impl SpecializedAnswersWriter for LoggingAnswersWriter {
+    fn write(&mut self, answers: &HashMap<u32, ValueRef>) {
+        leaf_runtime::outgen::log_json(answers.iter());
+    }
+
+    fn mark_infeasible(&mut self, info: &InfeasibilityInfo) {
+        log_warn!("Execution is marked as infeasible-divergent: {:?}", info);
+    }
+}
+
+/// A wrapper to convert [Value]s obtained from the solver to bytes.
+/// The ids of the answers are taken as the (one-based) positions of the bytes.
+struct BinaryFileAnswersWriter {
+    inner: SwitchableAnswersWriter<BinaryFileMultiAnswersWriter>,
+    encoder: Option<schema::InputEncoder>,
+    dir_path: PathBuf,
+    prefix: Option<String>,
+}
+
+impl BinaryFileAnswersWriter {
+    fn new(
+        config: &FileGenConfig,
+        parent_input: Option<&ParentInput>,
+        hooks: &[OutputHookConfig],
+        schema: Option<&InputSchemaConfig>,
+    ) -> Self {
+        debug_assert_matches!(config.format(), FileFormat::Binary);
+
+        let dir_path = config.ensure_dir().unwrap();
+
+        let mut writer = BinaryFileMultiAnswersWriter::new(
+            dir_path.clone(),
+            config.prefix().map(String::from),
+            config.format().default_extension().to_owned(),
+            parent_input.map(|p| p.content.as_slice()),
+        );
+        if let Some(parent_input) = parent_input {
+            writer.set_patching(parent_input.layout.clone());
+        }
+        hooks
+            .iter()
+            .for_each(|h| writer.add_post_processor(hooks::create_post_processor(h)));
+
+        Self {
+            inner: SwitchableAnswersWriter::new(writer),
+            encoder: schema.map(schema::InputEncoder::new),
+            dir_path,
+            prefix: config.prefix().map(String::from),
+        }
+    }
+
+    fn write_encoded(&mut self, encoder_result: Result<Vec<u8>, schema::EncodingError>) {
+        let encoded = match encoder_result {
+            Ok(encoded) => encoded,
+            Err(err) => {
+                log_warn!("Could not encode the answers according to the schema: {err}");
+                return;
+            }
+        };
+
+        if let Ok(Err(err)) = self
+            .inner
+            .write(encoded.into_iter().enumerate().map(|(i, b)| (i, Some(b))))
+        {
+            panic!("Could not write output: {err}")
+        }
+    }
+}
+
+struct ParentInput {
+    content: Vec<u8>,
+    layout: InputLayout,
+}
+
+impl ParentInput {
+    fn load(config: &ParentInputConfig) -> Result<Self, String> {
+        let content = std::fs::read(&config.path)
+            .map_err(|err| format!("Could not read {}: {err}", config.path.display()))?;
+        log_info!(
+            "Generated inputs will be patched over the parent input: {} ({} bytes)",
+            config.path.display(),
+            content.len(),
+        );
+
+        let layout = match config.layout.as_ref() {
+            Some(path) => {
+                let file = std::fs::File::open(path).map_err(|err| {
+                    format!(
+                        "Could not open the input layout at {}: {err}",
+                        path.display()
+                    )
+                })?;
+                serde_json::from_reader::<_, HashMap<SymVarId, usize>>(file)
+                    .map_err(|err| format!("Invalid input layout format: {err}"))?
+            }
+            None => Default::default(),
+        };
+        let layout = layout
+            .into_iter()
+            .map(|(id, offset)| {
+                // Ids in the answers writer are zero-based.
+                id.checked_sub(1)
+                    .map(|index| (index as usize, offset))
+                    .ok_or_else(|| format!("Invalid symbolic variable id in the layout: {id}"))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { content, layout })
+    }
+}
+
+impl SpecializedAnswersWriter for BinaryFileAnswersWriter {
+    fn write(&mut self, answers: &HashMap<u32, ValueRef>) {
+        if let Some(encoder) = self.encoder.as_ref() {
+            let result = encoder.encode(answers);
+            return self.write_encoded(result);
+        }
+
+        let Ok(result) = self.inner.write(answers.iter().map(|(id, v)| {
+            (
+                (id - 1) as usize,
+                TryInto::<u8>::try_into(AsRef::<Value>::as_ref(&v)).ok(),
+            )
+        })) else {
+            return;
+        };
+
+        if let Err(err) = result {
+            match err {
+                BinaryFileAnswerError::Incomplete => {
+                    log_warn!("Unexpected answers format. Not all symbolic values are present.");
+                }
+                BinaryFileAnswerError::NonByte(index) => {
+                    let id = index as u32 + 1;
+                    log_warn!("Value is not a byte: {} -> {}", id, answers[&id]);
+                    log_warn!(
+                        "Not all values are bytes. Disabling binary file answers writing for this execution."
+                    );
+                    self.inner.switch(false);
+                }
+                BinaryFileAnswerError::Io(error) => {
+                    panic!("Could not write output: {error}")
+                }
+            }
+        }
+    }
+
+    /// Appends a record for this execution to a marker next to the outputs,
+    /// so that the consumers can tell the outputs of this execution apart.
+    fn mark_infeasible(&mut self, info: &InfeasibilityInfo) {
+        use std::io::Write;
+
+        let path = self.dir_path.join(format!(
+            "{}{}.jsonl",
+            self.prefix.as_deref().unwrap_or_default(),
+            FILENAME_INFEASIBLE,
+        ));
+        let record = InfeasibilityRecord {
+            pid: std::process::id(),
+            info,
+        };
+        let result = serde_json::to_string(&record)
+            .map_err(|e| e.to_string())
+            .and_then(|line| {
+                // A single write per record, so that concurrent appends do not interleave.
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .and_then(|mut file| file.write_all(format!("{line}\n").as_bytes()))
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(err) = result {
+            log_warn!(
+                "Could not write the infeasibility marker to `{}`: {err}",
+                path.display()
+            );
+        }
+    }
+}
+
+impl TryFrom<&Value> for u8 {
+    type Error = ();
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Concrete(ConcreteValue::Const(ConstValue::Int {
+                bit_rep,
+                ty:
+                    IntType {
+                        bit_size: 8,
+                        is_signed: false,
+                    },
+            })) => Ok((bit_rep.0 & 0xFF) as u8),
+            _ => Err(()),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use derive_more as dm;
+
+use super::{backend, hooks::write_uint};
+use backend::{
+    ConcreteValue, ConstValue, SymVarId, Value, ValueRef,
+    config::{InputFieldConfig, InputSchemaConfig, ValueEncodingConfig},
+};
+
+#[derive(Debug, dm::Display)]
+pub(super) enum EncodingError {
+    #[display("No answer is available for symbolic variable {_0}")]
+    Missing(SymVarId),
+    #[display("Answer for symbolic variable {_0} is not encodable: {_1}")]
+    Unsupported(SymVarId, ValueRef),
+}
+
+/// Encodes the answers to the input format described by the schema.
+pub(super) struct InputEncoder {
+    fields: Vec<InputFieldConfig>,
+}
+
+impl InputEncoder {
+    pub(super) fn new(config: &InputSchemaConfig) -> Self {
+        Self {
+            fields: config.fields.clone(),
+        }
+    }
+
+    pub(super) fn encode(
+        &self,
+        answers: &HashMap<SymVarId, ValueRef>,
+    ) -> Result<Vec<u8>, EncodingError> {
+        let mut output = Vec::new();
+        let mut next_id: SymVarId = 1;
+        let mut take_ids = |count: usize| {
+            let ids = next_id..next_id + count as SymVarId;
+            next_id = ids.end;
+            ids
+        };
+        let encode = |output: &mut Vec<u8>, id: SymVarId, encoding: &ValueEncodingConfig| {
+            let value = answers.get(&id).ok_or(EncodingError::Missing(id))?;
+            encode_value(output, value, encoding)
+                .ok_or_else(|| EncodingError::Unsupported(id, value.clone()))
+        };
+
+        for field in self.fields.iter() {
+            match field {
+                InputFieldConfig::Value { encoding } => {
+                    encode(&mut output, take_ids(1).start, encoding)?
+                }
+                InputFieldConfig::Sequence { count, encoding } => {
+                    for id in take_ids(*count) {
+                        encode(&mut output, id, encoding)?;
+                    }
+                }
+                InputFieldConfig::LengthPrefixed {
+                    count,
+                    prefix_size,
+                    prefix_big_endian,
+                    encoding,
+                } => {
+                    // Reserved until the number of elements is known.
+                    let prefix_offset = output.len();
+                    write_uint(&mut output, prefix_offset, *prefix_size, 0, false);
+                    // The elements not read by the program have no answers.
+                    let mut len = 0;
+                    for id in take_ids(*count).take_while(|id| answers.contains_key(id)) {
+                        encode(&mut output, id, encoding)?;
+                        len += 1;
+                    }
+                    write_uint(
+                        &mut output,
+                        prefix_offset,
+                        *prefix_size,
+                        len,
+                        *prefix_big_endian,
+                    );
+                }
+                InputFieldConfig::Literal { bytes } => output.extend_from_slice(bytes),
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+fn encode_value(
+    output: &mut Vec<u8>,
+    value: &ValueRef,
+    encoding: &ValueEncodingConfig,
+) -> Option<()> {
+    let Value::Concrete(ConcreteValue::Const(value)) = value.as_ref() else {
+        return None;
+    };
+
+    let (bit_rep, natural_size) = match value {
+        ConstValue::Bool(value) => (*value as u128, 1),
+        ConstValue::Char(value) if encoding.size.is_none() => {
+            let mut buf = [0; 4];
+            output.extend_from_slice(value.encode_utf8(&mut buf).as_bytes());
+            return Some(());
+        }
+        ConstValue::Char(value) => (*value as u128, size_of::<char>()),
+        ConstValue::Int { bit_rep, ty } => (bit_rep.0, ty.bit_size as usize / 8),
+        ConstValue::Float { bit_rep, ty } => (*bit_rep, (ty.e_bits + ty.s_bits) as usize / 8),
+        ConstValue::Addr(..) => return None,
+    };
+
+    write_uint(
+        output,
+        output.len(),
+        encoding.size.unwrap_or(natural_size),
+        bit_rep,
+        encoding.big_endian,
+    );
+    Some(())
+}
@@ -38,23 +38,47 @@ mod handlers {
     use leaf_runtime::{
         abs::{PlaceUsage, place::HasMetadata},
         pri::fluent::backend::PlaceHandler,
+        utils::alias::RRef,
     };
 
     use super::*;
-    use backend::{SymExBackend, SymExPlaceInfo, TypeDatabase, VariablesState};
+    use backend::{
+        OffsetBounder, SymExBackend, SymExPlaceInfo, TypeDatabase, VariablesState,
+        expr::place::PlaceValue,
+    };
 
     pub(crate) struct SymExPlaceHandler<'a> {
         vars_state: &'a mut dyn VariablesState,
         usage: PlaceUsage,
         type_manager: &'a dyn TypeDatabase,
+        offset_bounder: Option<&'a RRef<OffsetBounder>>,
     }
 
     impl<'a> SymExPlaceHandler<'a> {
         pub fn new(usage: PlaceUsage, backend: &'a mut SymExBackend) -> SymExPlaceHandler<'a> {
             Self {
-                vars_state: &mut backend.vars_state,
+                vars_state: &mut backend.threads.current().vars_state,
                 usage,
                 type_manager: backend.type_manager.as_ref(),
+                offset_bounder: backend.offset_bounder.as_ref(),
+            }
+        }
+
+        /// Registers the referenced local as an allocation, as it is the whole
+        /// object that the pointers obtained from the reference may move within.
+        fn register_allocation(&self, info: &SymExPlaceInfo, place: &PlaceValueRef) {
+            let (Some(bounder), PlaceUsage::Ref, false) =
+                (self.offset_bounder, self.usage, info.has_projection())
+            else {
+                return;
+            };
+            if let PlaceValue::Deterministic(deter_place) = place.as_ref() {
+                if let Some(size) = deter_place.type_info().get_size(self.type_manager) {
+                    bounder
+                        .borrow_mut()
+                        .allocations
+                        .add(deter_place.address(), size);
+                }
             }
         }
     }
@@ -90,7 +114,9 @@ mod handlers {
                     }
                 }
             }
-            self.vars_state.ref_place(&info, self.usage)
+            let place = self.vars_state.ref_place(&info, self.usage);
+            self.register_allocation(&info, &place);
+            place
         }
 
         fn tag_of<'a>(self, info: Self::PlaceInfo<'a>) -> Self::DiscriminablePlace {
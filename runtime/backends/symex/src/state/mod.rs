@@ -1,7 +1,11 @@
 mod pointer_based;
 mod sym_place;
 
-use leaf_runtime::pri::fluent::backend::LifetimeHandler;
+use leaf_runtime::{
+    abs::{RawAddress, TypeId, TypeSize},
+    pri::fluent::backend::LifetimeHandler,
+    utils::alias::RRef,
+};
 
 pub(super) use pointer_based::RawPointerVariableState;
 pub(super) use sym_place::{
@@ -9,16 +13,23 @@ pub(super) use sym_place::{
 };
 
 use super::alias::backend;
-use backend::{SymExBackend, SymExPlaceValue, VariablesState};
+use backend::{
+    OffsetBounder, SymExBackend, SymExPlaceValue, TypeDatabase, VariablesState,
+    expr::place::PlaceValue,
+};
 
 pub(crate) struct SymExLifetimeHandler<'s> {
     vars_state: &'s mut dyn VariablesState,
+    offset_bounder: Option<&'s RRef<OffsetBounder>>,
+    type_manager: &'s dyn TypeDatabase,
 }
 
 impl<'s> SymExLifetimeHandler<'s> {
     pub(super) fn new(backend: &'s mut SymExBackend) -> Self {
         Self {
-            vars_state: &mut backend.vars_state,
+            vars_state: &mut backend.threads.current().vars_state,
+            offset_bounder: backend.offset_bounder.as_ref(),
+            type_manager: backend.type_manager.as_ref(),
         }
     }
 }
@@ -31,6 +42,37 @@ impl<'s> LifetimeHandler for SymExLifetimeHandler<'s> {
     }
 
     fn mark_dead(self, place: Self::Place) {
+        if let (Some(bounder), PlaceValue::Deterministic(deter_place)) =
+            (self.offset_bounder, place.as_ref())
+        {
+            if let Some(size) = deter_place.type_info().get_size(self.type_manager) {
+                bounder
+                    .borrow_mut()
+                    .allocations
+                    .remove(deter_place.address(), size);
+            }
+        }
         self.vars_state.drop_place(&place);
     }
+
+    fn mark_allocated(self, addr: RawAddress, size: TypeSize) {
+        if let Some(bounder) = self.offset_bounder {
+            bounder.borrow_mut().allocations.add(addr, size);
+        }
+    }
+
+    fn mark_deallocated(self, addr: RawAddress) {
+        if let Some(bounder) = self.offset_bounder {
+            bounder.borrow_mut().allocations.remove_at(addr);
+        }
+    }
+
+    fn mark_global(self, addr: RawAddress, ptr_type_id: TypeId) {
+        let Some(bounder) = self.offset_bounder else {
+            return;
+        };
+        if let Some(size) = self.type_manager.get_pointee_size(&ptr_type_id) {
+            bounder.borrow_mut().allocations.add(addr, size);
+        }
+    }
 }
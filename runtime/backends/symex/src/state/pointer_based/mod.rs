@@ -1,11 +1,13 @@
-use std::{cell::RefCell, num::NonZero, ops::DerefMut, rc::Rc};
+use std::{
+    cell::RefCell, collections::HashMap, num::NonZero, ops::DerefMut, rc::Rc, thread::ThreadId,
+};
 
 use derive_more as dm;
 
 use common::{log_warn, type_info::TypeInfo};
 
 use leaf_runtime::{
-    abs::{PlaceUsage, PointerOffset, TypeId, TypeSize},
+    abs::{DefId, PlaceUsage, PointerOffset, TypeId, TypeSize},
     type_info::{TypeInfoExt, TypeLayoutResolver, TypeLayoutResolverExt},
     utils::{InPlaceSelfHierarchical, RRef, byte_offset_from},
 };
@@ -13,9 +15,10 @@ use leaf_runtime::{
 use super::{SymPlaceHandler, backend};
 
 use backend::{
-    GenericVariablesState, SymExSymPlaceHandler, SymExTypeManager, ValueRef,
+    GenericVariablesState, LenBounder, SymExSymPlaceHandler, SymExTypeManager, ValueRef,
     alias::SymValueRefExprBuilder,
     expr::{lazy::RawPointerRetriever, prelude::*},
+    failure::fail,
     implication::{
         Antecedents, Implied, Precondition, PreconditionConstraints, PreconditionConstruct,
     },
@@ -133,7 +136,12 @@ pub(in super::super) struct RawPointerVariableState<EB> {
     sym_read_handler: SymPlaceHandlerObject,
     sym_write_handler: SymPlaceHandlerObject,
     sym_ref_handler: SymPlaceHandlerObject,
+    /// If set, the slices with symbolic lengths are dereferenced deterministically
+    /// and the indices into them are bounded by their lengths.
+    len_bounder: Option<Rc<LenBounder>>,
     expr_builder: RRef<EB>,
+    /// The places of the thread-local statics, which are distinct in each thread.
+    thread_locals: HashMap<(DefId, ThreadId), RawAddress>,
 }
 
 impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
@@ -141,17 +149,23 @@ impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
         type_manager: Rc<SymExTypeManager>,
         sym_read_handler: SymPlaceHandlerObject,
         sym_write_handler: SymPlaceHandlerObject,
+        sym_ref_handler: Option<SymPlaceHandlerObject>,
+        len_bounder: Option<Rc<LenBounder>>,
         expr_builder: RRef<EB>,
     ) -> Self {
         Self {
             memory: Default::default(),
             type_manager,
-            sym_ref_handler: RRef::new(RefCell::new(DerefBypassSymPlaceHandler(
-                sym_read_handler.clone(),
-            ))),
+            sym_ref_handler: sym_ref_handler.unwrap_or_else(|| {
+                RRef::new(RefCell::new(DerefBypassSymPlaceHandler(
+                    sym_read_handler.clone(),
+                )))
+            }),
             sym_read_handler,
             sym_write_handler,
+            len_bounder,
             expr_builder,
+            thread_locals: Default::default(),
         }
     }
 
@@ -211,7 +225,10 @@ impl<EB: SymValueRefExprBuilder> GenericVariablesState for RawPointerVariableSta
             PlaceValue::Deterministic(ref place) => {
                 self.set_deterministic_place(place, value);
             }
-            PlaceValue::Symbolic(..) => todo!("#238"),
+            PlaceValue::Symbolic(..) => fail!(
+                UnsupportedFeature,
+                "#238: Assignment to symbolic places is not supported."
+            ),
         }
     }
 
@@ -220,10 +237,30 @@ impl<EB: SymValueRefExprBuilder> GenericVariablesState for RawPointerVariableSta
         match place.as_ref() {
             PlaceValue::Deterministic(ref place) => self.drop_deterministic_place(place),
             PlaceValue::Symbolic(..) => {
-                panic!("Erasing a symbolic place is not expected")
+                fail!(
+                    InconsistentState,
+                    "Erasing a symbolic place is not expected"
+                )
             }
         }
     }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    fn ref_thread_local(&mut self, def_id: DefId, addr: RawAddress) -> Self::Value {
+        let key = (def_id, std::thread::current().id());
+        let registered = *self.thread_locals.entry(key).or_insert(addr);
+        if registered != addr {
+            log_warn!(
+                "Thread-local static {} has moved in the same thread from {:p} to {:p}",
+                def_id,
+                registered,
+                addr,
+            );
+            // The values at the previous place are no longer reachable through it.
+            self.thread_locals.insert(key, addr);
+        }
+        Implied::always(ConstValue::Addr(addr).to_value_ref())
+    }
 }
 
 #[derive(dm::From)]
@@ -418,7 +455,8 @@ impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
         for (offset, type_id, _) in &porter.sym_values {
             let value_size = self.type_manager.get_type(type_id).size;
             if offset + value_size > porter_size {
-                unimplemented!(
+                fail!(
+                    UnsupportedFeature,
                     "Overflowing symbolic values in a porter are not handled yet: {:?}",
                     porter
                 );
@@ -4,21 +4,24 @@ use core::{iter, ops::Bound};
 
 use common::log_debug;
 
-use leaf_runtime::abs::place::HasMetadata;
+use leaf_runtime::{abs::place::HasMetadata, type_info::FieldsShapeInfoExt};
 
 use super::*;
 
 use backend::{
     expr::{
-        MultiValue as ValueSelect, SliceIndex,
+        IntType, MultiValue as ValueSelect, SliceIndex, SymBinaryOperands,
         builders::sym_place::{SymbolicReadResolver, SymbolicReadTreeLeafMutator},
         place::*,
     },
+    failure::fail,
     place::PlaceMetadata,
 };
 
 use self::resolution::{DefaultSymPlaceResolver, Select as PlaceSelect, SinglePlaceResult};
 
+const FAT_PTR_FIELD_METADATA: usize = 1;
+
 impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
     pub(super) fn get_place<'a, 'b>(
         &'a self,
@@ -52,6 +55,7 @@ impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
 
         let base_place = DeterministicPlaceValue::new(base_metadata);
         let mut base_ref_conc_value = None;
+        let mut base_sym_len = None;
 
         // Process deref separately
         let (host_place, meta, projs) = if projs
@@ -63,9 +67,10 @@ impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
             let place =
                 match self.deref_if_sym_host(&base_place, dereferenced_meta, sym_place_handler) {
                     Ok(dereferenced_sym) => dereferenced_sym.into(),
-                    Err(base_ref_value) => {
+                    Err((base_ref_value, sym_len)) => {
                         // If an index is applied later, then this is the slice.
                         base_ref_conc_value = Some(base_ref_value);
+                        base_sym_len = sym_len;
                         DeterministicPlaceValue::new(dereferenced_meta).to_value_ref()
                     }
                 };
@@ -102,6 +107,7 @@ impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
                         sym_place_handler,
                         // We take the value as only the first index can be on a slice.
                         base_ref_conc_value.take(),
+                        base_sym_len.take(),
                     );
                     if let Some(sym_index) = opt_sym_index {
                         return SymbolicPlaceValue::from_base(sym_index, meta.into())
@@ -138,6 +144,13 @@ impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
     ) -> PlaceValueRef {
         let mut ptr_val = self.retrieve_value(ptr_val, ptr_type_id);
 
+        let pointee_ty = self.type_manager.get_pointee_ty(&ptr_type_id).unwrap();
+
+        if self.opt_sym_len_of(&ptr_val).is_some() {
+            // Only the length is symbolic, which does not affect the address.
+            return DeterministicPlaceValue::from_addr_type(conc_ptr, pointee_ty).to_value_ref();
+        }
+
         if ptr_val.is_symbolic() {
             ptr_val = self.sym_place_handler_for(usage).handle(
                 SymPlaceSymEntity::of_deref(SymValueRef::new(ptr_val)),
@@ -145,8 +158,6 @@ impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
             );
         }
 
-        let pointee_ty = self.type_manager.get_pointee_ty(&ptr_type_id).unwrap();
-
         if ptr_val.is_symbolic() {
             Self::deref_sym_val(SymValueRef::new(ptr_val), ptr_type_id, || pointee_ty.into()).into()
         } else {
@@ -159,14 +170,20 @@ impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
         host_place: &DeterministicPlaceValue,
         host_deref_metadata: &PlaceMetadata,
         mut sym_place_handler: &SymPlaceHandlerObject,
-    ) -> Result<SymPlaceValueRef, RawConcreteValue> {
+    ) -> Result<SymPlaceValueRef, (RawConcreteValue, Option<SymValueRef>)> {
         // FIXME: retain antecedents
         let host = self.copy_deterministic_place(&host_place).value;
         match host {
             DeterministicReadResult::MemObject(..) | DeterministicReadResult::Porter(..) => {
                 let concretize = || host_place.to_raw_value();
+                let host = host.to_value_ref();
+                if let Some(len) = self.opt_sym_len_of(&host) {
+                    log_debug!("Deref of slice with symbolic length observed: {}", len);
+                    return Err((concretize(), Some(len)));
+                }
+
                 let host = sym_place_handler.handle(
-                    SymPlaceSymEntity::of_deref(SymValueRef::new(host.to_value_ref())),
+                    SymPlaceSymEntity::of_deref(SymValueRef::new(host)),
                     Box::new(|| ConcreteValueRef::new(concretize().to_value_ref())),
                 );
                 if host.is_symbolic() {
@@ -177,10 +194,10 @@ impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
                     ))
                 } else {
                     // It is the same as the result of the closure above.
-                    Err(concretize())
+                    Err((concretize(), None))
                 }
             }
-            DeterministicReadResult::Lazy(raw_conc) => Err(raw_conc),
+            DeterministicReadResult::Lazy(raw_conc) => Err((raw_conc, None)),
         }
     }
 
@@ -208,65 +225,98 @@ impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
         index_proj: &'b Projection,
         mut sym_place_handler: &SymPlaceHandlerObject,
         base_slice_value: Option<RawConcreteValue>,
+        base_sym_len: Option<SymValueRef>,
     ) -> Option<SymIndexedPlace> {
-        let opt_sym_index_val = match index_proj {
+        let (host, index_val) = match index_proj {
             Projection::Index(index_place) => {
                 // FIXME: retain antecedents
-                Some(self.copy_deterministic_place(index_place).value)
-                    .take_if(|index| index.is_symbolic())
-                    .map(|index| {
-                        sym_place_handler.handle(
-                            SymPlaceSymEntity::of_index(SymValueRef::new(index.to_value_ref())),
-                            Self::conc_value_obtainer(index_place.as_ref()),
-                        )
-                    })
-                    .take_if(|index| index.is_symbolic())
-                    .map(|index_val| SymValueRef::new(index_val))
+                let index = self
+                    .copy_deterministic_place(index_place)
+                    .value
+                    .to_value_ref();
+                if let Some(len) = base_sym_len {
+                    self.bound_index(
+                        self.retrieve_value(index.clone(), index_place.type_id()),
+                        len,
+                    );
+                }
+                let index = Some(index).take_if(|index| index.is_symbolic())?;
+                let host = self.indexed_host(host, base_slice_value);
+                let index = sym_place_handler.handle(
+                    SymPlaceSymEntity::of_index(SymValueRef::new(index), self.len_of(&host)),
+                    Self::conc_value_obtainer(index_place.as_ref()),
+                );
+                let index = Some(index).take_if(|index| index.is_symbolic())?;
+                (host, SymValueRef::new(index))
             }
             Projection::ConstantIndex {
                 offset,
                 min_length: _,
                 from_end: true,
-            } => self
-                .opt_sym_index_val_from_end(host.as_ref(), *offset)
-                .map(|_| {
-                    let _index_place = todo!("#480: Index metadata is required for concretization");
-                }),
+            } => {
+                /* NOTE: No concrete index value is available to the handler here (#480),
+                 * thus the index is kept symbolic. */
+                let index = match base_sym_len {
+                    Some(len) => {
+                        let index = self.index_from_end(len.clone(), *offset);
+                        self.bound_index(index.clone().into(), len);
+                        index
+                    }
+                    None => self.opt_sym_index_val_from_end(host.as_ref(), *offset)?,
+                };
+                (self.indexed_host(host, base_slice_value), index)
+            }
             _ => unreachable!("Expecting only index projections. Got: {:?}", index_proj),
         };
 
-        opt_sym_index_val.map(|index_val| {
-            log_debug!("Symbolic index observed: {}", &index_val);
-
-            let host = if let Some(base_slice) = base_slice_value {
-                debug_assert!(
-                    !host.is_symbolic(),
-                    "With a concrete base slice value, the host is not expected to be symbolic. Got: {}",
-                    host,
-                );
-                let slice_value = {
-                let base_type_id = base_slice.1.id().unwrap();
-                 self
-                    .retrieve_conc_value(
-                        ConcreteValueRef::new(base_slice.to_value_ref()),
-                        base_type_id,
-                    )
-                };
-                let host_value = slice_value.expect_fat_ptr(self.type_manager.as_ref(), self)
-                    .deref(self.type_manager.as_ref(), self);
-                DeterministicPlaceValue::from_addr_type_info(host_value.0, host_value.1)
-                    .to_value_ref()
-            } else {
-                host.clone()
-            };
-
-            SymIndexedPlace {
-                host,
-                index: index_val,
-            }
+        log_debug!("Symbolic index observed: {}", &index_val);
+        Some(SymIndexedPlace {
+            host,
+            index: index_val,
         })
     }
 
+    /// Returns the host of indexing, which is the pointed slice if the base slice
+    /// value (the fat pointer) is given.
+    fn indexed_host(
+        &self,
+        host: &PlaceValueRef,
+        base_slice_value: Option<RawConcreteValue>,
+    ) -> PlaceValueRef {
+        let Some(base_slice) = base_slice_value else {
+            return host.clone();
+        };
+
+        debug_assert!(
+            !host.is_symbolic(),
+            "With a concrete base slice value, the host is not expected to be symbolic. Got: {}",
+            host,
+        );
+        let slice_value = {
+            let base_type_id = base_slice.1.id().unwrap();
+            self.retrieve_conc_value(
+                ConcreteValueRef::new(base_slice.to_value_ref()),
+                base_type_id,
+            )
+        };
+        let host_value = slice_value
+            .expect_fat_ptr(self.type_manager.as_ref(), self)
+            .deref(self.type_manager.as_ref(), self);
+        DeterministicPlaceValue::from_addr_type_info(host_value.0, host_value.1).to_value_ref()
+    }
+
+    /// Returns the number of elements of the host if it is a deterministic array.
+    fn len_of(&self, host: &PlaceValueRef) -> Option<u64> {
+        match host.as_ref() {
+            PlaceValue::Deterministic(place) => place
+                .type_info()
+                .get_type(self.type_manager.as_ref())
+                .and_then(|ty| ty.as_array())
+                .map(|shape| shape.len),
+            PlaceValue::Symbolic(..) => None,
+        }
+    }
+
     fn opt_sym_index_val_from_end(&self, host: &PlaceValue, offset: u64) -> Option<SymValueRef> {
         // FIXME: As indices from end refer to only one element, it is more reasonable
         // to introduce a new symbolic place kind and handle them in the resolver properly.
@@ -276,6 +326,7 @@ impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
         if let PlaceValue::Symbolic(
             sym_host @ SymbolicPlaceValue {
                 base: SymbolicPlaceBase::Deref(..),
+                proj: None,
                 ..
             },
         ) = host
@@ -285,11 +336,65 @@ impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
                 host,
                 offset
             );
-            let _len = self.retrieve_len_value(sym_host);
-            let _index: SymValueRef = todo!("#485");
+            let len = self.retrieve_len_value(sym_host);
+            Some(self.index_from_end(len, offset))
+        } else {
+            None
+        }
+    }
+
+    fn index_from_end(&self, len: SymValueRef, offset: u64) -> SymValueRef {
+        let index = self.expr_builder.borrow_mut().sub(SymBinaryOperands::Orig {
+            first: len,
+            second: ConstValue::new_int(offset, IntType::USIZE).to_value_ref(),
+        });
+        SymValueRef::new(index)
+    }
+
+    /// Returns the length of the slice pointed by the fat pointer if lengths are
+    /// kept symbolic and the length is the only symbolic part of the pointer.
+    /// In this case, the pointer is effectively concrete for dereferencing.
+    fn opt_sym_len_of(&self, ptr: &ValueRef) -> Option<SymValueRef> {
+        self.len_bounder.as_ref()?;
+        let Value::Symbolic(SymValue::Expression(Expr::Partial(porter))) = ptr.as_ref() else {
+            return None;
+        };
+
+        let ptr_ty = porter.as_concrete.1.get_type(self.type_manager.as_ref())?;
+        if !ptr_ty
+            .pointee_ty
+            .is_some_and(|pointee_ty| self.get_type(pointee_ty).is_slice())
+        {
+            return None;
+        }
+        let metadata_offset = ptr_ty
+            .expect_single_variant()
+            .fields
+            .expect_struct()
+            .fields()[FAT_PTR_FIELD_METADATA]
+            .offset;
+        if porter
+            .sym_values
+            .iter()
+            .any(|(offset, ..)| *offset < metadata_offset)
+        {
+            return None;
         }
 
-        None
+        let len = self.retrieve_porter_ptr_metadata(porter);
+        len.is_symbolic().then(|| SymValueRef::new(len))
+    }
+
+    /// Constrains the index to be within the symbolic length of the indexed slice.
+    fn bound_index(&self, index: ValueRef, len: SymValueRef) {
+        let Some(bounder) = self.len_bounder.as_ref() else {
+            return;
+        };
+        let in_bounds = self.expr_builder.borrow_mut().gt(SymBinaryOperands::Orig {
+            first: len,
+            second: index,
+        });
+        bounder.bound(in_bounds);
     }
 
     fn to_deterministic_proj<'a>(
@@ -543,6 +648,9 @@ impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
             SymValue::Expression(Expr::Multi(multi)) => Expr::from(multi.map_leaves(
                 Clone::clone,
                 |value| match value.as_ref() {
+                    Value::Symbolic(SymValue::Expression(Expr::Partial(porter))) => {
+                        self.retrieve_porter_ptr_metadata(porter)
+                    }
                     Value::Symbolic(host) => self.retrieve_ptr_metadata(host).into(),
                     Value::Concrete(ConcreteValue::FatPointer(fat_ptr)) => {
                         fat_ptr.metadata.0.clone()
@@ -554,13 +662,19 @@ impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
                 },
             ))
             .to_value_ref(),
-            SymValue::Expression(Expr::Transmutation { .. })
-            | SymValue::Expression(Expr::Partial(..)) => {
-                /* NOTE: Straight forward resolution of metadata from partial values should be handled in
-                 * expression builders. The value here should be something with an exceptional shape. */
-                todo!(
-                    "#443, #454: PtrMetadata from transmuted and partial values is not supported yet."
-                )
+            SymValue::Expression(Expr::Transmutation { source, dst_ty }) => {
+                self.retrieve_transmuted_ptr_metadata(source, dst_ty)
+            }
+            SymValue::Expression(Expr::Partial(porter)) => {
+                let metadata = self.retrieve_porter_ptr_metadata(porter);
+                /* NOTE: The partial values with concrete metadata are resolved by the expression
+                 * builders, so a symbolic metadata is expected here. */
+                debug_assert!(
+                    metadata.is_symbolic(),
+                    "Partial fat pointer with concrete metadata is not expected here: {:?}",
+                    porter,
+                );
+                SymValueRef::new(metadata)
             }
             _ => {
                 unreachable!(
@@ -570,6 +684,102 @@ impl<EB: SymValueRefExprBuilder> RawPointerVariableState<EB> {
             }
         }
     }
+
+    /// Extracts the metadata field of a partially symbolic fat pointer according to its layout.
+    fn retrieve_porter_ptr_metadata(&self, porter: &PorterValue) -> ValueRef {
+        let field = &porter
+            .as_concrete
+            .1
+            .get_type(self.type_manager.as_ref())
+            .expect("The type of the partial fat pointer is expected to be known.")
+            .expect_single_variant()
+            .fields
+            .expect_struct()
+            .fields()[FAT_PTR_FIELD_METADATA];
+        let field_range = field.offset..field.offset + self.get_type(field.ty).size().unwrap();
+
+        let sym_values = porter
+            .sym_values
+            .iter()
+            .filter(|(offset, ty_id, _)| {
+                let end = offset + self.get_type(*ty_id).size().unwrap();
+                *offset < field_range.end && field_range.start < end
+            })
+            .map(|(offset, ty_id, value)| {
+                let end = offset + self.get_type(*ty_id).size().unwrap();
+                assert!(
+                    field_range.start <= *offset && end <= field_range.end,
+                    "Symbolic values spanning over multiple fields of a fat pointer are not expected."
+                );
+                (offset - field.offset, *ty_id, value.clone())
+            })
+            .collect::<Vec<_>>();
+
+        let as_concrete = RawConcreteValue(
+            porter
+                .as_concrete
+                .0
+                .wrapping_byte_add(field.offset as usize),
+            LazyTypeInfo::from(field.ty),
+        );
+        match sym_values.as_slice() {
+            [] => self
+                .retrieve_conc_value(ConcreteValueRef::new(as_concrete.to_value_ref()), field.ty)
+                .into(),
+            [(0, ty_id, value)] if *ty_id == field.ty => {
+                self.retrieve_sym_value(value.clone(), field.ty).into()
+            }
+            _ => PorterValue::new(as_concrete, sym_values)
+                .try_to_concatenated_scalar(
+                    self.type_manager.as_ref(),
+                    &mut *self.expr_builder.borrow_mut(),
+                )
+                .unwrap_or_else(|ty| {
+                    fail!(
+                        InconsistentState,
+                        "The metadata is expected to be a scalar. Got: {ty:?}"
+                    )
+                })
+                .into(),
+        }
+    }
+
+    /// Resolves the metadata of a fat pointer obtained by transmutation, by reinterpreting
+    /// the source according to the layout of the fat pointer.
+    fn retrieve_transmuted_ptr_metadata(
+        &self,
+        source: &SymValueRef,
+        dst_ty: &LazyTypeInfo,
+    ) -> SymValueRef {
+        match ValueType::try_from(source.value()) {
+            // The bits of an integer (e.g., `u128`) holding both the address and the metadata.
+            Ok(ValueType::Int(src_ty)) => {
+                let metadata_field = &dst_ty
+                    .get_type(self.type_manager.as_ref())
+                    .expect("The type of the transmuted fat pointer is expected to be known.")
+                    .expect_single_variant()
+                    .fields
+                    .expect_struct()
+                    .fields()[FAT_PTR_FIELD_METADATA];
+                let mut expr_builder = self.expr_builder.borrow_mut();
+                let shifted = expr_builder.shr(SymBinaryOperands::Orig {
+                    first: source.clone(),
+                    second: ConstValue::new_int(metadata_field.offset * 8, src_ty).to_value_ref(),
+                });
+                // NOTE: The metadata is either a length or a vtable pointer, both of pointer size.
+                SymValueRef::new(expr_builder.to_int(
+                    SymValueRef::new(shifted),
+                    IntType::USIZE,
+                    LazyTypeInfo::from(metadata_field.ty),
+                ))
+            }
+            // Another fat pointer with the same layout, e.g., `&[u8]` to `&str`.
+            Err(_) => self.retrieve_ptr_metadata(source.as_ref()),
+            Ok(src_ty) => unreachable!(
+                "Only integers or fat pointers are expected to be transmuted to fat pointers. Got: {src_ty:?}"
+            ),
+        }
+    }
 }
 
 #[inline]
@@ -26,7 +26,7 @@
 use derive_more::From;
 
 use super::backend;
-use backend::expr::builders::sym_place::SymbolicReadResolver;
+use backend::{expr::builders::sym_place::SymbolicReadResolver, failure::fail};
 
 use super::*;
 
@@ -155,18 +155,34 @@ mod implementation {
         }
 
         fn deref_symbolic(&self, host: &SymValue, pointee_type_id: TypeId) -> Select {
-            let unexpected = || unreachable!("Unexpected symbolic host to dereference: {:?}", host);
+            let unexpected = || {
+                fail!(
+                    InconsistentState,
+                    "Unexpected symbolic host to dereference: {:?}",
+                    host
+                )
+            };
+            let unsupported = || {
+                fail!(
+                    UnsupportedFeature,
+                    "Dereferencing this symbolic host is not supported: {:?}",
+                    host
+                )
+            };
             let SymValue::Expression(expr) = host else {
                 unexpected()
             };
             match expr {
                 Expr::Multi(multi) => self.deref_multi(multi, pointee_type_id),
-                Expr::Offset { .. } => todo!(),
+                Expr::Offset { .. } => unsupported(),
                 // Cast
-                Expr::Ite { .. } | Expr::Truncation(..) | Expr::Extension(..) => todo!(),
-                Expr::Partial(..) => todo!(),
-                Expr::Concat(..) => todo!(),
-                Expr::Transmutation { .. } => todo!(),
+                Expr::Ite { .. }
+                | Expr::Truncation(..)
+                | Expr::Extension(..)
+                | Expr::FloatCast(..) => unsupported(),
+                Expr::Partial(..) => unsupported(),
+                Expr::Concat(..) => unsupported(),
+                Expr::Transmutation { .. } => unsupported(),
                 Expr::Unary { .. }
                 | Expr::Binary(..)
                 | Expr::BinaryBoundCheck { .. }
@@ -179,7 +195,7 @@ mod implementation {
             multi.map_expand(
                 |index| {
                     if index.from_end {
-                        todo!("Index from end is not supported yet.")
+                        fail!(UnsupportedFeature, "Index from end is not supported yet.")
                     }
                     index.index.clone()
                 },
@@ -209,13 +225,18 @@ mod implementation {
                     host.deref(self.type_manager, self.retriever).into()
                 }
                 ConcreteValue::Unevaluated(UnevalValue::Lazy(host)) => {
-                    panic!(
+                    fail!(
+                        InconsistentState,
                         "Lazy unevaluated value should be retrieved before: {:?}",
                         host
                     )
                 }
                 _ => {
-                    unreachable!("Unexpected concrete value to dereference: {:?}", host)
+                    fail!(
+                        InconsistentState,
+                        "Unexpected concrete value to dereference: {:?}",
+                        host
+                    )
                 }
             }
         }
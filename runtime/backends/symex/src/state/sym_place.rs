@@ -11,6 +11,9 @@ pub(crate) struct SymPlaceSymEntity {
     #[deref]
     value: SymValueRef,
     kind: ValueUsageInPlace,
+    /// The number of possible targets of the place if known, e.g., the length of
+    /// the array being indexed.
+    candidates: Option<u64>,
 }
 
 #[derive(Debug)]
@@ -33,10 +36,11 @@ pub(crate) trait SymPlaceHandler {
 }
 
 impl SymPlaceSymEntity {
-    pub(crate) fn of_index(value: SymValueRef) -> Self {
+    pub(crate) fn of_index(value: SymValueRef, candidates: Option<u64>) -> Self {
         Self {
             value,
             kind: ValueUsageInPlace::Index,
+            candidates,
         }
     }
 
@@ -44,6 +48,7 @@ impl SymPlaceSymEntity {
         Self {
             value,
             kind: ValueUsageInPlace::Deref,
+            candidates: None,
         }
     }
 
@@ -51,6 +56,7 @@ impl SymPlaceSymEntity {
         Self {
             value,
             kind: ValueUsageInPlace::Size,
+            candidates: None,
         }
     }
 }
@@ -60,7 +66,12 @@ pub(super) mod strategies {
 
     use super::*;
 
-    use backend::{concrete::Concretizer, config::SymbolicPlaceStrategy};
+    use backend::{
+        concrete::Concretizer,
+        config::SymbolicPlaceStrategy,
+        diagnostics::{SymbolismLossReason, report_symbolism_loss},
+        trace::{ConcretizationReason, record_concretization},
+    };
 
     pub(crate) fn make_sym_place_handler(
         config: SymbolicPlaceStrategy,
@@ -84,6 +95,12 @@ pub(super) mod strategies {
                     concretizer: concretizer_factory(),
                 },
             }),
+            BoundedProjExpression { max_candidates } => Box::new(BoundedProjExprSymPlaceHandler {
+                max_candidates,
+                fallback: StamperSymPlaceHandler {
+                    concretizer: concretizer_factory(),
+                },
+            }),
             Concretization => Box::new(ConcretizerSymPlaceHandler),
             Stamping => Box::new(StamperSymPlaceHandler {
                 concretizer: concretizer_factory(),
@@ -133,6 +150,47 @@ pub(super) mod strategies {
         }
     }
 
+    struct BoundedProjExprSymPlaceHandler<H> {
+        max_candidates: u64,
+        fallback: H,
+    }
+    impl<H> SymPlaceHandler for BoundedProjExprSymPlaceHandler<H>
+    where
+        H: SymPlaceHandler<SymEntity = SymPlaceSymEntity, Entity = ValueRef>,
+    {
+        type ConcEntity = H::ConcEntity;
+        type Entity = ValueRef;
+
+        fn handle<'a>(
+            &mut self,
+            sym_entity: Self::SymEntity,
+            get_conc: Box<ConcolicValueObtainer<'a, Self::ConcEntity>>,
+        ) -> Self::Entity {
+            match sym_entity.kind {
+                ValueUsageInPlace::Size => self.fallback.handle(sym_entity, get_conc),
+                /* NOTE: The targets of symbolic pointers are not known at this point.
+                 * They are mostly the result of referencing symbolic places (e.g., `&a[i]`),
+                 * which are bounded themselves. */
+                ValueUsageInPlace::Deref => sym_entity.into(),
+                ValueUsageInPlace::Index => {
+                    if sym_entity
+                        .candidates
+                        .is_some_and(|count| count <= self.max_candidates)
+                    {
+                        sym_entity.into()
+                    } else {
+                        log_debug!(
+                            "Symbolic index with {:?} candidates is concretized: {}",
+                            sym_entity.candidates,
+                            sym_entity.value
+                        );
+                        self.fallback.handle(sym_entity, get_conc)
+                    }
+                }
+            }
+        }
+    }
+
     struct ConcretizerSymPlaceHandler;
     impl SymPlaceHandler for ConcretizerSymPlaceHandler {
         type Entity = ValueRef;
@@ -142,7 +200,29 @@ pub(super) mod strategies {
             sym_value: Self::SymEntity,
             get_conc: Box<ConcolicValueObtainer<'a, Self::ConcEntity>>,
         ) -> Self::Entity {
+            Self::concretize(sym_value, get_conc, false)
+        }
+    }
+
+    impl ConcretizerSymPlaceHandler {
+        fn concretize<'a>(
+            sym_value: SymPlaceSymEntity,
+            get_conc: Box<ConcolicValueObtainer<'a, ConcreteValueRef>>,
+            constrained: bool,
+        ) -> ValueRef {
             log_info!("Concretizing symbolic value: {}", sym_value.value);
+            report_symbolism_loss(SymbolismLossReason::PlaceConcretization, || {
+                format!("{:?}: {}", sym_value.kind, sym_value.value)
+            });
+            record_concretization(
+                match sym_value.kind {
+                    ValueUsageInPlace::Deref => ConcretizationReason::Deref,
+                    ValueUsageInPlace::Index => ConcretizationReason::Index,
+                    ValueUsageInPlace::Size => ConcretizationReason::Size,
+                },
+                &sym_value.value,
+                constrained,
+            );
             get_conc().into()
         }
     }
@@ -159,7 +239,7 @@ pub(super) mod strategies {
             get_conc: Box<ConcolicValueObtainer<'a, Self::ConcEntity>>,
         ) -> Self::Entity {
             let conc_value = self.concretizer.stamp(sym_entity.clone(), get_conc);
-            ConcretizerSymPlaceHandler.handle(sym_entity, Box::new(|| conc_value))
+            ConcretizerSymPlaceHandler::concretize(sym_entity, Box::new(|| conc_value), true)
         }
     }
 
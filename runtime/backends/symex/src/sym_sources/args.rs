@@ -0,0 +1,127 @@
+//! Command-line arguments as a source of symbolic variables.
+//!
+//! The bytes of the arguments are marked symbolic by the program (see the
+//! `mark_args_symbolic` annotation) under a dedicated tag. The variables
+//! created under the tag are mapped to the bytes of the actual arguments of
+//! the process in order.
+
+use std::{cell::RefCell, collections::HashMap, fs, io, path::PathBuf};
+
+use common::{log_debug, log_info, log_warn, pri::tags};
+
+use leaf_runtime::{
+    abs::Tag,
+    utils::file::{FileFormat, FileGenConfig},
+};
+
+use super::backend;
+use backend::{SymVarId, config::OutputConfig};
+
+const FILENAME_PREFIX_DEFAULT: &str = "argv_";
+
+#[derive(Default)]
+struct SymbolicArgs {
+    /// The arguments of the process, except the program name.
+    args: Vec<Vec<u8>>,
+    /// The argument index and the byte offset of the variables.
+    positions: HashMap<SymVarId, (usize, usize)>,
+    /// The position of the next byte to be marked symbolic.
+    cursor: (usize, usize),
+    outputs: Vec<OutputConfig>,
+    counter: usize,
+}
+
+thread_local! {
+    static ARGS: RefCell<SymbolicArgs> = RefCell::new(Default::default());
+}
+
+pub(crate) fn init_args_outputs(outputs: &[OutputConfig]) {
+    ARGS.with_borrow_mut(|a| a.outputs = outputs.to_vec());
+}
+
+/// Relates the variable to the next byte of the arguments if it is created
+/// from the arguments, i.e., the arguments tag is active.
+pub(super) fn notify_variable(id: SymVarId, tags: &[Tag]) {
+    if !tags.contains(&tags::SYMBOLIC_ARGS) {
+        return;
+    }
+
+    ARGS.with_borrow_mut(|a| {
+        if a.positions.is_empty() {
+            a.args = std::env::args_os()
+                .skip(1)
+                .map(|arg| arg.into_encoded_bytes())
+                .collect();
+        }
+
+        // Skipping the exhausted (and empty) arguments.
+        let (mut arg, mut offset) = a.cursor;
+        while a.args.get(arg).is_some_and(|bytes| offset >= bytes.len()) {
+            arg += 1;
+            offset = 0;
+        }
+        if arg >= a.args.len() {
+            log_warn!(
+                "Symbolic variable {} is marked as an argument byte beyond the arguments",
+                id
+            );
+            return;
+        }
+
+        log_debug!("Symbolic variable {} is argument {}[{}]", id, arg, offset);
+        a.positions.insert(id, (arg, offset));
+        a.cursor = (arg, offset + 1);
+    });
+}
+
+pub(super) fn variable_ids() -> Vec<SymVarId> {
+    ARGS.with_borrow(|a| a.positions.keys().copied().collect())
+}
+
+pub(super) fn write_outputs(answers: &[(SymVarId, Option<u8>)]) {
+    ARGS.with_borrow_mut(|a| {
+        if a.positions.is_empty() || a.outputs.is_empty() {
+            return;
+        }
+
+        let mut args = a.args.clone();
+        for (id, answer) in answers {
+            let (Some((arg, offset)), Some(byte)) = (a.positions.get(id), answer) else {
+                continue;
+            };
+            args[*arg][*offset] = *byte;
+        }
+
+        for output in &a.outputs {
+            let OutputConfig::File(file_config) = output;
+            let _ = write(file_config, a.counter, &args)
+                .inspect(|path| log_info!("Arguments written to: {}", path.display()))
+                .inspect_err(|e| log_warn!("Could not write arguments: {e}"));
+        }
+        a.counter += 1;
+    });
+}
+
+/// Writes the arguments as a JSON array of strings (lossy) or as
+/// NUL-terminated byte strings in the binary format.
+fn write(config: &FileGenConfig, counter: usize, args: &[Vec<u8>]) -> io::Result<PathBuf> {
+    let path = config.ensure_dir()?.join(format!(
+        "{}{counter}.{}",
+        config.prefix().unwrap_or(FILENAME_PREFIX_DEFAULT),
+        config.extension_or_default(),
+    ));
+    let content = match config.format() {
+        FileFormat::Binary => args
+            .iter()
+            .flat_map(|arg| arg.iter().copied().chain([0]))
+            .collect(),
+        _ => serde_json::to_vec(
+            &args
+                .iter()
+                .map(|arg| String::from_utf8_lossy(arg))
+                .collect::<Vec<_>>(),
+        )?,
+    };
+    fs::write(&path, content)?;
+    Ok(path)
+}
@@ -0,0 +1,108 @@
+//! Environment variables as a source of symbolic variables.
+//!
+//! The values are marked symbolic by the program (see the `mark_env_var_symbolic`
+//! annotation) under a dedicated tag followed by a tag of the variable name.
+//! Only the names selected in the configuration get symbolic, and the rest
+//! keep their concrete values.
+
+use std::{cell::RefCell, collections::HashMap, fs, io, path::PathBuf};
+
+use common::{log_debug, log_info, log_warn, pri::tags};
+
+use leaf_runtime::{
+    abs::Tag,
+    utils::file::{FileFormat, FileGenConfig},
+};
+
+use super::{NamedValues, backend};
+use backend::{
+    SymVarId,
+    config::{OutputConfig, SymEnvVarsConfig},
+};
+
+const FILENAME_PREFIX_DEFAULT: &str = "env_";
+
+thread_local! {
+    static ENV_VARS: RefCell<NamedValues> = RefCell::new(Default::default());
+}
+
+pub(crate) fn init_env_vars(config: &SymEnvVarsConfig) {
+    ENV_VARS.set(NamedValues::new(
+        config.names.clone(),
+        config.outputs.clone(),
+    ));
+}
+
+pub(super) fn is_selected(tags: &[Tag]) -> bool {
+    NamedValues::name_of(tags, tags::SYMBOLIC_ENV_VAR).is_none_or(|name| {
+        ENV_VARS.with_borrow(|e| e.is_selected(name, |selected, name| selected == name))
+    })
+}
+
+pub(super) fn notify_variable(id: SymVarId, tags: &[Tag]) {
+    let Some(name) = NamedValues::name_of(tags, tags::SYMBOLIC_ENV_VAR) else {
+        return;
+    };
+
+    let read = |name: &str| {
+        std::env::var_os(name)
+            .map(|v| v.into_encoded_bytes())
+            .unwrap_or_default()
+    };
+    match ENV_VARS.with_borrow_mut(|e| e.add_variable(id, name, read)) {
+        Some(offset) => log_debug!("Symbolic variable {} is `{}`[{}]", id, name, offset),
+        None => log_warn!(
+            "Symbolic variable {} is marked as a byte beyond the value of `{}`",
+            id,
+            name
+        ),
+    }
+}
+
+pub(super) fn variable_ids() -> Vec<SymVarId> {
+    ENV_VARS.with_borrow(|e| e.variable_ids().collect())
+}
+
+pub(super) fn write_outputs(answers: &[(SymVarId, Option<u8>)]) {
+    ENV_VARS.with_borrow_mut(|e| {
+        let Some(values) = e.answered(answers) else {
+            return;
+        };
+
+        for output in &e.outputs {
+            let OutputConfig::File(file_config) = output;
+            let _ = write(file_config, e.counter, &values)
+                .inspect(|path| log_info!("Environment variables written to: {}", path.display()))
+                .inspect_err(|err| log_warn!("Could not write environment variables: {err}"));
+        }
+        e.counter += 1;
+    });
+}
+
+/// Writes the assignments as a JSON object (lossy), as `NAME=value` lines in
+/// the text format, or as NUL-terminated `NAME=value` entries in the binary format.
+fn write(config: &FileGenConfig, counter: usize, values: &[(Tag, Vec<u8>)]) -> io::Result<PathBuf> {
+    let path = config.ensure_dir()?.join(format!(
+        "{}{counter}.{}",
+        config.prefix().unwrap_or(FILENAME_PREFIX_DEFAULT),
+        config.extension_or_default(),
+    ));
+    let assignments = |terminator: u8| {
+        values
+            .iter()
+            .flat_map(|(name, value)| [name.as_bytes(), b"=", value, &[terminator]].concat())
+            .collect::<Vec<_>>()
+    };
+    let content = match config.format() {
+        FileFormat::Binary => assignments(0),
+        FileFormat::Text => assignments(b'\n'),
+        _ => serde_json::to_vec(
+            &values
+                .iter()
+                .map(|(name, value)| (*name, String::from_utf8_lossy(value)))
+                .collect::<HashMap<_, _>>(),
+        )?,
+    };
+    fs::write(&path, content)?;
+    Ok(path)
+}
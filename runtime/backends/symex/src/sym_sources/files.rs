@@ -0,0 +1,116 @@
+//! Files as a source of symbolic variables.
+//!
+//! The contents read from the files are marked symbolic by the program (see
+//! the `mark_file_symbolic` annotation) under a dedicated tag followed by a tag
+//! of the path of the file. Only the files selected in the configuration get
+//! symbolic, and the rest keep their concrete contents.
+
+use std::{
+    cell::RefCell,
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use common::{log_debug, log_info, log_warn, pri::tags};
+
+use leaf_runtime::{abs::Tag, utils::file::FileGenConfig};
+
+use super::{NamedValues, backend};
+use backend::{
+    SymVarId,
+    config::{OutputConfig, SymFilesConfig},
+};
+
+const DIRNAME_PREFIX_DEFAULT: &str = "files_";
+
+thread_local! {
+    static FILES: RefCell<NamedValues> = RefCell::new(Default::default());
+}
+
+pub(crate) fn init_files(config: &SymFilesConfig) {
+    FILES.set(NamedValues::new(
+        config
+            .paths
+            .iter()
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect(),
+        config.outputs.clone(),
+    ));
+}
+
+pub(super) fn is_selected(tags: &[Tag]) -> bool {
+    NamedValues::name_of(tags, tags::SYMBOLIC_FILE)
+        .is_none_or(|path| FILES.with_borrow(|f| f.is_selected(path, is_match)))
+}
+
+/// A path matches a selected one if it is equal to or ends with it, compared
+/// component-wise, e.g., `/data/input.txt` matches `input.txt`.
+fn is_match(selected: &str, path: &str) -> bool {
+    Path::new(path).ends_with(selected)
+}
+
+pub(super) fn notify_variable(id: SymVarId, tags: &[Tag]) {
+    let Some(path) = NamedValues::name_of(tags, tags::SYMBOLIC_FILE) else {
+        return;
+    };
+
+    let read = |path: &str| {
+        fs::read(path)
+            .inspect_err(|e| log_warn!("Could not read the symbolic file `{path}`: {e}"))
+            .unwrap_or_default()
+    };
+    match FILES.with_borrow_mut(|f| f.add_variable(id, path, read)) {
+        Some(offset) => log_debug!("Symbolic variable {} is `{}`[{}]", id, path, offset),
+        None => log_warn!(
+            "Symbolic variable {} is marked as a byte beyond the contents of `{}`",
+            id,
+            path
+        ),
+    }
+}
+
+pub(super) fn variable_ids() -> Vec<SymVarId> {
+    FILES.with_borrow(|f| f.variable_ids().collect())
+}
+
+pub(super) fn write_outputs(answers: &[(SymVarId, Option<u8>)]) {
+    FILES.with_borrow_mut(|f| {
+        let Some(contents) = f.answered(answers) else {
+            return;
+        };
+
+        for output in &f.outputs {
+            let OutputConfig::File(file_config) = output;
+            let _ = write(file_config, f.counter, &contents)
+                .inspect(|dir| log_info!("Files written to: {}", dir.display()))
+                .inspect_err(|err| log_warn!("Could not write files: {err}"));
+        }
+        f.counter += 1;
+    });
+}
+
+/// Writes the contents to a directory per input, where the files are placed
+/// at their paths relative to the directory.
+fn write(
+    config: &FileGenConfig,
+    counter: usize,
+    contents: &[(Tag, Vec<u8>)],
+) -> io::Result<PathBuf> {
+    let dir = config.ensure_dir()?.join(format!(
+        "{}{counter}",
+        config.prefix().unwrap_or(DIRNAME_PREFIX_DEFAULT),
+    ));
+    for (path, content) in contents {
+        // Keeping the files inside the directory regardless of the original path.
+        let relative = Path::new(path)
+            .components()
+            .filter(|c| matches!(c, Component::Normal(_)))
+            .collect::<PathBuf>();
+        let path = dir.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)?;
+    }
+    Ok(dir)
+}
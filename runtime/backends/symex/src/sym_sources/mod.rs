@@ -0,0 +1,143 @@
+//! Sources of symbolic variables other than the main input (e.g., stdin),
+//! i.e., the command-line arguments, the environment variables, and the files.
+//!
+//! The bytes read from these sources are marked symbolic by the program using
+//! the annotations, under the tags specific to each source. The variables
+//! created under the tags are mapped to the bytes of the source, so that the
+//! generated answers can be turned into new contents of the source. These
+//! variables are excluded from the main outputs.
+
+use std::collections::HashMap;
+
+use leaf_runtime::abs::Tag;
+
+use super::alias::backend;
+use backend::{SymVarId, config::OutputConfig};
+
+mod args;
+mod env;
+mod files;
+
+pub(crate) use args::init_args_outputs;
+pub(crate) use env::init_env_vars;
+pub(crate) use files::init_files;
+
+/// Relates the variable to the source it is created from, if any.
+pub(crate) fn notify_variable(id: SymVarId, tags: &[Tag]) {
+    args::notify_variable(id, tags);
+    env::notify_variable(id, tags);
+    files::notify_variable(id, tags);
+}
+
+/// Whether a symbolic variable should be created in the context of the tags,
+/// i.e., it is not from a named source (environment variable or file) that
+/// is not selected in the configuration.
+pub(crate) fn is_selected(tags: &[Tag]) -> bool {
+    env::is_selected(tags) && files::is_selected(tags)
+}
+
+/// # Returns
+/// The ids of the variables created from the sources in ascending order.
+pub(crate) fn variable_ids() -> Vec<SymVarId> {
+    let mut ids = args::variable_ids();
+    ids.extend(env::variable_ids());
+    ids.extend(files::variable_ids());
+    ids.sort();
+    ids
+}
+
+/// Writes the contents of the sources with the bytes replaced by the answers
+/// to the outputs of each source.
+/// `None` answers are considered non-byte values and leave the bytes intact.
+pub(crate) fn write_outputs(answers: &[(SymVarId, Option<u8>)]) {
+    args::write_outputs(answers);
+    env::write_outputs(answers);
+    files::write_outputs(answers);
+}
+
+/// A source of values identified by names, e.g., environment variables.
+/// The names are given by a tag following the tag of the source.
+#[derive(Default)]
+struct NamedValues {
+    /// The names selected to be symbolic.
+    selected: Vec<String>,
+    /// The values that are marked symbolic, in the order of marking.
+    values: Vec<(Tag, Vec<u8>)>,
+    /// The number of bytes marked symbolic for each of the values.
+    marked: Vec<usize>,
+    /// The index of the value and the byte offset of the symbolic variables.
+    positions: HashMap<SymVarId, (usize, usize)>,
+    outputs: Vec<OutputConfig>,
+    counter: usize,
+}
+
+impl NamedValues {
+    fn new(selected: Vec<String>, outputs: Vec<OutputConfig>) -> Self {
+        Self {
+            selected,
+            outputs,
+            ..Default::default()
+        }
+    }
+
+    /// # Returns
+    /// The name of the value being marked symbolic under the source tag, if any.
+    fn name_of(tags: &[Tag], source_tag: Tag) -> Option<Tag> {
+        let pos = tags.iter().rposition(|t| *t == source_tag)?;
+        tags.get(pos + 1).copied()
+    }
+
+    fn is_selected(&self, name: &str, matches: impl Fn(&str, &str) -> bool) -> bool {
+        self.selected.iter().any(|s| matches(s, name))
+    }
+
+    /// Relates the variable to the next byte of the value, which is read
+    /// using `read` when the value is seen for the first time.
+    /// # Returns
+    /// The byte offset of the variable in the value, if it is within the value.
+    fn add_variable(
+        &mut self,
+        id: SymVarId,
+        name: Tag,
+        read: impl FnOnce(&str) -> Vec<u8>,
+    ) -> Option<usize> {
+        let index = match self.values.iter().position(|(n, _)| *n == name) {
+            Some(index) => index,
+            None => {
+                self.values.push((name, read(name)));
+                self.marked.push(0);
+                self.values.len() - 1
+            }
+        };
+
+        let offset = self.marked[index];
+        if offset >= self.values[index].1.len() {
+            return None;
+        }
+        self.positions.insert(id, (index, offset));
+        self.marked[index] += 1;
+        Some(offset)
+    }
+
+    fn variable_ids(&self) -> impl Iterator<Item = SymVarId> + '_ {
+        self.positions.keys().copied()
+    }
+
+    /// # Returns
+    /// The values with the bytes replaced by the answers, or `None` if there
+    /// is nothing to write.
+    fn answered(&self, answers: &[(SymVarId, Option<u8>)]) -> Option<Vec<(Tag, Vec<u8>)>> {
+        if self.positions.is_empty() || self.outputs.is_empty() {
+            return None;
+        }
+
+        let mut values = self.values.clone();
+        for (id, answer) in answers {
+            let (Some((index, offset)), Some(byte)) = (self.positions.get(id), answer) else {
+                continue;
+            };
+            values[*index].1[*offset] = *byte;
+        }
+        Some(values)
+    }
+}
@@ -1,27 +1,103 @@
 use delegate::delegate;
+use serde::{Deserialize, Serialize};
 
-use std::collections::HashMap;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+};
 
-use common::{log_info, types::trace::Constraint};
+use common::{log_info, log_warn, types::trace::Constraint};
 
-use leaf_runtime::abs::SymVariable;
+use leaf_runtime::{
+    abs::{SymVariable, SymVariableInfo, Tag, ValueType},
+    utils::RRef,
+};
 
 use super::alias::backend;
 use backend::{
     ConcreteValue, ConcreteValueRef, SymExValue, SymValue, SymValueRef, SymVarId,
-    SymVariablesManager, SymbolicVar, Value, expr::ConstValue,
+    SymVariablesManager, SymbolicVar, Value,
+    config::{OutputConfig, StableSymVarIdsConfig, SymVariablesConfig},
+    expr::ConstValue,
+    sym_sources,
 };
 
 pub(super) struct DefaultSymVariablesManager {
     variables: HashMap<SymVarId, (SymValueRef, ConcreteValueRef)>,
     conc_constraints: HashMap<SymVarId, Constraint<SymValueRef, ConstValue>>,
+    stable_ids: Option<StableIds>,
+    tags: RRef<Vec<Tag>>,
+    /// The metadata of the values being marked symbolic (innermost last),
+    /// along with the number of variables created for each so far.
+    infos: Vec<(SymVariableInfo, usize)>,
+    table: Vec<TableEntry>,
+    table_output: Option<OutputConfig>,
 }
 
 impl DefaultSymVariablesManager {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(config: &SymVariablesConfig, tags: RRef<Vec<Tag>>) -> Self {
         Self {
             variables: HashMap::new(),
             conc_constraints: HashMap::new(),
+            stable_ids: config.stable_ids.as_ref().map(StableIds::load),
+            tags,
+            infos: Vec::new(),
+            table: Vec::new(),
+            table_output: config.table.clone(),
+        }
+    }
+
+    pub(crate) fn push_info(&mut self, info: SymVariableInfo) {
+        self.infos.push((info, 0));
+    }
+
+    pub(crate) fn pop_info(&mut self) {
+        if self.infos.pop().is_none() {
+            log_warn!("Popping the symbolic variable metadata while none is pushed");
+        }
+    }
+
+    /// Writes the table of the symbolic variables created in the execution.
+    pub(crate) fn dump_table(&self) {
+        let Some(OutputConfig::File(file_config)) = self.table_output.as_ref() else {
+            return;
+        };
+        let result = file_config
+            .open_or_create_single_with_path(FILENAME_TABLE_DEFAULT, None, true)
+            .map_err(serde_json::Error::io)
+            .and_then(|(path, file)| serde_json::to_writer_pretty(file, &self.table).map(|_| path));
+        match result {
+            Ok(path) => log_info!("Symbolic variable table written to: {}", path.display()),
+            Err(err) => log_warn!("Could not write the symbolic variable table: {err}"),
+        }
+    }
+
+    fn record_in_table(&mut self, id: SymVarId, ty: &ValueType) {
+        let (info, index) = match self.infos.last_mut() {
+            Some((info, count)) => {
+                *count += 1;
+                (Some(*info), *count - 1)
+            }
+            None => (None, 0),
+        };
+        self.table.push(TableEntry {
+            id,
+            name: info.map(|i| i.name),
+            index,
+            ty: ty.to_string(),
+            type_name: info.map(|i| i.type_name),
+            location: info.map(|i| i.location.to_string()),
+        });
+    }
+
+    fn next_id(&mut self) -> SymVarId {
+        let index = self.len();
+        match self.stable_ids.as_mut() {
+            Some(stable_ids) => stable_ids.id_of(index),
+            None => index as SymVarId + 1,
         }
     }
 
@@ -32,12 +108,6 @@ impl DefaultSymVariablesManager {
     }
 }
 
-impl Default for DefaultSymVariablesManager {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 impl SymVariablesManager for DefaultSymVariablesManager {
     fn add_variable(&mut self, var: SymVariable<SymExValue>) -> SymValueRef {
         let conc_val = var
@@ -45,10 +115,15 @@ impl SymVariablesManager for DefaultSymVariablesManager {
             .expect("Concrete value of symbolic variables is required.");
 
         let Value::Concrete(ConcreteValue::Const(const_val)) = conc_val.value.as_ref() else {
-            panic!("Only constant values are currently expected to be used as the concrete value.");
+            crate::failure::fail!(
+                UnsupportedFeature,
+                "Only constant values are currently expected to be used as the concrete value."
+            );
         };
 
-        let id = self.len() as u32 + 1;
+        let id = self.next_id();
+        sym_sources::notify_variable(id, &self.tags.borrow());
+        self.record_in_table(id, &var.ty);
 
         let sym_val = SymValue::Variable(SymbolicVar::new(id, var.ty)).to_value_ref();
         let conc_val = ConcreteValueRef::new(conc_val.value.clone());
@@ -79,3 +154,285 @@ impl SymVariablesManager for DefaultSymVariablesManager {
         self.conc_constraints.iter()
     }
 }
+
+const FILENAME_TABLE_DEFAULT: &str = "sym_vars";
+
+/// An entry of the symbolic variable table, mapping the variables to the
+/// program values they are created for.
+#[derive(Serialize)]
+struct TableEntry {
+    id: SymVarId,
+    /// The name given to the value marked symbolic, if any.
+    name: Option<&'static str>,
+    /// The index of the variable among the ones created for the same named
+    /// value, e.g., the elements of an array.
+    index: usize,
+    ty: String,
+    /// The name of the declaring type of the named value.
+    type_name: Option<&'static str>,
+    location: Option<String>,
+}
+
+/// A portion of the input that a symbolic variable is created from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct InputPortion {
+    source: String,
+    offset: usize,
+    #[serde(default = "default_portion_length")]
+    length: usize,
+}
+
+fn default_portion_length() -> usize {
+    1
+}
+
+#[derive(Serialize, Deserialize)]
+struct RegistryEntry {
+    #[serde(flatten)]
+    portion: InputPortion,
+    id: SymVarId,
+}
+
+/// The source recorded for the variables not described in the input layout
+/// descriptor, with the offset being the index of the variable.
+const SOURCE_UNDESCRIBED: &str = "#undescribed";
+
+thread_local! {
+    /// The offsets of the input portions that the variables are created from.
+    static INPUT_OFFSETS: RefCell<Option<HashMap<SymVarId, usize>>> =
+        const { RefCell::new(None) };
+}
+
+/// Returns the offsets of the input portions that the variables are created
+/// from, if the ids are derived from an input layout descriptor.
+/// In that case, the ids no longer follow the positions in the input.
+pub(crate) fn input_offsets() -> Option<HashMap<SymVarId, usize>> {
+    INPUT_OFFSETS.with_borrow(|o| o.clone())
+}
+
+/// Assigns the ids of symbolic variables based on the input portions they
+/// are created from, using a registry shared across executions.
+struct StableIds {
+    /// The ids of the variables in the order of creation.
+    ids: Vec<SymVarId>,
+    registry: PathBuf,
+    /// The next id that is unused in the registry and the current execution,
+    /// used only if the registry cannot be updated.
+    next_fresh_id: SymVarId,
+}
+
+impl StableIds {
+    fn load(config: &StableSymVarIdsConfig) -> Self {
+        let portions: Vec<InputPortion> = read_json(&config.descriptor).unwrap_or_else(|| {
+            panic!(
+                "Could not read the input layout descriptor at {}",
+                config.descriptor.display()
+            )
+        });
+
+        let ids = update_registry(&config.registry, |registry| {
+            log_info!(
+                "Symbolic variable ids are assigned from a registry of {} input portions",
+                registry.len()
+            );
+            assign_ids(registry, portions.iter().cloned())
+        })
+        .unwrap_or_else(|err| {
+            log_warn!(
+                "Could not update the symbolic variable id registry at {}: {err}",
+                config.registry.display()
+            );
+            assign_ids(
+                &mut read_registry(&config.registry),
+                portions.iter().cloned(),
+            )
+        });
+
+        INPUT_OFFSETS.set(Some(
+            ids.iter()
+                .copied()
+                .zip(portions.iter().map(|p| p.offset))
+                .collect(),
+        ));
+
+        Self {
+            next_fresh_id: ids.iter().max().map_or(1, |id| id + 1),
+            ids,
+            registry: config.registry.clone(),
+        }
+    }
+
+    fn id_of(&mut self, index: usize) -> SymVarId {
+        if let Some(id) = self.ids.get(index) {
+            return *id;
+        }
+
+        log_warn!(
+            "No input portion is described for symbolic variable #{}. Registering it by its index.",
+            index
+        );
+        let portion = InputPortion {
+            source: SOURCE_UNDESCRIBED.to_owned(),
+            offset: index,
+            length: 1,
+        };
+        let id = update_registry(&self.registry, |registry| {
+            assign_ids(registry, [portion])[0]
+        })
+        .unwrap_or_else(|err| {
+            log_warn!("Could not register the symbolic variable #{}: {err}", index);
+            self.next_fresh_id
+        });
+        self.next_fresh_id = self.next_fresh_id.max(id + 1);
+        debug_assert_eq!(index, self.ids.len());
+        self.ids.push(id);
+        id
+    }
+}
+
+/// Returns the ids of the portions, assigning fresh ids to the ones not in the registry.
+fn assign_ids(
+    registry: &mut HashMap<InputPortion, SymVarId>,
+    portions: impl IntoIterator<Item = InputPortion>,
+) -> Vec<SymVarId> {
+    let mut next_fresh_id = registry.values().max().map_or(1, |id| id + 1);
+    portions
+        .into_iter()
+        .map(|portion| {
+            *registry.entry(portion).or_insert_with(|| {
+                next_fresh_id += 1;
+                next_fresh_id - 1
+            })
+        })
+        .collect()
+}
+
+fn read_registry(path: &Path) -> HashMap<InputPortion, SymVarId> {
+    read_json::<Vec<RegistryEntry>>(path)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|e| (e.portion, e.id))
+        .collect()
+}
+
+/// Applies the update to the registry and persists it.
+/// As the registry is shared by the executions, it is read and written while
+/// holding an exclusive lock, and replaced by a fully written temporary file,
+/// so that no assignment is lost and no partially written registry is read.
+fn update_registry<T>(
+    path: &Path,
+    update: impl FnOnce(&mut HashMap<InputPortion, SymVarId>) -> T,
+) -> io::Result<T> {
+    let lock = File::create(path.with_extension("lock"))?;
+    lock.lock()?;
+
+    let mut registry = read_registry(path);
+    let result = update(&mut registry);
+
+    let mut entries = registry
+        .into_iter()
+        .map(|(portion, id)| RegistryEntry { portion, id })
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|e| e.id);
+    let temp_path = path.with_extension("tmp");
+    File::create(&temp_path)
+        .and_then(|file| serde_json::to_writer_pretty(file, &entries).map_err(io::Error::from))?;
+    std::fs::rename(&temp_path, path)?;
+
+    // The lock is released when the file is closed.
+    drop(lock);
+    Ok(result)
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Option<T> {
+    let file = File::open(path).ok()?;
+    serde_json::from_reader(file)
+        .inspect_err(|err| log_warn!("Invalid JSON file at {}: {err}", path.display()))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn portion(source: &str, offset: usize) -> InputPortion {
+        InputPortion {
+            source: source.to_owned(),
+            offset,
+            length: 1,
+        }
+    }
+
+    fn temp_registry(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("leaf_sym_vars_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn fresh_portions_get_consecutive_ids() {
+        let mut registry = HashMap::new();
+        let ids = assign_ids(&mut registry, [portion("stdin", 0), portion("stdin", 1)]);
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn registered_portions_keep_their_ids() {
+        let mut registry = HashMap::new();
+        assign_ids(&mut registry, [portion("stdin", 0), portion("stdin", 1)]);
+        let ids = assign_ids(
+            &mut registry,
+            [portion("stdin", 1), portion("file", 0), portion("stdin", 0)],
+        );
+        assert_eq!(ids, vec![2, 3, 1]);
+    }
+
+    #[test]
+    fn portions_of_different_lengths_are_distinct() {
+        let mut registry = HashMap::new();
+        let long = InputPortion {
+            length: 4,
+            ..portion("stdin", 0)
+        };
+        let ids = assign_ids(&mut registry, [portion("stdin", 0), long]);
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn registry_is_shared_across_executions() {
+        let path = temp_registry("shared.json");
+        let first = update_registry(&path, |registry| {
+            assign_ids(registry, [portion("stdin", 4), portion("stdin", 8)])
+        })
+        .unwrap();
+        // Another execution reaching the portions in a different order.
+        let second = update_registry(&path, |registry| {
+            assign_ids(registry, [portion("stdin", 8), portion("stdin", 2)])
+        })
+        .unwrap();
+
+        assert_eq!(first, vec![1, 2]);
+        assert_eq!(second, vec![2, 3]);
+        assert_eq!(read_registry(&path).len(), 3);
+        assert!(!path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn undescribed_variables_are_registered_by_index() {
+        let path = temp_registry("undescribed.json");
+        let mut stable_ids = StableIds {
+            ids: vec![5],
+            registry: path.clone(),
+            next_fresh_id: 6,
+        };
+        assert_eq!(stable_ids.id_of(0), 5);
+        let id = stable_ids.id_of(1);
+        assert_eq!(
+            read_registry(&path).get(&portion(SOURCE_UNDESCRIBED, 1)),
+            Some(&id)
+        );
+        assert_eq!(stable_ids.id_of(1), id);
+    }
+}
@@ -0,0 +1,106 @@
+//! Records the events in which a symbolic place is replaced by its concrete value.
+//! As such events make the exploration of the affected paths incomplete, they are
+//! streamed (as JSON lines) so that the orchestrator can tell them apart.
+
+use std::cell::RefCell;
+
+use serde::Serialize;
+use serde_json::Serializer as JsonSerializer;
+
+use common::{log_debug, log_warn};
+
+use leaf_runtime::{
+    abs::BasicBlockLocation,
+    utils::{RRef, file::JsonLinesFormatter},
+};
+
+use super::{SymExExeTraceRecorder, backend};
+use backend::{
+    SymValueRef,
+    config::OutputConfig,
+    expr::{SymValue, SymVarId},
+};
+
+const FILENAME_DEFAULT: &str = "concretizations";
+
+/// The usage of the symbolic value in the place that gets concretized.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ConcretizationReason {
+    Deref,
+    Index,
+    Size,
+}
+
+#[derive(Serialize)]
+struct ConcretizationRecord {
+    location: Option<BasicBlockLocation>,
+    reason: ConcretizationReason,
+    /// Whether the symbolic value is constrained to be equal to the concrete one,
+    /// i.e., the path condition remains sound.
+    constrained: bool,
+    /// The id of the symbolic variable if the value is a variable itself.
+    var_id: Option<SymVarId>,
+    value: String,
+}
+
+struct ConcretizationRecorder {
+    serializer: JsonSerializer<std::fs::File, JsonLinesFormatter>,
+    trace_recorder: RRef<SymExExeTraceRecorder>,
+}
+
+thread_local! {
+    static RECORDER: RefCell<Option<ConcretizationRecorder>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn init_concretization_recording(
+    output: &OutputConfig,
+    trace_recorder: RRef<SymExExeTraceRecorder>,
+) {
+    let OutputConfig::File(file_config) = output;
+    let file = match file_config.open_or_create_single(FILENAME_DEFAULT, None, true) {
+        Ok(file) => file,
+        Err(e) => {
+            log_warn!("Could not create file for concretizations recording: {e}");
+            return;
+        }
+    };
+    log_debug!("Enabling concretizations recording");
+    RECORDER.with_borrow_mut(|r| {
+        *r = Some(ConcretizationRecorder {
+            serializer: JsonSerializer::with_formatter(file, JsonLinesFormatter::default()),
+            trace_recorder,
+        })
+    });
+}
+
+/// Records the concretization of the symbolic value if the recording is enabled.
+pub(crate) fn record_concretization(
+    reason: ConcretizationReason,
+    value: &SymValueRef,
+    constrained: bool,
+) {
+    RECORDER.with_borrow_mut(|r| {
+        let Some(recorder) = r.as_mut() else {
+            return;
+        };
+        let record = ConcretizationRecord {
+            // The recorder may be already borrowed if the concretization happens during call control.
+            location: recorder
+                .trace_recorder
+                .try_borrow()
+                .ok()
+                .and_then(|r| r.current_location()),
+            reason,
+            constrained,
+            var_id: match value.as_ref() {
+                SymValue::Variable(var) => Some(var.id),
+                SymValue::Expression(..) => None,
+            },
+            value: value.to_string(),
+        };
+        let _ = record
+            .serialize(&mut recorder.serializer)
+            .inspect_err(|e| log_warn!("Could not record concretization: {e}"));
+    });
+}
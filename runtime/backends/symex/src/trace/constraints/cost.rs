@@ -0,0 +1,217 @@
+//! A cost model for the execution paths, used to search for inputs that make
+//! the program do more work, e.g., to discover algorithmic complexity issues.
+//!
+//! The cost of a path is measured by the length of its trace and the number of
+//! loop iterations in it. At the end of the execution, an optimization query is
+//! built over the symbolic decisions taken in the loops, favoring the inputs
+//! that keep the loops iterating.
+
+use core::borrow::Borrow;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use serde::Serialize;
+
+use common::{log_debug, log_info, types::trace::Constraint};
+
+use leaf_runtime::{trace::StepInspector, utils::Indexed, utils::RRef};
+
+use super::{
+    CurrentSolver, CurrentSolverCase, CurrentSolverValue, IStep, OutputConfig, Step, backend,
+    divergence::add_missing_answers,
+    utils::dumping::{Dumper, create_ser_dumper},
+};
+use backend::{
+    SymVarId, SymVariablesManager, ValueRef,
+    config::{InputSchemaConfig, OutputHookConfig, ParentInputConfig},
+    outgen::DefaultOutputGenerator,
+};
+
+const FILENAME_COST_DEFAULT: &str = "cost";
+
+/// The cost of the current execution path.
+#[derive(Debug, Default, Clone, Serialize)]
+pub(super) struct PathCost {
+    /// The number of records in the execution trace, i.e., the transitions
+    /// between the basic blocks by branches, calls, and returns.
+    trace_length: usize,
+    /// The number of branch decisions taken.
+    branches: usize,
+    /// The number of branch decisions taken at a location decided before,
+    /// i.e., the iterations of the loops (and recursions).
+    loop_iterations: usize,
+}
+
+#[derive(Default)]
+pub(super) struct CostCounter {
+    cost: PathCost,
+    visited: HashSet<Step>,
+}
+
+impl<V, C> StepInspector<IStep, V, C> for CostCounter {
+    fn inspect(&mut self, step: &IStep, _constraint: Constraint<&V, &C>) {
+        self.cost.trace_length = step.value.index;
+        self.cost.branches += 1;
+        if !self.visited.insert(step.value.value) {
+            self.cost.loop_iterations += 1;
+        }
+    }
+}
+
+pub(super) fn create_cost_counter(
+    report_config: &Option<OutputConfig>,
+) -> (RRef<CostCounter>, Option<impl Dumper>) {
+    let counter_ref = Rc::new(RefCell::new(CostCounter::default()));
+    let dumper = report_config.as_ref().map(|cfg| {
+        let config = match cfg {
+            OutputConfig::File(cfg) => cfg,
+        };
+        let counter = counter_ref.clone();
+        create_ser_dumper!(
+            config,
+            "Path Cost".to_owned(),
+            FILENAME_COST_DEFAULT,
+            || { counter.as_ref().borrow().cost.clone() }
+        )
+    });
+    (counter_ref, dumper)
+}
+
+/// Collects the symbolic decisions of the path to search for an input with a higher cost.
+#[derive(Default)]
+pub(super) struct CostMaximizer {
+    steps: Vec<Indexed<Step>>,
+    constraints: Vec<Constraint<CurrentSolverValue, CurrentSolverCase>>,
+}
+
+impl<V, C> StepInspector<IStep, V, C> for CostMaximizer
+where
+    V: Borrow<CurrentSolverValue>,
+    C: Borrow<CurrentSolverCase>,
+{
+    fn inspect(&mut self, step: &IStep, constraint: Constraint<&V, &C>) {
+        self.steps.push(step.value);
+        self.constraints.push(constraint.map(
+            |v| Borrow::<CurrentSolverValue>::borrow(v).clone(),
+            |c| Borrow::<CurrentSolverCase>::borrow(c).clone(),
+        ));
+    }
+}
+
+/// A soft constraint in the maximization query, referring to a step in the path.
+struct SoftDecision {
+    index: usize,
+    negated: bool,
+    weight: u32,
+}
+
+impl CostMaximizer {
+    /// Builds the maximization query over the decisions taken in the loops.
+    /// # Returns
+    /// The number of the constraints at the beginning of the path that are kept
+    /// as they are, and the soft decisions.
+    /// # Remarks
+    /// The decisions taken at a location decided before are considered as loop conditions.
+    /// For each loop condition, the decisions that continued the loop are favored with
+    /// the cost of the iteration following them, and the last decision, supposedly
+    /// exiting the loop, is favored to be negated with the average iteration cost.
+    fn query(&self) -> Option<(usize, Vec<SoftDecision>)> {
+        let mut occurrences = HashMap::<Step, Vec<usize>>::new();
+        for (i, step) in self.steps.iter().enumerate() {
+            occurrences.entry(step.value).or_default().push(i);
+        }
+
+        let loops = occurrences
+            .into_values()
+            .filter(|indices| indices.len() > 1)
+            .collect::<Vec<_>>();
+        let prefix_len = loops.iter().map(|indices| indices[0]).min()?;
+
+        let trace_index = |i: usize| self.steps[i].index;
+        let soft = loops
+            .iter()
+            .flat_map(|indices| {
+                let (first, last) = (indices[0], *indices.last().unwrap());
+                let average_cost = (trace_index(last) - trace_index(first)) / (indices.len() - 1);
+                indices
+                    .iter()
+                    .zip(indices.iter().skip(1).map(Some).chain([None]))
+                    .map(move |(&index, next)| SoftDecision {
+                        index,
+                        negated: next.is_none(),
+                        weight: next
+                            .map_or(average_cost, |&next| trace_index(next) - trace_index(index))
+                            .clamp(1, u32::MAX as usize) as u32,
+                    })
+            })
+            .collect();
+        Some((prefix_len, soft))
+    }
+
+    fn find_answers(&self, solver: &CurrentSolver) -> Option<HashMap<SymVarId, ValueRef>> {
+        let Some((prefix_len, soft)) = self.query() else {
+            log_debug!("No loop condition found in the path, cost maximization is skipped.");
+            return None;
+        };
+        log_debug!(
+            "Maximizing the cost with {} soft decisions after {} steps",
+            soft.len(),
+            prefix_len,
+        );
+
+        let hard = self.constraints[..prefix_len].iter().cloned();
+        let soft = soft.into_iter().map(|d| {
+            let constraint = self.constraints[d.index].clone();
+            let constraint = if d.negated {
+                constraint.not()
+            } else {
+                constraint
+            };
+            (constraint, d.weight)
+        });
+        match solver.check_maximizing(hard, soft) {
+            (z3::SatResult::Sat, model) => Some(
+                model
+                    .into_iter()
+                    .map(|(id, ans)| (id, ValueRef::from(ans)))
+                    .collect(),
+            ),
+            _ => {
+                log_info!("Could not find an input maximizing the cost of the path.");
+                None
+            }
+        }
+    }
+}
+
+/// # Returns
+/// The inspector collecting the path and the action to search for the
+/// cost-maximizing input, expected to be run at the end of the execution.
+pub(super) fn create_cost_maximizer(
+    sym_var_manager: RRef<impl SymVariablesManager + 'static>,
+    solver: CurrentSolver,
+    output_config: &[OutputConfig],
+    parent_input: Option<&ParentInputConfig>,
+    output_hooks: &[OutputHookConfig],
+    input_schema: Option<&InputSchemaConfig>,
+) -> (RRef<CostMaximizer>, impl FnMut()) {
+    let maximizer_ref = Rc::new(RefCell::new(CostMaximizer::default()));
+    let mut output_generator =
+        DefaultOutputGenerator::new(output_config, parent_input, output_hooks, input_schema);
+
+    let maximizer = maximizer_ref.clone();
+    let action = move || {
+        let maximizer = core::mem::take(&mut *maximizer.borrow_mut());
+        let Some(mut model) = maximizer.find_answers(&solver) else {
+            return;
+        };
+        if output_generator.requires_all_answers() {
+            add_missing_answers(&mut model, &*RefCell::borrow(&sym_var_manager));
+        }
+        output_generator.generate(&model);
+    };
+    (maximizer_ref, action)
+}
@@ -0,0 +1,114 @@
+//! A fixed-size map of the branch hits published in shared memory (AFL-style).
+//!
+//! The map is available to the orchestrator right after the execution, so it
+//! can decide whether parsing the full trace and solving are worthwhile for the
+//! input without doing them first. Each decision is keyed by its location, the
+//! taken case, and the previous decision, and its hit count is kept in one byte
+//! of the map.
+
+use core::{
+    borrow::Borrow,
+    hash::{Hash, Hasher},
+};
+use std::hash::DefaultHasher;
+
+use common::{
+    log_info,
+    types::trace::{Constraint, ConstraintKind},
+};
+
+use leaf_runtime::trace::StepInspector;
+
+use super::{Step, backend};
+use backend::ConstValue;
+
+pub(super) fn create_coverage_map_inspector<S, V, C>(
+    shm_id_env: &str,
+    size: usize,
+) -> Option<impl StepInspector<S, V, C>>
+where
+    S: Borrow<Step>,
+    C: Borrow<ConstValue>,
+{
+    assert!(size > 0, "The coverage map is expected to be non-empty.");
+    let map = shm::attach_from_env(shm_id_env, size)?;
+    log_info!(
+        "Publishing the coverage map of size {} in shared memory",
+        size
+    );
+
+    let mut prev_key = 0;
+    let inspector = move |step: &S, constraint: Constraint<&V, &C>| {
+        let key = decision_key(step.borrow(), &constraint.kind);
+        let entry = &mut map[(key ^ prev_key) % size];
+        *entry = entry.saturating_add(1);
+        // Shifted to distinguish the direction of the edges, as in AFL.
+        prev_key = key >> 1;
+    };
+    Some(inspector)
+}
+
+fn decision_key<C: Borrow<ConstValue>>(step: &Step, kind: &ConstraintKind<&C>) -> usize {
+    fn case_key(case: &ConstValue) -> u128 {
+        match case {
+            ConstValue::Bool(value) => *value as u128,
+            ConstValue::Char(value) => *value as u128,
+            ConstValue::Int { bit_rep, .. } => bit_rep.0,
+            _ => 0,
+        }
+    }
+
+    // The default hasher is deterministic, so the keys are stable across executions.
+    let mut hasher = DefaultHasher::new();
+    step.hash(&mut hasher);
+    match kind {
+        ConstraintKind::True => 1u8.hash(&mut hasher),
+        ConstraintKind::False => 0u8.hash(&mut hasher),
+        ConstraintKind::OneOf(cases) => cases
+            .iter()
+            .for_each(|c| case_key((*c).borrow()).hash(&mut hasher)),
+        // The otherwise case is distinguished by the absence of the cases.
+        ConstraintKind::NoneOf(..) => (),
+    }
+    hasher.finish() as usize
+}
+
+mod shm {
+    use core::ffi::{c_int, c_void};
+
+    use common::log_warn;
+
+    extern "C" {
+        fn shmat(shmid: c_int, shmaddr: *const c_void, shmflg: c_int) -> *mut c_void;
+    }
+
+    /// Attaches to the shared memory segment whose identifier is in the given
+    /// environment variable.
+    /// # Remarks
+    /// The segment is expected to be created (and cleared) by the orchestrator,
+    /// with at least `size` bytes. It stays attached until the process exits.
+    pub(super) fn attach_from_env(env_var: &str, size: usize) -> Option<&'static mut [u8]> {
+        let Ok(id) = std::env::var(env_var) else {
+            log_warn!(
+                "The shared memory id for the coverage map is not set in {}",
+                env_var
+            );
+            return None;
+        };
+        let Ok(id) = id.parse::<c_int>() else {
+            log_warn!("Invalid shared memory id for the coverage map: {}", id);
+            return None;
+        };
+
+        let ptr = unsafe { shmat(id, core::ptr::null(), 0) };
+        if ptr as isize == -1 {
+            log_warn!(
+                "Could not attach to the shared memory for the coverage map: {}",
+                std::io::Error::last_os_error()
+            );
+            return None;
+        }
+
+        Some(unsafe { core::slice::from_raw_parts_mut(ptr as *mut u8, size) })
+    }
+}
@@ -14,57 +14,84 @@ use leaf_runtime::{
 };
 
 use super::{
-    CurrentSolver, CurrentSolverCase, CurrentSolverValue, Dumper, IStep, OutputConfig, Step,
+    CurrentSolverCase, CurrentSolverValue, DivergenceSolver, Dumper, IStep, OutputConfig, Step,
     backend,
+    prediction::PredictionRecorder,
+    unsat_core::UnsatCoreReporter,
     utils::dumping::{DumperListExt, create_ser_dumper, deserialize_snapshot},
 };
 use backend::{
-    ConstValue, SymVarId, SymVariablesManager, ValueRef, config::DivergenceFilterType,
-    outgen::DefaultOutputGenerator,
+    ConstValue, SymVarId, SymVariablesManager, ValueRef,
+    config::{
+        DivergenceFilterType, InputSchemaConfig, OutputHookConfig, ParentInputConfig, TagSelection,
+    },
+    outgen::{DefaultOutputGenerator, InfeasibilityInfo},
 };
 
 pub(super) fn create_imm_diverging_ans_finder<'ctx, V: 'ctx, C: 'ctx>(
     sym_var_manager: RRef<impl SymVariablesManager + 'static>,
-    solver: CurrentSolver,
+    solver: DivergenceSolver,
     check_optimistic: bool,
     filters_config: &Vec<DivergenceFilterType>,
+    predictions_config: Option<&OutputConfig>,
+    unsat_core_reporter: Option<RRef<UnsatCoreReporter>>,
     branch_depth_provider: Option<RRef<impl DepthProvider<Step, ConstValue> + 'ctx>>,
     output_config: &Vec<OutputConfig>,
+    parent_input: Option<&ParentInputConfig>,
+    output_hooks: &[OutputHookConfig],
+    input_schema: Option<&InputSchemaConfig>,
 ) -> (impl TraceInspector<IStep, V, C> + 'ctx, impl Dumper + 'ctx)
 where
     V: Borrow<CurrentSolverValue>,
     C: Borrow<CurrentSolverCase>,
     C: Borrow<ConstValue>,
 {
-    let mut output_generator = DefaultOutputGenerator::new(output_config);
-    let model_consumer = move |mut model: Model<SymVarId, ValueRef>| {
-        // Add missing answers.
-        // FIXME: Performance can be improved.
-        let all_sym_values = RefCell::borrow(&sym_var_manager);
-        let missing_answers = all_sym_values
-            .iter_variables()
-            .filter(|(id, _, _)| !model.contains_key(id))
-            .map(|(id, _, conc)| (*id, conc.clone().0))
-            .collect::<Vec<_>>();
-        model.extend(missing_answers);
-
-        output_generator.generate(&model)
+    let output_generator = Rc::new(RefCell::new(DefaultOutputGenerator::new(
+        output_config,
+        parent_input,
+        output_hooks,
+        input_schema,
+    )));
+    let infeasibility_observer = {
+        let output_generator = output_generator.clone();
+        move |steps, constraints| {
+            output_generator
+                .borrow_mut()
+                .mark_infeasible(&InfeasibilityInfo { steps, constraints })
+        }
+    };
+    let prediction_recorder = predictions_config.map(PredictionRecorder::new);
+    let model_consumer = {
+        let prediction_recorder = prediction_recorder.clone();
+        move |mut model: Model<SymVarId, ValueRef>| {
+            let mut output_generator = output_generator.borrow_mut();
+            // E.g., when patching the parent input, the missing answers are already in place.
+            if output_generator.requires_all_answers() {
+                add_missing_answers(&mut model, &*RefCell::borrow(&sym_var_manager));
+            }
+            output_generator.generate(&model);
+
+            if let Some(recorder) = prediction_recorder.as_ref() {
+                recorder.borrow_mut().record();
+            }
+        }
     };
 
     let mut filters: Vec<Box<dyn DivergenceFilter<IStep, V, C> + '_>> = vec![];
     let mut dumpers: Vec<Box<dyn Dumper>> = vec![];
 
     // This filter is builtin and not overridable.
-    filters.push(Box::new(DivergenceTagFilter::new(&[
-        common::pri::tags::NO_DIVERGE.to_owned(),
-    ])));
+    filters.push(Box::new(DivergenceTagFilter::new(&TagSelection {
+        include_any_of: vec![],
+        exclude_any_of: vec![common::pri::tags::NO_DIVERGE.to_owned()],
+    })));
 
     filters.extend(
         filters_config
             .iter()
             .map::<Box<dyn DivergenceFilter<_, _, _>>, _>(|f| match f {
-                DivergenceFilterType::Tags { exclude_any_of } => {
-                    Box::new(DivergenceTagFilter::new(&exclude_any_of))
+                DivergenceFilterType::Tags(selection) => {
+                    Box::new(DivergenceTagFilter::new(selection))
                 }
                 DivergenceFilterType::BranchDepthDistance {
                     distance_threshold_factor,
@@ -83,23 +110,51 @@ where
             }),
     );
 
-    let inspector = ImmediateDivergingAnswerFinder::new(
+    // Expected to be among the last filters.
+    if let Some(reporter) = unsat_core_reporter.clone() {
+        filters.push(Box::new(reporter));
+    }
+
+    // Expected to be the last filter.
+    if let Some(recorder) = prediction_recorder {
+        filters.push(Box::new(recorder));
+    }
+
+    let mut inspector = ImmediateDivergingAnswerFinder::new(
         solver.clone().map_answers(ValueRef::from),
         divergence_filter_all(filters),
         check_optimistic.then(|| solver.clone().map_answers(ValueRef::from)),
         Box::new(model_consumer),
-    );
+    )
+    .with_infeasibility_observer(Box::new(infeasibility_observer));
+    if let Some(reporter) = unsat_core_reporter {
+        inspector = inspector.with_unsat_observer(Box::new(move || reporter.borrow_mut().report()));
+    }
     (inspector, dumpers)
 }
 
+/// Adds the current values of the symbolic variables missing in the model.
+pub(super) fn add_missing_answers(
+    model: &mut Model<SymVarId, ValueRef>,
+    sym_var_manager: &impl SymVariablesManager,
+) {
+    // FIXME: Performance can be improved.
+    let missing_answers = sym_var_manager
+        .iter_variables()
+        .filter(|(id, _, _)| !model.contains_key(id))
+        .map(|(id, _, conc)| (*id, conc.clone().0))
+        .collect::<Vec<_>>();
+    model.extend(missing_answers);
+}
+
 struct DivergenceTagFilter {
-    exclude_with_any_of: Vec<String>,
+    selection: TagSelection,
 }
 
 impl DivergenceTagFilter {
-    fn new(exclude_with_any_of: &[String]) -> Self {
+    fn new(selection: &TagSelection) -> Self {
         Self {
-            exclude_with_any_of: exclude_with_any_of.to_vec(),
+            selection: selection.clone(),
         }
     }
 }
@@ -107,23 +162,23 @@ impl DivergenceTagFilter {
 impl<S: HasTags, V, C> DivergenceFilter<S, V, C> for DivergenceTagFilter {
     fn should_find(&mut self, trace: &[S], _constraints: &[Constraint<V, C>]) -> bool {
         let latest = trace.last().unwrap();
-        let exclude = self
-            .exclude_with_any_of
-            .iter()
-            .filter(|t| latest.has_tag(t))
-            .inspect(|t| {
-                log_debug!(
-                    "Filtering out step with tags {:?} by `{}` from divergence",
-                    latest.tags(),
-                    t,
-                )
-            })
-            .next()
-            .is_some();
-        !exclude
+        let selected = is_selected_by_tags(&self.selection, latest);
+        if !selected {
+            log_debug!(
+                "Filtering out step with tags {:?} from divergence",
+                latest.tags(),
+            );
+        }
+        selected
     }
 }
 
+pub(super) fn is_selected_by_tags(selection: &TagSelection, step: &impl HasTags) -> bool {
+    let included = selection.include_any_of.is_empty()
+        || selection.include_any_of.iter().any(|t| step.has_tag(t));
+    included && !selection.exclude_any_of.iter().any(|t| step.has_tag(t))
+}
+
 const FILENAME_SNAPSHOT_DEFAULT: &str = "branch_cov_depth";
 
 fn create_branch_depth_filter<'ctx, S: 'ctx, V: 'ctx, C: 'ctx>(
@@ -1,107 +1,182 @@
 use core::borrow::Borrow;
+use std::{cell::RefCell, rc::Rc};
+
+use serde::{Serialize, Serializer, ser::SerializeStruct};
+
+use common::log_warn;
 
 use leaf_runtime::{
     abs::Constraint,
     trace::{StepInspector, StreamDumperStepInspector},
-    utils::{HasIndex, Indexed, RRef, file::FileFormat},
+    utils::{HasIndex, Indexed, RRef, current_thread_index, file::FileFormat},
 };
 
+use super::super::stream::{FramedTraceWriter, serialize_threaded};
 use super::{CurrentSolverCase, CurrentSolverValue, OutputConfig, Step, backend};
 use backend::{Precondition, implication::PreconditionQuery};
 
+/// # Returns
+/// The inspector and the action flushing the dumped records, which should be
+/// performed at the end of the execution.
 pub(super) fn create_solver_constraints_dumper<'ctx, S, V, C>(
     config: &OutputConfig,
-) -> impl StepInspector<S, V, C>
+) -> (impl StepInspector<S, V, C>, impl FnMut())
 where
     S: Borrow<Step> + HasIndex,
     V: Borrow<CurrentSolverValue>,
     C: Borrow<CurrentSolverCase>,
 {
-    let mut dumper_inspector = match config {
-        OutputConfig::File(cfg) => {
-            assert!(
-                cfg.format().is_streamable(),
-                "Only streamable formats are expected for symbolic constraints dumping"
-            );
-            match cfg.format() {
-                FileFormat::JsonLines => {
-                    const FILENAME_DEFAULT: &str = "sym_decisions";
-                    let file = cfg
-                        .open_or_create_single(FILENAME_DEFAULT, None, true)
-                        .unwrap_or_else(|e| {
-                            panic!("Could not create file for symbolic constraints dumping: {e}")
-                        });
-                    StreamDumperStepInspector::json_lines(file)
-                }
-                format @ FileFormat::Text => {
-                    unimplemented!("Format is not supported for this dumper: {:?}", format);
-                }
-                FileFormat::Binary | FileFormat::Json => unreachable!(),
-            }
-        }
+    const FILENAME_DEFAULT: &str = "sym_decisions";
+    let (mut json_inspector, framed_writer) = match open_stream_file(config, FILENAME_DEFAULT) {
+        StreamFile::JsonLines(file) => (Some(StreamDumperStepInspector::json_lines(file)), None),
+        StreamFile::Binary(writer) => (None, Some(writer)),
     };
+    let flush_writer = framed_writer.clone();
 
     let inspector = move |step: &S, constraint: Constraint<&V, &C>| {
-        let step: Indexed<Step> = (step.borrow().clone(), step.index()).into();
+        let step = ThreadedStep::current((step.borrow().clone(), step.index()).into());
         let constraint =
             constraint.map(|v| v.borrow().serializable(), |c| c.borrow().serializable());
-        dumper_inspector.inspect(&step, constraint.as_ref());
+        if let Some(inspector) = json_inspector.as_mut() {
+            inspector.inspect(&step, constraint.as_ref());
+        }
+        if let Some(writer) = framed_writer.as_ref() {
+            writer
+                .borrow_mut()
+                .send(&DumpedRecord {
+                    step: &step,
+                    field: ("constraint", &constraint.as_ref()),
+                })
+                .unwrap_or_else(|e| panic!("Could not dump step: {e}"));
+        }
     };
-    inspector
+    (inspector, move || flush(flush_writer.as_ref()))
 }
 
+/// # Returns
+/// The inspector and the action flushing the dumped records, which should be
+/// performed at the end of the execution.
 pub(super) fn create_preconditions_dumper<'ctx, S, V, C>(
     config: &OutputConfig,
-) -> impl StepInspector<S, V, C>
+) -> (impl StepInspector<S, V, C>, impl FnMut())
 where
     S: Borrow<Step> + HasIndex,
     V: Borrow<Precondition>,
 {
-    let mut serializer = match config {
-        OutputConfig::File(cfg) => {
-            assert!(
-                cfg.format().is_streamable(),
-                "Only streamable formats are expected for symbolic constraints dumping"
-            );
-            match cfg.format() {
-                FileFormat::JsonLines => {
-                    const FILENAME_DEFAULT: &str = "discr_preconditions";
-                    let file = cfg
-                        .open_or_create_single(FILENAME_DEFAULT, None, true)
-                        .unwrap_or_else(|e| {
-                            panic!("Could not create file for symbolic constraints dumping: {e}")
-                        });
-                    serde_json::Serializer::with_formatter(
-                        file,
-                        leaf_runtime::utils::file::JsonLinesFormatter::default(),
-                    )
-                }
-                format @ FileFormat::Text => {
-                    unimplemented!("Format is not supported for this dumper: {:?}", format);
-                }
-                FileFormat::Binary | FileFormat::Json => unreachable!(),
-            }
-        }
+    const FILENAME_DEFAULT: &str = "discr_preconditions";
+    let (mut serializer, framed_writer) = match open_stream_file(config, FILENAME_DEFAULT) {
+        StreamFile::JsonLines(file) => (
+            Some(serde_json::Serializer::with_formatter(
+                file,
+                leaf_runtime::utils::file::JsonLinesFormatter::default(),
+            )),
+            None,
+        ),
+        StreamFile::Binary(writer) => (None, Some(writer)),
     };
+    let flush_writer = framed_writer.clone();
 
     let inspector = move |step: &S, constraint: Constraint<&V, &C>| {
-        let step: Indexed<Step> = (step.borrow().clone(), step.index()).into();
+        let step = ThreadedStep::current((step.borrow().clone(), step.index()).into());
         let preconditions = constraint.discr.borrow();
         if !preconditions.is_some() {
             return;
         }
 
-        use serde::{Serializer, ser::SerializeStruct};
-        serializer
-            .serialize_struct("Record", 2)
-            .and_then(|mut rec_ser| {
-                rec_ser.serialize_field(stringify!(step), &step)?;
-                rec_ser.serialize_field("preconditions", preconditions)?;
-                rec_ser.end()
+        let record = DumpedRecord {
+            step: &step,
+            field: ("preconditions", preconditions),
+        };
+        if let Some(serializer) = serializer.as_mut() {
+            record
+                .serialize(serializer)
+                .unwrap_or_else(|e| panic!("Could not dump step: {e}"));
+        }
+        if let Some(writer) = framed_writer.as_ref() {
+            writer
+                .borrow_mut()
+                .send(&record)
+                .unwrap_or_else(|e| panic!("Could not dump step: {e}"));
+        }
+    };
+    (inspector, move || flush(flush_writer.as_ref()))
+}
+
+/// The dumped form of the steps, along with the thread taking them.
+/// The step index orders the dumped records across threads.
+struct ThreadedStep {
+    step: Indexed<Step>,
+    thread: usize,
+}
+
+impl Serialize for ThreadedStep {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_threaded(&self.step, self.thread, serializer)
+    }
+}
+
+impl ThreadedStep {
+    /// The inspection happens on the thread taking the step.
+    fn current(step: Indexed<Step>) -> Self {
+        Self {
+            step,
+            thread: current_thread_index(),
+        }
+    }
+}
+
+enum StreamFile {
+    JsonLines(std::fs::File),
+    Binary(RRef<FramedTraceWriter>),
+}
+
+fn open_stream_file(config: &OutputConfig, default_filename: &str) -> StreamFile {
+    let OutputConfig::File(cfg) = config;
+    assert!(
+        cfg.format().is_streamable() || matches!(cfg.format(), FileFormat::Binary),
+        "Only streamable formats are expected for symbolic constraints dumping"
+    );
+    let open = || {
+        cfg.open_or_create_single(default_filename, None, true)
+            .unwrap_or_else(|e| {
+                panic!("Could not create file for symbolic constraints dumping: {e}")
             })
-            .unwrap_or_else(|e| panic!("Could not dump step: {e}"));
     };
-    inspector
+    match cfg.format() {
+        FileFormat::JsonLines => StreamFile::JsonLines(open()),
+        FileFormat::Binary => {
+            StreamFile::Binary(Rc::new(RefCell::new(FramedTraceWriter::to_file(open()))))
+        }
+        format @ FileFormat::Text => {
+            unimplemented!("Format is not supported for this dumper: {:?}", format);
+        }
+        FileFormat::Json => unreachable!(),
+    }
+}
+
+/// The dumped record of the step along with the named field.
+struct DumpedRecord<'a, T> {
+    step: &'a ThreadedStep,
+    field: (&'static str, &'a T),
+}
+
+impl<T: Serialize> Serialize for DumpedRecord<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (name, value) = self.field;
+        let mut rec_ser = serializer.serialize_struct("Record", 2)?;
+        rec_ser.serialize_field(stringify!(step), self.step)?;
+        rec_ser.serialize_field(name, value)?;
+        rec_ser.end()
+    }
+}
+
+fn flush(writer: Option<&RRef<FramedTraceWriter>>) {
+    if let Some(writer) = writer {
+        let _ = writer
+            .borrow_mut()
+            .flush()
+            .inspect_err(|e| log_warn!("Could not flush the dumped records: {e}"));
+    }
 }
 
 pub(super) fn create_step_index_in_memory_dumper<'ctx, S, V, C>(
@@ -0,0 +1,185 @@
+//! Counters of the execution for monitoring long campaigns.
+//!
+//! The counters are written along with the other dumps, i.e., periodically if
+//! a dump interval is set and at the end of the execution, either as a JSON
+//! object or in the Prometheus text exposition format.
+//! The per-function overheads are included if their recording is enabled, and
+//! the statistics of the expression interning are included at the end of the execution.
+
+use std::{
+    cell::RefCell,
+    fmt::{Display, Write as _},
+    io::Write as _,
+    rc::Rc,
+};
+
+use serde::Serialize;
+use z3::SatResult;
+
+use common::{types::trace::Constraint, z3::QueryObserver};
+
+use leaf_runtime::{
+    trace::StepInspector,
+    utils::{
+        RRef,
+        file::{FileFormat, FileGenConfig},
+    },
+};
+
+use super::{OutputConfig, backend, utils::dumping::Dumper};
+use backend::{
+    ConstValue, SymExValue,
+    diagnostics::{self, FuncOverheadEntry},
+    expr::builders::InterningStats,
+};
+
+const FILENAME_DEFAULT: &str = "metrics";
+const PROMETHEUS_PREFIX: &str = "leaf_";
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub(super) struct Metrics {
+    /// The number of decisions notified to the trace manager.
+    decisions: u64,
+    /// The number of decisions with symbolic discriminants.
+    symbolic_decisions: u64,
+    solver_queries_sat: u64,
+    solver_queries_unsat: u64,
+    solver_queries_unknown: u64,
+    /// The functions sorted by the time spent in the backend while executing them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    func_overheads: Option<Vec<FuncOverheadEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expr_interning: Option<InterningStats>,
+}
+
+impl Metrics {
+    fn to_prometheus(&self) -> String {
+        let mut text = String::new();
+        write_counter(
+            &mut text,
+            "decisions_total",
+            "The number of decisions taken.",
+            [("", self.decisions)],
+        );
+        write_counter(
+            &mut text,
+            "symbolic_decisions_total",
+            "The number of decisions with symbolic discriminants.",
+            [("", self.symbolic_decisions)],
+        );
+        write_counter(
+            &mut text,
+            "solver_queries_total",
+            "The number of queries checked by the solver per result.",
+            [
+                ("{result=\"sat\"}", self.solver_queries_sat),
+                ("{result=\"unsat\"}", self.solver_queries_unsat),
+                ("{result=\"unknown\"}", self.solver_queries_unknown),
+            ],
+        );
+        if let Some(entries) = self.func_overheads.as_ref() {
+            let label = |entry: &FuncOverheadEntry| format!("{{body=\"{}\"}}", entry.body_id);
+            write_counter(
+                &mut text,
+                "func_overhead_calls_total",
+                "The number of PRI calls attributed to each function.",
+                entries.iter().map(|e| (label(e), e.calls)),
+            );
+            write_counter(
+                &mut text,
+                "func_overhead_micros_total",
+                "The time spent in the PRI calls attributed to each function.",
+                entries.iter().map(|e| (label(e), e.total_micros)),
+            );
+        }
+        if let Some(stats) = self.expr_interning.as_ref() {
+            write_counter(
+                &mut text,
+                "expr_interning_total",
+                "The number of built symbolic expressions per interning result.",
+                [
+                    ("{result=\"hit\"}", stats.hits),
+                    ("{result=\"miss\"}", stats.misses),
+                ],
+            );
+            write_counter(
+                &mut text,
+                "expr_interning_saved_bytes_total",
+                "The approximate number of bytes saved by sharing the expressions.",
+                [("", stats.saved_bytes)],
+            );
+        }
+        text
+    }
+}
+
+fn write_counter(
+    text: &mut String,
+    name: &str,
+    help: &str,
+    samples: impl IntoIterator<Item = (impl Display, impl Display)>,
+) {
+    let name = format!("{PROMETHEUS_PREFIX}{name}");
+    let _ = writeln!(text, "# HELP {name} {help}");
+    let _ = writeln!(text, "# TYPE {name} counter");
+    for (labels, value) in samples {
+        let _ = writeln!(text, "{name}{labels} {value}");
+    }
+}
+
+/// # Returns
+/// The observer counting the solver queries, the inspector counting the decisions,
+/// and the dumper writing the metrics.
+pub(super) fn create_metrics_collector<S>(
+    config: &OutputConfig,
+) -> (
+    QueryObserver,
+    impl StepInspector<S, SymExValue, ConstValue>,
+    impl Dumper,
+) {
+    let metrics_ref: RRef<Metrics> = Rc::new(RefCell::new(Default::default()));
+
+    let observer: QueryObserver = {
+        let metrics_ref = metrics_ref.clone();
+        Rc::new(move |_, result| {
+            let mut metrics = metrics_ref.borrow_mut();
+            match result {
+                SatResult::Sat => metrics.solver_queries_sat += 1,
+                SatResult::Unsat => metrics.solver_queries_unsat += 1,
+                SatResult::Unknown => metrics.solver_queries_unknown += 1,
+            }
+        })
+    };
+
+    let inspector = {
+        let metrics_ref = metrics_ref.clone();
+        move |_: &S, constraint: Constraint<&SymExValue, &ConstValue>| {
+            let mut metrics = metrics_ref.borrow_mut();
+            metrics.decisions += 1;
+            if constraint.discr.is_symbolic() {
+                metrics.symbolic_decisions += 1;
+            }
+        }
+    };
+
+    let OutputConfig::File(file_config) = config;
+    let file_config = file_config.clone();
+    let dumper = move || {
+        let metrics = Metrics {
+            func_overheads: diagnostics::func_overheads(),
+            expr_interning: diagnostics::expr_interning(),
+            ..metrics_ref.borrow().clone()
+        };
+        write(&file_config, &metrics).map_err(|e| format!("Metrics: {e}"))
+    };
+
+    (observer, inspector, dumper)
+}
+
+fn write(config: &FileGenConfig, metrics: &Metrics) -> std::io::Result<()> {
+    let mut file = config.open_or_create_single(FILENAME_DEFAULT, None, true)?;
+    match config.format() {
+        FileFormat::Text => file.write_all(metrics.to_prometheus().as_bytes()),
+        _ => Ok(serde_json::to_writer_pretty(file, metrics)?),
+    }
+}
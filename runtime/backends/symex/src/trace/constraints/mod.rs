@@ -1,21 +1,31 @@
 mod branch_cov;
+mod cost;
+mod cov_map;
 mod divergence;
 mod dumpers;
+mod metrics;
+mod prediction;
 mod sanity_check;
+mod simplify;
+mod unsat_core;
 mod utils;
+mod violation;
 
 use std::{cell::RefCell, rc::Rc};
 
-use common::types::trace::Constraint;
+use common::{types::trace::Constraint, z3::ValuePreferences};
 use delegate::delegate;
 
 use leaf_runtime::{
     abs::{
         Tag,
-        backend::{Shutdown, Solver, TraceManager as AbsTraceManager},
+        backend::{Shutdown, SolveResult, Solver, TraceManager as AbsTraceManager},
         utils::Tagged,
     },
-    solvers::z3::Z3Solver,
+    pri::events::differential::ConstraintObserver,
+    solvers::{
+        portfolio::PortfolioSolver, process::ProcessSolver, smtlib::QueryDumper, z3::Z3Solver,
+    },
     trace::{
         AdapterTraceManagerExt, AggregatorStepInspector, AggregatorTraceManager,
         FilterTraceManagerExt, InspectionTraceManagerExt, LoggerTraceManagerExt, StepInspector,
@@ -29,7 +39,10 @@ use backend::{
     TraceViewProvider, ValueRef,
     alias::{DynDecisionTraceRecorder, TraceManagerWithViews},
     config::ConstraintFilterType,
-    config::{ExecutionTraceConfig, OutputConfig, SolverImpl, TraceInspectorType},
+    config::{
+        ExecutionTraceConfig, InputSchemaConfig, OutputConfig, OutputHookConfig, ParentInputConfig,
+        SolverImpl, TraceInspectorType,
+    },
     expr::translators::z3::Z3ValueTranslator,
     implication::PreconditionQuery,
 };
@@ -46,6 +59,44 @@ type CurrentSolverValue = <CurrentSolver as Solver>::Value;
 type CurrentSolverCase = <CurrentSolver as Solver>::Case;
 type CurrentSolverTranslator = Z3ValueTranslator;
 
+/// The solver for finding the diverging inputs, which can be replaced
+/// as it does not need the features specific to the current solver.
+#[derive(Clone)]
+enum DivergenceSolver {
+    Current(CurrentSolver),
+    Process(ProcessSolver<SymVarId>),
+    Portfolio(PortfolioSolver<SymVarId>),
+}
+
+impl Solver for DivergenceSolver {
+    type Value = CurrentSolverValue;
+    type Case = CurrentSolverCase;
+    type Model = <CurrentSolver as Solver>::Model;
+
+    fn check(
+        &mut self,
+        constraints: impl Iterator<Item = Constraint<Self::Value, Self::Case>>,
+    ) -> SolveResult<Self::Model> {
+        match self {
+            Self::Current(solver) => Solver::check(solver, constraints),
+            Self::Process(solver) => Solver::check(solver, constraints),
+            Self::Portfolio(solver) => Solver::check(solver, constraints),
+        }
+    }
+
+    fn check_assuming(
+        &mut self,
+        constraints: impl Iterator<Item = Constraint<Self::Value, Self::Case>>,
+        assumption: Constraint<Self::Value, Self::Case>,
+    ) -> (SolveResult<Self::Model>, bool) {
+        match self {
+            Self::Current(solver) => Solver::check_assuming(solver, constraints, assumption),
+            Self::Process(solver) => Solver::check_assuming(solver, constraints, assumption),
+            Self::Portfolio(solver) => Solver::check_assuming(solver, constraints, assumption),
+        }
+    }
+}
+
 // These are the types of steps, values, and cases for the inner managers.
 // You can use them to give explicit types as opposed to generics.
 type IStep = Tagged<Indexed<Step>>;
@@ -110,36 +161,120 @@ pub(crate) fn create_trace_manager(
     sym_var_manager: RRef<impl SymVariablesManager + 'static>,
     trace_config: &ExecutionTraceConfig,
     output_config: &Vec<OutputConfig>,
+    parent_input: Option<&ParentInputConfig>,
+    output_hooks: &[OutputHookConfig],
+    input_schema: Option<&InputSchemaConfig>,
     solver_config: &SolverImpl,
 ) -> impl TraceManagerWithViews {
     // NOTE: It's very tricky to break this function down because of complicated borrows.
-    let (solver, translator) = match solver_config {
-        SolverImpl::Z3 { config } => {
-            leaf_runtime::solvers::z3::set_global_params(
-                config.global_params.iter().map(|(k, v)| (k, v.to_string())),
-            );
-            let solver: CurrentSolver = Z3Solver::<SymVarId>::new_in_global_context();
-            let translator = Z3ValueTranslator::new();
-            (solver, translator)
+    let (config, divergence_solver) = match solver_config {
+        SolverImpl::Z3 { config } => (config, None),
+        SolverImpl::Process { program, args, z3 } => (
+            z3,
+            Some(DivergenceSolver::Process(ProcessSolver::new(
+                program.clone(),
+                args.clone(),
+            ))),
+        ),
+        SolverImpl::Portfolio {
+            workers,
+            tactics,
+            config,
+        } => (
+            config,
+            Some(DivergenceSolver::Portfolio(PortfolioSolver::new(
+                *workers,
+                tactics.clone(),
+            ))),
+        ),
+    };
+    let (metrics_observer, metrics_inspector, metrics_dumper) = match &trace_config.metrics {
+        Some(metrics_config) => {
+            let (observer, inspector, dumper) = metrics::create_metrics_collector(metrics_config);
+            (Some(observer), Some(inspector), Some(dumper))
+        }
+        None => (None, None, None),
+    };
+
+    let (solver, translator) = {
+        leaf_runtime::solvers::z3::set_global_params(
+            config
+                .global_params
+                .iter()
+                .map(|(k, v)| (k.clone(), v.to_string()))
+                .chain(
+                    config
+                        .timeout_ms
+                        .map(|t| ("timeout".to_owned(), t.to_string())),
+                )
+                .chain(
+                    config
+                        .memory_limit_mb
+                        .map(|m| ("memory_max_size".to_owned(), m.to_string())),
+                ),
+        );
+        let mut solver: CurrentSolver = Z3Solver::<SymVarId>::new_in_global_context()
+            .with_preferences(ValuePreferences {
+                small_magnitude: config.preferences.small_magnitude,
+                printable_bytes: config.preferences.printable_bytes,
+            });
+        let mut query_observers = metrics_observer.into_iter().collect::<Vec<_>>();
+        if let Some(OutputConfig::File(dump_config)) = config.queries_dump.as_ref() {
+            query_observers.push(QueryDumper::new(dump_config.clone()).into_observer());
+        }
+        if !query_observers.is_empty() {
+            solver = solver.with_query_observer(Rc::new(move |constraints, result| {
+                query_observers
+                    .iter()
+                    .for_each(|observer| observer(constraints, result))
+            }));
         }
+        let translator = Z3ValueTranslator::new(
+            config.translation.select_ite_max_size,
+            config.translation.node_budget,
+        )
+        .with_seeds({
+            let sym_var_manager = sym_var_manager.clone();
+            Rc::new(move |id| {
+                sym_var_manager
+                    .borrow()
+                    .iter_variables()
+                    .find(|(var_id, ..)| **var_id == id)
+                    .and_then(|(_, _, conc_val)| conc_val.as_const().cloned())
+            })
+        });
+        (solver, translator)
     };
+    let divergence_solver =
+        divergence_solver.unwrap_or_else(|| DivergenceSolver::Current(solver.clone()));
     let sym_var_manager_ref = sym_var_manager;
 
     let mut dumpers: Vec<Box<dyn Dumper>> = vec![];
+    dumpers.extend_opt(metrics_dumper);
 
     let mut cov_inspector = None;
     let sym_discr_inspectors = trace_config
         .inspectors
         .iter()
         .filter(|t| !is_inner_inspector(t))
-        .map(|t| match t {
+        .filter_map(|t| match t {
             TraceInspectorType::BranchCoverage { output, .. } => {
                 let (inspector, dumper) =
                     branch_cov::create_branch_coverage_collector::<ValueRef>(output);
                 cov_inspector = Some(inspector.clone());
                 dumpers.extend_opt(dumper);
-                Box::new(inspector) as Box<dyn StepInspector<_, _, _>>
+                Some(Box::new(inspector) as Box<dyn StepInspector<_, _, _>>)
+            }
+            TraceInspectorType::CostMaximizer { report, .. } => {
+                let (inspector, dumper) = cost::create_cost_counter(report);
+                dumpers.extend_opt(dumper);
+                Some(Box::new(inspector))
             }
+            // Not inspecting the symbolic decisions.
+            TraceInspectorType::PredictionCheck { .. }
+            | TraceInspectorType::CoverageMap { .. }
+            | TraceInspectorType::UbChecker { .. }
+            | TraceInspectorType::PanicFinder { .. } => None,
             _ => unreachable!(),
         })
         .collect::<Vec<_>>();
@@ -161,14 +296,23 @@ pub(crate) fn create_trace_manager(
             TraceInspectorType::DivergingInput {
                 check_optimistic,
                 filters,
+                predictions,
+                unsat_cores,
             } => {
                 let (inspector, dumper) = divergence::create_imm_diverging_ans_finder(
                     sym_var_manager_ref.clone(),
-                    solver.clone(),
+                    divergence_solver.clone(),
                     *check_optimistic,
                     filters,
+                    predictions.as_ref(),
+                    unsat_cores
+                        .as_ref()
+                        .map(|cfg| unsat_core::UnsatCoreReporter::new(cfg, solver.clone())),
                     cov_inspector.clone(),
                     output_config,
+                    parent_input,
+                    output_hooks,
+                    input_schema,
                 );
                 dumpers.push(Box::new(dumper));
                 Box::new(inspector)
@@ -191,15 +335,70 @@ pub(crate) fn create_trace_manager(
                     solver.clone(),
                 ))
             }
+            ConstraintFilterType::Tags(selection) => {
+                let selection = selection.clone();
+                type_check_inner_filter(move |step, _| {
+                    divergence::is_selected_by_tags(&selection, step)
+                })
+            }
         })
         .collect::<Vec<_>>();
 
+    let mut shutdown_actions: Vec<Box<dyn FnMut()>> = vec![];
+
     let inner_step_inspectors = trace_config
         .constraints_dump
         .as_ref()
-        .map(|cfg| dumpers::create_solver_constraints_dumper(cfg))
-        .map(|inspector| Box::new(inspector) as Box<dyn StepInspector<_, _, _>>)
+        .map(|cfg| {
+            let (inspector, action) = dumpers::create_solver_constraints_dumper(cfg);
+            shutdown_actions.push(Box::new(action));
+            Box::new(inspector) as Box<dyn StepInspector<_, _, _>>
+        })
         .into_iter()
+        .chain(trace_config.inspectors.iter().filter_map(|t| match t {
+            TraceInspectorType::CostMaximizer { outputs, .. } => {
+                let (inspector, action) = cost::create_cost_maximizer(
+                    sym_var_manager_ref.clone(),
+                    solver.clone(),
+                    outputs,
+                    parent_input,
+                    output_hooks,
+                    input_schema,
+                );
+                shutdown_actions.push(Box::new(action));
+                Some(Box::new(inspector) as Box<dyn StepInspector<_, _, _>>)
+            }
+            TraceInspectorType::PredictionCheck { prediction, report } => {
+                let (inspector, action, dumper) =
+                    prediction::create_prediction_checker(prediction, report);
+                shutdown_actions.push(Box::new(action));
+                dumpers.extend_opt(dumper);
+                Some(Box::new(inspector))
+            }
+            TraceInspectorType::UbChecker { outputs, report } => {
+                Some(Box::new(violation::create_ub_checker(
+                    sym_var_manager_ref.clone(),
+                    solver.clone(),
+                    outputs,
+                    report.as_ref(),
+                    parent_input,
+                    output_hooks,
+                    input_schema,
+                )))
+            }
+            TraceInspectorType::PanicFinder { outputs, report } => {
+                Some(Box::new(violation::create_panic_finder(
+                    sym_var_manager_ref.clone(),
+                    solver.clone(),
+                    outputs,
+                    report.as_ref(),
+                    parent_input,
+                    output_hooks,
+                    input_schema,
+                )))
+            }
+            _ => None,
+        }))
         .collect::<Vec<_>>();
 
     let inner_manager = type_check_inner_manager(
@@ -210,18 +409,44 @@ pub(crate) fn create_trace_manager(
 
     let mut value_translator = translator.clone();
     let mut case_translator = translator.clone();
-    let core_manager = inner_manager.adapt(
-        |s| s,
-        move |v: ValueRef| Translation::of(v, &mut value_translator),
-        move |c: ConstValue| Translation::of(c, &mut case_translator),
-    );
+    let core_manager = inner_manager
+        .adapt(
+            |s| s,
+            move |v: ValueRef| Translation::of(v, &mut value_translator),
+            move |c: ConstValue| Translation::of(c, &mut case_translator),
+        )
+        .filtered_by(simplify::create_trivial_constraint_filter());
 
     let all_constraints_inspectors = trace_config
         .preconditions_dump
         .as_ref()
-        .map(|cfg| dumpers::create_preconditions_dumper(cfg))
-        .map(|inspector| Box::new(inspector) as Box<dyn StepInspector<_, _, _>>)
+        .map(|cfg| {
+            let (inspector, action) = dumpers::create_preconditions_dumper(cfg);
+            shutdown_actions.push(Box::new(action));
+            Box::new(inspector) as Box<dyn StepInspector<_, _, _>>
+        })
         .into_iter()
+        .chain(trace_config.inspectors.iter().filter_map(|t| {
+            match t {
+                TraceInspectorType::CoverageMap { shm_id_env, size } => {
+                    cov_map::create_coverage_map_inspector(shm_id_env, *size)
+                        .map(|inspector| Box::new(inspector) as Box<dyn StepInspector<_, _, _>>)
+                }
+                _ => None,
+            }
+        }))
+        .chain(
+            metrics_inspector
+                .map(|inspector| Box::new(inspector) as Box<dyn StepInspector<_, _, _>>),
+        )
+        .chain({
+            #[cfg(feature = "c_api")]
+            let inspector = backend::capi::create_callback_inspector()
+                .map(|inspector| Box::new(inspector) as Box<dyn StepInspector<_, _, _>>);
+            #[cfg(not(feature = "c_api"))]
+            let inspector = None;
+            inspector
+        })
         .collect::<Vec<_>>();
 
     let outer_agg_inspector = AggregatorStepInspector::default();
@@ -249,6 +474,7 @@ pub(crate) fn create_trace_manager(
             c.discr.by.is_some() || c.discr.is_symbolic()
         })
         .inspected_by(outer_agg_inspector)
+        .inspected_by(ConstraintObserver)
         .inspected_by(all_constraints_inspectors)
         .inspected_by(utils::dumping::create_timer_dumper_inspector(
             dumpers_ref.clone(),
@@ -256,7 +482,10 @@ pub(crate) fn create_trace_manager(
                 .dump_interval
                 .map(|i| core::time::Duration::from_secs(i.into())),
         ))
-        .on_shutdown(move || dump(&dumpers_ref));
+        .on_shutdown(move || {
+            shutdown_actions.iter_mut().for_each(|action| action());
+            dump(&dumpers_ref)
+        });
 
     SymExTraceManager {
         inner: manager,
@@ -271,7 +500,12 @@ fn is_inner_inspector(t: &TraceInspectorType) -> bool {
     use TraceInspectorType::*;
     match t {
         SanityChecker { .. } | DivergingInput { .. } => true,
-        BranchCoverage { .. } => false,
+        BranchCoverage { .. }
+        | CostMaximizer { .. }
+        | PredictionCheck { .. }
+        | CoverageMap { .. }
+        | UbChecker { .. }
+        | PanicFinder { .. } => false,
     }
 }
 
@@ -0,0 +1,266 @@
+//! Predictions of the paths that the generated inputs are expected to take, and
+//! their verification in the executions of those inputs.
+//!
+//! For each generated input, the symbolic decisions of the path up to the
+//! negated one are recorded. Given such a prediction, the execution of the
+//! input checks whether it actually took the predicted branch at the expected
+//! position and reports the first mispredicted decision otherwise.
+//!
+//! # Remarks
+//! The runtime does not re-execute the generated inputs itself, as how an input
+//! is fed to the program (e.g., as a file, through the standard input, or as
+//! the arguments) is only known to the driver of the executions. Thus, the
+//! driver is expected to run each generated input with the `prediction_check`
+//! inspector pointing to its prediction.
+
+use core::borrow::Borrow;
+use std::{cell::RefCell, path::Path, rc::Rc};
+
+use serde::{Deserialize, Serialize};
+
+use common::{
+    log_info, log_warn,
+    types::trace::{Constraint, ConstraintKind},
+};
+
+use leaf_runtime::{
+    trace::{DivergenceFilter, StepInspector},
+    utils::{HasIndex, RRef, file::FileGenConfig},
+};
+
+use super::{
+    OutputConfig, Step, backend,
+    utils::dumping::{Dumper, create_ser_dumper},
+};
+use backend::ConstValue;
+
+const FILENAME_PREDICTION_PREFIX: &str = "prediction_";
+const FILENAME_PREDICTION_CHECK_DEFAULT: &str = "prediction_check";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) struct Decision {
+    step: Step,
+    /// The index of the step in the trace of the execution it is taken in.
+    index: usize,
+    /// The decision with the cases in their textual representation.
+    kind: ConstraintKind<String>,
+}
+
+impl Decision {
+    pub(super) fn new<S, V, C>(step: &S, constraint: Constraint<&V, &C>) -> Self
+    where
+        S: Borrow<Step> + HasIndex,
+        C: Borrow<ConstValue>,
+    {
+        Self {
+            step: Borrow::<Step>::borrow(step).clone(),
+            index: step.index(),
+            kind: constraint
+                .kind
+                .map(|c| Borrow::<ConstValue>::borrow(c).to_string()),
+        }
+    }
+
+    fn is_compatible_with(&self, other: &Self) -> bool {
+        self.step == other.step
+            && match (&self.kind, &other.kind) {
+                (ConstraintKind::True | ConstraintKind::False, _)
+                | (_, ConstraintKind::True | ConstraintKind::False) => self.kind == other.kind,
+                // The set of cases is unknown, thus they are assumed to be unbounded.
+                _ => self.kind.and(&other.kind, || usize::MAX).is_some(),
+            }
+    }
+}
+
+/// The symbolic decisions that the execution of a generated input is expected to take.
+/// The last one is the negation of the decision taken in the parent execution.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(super) struct Prediction {
+    decisions: Vec<Decision>,
+}
+
+/// Records the predictions for the generated diverging inputs.
+/// # Remarks
+/// It captures the path as a divergence filter, thus it is expected to be the
+/// last filter so that it is only reached when the search is not filtered out.
+/// The predictions are numbered in the order of the generated inputs, i.e.,
+/// `prediction_<n>` corresponds to the `n`th output of this execution.
+/// For the inputs found optimistically (i.e., without the path prefix), the
+/// prefix of the prediction is not guaranteed to hold.
+pub(super) struct PredictionRecorder {
+    config: FileGenConfig,
+    pending: Option<Prediction>,
+    counter: usize,
+}
+
+impl PredictionRecorder {
+    pub(super) fn new(config: &OutputConfig) -> RRef<Self> {
+        let config = match config {
+            OutputConfig::File(cfg) => cfg.clone(),
+        };
+        Rc::new(RefCell::new(Self {
+            config,
+            pending: None,
+            counter: 0,
+        }))
+    }
+
+    /// Writes the prediction for the input that has just been generated.
+    pub(super) fn record(&mut self) {
+        let Some(prediction) = self.pending.as_ref() else {
+            return;
+        };
+
+        let result = self
+            .config
+            .open_or_create_single(
+                FILENAME_PREDICTION_PREFIX,
+                Some(self.counter.to_string()),
+                true,
+            )
+            .map_err(|e| e.to_string())
+            .and_then(|file| serde_json::to_writer(file, prediction).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            log_warn!(
+                "Could not write the prediction for output {}: {e}",
+                self.counter
+            );
+        }
+        self.counter += 1;
+    }
+}
+
+impl<S, V, C> DivergenceFilter<S, V, C> for PredictionRecorder
+where
+    S: Borrow<Step> + HasIndex,
+    C: Borrow<ConstValue>,
+{
+    fn should_find(&mut self, trace: &[S], constraints: &[Constraint<V, C>]) -> bool {
+        let last = constraints.len() - 1;
+        let decisions = trace
+            .iter()
+            .zip(constraints.iter())
+            .enumerate()
+            .map(|(i, (step, constraint))| {
+                let constraint = constraint.as_ref();
+                let constraint = if i == last {
+                    constraint.not()
+                } else {
+                    constraint
+                };
+                Decision::new(step, constraint)
+            })
+            .collect();
+        self.pending = Some(Prediction { decisions });
+        true
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub(super) enum PredictionCheckResult {
+    /// All the predicted decisions were taken.
+    Verified,
+    /// A decision different from the predicted one was taken.
+    Mispredicted {
+        /// The position of the decision among the symbolic decisions.
+        position: usize,
+        /// Whether the misprediction is at the negated decision rather than in the prefix.
+        at_negated: bool,
+        expected: Decision,
+        actual: Decision,
+    },
+    /// The execution ended before reaching the predicted decisions.
+    Incomplete { taken: usize, expected: usize },
+}
+
+/// Checks the symbolic decisions of the current execution against the prediction.
+pub(super) struct PredictionChecker {
+    prediction: Prediction,
+    position: usize,
+    misprediction: Option<PredictionCheckResult>,
+}
+
+impl PredictionChecker {
+    fn result(&self) -> PredictionCheckResult {
+        if let Some(misprediction) = self.misprediction.as_ref() {
+            misprediction.clone()
+        } else if self.position < self.prediction.decisions.len() {
+            PredictionCheckResult::Incomplete {
+                taken: self.position,
+                expected: self.prediction.decisions.len(),
+            }
+        } else {
+            PredictionCheckResult::Verified
+        }
+    }
+}
+
+impl<S, V, C> StepInspector<S, V, C> for PredictionChecker
+where
+    S: Borrow<Step> + HasIndex,
+    C: Borrow<ConstValue>,
+{
+    fn inspect(&mut self, step: &S, constraint: Constraint<&V, &C>) {
+        if self.misprediction.is_some() {
+            return;
+        }
+        let Some(expected) = self.prediction.decisions.get(self.position) else {
+            return;
+        };
+
+        let actual = Decision::new(step, constraint);
+        if !expected.is_compatible_with(&actual) {
+            self.misprediction = Some(PredictionCheckResult::Mispredicted {
+                position: self.position,
+                at_negated: self.position == self.prediction.decisions.len() - 1,
+                expected: expected.clone(),
+                actual,
+            });
+        }
+        self.position += 1;
+    }
+}
+
+/// # Returns
+/// The checker, the action to report the result at the end of the execution,
+/// and the dumper for the result if requested.
+pub(super) fn create_prediction_checker(
+    prediction_path: &Path,
+    report_config: &Option<OutputConfig>,
+) -> (RRef<PredictionChecker>, impl FnMut(), Option<impl Dumper>) {
+    let prediction = std::fs::File::open(prediction_path)
+        .map_err(|e| e.to_string())
+        .and_then(|file| serde_json::from_reader(file).map_err(|e| e.to_string()))
+        .unwrap_or_else(|e| {
+            panic!(
+                "Could not load the prediction from {}: {e}",
+                prediction_path.display()
+            )
+        });
+    let checker_ref = Rc::new(RefCell::new(PredictionChecker {
+        prediction,
+        position: 0,
+        misprediction: None,
+    }));
+
+    let checker = checker_ref.clone();
+    let action = move || match checker.as_ref().borrow().result() {
+        PredictionCheckResult::Verified => log_info!("The predicted path is taken."),
+        result => log_warn!("The predicted path is not taken: {:#?}", result),
+    };
+
+    let dumper = report_config.as_ref().map(|cfg| {
+        let config = match cfg {
+            OutputConfig::File(cfg) => cfg,
+        };
+        let checker = checker_ref.clone();
+        create_ser_dumper!(
+            config,
+            "Prediction Check".to_owned(),
+            FILENAME_PREDICTION_CHECK_DEFAULT,
+            || { checker.as_ref().borrow().result() }
+        )
+    });
+    (checker_ref, action, dumper)
+}
@@ -0,0 +1,39 @@
+use common::{
+    log_debug, log_warn,
+    types::trace::{Constraint, ConstraintKind},
+};
+
+use super::{IStep, backend};
+use backend::{ConstValue, ValueRef, expr::fold::try_fold};
+
+/// Creates a filter that discharges the constraints whose discriminants evaluate to
+/// a constant regardless of the symbolic values, so that they are not translated and
+/// submitted to the solver.
+/// # Remarks
+/// As the execution has taken the branch, such a constraint is expected to be always
+/// satisfied. The negations of these constraints are trivially unsatisfiable, thus no
+/// diverging input is missed by dropping them.
+pub(super) fn create_trivial_constraint_filter()
+-> impl FnMut(&IStep, Constraint<&ValueRef, &ConstValue>) -> bool {
+    |step, constraint| {
+        let Some(value) = constraint.discr.as_sym().and_then(try_fold) else {
+            return true;
+        };
+
+        let holds = match &constraint.kind {
+            ConstraintKind::True => value == ConstValue::Bool(true),
+            ConstraintKind::False => value == ConstValue::Bool(false),
+            ConstraintKind::OneOf(cases) => cases.iter().any(|c| **c == value),
+            ConstraintKind::NoneOf(cases) => cases.iter().all(|c| **c != value),
+        };
+        if holds {
+            log_debug!("Discharged the trivially satisfied constraint at step {step}");
+            false
+        } else {
+            log_warn!(
+                "The constraint at step {step} is never satisfied, its discriminant always evaluates to {value:?}"
+            );
+            true
+        }
+    }
+}
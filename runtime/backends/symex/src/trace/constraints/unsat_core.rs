@@ -0,0 +1,129 @@
+//! Reports of the unsatisfiable cores for the decisions whose negations are found
+//! unsatisfiable, i.e., the earlier decisions that block reaching the other branches.
+
+use core::borrow::Borrow;
+use std::{cell::RefCell, fs, io::Write, rc::Rc};
+
+use serde::Serialize;
+use z3::SatResult;
+
+use common::{log_debug, log_warn, types::trace::Constraint};
+
+use leaf_runtime::{
+    trace::DivergenceFilter,
+    utils::{HasIndex, RRef},
+};
+
+use super::{
+    CurrentSolver, CurrentSolverCase, CurrentSolverValue, OutputConfig, Step, backend,
+    prediction::Decision,
+};
+use backend::ConstValue;
+
+const FILENAME_UNSAT_CORES_DEFAULT: &str = "unsat_cores";
+
+#[derive(Debug, Serialize)]
+struct UnsatCoreRecord<'a> {
+    /// The decision whose negation is unsatisfiable.
+    target: &'a Decision,
+    /// The earlier decisions that make the negation of the target unsatisfiable.
+    core: Vec<&'a Decision>,
+}
+
+type PendingQuery = (
+    Vec<Decision>,
+    Vec<Constraint<CurrentSolverValue, CurrentSolverCase>>,
+);
+
+/// Finds and writes (as JSON lines) the unsatisfiable cores for the negated decisions.
+/// # Remarks
+/// It captures the query as a divergence filter, thus it is expected to be
+/// among the last filters so that it is only reached when the search is not filtered out.
+pub(super) struct UnsatCoreReporter {
+    solver: CurrentSolver,
+    file: Option<fs::File>,
+    pending: Option<PendingQuery>,
+}
+
+impl UnsatCoreReporter {
+    pub(super) fn new(config: &OutputConfig, solver: CurrentSolver) -> RRef<Self> {
+        let config = match config {
+            OutputConfig::File(cfg) => cfg,
+        };
+        let file = config
+            .open_or_create_single(FILENAME_UNSAT_CORES_DEFAULT, None, true)
+            .inspect_err(|e| log_warn!("Could not create the unsat cores report: {e}"))
+            .ok();
+        Rc::new(RefCell::new(Self {
+            solver,
+            file,
+            pending: None,
+        }))
+    }
+
+    /// Finds the core for the query that has just been found unsatisfiable and writes it.
+    pub(super) fn report(&mut self) {
+        let Some((decisions, constraints)) = self.pending.take() else {
+            return;
+        };
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+
+        let (result, core) = self.solver.check_with_unsat_core(constraints.into_iter());
+        if result != SatResult::Unsat {
+            log_debug!("The query is not unsatisfiable for the core: {:?}", result);
+            return;
+        }
+
+        let target_index = decisions.len() - 1;
+        let record = UnsatCoreRecord {
+            target: &decisions[target_index],
+            core: core
+                .into_iter()
+                .filter(|i| *i != target_index)
+                .map(|i| &decisions[i])
+                .collect(),
+        };
+        if let Err(e) = serde_json::to_writer(&mut *file, &record)
+            .map_err(|e| e.to_string())
+            .and_then(|_| writeln!(file).map_err(|e| e.to_string()))
+        {
+            log_warn!("Could not write the unsat core: {e}");
+        }
+    }
+}
+
+impl<S, V, C> DivergenceFilter<S, V, C> for UnsatCoreReporter
+where
+    S: Borrow<Step> + HasIndex,
+    V: Borrow<CurrentSolverValue>,
+    C: Borrow<CurrentSolverCase> + Borrow<ConstValue>,
+{
+    fn should_find(&mut self, trace: &[S], constraints: &[Constraint<V, C>]) -> bool {
+        let last = constraints.len() - 1;
+        let decisions = trace
+            .iter()
+            .zip(constraints.iter())
+            .map(|(step, constraint)| Decision::new(step, constraint.as_ref()))
+            .collect();
+        let constraints = constraints
+            .iter()
+            .enumerate()
+            .map(|(i, constraint)| {
+                let constraint = constraint.as_ref().map(
+                    Borrow::<CurrentSolverValue>::borrow,
+                    Borrow::<CurrentSolverCase>::borrow,
+                );
+                let constraint = if i == last {
+                    constraint.not()
+                } else {
+                    constraint
+                };
+                constraint.cloned()
+            })
+            .collect();
+        self.pending = Some((decisions, constraints));
+        true
+    }
+}
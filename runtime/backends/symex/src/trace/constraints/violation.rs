@@ -0,0 +1,227 @@
+//! A search for the inputs violating the checks of the operations along the path.
+//!
+//! The backend records the checks as decisions of the path, tagged by their kind.
+//! These are either the implicit checks of the operations that the program does
+//! not check, e.g., offsetting raw pointers in `unsafe` code (undefined behavior),
+//! or the assertions the compiler inserts for the operations panicking on invalid
+//! operands, e.g., arithmetic overflow (panics).
+//! For each check, the negation of it is solved along the path before it,
+//! and the answers are written as candidate inputs violating the check.
+
+use core::borrow::Borrow;
+use std::{cell::RefCell, collections::HashSet, fs, io::Write};
+
+use serde::Serialize;
+
+use common::{log_debug, log_info, log_warn, types::trace::Constraint};
+
+use leaf_runtime::{
+    abs::{
+        HasTags, Tag,
+        backend::{Model, SolveResult, Solver},
+    },
+    trace::StepInspector,
+    utils::RRef,
+};
+
+use super::{
+    CurrentSolver, CurrentSolverCase, CurrentSolverValue, IStep, OutputConfig, Step, backend,
+    divergence::add_missing_answers, prediction::Decision,
+};
+use backend::{
+    ConstValue, SymVarId, SymVariablesManager, ValueRef, alloc, assertion,
+    config::{InputSchemaConfig, OutputHookConfig, ParentInputConfig},
+    outgen::DefaultOutputGenerator,
+};
+
+const FILENAME_UB_REPORT_DEFAULT: &str = "ub_candidates";
+const FILENAME_PANIC_REPORT_DEFAULT: &str = "panic_candidates";
+
+#[derive(Debug, Serialize)]
+struct ViolationRecord<'a> {
+    /// The kind of the violated check.
+    kind: Tag,
+    /// The check that the candidate input is expected to violate.
+    check: &'a Decision,
+    /// The number of the candidate among the inputs generated by this checker.
+    input: usize,
+}
+
+/// Solves for the violations of the checks with the target tags as they appear
+/// in the path.
+/// # Remarks
+/// Each check location is only targeted once per execution, as the checks
+/// in loops would otherwise lead to many similar candidates.
+/// The path is asserted in the solver as it grows, and the negation of each
+/// check is solved in its own scope on top of it.
+pub(super) struct ViolationFinder {
+    targets: &'static [Tag],
+    solver: CurrentSolver,
+    targeted: HashSet<Step>,
+    candidates: usize,
+    on_model: Box<dyn FnMut(Model<SymVarId, ValueRef>)>,
+    report: Option<fs::File>,
+}
+
+impl<V, C> StepInspector<IStep, V, C> for ViolationFinder
+where
+    V: Borrow<CurrentSolverValue>,
+    C: Borrow<CurrentSolverCase> + Borrow<ConstValue>,
+{
+    fn inspect(&mut self, step: &IStep, constraint: Constraint<&V, &C>) {
+        let target = self
+            .targets
+            .iter()
+            .copied()
+            .find(|t| step.has_tag(t))
+            .filter(|_| self.targeted.insert(step.value.value))
+            .map(|kind| (kind, Decision::new(step, constraint.clone())));
+
+        let constraint = constraint.map(
+            |v| Borrow::<CurrentSolverValue>::borrow(v).clone(),
+            |c| Borrow::<CurrentSolverCase>::borrow(c).clone(),
+        );
+        if let Some((kind, decision)) = target {
+            self.check(kind, &decision, constraint.clone());
+        }
+        self.solver.assert(constraint);
+    }
+}
+
+impl ViolationFinder {
+    fn check(
+        &mut self,
+        kind: Tag,
+        decision: &Decision,
+        check: Constraint<CurrentSolverValue, CurrentSolverCase>,
+    ) {
+        log_debug!(
+            "Searching for a violation of {kind} check at {:?}",
+            decision
+        );
+
+        let model: Model<SymVarId, ValueRef> =
+            match Solver::check(&mut self.solver, core::iter::once(check.not())) {
+                SolveResult::Sat(model) => model
+                    .into_iter()
+                    .map(|(id, ans)| (id, ValueRef::from(ans)))
+                    .collect(),
+                _ => {
+                    log_debug!("The {kind} check cannot be violated in this path.");
+                    return;
+                }
+            };
+
+        log_info!(
+            "Found a candidate input violating {kind} check at {:?}",
+            decision
+        );
+        (self.on_model)(model);
+        self.report(kind, decision);
+        self.candidates += 1;
+    }
+
+    fn report(&mut self, kind: Tag, decision: &Decision) {
+        let Some(file) = self.report.as_mut() else {
+            return;
+        };
+        let record = ViolationRecord {
+            kind,
+            check: decision,
+            input: self.candidates,
+        };
+        if let Err(e) = serde_json::to_writer(&mut *file, &record)
+            .map_err(|e| e.to_string())
+            .and_then(|_| writeln!(file).map_err(|e| e.to_string()))
+        {
+            log_warn!("Could not write the violation: {e}");
+        }
+    }
+}
+
+/// Creates a finder of the inputs violating the checks against undefined behavior.
+pub(super) fn create_ub_checker(
+    sym_var_manager: RRef<impl SymVariablesManager + 'static>,
+    solver: CurrentSolver,
+    output_config: &[OutputConfig],
+    report_config: Option<&OutputConfig>,
+    parent_input: Option<&ParentInputConfig>,
+    output_hooks: &[OutputHookConfig],
+    input_schema: Option<&InputSchemaConfig>,
+) -> ViolationFinder {
+    create_violation_finder(
+        &alloc::tags::ALL,
+        FILENAME_UB_REPORT_DEFAULT,
+        sym_var_manager,
+        solver,
+        output_config,
+        report_config,
+        parent_input,
+        output_hooks,
+        input_schema,
+    )
+}
+
+/// Creates a finder of the inputs failing the assertions of the panicking operations.
+pub(super) fn create_panic_finder(
+    sym_var_manager: RRef<impl SymVariablesManager + 'static>,
+    solver: CurrentSolver,
+    output_config: &[OutputConfig],
+    report_config: Option<&OutputConfig>,
+    parent_input: Option<&ParentInputConfig>,
+    output_hooks: &[OutputHookConfig],
+    input_schema: Option<&InputSchemaConfig>,
+) -> ViolationFinder {
+    create_violation_finder(
+        &assertion::tags::ALL,
+        FILENAME_PANIC_REPORT_DEFAULT,
+        sym_var_manager,
+        solver,
+        output_config,
+        report_config,
+        parent_input,
+        output_hooks,
+        input_schema,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_violation_finder(
+    targets: &'static [Tag],
+    report_filename: &str,
+    sym_var_manager: RRef<impl SymVariablesManager + 'static>,
+    solver: CurrentSolver,
+    output_config: &[OutputConfig],
+    report_config: Option<&OutputConfig>,
+    parent_input: Option<&ParentInputConfig>,
+    output_hooks: &[OutputHookConfig],
+    input_schema: Option<&InputSchemaConfig>,
+) -> ViolationFinder {
+    let mut output_generator =
+        DefaultOutputGenerator::new(output_config, parent_input, output_hooks, input_schema);
+    let on_model = move |mut model| {
+        if output_generator.requires_all_answers() {
+            add_missing_answers(&mut model, &*RefCell::borrow(&sym_var_manager));
+        }
+        output_generator.generate(&model);
+    };
+
+    let report = report_config.and_then(|cfg| {
+        let config = match cfg {
+            OutputConfig::File(cfg) => cfg,
+        };
+        config
+            .open_or_create_single(report_filename, None, true)
+            .inspect_err(|e| log_warn!("Could not create the violations report: {e}"))
+            .ok()
+    });
+
+    ViolationFinder {
+        targets,
+        solver,
+        targeted: HashSet::new(),
+        candidates: 0,
+        on_model: Box::new(on_model),
+        report,
+    }
+}
@@ -0,0 +1,429 @@
+//! Coverage of the whole execution, aggregated from the execution trace and
+//! reported at the end of the execution.
+//!
+//! The basic blocks appearing in the trace are the branching nodes, the call
+//! sites, the return points, and the entry blocks of the called bodies. The edges
+//! are the decisions taken at the branching nodes. If the program map exported
+//! by the compiler is found next to the executable, the blocks of these kinds
+//! and the edges that are not covered are reported as well, and the blocks are
+//! mapped to their source lines for the lcov format.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashSet},
+    io::{self, Write},
+};
+
+use serde::Serialize;
+
+use common::{
+    directed::{CfgConstraint, CfgEdgeDestination, ProgramMap},
+    log_debug, log_info, log_warn,
+    types::{BasicBlockIndex, InstanceKindId, trace::RawCaseValue},
+};
+
+use leaf_runtime::{
+    abs::{BasicBlockLocation, Constraint, ConstraintKind, ExeTraceRecord},
+    trace::{BranchCoverageStepInspector, StepInspector},
+    utils::{
+        RRef,
+        file::{FileFormat, FileGenConfig},
+    },
+};
+
+use super::{SymExExeTraceRecorder, backend, record::to_raw_case};
+use backend::{ExeTraceStorage, config::OutputConfig};
+
+const FILENAME_DEFAULT: &str = "coverage";
+const LCOV_EXTENSION: &str = "info";
+
+#[derive(Serialize)]
+struct EdgeCoverage {
+    decision: ConstraintKind<RawCaseValue>,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct UncoveredEdge {
+    from: BasicBlockIndex,
+    to: BasicBlockIndex,
+    constraint: Option<CfgConstraint>,
+}
+
+#[derive(Default, Serialize)]
+struct BodyCoverage {
+    /// Hit counts of the basic blocks.
+    blocks: BTreeMap<BasicBlockIndex, usize>,
+    /// Hit counts of the decisions taken at each branching node.
+    edges: BTreeMap<BasicBlockIndex, Vec<EdgeCoverage>>,
+    /// The blocks of the kinds appearing in the trace that are not hit.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    uncovered_blocks: Vec<BasicBlockIndex>,
+    /// The edges of the branching nodes that are not taken.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    uncovered_edges: Vec<UncoveredEdge>,
+}
+
+impl BodyCoverage {
+    fn hit_block(&mut self, index: BasicBlockIndex) {
+        *self.blocks.entry(index).or_default() += 1;
+    }
+
+    fn hits_of(&self, index: BasicBlockIndex) -> usize {
+        self.blocks.get(&index).copied().unwrap_or_default()
+    }
+
+    /// # Returns
+    /// The hit counts of the edges of the branching node, in the order of the
+    /// edges in the control flow graph.
+    fn edge_hits(&self, index: BasicBlockIndex, edges: &[CfgEdgeDestination]) -> Vec<usize> {
+        let mut hits = vec![0; edges.len()];
+        for edge in self.edges.get(&index).into_iter().flatten() {
+            if let Some(i) = edge_index_of(&edge.decision, edges) {
+                hits[i] += edge.count;
+            }
+        }
+        hits
+    }
+}
+
+#[derive(Serialize)]
+struct CoverageEntry<'a> {
+    body: InstanceKindId,
+    #[serde(flatten)]
+    coverage: &'a BodyCoverage,
+}
+
+type Coverage = BTreeMap<InstanceKindId, BodyCoverage>;
+
+struct CoverageReporter {
+    outputs: Vec<OutputConfig>,
+    trace_recorder: RRef<SymExExeTraceRecorder>,
+    program_map: Option<ProgramMap>,
+}
+
+thread_local! {
+    static REPORTER: RefCell<Option<CoverageReporter>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn init_coverage_reporting(
+    outputs: &[OutputConfig],
+    trace_recorder: RRef<SymExExeTraceRecorder>,
+) {
+    if outputs.is_empty() {
+        return;
+    }
+    let program_map =
+        common::utils::search_next_to_exe_for(ProgramMap::FILENAME).and_then(|path| {
+            ProgramMap::read(&path)
+                .inspect_err(|e| log_warn!("Could not read the program map: {e}"))
+                .ok()
+        });
+    if program_map.is_none() {
+        log_warn!(
+            "The program map is not available, the coverage will not include the uncovered parts"
+        );
+    }
+    REPORTER.with_borrow_mut(|r| {
+        *r = Some(CoverageReporter {
+            outputs: outputs.to_vec(),
+            trace_recorder,
+            program_map,
+        })
+    });
+}
+
+pub(crate) fn dump_coverage() {
+    REPORTER.with_borrow(|r| {
+        let Some(reporter) = r.as_ref() else {
+            return;
+        };
+        let mut coverage = collect(&reporter.trace_recorder.borrow());
+        if let Some(program_map) = reporter.program_map.as_ref() {
+            add_uncovered(&mut coverage, program_map);
+        }
+        for output in &reporter.outputs {
+            let OutputConfig::File(file_config) = output;
+            let result = match (file_config.format(), reporter.program_map.as_ref()) {
+                (FileFormat::Text, Some(program_map)) => {
+                    write_lcov(file_config, &coverage, program_map)
+                }
+                (FileFormat::Text, None) => {
+                    log_warn!("Skipping the lcov report as the program map is not available");
+                    Ok(())
+                }
+                _ => write_json(file_config, &coverage),
+            };
+            let _ = result.inspect_err(|e| log_warn!("Could not write coverage report: {e}"));
+        }
+    });
+}
+
+fn collect(recorder: &SymExExeTraceRecorder) -> Coverage {
+    let mut coverage = Coverage::new();
+    let mut hit_block = |location: &BasicBlockLocation| {
+        coverage
+            .entry(location.body)
+            .or_default()
+            .hit_block(location.index)
+    };
+
+    let records = recorder.records();
+    let mut branches = BranchCoverageStepInspector::<BasicBlockLocation, RawCaseValue>::new();
+    for record in records.borrow().iter() {
+        use ExeTraceRecord::*;
+        match &record.value {
+            Call { from, to, .. } => {
+                hit_block(from);
+                hit_block(&BasicBlockLocation {
+                    body: *to,
+                    index: 0,
+                });
+            }
+            Return { from, .. } | ExternalCall { from, .. } | Termination { from, .. } => {
+                hit_block(from)
+            }
+            Branch(branch) => {
+                hit_block(&branch.location);
+                let branch = to_raw_case(branch);
+                StepInspector::<_, (), _>::inspect(
+                    &mut branches,
+                    &branch.location,
+                    Constraint {
+                        discr: &(),
+                        kind: branch.decision.as_ref(),
+                    },
+                );
+            }
+            // The comparisons in the chain are already counted as branches.
+            MatchChain(..) => {}
+            // The call site is already counted when the external call is made.
+            ExternalReturn { .. } => {}
+            // The call site of the annotation is counted as an external call.
+            Reach { .. } | Reject { .. } => {}
+        }
+    }
+
+    for (location, decisions) in branches.get_coverage() {
+        coverage.entry(location.body).or_default().edges.insert(
+            location.index,
+            decisions
+                .iter()
+                .map(|(decision, data)| EdgeCoverage {
+                    decision: decision.clone(),
+                    count: data.count(),
+                })
+                .collect(),
+        );
+    }
+    coverage
+}
+
+/// Adds the blocks and the edges of the control flow graphs that are not
+/// covered to the bodies that are hit.
+fn add_uncovered(coverage: &mut Coverage, program_map: &ProgramMap) {
+    for (body, coverage) in coverage.iter_mut() {
+        let Some(cfg) = program_map.cfgs.get(body) else {
+            log_debug!("Body {body} is not found in the program map");
+            continue;
+        };
+
+        let mut uncovered_blocks = observable_blocks(program_map, body)
+            .into_iter()
+            .filter(|index| coverage.hits_of(*index) == 0)
+            .collect::<Vec<_>>();
+        uncovered_blocks.sort();
+        coverage.uncovered_blocks = uncovered_blocks;
+
+        let mut uncovered_edges = Vec::new();
+        for (from, edges) in cfg.iter().filter(|(_, edges)| is_branching(edges)) {
+            let hits = coverage.edge_hits(*from, edges);
+            uncovered_edges.extend(edges.iter().zip(hits).filter(|(_, hits)| *hits == 0).map(
+                |((to, constraint), _)| UncoveredEdge {
+                    from: *from,
+                    to: *to,
+                    constraint: *constraint,
+                },
+            ));
+        }
+        uncovered_edges.sort_by_key(|e| (e.from, e.to));
+        coverage.uncovered_edges = uncovered_edges;
+    }
+}
+
+/// # Returns
+/// The blocks of the body that would appear in the trace if executed.
+fn observable_blocks(program_map: &ProgramMap, body: &InstanceKindId) -> HashSet<BasicBlockIndex> {
+    let branching = program_map
+        .cfgs
+        .get(body)
+        .into_iter()
+        .flatten()
+        .filter(|(_, edges)| is_branching(edges))
+        .map(|(index, _)| *index);
+    let call_sites = program_map
+        .call_graph
+        .get(body)
+        .into_iter()
+        .flatten()
+        .map(|(index, ..)| *index);
+    let ret_points = program_map
+        .ret_points
+        .get(body)
+        .into_iter()
+        .flatten()
+        .copied();
+    core::iter::once(0)
+        .chain(branching)
+        .chain(call_sites)
+        .chain(ret_points)
+        .collect()
+}
+
+fn is_branching(edges: &[CfgEdgeDestination]) -> bool {
+    edges.iter().any(|(_, constraint)| constraint.is_some())
+}
+
+/// # Returns
+/// The index of the edge that the decision taken at the branching node leads to.
+fn edge_index_of(
+    decision: &ConstraintKind<RawCaseValue>,
+    edges: &[CfgEdgeDestination],
+) -> Option<usize> {
+    use ConstraintKind::*;
+    let case = match decision {
+        True => Some(1),
+        False => Some(0),
+        OneOf(values) => values.first().copied(),
+        NoneOf(..) => None,
+    };
+    case.and_then(|case| {
+        edges
+            .iter()
+            .position(|(_, c)| matches!(c, Some(CfgConstraint::Case(v)) if *v == case))
+    })
+    .or_else(|| {
+        edges
+            .iter()
+            .position(|(_, c)| matches!(c, Some(CfgConstraint::Otherwise)))
+    })
+}
+
+fn write_json(file_config: &FileGenConfig, coverage: &Coverage) -> io::Result<()> {
+    let (path, file) = file_config.open_or_create_single_with_path(FILENAME_DEFAULT, None, true)?;
+    let entries = coverage
+        .iter()
+        .map(|(body, coverage)| CoverageEntry {
+            body: *body,
+            coverage,
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_writer_pretty(file, &entries)?;
+    log_info!("Coverage report written to: {}", path.display());
+    Ok(())
+}
+
+#[derive(Default)]
+struct FileCoverage {
+    /// Hit counts of the lines.
+    lines: BTreeMap<u32, usize>,
+    /// The hit counts of the edges of the branching nodes by their lines and
+    /// blocks, `None` if the node is not reached.
+    branches: BTreeMap<(u32, BasicBlockIndex), Vec<Option<usize>>>,
+}
+
+/// Writes the coverage in the lcov tracefile format.
+/// # Remarks
+/// Only the lines of the blocks of the kinds appearing in the trace are
+/// reported, as the other ones cannot be told apart from the unreached ones.
+/// A line gets the maximum hit count of its blocks.
+fn write_lcov(
+    file_config: &FileGenConfig,
+    coverage: &Coverage,
+    program_map: &ProgramMap,
+) -> io::Result<()> {
+    let mut files = BTreeMap::<&str, FileCoverage>::new();
+    for (body, coverage) in coverage {
+        let Some(lines) = program_map.debug_info.block_lines.get(body) else {
+            log_debug!("The lines of body {body} are not found in the program map");
+            continue;
+        };
+
+        for index in observable_blocks(program_map, body)
+            .into_iter()
+            .chain(coverage.blocks.keys().copied())
+        {
+            let Some(line) = lines.get(&index) else {
+                continue;
+            };
+            let hits = files
+                .entry(line.file.as_str())
+                .or_default()
+                .lines
+                .entry(line.line)
+                .or_default();
+            *hits = (*hits).max(coverage.hits_of(index));
+        }
+
+        let cfg = program_map.cfgs.get(body).into_iter().flatten();
+        for (index, edges) in cfg.filter(|(_, edges)| is_branching(edges)) {
+            let Some(line) = lines.get(index) else {
+                continue;
+            };
+            let hits = if coverage.hits_of(*index) > 0 {
+                coverage
+                    .edge_hits(*index, edges)
+                    .into_iter()
+                    .map(Some)
+                    .collect()
+            } else {
+                vec![None; edges.len()]
+            };
+            files
+                .entry(line.file.as_str())
+                .or_default()
+                .branches
+                .insert((line.line, *index), hits);
+        }
+    }
+
+    let path = file_config.ensure_dir()?.join(format!(
+        "{}.{}",
+        file_config.prefix().unwrap_or(FILENAME_DEFAULT),
+        file_config.extension().unwrap_or(LCOV_EXTENSION),
+    ));
+    let mut writer = io::BufWriter::new(std::fs::File::create(&path)?);
+
+    for (file, coverage) in files {
+        writeln!(writer, "TN:")?;
+        writeln!(writer, "SF:{file}")?;
+        let (mut branch_count, mut branch_hit_count) = (0, 0);
+        for ((line, index), hits) in &coverage.branches {
+            for (i, hits) in hits.iter().enumerate() {
+                match hits {
+                    Some(hits) => writeln!(writer, "BRDA:{line},{index},{i},{hits}")?,
+                    // The node is not reached.
+                    None => writeln!(writer, "BRDA:{line},{index},{i},-")?,
+                }
+            }
+            branch_count += hits.len();
+            branch_hit_count += hits.iter().filter(|h| h.is_some_and(|h| h > 0)).count();
+        }
+        writeln!(writer, "BRF:{branch_count}")?;
+        writeln!(writer, "BRH:{branch_hit_count}")?;
+        for (line, hits) in &coverage.lines {
+            writeln!(writer, "DA:{line},{hits}")?;
+        }
+        writeln!(writer, "LF:{}", coverage.lines.len())?;
+        writeln!(
+            writer,
+            "LH:{}",
+            coverage.lines.values().filter(|h| **h > 0).count()
+        )?;
+        writeln!(writer, "end_of_record")?;
+    }
+    writer.flush()?;
+
+    log_info!("Coverage report (lcov) written to: {}", path.display());
+    Ok(())
+}
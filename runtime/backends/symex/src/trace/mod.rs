@@ -6,12 +6,22 @@ use common::pri::BasicBlockLocation;
 
 use super::alias::backend;
 
+mod concretization;
+pub(crate) use concretization::{
+    ConcretizationReason, init_concretization_recording, record_concretization,
+};
+
 mod constraints;
 pub(crate) use constraints::create_trace_manager;
 
+mod coverage;
+pub(crate) use coverage::{dump_coverage, init_coverage_reporting};
+
 mod record;
 pub(crate) use record::{SymExExeTraceRecorder, create_trace_recorder};
 
+mod stream;
+
 mod query;
 pub(crate) use query::default_trace_querier;
 
@@ -203,7 +203,29 @@ mod helpers {
                 ExeTraceRecord::Branch(BranchRecord {
                     location: BasicBlockLocation { body, .. },
                     ..
+                })
+                | ExeTraceRecord::MatchChain(BranchRecord {
+                    location: BasicBlockLocation { body, .. },
+                    ..
                 }) => body,
+                ExeTraceRecord::ExternalCall {
+                    from: BasicBlockLocation { body, .. },
+                    ..
+                } => body,
+                ExeTraceRecord::ExternalReturn {
+                    to: BasicBlockLocation { body, .. },
+                } => body,
+                ExeTraceRecord::Termination {
+                    from: BasicBlockLocation { body, .. },
+                    ..
+                }
+                | ExeTraceRecord::Reach {
+                    from: BasicBlockLocation { body, .. },
+                    ..
+                }
+                | ExeTraceRecord::Reject {
+                    from: BasicBlockLocation { body, .. },
+                } => body,
             }
             .eq(&body_id)
         }
@@ -12,14 +12,23 @@ use common::{
 
 use leaf_runtime::{
     abs::{
-        BasicBlockLocation, ConstraintKind, ExeTraceRecord as AbsExeTraceRecord, FuncDef,
+        BasicBlockLocation, CalleeDef, ConstraintKind, ExeTraceRecord as AbsExeTraceRecord,
+        FuncDef,
         backend::{DecisionTraceRecorder, PhasedCallTraceRecorder},
     },
-    utils::{HasIndex, Indexed, RRef, RefView, file::JsonLinesFormatter},
+    utils::{
+        HasIndex, Indexed, PerThread, RRef, RefView, current_thread_index,
+        file::{FileFormat, FileGenConfig, JsonLinesFormatter},
+    },
 };
 
 use super::backend;
-use backend::{ConstValue, ExeTraceStorage, alias::ExeTraceRecorder, config::OutputConfig};
+use super::stream::{FramedTraceWriter, serialize_threaded};
+use backend::{
+    ConstValue, ExeTraceStorage,
+    alias::ExeTraceRecorder,
+    config::{OutputConfig, TraceStreamConfig},
+};
 
 type ExeTraceRecord = AbsExeTraceRecord<ConstValue>;
 
@@ -28,6 +37,8 @@ pub(crate) struct Record {
     #[deref]
     record: Indexed<AbsExeTraceRecord<ConstValue>>,
     pub(super) depth: usize,
+    /// The index of the thread taking the step, while `index` orders the records across threads.
+    pub(super) thread: usize,
 }
 
 impl HasIndex for Record {
@@ -42,51 +53,80 @@ impl Borrow<AbsExeTraceRecord<ConstValue>> for Record {
     }
 }
 
+#[derive(Default)]
+struct ThreadCallFlow {
+    stack: Vec<BasicBlockLocation<FuncDef>>,
+    last_ret_point: Option<BasicBlockLocation<FuncDef>>,
+    /// The call site and the expected callee of the latest call that has not entered yet.
+    pending_call: Option<(BasicBlockLocation<FuncDef>, Option<CalleeDef>)>,
+}
+
 pub(crate) struct SymExExeTraceRecorder {
     counter: usize,
     records: RRef<Vec<Record>>,
-    stack: Vec<BasicBlockLocation<FuncDef>>,
-    last_ret_point: Option<BasicBlockLocation<FuncDef>>,
+    /// The call flow of each thread, while the records are ordered globally.
+    threads: PerThread<ThreadCallFlow>,
     serializer: Option<JsonSerializer<std::fs::File, JsonLinesFormatter>>,
+    /// The binary file and the stream (if set) that the records are written to in frames.
+    framed_writers: Vec<FramedTraceWriter>,
 }
 
 impl SymExExeTraceRecorder {
-    fn new(config: Option<&OutputConfig>) -> Self {
-        let file = config
-            .and_then(|c| match c {
-                OutputConfig::File(file) => Some(file),
-            })
-            .filter(|c| matches!(c.format(), leaf_runtime::utils::file::FileFormat::JsonLines))
-            .map(|c| {
-                c.open_or_create_single("exe_trace", None, true)
-                    .unwrap_or_else(|e| panic!("Could not create file for trace recording: {e}"))
-            });
+    fn new(config: Option<&OutputConfig>, stream_config: Option<&TraceStreamConfig>) -> Self {
+        let file_config = config.map(|c| match c {
+            OutputConfig::File(file) => file,
+        });
+        let open_file = |c: &FileGenConfig| {
+            c.open_or_create_single("exe_trace", None, true)
+                .unwrap_or_else(|e| panic!("Could not create file for trace recording: {e}"))
+        };
+        let file = file_config
+            .filter(|c| matches!(c.format(), FileFormat::JsonLines))
+            .map(open_file);
+        let binary_file = file_config
+            .filter(|c| matches!(c.format(), FileFormat::Binary))
+            .map(open_file);
+
+        let stream = stream_config.and_then(|c| {
+            FramedTraceWriter::connect(c)
+                .inspect_err(|e| log_warn!("Could not connect for trace streaming, disabled: {e}"))
+                .ok()
+        });
 
         Self {
             serializer: file
                 .map(|f| JsonSerializer::with_formatter(f, JsonLinesFormatter::default())),
+            framed_writers: binary_file
+                .map(FramedTraceWriter::to_file)
+                .into_iter()
+                .chain(stream)
+                .collect(),
             counter: 0,
             records: Default::default(),
-            stack: Default::default(),
-            last_ret_point: Default::default(),
+            threads: Default::default(),
         }
     }
 }
 
-pub(crate) fn create_trace_recorder(config: Option<&OutputConfig>) -> SymExExeTraceRecorder
+pub(crate) fn create_trace_recorder(
+    config: Option<&OutputConfig>,
+    stream_config: Option<&TraceStreamConfig>,
+) -> SymExExeTraceRecorder
 where
     SymExExeTraceRecorder: ExeTraceRecorder,
 {
-    SymExExeTraceRecorder::new(config)
+    SymExExeTraceRecorder::new(config, stream_config)
 }
 
 impl PhasedCallTraceRecorder for SymExExeTraceRecorder {
     #[tracing::instrument(level = "debug", skip(self))]
-    fn start_call(&mut self, call_site: BasicBlockLocation<FuncDef>) {
-        let last_ret_point = self.last_ret_point.take();
+    fn start_call(&mut self, call_site: BasicBlockLocation<FuncDef>, callee: Option<CalleeDef>) {
+        let last_ret_point = self.flow().last_ret_point.take();
         self.handle_maybe_unfinished_return(last_ret_point);
+        self.flow().pending_call = Some((call_site, callee));
 
         *self
+            .flow()
             .stack
             .last_mut()
             .inspect(|l| debug_assert_eq!(&l.body, &call_site.body))
@@ -95,8 +135,16 @@ impl PhasedCallTraceRecorder for SymExExeTraceRecorder {
 
     #[tracing::instrument(level = "debug", skip(self))]
     fn finish_call(&mut self, entered_func: FuncDef, broken: Option<bool>) {
-        let call_site = self.stack.last().copied();
-        self.stack.push(BasicBlockLocation {
+        // The expected callee was external and has called back the instrumented code.
+        let pending_call = self.flow().pending_call.take();
+        if broken == Some(true) {
+            if let Some(pending_call) = pending_call {
+                self.notify_external_call(pending_call);
+            }
+        }
+
+        let call_site = self.stack().last().copied();
+        self.flow().stack.push(BasicBlockLocation {
             body: entered_func,
             index: 0,
         });
@@ -120,21 +168,32 @@ impl PhasedCallTraceRecorder for SymExExeTraceRecorder {
 
     #[tracing::instrument(level = "debug", skip(self))]
     fn start_return(&mut self, ret_point: BasicBlockLocation<FuncDef>) {
-        self.stack.pop().expect("Inconsistent stack info");
-        let unfinished = self.last_ret_point.replace(ret_point);
+        self.flow().stack.pop().expect("Inconsistent stack info");
+        let unfinished = self.flow().last_ret_point.replace(ret_point);
         self.handle_maybe_unfinished_return(unfinished);
     }
 
     #[tracing::instrument(level = "debug", skip(self))]
     fn finish_return(&mut self, broken: bool) -> FuncDef {
-        let call_site = self.stack.last().copied().expect("Inconsistent stack info");
-        let Some(ret_point) = self.last_ret_point.take() else {
+        let call_site = self
+            .stack()
+            .last()
+            .copied()
+            .expect("Inconsistent stack info");
+        let Some(ret_point) = self.flow().last_ret_point.take() else {
             if !broken {
                 panic!(
                     "Last return point is expected when not broken, current: {}",
                     call_site
                 )
             } else {
+                // The callee has been external. It may or may not have called back the instrumented code.
+                if let Some(pending_call) = self.flow().pending_call.take() {
+                    self.notify_external_call(pending_call);
+                }
+                self.notify_step(ExeTraceRecord::ExternalReturn {
+                    to: call_site.into(),
+                });
                 return call_site.body;
             }
         };
@@ -172,6 +231,52 @@ impl ExeTraceStorage for SymExExeTraceRecorder {
 }
 
 impl SymExExeTraceRecorder {
+    pub(crate) fn notify_termination(&mut self, location: BasicBlockLocation, exit_code: u8) {
+        self.notify_step(ExeTraceRecord::Termination {
+            from: self.ensure_in_current_body(location),
+            exit_code,
+        });
+    }
+
+    /// Records a chain of string comparisons as a multi-way branch at its head.
+    pub(crate) fn notify_match_chain(
+        &mut self,
+        head: BasicBlockLocation,
+        decision: ConstraintKind<ConstValue>,
+    ) {
+        self.notify_step(ExeTraceRecord::MatchChain(BranchRecord {
+            location: self.ensure_in_current_body(head),
+            decision,
+        }));
+    }
+
+    /// Records reaching an objective, i.e., a `Reach` or `Reject` record.
+    pub(crate) fn notify_objective(&mut self, record: ExeTraceRecord) {
+        debug_assert!(matches!(
+            record,
+            ExeTraceRecord::Reach { .. } | ExeTraceRecord::Reject { .. }
+        ));
+        self.notify_step(record);
+    }
+
+    #[inline]
+    fn flow(&mut self) -> &mut ThreadCallFlow {
+        self.threads.current_mut()
+    }
+
+    #[inline]
+    fn stack(&self) -> &[BasicBlockLocation<FuncDef>] {
+        self.threads
+            .current()
+            .map(|t| t.stack.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Returns the current function and the latest call site in it (if any).
+    pub(crate) fn current_location(&self) -> Option<BasicBlockLocation> {
+        self.stack().last().copied().map(|l| l.into())
+    }
+
     #[tracing::instrument(level = "debug", skip(self), fields(index = self.counter + 1))]
     fn notify_step(&mut self, record: ExeTraceRecord) -> usize {
         let index = {
@@ -184,18 +289,48 @@ impl SymExExeTraceRecorder {
                 value: record,
                 index,
             },
-            depth: self.stack.len(),
+            depth: self.stack().len(),
+            thread: current_thread_index(),
         });
         self.append_last_to_file();
         index
     }
 
+    #[inline]
+    fn notify_external_call(
+        &mut self,
+        (call_site, callee): (BasicBlockLocation<FuncDef>, Option<CalleeDef>),
+    ) {
+        core::hint::cold_path();
+        self.notify_step(ExeTraceRecord::ExternalCall {
+            from: call_site.into(),
+            callee: callee
+                .map(|c| c.callee_id)
+                .filter(|id| *id != InstanceKindId::INVALID),
+        });
+    }
+
     fn append_last_to_file(&mut self) {
-        let Some(serializer) = self.serializer.as_mut() else {
-            return;
-        };
-        let _ = serialize_rec(self.records.as_ref().borrow().last().unwrap(), serializer)
-            .inspect_err(|e| log_debug!("Failed to dump trace: {}", e));
+        if let Some(serializer) = self.serializer.as_mut() {
+            let _ = serialize_rec(self.records.as_ref().borrow().last().unwrap(), serializer)
+                .inspect_err(|e| log_debug!("Failed to dump trace: {}", e));
+        }
+        for writer in self.framed_writers.iter_mut() {
+            let _ = writer
+                .send(&SerializedRecord(
+                    self.records.as_ref().borrow().last().unwrap(),
+                ))
+                .inspect_err(|e| log_warn!("Failed to write framed trace: {}", e));
+        }
+    }
+
+    /// Flushes the records that are buffered for the framed writers.
+    pub(crate) fn flush(&mut self) {
+        for writer in self.framed_writers.iter_mut() {
+            let _ = writer
+                .flush()
+                .inspect_err(|e| log_warn!("Failed to flush framed trace: {}", e));
+        }
     }
 
     #[inline]
@@ -220,7 +355,7 @@ impl SymExExeTraceRecorder {
     }
 
     fn ensure_in_current_body(&self, mut location: BasicBlockLocation) -> BasicBlockLocation {
-        let current = if let Some(current) = self.stack.last() {
+        let current = if let Some(current) = self.stack().last() {
             current.body.body_id
         } else {
             core::hint::cold_path();
@@ -242,27 +377,58 @@ impl SymExExeTraceRecorder {
     }
 }
 
+/// The serialized form of the records, along with the thread taking the step.
+struct ThreadedRecord<T> {
+    record: Indexed<T>,
+    thread: usize,
+}
+
+impl<T: Serialize> Serialize for ThreadedRecord<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_threaded(&self.record, self.thread, serializer)
+    }
+}
+
+struct SerializedRecord<'a>(&'a Record);
+
+impl Serialize for SerializedRecord<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_rec(self.0, serializer)
+    }
+}
+
 fn serialize_rec<S: Serializer>(record: &Record, serializer: S) -> Result<S::Ok, S::Error> {
     let Record {
-        record: record @ Indexed {
+        record: Indexed {
             ref value,
             ref index,
         },
+        thread,
         ..
-    } = record;
+    } = *record;
 
     use AbsExeTraceRecord::*;
     match value {
-        Branch(ref branch) => Indexed {
-            value: Branch(to_raw_case(branch)),
-            index: *index,
+        Branch(ref branch) => ThreadedRecord {
+            record: Indexed {
+                value: Branch(to_raw_case(branch)),
+                index: *index,
+            },
+            thread,
+        }
+        .serialize(serializer),
+        _ => ThreadedRecord {
+            record: Indexed {
+                value,
+                index: *index,
+            },
+            thread,
         }
         .serialize(serializer),
-        _ => record.serialize(serializer),
     }
 }
 
-fn to_raw_case(branch: &BranchRecord<ConstValue>) -> BranchRecord<RawCaseValue> {
+pub(super) fn to_raw_case(branch: &BranchRecord<ConstValue>) -> BranchRecord<RawCaseValue> {
     BranchRecord {
         location: branch.location,
         decision: branch
@@ -0,0 +1,186 @@
+//! Framed writing of the trace records, used for streaming them to an external
+//! consumer (e.g., the orchestrator) while the execution is in progress,
+//! and for the compact (binary) trace files.
+//!
+//! Each record is written as a frame: its length as a little-endian `u32`
+//! followed by the record encoded in postcard. As the binary formats do not
+//! support flattening, the records are laid out as tuples in them (see
+//! [`serialize_threaded`]).
+//! The streams are flushed after each frame so that the consumer observes the
+//! records as they happen, while the binary files are buffered.
+//! With the `zstd` feature, the binary files are compressed in independent zstd
+//! frames, each holding a chunk of the records, so that the written chunks
+//! remain readable even if the execution terminates abruptly.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+use serde::{Serialize, Serializer};
+
+use common::log_info;
+
+use super::backend;
+use backend::config::TraceStreamConfig;
+
+pub(super) struct FramedTraceWriter {
+    writer: Box<dyn Write>,
+    buffer: Vec<u8>,
+    flush_each_frame: bool,
+}
+
+impl FramedTraceWriter {
+    pub(super) fn connect(config: &TraceStreamConfig) -> io::Result<Self> {
+        let stream: Box<dyn Write> = match config {
+            #[cfg(unix)]
+            TraceStreamConfig::Unix { path } => {
+                Box::new(std::os::unix::net::UnixStream::connect(path)?)
+            }
+            #[cfg(not(unix))]
+            TraceStreamConfig::Unix { .. } => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "Unix domain sockets are not supported on this platform",
+                ));
+            }
+            TraceStreamConfig::Tcp { address } => {
+                let stream = std::net::TcpStream::connect(address)?;
+                stream.set_nodelay(true)?;
+                Box::new(stream)
+            }
+        };
+        log_info!("Streaming the trace to {config:?}");
+        Ok(Self::new(Box::new(BufWriter::new(stream)), true))
+    }
+
+    pub(super) fn to_file(file: File) -> Self {
+        #[cfg(feature = "zstd")]
+        let writer = Box::new(CompressedChunksWriter {
+            file,
+            pending: Vec::with_capacity(COMPRESSION_CHUNK_SIZE),
+        });
+        #[cfg(not(feature = "zstd"))]
+        let writer = Box::new(BufWriter::new(file));
+        Self::new(writer, false)
+    }
+
+    fn new(writer: Box<dyn Write>, flush_each_frame: bool) -> Self {
+        Self {
+            writer,
+            buffer: Vec::new(),
+            flush_each_frame,
+        }
+    }
+
+    /// Sends a frame with the record.
+    pub(super) fn send(&mut self, record: &impl Serialize) -> io::Result<()> {
+        self.buffer.clear();
+        self.buffer = postcard::to_extend(record, core::mem::take(&mut self.buffer))
+            .map_err(io::Error::other)?;
+        let len = u32::try_from(self.buffer.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Record is too large"))?;
+        self.writer.write_all(&len.to_le_bytes())?;
+        self.writer.write_all(&self.buffer)?;
+        if self.flush_each_frame {
+            self.writer.flush()?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Serializes the record along with the thread taking it.
+/// The record is flattened in the human-readable formats, i.e., JSON, and is
+/// paired with the thread in the others.
+pub(super) fn serialize_threaded<T: Serialize, S: Serializer>(
+    record: &T,
+    thread: usize,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct Flattened<'a, T> {
+        #[serde(flatten)]
+        record: &'a T,
+        thread: usize,
+    }
+
+    if serializer.is_human_readable() {
+        Flattened { record, thread }.serialize(serializer)
+    } else {
+        (record, thread).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "zstd")]
+const COMPRESSION_CHUNK_SIZE: usize = 1 << 20;
+
+#[cfg(feature = "zstd")]
+struct CompressedChunksWriter {
+    file: File,
+    pending: Vec<u8>,
+}
+
+#[cfg(feature = "zstd")]
+impl CompressedChunksWriter {
+    fn write_chunk(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let compressed = zstd::bulk::compress(&self.pending, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+        self.file.write_all(&compressed)?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl Write for CompressedChunksWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        if self.pending.len() >= COMPRESSION_CHUNK_SIZE {
+            self.write_chunk()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_chunk()?;
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_hold_length_prefixed_frames() {
+        let path = std::env::temp_dir().join(format!("leaf_frames_{}.bin", std::process::id()));
+        let mut writer = FramedTraceWriter::to_file(File::create(&path).unwrap());
+        let records = [(1u32, "first"), (2u32, "second")];
+        for record in &records {
+            writer.send(record).unwrap();
+        }
+        writer.flush().unwrap();
+        drop(writer);
+
+        let content = std::fs::read(&path).unwrap();
+        #[cfg(feature = "zstd")]
+        let content = zstd::decode_all(content.as_slice()).unwrap();
+
+        let mut rest = content.as_slice();
+        for record in &records {
+            let (len, frame) = rest.split_at(4);
+            let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+            let (frame, next) = frame.split_at(len);
+            assert_eq!(postcard::from_bytes::<(u32, &str)>(frame).unwrap(), *record);
+            rest = next;
+        }
+        assert!(rest.is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -57,7 +57,7 @@ macro_rules! impl_float_type {
     ($($name: ident),*$(,)?) => {
         $(
             fn $name(&self) -> ValueType {
-                unimplemented!()
+                crate::failure::fail!(UnsupportedFeature, "Float types are not supported yet.")
             }
         )*
     };
@@ -49,3 +49,11 @@ macro_rules! export_to_c_abi {
 }
 
 common::pri::list_func_decls!(modifier: export_to_c_abi, (from common::ffi));
+
+/* Read by the fast path in the shim before the PRI calls it may skip.
+ * The flag is kept per frame of each thread, thus the address of the flag of
+ * the calling thread is exported, which the shim caches per thread. */
+#[no_mangle]
+pub extern "C" fn leaf_symbolic_data_flag() -> *const bool {
+    leaf_runtime::pri::fast_path::symbolic_data_flag()
+}
@@ -1,3 +1,5 @@
+use leafrt as leaf_runtime;
+
 type PriImpl = leafrt::pri::NoOpPri;
 
 include!("../../ffi_template.rs");
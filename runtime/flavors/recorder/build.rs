@@ -0,0 +1,5 @@
+include!("../shared_build.rs");
+
+fn main() {
+    set_so_name();
+}
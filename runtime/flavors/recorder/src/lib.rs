@@ -0,0 +1,11 @@
+/* Records the PRI calls to a file (refer to `FileSink`) without any analysis,
+ * so that they can be replayed offline on the backends that do not read the
+ * program's memory (refer to `leaf_replay`).
+ * The references are assigned by a fluent PRI to match the ones the backends
+ * expect on replay. */
+type PriImpl = leaf_runtime::pri::events::RecordingPri<
+    leaf_runtime::pri::events::NullFluentPri,
+    leaf_runtime::pri::events::FileSink,
+>;
+
+include!("../../ffi_template.rs");
@@ -0,0 +1,88 @@
+/*
+ * The stable C API of the Leaf runtime library (libleafrt.so), for embedding
+ * the runtime in non-Rust harnesses and tools.
+ *
+ * Exported by the symbolic execution flavors of the runtime (see the `capi`
+ * module of the symex backend). The configuration overrides and the callbacks
+ * are only effective if they are set before the runtime is initialized, either
+ * by `leafrt_init` or by the first call from an instrumented program.
+ * As for instrumented programs, the type information of the program is loaded
+ * on initialization.
+ */
+
+#ifndef LEAFRT_H
+#define LEAFRT_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Incremented on any incompatible change to this header. */
+#define LEAFRT_API_VERSION 1
+
+#define LEAFRT_OK 0
+#define LEAFRT_ERR_INVALID_ARG 1
+/* The runtime is already initialized. */
+#define LEAFRT_ERR_INITIALIZED 2
+
+/* The location of a basic block in the program. */
+typedef struct leafrt_location {
+    uint8_t instance_kind;
+    uint32_t crate_num;
+    uint32_t def_index;
+    uint32_t basic_block;
+} leafrt_location_t;
+
+typedef enum leafrt_decision_kind {
+    LEAFRT_DECISION_TRUE = 0,
+    LEAFRT_DECISION_FALSE = 1,
+    /* The discriminant is equal to one of the cases. */
+    LEAFRT_DECISION_ONE_OF = 2,
+    /* The discriminant is equal to none of the cases. */
+    LEAFRT_DECISION_NONE_OF = 3,
+} leafrt_decision_kind_t;
+
+/* A decision taken in the execution.
+ * The discriminant and the cases are in their textual form, and are only
+ * valid during the callback. */
+typedef struct leafrt_decision {
+    leafrt_location_t location;
+    leafrt_decision_kind_t kind;
+    bool is_symbolic;
+    const char *discr;
+    const char *const *cases;
+    size_t cases_len;
+} leafrt_decision_t;
+
+typedef void (*leafrt_decision_callback_t)(const leafrt_decision_t *decision, void *user_data);
+
+/* Returns the version of the API implemented by the loaded library,
+ * to be checked against `LEAFRT_API_VERSION`. */
+uint32_t leafrt_api_version(void);
+
+void leafrt_init(void);
+
+/* Flushes all the outputs (e.g., generated inputs). */
+void leafrt_shutdown(void);
+
+/* Sets a configuration entry, taking precedence over the configuration file
+ * and the environment. Nested keys are separated by dots, e.g.,
+ * `leafrt_config_set("solver.timeout_ms", "10000")`. */
+int leafrt_config_set(const char *key, const char *value);
+
+/* Registers a callback for the decisions on symbolic values, i.e., the
+ * constraints of the path. */
+int leafrt_on_constraint(leafrt_decision_callback_t callback, void *user_data);
+
+/* Registers a callback for all decisions, i.e., the branches taken. */
+int leafrt_on_branch(leafrt_decision_callback_t callback, void *user_data);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* LEAFRT_H */
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-use super::{BasicBlockLocation, Constraint, ConstraintKind, FuncDef, IntType, ValueType};
+use super::{
+    BasicBlockLocation, CalleeDef, Constraint, ConstraintKind, FuncDef, IntType, ValueType,
+};
 
 pub trait Shutdown {
     fn shutdown(&mut self);
@@ -24,6 +26,35 @@ pub trait Solver {
         &mut self,
         constraints: impl Iterator<Item = Constraint<Self::Value, Self::Case>>,
     ) -> SolveResult<Self::Model>;
+
+    /// Checks the constraints along with the assumption.
+    /// # Returns
+    /// The result, and whether the constraints are found to be unsatisfiable
+    /// regardless of the assumption (only if the result is [`SolveResult::Unsat`]).
+    /// # Remarks
+    /// By default, the constraints are checked again on their own if the result is
+    /// unsatisfiable. Solvers with incremental solving should override it to
+    /// find both in a single query.
+    fn check_assuming(
+        &mut self,
+        constraints: impl Iterator<Item = Constraint<Self::Value, Self::Case>>,
+        assumption: Constraint<Self::Value, Self::Case>,
+    ) -> (SolveResult<Self::Model>, bool)
+    where
+        Self::Value: Clone,
+        Self::Case: Clone,
+    {
+        let constraints = constraints.collect::<Vec<_>>();
+        let result = self.check(
+            constraints
+                .iter()
+                .cloned()
+                .chain(core::iter::once(assumption)),
+        );
+        let is_unconditionally_unsat = matches!(result, SolveResult::Unsat)
+            && matches!(self.check(constraints.into_iter()), SolveResult::Unsat);
+        (result, is_unconditionally_unsat)
+    }
 }
 
 /// The result of the checking performed by [`Solver`].
@@ -70,7 +101,9 @@ pub trait CoreTypeProvider<V> {
 }
 
 pub trait PhasedCallTraceRecorder {
-    fn start_call(&mut self, call_site: BasicBlockLocation<FuncDef>);
+    /// # Arguments
+    /// - `callee`: The expected callee if known statically.
+    fn start_call(&mut self, call_site: BasicBlockLocation<FuncDef>, callee: Option<CalleeDef>);
 
     fn finish_call(&mut self, entered_func: FuncDef, broken: Option<bool>);
 
@@ -114,6 +114,12 @@ impl Display for BinaryOp {
             BinaryOp::RotateL => "_<_",
             BinaryOp::RotateR => "_>_",
             BinaryOp::CarrylessMul => "*^",
+            BinaryOp::Nand => "!&",
+            BinaryOp::Min => "min",
+            BinaryOp::Max => "max",
+            BinaryOp::Powi => "powi",
+            BinaryOp::Minimum => "minimum",
+            BinaryOp::Maximum => "maximum",
             BinaryOp::Eq => "==",
             BinaryOp::Ne => "!=",
             BinaryOp::Lt => "<",
@@ -139,6 +145,8 @@ impl Display for UnaryOp {
             UnaryOp::LeadingZeros => "ctlz",
             UnaryOp::NonZeroLeadingZeros => "ctlz_nonzero",
             UnaryOp::ByteSwap => "↔",
+            UnaryOp::Sqrt => "√",
+            UnaryOp::Abs => "abs",
         })
     }
 }
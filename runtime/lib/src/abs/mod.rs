@@ -41,6 +41,12 @@ pub enum BinaryOp {
     RotateL = common::pri::BinaryOp::ROT_L.to_raw(),
     RotateR = common::pri::BinaryOp::ROT_R.to_raw(),
     CarrylessMul = common::pri::BinaryOp::CARRYLESS_MUL.to_raw(),
+    Nand = common::pri::BinaryOp::NAND.to_raw(),
+    Min = common::pri::BinaryOp::MIN.to_raw(),
+    Max = common::pri::BinaryOp::MAX.to_raw(),
+    Powi = common::pri::BinaryOp::POWI.to_raw(),
+    Minimum = common::pri::BinaryOp::MINIMUM.to_raw(),
+    Maximum = common::pri::BinaryOp::MAXIMUM.to_raw(),
 
     Eq = common::pri::BinaryOp::EQ.to_raw(),
     Lt = common::pri::BinaryOp::LT.to_raw(),
@@ -66,6 +72,8 @@ pub enum UnaryOp {
     NonZeroLeadingZeros = common::pri::UnaryOp::CTLZ_NONZERO.to_raw(),
     LeadingZeros = common::pri::UnaryOp::CTLZ.to_raw(),
     ByteSwap = common::pri::UnaryOp::BSWAP.to_raw(),
+    Sqrt = common::pri::UnaryOp::SQRT.to_raw(),
+    Abs = common::pri::UnaryOp::ABS.to_raw(),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -122,7 +130,15 @@ pub enum Constant {
     Str(&'static str),
     ByteStr(&'static [u8]),
     Addr(RawAddress),
+    /// Constant materialized in memory (e.g., a large aggregate), given by its bytes and type.
+    ByRef {
+        bytes: &'static [u8],
+        ty: TypeId,
+    },
     Zst,
+    /// A function item, which is zero-sized and identifies the function by its type.
+    #[from(ignore)]
+    FnItem(TypeId),
     /// Constant of some type that is not modeled by instrumentation but exists in MIR.
     Some,
 }
@@ -132,6 +148,24 @@ pub struct SymVariable<C> {
     pub conc_value: Option<C>,
 }
 
+/// The metadata given by the program for the symbolic variables it creates,
+/// e.g., by `mark_symbolic_named`.
+#[derive(Debug, Clone, Copy)]
+pub struct SymVariableInfo {
+    pub name: &'static str,
+    /// The name of the type of the value being marked symbolic.
+    pub type_name: &'static str,
+    pub location: SourceLocation,
+}
+
+#[derive(Debug, Clone, Copy, dm::Display)]
+#[display("{file}:{line}:{col}")]
+pub struct SourceLocation {
+    pub file: &'static str,
+    pub line: u32,
+    pub col: u32,
+}
+
 #[derive(Debug)]
 pub enum AssertKind<O> {
     BoundsCheck { len: O, index: O },
@@ -192,7 +226,7 @@ impl PrimitiveType {
 }
 
 // FIXME: Replace with PrimitiveType
-#[derive(Clone, Copy, Debug, PartialEq, Eq, derive_more::From)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, derive_more::From)]
 pub enum ValueType {
     Bool,
     Char,
@@ -4,7 +4,10 @@ use derive_more as dm;
 
 use common::{log_debug, log_trace, log_warn};
 
-use crate::abs::{CalleeDef, FuncDef, PlaceUsage};
+use crate::{
+    abs::{CalleeDef, FuncDef, PlaceUsage},
+    utils::PerThread,
+};
 
 // FIXME: Completely separate data and control flow for data agnostic backends.
 
@@ -139,6 +142,13 @@ pub trait CallFlowManager {
 
     fn prepare_for_call(&mut self);
 
+    /// Notifies that the current thread is started.
+    /// # Remarks
+    /// The call flow of each thread is tracked separately, thus the first
+    /// function entered in a thread is not mistaken for a call from the
+    /// functions of the other threads.
+    fn start_thread(&mut self);
+
     fn enter(&mut self, entered_func: FuncDef) -> CallFlowSanity;
 
     fn start_return(&mut self) -> Self::ReturnToken;
@@ -370,17 +380,31 @@ mod implementation {
     use super::*;
 
     pub struct DefaultCallFlowManager<P, V, BC, S = ()> {
+        /// The call flow of each thread, as the threads start with their own stacks.
+        threads: PerThread<ThreadCallFlow<P, V, S>>,
+
+        /// The callback to be called when breakage in the call flow is detected.
+        breakage_callback: BC,
+
+        log_span: tracing::span::EnteredSpan,
+    }
+
+    struct ThreadCallFlow<P, V, S> {
         /// Stacked storage that holds data living during the function execution.
         stack: Vec<StackInfo<P, V, S>>,
 
         /// Non-stacked storage that holds data living during call transfers or
         /// between consecutive operations in the function.
         ephemeral: EphemeralInfo<V>,
+    }
 
-        /// The callback to be called when breakage in the call flow is detected.
-        breakage_callback: BC,
-
-        log_span: tracing::span::EnteredSpan,
+    impl<P, V, S> Default for ThreadCallFlow<P, V, S> {
+        fn default() -> Self {
+            Self {
+                stack: vec![],
+                ephemeral: EphemeralInfo::default(),
+            }
+        }
     }
 
     struct StackInfo<P, V, S> {
@@ -476,8 +500,7 @@ mod implementation {
     impl<P, V, BC, S> DefaultCallFlowManager<P, V, BC, S> {
         pub fn new(breakage_callback: BC) -> Self {
             Self {
-                stack: vec![],
-                ephemeral: EphemeralInfo::default(),
+                threads: PerThread::new(),
                 log_span: tracing::Span::none().entered(),
                 breakage_callback,
             }
@@ -500,7 +523,7 @@ mod implementation {
         {
             self.clear_entrance();
 
-            if self.ephemeral.from_caller.is_none() {
+            if self.thread_mut().ephemeral.from_caller.is_none() {
                 self.log_span_start_trans(logging::TransitionDirection::Call);
             }
 
@@ -514,33 +537,41 @@ mod implementation {
                 };
             }
 
-            let parcel = self.ephemeral.from_caller.get_or_insert_default();
+            let parcel = self
+                .thread_mut()
+                .ephemeral
+                .from_caller
+                .get_or_insert_default();
             if let Some(expected_func) = expected_func {
-                debug_assert!(
-                    parcel.expected_func.is_none(),
-                    cleanup_msg!(),
-                    self.ephemeral.from_caller,
-                );
+                debug_assert!(parcel.expected_func.is_none(), cleanup_msg!(), parcel);
                 parcel.expected_func = Some(expected_func);
             }
             if let Some(args) = args {
-                debug_assert!(
-                    parcel.args.is_none(),
-                    cleanup_msg!(),
-                    self.ephemeral.from_caller,
-                );
+                debug_assert!(parcel.args.is_none(), cleanup_msg!(), parcel);
                 parcel.args = Some(args);
             }
         }
 
+        fn stack(&self) -> &[StackInfo<P, V, S>] {
+            self.threads
+                .current()
+                .map(|t| t.stack.as_slice())
+                .unwrap_or_default()
+        }
+
+        fn thread_mut(&mut self) -> &mut ThreadCallFlow<P, V, S> {
+            self.threads.current_mut()
+        }
+
         fn top_frame(&mut self) -> &mut StackInfo<P, V, S> {
-            self.stack
+            self.thread_mut()
+                .stack
                 .last_mut()
                 .expect("Call stack should not be empty")
         }
 
         fn current_func(&self) -> FuncDef {
-            self.stack
+            self.stack()
                 .last()
                 .expect("Call stack should not be empty")
                 .def
@@ -548,15 +579,15 @@ mod implementation {
         }
 
         fn latest_caller_func(&self) -> FuncDef {
-            self.stack
-                .get(self.stack.len().saturating_sub(2))
+            self.stack()
+                .get(self.stack().len().saturating_sub(2))
                 .expect("A caller was expected.")
                 .def
                 .clone()
         }
 
         fn clear_entrance(&mut self) {
-            self.ephemeral.entrance = None;
+            self.thread_mut().ephemeral.entrance = None;
         }
     }
 
@@ -806,18 +837,31 @@ mod implementation {
             self.prepare_for_call_partial(None, None);
         }
 
+        fn start_thread(&mut self) {
+            let thread = self.thread_mut();
+            if !thread.stack.is_empty() {
+                log_warn!(
+                    target: TAG,
+                    "Thread start is notified while the thread has {} frames.",
+                    thread.stack.len(),
+                );
+            }
+            log_debug!(target: TAG, "Thread started: {:?}", std::thread::current().id());
+        }
+
         fn enter(&mut self, entered_func: FuncDef) -> CallFlowSanity {
             debug_assert!(
-                self.ephemeral.entrance.is_none(),
+                self.thread_mut().ephemeral.entrance.is_none(),
                 concat!(
                     "The entrance parcel is not consumed or cleaned correctly. ",
                     "This is due to a problem in external function handling or instrumentation. ",
                     "{:?}",
                 ),
-                self.ephemeral.entrance,
+                self.thread_mut().ephemeral.entrance,
             );
 
-            let entrance = if let Some(call_info) = self.ephemeral.from_caller.take() {
+            let from_caller = self.thread_mut().ephemeral.from_caller.take();
+            let entrance = if let Some(call_info) = from_caller {
                 if let Some(expected_func) = call_info.expected_func {
                     if entered_func == expected_func {
                         log_trace!(
@@ -839,12 +883,12 @@ mod implementation {
                     EntranceInfo(CallFlowSanity::Unknown(Some(call_info)))
                 }
             } else {
-                if let Some(from_callee) = self.ephemeral.from_callee.take() {
+                if let Some(from_callee) = self.thread_mut().ephemeral.from_callee.take() {
                     log_debug!(target: TAG, "External 4: No call information available");
                     EntranceInfo(CallFlowSanity::Broken(Either::Right(from_callee)))
                 } else {
                     log_debug!(target: TAG, "External 2: No call information available");
-                    if !self.stack.is_empty() {
+                    if !self.stack().is_empty() {
                         log_warn!(
                             target: TAG,
                             "Observing unexpected call. Probable caller: {}, entered: {}.",
@@ -857,21 +901,23 @@ mod implementation {
             };
             let sanity = entrance.0.no_info();
 
-            if let Some(parent_frame) = self.stack.last_mut() {
+            let stack = &mut self.thread_mut().stack;
+            if let Some(parent_frame) = stack.last_mut() {
                 parent_frame.latest_call_sanity = Some(sanity);
             }
 
-            self.stack.push(StackInfo {
+            let is_unexpected =
+                matches!(entrance.0, CallFlowSanity::Unknown(None)) && !stack.is_empty();
+            stack.push(StackInfo {
                 def: entered_func,
                 latest_call_sanity: None,
                 return_val_place: None,
                 overridden_return_val: None,
-                is_unexpected: matches!(entrance.0, CallFlowSanity::Unknown(None))
-                    && !self.stack.is_empty(),
+                is_unexpected,
                 user_storage: S::default(),
             });
 
-            self.ephemeral.entrance = Some(entrance);
+            self.thread_mut().ephemeral.entrance = Some(entrance);
 
             self.log_span_reset();
             log_debug!(target: TAG, "Entered the function");
@@ -883,7 +929,7 @@ mod implementation {
             let current_func = self.current_func();
 
             self.clear_entrance();
-            if let Some(from_caller) = self.ephemeral.from_callee.take() {
+            if let Some(from_caller) = self.thread_mut().ephemeral.from_callee.take() {
                 log_warn!(
                     target: TAG,
                     concat!(
@@ -895,11 +941,11 @@ mod implementation {
                 );
             }
 
-            let popped_frame = self.stack.pop().unwrap();
+            let popped_frame = self.thread_mut().stack.pop().unwrap();
 
             self.log_span_reset();
 
-            let caller_frame = self.stack.last();
+            let caller_frame = self.stack().last();
             if caller_frame
                 .is_some_and(|f| !f.latest_call_sanity.unwrap().is_broken().unwrap_or(false))
                 && !popped_frame.is_unexpected
@@ -908,7 +954,7 @@ mod implementation {
             }
 
             if !popped_frame.is_unexpected {
-                self.ephemeral.from_callee = Some(CalleeParcel {
+                self.thread_mut().ephemeral.from_callee = Some(CalleeParcel {
                     func: current_func,
                     return_val: None,
                 });
@@ -932,26 +978,27 @@ mod implementation {
                     CallFlowSanity::Broken(())
                 });
 
+            let ephemeral = &mut self.thread_mut().ephemeral;
             match sanity {
                 CallFlowSanity::Expected(..) | CallFlowSanity::Unknown(..) => {
-                    CallFlowSanity::Expected(self.ephemeral.from_callee.take().expect(
+                    CallFlowSanity::Expected(ephemeral.from_callee.take().expect(
                         "Callee parcel is expected to be set when the sanity is not broken.",
                     ))
                 }
                 CallFlowSanity::Broken(..) => CallFlowSanity::Broken(
-                    if let Some(from_caller) = self.ephemeral.from_caller.take() {
+                    if let Some(from_caller) = ephemeral.from_caller.take() {
                         debug_assert!(
-                            self.ephemeral.from_callee.is_none(),
+                            ephemeral.from_callee.is_none(),
                             concat!(
                                 "Both caller and callee parcels are available. ",
                                 "This is due to a problem in external function handling or instrumentation. ",
                                 "{:?}, {:?}",
                             ),
                             from_caller,
-                            self.ephemeral.from_callee,
+                            ephemeral.from_callee,
                         );
                         Either::Left(from_caller)
-                    } else if let Some(from_callee) = self.ephemeral.from_callee.take() {
+                    } else if let Some(from_callee) = ephemeral.from_callee.take() {
                         Either::Right(from_callee)
                     } else {
                         panic!("Caller parcel is consumed but no callee parcel is available.")
@@ -1065,7 +1112,12 @@ mod implementation {
             }
 
             if !token.popped_frame.is_unexpected {
-                self.ephemeral.from_callee.as_mut().unwrap().return_val = Some(return_val);
+                self.thread_mut()
+                    .ephemeral
+                    .from_callee
+                    .as_mut()
+                    .unwrap()
+                    .return_val = Some(return_val);
             } else {
                 self.breakage_callback
                     .at_return_with_return_val(token.popped_frame.def, return_val);
@@ -1231,7 +1283,7 @@ mod implementation {
                     return;
                 }
 
-                if self.stack.len() == 0 {
+                if self.stack().is_empty() {
                     self.log_span = Span::none().entered();
                     return;
                 }
@@ -1241,7 +1293,7 @@ mod implementation {
                     target: TAG_STACK,
                     parent: Span::none(),
                     SPAN_CALL,
-                    { FIELD_DEPTH } = self.stack.len(),
+                    { FIELD_DEPTH } = self.stack().len(),
                 )
                 .entered();
             }
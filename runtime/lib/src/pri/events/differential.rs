@@ -0,0 +1,232 @@
+//! Differential testing of backends using recorded PRI event streams.
+//!
+//! The same stream is replayed on two PRIs step by step, and after each step
+//! an observation (e.g., the constraints collected so far) is taken from both
+//! and compared. This is mainly meant for validating redesigns of a backend
+//! (such as a new state implementation) against the existing one; the first
+//! step at which they disagree is reported.
+//!
+//! ```ignore
+//! let events = take_recorded_events();
+//! let result = run_differential::<BasicPri, OptimizedPri, _>(
+//!     &events,
+//!     || basic_constraints(),
+//!     || optimized_constraints(),
+//! );
+//! ```
+//!
+//! The constraints that the backends generate are compared the same way by
+//! [`run_constraint_differential`], for which the backends make their
+//! constraints observable using [`ConstraintObserver`].
+//!
+//! As PRIs are static, the two PRIs must not share their instance manager.
+
+use core::{cell::RefCell, fmt::Display};
+
+use crate::{abs::Constraint, trace::StepInspector};
+
+use super::{PriEvent, ReplayTarget, Replayer};
+
+/// The point at which the two PRIs disagree.
+#[derive(Debug)]
+pub struct Divergence<O> {
+    /// The index of the event after which the divergence was observed.
+    pub step: usize,
+    /// The name of the PRI function called at the step.
+    pub func: &'static str,
+    pub kind: DivergenceKind<O>,
+}
+
+#[derive(Debug)]
+pub enum DivergenceKind<O> {
+    /// The returned references are different, thus the rest of the stream is
+    /// not meaningful for one of the PRIs.
+    Ret {
+        left: Option<common::pri::Ref>,
+        right: Option<common::pri::Ref>,
+    },
+    /// The observations are different.
+    Observation { left: O, right: O },
+}
+
+/// Replays the events on `L` and `R` and compares the observations after each step.
+/// # Returns
+/// The number of replayed steps if no divergence is found, otherwise the first divergence.
+pub fn run_differential<L: ReplayTarget, R: ReplayTarget, O: PartialEq>(
+    events: &[PriEvent],
+    mut observe_left: impl FnMut() -> O,
+    mut observe_right: impl FnMut() -> O,
+) -> Result<usize, Divergence<O>> {
+    let left = Replayer::<L>::new();
+    let right = Replayer::<R>::new();
+
+    for (step, event) in events.iter().enumerate() {
+        let divergence = |kind| Divergence {
+            step,
+            func: event.func,
+            kind,
+        };
+
+        // Each side is observed right after its replay, so that observations are not mixed.
+        let left_ret = left.replay(event);
+        let left_obs = observe_left();
+        let right_ret = right.replay(event);
+        let right_obs = observe_right();
+
+        if left_ret != right_ret {
+            return Err(divergence(DivergenceKind::Ret {
+                left: left_ret,
+                right: right_ret,
+            }));
+        }
+
+        if left_obs != right_obs {
+            return Err(divergence(DivergenceKind::Observation {
+                left: left_obs,
+                right: right_obs,
+            }));
+        }
+    }
+
+    Ok(events.len())
+}
+
+thread_local! {
+    /// The constraints observed since the last take, if a constraint differential run is in progress.
+    static OBSERVED_CONSTRAINTS: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// Makes the constraints of a backend observable by [`run_constraint_differential`].
+/// It does nothing outside a constraint differential run.
+#[derive(Default, Clone, Copy)]
+pub struct ConstraintObserver;
+
+impl<S, V: Display, C: Display> StepInspector<S, V, C> for ConstraintObserver {
+    fn inspect(&mut self, _step: &S, constraint: Constraint<&V, &C>) {
+        OBSERVED_CONSTRAINTS.with_borrow_mut(|observed| {
+            if let Some(observed) = observed {
+                observed.push(constraint.to_string());
+            }
+        });
+    }
+}
+
+/// Replays the events on `L` and `R` and compares the constraints that each
+/// generates at each step.
+/// # Remarks
+/// The constraints are compared by their textual representation, so that
+/// backends with different value types can be compared.
+pub fn run_constraint_differential<L: ReplayTarget, R: ReplayTarget>(
+    events: &[PriEvent],
+) -> Result<usize, Divergence<Vec<String>>> {
+    OBSERVED_CONSTRAINTS.set(Some(Vec::new()));
+    let result =
+        run_differential::<L, R, _>(events, take_observed_constraints, take_observed_constraints);
+    OBSERVED_CONSTRAINTS.set(None);
+    result
+}
+
+fn take_observed_constraints() -> Vec<String> {
+    OBSERVED_CONSTRAINTS
+        .with_borrow_mut(|observed| observed.as_mut().map(core::mem::take).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use common::pri::ProgramRuntimeInterface;
+
+    use super::super::{RecordingPri, take_recorded_events};
+    use super::*;
+    use crate::pri::NoOpPri;
+
+    type Recorder = RecordingPri<NoOpPri>;
+
+    fn record_events() -> Vec<PriEvent> {
+        let dest = Recorder::ref_place_local(1);
+        let operand = Recorder::ref_operand_const_bool(true);
+        Recorder::assign_use(0, dest, operand);
+        let src = Recorder::ref_place_local(1);
+        Recorder::ref_operand_copy(src);
+        take_recorded_events()
+    }
+
+    #[test]
+    fn replays_recorded_events_in_order() {
+        let events = record_events();
+        assert_eq!(
+            events.iter().map(|e| e.func).collect::<Vec<_>>(),
+            [
+                "ref_place_local",
+                "ref_operand_const_bool",
+                "assign_use",
+                "ref_place_local",
+                "ref_operand_copy",
+            ],
+        );
+
+        let result = run_differential::<NoOpPri, Recorder, _>(&events, || (), || ());
+        assert!(matches!(result, Ok(5)));
+        // The replayed calls on the recorder are recorded again.
+        assert_eq!(take_recorded_events().len(), events.len());
+    }
+
+    #[test]
+    fn reports_first_diverging_observation() {
+        let events = record_events();
+
+        let mut left_steps = 0;
+        let mut right_steps = 0;
+        let result = run_differential::<NoOpPri, NoOpPri, _>(
+            &events,
+            || {
+                left_steps += 1;
+                left_steps
+            },
+            || {
+                right_steps += 1;
+                // Diverges after the assignment.
+                if right_steps >= 3 { 0 } else { right_steps }
+            },
+        );
+        let Err(divergence) = result else {
+            panic!("Expected a divergence: {result:?}");
+        };
+        assert_eq!(divergence.step, 2);
+        assert_eq!(divergence.func, "assign_use");
+        assert!(matches!(
+            divergence.kind,
+            DivergenceKind::Observation { left: 3, right: 0 }
+        ));
+    }
+
+    fn observe(constraint: &str) {
+        StepInspector::<(), _, _>::inspect(
+            &mut ConstraintObserver,
+            &(),
+            Constraint::equality(&constraint, &true),
+        );
+    }
+
+    #[test]
+    fn observes_constraints_only_in_constraint_runs() {
+        observe("outside");
+        assert!(take_observed_constraints().is_empty());
+
+        let events = record_events();
+        let result = run_constraint_differential::<NoOpPri, NoOpPri>(&events);
+        assert!(matches!(result, Ok(5)));
+
+        observe("after");
+        assert!(take_observed_constraints().is_empty());
+    }
+
+    #[test]
+    fn collects_observed_constraints_until_taken() {
+        OBSERVED_CONSTRAINTS.set(Some(Vec::new()));
+        observe("a");
+        observe("b");
+        assert_eq!(take_observed_constraints().len(), 2);
+        assert!(take_observed_constraints().is_empty());
+        OBSERVED_CONSTRAINTS.set(None);
+    }
+}
@@ -0,0 +1,238 @@
+//! Recording and replaying the stream of PRI calls.
+//!
+//! A PRI event is a single call to one of the functions of the PRI along with
+//! its arguments and the reference it returned (if any). Wrapping a PRI with
+//! [`RecordingPri`] captures the events in the order they are called, and
+//! [`Replayer`] feeds a captured stream to any other PRI with the same
+//! (Rust ABI) interface types. This makes it possible to run the same stream
+//! through multiple backends without running the program again
+//! (see [`differential`]).
+//!
+//! Recorded references are passed as is, thus the targets are expected to
+//! assign references in the same way as the recorded PRI. [`NullFluentPri`]
+//! assigns them the same way as the fluent PRIs of the backends, without any
+//! analysis behind it.
+//!
+//! Event streams can also be written to and read from files
+//! (see [`EventWriter`] and [`EventReader`]) to replay them offline.
+//! The recorded addresses are only valid in the recorded process, thus offline
+//! replay is limited to the PRIs that do not read the program's memory.
+
+/* NOTE: The recorded argument types are obtained from the parameter types that
+ * `RecordingPri` receives (with slices being made static). */
+macro_rules! static_slice_of {
+    ($t:ty) => { &'static [$t] };
+}
+
+macro_rules! list_func_decls_static {
+    ($modifier:path) => {
+        common::pri::list_func_decls! {
+            modifier: $modifier,
+            (
+                u128: u128,
+                char: char,
+                &str: &'static str,
+                &[u8]: &'static [u8],
+                slice: static_slice_of,
+                type_id: abs::TypeId,
+                primitive_type: abs::PrimitiveType,
+                binary_op: abs::BinaryOp,
+                unary_op: abs::UnaryOp,
+                atomic_ord: abs::AtomicOrdering,
+                atomic_bin_op: abs::AtomicBinaryOp,
+                dbg_info: DebugInfo,
+                tag: Tag,
+            )
+        }
+    };
+}
+
+pub mod differential;
+mod null;
+mod record;
+mod replay;
+mod serdes;
+
+use core::any::Any;
+
+use common::pri::{
+    AssertionInfo, DebugInfo, DynRawMetadata, InstanceKindId, PlaceMetadata,
+    ProgramRuntimeInterface, RawAddress, Ref, Tag,
+};
+
+use crate::abs;
+
+pub use null::{NullBackend, NullFluentPri, NullInstanceManager};
+pub use record::{EventSink, FileSink, InMemorySink, RecordingPri, take_recorded_events};
+pub use replay::Replayer;
+pub use serdes::{EventReader, EventWriter};
+
+use serdes::{Decode, Encode};
+
+/// A single call to a function of the PRI.
+pub struct PriEvent {
+    /// The name of the called PRI function.
+    pub func: &'static str,
+    /// The owned arguments as a tuple, in the order of the parameters.
+    args: Box<dyn EventArgs>,
+    /// The reference returned by the call, if the function returns any.
+    pub ret: Option<Ref>,
+}
+
+impl core::fmt::Debug for PriEvent {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PriEvent")
+            .field("func", &self.func)
+            .field("ret", &self.ret)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The type-erased arguments of an event.
+trait EventArgs: Any {
+    fn as_any(&self) -> &dyn Any;
+
+    fn encode(&self, w: &mut dyn std::io::Write) -> std::io::Result<()>;
+}
+
+impl<T: Any + Encode> EventArgs for T {
+    #[inline(always)]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    #[inline]
+    fn encode(&self, w: &mut dyn std::io::Write) -> std::io::Result<()> {
+        Encode::encode(self, w)
+    }
+}
+
+/// A PRI that events can be replayed on.
+///
+/// The interface types are fixed to the ones used by the runtime library's
+/// PRIs, so that recorded arguments can be passed without conversion.
+pub trait ReplayTarget:
+    ProgramRuntimeInterface<
+        U128 = u128,
+        Char = char,
+        ConstStr = &'static str,
+        ConstByteStr = &'static [u8],
+        TypeId = abs::TypeId,
+        PrimitiveType = abs::PrimitiveType,
+        BinaryOp = abs::BinaryOp,
+        UnaryOp = abs::UnaryOp,
+        AtomicOrdering = abs::AtomicOrdering,
+        AtomicBinaryOp = abs::AtomicBinaryOp,
+        DebugInfo = DebugInfo,
+        Tag = Tag,
+    >
+{
+    // Equality constraints on generic associated types are not supported yet.
+    fn slice<'a, T: 'a>(slice: &'a [T]) -> Self::Slice<'a, T>;
+}
+
+/// Conversion of a PRI argument to a value that can be kept after the call.
+trait Record {
+    type Owned: Replay + Encode + Decode;
+
+    fn to_owned_arg(self) -> Self::Owned;
+}
+
+/// Conversion of a recorded argument back to the parameter type of the target.
+trait Replay: 'static {
+    type Arg<'a, P: ReplayTarget>;
+
+    fn as_arg<'a, P: ReplayTarget>(&'a self) -> Self::Arg<'a, P>;
+}
+
+/// The element types of the slices appearing in the PRI.
+/// Byte slices are excluded as they are static and recorded as is.
+trait SliceElement: Copy + 'static {}
+impl SliceElement for u32 {}
+impl SliceElement for u128 {}
+impl SliceElement for char {}
+
+macro_rules! impl_record_as_is {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Record for $ty {
+                type Owned = Self;
+
+                #[inline(always)]
+                fn to_owned_arg(self) -> Self::Owned {
+                    self
+                }
+            }
+
+            impl Replay for $ty {
+                type Arg<'a, P: ReplayTarget> = Self;
+
+                #[inline(always)]
+                fn as_arg<'a, P: ReplayTarget>(&'a self) -> Self::Arg<'a, P> {
+                    *self
+                }
+            }
+        )*
+    };
+}
+
+impl_record_as_is!(
+    bool,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    char,
+    abs::TypeId,
+    RawAddress,
+    DynRawMetadata,
+    (DynRawMetadata, u64),
+    InstanceKindId,
+    AssertionInfo,
+    PlaceMetadata,
+    abs::PrimitiveType,
+    abs::BinaryOp,
+    abs::UnaryOp,
+    abs::AtomicOrdering,
+    abs::AtomicBinaryOp,
+    &'static str,
+    &'static [u8],
+);
+
+impl<'s, T: SliceElement> Record for &'s [T] {
+    type Owned = Vec<T>;
+
+    #[inline]
+    fn to_owned_arg(self) -> Self::Owned {
+        self.to_vec()
+    }
+}
+
+impl<T: SliceElement> Replay for Vec<T> {
+    type Arg<'a, P: ReplayTarget> = P::Slice<'a, T>;
+
+    #[inline]
+    fn as_arg<'a, P: ReplayTarget>(&'a self) -> Self::Arg<'a, P> {
+        P::slice(self.as_slice())
+    }
+}
+
+/// The return values of the PRI functions, i.e., unit or references.
+trait EventRet {
+    fn to_ret(&self) -> Option<Ref>;
+}
+
+impl EventRet for () {
+    #[inline(always)]
+    fn to_ret(&self) -> Option<Ref> {
+        None
+    }
+}
+
+impl EventRet for Ref {
+    #[inline(always)]
+    fn to_ret(&self) -> Option<Ref> {
+        Some(*self)
+    }
+}
@@ -0,0 +1,161 @@
+use core::cell::RefCell;
+
+use crate::{
+    abs::{BasicBlockIndex, PlaceUsage, backend::Shutdown},
+    pri::{
+        fluent::{
+            FluentPri, InstanceManager,
+            backend::{AssignmentHandler, RuntimeBackend, shared::noop::*},
+        },
+        refs::DefaultRefManager,
+    },
+};
+
+/// A fluent PRI with no analysis behind it.
+///
+/// The references are assigned by the same logic and the same kind of
+/// reference managers as the fluent PRIs of the backends, thus the streams
+/// recorded on it can be replayed on them.
+pub type NullFluentPri = FluentPri<NullInstanceManager>;
+
+type NullOperand = ();
+
+pub struct NullInstanceManager;
+
+thread_local! {
+    static BACKEND: RefCell<NullBackend> = const { RefCell::new(NullBackend) };
+    static PLACE_REF_MANAGER: RefCell<DefaultRefManager<NullPlace>> =
+        RefCell::new(DefaultRefManager::new());
+    static OPERAND_REF_MANAGER: RefCell<DefaultRefManager<NullOperand>> =
+        RefCell::new(DefaultRefManager::new());
+}
+
+impl InstanceManager for NullInstanceManager {
+    type PlaceInfo = NullPlace;
+    type Place = NullPlace;
+    type Operand = NullOperand;
+
+    type Backend = NullBackend;
+
+    type PlaceBuilder = NoOpPlaceBuilder<NullPlace, NullPlace>;
+
+    type PlaceRefManager = DefaultRefManager<NullPlace>;
+
+    type OperandRefManager = DefaultRefManager<NullOperand>;
+
+    fn init() {}
+
+    fn deinit() {}
+
+    fn perform_on_backend<T>(action: impl for<'a> FnOnce(&'a mut Self::Backend) -> T) -> T {
+        BACKEND.with_borrow_mut(action)
+    }
+
+    fn perform_on_place_ref_manager<T>(action: impl FnOnce(&mut Self::PlaceRefManager) -> T) -> T {
+        PLACE_REF_MANAGER.with_borrow_mut(action)
+    }
+
+    fn perform_on_operand_ref_manager<T>(
+        action: impl FnOnce(&mut Self::OperandRefManager) -> T,
+    ) -> T {
+        OPERAND_REF_MANAGER.with_borrow_mut(action)
+    }
+}
+
+pub struct NullBackend;
+
+impl RuntimeBackend for NullBackend {
+    type PlaceHandler<'a>
+        = NoOpPlaceHandler<Self::PlaceInfo, Self::Place>
+    where
+        Self: 'a;
+
+    type OperandHandler<'a>
+        = NoOpOperandHandler<Self::Place, Self::Operand>
+    where
+        Self: 'a;
+
+    type AssignmentHandler<'a>
+        = NoOpAssignmentHandler<Self::Place, Self::Operand>
+    where
+        Self: 'a;
+
+    type MemoryHandler<'a>
+        = NoOpLifetimeHandler
+    where
+        Self: 'a;
+
+    type RawMemoryHandler<'a>
+        = NoOpRawMemoryHandler<Self::Place, Self::Operand>
+    where
+        Self: 'a;
+
+    type ConstraintHandler<'a>
+        = NoOpConstraintHandler<Self::Operand, Self::DiscriminablePlace>
+    where
+        Self: 'a;
+
+    type CallHandler<'a>
+        = NoOpCallHandler<Self::Place, Self::Operand>
+    where
+        Self: 'a;
+
+    type DropHandler<'a>
+        = NoOpCallHandler<Self::Place, Self::Operand>
+    where
+        Self: 'a;
+
+    type AnnotationHandler<'a>
+        = NoOpAnnotationHandler
+    where
+        Self: 'a;
+
+    type PlaceInfo = NullPlace;
+    type Place = NullPlace;
+    type DiscriminablePlace = NullPlace;
+    type Operand = NullOperand;
+
+    fn place(&mut self, _usage: PlaceUsage) -> Self::PlaceHandler<'_> {
+        Default::default()
+    }
+
+    fn operand(&mut self) -> Self::OperandHandler<'_> {
+        Default::default()
+    }
+
+    fn assign_to<'a>(
+        &'a mut self,
+        _id: common::pri::AssignmentId,
+        _dest: <Self::AssignmentHandler<'a> as AssignmentHandler>::Place,
+    ) -> Self::AssignmentHandler<'a> {
+        Default::default()
+    }
+
+    fn memory<'a>(&'a mut self) -> Self::MemoryHandler<'a> {
+        Default::default()
+    }
+
+    fn raw_memory<'a>(&'a mut self) -> Self::RawMemoryHandler<'a> {
+        Default::default()
+    }
+
+    fn constraint_at(&mut self, _location: BasicBlockIndex) -> Self::ConstraintHandler<'_> {
+        Default::default()
+    }
+
+    fn call_control(&mut self) -> Self::CallHandler<'_> {
+        Default::default()
+    }
+
+    fn dropping(&mut self) -> Self::DropHandler<'_> {
+        Default::default()
+    }
+
+    fn annotate(&mut self) -> Self::AnnotationHandler<'_> {
+        Default::default()
+    }
+}
+
+impl Shutdown for NullBackend {
+    fn shutdown(&mut self) {}
+}
@@ -0,0 +1,164 @@
+use core::cell::RefCell;
+use std::{fs::File, io::BufWriter};
+
+use common::{log_info, pri::*};
+
+use crate::{abs, utils::current_thread_index};
+
+use super::{EventArgs, EventRet, EventWriter, PriEvent, Record, Replay, ReplayTarget};
+
+/// A PRI that delegates all calls to `P` and records them as events into `S`.
+pub struct RecordingPri<P, S = InMemorySink>(core::marker::PhantomData<(P, S)>);
+
+/// The destination of the recorded events.
+pub trait EventSink {
+    fn init() {}
+
+    fn push(event: PriEvent);
+
+    fn deinit() {}
+}
+
+/// Keeps the events in memory per thread.
+/// The events can be collected using [`take_recorded_events`].
+pub struct InMemorySink;
+
+thread_local! {
+    static RECORDED_EVENTS: RefCell<Vec<PriEvent>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Takes the events recorded so far on the current thread.
+pub fn take_recorded_events() -> Vec<PriEvent> {
+    RECORDED_EVENTS.with_borrow_mut(core::mem::take)
+}
+
+impl EventSink for InMemorySink {
+    #[inline]
+    fn push(event: PriEvent) {
+        RECORDED_EVENTS.with_borrow_mut(|events| events.push(event));
+    }
+}
+
+/// Writes the events to a file as they are recorded, buffered per thread.
+/// The path is taken from the environment variable `LEAF_PRI_EVENTS` and
+/// defaults to `pri_events.bin` in the current working directory.
+/// The events of each thread other than the first one go to a separate file
+/// with the index of the thread appended to the path (e.g., `pri_events.bin.1`).
+pub struct FileSink;
+
+thread_local! {
+    /* NOTE: The writers of the threads that are not shut down explicitly are
+     * flushed when the thread local is dropped. */
+    static FILE_WRITER: RefCell<Option<EventWriter<BufWriter<File>>>> =
+        const { RefCell::new(None) };
+}
+
+impl FileSink {
+    const ENV_PATH: &str = "LEAF_PRI_EVENTS";
+    const DEFAULT_PATH: &str = "pri_events.bin";
+
+    fn open() -> EventWriter<BufWriter<File>> {
+        let mut path =
+            std::env::var(Self::ENV_PATH).unwrap_or_else(|_| Self::DEFAULT_PATH.to_owned());
+        let thread_index = current_thread_index();
+        if thread_index != 0 {
+            path = format!("{path}.{thread_index}");
+        }
+        log_info!("Recording PRI events to `{}`", path);
+        File::create(&path)
+            .map(BufWriter::new)
+            .and_then(EventWriter::new)
+            .unwrap_or_else(|e| panic!("Failed to create the PRI events file `{path}`: {e}"))
+    }
+}
+
+impl EventSink for FileSink {
+    fn init() {
+        FILE_WRITER.with_borrow_mut(|writer| {
+            writer.get_or_insert_with(Self::open);
+        });
+    }
+
+    #[inline]
+    fn push(event: PriEvent) {
+        FILE_WRITER.with_borrow_mut(|writer| {
+            writer
+                .get_or_insert_with(Self::open)
+                .write(&event)
+                .expect("Failed to write a PRI event")
+        });
+    }
+
+    fn deinit() {
+        if let Some(writer) = FILE_WRITER.with_borrow_mut(Option::take) {
+            writer
+                .finish()
+                .expect("Failed to flush the PRI events file");
+        }
+    }
+}
+
+#[inline]
+fn record<S: EventSink>(func: &'static str, args: impl EventArgs, ret: Option<Ref>) {
+    S::push(PriEvent {
+        func,
+        args: Box::new(args),
+        ret,
+    });
+}
+
+macro_rules! record_and_delegate {
+    ($(#[$($attr: meta)*])* fn init_runtime_lib ($(,)?) $(-> $ret_ty:ty)?;) => {
+        $(#[$($attr)*])*
+        fn init_runtime_lib() {
+            S::init();
+            P::init_runtime_lib();
+            record::<S>("init_runtime_lib", (), None);
+        }
+    };
+    ($(#[$($attr: meta)*])* fn shutdown_runtime_lib ($(,)?) $(-> $ret_ty:ty)?;) => {
+        $(#[$($attr)*])*
+        fn shutdown_runtime_lib() {
+            P::shutdown_runtime_lib();
+            record::<S>("shutdown_runtime_lib", (), None);
+            S::deinit();
+        }
+    };
+    ($(#[$($attr: meta)*])* fn $name:ident ($($(#[$($arg_attr: meta)*])* $arg:ident : $arg_type:ty),* $(,)?) $(-> $ret_ty:ty)?;) => {
+        $(#[$($attr)*])*
+        #[inline]
+        fn $name ($($(#[$($arg_attr)*])* $arg : $arg_type),*) $(-> $ret_ty)? {
+            // Passing the owned arguments makes slices convertible to the ones of `P`.
+            let args = ($(Record::to_owned_arg($arg),)*);
+            let ($($arg,)*) = &args;
+            let result = P::$name($(Replay::as_arg::<P>($arg)),*);
+            record::<S>(stringify!($name), args, EventRet::to_ret(&result));
+            result
+        }
+    };
+}
+
+impl<P: ReplayTarget, S: EventSink> ProgramRuntimeInterface for RecordingPri<P, S> {
+    type U128 = u128;
+    type Char = char;
+    type ConstStr = &'static str;
+    type ConstByteStr = &'static [u8];
+    type Slice<'a, T: 'a> = &'a [T];
+    type TypeId = abs::TypeId;
+    type PrimitiveType = abs::PrimitiveType;
+    type BinaryOp = abs::BinaryOp;
+    type UnaryOp = abs::UnaryOp;
+    type AtomicOrdering = abs::AtomicOrdering;
+    type AtomicBinaryOp = abs::AtomicBinaryOp;
+    type DebugInfo = DebugInfo;
+    type Tag = Tag;
+
+    common::pri::list_func_decls! { modifier: record_and_delegate, (from Self) }
+}
+
+impl<P: ReplayTarget, S: EventSink> ReplayTarget for RecordingPri<P, S> {
+    #[inline(always)]
+    fn slice<'a, T: 'a>(slice: &'a [T]) -> Self::Slice<'a, T> {
+        slice
+    }
+}
@@ -0,0 +1,72 @@
+use core::any::Any;
+use std::collections::HashMap;
+
+use common::pri::*;
+
+use crate::abs;
+
+use super::{EventRet, PriEvent, Record, Replay, ReplayTarget};
+
+type ReplayFn = fn(&dyn Any) -> Option<Ref>;
+
+/// Feeds recorded events to the PRI `P`.
+pub struct Replayer<P> {
+    funcs: HashMap<&'static str, ReplayFn>,
+    _phantom: core::marker::PhantomData<P>,
+}
+
+impl<P: ReplayTarget> Default for Replayer<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: ReplayTarget> Replayer<P> {
+    pub fn new() -> Self {
+        Self {
+            funcs: Self::funcs(),
+            _phantom: Default::default(),
+        }
+    }
+
+    /// Calls the function of `P` corresponding to the event with its recorded arguments.
+    /// # Returns
+    /// The reference returned by `P`, if the function returns any.
+    pub fn replay(&self, event: &PriEvent) -> Option<Ref> {
+        let func = self
+            .funcs
+            .get(event.func)
+            .unwrap_or_else(|| panic!("Unknown PRI function: {}", event.func));
+        func(event.args.as_any())
+    }
+}
+
+macro_rules! replay_func {
+    ($(#[$($attr: meta)*])* fn $name:ident ($($(#[$($arg_attr: meta)*])* $arg:ident : $arg_type:ty),* $(,)?) $(-> $ret_ty:ty)?;) => {
+        #[allow(unused_parens)]
+        fn $name(args: &dyn Any) -> Option<Ref> {
+            let ($($arg,)*) = args
+                .downcast_ref::<($(<$arg_type as Record>::Owned,)*)>()
+                .expect(concat!("Unexpected arguments recorded for ", stringify!($name)));
+            EventRet::to_ret(&P::$name($(Replay::as_arg::<P>($arg)),*))
+        }
+    };
+}
+
+impl<P: ReplayTarget> Replayer<P> {
+    list_func_decls_static!(replay_func);
+}
+
+macro_rules! funcs_table {
+    ($($name:ident),* $(,)?) => {
+        impl<P: ReplayTarget> Replayer<P> {
+            fn funcs() -> HashMap<&'static str, ReplayFn> {
+                HashMap::from_iter([
+                    $((stringify!($name), Self::$name as ReplayFn),)*
+                ])
+            }
+        }
+    };
+}
+
+common::pri::pass_func_names_to!(funcs_table, all_comma_separated);
@@ -0,0 +1,389 @@
+//! A compact binary format for PRI event streams.
+//!
+//! The file starts with a magic number followed by the names of the PRI
+//! functions, so that events can refer to functions by their index, and
+//! streams remain readable if the order of functions changes. Each event is
+//! then written as the index of its function, its returned reference, and its
+//! arguments in order. All numbers are in little-endian.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    io::{self, Read, Write},
+    ptr::NonNull,
+};
+
+use common::pri::*;
+
+use crate::abs;
+
+use super::{EventArgs, PriEvent, Record};
+
+const MAGIC: &[u8; 8] = b"LEAFPRI\x01";
+
+pub(super) trait Encode {
+    fn encode(&self, w: &mut dyn Write) -> io::Result<()>;
+}
+
+pub(super) trait Decode: Sized {
+    fn decode(r: &mut dyn Read) -> io::Result<Self>;
+}
+
+fn invalid_data(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+macro_rules! impl_for_num {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Encode for $ty {
+                #[inline]
+                fn encode(&self, w: &mut dyn Write) -> io::Result<()> {
+                    w.write_all(&self.to_le_bytes())
+                }
+            }
+
+            impl Decode for $ty {
+                #[inline]
+                fn decode(r: &mut dyn Read) -> io::Result<Self> {
+                    let mut bytes = [0; core::mem::size_of::<$ty>()];
+                    r.read_exact(&mut bytes)?;
+                    Ok(<$ty>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+impl_for_num!(u8, i8, u16, u32, u64, u128);
+
+/// Implements the traits by converting from/to another encodable type.
+macro_rules! impl_via {
+    ($ty:ty => $repr:ty, |$v:ident| $to:expr, |$r:ident| $from:expr) => {
+        impl Encode for $ty {
+            #[inline]
+            fn encode(&self, w: &mut dyn Write) -> io::Result<()> {
+                let $v = *self;
+                Encode::encode(&$to, w)
+            }
+        }
+
+        impl Decode for $ty {
+            #[inline]
+            fn decode(r: &mut dyn Read) -> io::Result<Self> {
+                let $r = <$repr>::decode(r)?;
+                $from
+            }
+        }
+    };
+}
+
+impl_via!(bool => u8, |v| v as u8, |r| Ok(r != 0));
+impl_via!(usize => u64, |v| v as u64, |r| Ok(r as usize));
+impl_via!(char => u32, |v| v as u32, |r| {
+    char::from_u32(r).ok_or_else(|| invalid_data("Invalid char"))
+});
+impl_via!(abs::TypeId => u128, |v| v.get(), |r| {
+    abs::TypeId::new(r).ok_or_else(|| invalid_data("Invalid type id"))
+});
+/* NOTE: Addresses belong to the recorded process and are only kept as values. */
+impl_via!(RawAddress => u64, |v| v.addr() as u64, |r| {
+    Ok(core::ptr::without_provenance::<()>(r as usize))
+});
+impl_via!(DynRawMetadata => u64, |v| {
+    // Safe: `DynRawMetadata` is a transparent wrapper around a non-null pointer.
+    unsafe { core::mem::transmute::<DynRawMetadata, NonNull<()>>(v) }.addr().get() as u64
+}, |r| {
+    NonNull::new(core::ptr::without_provenance_mut::<()>(r as usize))
+        .map(|ptr| unsafe { core::mem::transmute::<NonNull<()>, DynRawMetadata>(ptr) })
+        .ok_or_else(|| invalid_data("Invalid dyn metadata"))
+});
+impl_via!(InstanceKindId => (u8, u32, u32), |v| (v.0, v.1.0, v.1.1), |r| {
+    Ok(InstanceKindId(r.0, common::types::DefId(r.1, r.2)))
+});
+impl_via!(AssertionInfo => (u32, u32, bool, &'static str), |v| {
+    (v.location, v.condition, v.expected, <&'static str>::from(v.message))
+}, |r| {
+    Ok(AssertionInfo {
+        location: r.0,
+        condition: r.1,
+        expected: r.2,
+        message: common::ffi::NoCallFrom::from(r.3),
+    })
+});
+impl_via!(PlaceMetadata => (u8, RawAddress, u128, i8, u64), |v| {
+    (v.flags, v.address, v.type_id.into(), v.primitive_type.to_raw(), v.size)
+}, |r| {
+    Ok(PlaceMetadata {
+        flags: r.0,
+        address: r.1,
+        type_id: r.2.into(),
+        primitive_type: common::pri::PrimitiveType(r.3),
+        size: r.4,
+    })
+});
+impl_via!(abs::PrimitiveType => i8, |v| v as i8, |r| {
+    Ok(common::pri::PrimitiveType::from_raw(r).into())
+});
+impl_via!(abs::BinaryOp => u8, |v| v as u8, |r| {
+    Ok(common::pri::BinaryOp::from_raw(r).into())
+});
+impl_via!(abs::UnaryOp => u8, |v| v as u8, |r| {
+    Ok(common::pri::UnaryOp::from_raw(r).into())
+});
+impl_via!(abs::AtomicOrdering => u8, |v| v as u8, |r| {
+    Ok(common::pri::AtomicOrdering::from_raw(r).into())
+});
+impl_via!(abs::AtomicBinaryOp => u8, |v| v as u8, |r| {
+    Ok(common::pri::AtomicBinaryOp::from_raw(r).into())
+});
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, w: &mut dyn Write) -> io::Result<()> {
+        (self.len() as u32).encode(w)?;
+        self.iter().try_for_each(|item| item.encode(w))
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    #[inline]
+    fn encode(&self, w: &mut dyn Write) -> io::Result<()> {
+        self.as_slice().encode(w)
+    }
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    fn decode(r: &mut dyn Read) -> io::Result<Self> {
+        let len = u32::decode(r)? as usize;
+        (0..len).map(|_| T::decode(r)).collect()
+    }
+}
+
+impl Encode for &'static [u8] {
+    #[inline]
+    fn encode(&self, w: &mut dyn Write) -> io::Result<()> {
+        <[u8] as Encode>::encode(self, w)
+    }
+}
+
+impl Encode for &'static str {
+    #[inline]
+    fn encode(&self, w: &mut dyn Write) -> io::Result<()> {
+        <[u8] as Encode>::encode(self.as_bytes(), w)
+    }
+}
+
+thread_local! {
+    /* Strings (e.g., tags and debug info) are mostly repeated, so they are
+     * interned to leak each distinct one only once. */
+    static INTERNED: RefCell<HashSet<&'static [u8]>> = RefCell::new(HashSet::new());
+}
+
+impl Decode for &'static [u8] {
+    fn decode(r: &mut dyn Read) -> io::Result<Self> {
+        let bytes = Vec::<u8>::decode(r)?;
+        Ok(
+            INTERNED.with_borrow_mut(|interned| match interned.get(bytes.as_slice()) {
+                Some(existing) => *existing,
+                None => {
+                    let leaked: &'static [u8] = bytes.leak();
+                    interned.insert(leaked);
+                    leaked
+                }
+            }),
+        )
+    }
+}
+
+impl Decode for &'static str {
+    fn decode(r: &mut dyn Read) -> io::Result<Self> {
+        let bytes = <&'static [u8]>::decode(r)?;
+        core::str::from_utf8(bytes).map_err(|e| invalid_data(e.to_string()))
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, w: &mut dyn Write) -> io::Result<()> {
+        match self {
+            Some(value) => {
+                true.encode(w)?;
+                value.encode(w)
+            }
+            None => false.encode(w),
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    fn decode(r: &mut dyn Read) -> io::Result<Self> {
+        if bool::decode(r)? {
+            T::decode(r).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+macro_rules! impl_for_tuple {
+    ($($t:ident),*) => {
+        impl<$($t: Encode),*> Encode for ($($t,)*) {
+            #[allow(non_snake_case, unused_variables)]
+            #[inline]
+            fn encode(&self, w: &mut dyn Write) -> io::Result<()> {
+                let ($($t,)*) = self;
+                $($t.encode(w)?;)*
+                Ok(())
+            }
+        }
+
+        impl<$($t: Decode),*> Decode for ($($t,)*) {
+            #[allow(unused_variables)]
+            #[inline]
+            fn decode(r: &mut dyn Read) -> io::Result<Self> {
+                Ok(($($t::decode(r)?,)*))
+            }
+        }
+    };
+}
+
+impl_for_tuple!();
+impl_for_tuple!(A);
+impl_for_tuple!(A, B);
+impl_for_tuple!(A, B, C);
+impl_for_tuple!(A, B, C, D);
+impl_for_tuple!(A, B, C, D, E);
+impl_for_tuple!(A, B, C, D, E, F);
+impl_for_tuple!(A, B, C, D, E, F, G);
+impl_for_tuple!(A, B, C, D, E, F, G, H);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_for_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+type DecodeFn = fn(&mut dyn Read) -> io::Result<Box<dyn EventArgs>>;
+
+mod decoders {
+    use super::*;
+
+    macro_rules! decode_func {
+        ($(#[$($attr: meta)*])* fn $name:ident ($($(#[$($arg_attr: meta)*])* $arg:ident : $arg_type:ty),* $(,)?) $(-> $ret_ty:ty)?;) => {
+            #[allow(unused_parens)]
+            pub(super) fn $name(r: &mut dyn Read) -> io::Result<Box<dyn EventArgs>> {
+                <($(<$arg_type as Record>::Owned,)*)>::decode(r)
+                    .map(|args| Box::new(args) as Box<dyn EventArgs>)
+            }
+        };
+    }
+
+    list_func_decls_static!(decode_func);
+}
+
+macro_rules! funcs_table {
+    ($($name:ident),* $(,)?) => {
+        const FUNC_NAMES: &[&str] = &[$(stringify!($name)),*];
+
+        fn decoders() -> HashMap<&'static str, DecodeFn> {
+            HashMap::from_iter([
+                $((stringify!($name), decoders::$name as DecodeFn),)*
+            ])
+        }
+    };
+}
+
+common::pri::pass_func_names_to!(funcs_table, all_comma_separated);
+
+/// Writes PRI events in the binary format.
+pub struct EventWriter<W: Write> {
+    inner: W,
+    func_indices: HashMap<&'static str, u16>,
+}
+
+impl<W: Write> EventWriter<W> {
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        inner.write_all(MAGIC)?;
+        FUNC_NAMES.encode(&mut inner)?;
+        Ok(Self {
+            inner,
+            func_indices: FUNC_NAMES
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (*name, i as u16))
+                .collect(),
+        })
+    }
+
+    pub fn write(&mut self, event: &PriEvent) -> io::Result<()> {
+        let index = self
+            .func_indices
+            .get(event.func)
+            .ok_or_else(|| invalid_data(format!("Unknown PRI function: {}", event.func)))?;
+        index.encode(&mut self.inner)?;
+        event.ret.encode(&mut self.inner)?;
+        event.args.encode(&mut self.inner)
+    }
+
+    /// Flushes the written events and returns the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+/// Reads PRI events in the binary format.
+pub struct EventReader<R: Read> {
+    inner: R,
+    /// The functions by their indices in the stream.
+    funcs: Vec<(&'static str, DecodeFn)>,
+}
+
+impl<R: Read> EventReader<R> {
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let mut magic = [0; MAGIC.len()];
+        inner.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("Not a PRI events stream"));
+        }
+
+        let decoders = decoders();
+        let funcs = Vec::<&'static str>::decode(&mut inner)?
+            .into_iter()
+            .map(|name| {
+                decoders
+                    .get_key_value(name)
+                    .map(|(name, decode)| (*name, *decode))
+                    .ok_or_else(|| invalid_data(format!("Unknown PRI function: {name}")))
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self { inner, funcs })
+    }
+
+    fn read_event(&mut self, index: u16) -> io::Result<PriEvent> {
+        let (func, decode) = self
+            .funcs
+            .get(index as usize)
+            .copied()
+            .ok_or_else(|| invalid_data(format!("Invalid function index: {index}")))?;
+        let ret = Option::<Ref>::decode(&mut self.inner)?;
+        let args = decode(&mut self.inner)?;
+        Ok(PriEvent { func, args, ret })
+    }
+}
+
+impl<R: Read> Iterator for EventReader<R> {
+    type Item = io::Result<PriEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut index = [0; core::mem::size_of::<u16>()];
+        // A clean end of the stream is only possible at the event boundaries.
+        match self.inner.read(&mut index[..1]) {
+            Ok(0) => return None,
+            Ok(_) => {}
+            Err(e) => return Some(Err(e)),
+        }
+        Some(
+            self.inner
+                .read_exact(&mut index[1..])
+                .and_then(|_| self.read_event(u16::from_le_bytes(index))),
+        )
+    }
+}
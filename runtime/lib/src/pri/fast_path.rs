@@ -0,0 +1,87 @@
+//! The runtime side of the concrete fast path in the shim.
+//!
+//! When enabled in the shim, PRI calls other than the control flow events
+//! (calls, branches, etc.) are skipped as long as no symbolic data is present
+//! in the current frame.
+//!
+//! # Remarks
+//! The flag is kept per frame of each thread and is maintained conservatively,
+//! as a frame without symbolic operands may still read symbolic data through
+//! pointers:
+//! - A callee inherits the flag of the caller, unless the backend reports that
+//!   none of the arguments may carry symbolic data (see [`notify_call`]).
+//! - A caller gets the flag of the callee back on return, as the callee may have
+//!   written symbolic data to the memory of the caller.
+//! - A new thread starts raised if any symbolic data exists in the program.
+//! - A frame that accesses memory through dereferences or statics is raised
+//!   if any symbolic data exists in the program (see [`notify_indirect_access`]),
+//!   as the symbolic data it reads or overwrites is not tracked by the flag.
+//!
+//! The shim reads the flag of its thread directly through its address
+//! (see [`symbolic_data_flag`]).
+
+use core::{
+    cell::{Cell, RefCell},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Whether any symbolic data has been created in the program.
+static ANY_SYMBOLIC_DATA: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Whether any symbolic data may be present in the current frame.
+    static CURRENT: Cell<bool> = Cell::new(ANY_SYMBOLIC_DATA.load(Ordering::Relaxed));
+    /// The flags of the callers of the current frame.
+    static CALLERS: RefCell<Vec<bool>> = const { RefCell::new(Vec::new()) };
+    /// Whether the arguments of the call in progress may carry symbolic data,
+    /// if reported by the backend.
+    static PENDING_CALL: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// The address of the flag of the current thread, valid as long as the thread lives.
+#[inline]
+pub fn symbolic_data_flag() -> *const bool {
+    CURRENT.with(|current| current.as_ptr().cast_const())
+}
+
+#[inline]
+pub fn mark_symbolic_data_present() {
+    ANY_SYMBOLIC_DATA.store(true, Ordering::Relaxed);
+    CURRENT.set(true);
+}
+
+/// Reports whether the arguments passed to the call in progress may carry
+/// symbolic data, i.e., they are symbolic or (may) point to memory.
+#[inline]
+pub fn notify_call(may_pass_symbolic: bool) {
+    PENDING_CALL.set(Some(may_pass_symbolic));
+}
+
+#[inline]
+pub(crate) fn notify_enter() {
+    let caller = CURRENT.get();
+    CALLERS.with_borrow_mut(|callers| callers.push(caller));
+    CURRENT.set(caller && PENDING_CALL.take().unwrap_or(true));
+}
+
+#[inline]
+pub(crate) fn notify_return() {
+    if let Some(caller) = CALLERS.with_borrow_mut(Vec::pop) {
+        CURRENT.set(caller || CURRENT.get());
+    }
+}
+
+/// Reports that the current frame accesses memory through dereferences or statics.
+#[inline]
+pub(crate) fn notify_indirect_access() {
+    if ANY_SYMBOLIC_DATA.load(Ordering::Relaxed) {
+        CURRENT.set(true);
+    }
+}
+
+/// Called when the control is back in the caller, so that a report for
+/// a call not entering the instrumented code is not taken by the next one.
+#[inline]
+pub(crate) fn notify_after_call() {
+    PENDING_CALL.set(None);
+}
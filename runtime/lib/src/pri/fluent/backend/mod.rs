@@ -3,9 +3,10 @@ use derive_more as dm;
 pub mod shared;
 
 use crate::abs::{
-    AssertKind, AssignmentId, BasicBlockIndex, BinaryOp, CalleeDef, CastKind, Constant, FieldIndex,
-    FuncDef, Local, PlaceUsage, Projection, RawAddress, SwitchCaseIndex, SymVariable, Tag,
-    TernaryOp, TypeId, TypeSize, UnaryOp, ValueType, VariantIndex, backend::Shutdown,
+    AssertKind, AssignmentId, BasicBlockIndex, BinaryOp, CalleeDef, CastKind, Constant, DefId,
+    FieldIndex, FuncDef, Local, PlaceUsage, Projection, RawAddress, SwitchCaseIndex, SymVariable,
+    SymVariableInfo, Tag, TernaryOp, TypeId, TypeSize, UnaryOp, ValueType, VariantIndex,
+    backend::Shutdown,
 };
 
 pub trait RuntimeBackend: Shutdown {
@@ -34,7 +35,7 @@ pub trait RuntimeBackend: Shutdown {
     type RawMemoryHandler<'a>: RawMemoryHandler<Place = Self::Place, Operand = Self::Operand>
     where
         Self: 'a;
-    type ConstraintHandler<'a>: ConstraintHandler<Operand = Self::Operand>
+    type ConstraintHandler<'a>: ConstraintHandler<Operand = Self::Operand, DiscriminablePlace = Self::DiscriminablePlace>
     where
         Self: 'a;
     type CallHandler<'a>: CallHandler<Place = Self::Place, Operand = Self::Operand>
@@ -205,7 +206,10 @@ pub trait AssignmentHandler: Sized {
         self.some()
     }
 
-    fn thread_local_ref_to(self) {
+    /// # Arguments
+    /// * `def_id` - The definition of the thread-local static being referenced.
+    /// * `addr` - The address of the thread-local static in the current thread.
+    fn thread_local_ref_to(self, _def_id: DefId, _addr: RawAddress) {
         self.some()
     }
 
@@ -246,6 +250,26 @@ pub trait AssignmentHandler: Sized {
         self.some()
     }
 
+    /// Element-wise binary operation between two SIMD vectors, given lane by lane.
+    fn simd_binary_op_between(
+        self,
+        _operator: BinaryOp,
+        _first: impl Iterator<Item = Self::Operand>,
+        _second: impl Iterator<Item = Self::Operand>,
+    ) {
+        self.some()
+    }
+
+    /// Element-wise unary operation on a SIMD vector, given lane by lane.
+    fn simd_unary_op_on(self, _operator: UnaryOp, _operand: impl Iterator<Item = Self::Operand>) {
+        self.some()
+    }
+
+    /// Reduction of the lanes of a SIMD vector using the binary operator.
+    fn simd_reduce(self, _operator: BinaryOp, _operand: impl Iterator<Item = Self::Operand>) {
+        self.some()
+    }
+
     fn discriminant_from(self, _place: Self::DiscriminablePlace) {
         self.some()
     }
@@ -311,6 +335,17 @@ pub trait LifetimeHandler {
     fn mark_live(self, place: Self::Place);
 
     fn mark_dead(self, place: Self::Place);
+
+    /// Marks the memory returned by the allocator as an allocation.
+    fn mark_allocated(self, addr: RawAddress, size: TypeSize);
+
+    /// Marks the allocation starting at the address as freed.
+    fn mark_deallocated(self, addr: RawAddress);
+
+    /// Marks the memory a constant pointer points to as an allocation,
+    /// i.e., a static or a promoted constant.
+    /// `ptr_type_id` is the type of the pointer.
+    fn mark_global(self, addr: RawAddress, ptr_type_id: TypeId);
 }
 
 pub trait RawMemoryHandler {
@@ -381,11 +416,27 @@ pub trait RawMemoryHandler {
 
 pub trait ConstraintHandler {
     type Operand;
+    type DiscriminablePlace;
     type SwitchHandler: SwitchHandler;
 
     fn switch(self, discriminant: Option<Self::Operand>) -> Self::SwitchHandler;
 
     fn assert(self, cond: Self::Operand, expected: bool, assert_kind: AssertKind<Self::Operand>);
+
+    /// Reports that the last assertion has held, i.e., the execution has reached
+    /// its target.
+    fn assert_passed(self);
+
+    /// Reports that the program is about to panic explicitly, e.g., by `panic!`
+    /// or a failed `assert!`. `source` is the source text of the panicking
+    /// expression, if available.
+    fn panic(self, source: &'static str);
+
+    /// # Remarks
+    /// Summarizes the decision made on the value returned from the entry function
+    /// when the program terminates, i.e., `Termination::report`, which is not
+    /// instrumented. `discr` is the concrete discriminant of `result`.
+    fn terminate(self, result: Self::DiscriminablePlace, discr: super::Constant, exit_code: u8);
 }
 
 pub trait SwitchHandler {
@@ -419,6 +470,9 @@ pub trait CallHandler {
         are_args_tupled: bool,
     );
 
+    /// Notifies that a thread is started, right before entering its first function.
+    fn start_thread(self);
+
     fn enter(self, def: FuncDef);
 
     fn emplace_arguments(
@@ -430,6 +484,11 @@ pub trait CallHandler {
 
     fn override_return_value(self, value: Self::Operand);
 
+    /// # Remarks
+    /// Called in an external call whose returned value is the concrete `value`
+    /// of its argument (see the `concretize` annotation).
+    fn concretize_return_value(self, value: Self::Operand);
+
     fn ret(self, ret_point: BasicBlockIndex);
 
     fn after_call(self, assignment_id: AssignmentId, result_dest: Self::Place);
@@ -446,10 +505,23 @@ pub trait DropHandler {
     fn take_data_before_drop(self, func: Self::Operand, arg: Self::Operand, place: Self::Place);
 
     fn after_drop(self);
+
+    /// Called on the unwinding path of a drop, i.e., when the drop glue panics.
+    fn after_drop_unwind(self);
 }
 
 pub trait AnnotationHandler {
     fn push_tag(self, tag: Tag);
 
     fn pop_tag(self);
+
+    /// Notifies that an assumption (`leaf::annotations::assume`) does not hold,
+    /// right before the execution is terminated.
+    fn violate_assumption(self);
+
+    /// Attaches the metadata to the symbolic variables created until the
+    /// matching `pop_sym_var_info`.
+    fn push_sym_var_info(self, info: SymVariableInfo);
+
+    fn pop_sym_var_info(self);
 }
@@ -232,7 +232,7 @@ pub mod noop {
 
         fn ref_to(self, _place: Self::Place, _is_mutable: bool) {}
 
-        fn thread_local_ref_to(self) {}
+        fn thread_local_ref_to(self, _def_id: DefId, _addr: RawAddress) {}
 
         fn address_of(self, _place: Self::Place, _is_mutable: bool) {}
         fn cast_of(self, _operand: Self::Operand, _target: CastKind) {}
@@ -265,6 +265,23 @@ pub mod noop {
         ) {
         }
 
+        fn simd_binary_op_between(
+            self,
+            _operator: BinaryOp,
+            _first: impl Iterator<Item = Self::Operand>,
+            _second: impl Iterator<Item = Self::Operand>,
+        ) {
+        }
+
+        fn simd_unary_op_on(
+            self,
+            _operator: UnaryOp,
+            _operand: impl Iterator<Item = Self::Operand>,
+        ) {
+        }
+
+        fn simd_reduce(self, _operator: BinaryOp, _operand: impl Iterator<Item = Self::Operand>) {}
+
         fn discriminant_from(self, _place: Self::DiscriminablePlace) {}
 
         fn array_from(self, _items: impl Iterator<Item = Self::Operand>) {}
@@ -310,6 +327,12 @@ pub mod noop {
         fn mark_live(self, _place: Self::Place) {}
 
         fn mark_dead(self, _place: Self::Place) {}
+
+        fn mark_allocated(self, _addr: RawAddress, _size: TypeSize) {}
+
+        fn mark_deallocated(self, _addr: RawAddress) {}
+
+        fn mark_global(self, _addr: RawAddress, _ptr_type_id: TypeId) {}
     }
 
     #[derive(Default)]
@@ -393,9 +416,9 @@ pub mod noop {
         }
     }
 
-    pub struct NoOpConstraintHandler<O>(PhantomData<O>);
+    pub struct NoOpConstraintHandler<O, D = O>(PhantomData<(O, D)>);
 
-    impl<O> Default for NoOpConstraintHandler<O> {
+    impl<O, D> Default for NoOpConstraintHandler<O, D> {
         fn default() -> Self {
             Self(Default::default())
         }
@@ -404,8 +427,9 @@ pub mod noop {
     #[derive(Default)]
     pub struct NoOpSwitchHandler;
 
-    impl<O> ConstraintHandler for NoOpConstraintHandler<O> {
+    impl<O, D> ConstraintHandler for NoOpConstraintHandler<O, D> {
         type Operand = O;
+        type DiscriminablePlace = D;
 
         type SwitchHandler = NoOpSwitchHandler;
 
@@ -421,6 +445,18 @@ pub mod noop {
         ) {
             Default::default()
         }
+
+        fn assert_passed(self) {
+            Default::default()
+        }
+
+        fn panic(self, _source: &'static str) {
+            Default::default()
+        }
+
+        fn terminate(self, _result: Self::DiscriminablePlace, _discr: Constant, _exit_code: u8) {
+            Default::default()
+        }
     }
 
     impl SwitchHandler for NoOpSwitchHandler {
@@ -433,6 +469,67 @@ pub mod noop {
         }
     }
 
+    #[derive(Default)]
+    pub struct NoOpCallHandler<P, O>(PhantomData<(P, O)>);
+
+    impl<P, O> CallHandler for NoOpCallHandler<P, O> {
+        type Place = P;
+        type Operand = O;
+
+        fn before_call(self, _def: CalleeDef, _call_site: BasicBlockIndex) {}
+
+        fn before_call_some(self) {}
+
+        fn take_data_before_call(
+            self,
+            _func: Self::Operand,
+            _args: impl IntoIterator<Item = Self::Operand>,
+            _are_args_tupled: bool,
+        ) {
+        }
+
+        fn start_thread(self) {}
+
+        fn enter(self, _def: FuncDef) {}
+
+        fn emplace_arguments(
+            self,
+            _arg_places: Vec<Self::Place>,
+            _ret_val_place: Self::Place,
+            _tupling: ArgsTupling,
+        ) {
+        }
+
+        fn override_return_value(self, _value: Self::Operand) {}
+
+        fn concretize_return_value(self, _value: Self::Operand) {}
+
+        fn ret(self, _ret_point: BasicBlockIndex) {}
+
+        fn after_call(self, _assignment_id: AssignmentId, _result_dest: Self::Place) {}
+    }
+
+    impl<P, O> DropHandler for NoOpCallHandler<P, O> {
+        type Place = P;
+        type Operand = O;
+
+        fn before_drop(self, _def: CalleeDef, _call_site: BasicBlockIndex) {}
+
+        fn before_drop_some(self) {}
+
+        fn take_data_before_drop(
+            self,
+            _func: Self::Operand,
+            _arg: Self::Operand,
+            _place: Self::Place,
+        ) {
+        }
+
+        fn after_drop(self) {}
+
+        fn after_drop_unwind(self) {}
+    }
+
     #[derive(Default)]
     pub struct NoOpAnnotationHandler;
 
@@ -440,5 +537,11 @@ pub mod noop {
         fn push_tag(self, _tag: Tag) {}
 
         fn pop_tag(self) {}
+
+        fn violate_assumption(self) {}
+
+        fn push_sym_var_info(self, _info: SymVariableInfo) {}
+
+        fn pop_sym_var_info(self) {}
     }
 }
@@ -4,8 +4,8 @@ pub mod backend;
 
 use common::pri::{
     AssertionInfo, AssignmentId, BasicBlockIndex, DynRawMetadata, FieldIndex, InstanceKindId,
-    LocalIndex, OperandRef, PlaceRef, ProgramRuntimeInterface, RawAddress, SwitchCaseIndex, TypeId,
-    TypeSize, VariantIndex, refs::encoding as ref_enc,
+    LocalIndex, OperandRef, PlaceMetadata, PlaceRef, ProgramRuntimeInterface, RawAddress,
+    SwitchCaseIndex, TypeId, TypeSize, VariantIndex, refs::encoding as ref_enc,
 };
 use common::{log_debug, log_info};
 use leaf_macros::trait_log_fn;
@@ -13,10 +13,11 @@ use leaf_macros::trait_log_fn;
 use crate::abs::FuncRawAddr;
 use crate::abs::{
     self, AssertKind, CalleeDef, CastKind, Constant, FloatType, FuncDef, IntType, Local,
-    PlaceUsage, PrimitiveType, SymVariable, ValueType, backend::Shutdown,
+    PlaceUsage, PrimitiveType, SourceLocation, SymVariable, SymVariableInfo, ValueType,
+    backend::Shutdown,
 };
 
-use super::refs::RefManager;
+use super::{fast_path, refs::RefManager};
 
 use self::backend::*;
 
@@ -76,6 +77,36 @@ where
         IM::deinit();
     }
 
+    fn report_termination(
+        node_loc: BasicBlockIndex,
+        result: PlaceRef,
+        discr_bit_rep: u32,
+        discr_ty: Self::PrimitiveType,
+    ) {
+        // `Termination` for `Result<(), E>` reports failure for any error.
+        const EXIT_SUCCESS: u8 = 0;
+        const EXIT_FAILURE: u8 = 1;
+        const DISCR_OK: u32 = 0;
+
+        let place_info = Self::take_back_place_info(result);
+        let result = Self::get_backend_place(abs::PlaceUsage::Copy, |h| h.tag_of(place_info));
+        let exit_code = if discr_bit_rep == DISCR_OK {
+            EXIT_SUCCESS
+        } else {
+            EXIT_FAILURE
+        };
+        Self::constraint_at(node_loc, |h| {
+            h.terminate(
+                result,
+                Constant::Int {
+                    bit_rep: discr_bit_rep.into(),
+                    ty: ValueType::from(discr_ty).expect_int(),
+                },
+                exit_code,
+            )
+        })
+    }
+
     #[tracing::instrument(target = "pri", skip_all, level = "trace")]
     fn debug_info(info: Self::DebugInfo) {
         let str_rep = String::from_utf8_lossy(info);
@@ -98,6 +129,34 @@ where
         Self::annotate(|h| h.pop_tag())
     }
 
+    fn violate_assumption() {
+        Self::annotate(|h| h.violate_assumption());
+        // The rest of the execution is outside the assumed input space,
+        // thus it is terminated as if it has finished normally.
+        Self::shutdown_runtime_lib();
+        std::process::exit(0)
+    }
+
+    fn push_sym_var_info(
+        name: Self::Tag,
+        type_name: Self::Tag,
+        file: Self::Tag,
+        line: u32,
+        col: u32,
+    ) {
+        Self::annotate(|h| {
+            h.push_sym_var_info(SymVariableInfo {
+                name,
+                type_name,
+                location: SourceLocation { file, line, col },
+            })
+        })
+    }
+
+    fn pop_sym_var_info() {
+        Self::annotate(|h| h.pop_sym_var_info())
+    }
+
     #[tracing::instrument(target = "pri::place", level = "debug", ret)]
     fn ref_place_return_value() -> PlaceRef {
         Self::push_place_info(Self::build_return_value_place)
@@ -182,6 +241,24 @@ where
     fn place_with_size(place: PlaceRef, byte_size: TypeSize) -> PlaceRef {
         Self::transform_place_info(place, |h, p| h.metadata(p).set_size(byte_size))
     }
+    #[tracing::instrument(target = "pri::place", level = "debug", ret)]
+    fn place_with_metadata(place: PlaceRef, metadata: PlaceMetadata) -> PlaceRef {
+        Self::transform_place_info(place, |h, p| {
+            let mut handler = h.metadata(p);
+            if metadata.has(PlaceMetadata::HAS_ADDRESS) {
+                handler.set_address(metadata.address);
+            }
+            if metadata.has(PlaceMetadata::HAS_TYPE_ID) {
+                handler.set_type_id(metadata.type_id.into());
+            }
+            if metadata.has(PlaceMetadata::HAS_PRIMITIVE_TYPE) {
+                handler.set_primitive_type(PrimitiveType::from(metadata.primitive_type).into());
+            }
+            if metadata.has(PlaceMetadata::HAS_SIZE) {
+                handler.set_size(metadata.size);
+            }
+        })
+    }
 
     #[tracing::instrument(target = "pri::operand", level = "debug", ret)]
     fn ref_operand_copy(place: PlaceRef) -> OperandRef {
@@ -238,10 +315,24 @@ where
         Self::push_const_operand(value)
     }
     #[tracing::instrument(target = "pri::operand", level = "debug", ret)]
+    fn ref_operand_const_ptr(value: RawAddress, type_id: Self::TypeId) -> OperandRef {
+        // The address is sufficient for the value, the type is used to know the pointee.
+        Self::memory(|h| h.mark_global(value, type_id));
+        Self::push_const_operand(value)
+    }
+    #[tracing::instrument(target = "pri::operand", level = "debug", ret)]
+    fn ref_operand_const_byref(bytes: &'static [u8], type_id: Self::TypeId) -> OperandRef {
+        Self::push_const_operand(Constant::ByRef { bytes, ty: type_id })
+    }
+    #[tracing::instrument(target = "pri::operand", level = "debug", ret)]
     fn ref_operand_const_zst() -> OperandRef {
         Self::push_const_operand(Constant::Zst)
     }
     #[tracing::instrument(target = "pri::operand", level = "debug", ret)]
+    fn ref_operand_const_fn_item(type_id: Self::TypeId) -> OperandRef {
+        Self::push_const_operand(Constant::FnItem(type_id))
+    }
+    #[tracing::instrument(target = "pri::operand", level = "debug", ret)]
     fn ref_operand_const_some() -> OperandRef {
         Self::push_const_operand(Constant::Some)
     }
@@ -252,6 +343,7 @@ where
     }
 
     fn new_sym_value_bool(conc_val: bool) -> OperandRef {
+        fast_path::mark_symbolic_data_present();
         // FIXME: Redundant referencing.
         let conc_val = Self::take_back_operand(Self::ref_operand_const_bool(conc_val));
         Self::push_operand(|o| {
@@ -262,6 +354,7 @@ where
         })
     }
     fn new_sym_value_char(conc_val: char) -> OperandRef {
+        fast_path::mark_symbolic_data_present();
         // FIXME: Redundant referencing.
         let conc_val = Self::take_back_operand(Self::ref_operand_const_char(conc_val));
         Self::push_operand(|o| {
@@ -272,6 +365,7 @@ where
         })
     }
     fn new_sym_value_int(conc_val_bit_rep: u128, bit_size: u64, is_signed: bool) -> OperandRef {
+        fast_path::mark_symbolic_data_present();
         // FIXME: Redundant referencing.
         let conc_val = Self::take_back_operand(Self::ref_operand_const_int_arb(
             conc_val_bit_rep,
@@ -286,6 +380,7 @@ where
         })
     }
     fn new_sym_value_float(conc_val_bit_rep: u128, e_bits: u64, s_bits: u64) -> OperandRef {
+        fast_path::mark_symbolic_data_present();
         // FIXME: Redundant referencing.
         let conc_val = Self::take_back_operand(Self::ref_operand_const_float(
             conc_val_bit_rep,
@@ -313,8 +408,16 @@ where
         let place = Self::take_place_info_to(PlaceUsage::Ref, place);
         Self::assign_to(id, dest, |h| h.ref_to(place, is_mutable))
     }
-    fn assign_thread_local_ref(id: AssignmentId, dest: PlaceRef /* TODO: #365 */) {
-        Self::assign_to(id, dest, |h| h.thread_local_ref_to())
+    fn assign_thread_local_ref(
+        id: AssignmentId,
+        dest: PlaceRef,
+        crate_id: u32,
+        def_index: u32,
+        addr: RawAddress,
+    ) {
+        Self::assign_to(id, dest, |h| {
+            h.thread_local_ref_to(abs::DefId(crate_id, def_index), addr)
+        })
     }
     fn assign_raw_ptr_of(id: AssignmentId, dest: PlaceRef, place: PlaceRef, is_mutable: bool) {
         let place = Self::take_place_info_to(PlaceUsage::Ref, place);
@@ -512,6 +615,12 @@ where
         let place = Self::get_backend_place(abs::PlaceUsage::Mark, |h| h.from_info(place_info));
         Self::memory(|h| h.mark_dead(place));
     }
+    fn mark_allocated(addr: RawAddress, size: usize) {
+        Self::memory(|h| h.mark_allocated(addr, size as TypeSize));
+    }
+    fn mark_deallocated(addr: RawAddress) {
+        Self::memory(|h| h.mark_deallocated(addr));
+    }
 
     fn take_branch(node_loc: BasicBlockIndex, case_index: SwitchCaseIndex) {
         Self::constraint_at(node_loc, |c| {
@@ -696,6 +805,12 @@ where
         let assert_kind = AssertKind::InvalidEnumConstruction(Self::take_back_operand(discr));
         Self::assert(info, assert_kind)
     }
+    fn check_assert_passed(location: BasicBlockIndex) {
+        Self::constraint_at(location, |h| h.assert_passed())
+    }
+    fn check_panic(location: BasicBlockIndex, source: &'static str) {
+        Self::constraint_at(location, |h| h.panic(source))
+    }
 
     #[tracing::instrument(target = "pri::call", level = "debug")]
     fn before_call_control(call_site: BasicBlockIndex, callee_id: InstanceKindId) {
@@ -766,12 +881,19 @@ where
         Self::func_control(|h| h.before_call_some());
     }
 
+    #[tracing::instrument(target = "pri::call", level = "debug")]
+    fn notify_thread_start() {
+        Self::func_control(|h| h.start_thread());
+    }
+
     #[tracing::instrument(target = "pri::call", level = "debug")]
     fn enter_func(body_id: InstanceKindId) {
+        fast_path::notify_enter();
         Self::func_control(|h| h.enter(FuncDef { body_id, raw: None }));
     }
     #[tracing::instrument(target = "pri::call", level = "debug")]
     fn enter_func_precise(body_id: InstanceKindId, static_addr: RawAddress) {
+        fast_path::notify_enter();
         Self::func_control(|h| {
             h.enter(FuncDef {
                 body_id,
@@ -790,6 +912,7 @@ where
         static_addr: RawAddress,
         dyn_id: (DynRawMetadata, u64),
     ) {
+        fast_path::notify_enter();
         Self::func_control(|h| {
             h.enter(FuncDef {
                 body_id,
@@ -803,6 +926,10 @@ where
         });
     }
     #[tracing::instrument(target = "pri::call", level = "debug")]
+    fn mark_frame_indirect() {
+        fast_path::notify_indirect_access();
+    }
+    #[tracing::instrument(target = "pri::call", level = "debug")]
     fn enter_func_data(arg_places: &[PlaceRef], ret_val_place: PlaceRef) {
         Self::enter_func_data_with_tupling(arg_places, ret_val_place, ArgsTupling::Normal);
     }
@@ -828,7 +955,8 @@ where
     }
     #[tracing::instrument(target = "pri::call", level = "debug")]
     fn return_from_func(ret_point: BasicBlockIndex) {
-        Self::func_control(|h| h.ret(ret_point))
+        Self::func_control(|h| h.ret(ret_point));
+        fast_path::notify_return();
     }
     /// Overrides (forces) the return value of a function.
     /// In an external call chain, the value will be kept as the return value
@@ -838,8 +966,16 @@ where
         let operand = Self::take_back_operand(operand);
         Self::func_control(|h| h.override_return_value(operand))
     }
+    /// Forces the return value of the current external call to the concrete
+    /// value, while the symbolic argument passed to the call is bound to it.
+    #[tracing::instrument(target = "pri::call", level = "debug")]
+    fn concretize_return_value(operand: OperandRef) {
+        let operand = Self::take_back_operand(operand);
+        Self::func_control(|h| h.concretize_return_value(operand))
+    }
     #[tracing::instrument(target = "pri::call", level = "debug")]
     fn after_call_func(id: AssignmentId, dest: PlaceRef) {
+        fast_path::notify_after_call();
         let dest_place = Self::take_place_info_to(PlaceUsage::Write, dest);
         Self::func_control(|h| h.after_call(id, dest_place))
     }
@@ -926,8 +1062,13 @@ where
     }
     #[tracing::instrument(target = "pri::drop", level = "debug")]
     fn after_drop() {
+        fast_path::notify_after_call();
         Self::dropping(|h| h.after_drop());
     }
+    #[tracing::instrument(target = "pri::drop", level = "debug")]
+    fn after_drop_unwind() {
+        Self::dropping(|h| h.after_drop_unwind());
+    }
 
     fn intrinsic_assign_identity(id: AssignmentId, dest: PlaceRef, x: OperandRef) {
         Self::assign_use(id, dest, x)
@@ -1087,6 +1228,86 @@ where
         })
     }
 
+    fn intrinsic_assign_sqrt(id: AssignmentId, dest: PlaceRef, x: OperandRef) {
+        Self::assign_unary_op(id, dest, Self::UnaryOp::Sqrt, x);
+    }
+
+    fn intrinsic_assign_fabs(id: AssignmentId, dest: PlaceRef, x: OperandRef) {
+        Self::assign_unary_op(id, dest, Self::UnaryOp::Abs, x);
+    }
+
+    fn intrinsic_assign_powi(id: AssignmentId, dest: PlaceRef, x: OperandRef, n: OperandRef) {
+        Self::assign_binary_op(id, dest, Self::BinaryOp::Powi, x, n)
+    }
+
+    fn intrinsic_assign_minimum(
+        id: AssignmentId,
+        dest: PlaceRef,
+        first: OperandRef,
+        second: OperandRef,
+    ) {
+        Self::assign_binary_op(id, dest, Self::BinaryOp::Minimum, first, second)
+    }
+
+    fn intrinsic_assign_maximum(
+        id: AssignmentId,
+        dest: PlaceRef,
+        first: OperandRef,
+        second: OperandRef,
+    ) {
+        Self::assign_binary_op(id, dest, Self::BinaryOp::Maximum, first, second)
+    }
+
+    fn intrinsic_assign_minimum_number(
+        id: AssignmentId,
+        dest: PlaceRef,
+        first: OperandRef,
+        second: OperandRef,
+    ) {
+        Self::assign_binary_op(id, dest, Self::BinaryOp::Min, first, second)
+    }
+
+    fn intrinsic_assign_maximum_number(
+        id: AssignmentId,
+        dest: PlaceRef,
+        first: OperandRef,
+        second: OperandRef,
+    ) {
+        Self::assign_binary_op(id, dest, Self::BinaryOp::Max, first, second)
+    }
+
+    fn intrinsic_assign_simd_binary_op(
+        id: AssignmentId,
+        dest: PlaceRef,
+        operator: Self::BinaryOp,
+        first: &[OperandRef],
+        second: &[OperandRef],
+    ) {
+        let first = first.iter().map(|o| Self::take_back_operand(*o));
+        let second = second.iter().map(|o| Self::take_back_operand(*o));
+        Self::assign_to(id, dest, |h| {
+            h.simd_binary_op_between(operator, first, second)
+        })
+    }
+    fn intrinsic_assign_simd_unary_op(
+        id: AssignmentId,
+        dest: PlaceRef,
+        operator: Self::UnaryOp,
+        operand: &[OperandRef],
+    ) {
+        let operand = operand.iter().map(|o| Self::take_back_operand(*o));
+        Self::assign_to(id, dest, |h| h.simd_unary_op_on(operator, operand))
+    }
+    fn intrinsic_assign_simd_reduce(
+        id: AssignmentId,
+        dest: PlaceRef,
+        operator: Self::BinaryOp,
+        operand: &[OperandRef],
+    ) {
+        let operand = operand.iter().map(|o| Self::take_back_operand(*o));
+        Self::assign_to(id, dest, |h| h.simd_reduce(operator, operand))
+    }
+
     fn intrinsic_atomic_binary_op(
         _ordering: Self::AtomicOrdering,
         id: AssignmentId,
@@ -1103,10 +1324,10 @@ where
             abs::AtomicBinaryOp::Sub => Self::BinaryOp::Sub,
             abs::AtomicBinaryOp::Xor => Self::BinaryOp::BitXor,
             abs::AtomicBinaryOp::And => Self::BinaryOp::BitAnd,
-            abs::AtomicBinaryOp::Nand => todo!(),
+            abs::AtomicBinaryOp::Nand => Self::BinaryOp::Nand,
             abs::AtomicBinaryOp::Or => Self::BinaryOp::BitOr,
-            abs::AtomicBinaryOp::Min => todo!(),
-            abs::AtomicBinaryOp::Max => todo!(),
+            abs::AtomicBinaryOp::Min => Self::BinaryOp::Min,
+            abs::AtomicBinaryOp::Max => Self::BinaryOp::Max,
         };
 
         Self::update_by_ptr_return_old(
@@ -1336,6 +1557,16 @@ where
     }
 }
 
+impl<IM: InstanceManager> super::events::ReplayTarget for FluentPri<IM>
+where
+    <IM::Backend as RuntimeBackend>::Operand: Clone,
+{
+    #[inline(always)]
+    fn slice<'a, T: 'a>(slice: &'a [T]) -> Self::Slice<'a, T> {
+        slice
+    }
+}
+
 #[allow(private_bounds)]
 impl<IM: InstanceManager> FluentPri<IM> {
     fn build_return_value_place(builder: IM::PlaceBuilder) -> IM::PlaceInfo {
@@ -1392,6 +1623,13 @@ impl<IM: InstanceManager> FluentPri<IM> {
         info: AssertionInfo,
         assert_kind: AssertKind<<IM::Backend as RuntimeBackend>::Operand>,
     ) {
+        const ASSERT_TAG: &str = const_format::concatcp!(TAG, "::assert");
+        log_debug!(
+            target: ASSERT_TAG,
+            "Assertion at {}: {}",
+            info.location,
+            <&'static str>::from(info.message),
+        );
         let condition = Self::take_back_operand(info.condition);
         Self::constraint_at(info.location, |h| {
             h.assert(condition, info.expected, assert_kind)
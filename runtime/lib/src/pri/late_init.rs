@@ -67,6 +67,13 @@ macro_rules! impl_pri_for_late_init_pri_of {
 
                     common::pri::list_func_decls! { modifier: $crate::late_init_func_defs, (from Self) }
                 }
+
+                impl $crate::pri::events::ReplayTarget for LateInitPri<MainPri> {
+                    #[inline(always)]
+                    fn slice<'a, T: 'a>(slice: &'a [T]) -> Self::Slice<'a, T> {
+                        slice
+                    }
+                }
             }
         }
     };
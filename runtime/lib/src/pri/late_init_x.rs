@@ -67,6 +67,13 @@ macro_rules! make_late_init_pri_of {
 
                     common::pri::list_func_decls! { modifier: $crate::def_late_init, (from Self) }
                 }
+
+                impl $crate::pri::events::ReplayTarget for [<$t LateInit>] {
+                    #[inline(always)]
+                    fn slice<'a, T: 'a>(slice: &'a [T]) -> Self::Slice<'a, T> {
+                        slice
+                    }
+                }
             }
             pub use [<_for_ $t>]::[<$t LateInit>];
         }
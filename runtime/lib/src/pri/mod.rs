@@ -1,3 +1,5 @@
+pub mod events;
+pub mod fast_path;
 mod ffi;
 pub mod fluent;
 mod late_init;
@@ -34,3 +34,10 @@ impl ProgramRuntimeInterface for NoOpPri {
 
     common::pri::list_func_decls! { modifier: noop, (from Self) }
 }
+
+impl super::events::ReplayTarget for NoOpPri {
+    #[inline(always)]
+    fn slice<'a, T: 'a>(slice: &'a [T]) -> Self::Slice<'a, T> {
+        slice
+    }
+}
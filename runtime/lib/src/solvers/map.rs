@@ -28,6 +28,19 @@ where
     ) -> SolveResult<Self::Model> {
         self.inner.check(constraints).map(&mut self.f)
     }
+
+    fn check_assuming(
+        &mut self,
+        constraints: impl Iterator<Item = Constraint<Self::Value, Self::Case>>,
+        assumption: Constraint<Self::Value, Self::Case>,
+    ) -> (SolveResult<Self::Model>, bool)
+    where
+        Self::Value: Clone,
+        Self::Case: Clone,
+    {
+        let (result, is_unconditionally_unsat) = self.inner.check_assuming(constraints, assumption);
+        (result.map(&mut self.f), is_unconditionally_unsat)
+    }
 }
 
 impl<MFrom> SolveResult<MFrom> {
@@ -1,6 +1,9 @@
 use crate::abs::backend::Solver;
 
 mod map;
+pub mod portfolio;
+pub mod process;
+pub mod smtlib;
 pub mod z3;
 
 pub use map::SolverExt as MapSolverExt;
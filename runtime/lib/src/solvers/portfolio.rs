@@ -0,0 +1,241 @@
+use std::{
+    collections::HashMap,
+    ffi::{CStr, CString},
+    hash::Hash,
+    sync::{Mutex, mpsc},
+    thread,
+};
+
+use common::{log_debug, log_warn, z3::*};
+
+use crate::abs::{Constraint, backend};
+
+use self::backend::SolveResult;
+
+use super::smtlib::{parse_query_output, to_query_script};
+
+/// Runs multiple instances of Z3 with different random seeds (and tactics if given)
+/// in parallel and takes the first definitive result.
+/// # Remarks
+/// As the terms are bound to the context of the current thread, each worker
+/// evaluates the query as an SMT-LIB2 script in its own context. The workers that
+/// lose the race are interrupted and all of them are joined before returning.
+pub struct PortfolioSolver<I> {
+    workers: usize,
+    tactics: Vec<String>,
+    _phantom: core::marker::PhantomData<(I,)>,
+}
+
+impl<I> PortfolioSolver<I> {
+    pub fn new(workers: usize, tactics: Vec<String>) -> Self {
+        Self {
+            workers: workers.max(1),
+            tactics,
+            _phantom: Default::default(),
+        }
+    }
+
+    fn check_command(&self, worker: usize) -> String {
+        let seed =
+            format!("(set-option :random-seed {worker})\n(set-option :smt.random_seed {worker})\n");
+        match self.tactics.get(worker % self.tactics.len().max(1)) {
+            Some(tactic) => format!("{seed}(check-sat-using {tactic})"),
+            None => format!("{seed}(check-sat)"),
+        }
+    }
+}
+
+impl<I> Clone for PortfolioSolver<I> {
+    fn clone(&self) -> Self {
+        Self::new(self.workers, self.tactics.clone())
+    }
+}
+
+impl<I> backend::Solver for PortfolioSolver<I>
+where
+    I: Eq + Hash + Clone,
+{
+    type Value = AstAndVars<I>;
+    type Case = AstNode;
+    type Model = HashMap<I, AstNode>;
+
+    fn check(
+        &mut self,
+        constraints: impl Iterator<Item = Constraint<Self::Value, Self::Case>>,
+    ) -> SolveResult<Self::Model> {
+        let mut all_vars = HashMap::<I, AstNode>::new();
+        let asts = constraints
+            .map(|constraint| WrappedSolver::to_ast(constraint, &mut all_vars))
+            .collect::<Vec<_>>();
+        let vars = all_vars.into_iter().collect::<Vec<_>>();
+
+        let states = (0..self.workers)
+            .map(|_| Mutex::new(WorkerState::Pending))
+            .collect::<Vec<_>>();
+        let (sender, receiver) = mpsc::channel();
+        thread::scope(|scope| {
+            for (worker, state) in states.iter().enumerate() {
+                let script = to_query_script(
+                    &asts,
+                    vars.iter().map(|(_, var)| var),
+                    &self.check_command(worker),
+                );
+                let sender = sender.clone();
+                scope.spawn(move || {
+                    // The receiver may be gone if another worker has already answered.
+                    let _ = sender.send((worker, eval_script(&script, state)));
+                });
+            }
+            drop(sender);
+
+            let mut result = SolveResult::Unknown;
+            for (worker, output) in &receiver {
+                match output {
+                    Ok(output) if !is_unknown(&output) => {
+                        log_debug!("Portfolio worker {worker} answered first");
+                        result = parse_query_output(&output, vars);
+                        break;
+                    }
+                    Ok(_) => {}
+                    Err(e) => log_warn!("Portfolio worker {worker} failed: {e}"),
+                }
+            }
+
+            // The rest of the workers are joined at the end of the scope.
+            for state in &states {
+                state.lock().unwrap().cancel();
+            }
+            result
+        })
+    }
+}
+
+enum WorkerState {
+    Pending,
+    /// Evaluating the script in the context.
+    Running(RunningContext),
+    Finished,
+}
+
+struct RunningContext(z3_sys::Z3_context);
+
+// SAFETY: The context is only used for interruption, which is meant to be
+// called from other threads, and only while the owning worker is running.
+unsafe impl Send for RunningContext {}
+
+impl WorkerState {
+    fn cancel(&mut self) {
+        if let Self::Running(RunningContext(ctx)) = self {
+            unsafe { z3_sys::Z3_interrupt(*ctx) };
+        }
+        *self = Self::Finished;
+    }
+}
+
+fn is_unknown(output: &str) -> bool {
+    !matches!(
+        output.trim_start().lines().next().map(str::trim),
+        Some("sat" | "unsat")
+    )
+}
+
+/// Evaluates the script in the context of the current thread and returns its output.
+/// The context is exposed through the state during the evaluation, so that it
+/// can be interrupted.
+fn eval_script(script: &str, state: &Mutex<WorkerState>) -> Result<String, String> {
+    let script = CString::new(script).map_err(|e| e.to_string())?;
+    let ctx = z3::Context::thread_local();
+    {
+        let mut state = state.lock().unwrap();
+        if matches!(*state, WorkerState::Finished) {
+            return Err("Cancelled before starting".to_owned());
+        }
+        *state = WorkerState::Running(RunningContext(ctx.get_z3_context()));
+    }
+    let output = unsafe {
+        Some(z3_sys::Z3_eval_smtlib2_string(
+            ctx.get_z3_context(),
+            script.as_ptr(),
+        ))
+        .filter(|output| !output.is_null())
+        .map(|output| CStr::from_ptr(output).to_string_lossy().into_owned())
+        .ok_or_else(|| "Evaluation of the script failed".to_owned())
+    };
+    // Not interruptible anymore, as the context is dropped with the thread.
+    *state.lock().unwrap() = WorkerState::Finished;
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use z3::ast::{self, Ast};
+
+    use crate::abs::{ConstraintKind, backend::Solver};
+
+    use super::*;
+
+    fn byte_var(id: u32) -> (u32, AstNode) {
+        (id, AstNode::from_ubv(ast::BV::new_const(id, 8)))
+    }
+
+    fn equals(var: &(u32, AstNode), value: u64) -> Constraint<AstAndVars<u32>, AstNode> {
+        let condition = var.1.as_bit_vector().eq(&ast::BV::from_u64(value, 8));
+        Constraint {
+            discr: AstAndVars {
+                value: condition.into(),
+                variables: vec![var.clone()],
+            },
+            kind: ConstraintKind::True,
+        }
+    }
+
+    #[test]
+    fn first_answer_is_the_model() {
+        let x = byte_var(1);
+        let mut solver = PortfolioSolver::<u32>::new(4, vec![]);
+        let SolveResult::Sat(model) = solver.check([equals(&x, 5)].into_iter()) else {
+            panic!("Expected a model");
+        };
+        assert_eq!(model[&1].as_bit_vector().as_u64(), Some(5));
+    }
+
+    #[test]
+    fn unsat_is_reported() {
+        let x = byte_var(1);
+        let mut solver = PortfolioSolver::<u32>::new(2, vec!["smt".to_owned()]);
+        assert!(matches!(
+            solver.check([equals(&x, 5), equals(&x, 6)].into_iter()),
+            SolveResult::Unsat
+        ));
+    }
+
+    #[test]
+    fn workers_take_distinct_seeds_and_cycle_tactics() {
+        let solver = PortfolioSolver::<u32>::new(3, vec!["smt".to_owned(), "qfbv".to_owned()]);
+        assert!(solver.check_command(0).contains(":random-seed 0"));
+        assert!(solver.check_command(1).contains(":random-seed 1"));
+        assert!(solver.check_command(1).ends_with("(check-sat-using qfbv)"));
+        assert!(solver.check_command(2).ends_with("(check-sat-using smt)"));
+
+        let solver = PortfolioSolver::<u32>::new(0, vec![]);
+        assert_eq!(solver.workers, 1);
+        assert!(solver.check_command(0).ends_with("(check-sat)"));
+    }
+
+    #[test]
+    fn only_definitive_outputs_are_answers() {
+        assert!(!is_unknown("sat\n((x #x05))"));
+        assert!(!is_unknown("\nunsat\n"));
+        assert!(is_unknown("unknown\n"));
+        assert!(is_unknown("(error \"canceled\")"));
+        assert!(is_unknown(""));
+    }
+
+    #[test]
+    fn cancelled_workers_do_not_start() {
+        let state = Mutex::new(WorkerState::Pending);
+        state.lock().unwrap().cancel();
+        assert!(eval_script("(check-sat)", &state).is_err());
+        assert!(matches!(*state.lock().unwrap(), WorkerState::Finished));
+    }
+}
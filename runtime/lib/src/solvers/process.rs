@@ -0,0 +1,115 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    io::{Read, Write},
+    path::PathBuf,
+    process::{Command, Stdio},
+    thread,
+};
+
+use common::{log_debug, log_warn, z3::*};
+
+use crate::abs::{Constraint, backend};
+
+use self::backend::SolveResult;
+
+use super::smtlib::{parse_query_output, to_query_script};
+
+/// Checks the queries with an external solver process that reads SMT-LIB2 scripts
+/// from its standard input, e.g., Bitwuzla or cvc5.
+/// # Remarks
+/// The constraints are expected in the same form as for [`super::z3::Z3Solver`],
+/// i.e., Z3 terms, and they are passed to the process as an SMT-LIB2 script.
+/// The model is then read back through `get-value` and converted to Z3 terms, so
+/// the solvers are interchangeable.
+pub struct ProcessSolver<I> {
+    program: PathBuf,
+    args: Vec<String>,
+    _phantom: core::marker::PhantomData<(I,)>,
+}
+
+impl<I> ProcessSolver<I> {
+    pub fn new(program: PathBuf, args: Vec<String>) -> Self {
+        Self {
+            program,
+            args,
+            _phantom: Default::default(),
+        }
+    }
+}
+
+impl<I> Clone for ProcessSolver<I> {
+    fn clone(&self) -> Self {
+        Self::new(self.program.clone(), self.args.clone())
+    }
+}
+
+impl<I> backend::Solver for ProcessSolver<I>
+where
+    I: Eq + Hash + Clone,
+{
+    type Value = AstAndVars<I>;
+    type Case = AstNode;
+    type Model = HashMap<I, AstNode>;
+
+    fn check(
+        &mut self,
+        constraints: impl Iterator<Item = Constraint<Self::Value, Self::Case>>,
+    ) -> SolveResult<Self::Model> {
+        let mut all_vars = HashMap::<I, AstNode>::new();
+        let asts = constraints
+            .map(|constraint| WrappedSolver::to_ast(constraint, &mut all_vars))
+            .collect::<Vec<_>>();
+        let vars = all_vars.into_iter().collect::<Vec<_>>();
+
+        let script = to_query_script(&asts, vars.iter().map(|(_, var)| var), "(check-sat)");
+        log_debug!("Sending query to {}: {}", self.program.display(), script);
+        match self.run(&script) {
+            Ok(output) => parse_query_output(&output, vars),
+            Err(e) => {
+                log_warn!("Could not run the solver {}: {e}", self.program.display());
+                SolveResult::Unknown
+            }
+        }
+    }
+}
+
+impl<I> ProcessSolver<I> {
+    fn run(&self, script: &str) -> std::io::Result<String> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        // The output is read concurrently, otherwise the process may block on
+        // a full stdout pipe while we are blocked on a full stdin pipe.
+        let mut stdout = child.stdout.take().unwrap();
+        let reader = thread::spawn(move || {
+            let mut output = Vec::new();
+            stdout.read_to_end(&mut output).map(|_| output)
+        });
+
+        // Dropping stdin closes it, which signals the end of the script.
+        let written = child.stdin.take().unwrap().write_all(script.as_bytes());
+        let output = reader.join().expect("The solver output reader panicked")?;
+        child.wait()?;
+        written?;
+        Ok(String::from_utf8_lossy(&output).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn large_scripts_do_not_block_on_the_pipes() {
+        // Echoing the script makes the output as large as the input.
+        let solver = ProcessSolver::<u32>::new(PathBuf::from("cat"), vec![]);
+        let script = "(assert true)\n".repeat(1 << 16);
+        assert_eq!(solver.run(&script).unwrap(), script);
+    }
+}
@@ -0,0 +1,245 @@
+use std::{cell::Cell, collections::HashMap, fs, hash::Hash, rc::Rc};
+
+use z3::{SatResult, Solver, ast};
+
+use common::{log_warn, z3::*};
+
+use crate::{abs::backend::SolveResult, utils::file::FileGenConfig};
+
+const FILENAME_QUERY_PREFIX: &str = "query_";
+const DEFAULT_EXTENSION: &str = "smt2";
+
+/// Renders the constraints as a standalone SMT-LIB2 script, i.e., the declarations
+/// of the variables, the assertions, and the commands to check them.
+pub fn to_smtlib2(constraints: &[ast::Bool]) -> String {
+    format!(
+        "{}(check-sat)\n(get-model)\n",
+        to_smtlib2_assertions(constraints)
+    )
+}
+
+/// Renders the declarations of the variables and the assertions of the constraints.
+pub fn to_smtlib2_assertions(constraints: &[ast::Bool]) -> String {
+    let solver = Solver::new();
+    constraints.iter().for_each(|c| solver.assert(c));
+    solver.to_string()
+}
+
+/// Writes each query sent to the solver as an SMT-LIB2 script.
+/// `query_<n>` corresponds to the `n`th query and the result of the check
+/// is recorded as a comment at the top of the file.
+pub struct QueryDumper {
+    config: FileGenConfig,
+    counter: Cell<usize>,
+}
+
+impl QueryDumper {
+    pub fn new(config: FileGenConfig) -> Self {
+        Self {
+            config,
+            counter: Cell::new(0),
+        }
+    }
+
+    pub fn dump(&self, constraints: &[ast::Bool], result: SatResult) {
+        let index = self.counter.get();
+        self.counter.set(index + 1);
+
+        let mut path = self
+            .config
+            .single_file_path(FILENAME_QUERY_PREFIX, Some(index.to_string()));
+        if self.config.extension().is_none() {
+            path.set_extension(DEFAULT_EXTENSION);
+        }
+        let content = format!("; result: {result:?}\n{}", to_smtlib2(constraints));
+        if let Err(e) = self
+            .config
+            .ensure_dir()
+            .and_then(|_| fs::write(&path, content))
+        {
+            log_warn!("Could not write query {index} to {}: {e}", path.display());
+        }
+    }
+
+    pub fn into_observer(self) -> QueryObserver {
+        Rc::new(move |constraints, result| self.dump(constraints, result))
+    }
+}
+
+/// Renders a script that checks the constraints using the given command and
+/// then queries the values of the variables.
+pub(super) fn to_query_script<'a>(
+    constraints: &[ast::Bool],
+    vars: impl Iterator<Item = &'a AstNode>,
+    check_command: &str,
+) -> String {
+    let vars = vars
+        .map(|var| var.dyn_ast().to_string())
+        .collect::<Vec<_>>();
+    let get_value = if vars.is_empty() {
+        String::new()
+    } else {
+        format!("(get-value ({}))\n", vars.join(" "))
+    };
+    format!(
+        "(set-option :produce-models true)\n{}{check_command}\n{get_value}",
+        to_smtlib2_assertions(constraints),
+    )
+}
+
+/// Reads the output of a script made by [`to_query_script`].
+pub(super) fn parse_query_output<I: Eq + Hash>(
+    output: &str,
+    vars: Vec<(I, AstNode)>,
+) -> SolveResult<HashMap<I, AstNode>> {
+    let mut lines = output.trim_start().splitn(2, '\n');
+    match lines.next().map(str::trim) {
+        Some("sat") => {}
+        Some("unsat") => return SolveResult::Unsat,
+        _ => return SolveResult::Unknown,
+    }
+
+    match parse_values(lines.next().unwrap_or_default(), vars) {
+        Ok(model) => SolveResult::Sat(model),
+        Err(e) => {
+            log_warn!("Could not read the model of the query: {e}");
+            SolveResult::Unknown
+        }
+    }
+}
+
+/// Reads the response of `get-value`, which lists the values in the same order
+/// as the variables.
+fn parse_values<I: Eq + Hash>(
+    response: &str,
+    vars: Vec<(I, AstNode)>,
+) -> Result<HashMap<I, AstNode>, String> {
+    if vars.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let SExpr::List(pairs) = SExpr::parse(response)? else {
+        return Err(format!("Unexpected response: {response}"));
+    };
+    if pairs.len() != vars.len() {
+        return Err(format!("Unexpected number of values: {response}"));
+    }
+
+    vars.into_iter()
+        .zip(pairs)
+        .map(|((id, var), pair)| match pair {
+            SExpr::List(mut pair) if pair.len() == 2 => {
+                to_ast_node(&pair.pop().unwrap(), &var.sort()).map(|value| (id, value))
+            }
+            _ => Err(format!("Unexpected value pair: {pair:?}")),
+        })
+        .collect()
+}
+
+fn to_ast_node(value: &SExpr, sort: &AstNodeSort) -> Result<AstNode, String> {
+    let unexpected = || format!("Unexpected value for sort {sort:?}: {value:?}");
+    match (sort, value) {
+        (AstNodeSort::Bool, SExpr::Atom(atom)) => match atom.as_str() {
+            "true" => Ok(ast::Bool::from_bool(true).into()),
+            "false" => Ok(ast::Bool::from_bool(false).into()),
+            _ => Err(unexpected()),
+        },
+        (AstNodeSort::BitVector(sort), SExpr::Atom(atom)) => {
+            let (bits, size) = parse_bv_literal(atom).ok_or_else(unexpected)?;
+            Ok(AstNode::BitVector(BVNode(bv_from_u128(bits, size), *sort)))
+        }
+        (AstNodeSort::Float(sort), SExpr::List(items)) => match items.as_slice() {
+            [
+                SExpr::Atom(fp),
+                SExpr::Atom(sign),
+                SExpr::Atom(exp),
+                SExpr::Atom(sig),
+            ] if fp == "fp" => {
+                let (bits, size) = [sign, exp, sig]
+                    .into_iter()
+                    .map(|atom| parse_bv_literal(atom))
+                    .try_fold((0u128, 0u32), |(acc, acc_size), part| {
+                        part.map(|(bits, size)| ((acc << size) | bits, acc_size + size))
+                    })
+                    .ok_or_else(unexpected)?;
+                let bv = bv_from_u128(bits, size);
+                Ok(AstNode::Float(FloatNode(
+                    ast::Float::from_ieee_bv(&bv, *sort),
+                    *sort,
+                )))
+            }
+            _ => Err(unexpected()),
+        },
+        _ => Err(unexpected()),
+    }
+}
+
+/// Parses `#b...` and `#x...` literals to their value and size in bits.
+fn parse_bv_literal(atom: &str) -> Option<(u128, u32)> {
+    let (digits, radix, bits_per_digit) = if let Some(digits) = atom.strip_prefix("#b") {
+        (digits, 2, 1)
+    } else if let Some(digits) = atom.strip_prefix("#x") {
+        (digits, 16, 4)
+    } else {
+        return None;
+    };
+    let size = digits.len() as u32 * bits_per_digit;
+    (size <= 128)
+        .then(|| u128::from_str_radix(digits, radix).ok())
+        .flatten()
+        .map(|value| (value, size))
+}
+
+fn bv_from_u128(value: u128, size: u32) -> ast::BV {
+    if size <= 64 {
+        ast::BV::from_u64(value as u64, size)
+    } else {
+        ast::BV::from_u64((value >> 64) as u64, size - 64)
+            .concat(&ast::BV::from_u64(value as u64, 64))
+    }
+}
+
+#[derive(Debug)]
+enum SExpr {
+    Atom(String),
+    List(Vec<SExpr>),
+}
+
+impl SExpr {
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut stack: Vec<Vec<SExpr>> = vec![vec![]];
+        let mut atom = String::new();
+        let flush = |atom: &mut String, stack: &mut Vec<Vec<SExpr>>| {
+            if !atom.is_empty() {
+                stack
+                    .last_mut()
+                    .unwrap()
+                    .push(SExpr::Atom(core::mem::take(atom)));
+            }
+        };
+        for c in text.chars() {
+            match c {
+                '(' => {
+                    flush(&mut atom, &mut stack);
+                    stack.push(vec![]);
+                }
+                ')' => {
+                    flush(&mut atom, &mut stack);
+                    let list = stack.pop().unwrap();
+                    stack
+                        .last_mut()
+                        .ok_or_else(|| format!("Unbalanced parentheses: {text}"))?
+                        .push(SExpr::List(list));
+                }
+                c if c.is_whitespace() => flush(&mut atom, &mut stack),
+                c => atom.push(c),
+            }
+        }
+        flush(&mut atom, &mut stack);
+
+        match stack.pop() {
+            Some(mut top) if stack.is_empty() && top.len() == 1 => Ok(top.pop().unwrap()),
+            _ => Err(format!("Malformed S-expression: {text}")),
+        }
+    }
+}
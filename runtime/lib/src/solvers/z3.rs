@@ -30,6 +30,18 @@ where
             (SatResult::Unknown, _) => SolveResult::Unknown,
         }
     }
+
+    fn check_assuming(
+        &mut self,
+        constraints: impl Iterator<Item = Constraint<Self::Value, Self::Case>>,
+        assumption: Constraint<Self::Value, Self::Case>,
+    ) -> (SolveResult<Self::Model>, bool) {
+        match Z3Solver::check_assuming(self, constraints, assumption) {
+            (SatResult::Sat, model, _) => (SolveResult::Sat(model), false),
+            (SatResult::Unsat, _, is_unconditional) => (SolveResult::Unsat, is_unconditional),
+            (SatResult::Unknown, ..) => (SolveResult::Unknown, false),
+        }
+    }
 }
 
 impl TryFrom<ValueType> for BVSort {
@@ -14,6 +14,12 @@ pub struct CoverageData {
     last_depth: usize,
 }
 
+impl CoverageData {
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
 #[derive(dm::Deref, dm::DerefMut, Serialize)]
 pub struct Decisions<C>(Vec<(ConstraintKind<C>, CoverageData)>);
 
@@ -1,7 +1,7 @@
 use core::borrow::Borrow;
 use core::iter;
 
-use common::{log_debug, log_info};
+use common::{log_debug, log_info, log_warn};
 
 use crate::abs::backend::{SolveResult, Solver};
 
@@ -18,6 +18,10 @@ pub struct ImmediateDivergingAnswerFinder<TSolver: Solver, F> {
     filter: F,
     optimistic_divergence_solver: Option<TSolver>,
     model_consumer: Box<dyn FnMut(TSolver::Model)>,
+    /// Set when the path condition of the current execution is found to be unsatisfiable.
+    infeasible: bool,
+    infeasibility_observer: Option<Box<dyn FnOnce(usize, usize)>>,
+    unsat_observer: Option<Box<dyn FnMut()>>,
     _phantom: core::marker::PhantomData<()>,
 }
 
@@ -30,6 +34,10 @@ where
     TSolver::Case: Clone,
 {
     fn inspect(&mut self, steps: &[S], constraints: &[Constraint<V, C>]) {
+        if self.infeasible {
+            return;
+        }
+
         if !self.filter.should_find(steps, constraints) {
             log_debug!("Diverging answer finding will be skipped.");
             return;
@@ -38,14 +46,37 @@ where
         log_debug!("Negating the last constraint");
         let not_last = constraints.last().unwrap().as_ref().not();
 
-        if !Self::check(
+        let (result, is_prefix_unsat) = Self::check_assuming(
             &mut self.solver,
             constraints[..constraints.len() - 1]
                 .iter()
-                .map(Constraint::as_ref)
-                .chain(iter::once(not_last.clone())),
+                .map(Constraint::as_ref),
+            not_last.clone(),
             &mut self.model_consumer,
-        ) {
+        );
+        if !matches!(result, SolveResult::Sat(())) {
+            /* NOTE: An unsatisfiable prefix makes the negation unsatisfiable as well.
+             * This is possible when the constraints are not purely coming from
+             * the execution (e.g., assumptions and summaries). In such case, any
+             * further query from this execution is meaningless.
+             * The negation is checked as an assumption, so that the solver tells
+             * it in the same query. */
+            if is_prefix_unsat {
+                log_warn!(
+                    "Path condition is unsatisfiable at step {}, no further diverging answers will be searched for this execution",
+                    steps.len(),
+                );
+                self.infeasible = true;
+                if let Some(observer) = self.infeasibility_observer.take() {
+                    observer(steps.len(), constraints.len());
+                }
+                return;
+            }
+
+            if let (SolveResult::Unsat, Some(observer)) = (&result, self.unsat_observer.as_mut()) {
+                observer();
+            }
+
             /* NOTE: What is optimistic checking?
              * Consider two independent branch conditions at the same level
              * that the current execution has taken neither.
@@ -78,9 +109,33 @@ impl<S: Solver, F> ImmediateDivergingAnswerFinder<S, F> {
             filter,
             optimistic_divergence_solver,
             model_consumer,
+            infeasible: false,
+            infeasibility_observer: None,
+            unsat_observer: None,
             _phantom: Default::default(),
         }
     }
+
+    /// Sets the observer to be notified (once) when the path condition is found
+    /// to be unsatisfiable, with the number of steps and constraints observed so far.
+    pub fn with_infeasibility_observer(mut self, observer: Box<dyn FnOnce(usize, usize)>) -> Self {
+        self.infeasibility_observer = Some(observer);
+        self
+    }
+
+    /// Sets the observer to be notified when the negation of a decision is unsatisfiable,
+    /// i.e., when the other branch is not reachable with the current prefix.
+    pub fn with_unsat_observer(mut self, observer: Box<dyn FnMut()>) -> Self {
+        self.unsat_observer = Some(observer);
+        self
+    }
+
+    /// Whether the path condition of the current execution is found to be unsatisfiable.
+    /// If so, the execution is divergent from what the constraints describe and
+    /// no diverging answer will be searched for it anymore.
+    pub fn is_infeasible(&self) -> bool {
+        self.infeasible
+    }
 }
 
 impl<TSolver: Solver, F> ImmediateDivergingAnswerFinder<TSolver, F> {
@@ -88,7 +143,7 @@ impl<TSolver: Solver, F> ImmediateDivergingAnswerFinder<TSolver, F> {
         solver: &mut TSolver,
         constraints: impl Iterator<Item = Constraint<&'a V, &'a C>>,
         model_consumer: &'b mut dyn FnMut(TSolver::Model),
-    ) -> bool
+    ) -> SolveResult<()>
     where
         V: Borrow<TSolver::Value>,
         C: Borrow<TSolver::Case>,
@@ -100,14 +155,53 @@ impl<TSolver: Solver, F> ImmediateDivergingAnswerFinder<TSolver, F> {
                 .map(|c| c.map(Borrow::borrow, Borrow::borrow))
                 .map(Constraint::cloned),
         );
+        Self::consume(result, model_consumer)
+    }
+
+    /// Checks the constraints along with the assumption.
+    /// # Returns
+    /// The result, and whether the constraints are unsatisfiable regardless of the assumption.
+    /// Unknown results are not considered as evidence for the latter.
+    fn check_assuming<'a, 'b, V: 'a, C: 'a>(
+        solver: &mut TSolver,
+        constraints: impl Iterator<Item = Constraint<&'a V, &'a C>>,
+        assumption: Constraint<&'a V, &'a C>,
+        model_consumer: &'b mut dyn FnMut(TSolver::Model),
+    ) -> (SolveResult<()>, bool)
+    where
+        V: Borrow<TSolver::Value>,
+        C: Borrow<TSolver::Case>,
+        TSolver::Value: Clone,
+        TSolver::Case: Clone,
+    {
+        let (result, is_unconditionally_unsat) = solver.check_assuming(
+            constraints
+                .map(|c| c.map(Borrow::borrow, Borrow::borrow))
+                .map(Constraint::cloned),
+            assumption.map(Borrow::borrow, Borrow::borrow).cloned(),
+        );
+        (
+            Self::consume(result, model_consumer),
+            is_unconditionally_unsat,
+        )
+    }
+
+    fn consume(
+        result: SolveResult<TSolver::Model>,
+        model_consumer: &mut dyn FnMut(TSolver::Model),
+    ) -> SolveResult<()> {
         match result {
             SolveResult::Sat(model) => {
                 model_consumer(model);
-                true
+                SolveResult::Sat(())
+            }
+            SolveResult::Unsat => {
+                log_info!("Unsatisfiable result.");
+                SolveResult::Unsat
             }
-            _ => {
-                log_info!("Unsatisfiable or unknown result.");
-                false
+            SolveResult::Unknown => {
+                log_warn!("Unknown result (e.g., the solver timed out), skipping the divergence.");
+                SolveResult::Unknown
             }
         }
     }
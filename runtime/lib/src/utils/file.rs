@@ -31,6 +31,11 @@ impl FileGenConfig {
         self.prefix.as_ref().map(|s| s.as_str())
     }
 
+    #[inline]
+    pub fn extension(&self) -> Option<&str> {
+        self.extension.as_ref().map(|s| s.as_str())
+    }
+
     pub fn dir_or_default(&self) -> PathBuf {
         self.directory.clone().unwrap_or_else(|| {
             std::env::current_dir().expect("Cannot get current working directory")
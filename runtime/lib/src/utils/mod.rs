@@ -4,6 +4,15 @@ use core::{
     ops::{Deref, DerefMut, RangeBounds},
 };
 
+use std::{
+    collections::HashMap,
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread::ThreadId,
+};
+
 use derive_more as dm;
 
 pub mod alias;
@@ -160,6 +169,111 @@ impl<'a, T> DerefMut for MutAccess<'a, T> {
     }
 }
 
+static NEXT_THREAD_INDEX: AtomicUsize = AtomicUsize::new(0);
+
+/// The threads that have exited, in the order they exit.
+/// The ids are never reused, thus the values kept for them are safe to remove
+/// at any time.
+static EXITED_THREADS: Mutex<Vec<ThreadId>> = Mutex::new(Vec::new());
+static EXITED_THREADS_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Records the exit of the thread when its thread locals are destroyed.
+struct ThreadExitNotifier(ThreadId);
+
+impl Drop for ThreadExitNotifier {
+    fn drop(&mut self) {
+        let mut exited = EXITED_THREADS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        exited.push(self.0);
+        EXITED_THREADS_COUNT.store(exited.len(), Ordering::Release);
+    }
+}
+
+thread_local! {
+    static CURRENT_THREAD_ID: ThreadId = {
+        /* The id itself is kept without a destructor, as it may be queried
+         * during the destruction of the other thread locals. */
+        let _ = THREAD_EXIT_NOTIFIER.try_with(|_| ());
+        std::thread::current().id()
+    };
+    static THREAD_EXIT_NOTIFIER: ThreadExitNotifier =
+        ThreadExitNotifier(std::thread::current().id());
+    static CURRENT_THREAD_INDEX: usize = NEXT_THREAD_INDEX.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Returns the id of the current thread, cached to avoid querying the thread handle.
+#[inline]
+pub fn current_thread_id() -> ThreadId {
+    CURRENT_THREAD_ID.with(|id| *id)
+}
+
+/// Returns a compact index for the current thread, which unlike [`ThreadId`]
+/// can be recorded in the outputs.
+/// The threads are indexed in the order they first ask for it.
+#[inline]
+pub fn current_thread_index() -> usize {
+    CURRENT_THREAD_INDEX.with(|i| *i)
+}
+
+/// Keeps a separate value for each thread, e.g., the parts of the state that
+/// follow the stack of the thread.
+/// The values of the exited threads are removed on the next mutable access.
+/// # Remarks
+/// The values are accessed from multiple threads, thus the owner is expected
+/// to be behind a synchronized access if the program is multi-threaded.
+pub struct PerThread<T> {
+    values: HashMap<ThreadId, T>,
+    /// The number of the exited threads whose values are removed.
+    purged: usize,
+}
+
+impl<T> PerThread<T> {
+    pub fn new() -> Self {
+        Self {
+            values: HashMap::new(),
+            purged: 0,
+        }
+    }
+
+    pub fn current(&self) -> Option<&T> {
+        self.values.get(&current_thread_id())
+    }
+
+    pub fn current_or_insert_with(&mut self, f: impl FnOnce() -> T) -> &mut T {
+        self.remove_exited();
+        self.values.entry(current_thread_id()).or_insert_with(f)
+    }
+
+    pub fn current_mut(&mut self) -> &mut T
+    where
+        T: Default,
+    {
+        self.current_or_insert_with(T::default)
+    }
+
+    #[inline]
+    fn remove_exited(&mut self) {
+        if EXITED_THREADS_COUNT.load(Ordering::Acquire) == self.purged {
+            return;
+        }
+
+        let exited = EXITED_THREADS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        for id in &exited[self.purged..] {
+            self.values.remove(id);
+        }
+        self.purged = exited.len();
+    }
+}
+
+impl<T> Default for PerThread<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub trait IntTypeExt {
     fn bit_mask(bit_size: u32) -> u128;
     fn all_one(&self) -> u128;
@@ -188,3 +302,26 @@ impl IntTypeExt for crate::abs::IntType {
         (bit_rep as i128) << (128 - self.bit_size) >> (128 - self.bit_size)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn values_of_exited_threads_are_removed() {
+        let per_thread = Arc::new(Mutex::new(PerThread::<u32>::new()));
+        *per_thread.lock().unwrap().current_mut() = 1;
+
+        let shared = per_thread.clone();
+        std::thread::spawn(move || *shared.lock().unwrap().current_mut() = 2)
+            .join()
+            .unwrap();
+        assert_eq!(per_thread.lock().unwrap().values.len(), 2);
+
+        let mut per_thread = per_thread.lock().unwrap();
+        assert_eq!(*per_thread.current_mut(), 1);
+        assert_eq!(per_thread.values.len(), 1);
+    }
+}
@@ -0,0 +1,75 @@
+//! Replays a PRI events stream recorded by the recorder flavor on a backend.
+//!
+//! This makes it possible to debug or benchmark a backend on a fixed workload
+//! without running the instrumented program again. The backend is initialized
+//! as usual by the recorded `init_runtime_lib` event, so the replay should be
+//! run where the backend can find the program's information (e.g., the type
+//! information database) and its configuration.
+//!
+//! Only the backends that do not read the program's memory are supported, i.e.,
+//! the no-op PRI, cf_tracer, and mdsan. Addresses in the stream belong to the
+//! recorded process, thus symex (which reads the concrete values behind
+//! symbolic pointers and raw places) cannot be replayed offline. Streams can
+//! still be replayed on symex in-process, e.g., by the differential harness.
+
+use std::{fs::File, io::BufReader, path::PathBuf, process::ExitCode, time::Instant};
+
+use clap::{Parser, ValueEnum};
+
+use leaf_runtime::pri::{
+    NoOpPri,
+    events::{EventReader, ReplayTarget, Replayer},
+};
+
+#[derive(Parser)]
+#[command(
+    about = "Replays a recorded PRI events stream on a backend that does not read the program's memory"
+)]
+struct Args {
+    /// The file containing the recorded events.
+    events: PathBuf,
+    /// The backend to replay the events on.
+    #[arg(short, long, value_enum, default_value_t = Backend::NoOp)]
+    backend: Backend,
+}
+
+/// The backends that can be replayed offline.
+#[derive(Clone, Copy, ValueEnum)]
+enum Backend {
+    NoOp,
+    CfTracer,
+    MdSan,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let replay = match args.backend {
+        Backend::NoOp => replay::<NoOpPri>,
+        Backend::CfTracer => replay::<cf_tracer::interface::DefaultPri>,
+        Backend::MdSan => replay::<mdsan::interface::DefaultPri>,
+    };
+
+    let start = Instant::now();
+    match replay(&args.events) {
+        Ok(count) => {
+            println!("Replayed {} events in {:?}", count, start.elapsed());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Failed to replay `{}`: {}", args.events.display(), e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn replay<P: ReplayTarget>(path: &PathBuf) -> std::io::Result<usize> {
+    let reader = EventReader::new(BufReader::new(File::open(path)?))?;
+    let replayer = Replayer::<P>::new();
+    let mut count = 0;
+    for event in reader {
+        replayer.replay(&event?);
+        count += 1;
+    }
+    Ok(count)
+}
@@ -0,0 +1,100 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, Path, parse_macro_input, parse_quote};
+
+/// Derives `Symbolizable` for structs and enums by marking each field symbolic.
+/// All the fields are required to be `Symbolizable`.
+/// # Remarks
+/// - The discriminant of enums remains concrete, i.e., only the fields of the
+///   current variant are marked symbolic.
+/// - Aggregates can only be marked symbolic by value (`mark_symbolic`), as
+///   `symbolize` replaces the whole value.
+#[proc_macro_derive(Symbolizable)]
+pub fn derive_symbolizable(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as DeriveInput);
+    let trait_path: Path = parse_quote!(leaf::annotations::Symbolizable);
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let (pattern, construction) = destructure(&trait_path, quote!(Self), &data.fields);
+            quote! {
+                let #pattern = self;
+                #construction
+            }
+        }
+        Data::Enum(data) => {
+            let arms = data.variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                let (pattern, construction) =
+                    destructure(&trait_path, quote!(Self::#ident), &variant.fields);
+                quote! { #pattern => #construction, }
+            });
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(data) => {
+            return syn::Error::new(
+                data.union_token.span,
+                "`Symbolizable` cannot be derived for unions",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    for param in input.generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#trait_path));
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #trait_path for #name #ty_generics #where_clause {
+            fn symbolize(&self) {
+                panic!("Aggregates are expected to be marked symbolic by value.")
+            }
+
+            fn mark_symbolic(self) -> Self {
+                #body
+            }
+        }
+    }
+    .into()
+}
+
+/// # Returns
+/// The pattern binding the fields and the expression constructing the value
+/// from the fields marked symbolic.
+fn destructure(
+    trait_path: &Path,
+    path: TokenStream2,
+    fields: &Fields,
+) -> (TokenStream2, TokenStream2) {
+    match fields {
+        Fields::Named(fields) => {
+            let names = fields
+                .named
+                .iter()
+                .map(|f| f.ident.as_ref().unwrap())
+                .collect::<Vec<_>>();
+            (
+                quote! { #path { #(#names),* } },
+                quote! { #path { #(#names: #trait_path::mark_symbolic(#names)),* } },
+            )
+        }
+        Fields::Unnamed(fields) => {
+            let bindings = (0..fields.unnamed.len())
+                .map(|i| format_ident!("field_{}", i))
+                .collect::<Vec<_>>();
+            (
+                quote! { #path ( #(#bindings),* ) },
+                quote! { #path ( #(#trait_path::mark_symbolic(#bindings)),* ) },
+            )
+        }
+        Fields::Unit => (quote! { #path }, quote! { #path }),
+    }
+}
@@ -9,6 +9,69 @@ pub trait Symbolizable: Sized {
         Self::symbolize(&self);
         self
     }
+
+    /// Marks the value symbolic as `mark_symbolic` does, while the created
+    /// symbolic variables are recorded with the name, e.g., `len.mark_symbolic_named("len")`.
+    /// The name of the type and the location of the call are recorded as well,
+    /// so the generated models can be mapped back to the program inputs.
+    #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+    #[track_caller]
+    fn mark_symbolic_named(self, name: &'static str) -> Self {
+        push_sym_var_info(
+            name,
+            core::any::type_name::<Self>(),
+            core::panic::Location::caller(),
+        );
+        let value = self.mark_symbolic();
+        pop_sym_var_info();
+        value
+    }
+}
+
+/// Derives `Symbolizable` for structs and enums by marking each field symbolic.
+#[cfg(not(core_build))]
+pub use macros::Symbolizable;
+
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+impl<T: Symbolizable, const N: usize> Symbolizable for [T; N] {
+    fn symbolize(&self) {
+        panic!("Arrays are expected to be marked symbolic by value.")
+    }
+
+    fn mark_symbolic(self) -> Self {
+        self.map(T::mark_symbolic)
+    }
+}
+
+/// Marks the elements of the slice symbolic in place, e.g., the bytes of a buffer.
+/// The elements are required to be `Copy`, as they are replaced in place.
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+impl<T: Symbolizable + Copy> Symbolizable for &mut [T] {
+    fn symbolize(&self) {
+        panic!("Slices are expected to be marked symbolic by value.")
+    }
+
+    fn mark_symbolic(self) -> Self {
+        for element in self.iter_mut() {
+            *element = (*element).mark_symbolic();
+        }
+        self
+    }
+}
+
+/// Marks the elements of the vector symbolic.
+/// # Remarks
+/// Only available when the shim is not integrated into the core library, as
+/// vectors are not available there. Mark the slice of the vector instead.
+#[cfg(not(core_build))]
+impl<T: Symbolizable> Symbolizable for alloc::vec::Vec<T> {
+    fn symbolize(&self) {
+        panic!("Vectors are expected to be marked symbolic by value.")
+    }
+
+    fn mark_symbolic(self) -> Self {
+        self.into_iter().map(T::mark_symbolic).collect()
+    }
 }
 
 #[leaf_attr::instrument(false)]
@@ -66,7 +129,7 @@ mod implementation {
                     fn symbolize(&self) {
                         let bit_rep = ${concat($ty, _to_bits)}(*self);
                         let sbits = <$ty>::MANTISSA_DIGITS as u64;
-                        let ebits = (<$ty>::MAX_EXP - <$ty>::MIN_EXP + 1) as u64;
+                        let ebits = size_of::<$ty>() as u64 * 8 - sbits;
                         let operand_ref = new_sym_value_float(bit_rep, ebits, sbits);
                         override_return_value(operand_ref);
                     }
@@ -78,6 +141,129 @@ mod implementation {
     impl_symbolizable_float!(f32, f64);
 }
 
+/// Marks the bytes of the command-line arguments as symbolic, e.g.,
+/// ```ignore
+/// let mut args: Vec<Vec<u8>> = std::env::args_os()
+///     .skip(1)
+///     .map(|a| a.into_encoded_bytes())
+///     .collect();
+/// leaf::annotations::mark_args_symbolic(&mut args);
+/// ```
+/// All the arguments passed to the program (except the program name) are
+/// expected to be given in order, so that the generated inputs can be mapped
+/// back to the arguments.
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+pub fn mark_args_symbolic<A: AsMut<[u8]>>(args: &mut [A]) {
+    push_tag(tags::SYMBOLIC_ARGS);
+    for arg in args.iter_mut() {
+        arg.as_mut().mark_symbolic();
+    }
+    pop_tag();
+}
+
+/// Marks the bytes of the value of the environment variable as symbolic, e.g.,
+/// ```ignore
+/// let mut value = std::env::var_os("MY_VAR").unwrap_or_default().into_encoded_bytes();
+/// leaf::annotations::mark_env_var_symbolic("MY_VAR", &mut value);
+/// ```
+/// The value is expected to be the one read from the environment, and is only
+/// made symbolic if the variable is selected in the configuration of the backend.
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+pub fn mark_env_var_symbolic(name: &'static str, value: &mut [u8]) {
+    push_tag(tags::SYMBOLIC_ENV_VAR);
+    push_tag(name);
+    value.mark_symbolic();
+    pop_tag();
+    pop_tag();
+}
+
+/// Marks the bytes read from the file as symbolic, e.g.,
+/// ```ignore
+/// let mut content = std::fs::read("input.txt").unwrap();
+/// leaf::annotations::mark_file_symbolic("input.txt", &mut content);
+/// ```
+/// The content is expected to be read from the beginning of the file, possibly
+/// in multiple calls, and is only made symbolic if the file is selected in the
+/// configuration of the backend. Paths known at runtime can be leaked to get a
+/// static reference (e.g., using `Box::leak`).
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+pub fn mark_file_symbolic(path: &'static str, content: &mut [u8]) {
+    push_tag(tags::SYMBOLIC_FILE);
+    push_tag(path);
+    content.mark_symbolic();
+    pop_tag();
+    pop_tag();
+}
+
+/// Constrains the execution to the inputs satisfying the (possibly symbolic)
+/// condition, e.g., `assume(len < 64)`.
+/// The condition is added to the path constraints without being a branch
+/// to diverge at. If it does not hold, the execution is terminated.
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+pub fn assume(cond: bool) {
+    push_tag(tags::NO_DIVERGE);
+    if !cond {
+        super::pri::violate_assumption();
+    }
+    pop_tag();
+}
+
+/// Replaces the (possibly symbolic) value with its concrete value, while the
+/// symbolic value is constrained to be equal to it, e.g., `concretize(hash(data))`.
+/// It stops the propagation of the symbolic value to the rest of the execution,
+/// which helps to avoid the state explosion in sub-routines such as hashing or
+/// compression, at the cost of not exploring the other values.
+#[leaf_attr::instrument(false)]
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+pub fn concretize<T: 'static>(value: T) -> T {
+    use super::pri::{
+        compiler_helpers::{size_of, type_id_of},
+        *,
+    };
+
+    // The bytes are only read during the call, while the value is alive.
+    let bytes = unsafe {
+        core::slice::from_raw_parts((&raw const value).cast::<u8>(), size_of::<T>() as usize)
+    };
+    concretize_return_value(ref_operand_const_byref(bytes, type_id_of::<T>()));
+    value
+}
+
+/// Marks reaching an objective of the program identified by the label,
+/// e.g., `reach("parsed_header")`. The objective is recorded in the execution
+/// trace at the call site, so it can be targeted regardless of the basic
+/// block locations, which change across builds.
+#[leaf_attr::instrument(false)]
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+pub fn reach(label: &'static str) {
+    push_tag(tags::REACH);
+    push_tag(label);
+    pop_tag();
+    pop_tag();
+}
+
+/// Marks reaching a state that is not interesting to explore further,
+/// e.g., an input rejected by validation. The state is recorded in the execution
+/// trace at the call site.
+#[leaf_attr::instrument(false)]
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+pub fn reject() {
+    push_tag(tags::REJECT);
+    pop_tag();
+}
+
+/// Runs the closure with the tag active, e.g., `tagged("parser", || parse(input))`.
+/// The steps taken inside the closure carry the tag, so the trace filters and
+/// divergence selection can be restricted to them.
+/// For whole functions, `#[leaf_attr::tag("parser")]` has the same effect.
+#[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
+pub fn tagged<R>(tag: &'static str, f: impl FnOnce() -> R) -> R {
+    push_tag(tag);
+    let result = f();
+    pop_tag();
+    result
+}
+
 #[leaf_attr::instrument(false)]
 #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
 pub fn push_tag(tag: &'static str) {
@@ -90,6 +276,26 @@ pub fn pop_tag() {
     super::pri::pop_tag();
 }
 
+#[leaf_attr::instrument(false)]
+fn push_sym_var_info(
+    name: &'static str,
+    type_name: &'static str,
+    location: &'static core::panic::Location<'static>,
+) {
+    super::pri::push_sym_var_info(
+        name,
+        type_name,
+        location.file(),
+        location.line(),
+        location.column(),
+    );
+}
+
+#[leaf_attr::instrument(false)]
+fn pop_sym_var_info() {
+    super::pri::pop_sym_var_info();
+}
+
 #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
 pub mod tags {
     #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
@@ -7,11 +7,15 @@
 #![cfg_attr(not(core_build), no_std)]
 #![cfg_attr(not(core_build), allow(internal_features), feature(rustc_attrs))]
 #![cfg_attr(not(core_build), feature(register_tool))]
+#![cfg_attr(not(core_build), feature(thread_local))]
 #![cfg_attr(not(core_build), register_tool(leaf_attr))]
 // -----
 #![cfg_attr(core_build, allow(missing_docs))]
 #![cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
 
+#[cfg(not(core_build))]
+extern crate alloc;
+
 #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
 pub mod annotations;
 
@@ -39,8 +39,8 @@ pub fn f64_to_bits(value: f64) -> u128 {
  */
 
 #[used]
-static _PLACE_WITH_ADDRESS_TYPED_REFERENCER: fn(PlaceRef, *const u32) -> PlaceRef =
-    place_with_address_typed::<u32>;
+static _PLACE_WITH_METADATA_TYPED_REFERENCER: fn(PlaceRef, *const u32, i8, u8) -> PlaceRef =
+    place_with_metadata_typed::<u32>;
 
 #[used]
 static _TYPE_ID_OF_REFERENCER: fn() -> TypeId = type_id_of::<u32>;
@@ -60,10 +60,30 @@ static _CONST_ATOMIC_ORD_OF_REFERENCER: fn(u8) -> AtomicOrdering = const_atomic_
 #[used]
 static _CONST_ATOMIC_BINARY_OP_OF_REFERENCER: fn(u8) -> AtomicBinaryOp = const_atomic_binary_op_of;
 
+/* NOTE: The arguments other than the address are constants at instrumentation time,
+ * so after inlining, the metadata is mostly built from constants. */
 #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
 #[inline(always)]
-pub fn place_with_address_typed<T>(place: PlaceRef, address: *const T) -> PlaceRef {
-    super::place_with_address(place, address as RawAddress)
+pub fn place_with_metadata_typed<T: ?Sized + 'static>(
+    place: PlaceRef,
+    address: *const T,
+    primitive_type: i8,
+    flags: u8,
+) -> PlaceRef {
+    super::place_with_metadata(
+        place,
+        PlaceMetadata {
+            flags,
+            address: address as RawAddress,
+            type_id: common::ffi::NoCallFrom::from(type_id_of::<T>()),
+            primitive_type: PrimitiveType(primitive_type),
+            size: if flags & PlaceMetadata::HAS_SIZE != 0 {
+                unsafe { intrinsics::size_of_val(address) as TypeSize }
+            } else {
+                0
+            },
+        },
+    )
 }
 
 #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
@@ -82,18 +102,20 @@ pub const fn size_of<T>() -> TypeSize {
     const { intrinsics::size_of::<T>() as TypeSize }
 }
 
+/* NOTE: Not const as the conversion of the message is done by a trait. */
 #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
-#[cfg_attr(core_build, rustc_const_stable(feature = "rust1", since = "1.0.0"))]
 #[inline(always)]
-pub const fn assertion_info(
+pub fn assertion_info(
     location: BasicBlockIndex,
     condition: OperandRef,
     expected: bool,
+    message: &'static str,
 ) -> AssertionInfo {
     AssertionInfo {
         location,
         condition,
         expected,
+        message: common::ffi::NoCallFrom::from(message),
     }
 }
 
@@ -29,6 +29,9 @@ mod ffi {
     #[link(name = "leafrt")]
     extern "C" {
         common::pri::macros::list_func_decls!(modifier: declare_fn, (from common::ffi));
+
+        #[cfg(concrete_fast_path)]
+        pub(super) fn leaf_symbolic_data_flag() -> *const bool;
     }
 
     macro_rules! delegate_to_leafrt {
@@ -64,12 +67,106 @@ mod ffi {
     impl FfiPri for ForeignPri {}
 }
 
+/* A fast path that skips the PRI calls not related to the control flow,
+ * while no symbolic data is present in the current frame according to the runtime.
+ * The references returned in place of the skipped ones are inlined "some" references,
+ * so the retained calls (e.g., branches) receive an unknown (concrete) value.
+ * Frames accessing memory through dereferences or statics are reported by the
+ * instrumentation, so that the runtime keeps the flag raised for them. */
+#[cfg(concrete_fast_path)]
+mod fast_path {
+    use core::cell::Cell;
+
+    use super::common::pri::refs::{Ref, encoding as r_enc};
+
+    #[cfg(not(refs_inlining))]
+    compile_error!("The concrete fast path requires `refs_inlining`.");
+
+    const SKIPPABLE_PREFIXES: &[&str] = &[
+        "ref_place_",
+        "place_with_",
+        "ref_operand_",
+        "assign_",
+        "set_discriminant",
+        "mark_storage_",
+        "assert_",
+        "intrinsic_",
+    ];
+
+    const PLACE_REF_PREFIXES: &[&str] = &["ref_place_", "place_with_"];
+
+    const fn starts_with_any(name: &str, prefixes: &[&str]) -> bool {
+        let name = name.as_bytes();
+        let mut i = 0;
+        while i < prefixes.len() {
+            let prefix = prefixes[i].as_bytes();
+            if name.len() >= prefix.len() {
+                let mut j = 0;
+                while j < prefix.len() && name[j] == prefix[j] {
+                    j += 1;
+                }
+                if j == prefix.len() {
+                    return true;
+                }
+            }
+            i += 1;
+        }
+        false
+    }
+
+    pub(super) const fn is_skippable(name: &str) -> bool {
+        starts_with_any(name, SKIPPABLE_PREFIXES)
+    }
+
+    pub(super) const fn is_place_ref(name: &str) -> bool {
+        starts_with_any(name, PLACE_REF_PREFIXES)
+    }
+
+    /// The address of the runtime's flag for the current thread, fetched once per thread.
+    #[thread_local]
+    static FLAG: Cell<*const bool> = Cell::new(core::ptr::null());
+
+    #[inline(always)]
+    pub(super) fn should_skip() -> bool {
+        let mut flag = FLAG.get();
+        if flag.is_null() {
+            flag = unsafe { super::ffi::leaf_symbolic_data_flag() };
+            FLAG.set(flag);
+        }
+        !unsafe { *flag }
+    }
+
+    pub(super) trait Skipped {
+        fn skipped(is_place: bool) -> Self;
+    }
+
+    impl Skipped for () {
+        #[inline(always)]
+        fn skipped(_is_place: bool) -> Self {}
+    }
+
+    impl Skipped for Ref {
+        #[inline(always)]
+        fn skipped(is_place: bool) -> Self {
+            if is_place {
+                r_enc::place::encode_some()
+            } else {
+                r_enc::operand::encode_some()
+            }
+        }
+    }
+}
+
 macro_rules! export_to_rust_abi {
     ($(#[$($attr: meta)*])* fn $name:ident ($($(#[$($arg_attr: meta)*])* $arg:ident : $arg_type:ty),* $(,)?) $(-> $ret_ty:ty)?;) => {
         $(#[$($attr)*])*
         #[inline(always)]
         #[cfg_attr(core_build, stable(feature = "rust1", since = "1.0.0"))]
         pub fn $name ($($(#[$($arg_attr)*])* $arg : $arg_type),*) $(-> $ret_ty)? {
+            #[cfg(concrete_fast_path)]
+            if const { fast_path::is_skippable(stringify!($name)) } && fast_path::should_skip() {
+                return fast_path::Skipped::skipped(const { fast_path::is_place_ref(stringify!($name)) });
+            }
             ffi::ForeignPri::$name($(common::ffi::NoCallFrom::from($arg)),*)
         }
     };
@@ -0,0 +1,14 @@
+use leaf::annotations::Symbolizable;
+
+fn main() {
+    let x = 10_u32.mark_symbolic();
+    let result = std::panic::catch_unwind(|| {
+        assert!(x < 5, "x should be less than 5 but is {}", x);
+        x
+    });
+    if result.is_err() && x > 7 {
+        foo();
+    }
+}
+
+fn foo() {}
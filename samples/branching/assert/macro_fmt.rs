@@ -0,0 +1,7 @@
+use leaf::annotations::Symbolizable;
+
+fn main() {
+    let x = 10_u32.mark_symbolic();
+    assert!(x > 5, "x should be greater than 5 but is {}", x);
+    assert_eq!(x % 2, 0, "x should be even: {x}");
+}
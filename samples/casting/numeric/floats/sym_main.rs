@@ -0,0 +1,40 @@
+use leaf::annotations::Symbolizable;
+
+#[allow(unused_variables)]
+
+fn main() {
+    let a = get_int().mark_symbolic();
+    let b = a as f32;
+    if b > 1000.0 {
+        foo(a);
+    }
+
+    let c = get_float().mark_symbolic();
+    let d = c as f32;
+    if d < 0.5 {
+        foo(c);
+    }
+
+    let e = get_float().mark_symbolic();
+    let f = e as u8;
+    if f == u8::MAX {
+        foo(e);
+    }
+
+    let g = get_float().mark_symbolic();
+    let h = g as i32;
+    if h == -3 {
+        foo(g);
+    }
+}
+
+fn get_int() -> isize {
+    10
+}
+
+fn get_float() -> f64 {
+    10.0
+}
+
+#[inline(never)]
+fn foo<T>(x: T) {}
@@ -0,0 +1,18 @@
+use leaf::annotations::Symbolizable;
+
+fn main() {
+    let x = 1.5f32.mark_symbolic();
+    let bits = unsafe { core::mem::transmute::<f32, u32>(x) };
+    if bits >> 31 == 1 {
+        foo(x);
+    }
+
+    let y = 0x3ff8_0000_0000_0000u64.mark_symbolic();
+    let value = unsafe { core::mem::transmute::<u64, f64>(y) };
+    if value < 0.0 {
+        foo(y);
+    }
+}
+
+#[inline(never)]
+fn foo<T>(_x: T) {}
@@ -0,0 +1,16 @@
+use leaf::annotations::Symbolizable;
+
+const LIMIT: u32 = scale(4);
+
+fn main() {
+    let x = 10u32.mark_symbolic();
+    if scale(x) == LIMIT {
+        foo();
+    }
+}
+
+const fn scale(x: u32) -> u32 {
+    x * 3 + 1
+}
+
+fn foo() {}
@@ -0,0 +1,22 @@
+/* Meant to be run with a small call depth limit, e.g., `LEAF_CALL__MAX_DEPTH=2`.
+ * The frames beyond the limit are summarized, while the locals of their callers
+ * should keep their symbolic values when the summarized frames return.
+ */
+
+use leaf::annotations::Symbolizable;
+
+fn main() {
+    let x = 4_u32.mark_symbolic();
+    descend(x, 5);
+}
+
+fn descend(x: u32, n: u32) -> u32 {
+    let local = x + n;
+    let depth = if n == 0 { 0 } else { descend(x, n - 1) + 1 };
+    if local == 12 {
+        foo();
+    }
+    depth
+}
+
+fn foo() {}
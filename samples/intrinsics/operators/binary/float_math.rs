@@ -0,0 +1,15 @@
+#![feature(core_intrinsics)]
+
+use core::intrinsics;
+
+use leaf::annotations::*;
+
+fn main() {
+    let x = 16.0f64.mark_symbolic();
+    let r = intrinsics::sqrtf64(x);
+    let m = intrinsics::maximumf64(r, intrinsics::fabs(x - 20.0));
+
+    if (m == 5.0) & (x < 16.0) {
+        intrinsics::black_box(0);
+    }
+}
@@ -0,0 +1,30 @@
+#![feature(core_intrinsics, repr_simd)]
+
+use core::intrinsics::{black_box, simd};
+
+use leaf::annotations::*;
+
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct I32x4([i32; 4]);
+
+fn main() {
+    let a = I32x4([-5i32.mark_symbolic(), 7i32.mark_symbolic(), 0, 12]);
+    let b = I32x4([3, 7, 1i32.mark_symbolic(), -12i32.mark_symbolic()]);
+
+    // The lanes of the results are masks: all ones for true, zero for false.
+    let eq: I32x4 = unsafe { simd::simd_eq(a, b) };
+    let ne: I32x4 = unsafe { simd::simd_ne(a, b) };
+    let gt: I32x4 = unsafe { simd::simd_gt(a, b) };
+    let le: I32x4 = unsafe { simd::simd_le(a, b) };
+
+    if eq.0[1] == -1 {
+        black_box(eq);
+    }
+    if ne.0[0] == 0 {
+        black_box(ne);
+    }
+    if gt.0[3] == -1 && le.0[2] == -1 {
+        black_box((gt, le));
+    }
+}
@@ -0,0 +1,35 @@
+#![feature(core_intrinsics, repr_simd)]
+
+use core::intrinsics::{black_box, simd};
+
+use leaf::annotations::*;
+
+#[repr(simd)]
+#[derive(Clone, Copy)]
+struct U8x4([u8; 4]);
+
+fn main() {
+    let a = U8x4([
+        10u8.mark_symbolic(),
+        20u8.mark_symbolic(),
+        30,
+        40u8.mark_symbolic(),
+    ]);
+    let b = U8x4([1, 2u8.mark_symbolic(), 3, 4]);
+
+    let sum: U8x4 = unsafe { simd::simd_add(a, b) };
+    let mask: U8x4 = unsafe { simd::simd_lt(a, b) };
+    let swapped: U8x4 = unsafe { simd::simd_bswap(a) };
+    let max: u8 = unsafe { simd::simd_reduce_max(sum) };
+
+    use_num(sum.0[1]);
+    use_num(mask.0[0]);
+    use_num(swapped.0[3]);
+    use_num(max);
+}
+
+fn use_num<T: Default + Eq>(x: T) {
+    if x.eq(&T::default()) {
+        black_box(x);
+    }
+}
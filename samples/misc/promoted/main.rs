@@ -1,3 +1,12 @@
+use leaf::annotations::Symbolizable;
+
 fn main() {
     let x = &(4 / 2);
+    let table: &[u8; 4] = &[1, 2, 3, 4];
+    let i = 1usize.mark_symbolic();
+    if table[i % 4] == 3 {
+        foo(*x);
+    }
 }
+
+fn foo(_x: i32) {}
@@ -0,0 +1,148 @@
+use std::{collections::HashMap, fs::File, ops::Range, path::Path};
+
+use serde::Deserialize;
+
+/// The portion of an input that a symbolic variable is created from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct InputPortion {
+    pub source: Option<String>,
+    pub bytes: Range<usize>,
+}
+
+/// Resolves the symbolic variables to the input portions they are created from.
+/// # Remarks
+/// Variables not described in the layout are considered to be created from
+/// the bytes in the order of their ids, i.e., variable `n` from the byte at `n - 1`,
+/// which is how the ids are assigned when no layout is used.
+#[derive(Debug, Default)]
+pub(crate) struct InputLayout {
+    portions: HashMap<u32, InputPortion>,
+}
+
+/// The formats of the layout files used by the symex backend.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LayoutFile {
+    /// The offsets of the variables in the parent input (`parent_input.layout`).
+    Offsets(HashMap<u32, usize>),
+    /// The registry of the stable variable ids (`sym_vars.stable_ids.registry`).
+    Registry(Vec<RegistryEntry>),
+}
+
+#[derive(Deserialize)]
+struct RegistryEntry {
+    id: u32,
+    source: String,
+    offset: usize,
+    #[serde(default = "default_portion_length")]
+    length: usize,
+}
+
+fn default_portion_length() -> usize {
+    1
+}
+
+/// The source recorded in the registry for the variables not described in
+/// the input layout descriptor, which are created in the order of their ids.
+const SOURCE_UNDESCRIBED: &str = "#undescribed";
+
+impl InputLayout {
+    pub(crate) fn load(path: &Path) -> Result<Self, String> {
+        let file = File::open(path)
+            .map_err(|e| format!("Failed to open layout `{}`: {}", path.display(), e))?;
+        let layout: LayoutFile = serde_json::from_reader(file)
+            .map_err(|e| format!("Invalid layout `{}`: {}", path.display(), e))?;
+        Ok(Self::from(layout))
+    }
+
+    pub(crate) fn portion_of(&self, id: u32) -> Option<InputPortion> {
+        self.portions.get(&id).cloned().or_else(|| {
+            let offset = id.checked_sub(1)? as usize;
+            Some(InputPortion {
+                source: None,
+                bytes: offset..offset + 1,
+            })
+        })
+    }
+}
+
+impl From<LayoutFile> for InputLayout {
+    fn from(layout: LayoutFile) -> Self {
+        let portions = match layout {
+            LayoutFile::Offsets(offsets) => offsets
+                .into_iter()
+                .map(|(id, offset)| {
+                    (
+                        id,
+                        InputPortion {
+                            source: None,
+                            bytes: offset..offset + 1,
+                        },
+                    )
+                })
+                .collect(),
+            LayoutFile::Registry(entries) => entries
+                .into_iter()
+                .map(|e| {
+                    (
+                        e.id,
+                        InputPortion {
+                            source: (e.source != SOURCE_UNDESCRIBED).then_some(e.source),
+                            bytes: e.offset..e.offset + e.length,
+                        },
+                    )
+                })
+                .collect(),
+        };
+        Self { portions }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> InputLayout {
+        InputLayout::from(serde_json::from_str::<LayoutFile>(json).unwrap())
+    }
+
+    #[test]
+    fn ids_are_positions_without_layout() {
+        let layout = InputLayout::default();
+        assert_eq!(layout.portion_of(1).unwrap().bytes, 0..1);
+        assert_eq!(layout.portion_of(5).unwrap().bytes, 4..5);
+        assert_eq!(layout.portion_of(0), None);
+    }
+
+    #[test]
+    fn offsets_are_resolved() {
+        let layout = parse(r#"{ "1": 16, "2": 3 }"#);
+        assert_eq!(layout.portion_of(1).unwrap().bytes, 16..17);
+        assert_eq!(layout.portion_of(2).unwrap().bytes, 3..4);
+        assert_eq!(layout.portion_of(3).unwrap().bytes, 2..3);
+    }
+
+    #[test]
+    fn registry_portions_are_resolved() {
+        let layout = parse(
+            r##"[
+                { "source": "stdin", "offset": 8, "length": 4, "id": 1 },
+                { "source": "#undescribed", "offset": 1, "id": 2 }
+            ]"##,
+        );
+        assert_eq!(
+            layout.portion_of(1),
+            Some(InputPortion {
+                source: Some("stdin".to_owned()),
+                bytes: 8..12,
+            })
+        );
+        assert_eq!(
+            layout.portion_of(2),
+            Some(InputPortion {
+                source: None,
+                bytes: 1..2,
+            })
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Aligns two symbolic decision traces dumped by the symex backend
+//! (`sym_decisions`) and reports the first point where they diverge.
+//!
+//! The typical use is debugging why an input generated for negating a decision
+//! in the parent execution did not take the predicted branch. Given the inputs
+//! of the two executions, the bytes corresponding to the symbolic variables
+//! involved in the diverging constraints are reported as well.
+//! The variables are resolved to the input bytes through the layout used in
+//! the executions, i.e., the parent input layout or the registry of the stable
+//! variable ids. Without a layout, variable `n` corresponds to the byte at `n - 1`.
+
+mod layout;
+
+use std::{
+    fs::{self, File},
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::Parser;
+use serde_json::Value;
+
+use layout::{InputLayout, InputPortion};
+
+#[derive(Parser)]
+#[command(about = "Finds the first divergence between two symbolic decision traces")]
+struct Args {
+    /// The decision trace of the first (e.g., the parent) execution.
+    first: PathBuf,
+    /// The decision trace of the second (e.g., the generated input's) execution.
+    second: PathBuf,
+    /// The input of the first execution.
+    #[arg(long)]
+    first_input: Option<PathBuf>,
+    /// The input of the second execution.
+    #[arg(long)]
+    second_input: Option<PathBuf>,
+    /// The layout mapping the symbolic variables to the input portions,
+    /// either the offsets of the variables in the parent input or the registry
+    /// of the stable variable ids.
+    #[arg(long)]
+    layout: Option<PathBuf>,
+}
+
+struct Record {
+    step: Value,
+    constraint: Value,
+}
+
+impl Record {
+    fn location(&self) -> &Value {
+        &self.step["value"]
+    }
+
+    fn trace_index(&self) -> &Value {
+        &self.step["index"]
+    }
+
+    fn decision(&self) -> &Value {
+        &self.constraint["kind"]
+    }
+
+    fn var_ids(&self) -> Vec<u32> {
+        let mut ids = self.constraint["discr"]["decls"]
+            .as_object()
+            .map(|decls| decls.keys().filter_map(|id| id.parse().ok()).collect())
+            .unwrap_or_else(Vec::new);
+        ids.sort_unstable();
+        ids
+    }
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let result = (|| -> Result<(), String> {
+        let first = read_trace(&args.first)?;
+        let second = read_trace(&args.second)?;
+        let first_input = args.first_input.as_deref().map(read_input).transpose()?;
+        let second_input = args.second_input.as_deref().map(read_input).transpose()?;
+        let layout = args
+            .layout
+            .as_deref()
+            .map(InputLayout::load)
+            .transpose()?
+            .unwrap_or_default();
+
+        let Some(index) = first_divergence(&first, &second) else {
+            println!(
+                "The traces are identical over {} symbolic decisions.",
+                first.len()
+            );
+            return Ok(());
+        };
+        println!("The traces diverge at symbolic decision #{}.", index);
+
+        let mut var_ids = Vec::new();
+        for (name, trace) in [("first", &first), ("second", &second)] {
+            match trace.get(index) {
+                Some(record) => {
+                    println!("In the {} trace:", name);
+                    println!("  Location: {}", record.location());
+                    println!("  Trace index: {}", record.trace_index());
+                    println!(
+                        "  Discriminant: {}",
+                        record.constraint["discr"]["smtlib_rep"]
+                    );
+                    println!("  Decision: {}", record.decision());
+                    var_ids.extend(record.var_ids());
+                }
+                None => println!("The {} trace ends before the divergence.", name),
+            }
+        }
+        var_ids.sort_unstable();
+        var_ids.dedup();
+
+        if first_input.is_some() || second_input.is_some() {
+            println!("Involved input bytes:");
+            for id in var_ids {
+                let Some(portion) = layout.portion_of(id) else {
+                    println!("  Variable {:>4}: not in the input", id);
+                    continue;
+                };
+                println!(
+                    "  Variable {:>4} ({}): {} -> {}",
+                    id,
+                    describe(&portion),
+                    bytes_at(&first_input, &portion),
+                    bytes_at(&second_input, &portion),
+                );
+            }
+        }
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn describe(portion: &InputPortion) -> String {
+    let bytes = &portion.bytes;
+    let range = if bytes.len() == 1 {
+        format!("byte {}", bytes.start)
+    } else {
+        format!("bytes {}..{}", bytes.start, bytes.end)
+    };
+    match portion.source.as_ref() {
+        Some(source) => format!("{} of {}", range, source),
+        None => range,
+    }
+}
+
+fn bytes_at(input: &Option<Vec<u8>>, portion: &InputPortion) -> String {
+    input
+        .as_ref()
+        .and_then(|input| input.get(portion.bytes.clone()))
+        .map_or("--".to_owned(), |bytes| {
+            let hex = bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>();
+            format!("0x{}", hex)
+        })
+}
+
+/// # Returns
+/// The index of the first decision taken at different locations or to different
+/// directions, or `None` if the traces are identical.
+fn first_divergence(first: &[Record], second: &[Record]) -> Option<usize> {
+    first
+        .iter()
+        .zip(second.iter())
+        .position(|(a, b)| a.location() != b.location() || a.decision() != b.decision())
+        .or_else(|| (first.len() != second.len()).then(|| first.len().min(second.len())))
+}
+
+fn read_trace(path: &Path) -> Result<Vec<Record>, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open trace `{}`: {}", path.display(), e))?;
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.as_ref().is_ok_and(|l| l.trim().is_empty()))
+        .map(|(i, line)| {
+            let line = line.map_err(|e| format!("Failed to read `{}`: {}", path.display(), e))?;
+            let mut record: Value = serde_json::from_str(&line).map_err(|e| {
+                format!(
+                    "Invalid record at line {} of `{}`: {}",
+                    i + 1,
+                    path.display(),
+                    e
+                )
+            })?;
+            Ok(Record {
+                step: record["step"].take(),
+                constraint: record["constraint"].take(),
+            })
+        })
+        .collect()
+}
+
+fn read_input(path: &Path) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|e| format!("Failed to read input `{}`: {}", path.display(), e))
+}